@@ -70,6 +70,15 @@ impl AssemblyFile {
     pub fn data_sec_add_data(&mut self, label: impl Into<String>, directive: DataDirective) {
         self.data_section.add_data(label, directive);
     }
+    /// Adds a reservation to the BSS section; ignored for ABIs without one.
+    pub fn bss_sec_add_data(&mut self, label: impl Into<String>, directive: DataDirective) {
+        if let Some(bss_section) = &mut self.bss_section {
+            bss_section.add_data(label, directive);
+        }
+    }
+    pub fn text_sec_add_data(&mut self, label: impl Into<String>, directive: DataDirective) {
+        self.text_section.add_data(label, directive);
+    }
     pub fn text_sec_add_instruction(&mut self, instr: Instruction) {
         self.text_section.add_instruction(instr);
     }
@@ -4,7 +4,7 @@
 /// data directives (db, dw, dd, dq), string declarations, space reservations, and
 /// complete assembly sections with their elements.
 use super::{Instruction, Section};
-use std::{fmt, sync::Arc};
+use std::{fmt, fmt::Write as _, sync::Arc};
 
 /// Represents an expression used in EQU directives.
 ///
@@ -114,6 +114,13 @@ pub enum DataDirective {
     /// or numeric constants. The expression can be a simple constant or a
     /// calculation involving labels and the current position marker ($).
     Equ(EquExpression),
+
+    /// Table of 32-bit label offsets.
+    ///
+    /// Stores, for each label, its signed distance from the start of the
+    /// directive (`dd label - $`). Used for position independent jump tables;
+    /// the labels must be defined in the same section as the table.
+    LabelOffsets(Vec<String>),
 }
 
 impl DataDirective {
@@ -313,16 +320,26 @@ impl fmt::Display for DataDirective {
                 Ok(())
             }
             Self::Asciz(s, terminator) => {
-                write!(f, "db \"{}\", {terminator}", escape_string(s))
+                write!(f, "db {}, {terminator}", quote_string(s))
             }
             Self::Ascii(s) => {
-                write!(f, "db \"{}\"", escape_string(s))
+                write!(f, "db {}", quote_string(s))
             }
             Self::Resb(size) => write!(f, "resb {size}"),
             Self::Resw(size) => write!(f, "resw {size}"),
             Self::Resd(size) => write!(f, "resd {size}"),
             Self::Resq(size) => write!(f, "resq {size}"),
             Self::Equ(expr) => write!(f, "equ {expr}"),
+            Self::LabelOffsets(labels) => {
+                write!(f, "dd ")?;
+                for (i, label) in labels.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{label} - $")?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -615,23 +632,46 @@ impl AssemblySection {
     }
 }
 
-/// Escapes special characters in strings for assembly output.
+/// Quotes a string for use as a NASM `db` operand.
+///
+/// NASM takes double-quoted strings verbatim, so they are used whenever the
+/// text is printable ASCII without a double quote. Anything else goes into a
+/// backquoted string, the only NASM form that understands escape sequences.
 ///
 /// # Arguments
 ///
-/// * `s` - The string to escape.
+/// * `s` - The string to quote.
 ///
 /// # Returns
 ///
-/// A new `String` with special characters properly escaped for use in assembly
-/// string literals.
+/// The quoted string literal, including its delimiters.
 ///
 /// # Escaped Characters
 ///
-/// * `\` becomes `\\` (backslash)
-/// * `"` becomes `\"` (double quote)
-/// * `\n` becomes `\\n` (newline)
-/// * `\t` becomes `\\t` (tab)
-fn escape_string(s: &str) -> String {
-    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\\\n").replace('\t', "\\\\t")
+/// Inside backquotes:
+///
+/// * `` ` `` and `\` are prefixed with a backslash
+/// * newline, tab and carriage return become `\n`, `\t` and `\r`
+/// * every other control or non-ASCII byte becomes `\xHH` (UTF-8 bytes are kept)
+fn quote_string(s: &str) -> String {
+    if s.bytes().all(|b| (b' '..=b'~').contains(&b) && b != b'"') {
+        return format!("\"{s}\"");
+    }
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('`');
+    for byte in s.bytes() {
+        match byte {
+            b'`' => out.push_str("\\`"),
+            b'\\' => out.push_str("\\\\"),
+            b'\n' => out.push_str("\\n"),
+            b'\t' => out.push_str("\\t"),
+            b'\r' => out.push_str("\\r"),
+            b' '..=b'~' => out.push(char::from(byte)),
+            _ => {
+                let _ = write!(out, "\\x{byte:02x}");
+            }
+        }
+    }
+    out.push('`');
+    out
 }
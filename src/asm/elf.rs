@@ -0,0 +1,564 @@
+//! ELF64 relocatable object writer.
+//!
+//! [`ObjectFile::from_assembly`] assembles every section of an
+//! [`AssemblyFile`] with the built-in [encoder](super::encode_instruction) and
+//! collects its labels into a symbol table. [`ObjectFile::to_elf_bytes`] then
+//! serializes the result as an `x86_64` ELF64 relocatable object (`ET_REL`)
+//! that can be passed to `cc` or `ld`.
+//!
+//! Label references inside `.text` are resolved directly; references to other
+//! sections or to undefined symbols become `.rela.text` relocations. The
+//! entries of [`DataDirective::LabelOffsets`] tables are resolved within their
+//! own section. Labels
+//! listed in a section's `global_labels` get global binding, every other label
+//! stays local to the object.
+
+use super::{AssemblyElement, AssemblyFile, AssemblySection, DataDirective, EquExpression, Section};
+use super::{RelocationKind, encode_instruction};
+use crate::error::compile_error::CompileError;
+use crate::error::error_code::ErrorCode;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Section index of absolute symbols (`SHN_ABS`).
+const SHN_ABS: u16 = 0xFFF1;
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_STRTAB: u32 = 3;
+const SHT_RELA: u32 = 4;
+const SHT_NOBITS: u32 = 8;
+
+const SHF_WRITE: u64 = 0x1;
+const SHF_ALLOC: u64 = 0x2;
+const SHF_EXECINSTR: u64 = 0x4;
+const SHF_INFO_LINK: u64 = 0x40;
+
+const STB_LOCAL: u8 = 0;
+const STB_GLOBAL: u8 = 1;
+const STT_NOTYPE: u8 = 0;
+const STT_OBJECT: u8 = 1;
+const STT_FUNC: u8 = 2;
+const STT_SECTION: u8 = 3;
+
+const EHDR_SIZE: usize = 64;
+const SHDR_SIZE: usize = 64;
+const SYM_SIZE: usize = 24;
+const RELA_SIZE: usize = 24;
+
+/// Where a symbol is defined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolDefinition {
+    /// Offset into one of the object's sections.
+    Section(Section),
+    /// Absolute value, produced by `equ` directives.
+    Absolute,
+    /// Defined in another object file.
+    Undefined,
+}
+
+/// An entry of the object's symbol table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// Label name.
+    pub name: String,
+    /// Section, absolute value or external definition.
+    pub definition: SymbolDefinition,
+    /// Offset within the section, or the absolute value.
+    pub value: u64,
+    /// Whether the symbol is visible to other object files.
+    pub global: bool,
+}
+
+/// A label reference in `.text` left for the linker to resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relocation {
+    /// Offset of the patched field within `.text`.
+    pub offset: u64,
+    /// Referenced symbol.
+    pub symbol: String,
+    /// Relocation type.
+    pub kind: RelocationKind,
+    /// Constant added to the symbol address.
+    pub addend: i64,
+}
+
+/// Contents of one assembled section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectSection {
+    /// Which section this is.
+    pub section: Section,
+    /// Section contents; empty for `.bss`.
+    pub bytes: Vec<u8>,
+    /// Size in memory, which for `.bss` exceeds `bytes.len()`.
+    pub size: u64,
+}
+
+/// A relocatable object assembled from an [`AssemblyFile`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectFile {
+    sections: Vec<ObjectSection>,
+    symbols: Vec<Symbol>,
+    relocations: Vec<Relocation>,
+}
+
+/// A label reference recorded while assembling `.text`.
+struct PendingFixup {
+    /// Offset of the 32-bit field within the section.
+    offset: u64,
+    symbol: String,
+    kind: RelocationKind,
+    addend: i64,
+}
+
+fn asm_error(message: impl Into<String>) -> CompileError {
+    CompileError::AsmGeneratorError { code: Some(ErrorCode::E4001), message: Arc::from(message.into()) }
+}
+
+impl ObjectFile {
+    /// Assembles `assembly` into an object file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CompileError::AsmGeneratorError`] with code
+    /// [`ErrorCode::E4001`] when an instruction cannot be encoded, a label is
+    /// defined twice, `.bss` contains initialized data or an `equ` expression
+    /// cannot be evaluated.
+    pub fn from_assembly(assembly: &AssemblyFile) -> Result<Self, CompileError> {
+        let mut object = Self { sections: Vec::new(), symbols: Vec::new(), relocations: Vec::new() };
+        let mut fixups = Vec::new();
+        let sources = [
+            Some(assembly.text_section()),
+            Some(assembly.data_section()),
+            assembly.bss_section(),
+            assembly.rodata_section(),
+        ];
+        for source in sources.into_iter().flatten() {
+            object.assemble_section(source, &mut fixups)?;
+        }
+        object.apply_globals(&sources);
+        object.resolve(fixups);
+        Ok(object)
+    }
+
+    /// Returns the assembled sections in section header order.
+    #[must_use]
+    pub fn sections(&self) -> &[ObjectSection] {
+        &self.sections
+    }
+
+    /// Returns the assembled contents of `section`, if present.
+    #[must_use]
+    pub fn section(&self, section: &Section) -> Option<&ObjectSection> {
+        self.sections.iter().find(|s| &s.section == section)
+    }
+
+    /// Returns the symbols in definition order, followed by undefined
+    /// symbols in order of first reference.
+    #[must_use]
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// Returns the symbol named `name`, if any.
+    #[must_use]
+    pub fn symbol(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|symbol| symbol.name == name)
+    }
+
+    /// Returns the relocations of `.text`.
+    #[must_use]
+    pub fn relocations(&self) -> &[Relocation] {
+        &self.relocations
+    }
+
+    fn define(&mut self, name: &str, definition: SymbolDefinition, value: u64) -> Result<(), CompileError> {
+        if self.symbol(name).is_some() {
+            return Err(asm_error(format!("label `{name}` is defined more than once")));
+        }
+        self.symbols.push(Symbol { name: name.to_string(), definition, value, global: false });
+        Ok(())
+    }
+
+    fn assemble_section(
+        &mut self, source: &AssemblySection, fixups: &mut Vec<PendingFixup>,
+    ) -> Result<(), CompileError> {
+        let section = source.section.clone();
+        let nobits = section.is_bss();
+        let mut bytes = Vec::new();
+        let mut size = 0u64;
+        let mut offsets: HashMap<&str, u64> = HashMap::new();
+        // (field offset, label, table start) of every `LabelOffsets` entry
+        let mut label_offsets: Vec<(u64, &str, u64)> = Vec::new();
+
+        for element in &source.elements {
+            match element {
+                AssemblyElement::Label(name) => {
+                    self.define(name, SymbolDefinition::Section(section.clone()), size)?;
+                    offsets.insert(name, size);
+                }
+                AssemblyElement::Instruction(instr) | AssemblyElement::InstructionWithComment(instr, _) => {
+                    if nobits {
+                        return Err(asm_error(format!("instruction `{instr}` in {section}")));
+                    }
+                    let encoded = encode_instruction(instr)?;
+                    if let Some(fixup) = encoded.fixup {
+                        fixups.push(PendingFixup {
+                            offset: size + fixup.offset as u64,
+                            symbol: fixup.symbol,
+                            kind: fixup.kind,
+                            addend: fixup.addend,
+                        });
+                    }
+                    size += encoded.bytes.len() as u64;
+                    bytes.extend(encoded.bytes);
+                }
+                AssemblyElement::Data(label, DataDirective::Equ(expr)) => {
+                    let value = match expr {
+                        EquExpression::Constant(value) => value.cast_unsigned(),
+                        EquExpression::LengthOf(target) => {
+                            let start = offsets
+                                .get(target.as_str())
+                                .ok_or_else(|| asm_error(format!("`equ` refers to unknown label `{target}`")))?;
+                            size - start
+                        }
+                        EquExpression::Generic(text) => text
+                            .trim()
+                            .parse::<i64>()
+                            .map_err(|_| asm_error(format!("cannot evaluate `equ {text}`")))?
+                            .cast_unsigned(),
+                    };
+                    self.define(label, SymbolDefinition::Absolute, value)?;
+                }
+                AssemblyElement::Data(label, directive) => {
+                    self.define(label, SymbolDefinition::Section(section.clone()), size)?;
+                    offsets.insert(label, size);
+                    let (data, reserved) = directive_bytes(directive);
+                    if nobits && !data.is_empty() {
+                        return Err(asm_error(format!("initialized data `{label}` in {section}")));
+                    }
+                    if let DataDirective::LabelOffsets(labels) = directive {
+                        label_offsets
+                            .extend((size..).step_by(4).zip(labels).map(|(at, name)| (at, name.as_str(), size)));
+                    }
+                    size += (data.len() + reserved) as u64;
+                    if !nobits {
+                        bytes.extend(data);
+                        bytes.resize(bytes.len() + reserved, 0);
+                    }
+                }
+                AssemblyElement::Comment(_) | AssemblyElement::EmptyLine => {}
+            }
+        }
+        for (at, name, table) in label_offsets {
+            let target = offsets
+                .get(name)
+                .ok_or_else(|| asm_error(format!("label offset table refers to `{name}`, not defined in {section}")))?;
+            let relative = i32::try_from(target.cast_signed() - table.cast_signed())
+                .map_err(|_| asm_error(format!("offset of `{name}` does not fit in 32 bits")))?;
+            let start = usize::try_from(at).unwrap_or_default();
+            bytes[start..start + 4].copy_from_slice(&relative.to_le_bytes());
+        }
+        self.sections.push(ObjectSection { section, bytes, size });
+        Ok(())
+    }
+
+    /// Marks the labels listed in `global_labels` as global, declaring the
+    /// ones not defined in this object as undefined symbols.
+    fn apply_globals(&mut self, sources: &[Option<&AssemblySection>]) {
+        let globals = sources.iter().flatten().filter_map(|source| source.global_labels.as_ref()).flatten();
+        for name in globals {
+            if let Some(symbol) = self.symbols.iter_mut().find(|symbol| symbol.name == name.as_ref()) {
+                symbol.global = true;
+            } else {
+                self.symbols.push(Symbol {
+                    name: name.to_string(),
+                    definition: SymbolDefinition::Undefined,
+                    value: 0,
+                    global: true,
+                });
+            }
+        }
+    }
+
+    /// Patches label references within `.text` and records the others as
+    /// relocations, declaring unknown labels as undefined globals.
+    fn resolve(&mut self, fixups: Vec<PendingFixup>) {
+        for fixup in fixups {
+            let target = self.symbol(&fixup.symbol).map(|symbol| (symbol.definition.clone(), symbol.value));
+            if let Some((SymbolDefinition::Section(Section::Text), value)) = target {
+                let relative = value.cast_signed() + fixup.addend - fixup.offset.cast_signed();
+                let text = self.sections.iter_mut().find(|s| s.section == Section::Text).map(|s| &mut s.bytes);
+                if let (Some(text), Ok(relative)) = (text, i32::try_from(relative)) {
+                    let start = usize::try_from(fixup.offset).unwrap_or_default();
+                    text[start..start + 4].copy_from_slice(&relative.to_le_bytes());
+                    continue;
+                }
+            }
+            if target.is_none() {
+                self.symbols.push(Symbol {
+                    name: fixup.symbol.clone(),
+                    definition: SymbolDefinition::Undefined,
+                    value: 0,
+                    global: true,
+                });
+            }
+            self.relocations.push(Relocation {
+                offset: fixup.offset,
+                symbol: fixup.symbol,
+                kind: fixup.kind,
+                addend: fixup.addend,
+            });
+        }
+    }
+
+    /// Serializes the object as an `x86_64` ELF64 relocatable file.
+    ///
+    /// The file contains the assembled sections followed by `.symtab`,
+    /// `.strtab`, `.rela.text` and `.shstrtab`. Section symbols and local
+    /// labels precede global ones, as the ELF specification requires.
+    #[must_use]
+    pub fn to_elf_bytes(&self) -> Vec<u8> {
+        let mut shstrtab = StringTable::new();
+        let mut strtab = StringTable::new();
+
+        // Section header indices: 0 is the null section, then the assembled
+        // sections, then the metadata sections.
+        let section_index = |section: &Section| {
+            self.sections
+                .iter()
+                .position(|s| &s.section == section)
+                .map_or(0, |pos| u16::try_from(pos + 1).unwrap_or(0))
+        };
+        let note_index = self.sections.len() + 1;
+        let symtab_index = note_index + 1;
+        let strtab_index = symtab_index + 1;
+
+        let (symtab, symbol_index, first_global) = self.symbol_table(&mut strtab, section_index);
+
+        let mut rela = Vec::with_capacity(self.relocations.len() * RELA_SIZE);
+        for relocation in &self.relocations {
+            let symbol = symbol_index.get(relocation.symbol.as_str()).copied().unwrap_or(0) as u64;
+            rela.extend(relocation.offset.to_le_bytes());
+            rela.extend(((symbol << 32) | u64::from(relocation.kind.elf_type())).to_le_bytes());
+            rela.extend(relocation.addend.to_le_bytes());
+        }
+
+        let mut headers = vec![SectionHeader::default()];
+        let mut contents: Vec<&[u8]> = vec![&[]];
+        for section in &self.sections {
+            let (kind, flags, align) = match &section.section {
+                Section::Text => (SHT_PROGBITS, SHF_ALLOC | SHF_EXECINSTR, 16),
+                Section::Data => (SHT_PROGBITS, SHF_WRITE | SHF_ALLOC, 8),
+                Section::Bss => (SHT_NOBITS, SHF_WRITE | SHF_ALLOC, 8),
+                Section::Rodata => (SHT_PROGBITS, SHF_ALLOC, 8),
+            };
+            headers.push(SectionHeader {
+                name: shstrtab.add(section.section.name()),
+                kind,
+                flags,
+                size: section.size,
+                align,
+                ..SectionHeader::default()
+            });
+            contents.push(&section.bytes);
+        }
+        // An empty `.note.GNU-stack` marks the stack as non-executable.
+        headers.push(SectionHeader {
+            name: shstrtab.add(".note.GNU-stack"),
+            kind: SHT_PROGBITS,
+            align: 1,
+            ..SectionHeader::default()
+        });
+        contents.push(&[]);
+        headers.push(SectionHeader {
+            name: shstrtab.add(".symtab"),
+            kind: SHT_SYMTAB,
+            size: symtab.len() as u64,
+            link: u32::try_from(strtab_index).unwrap_or(0),
+            info: u32::try_from(first_global).unwrap_or(0),
+            align: 8,
+            entsize: SYM_SIZE as u64,
+            ..SectionHeader::default()
+        });
+        contents.push(&symtab);
+        headers.push(SectionHeader {
+            name: shstrtab.add(".strtab"),
+            kind: SHT_STRTAB,
+            size: strtab.bytes.len() as u64,
+            align: 1,
+            ..SectionHeader::default()
+        });
+        contents.push(&strtab.bytes);
+        headers.push(SectionHeader {
+            name: shstrtab.add(".rela.text"),
+            kind: SHT_RELA,
+            flags: SHF_INFO_LINK,
+            size: rela.len() as u64,
+            link: u32::try_from(symtab_index).unwrap_or(0),
+            info: u32::from(section_index(&Section::Text)),
+            align: 8,
+            entsize: RELA_SIZE as u64,
+            ..SectionHeader::default()
+        });
+        contents.push(&rela);
+        let shstrtab_index = headers.len();
+        let shstrtab_name = shstrtab.add(".shstrtab");
+        headers.push(SectionHeader {
+            name: shstrtab_name,
+            kind: SHT_STRTAB,
+            size: shstrtab.bytes.len() as u64,
+            align: 1,
+            ..SectionHeader::default()
+        });
+        contents.push(&shstrtab.bytes);
+
+        // Section contents follow the ELF header, each at its alignment;
+        // the section header table comes last.
+        let mut out = vec![0; EHDR_SIZE];
+        for (header, data) in headers.iter_mut().zip(&contents).skip(1) {
+            out.resize(out.len().next_multiple_of(usize::try_from(header.align).unwrap_or(1)), 0);
+            header.offset = out.len() as u64;
+            out.extend_from_slice(data);
+        }
+        out.resize(out.len().next_multiple_of(8), 0);
+        let shoff = out.len() as u64;
+        for header in &headers {
+            header.write(&mut out);
+        }
+
+        out[..EHDR_SIZE].copy_from_slice(&elf_header(shoff, headers.len(), shstrtab_index));
+        out
+    }
+
+    /// Builds the `.symtab` contents: the null symbol, one symbol per
+    /// section, the local labels and then the global ones.
+    ///
+    /// Returns the table, the index of every named symbol and the index of
+    /// the first global symbol.
+    fn symbol_table(
+        &self, strtab: &mut StringTable, section_index: impl Fn(&Section) -> u16,
+    ) -> (Vec<u8>, HashMap<&str, usize>, usize) {
+        let mut symtab = vec![0; SYM_SIZE];
+        for index in 1..=self.sections.len() {
+            push_symbol(&mut symtab, 0, STT_SECTION, STB_LOCAL, u16::try_from(index).unwrap_or(0), 0);
+        }
+        let (globals, locals): (Vec<&Symbol>, Vec<&Symbol>) = self.symbols.iter().partition(|symbol| symbol.global);
+        let first_global = self.sections.len() + 1 + locals.len();
+        let mut symbol_index = HashMap::new();
+        for (index, symbol) in (self.sections.len() + 1..).zip(locals.into_iter().chain(globals)) {
+            let (shndx, kind) = match &symbol.definition {
+                SymbolDefinition::Section(section) if symbol.global && section.is_text() => {
+                    (section_index(section), STT_FUNC)
+                }
+                SymbolDefinition::Section(section) if symbol.global => (section_index(section), STT_OBJECT),
+                SymbolDefinition::Section(section) => (section_index(section), STT_NOTYPE),
+                SymbolDefinition::Absolute => (SHN_ABS, STT_NOTYPE),
+                SymbolDefinition::Undefined => (0, STT_NOTYPE),
+            };
+            let binding = if symbol.global { STB_GLOBAL } else { STB_LOCAL };
+            push_symbol(&mut symtab, strtab.add(&symbol.name), kind, binding, shndx, symbol.value);
+            symbol_index.insert(symbol.name.as_str(), index);
+        }
+        (symtab, symbol_index, first_global)
+    }
+}
+
+/// Returns the initialized bytes of `directive` and the number of zero bytes
+/// it reserves after them.
+fn directive_bytes(directive: &DataDirective) -> (Vec<u8>, usize) {
+    match directive {
+        DataDirective::Db(values) => (values.clone(), 0),
+        DataDirective::Dw(values) => (values.iter().flat_map(|v| v.to_le_bytes()).collect(), 0),
+        DataDirective::Dd(values) => (values.iter().flat_map(|v| v.to_le_bytes()).collect(), 0),
+        DataDirective::Dq(values) => (values.iter().flat_map(|v| v.to_le_bytes()).collect(), 0),
+        DataDirective::Asciz(text, terminator) => {
+            let mut bytes = text.as_bytes().to_vec();
+            bytes.push(*terminator);
+            (bytes, 0)
+        }
+        DataDirective::Ascii(text) => (text.as_bytes().to_vec(), 0),
+        DataDirective::Resb(count) => (Vec::new(), *count),
+        DataDirective::Resw(count) => (Vec::new(), count * 2),
+        DataDirective::Resd(count) => (Vec::new(), count * 4),
+        DataDirective::Resq(count) => (Vec::new(), count * 8),
+        DataDirective::Equ(_) => (Vec::new(), 0),
+        // Patched once the section's labels are known
+        DataDirective::LabelOffsets(labels) => (vec![0; labels.len() * 4], 0),
+    }
+}
+
+/// Builds the ELF header of an `x86_64` relocatable object.
+fn elf_header(shoff: u64, shnum: usize, shstrndx: usize) -> Vec<u8> {
+    let mut ehdr = Vec::with_capacity(EHDR_SIZE);
+    ehdr.extend([0x7F, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    ehdr.extend(1u16.to_le_bytes()); // ET_REL
+    ehdr.extend(62u16.to_le_bytes()); // EM_X86_64
+    ehdr.extend(1u32.to_le_bytes()); // EV_CURRENT
+    ehdr.extend(0u64.to_le_bytes()); // e_entry
+    ehdr.extend(0u64.to_le_bytes()); // e_phoff
+    ehdr.extend(shoff.to_le_bytes());
+    ehdr.extend(0u32.to_le_bytes()); // e_flags
+    ehdr.extend(EHDR_SIZE.to_le_bytes()[..2].iter());
+    ehdr.extend(0u16.to_le_bytes()); // e_phentsize
+    ehdr.extend(0u16.to_le_bytes()); // e_phnum
+    ehdr.extend(SHDR_SIZE.to_le_bytes()[..2].iter());
+    ehdr.extend(u16::try_from(shnum).unwrap_or(0).to_le_bytes());
+    ehdr.extend(u16::try_from(shstrndx).unwrap_or(0).to_le_bytes());
+    ehdr
+}
+
+fn push_symbol(out: &mut Vec<u8>, name: u32, kind: u8, binding: u8, shndx: u16, value: u64) {
+    out.extend(name.to_le_bytes());
+    out.push((binding << 4) | kind);
+    out.push(0); // st_other: default visibility
+    out.extend(shndx.to_le_bytes());
+    out.extend(value.to_le_bytes());
+    out.extend(0u64.to_le_bytes()); // st_size
+}
+
+/// A string table: NUL-terminated names referenced by offset.
+struct StringTable {
+    bytes: Vec<u8>,
+}
+
+impl StringTable {
+    fn new() -> Self {
+        Self { bytes: vec![0] }
+    }
+
+    fn add(&mut self, name: &str) -> u32 {
+        let offset = u32::try_from(self.bytes.len()).unwrap_or(0);
+        self.bytes.extend(name.as_bytes());
+        self.bytes.push(0);
+        offset
+    }
+}
+
+#[derive(Default)]
+struct SectionHeader {
+    name: u32,
+    kind: u32,
+    flags: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+    align: u64,
+    entsize: u64,
+}
+
+impl SectionHeader {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend(self.name.to_le_bytes());
+        out.extend(self.kind.to_le_bytes());
+        out.extend(self.flags.to_le_bytes());
+        out.extend(0u64.to_le_bytes()); // sh_addr
+        out.extend(self.offset.to_le_bytes());
+        out.extend(self.size.to_le_bytes());
+        out.extend(self.link.to_le_bytes());
+        out.extend(self.info.to_le_bytes());
+        out.extend(self.align.to_le_bytes());
+        out.extend(self.entsize.to_le_bytes());
+    }
+}
@@ -0,0 +1,972 @@
+//! Machine-code encoder for the `x86_64` instruction subset used by the backend.
+//!
+//! [`encode_instruction`] turns an [`Instruction`] into its byte encoding:
+//! legacy and mandatory prefixes, REX, opcode, `ModRM`, SIB, displacement and
+//! immediate. The general purpose and SSE instructions emitted by the code
+//! generator are supported; AVX and x87 instructions are rejected.
+//!
+//! References to labels (jump and call targets, RIP-relative data) cannot be
+//! resolved while encoding a single instruction. They are encoded with a zero
+//! 32-bit field and described by a [`LabelFixup`], which the object writer
+//! either patches directly or turns into a relocation.
+//!
+//! Loading the address of a label (`mov reg, label`) is encoded as a
+//! RIP-relative `lea`, which yields the same value and keeps the code
+//! position independent.
+
+use super::{GPRegister8, GPRegister16, GPRegister32, GPRegister64, Instruction, MemoryOperand, Operand};
+use super::{X86Register, XMMRegister};
+use crate::error::compile_error::CompileError;
+use crate::error::error_code::ErrorCode;
+use std::sync::Arc;
+
+/// How a label reference is resolved by the linker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelocationKind {
+    /// 32-bit PC-relative reference (`R_X86_64_PC32`), used for jumps and
+    /// RIP-relative data.
+    Pc32,
+    /// 32-bit PC-relative reference through the PLT (`R_X86_64_PLT32`), used
+    /// for calls.
+    Plt32,
+}
+
+impl RelocationKind {
+    /// Returns the ELF relocation type number.
+    #[must_use]
+    pub const fn elf_type(self) -> u32 {
+        match self {
+            Self::Pc32 => 2,
+            Self::Plt32 => 4,
+        }
+    }
+}
+
+/// A 32-bit field of an encoded instruction that refers to a label.
+///
+/// The final value of the field is `symbol + addend - P`, where `P` is the
+/// address of the field itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelFixup {
+    /// Offset of the 32-bit field from the start of the instruction.
+    pub offset: usize,
+    /// Referenced label.
+    pub symbol: String,
+    /// How the reference is resolved.
+    pub kind: RelocationKind,
+    /// Constant added to the symbol address.
+    pub addend: i64,
+}
+
+/// Bytes of one encoded instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedInstruction {
+    /// Machine code, with label references left as zero.
+    pub bytes: Vec<u8>,
+    /// Label reference contained in the instruction, if any.
+    pub fixup: Option<LabelFixup>,
+}
+
+/// Encodes `instr` into `x86_64` machine code.
+///
+/// # Errors
+///
+/// Returns a [`CompileError::AsmGeneratorError`] with code
+/// [`ErrorCode::E4001`] when the instruction or one of its operand
+/// combinations has no encoding in the supported subset.
+///
+/// # Examples
+///
+/// ```
+/// use jsavrs::asm::{GPRegister64, Instruction, Operand, encode_instruction};
+///
+/// let add = Instruction::Add { dest: Operand::reg64(GPRegister64::Rax), src: Operand::reg64(GPRegister64::Rcx) };
+/// assert_eq!(encode_instruction(&add).unwrap().bytes, vec![0x48, 0x01, 0xC8]);
+/// ```
+pub fn encode_instruction(instr: &Instruction) -> Result<EncodedInstruction, CompileError> {
+    encode(instr).map_err(|reason| CompileError::AsmGeneratorError {
+        code: Some(ErrorCode::E4001),
+        message: Arc::from(format!("cannot encode `{instr}`: {reason}")),
+    })
+}
+
+type EncodeResult = Result<EncodedInstruction, String>;
+
+/// Condition codes in the order of their `Jcc`/`SETcc`/`CMOVcc` encoding.
+#[derive(Clone, Copy)]
+#[repr(u8)]
+enum Cond {
+    O = 0,
+    No = 1,
+    B = 2,
+    Ae = 3,
+    E = 4,
+    Ne = 5,
+    Be = 6,
+    A = 7,
+    S = 8,
+    Ns = 9,
+    P = 10,
+    Np = 11,
+    L = 12,
+    Ge = 13,
+    Le = 14,
+    G = 15,
+}
+
+#[allow(clippy::too_many_lines)]
+fn encode(instr: &Instruction) -> EncodeResult {
+    use Instruction as I;
+    match instr {
+        I::Add { dest, src } => alu(0, dest, src),
+        I::Or { dest, src } => alu(1, dest, src),
+        I::Adc { dest, src } => alu(2, dest, src),
+        I::Sbb { dest, src } => alu(3, dest, src),
+        I::And { dest, src } => alu(4, dest, src),
+        I::Sub { dest, src } => alu(5, dest, src),
+        I::Xor { dest, src } => alu(6, dest, src),
+        I::Cmp { op1, op2 } => alu(7, op1, op2),
+        I::Test { op1, op2 } => test(op1, op2),
+        I::Mov { dest, src } => mov(dest, src),
+        I::Movsx { dest, src } => extend(dest, src, true),
+        I::Movzx { dest, src } => extend(dest, src, false),
+        I::Movsxd { dest, src } => movsxd(dest, src),
+        I::Lea { dest, src } => lea(dest, src),
+        I::Push { src } => push(src),
+        I::Pop { dest } => pop(dest),
+        I::Xchg { op1, op2 } => xchg(op1, op2),
+        I::Inc { dest } => group(0xFE, 0, dest),
+        I::Dec { dest } => group(0xFE, 1, dest),
+        I::Not { dest } => group(0xF6, 2, dest),
+        I::Neg { dest } => group(0xF6, 3, dest),
+        I::Mul { src } => group(0xF6, 4, src),
+        I::Div { src } => group(0xF6, 6, src),
+        I::Idiv { src } => group(0xF6, 7, src),
+        I::Imul { dest, src1, src2 } => imul(dest.as_ref(), src1, src2.as_ref()),
+        I::Rol { dest, count } => shift(0, dest, count),
+        I::Ror { dest, count } => shift(1, dest, count),
+        I::Rcl { dest, count } => shift(2, dest, count),
+        I::Rcr { dest, count } => shift(3, dest, count),
+        I::Shl { dest, count } | I::Sal { dest, count } => shift(4, dest, count),
+        I::Shr { dest, count } => shift(5, dest, count),
+        I::Sar { dest, count } => shift(7, dest, count),
+
+        I::Jmp { target } => branch(&[0xE9], 4, target, RelocationKind::Pc32),
+        I::Call { target } => branch(&[0xE8], 2, target, RelocationKind::Plt32),
+        I::Je { target } | I::Jz { target } => jcc(Cond::E, target),
+        I::Jne { target } | I::Jnz { target } => jcc(Cond::Ne, target),
+        I::Jg { target } => jcc(Cond::G, target),
+        I::Jge { target } => jcc(Cond::Ge, target),
+        I::Jl { target } => jcc(Cond::L, target),
+        I::Jle { target } => jcc(Cond::Le, target),
+        I::Ja { target } => jcc(Cond::A, target),
+        I::Jae { target } => jcc(Cond::Ae, target),
+        I::Jb { target } => jcc(Cond::B, target),
+        I::Jbe { target } => jcc(Cond::Be, target),
+        I::Js { target } => jcc(Cond::S, target),
+        I::Jns { target } => jcc(Cond::Ns, target),
+        I::Jo { target } => jcc(Cond::O, target),
+        I::Jno { target } => jcc(Cond::No, target),
+        I::Jp { target } => jcc(Cond::P, target),
+        I::Jnp { target } => jcc(Cond::Np, target),
+
+        I::Sete { dest } => setcc(Cond::E, dest),
+        I::Setne { dest } => setcc(Cond::Ne, dest),
+        I::Setg { dest } => setcc(Cond::G, dest),
+        I::Setge { dest } => setcc(Cond::Ge, dest),
+        I::Setl { dest } => setcc(Cond::L, dest),
+        I::Setle { dest } => setcc(Cond::Le, dest),
+        I::Seta { dest } => setcc(Cond::A, dest),
+        I::Setae { dest } => setcc(Cond::Ae, dest),
+        I::Setb { dest } => setcc(Cond::B, dest),
+        I::Setbe { dest } => setcc(Cond::Be, dest),
+        I::Setp { dest } => setcc(Cond::P, dest),
+        I::Setnp { dest } => setcc(Cond::Np, dest),
+
+        I::Cmove { dest, src } => cmovcc(Cond::E, dest, src),
+        I::Cmovne { dest, src } => cmovcc(Cond::Ne, dest, src),
+        I::Cmovg { dest, src } => cmovcc(Cond::G, dest, src),
+        I::Cmovge { dest, src } => cmovcc(Cond::Ge, dest, src),
+        I::Cmovl { dest, src } => cmovcc(Cond::L, dest, src),
+        I::Cmovle { dest, src } => cmovcc(Cond::Le, dest, src),
+        I::Cmova { dest, src } => cmovcc(Cond::A, dest, src),
+        I::Cmovae { dest, src } => cmovcc(Cond::Ae, dest, src),
+        I::Cmovb { dest, src } => cmovcc(Cond::B, dest, src),
+        I::Cmovbe { dest, src } => cmovcc(Cond::Be, dest, src),
+
+        I::Bt { dest, src } => bit_test(4, dest, src),
+        I::Bts { dest, src } => bit_test(5, dest, src),
+        I::Btr { dest, src } => bit_test(6, dest, src),
+        I::Btc { dest, src } => bit_test(7, dest, src),
+        I::Bsf { dest, src } => reg_rm(None, &[0x0F, 0xBC], dest, src),
+        I::Bsr { dest, src } => reg_rm(None, &[0x0F, 0xBD], dest, src),
+        I::Popcnt { dest, src } => reg_rm(Some(0xF3), &[0x0F, 0xB8], dest, src),
+        I::Lzcnt { dest, src } => reg_rm(Some(0xF3), &[0x0F, 0xBD], dest, src),
+        I::Tzcnt { dest, src } => reg_rm(Some(0xF3), &[0x0F, 0xBC], dest, src),
+
+        I::Ret => Ok(plain(&[0xC3])),
+        I::RetImm { imm } => {
+            let mut bytes = vec![0xC2];
+            bytes.extend(imm.to_le_bytes());
+            Ok(plain(&bytes))
+        }
+        I::Nop => Ok(plain(&[0x90])),
+        I::Hlt => Ok(plain(&[0xF4])),
+        I::Cpuid => Ok(plain(&[0x0F, 0xA2])),
+        I::Pause => Ok(plain(&[0xF3, 0x90])),
+        I::Cqo => Ok(plain(&[0x48, 0x99])),
+        I::Cdq => Ok(plain(&[0x99])),
+        I::Syscall => Ok(plain(&[0x0F, 0x05])),
+        I::Sysret => Ok(plain(&[0x0F, 0x07])),
+        I::Movsb => Ok(plain(&[0xA4])),
+        I::Movsw => Ok(plain(&[0x66, 0xA5])),
+        I::MovsdString => Ok(plain(&[0xA5])),
+        I::Movsq => Ok(plain(&[0x48, 0xA5])),
+        I::Stosb => Ok(plain(&[0xAA])),
+        I::Stosw => Ok(plain(&[0x66, 0xAB])),
+        I::Stosd => Ok(plain(&[0xAB])),
+        I::Stosq => Ok(plain(&[0x48, 0xAB])),
+
+        I::Movaps { dest, src } => sse_move(None, 0x28, 0x29, dest, src),
+        I::Movapd { dest, src } => sse_move(Some(0x66), 0x28, 0x29, dest, src),
+        I::Movups { dest, src } => sse_move(None, 0x10, 0x11, dest, src),
+        I::Movupd { dest, src } => sse_move(Some(0x66), 0x10, 0x11, dest, src),
+        I::Movss { dest, src } => sse_move(Some(0xF3), 0x10, 0x11, dest, src),
+        I::Movsd { dest, src } => sse_move(Some(0xF2), 0x10, 0x11, dest, src),
+        I::Movdqa { dest, src } => sse_move(Some(0x66), 0x6F, 0x7F, dest, src),
+        I::Movdqu { dest, src } => sse_move(Some(0xF3), 0x6F, 0x7F, dest, src),
+        I::Movq { dest, src } => movq(dest, src),
+
+        I::Addps { dest, src } => sse_op(None, 0x58, dest, src),
+        I::Addpd { dest, src } => sse_op(Some(0x66), 0x58, dest, src),
+        I::Addss { dest, src } => sse_op(Some(0xF3), 0x58, dest, src),
+        I::Addsd { dest, src } => sse_op(Some(0xF2), 0x58, dest, src),
+        I::Mulps { dest, src } => sse_op(None, 0x59, dest, src),
+        I::Mulpd { dest, src } => sse_op(Some(0x66), 0x59, dest, src),
+        I::Mulss { dest, src } => sse_op(Some(0xF3), 0x59, dest, src),
+        I::Mulsd { dest, src } => sse_op(Some(0xF2), 0x59, dest, src),
+        I::Subps { dest, src } => sse_op(None, 0x5C, dest, src),
+        I::Subpd { dest, src } => sse_op(Some(0x66), 0x5C, dest, src),
+        I::Subss { dest, src } => sse_op(Some(0xF3), 0x5C, dest, src),
+        I::Subsd { dest, src } => sse_op(Some(0xF2), 0x5C, dest, src),
+        I::Divps { dest, src } => sse_op(None, 0x5E, dest, src),
+        I::Divpd { dest, src } => sse_op(Some(0x66), 0x5E, dest, src),
+        I::Divss { dest, src } => sse_op(Some(0xF3), 0x5E, dest, src),
+        I::Divsd { dest, src } => sse_op(Some(0xF2), 0x5E, dest, src),
+        I::Andps { dest, src } => sse_op(None, 0x54, dest, src),
+        I::Andpd { dest, src } => sse_op(Some(0x66), 0x54, dest, src),
+        I::Andnps { dest, src } => sse_op(None, 0x55, dest, src),
+        I::Andnpd { dest, src } => sse_op(Some(0x66), 0x55, dest, src),
+        I::Orps { dest, src } => sse_op(None, 0x56, dest, src),
+        I::Orpd { dest, src } => sse_op(Some(0x66), 0x56, dest, src),
+        I::Xorps { dest, src } => sse_op(None, 0x57, dest, src),
+        I::Xorpd { dest, src } => sse_op(Some(0x66), 0x57, dest, src),
+        I::Cvtss2sd { dest, src } => sse_op(Some(0xF3), 0x5A, dest, src),
+        I::Cvtsd2ss { dest, src } => sse_op(Some(0xF2), 0x5A, dest, src),
+        I::Ucomiss { op1, op2 } => sse_op(None, 0x2E, op1, op2),
+        I::Ucomisd { op1, op2 } => sse_op(Some(0x66), 0x2E, op1, op2),
+        I::Cvtsi2ss { dest, src } => int_to_float(0xF3, dest, src),
+        I::Cvtsi2sd { dest, src } => int_to_float(0xF2, dest, src),
+        I::Cvttss2si { dest, src } => float_to_int(0xF3, dest, src),
+        I::Cvttsd2si { dest, src } => float_to_int(0xF2, dest, src),
+
+        _ => Err(format!("`{}` is not supported by the built-in encoder", instr.mnemonic())),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Operand classification
+// ---------------------------------------------------------------------------
+
+/// A general purpose register with its hardware number and width.
+#[derive(Clone, Copy)]
+struct Gp {
+    num: u8,
+    size: usize,
+    /// `SPL`, `BPL`, `SIL` and `DIL` are only reachable with a REX prefix.
+    needs_rex: bool,
+    /// `AH`, `BH`, `CH` and `DH` cannot be encoded together with a REX prefix.
+    high_byte: bool,
+}
+
+const fn gp64_num(reg: GPRegister64) -> u8 {
+    match reg {
+        GPRegister64::Rax => 0,
+        GPRegister64::Rcx => 1,
+        GPRegister64::Rdx => 2,
+        GPRegister64::Rbx => 3,
+        GPRegister64::Rsp => 4,
+        GPRegister64::Rbp => 5,
+        GPRegister64::Rsi => 6,
+        GPRegister64::Rdi => 7,
+        GPRegister64::R8 => 8,
+        GPRegister64::R9 => 9,
+        GPRegister64::R10 => 10,
+        GPRegister64::R11 => 11,
+        GPRegister64::R12 => 12,
+        GPRegister64::R13 => 13,
+        GPRegister64::R14 => 14,
+        GPRegister64::R15 => 15,
+    }
+}
+
+const fn gp32_num(reg: GPRegister32) -> u8 {
+    match reg {
+        GPRegister32::Eax => 0,
+        GPRegister32::Ecx => 1,
+        GPRegister32::Edx => 2,
+        GPRegister32::Ebx => 3,
+        GPRegister32::Esp => 4,
+        GPRegister32::Ebp => 5,
+        GPRegister32::Esi => 6,
+        GPRegister32::Edi => 7,
+        GPRegister32::R8d => 8,
+        GPRegister32::R9d => 9,
+        GPRegister32::R10d => 10,
+        GPRegister32::R11d => 11,
+        GPRegister32::R12d => 12,
+        GPRegister32::R13d => 13,
+        GPRegister32::R14d => 14,
+        GPRegister32::R15d => 15,
+    }
+}
+
+const fn gp16_num(reg: GPRegister16) -> u8 {
+    match reg {
+        GPRegister16::Ax => 0,
+        GPRegister16::Cx => 1,
+        GPRegister16::Dx => 2,
+        GPRegister16::Bx => 3,
+        GPRegister16::Sp => 4,
+        GPRegister16::Bp => 5,
+        GPRegister16::Si => 6,
+        GPRegister16::Di => 7,
+        GPRegister16::R8w => 8,
+        GPRegister16::R9w => 9,
+        GPRegister16::R10w => 10,
+        GPRegister16::R11w => 11,
+        GPRegister16::R12w => 12,
+        GPRegister16::R13w => 13,
+        GPRegister16::R14w => 14,
+        GPRegister16::R15w => 15,
+    }
+}
+
+const fn gp8(reg: GPRegister8) -> Gp {
+    let (num, needs_rex, high_byte) = match reg {
+        GPRegister8::Al => (0, false, false),
+        GPRegister8::Cl => (1, false, false),
+        GPRegister8::Dl => (2, false, false),
+        GPRegister8::Bl => (3, false, false),
+        GPRegister8::Ah => (4, false, true),
+        GPRegister8::Ch => (5, false, true),
+        GPRegister8::Dh => (6, false, true),
+        GPRegister8::Bh => (7, false, true),
+        GPRegister8::Spl => (4, true, false),
+        GPRegister8::Bpl => (5, true, false),
+        GPRegister8::Sil => (6, true, false),
+        GPRegister8::Dil => (7, true, false),
+        GPRegister8::R8b => (8, false, false),
+        GPRegister8::R9b => (9, false, false),
+        GPRegister8::R10b => (10, false, false),
+        GPRegister8::R11b => (11, false, false),
+        GPRegister8::R12b => (12, false, false),
+        GPRegister8::R13b => (13, false, false),
+        GPRegister8::R14b => (14, false, false),
+        GPRegister8::R15b => (15, false, false),
+    };
+    Gp { num, size: 1, needs_rex, high_byte }
+}
+
+const fn gp(op: &Operand) -> Option<Gp> {
+    let Operand::Register(reg) = op else { return None };
+    let (num, size) = match reg {
+        X86Register::GP64(r) => (gp64_num(*r), 8),
+        X86Register::GP32(r) => (gp32_num(*r), 4),
+        X86Register::GP16(r) => (gp16_num(*r), 2),
+        X86Register::GP8(r) => return Some(gp8(*r)),
+        _ => return None,
+    };
+    Some(Gp { num, size, needs_rex: false, high_byte: false })
+}
+
+const fn xmm_num(reg: XMMRegister) -> u8 {
+    match reg {
+        XMMRegister::Xmm0 => 0,
+        XMMRegister::Xmm1 => 1,
+        XMMRegister::Xmm2 => 2,
+        XMMRegister::Xmm3 => 3,
+        XMMRegister::Xmm4 => 4,
+        XMMRegister::Xmm5 => 5,
+        XMMRegister::Xmm6 => 6,
+        XMMRegister::Xmm7 => 7,
+        XMMRegister::Xmm8 => 8,
+        XMMRegister::Xmm9 => 9,
+        XMMRegister::Xmm10 => 10,
+        XMMRegister::Xmm11 => 11,
+        XMMRegister::Xmm12 => 12,
+        XMMRegister::Xmm13 => 13,
+        XMMRegister::Xmm14 => 14,
+        XMMRegister::Xmm15 => 15,
+    }
+}
+
+const fn xmm(op: &Operand) -> Option<u8> {
+    match op {
+        Operand::Register(X86Register::Xmm(r)) => Some(xmm_num(*r)),
+        _ => None,
+    }
+}
+
+/// The `r/m` operand of a `ModRM`-encoded instruction.
+enum Rm<'a> {
+    Reg(u8),
+    Mem(&'a MemoryOperand),
+    Rip(&'a str),
+}
+
+/// Classifies `op` as an `r/m` operand, returning it together with the byte
+/// register flags of general purpose registers.
+fn rm_of(op: &Operand) -> Result<(Rm<'_>, Option<Gp>), String> {
+    match op {
+        Operand::Memory(mem) => Ok((Rm::Mem(mem), None)),
+        Operand::Label(label) => Ok((Rm::Rip(label), None)),
+        _ => match (gp(op), xmm(op)) {
+            (Some(reg), _) => Ok((Rm::Reg(reg.num), Some(reg))),
+            (None, Some(num)) => Ok((Rm::Reg(num), None)),
+            (None, None) => Err(format!("unsupported operand `{op}`")),
+        },
+    }
+}
+
+/// Operand size of an `r/m` operand: the register width or the memory size.
+fn rm_size(op: &Operand) -> Option<usize> {
+    match op {
+        Operand::Memory(mem) => Some(mem.size),
+        _ => gp(op).map(|reg| reg.size),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Core encoding
+// ---------------------------------------------------------------------------
+
+/// A `ModRM`-encoded instruction before serialization.
+struct Form<'a> {
+    /// Operand-size override (`0x66`) for 16-bit operations.
+    opsize: bool,
+    /// Mandatory prefix of SSE and bit-count instructions.
+    mandatory: Option<u8>,
+    rex_w: bool,
+    opcode: &'a [u8],
+    reg: u8,
+    rm: Rm<'a>,
+    imm: Vec<u8>,
+    /// Byte registers involved, checked against the REX prefix.
+    byte_regs: [Option<Gp>; 2],
+}
+
+impl<'a> Form<'a> {
+    const fn new(opcode: &'a [u8], reg: u8, rm: Rm<'a>) -> Self {
+        Self { opsize: false, mandatory: None, rex_w: false, opcode, reg, rm, imm: Vec::new(), byte_regs: [None; 2] }
+    }
+
+    /// Sets the operand-size prefixes for an operation of `size` bytes.
+    const fn sized(mut self, size: usize) -> Self {
+        self.opsize = size == 2;
+        self.rex_w = size == 8;
+        self
+    }
+
+    fn encode(self) -> EncodeResult {
+        let mut bytes = Vec::with_capacity(15);
+        if self.opsize {
+            bytes.push(0x66);
+        }
+        if let Some(prefix) = self.mandatory {
+            bytes.push(prefix);
+        }
+
+        let mut rex = 0u8;
+        if self.rex_w {
+            rex |= 0b1000;
+        }
+        if self.reg & 8 != 0 {
+            rex |= 0b0100;
+        }
+        match &self.rm {
+            Rm::Reg(num) if num & 8 != 0 => rex |= 0b0001,
+            Rm::Mem(mem) => {
+                if mem.index.is_some_and(|index| gp64_num(index) & 8 != 0) {
+                    rex |= 0b0010;
+                }
+                if mem.base.is_some_and(|base| gp64_num(base) & 8 != 0) {
+                    rex |= 0b0001;
+                }
+            }
+            _ => {}
+        }
+        let byte_regs = self.byte_regs.iter().flatten();
+        if rex != 0 || byte_regs.clone().any(|reg| reg.needs_rex) {
+            if byte_regs.clone().any(|reg| reg.high_byte) {
+                return Err("AH, BH, CH and DH cannot be used with a REX prefix".to_string());
+            }
+            bytes.push(0x40 | rex);
+        }
+
+        bytes.extend_from_slice(self.opcode);
+        let fixup = push_modrm(&mut bytes, self.reg, &self.rm, self.imm.len())?;
+        bytes.extend(self.imm);
+        Ok(EncodedInstruction { bytes, fixup })
+    }
+}
+
+/// Appends the `ModRM`, SIB and displacement bytes addressing `rm`.
+fn push_modrm(bytes: &mut Vec<u8>, reg: u8, rm: &Rm<'_>, imm_len: usize) -> Result<Option<LabelFixup>, String> {
+    let reg = (reg & 7) << 3;
+    match rm {
+        Rm::Reg(num) => {
+            bytes.push(0xC0 | reg | (num & 7));
+            Ok(None)
+        }
+        Rm::Rip(label) => {
+            bytes.push(reg | 0b101);
+            let offset = bytes.len();
+            bytes.extend([0; 4]);
+            let addend = -4 - i64::try_from(imm_len).unwrap_or_default();
+            Ok(Some(LabelFixup { offset, symbol: (*label).to_string(), kind: RelocationKind::Pc32, addend }))
+        }
+        Rm::Mem(mem) => {
+            let scale = match mem.scale {
+                1 => 0,
+                2 => 1,
+                4 => 2,
+                8 => 3,
+                other => return Err(format!("invalid scale factor {other}")),
+            };
+            let index = mem.index.map(gp64_num);
+            if index == Some(4) {
+                return Err("RSP cannot be used as an index register".to_string());
+            }
+            let sib_index = (index.unwrap_or(0b100) & 7) << 3;
+            let disp = mem.displacement;
+
+            let Some(base) = mem.base.map(gp64_num) else {
+                // Absolute address: SIB with no base and a 32-bit displacement.
+                bytes.extend([reg | 0b100, (scale << 6) | sib_index | 0b101]);
+                bytes.extend(disp.to_le_bytes());
+                return Ok(None);
+            };
+            let mode: u8 = if disp == 0 && base & 7 != 0b101 {
+                0b00
+            } else if i8::try_from(disp).is_ok() {
+                0b01
+            } else {
+                0b10
+            };
+            if index.is_some() || base & 7 == 0b100 {
+                bytes.extend([(mode << 6) | reg | 0b100, (scale << 6) | sib_index | (base & 7)]);
+            } else {
+                bytes.push((mode << 6) | reg | (base & 7));
+            }
+            match mode {
+                0b01 => bytes.extend(disp.to_le_bytes().first()),
+                0b10 => bytes.extend(disp.to_le_bytes()),
+                _ => {}
+            }
+            Ok(None)
+        }
+    }
+}
+
+fn plain(bytes: &[u8]) -> EncodedInstruction {
+    EncodedInstruction { bytes: bytes.to_vec(), fixup: None }
+}
+
+/// Encodes `value` as an immediate of `size` bytes.
+///
+/// Values must fit the signed or unsigned range of the immediate.
+fn imm_bytes(value: i64, size: usize) -> Result<Vec<u8>, String> {
+    let fits = match size {
+        1 => (-0x80..=0xFF).contains(&value),
+        2 => (-0x8000..=0xFFFF).contains(&value),
+        4 => (-0x8000_0000..=0xFFFF_FFFF).contains(&value),
+        _ => true,
+    };
+    if !fits {
+        return Err(format!("immediate {value} does not fit in {size} bytes"));
+    }
+    Ok(value.to_le_bytes()[..size].to_vec())
+}
+
+/// Encodes a sign-extended immediate for an operation of `size` bytes:
+/// at most 32 bits, even for 64-bit operations.
+fn sign_extended_imm(value: i64, size: usize) -> Result<Vec<u8>, String> {
+    if size == 8 {
+        let value = i32::try_from(value).map_err(|_| format!("immediate {value} does not fit in 32 bits"))?;
+        return Ok(value.to_le_bytes().to_vec());
+    }
+    imm_bytes(value, size.min(4))
+}
+
+const fn imm_value(op: &Operand) -> Option<i64> {
+    match op {
+        Operand::Immediate(imm) => Some(imm.as_i64()),
+        _ => None,
+    }
+}
+
+fn operand_size(op: &Operand) -> Result<usize, String> {
+    match rm_size(op) {
+        Some(size @ (1 | 2 | 4 | 8)) => Ok(size),
+        Some(size) => Err(format!("unsupported operand size {size}")),
+        None => Err(format!("unsupported operand `{op}`")),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// General purpose instructions
+// ---------------------------------------------------------------------------
+
+/// `ADD`, `OR`, `ADC`, `SBB`, `AND`, `SUB`, `XOR` and `CMP`, selected by their
+/// opcode extension `ext`.
+fn alu(ext: u8, dest: &Operand, src: &Operand) -> EncodeResult {
+    let base = ext * 8;
+    if let Some(value) = imm_value(src) {
+        let size = operand_size(dest)?;
+        let (rm, reg) = rm_of(dest)?;
+        let (opcode, imm): (&[u8], _) = if size == 1 {
+            (&[0x80], imm_bytes(value, 1)?)
+        } else if i8::try_from(value).is_ok() {
+            (&[0x83], imm_bytes(value, 1)?)
+        } else {
+            (&[0x81], sign_extended_imm(value, size)?)
+        };
+        let mut form = Form::new(opcode, ext, rm).sized(size);
+        form.imm = imm;
+        form.byte_regs = [reg, None];
+        return form.encode();
+    }
+    rm_reg_pair([base, base + 1], [base + 2, base + 3], dest, src)
+}
+
+/// Encodes a two-operand instruction with an `r/m, reg` and a `reg, r/m`
+/// form, each given as its byte and word opcode.
+fn rm_reg_pair(store: [u8; 2], load: [u8; 2], dest: &Operand, src: &Operand) -> EncodeResult {
+    let (opcodes, reg, rm) = if let Some(reg) = gp(src) {
+        (store, reg, dest)
+    } else if let Some(reg) = gp(dest) {
+        (load, reg, src)
+    } else {
+        return Err(format!("unsupported operands `{dest}`, `{src}`"));
+    };
+    if rm_size(rm).is_some_and(|size| size != reg.size) {
+        return Err(format!("operand size mismatch between `{dest}` and `{src}`"));
+    }
+    let (rm, rm_reg) = rm_of(rm)?;
+    let opcode = if reg.size == 1 { &opcodes[..1] } else { &opcodes[1..] };
+    let mut form = Form::new(opcode, reg.num, rm).sized(reg.size);
+    form.byte_regs = [Some(reg), rm_reg];
+    form.encode()
+}
+
+fn test(op1: &Operand, op2: &Operand) -> EncodeResult {
+    if let Some(value) = imm_value(op2) {
+        let size = operand_size(op1)?;
+        let (rm, reg) = rm_of(op1)?;
+        let opcode: &[u8] = if size == 1 { &[0xF6] } else { &[0xF7] };
+        let mut form = Form::new(opcode, 0, rm).sized(size);
+        form.imm = sign_extended_imm(value, size)?;
+        form.byte_regs = [reg, None];
+        return form.encode();
+    }
+    if gp(op2).is_some() { rm_reg_pair([0x84, 0x85], [0x84, 0x85], op1, op2) } else { test(op2, op1) }
+}
+
+fn mov(dest: &Operand, src: &Operand) -> EncodeResult {
+    if let Operand::Label(_) = src {
+        return lea(dest, src);
+    }
+    let Some(value) = imm_value(src) else {
+        return rm_reg_pair([0x88, 0x89], [0x8A, 0x8B], dest, src);
+    };
+    let size = operand_size(dest)?;
+    if let Some(reg) = gp(dest) {
+        // `mov r64, imm32` sign-extends; other values use `mov r32` or `movabs`.
+        if size == 8 && i32::try_from(value).is_ok() {
+            let mut form = Form::new(&[0xC7], 0, Rm::Reg(reg.num)).sized(8);
+            form.imm = sign_extended_imm(value, 8)?;
+            return form.encode();
+        }
+        let (op_size, imm) = match size {
+            8 if u32::try_from(value).is_ok() => (4, imm_bytes(value, 4)?),
+            8 => (8, value.to_le_bytes().to_vec()),
+            _ => (size, imm_bytes(value, size)?),
+        };
+        let opcode = if size == 1 { 0xB0 } else { 0xB8 };
+        return plus_reg(opcode, reg, op_size, &imm);
+    }
+    let (rm, _) = rm_of(dest)?;
+    let opcode: &[u8] = if size == 1 { &[0xC6] } else { &[0xC7] };
+    let mut form = Form::new(opcode, 0, rm).sized(size);
+    form.imm = sign_extended_imm(value, size)?;
+    form.encode()
+}
+
+/// Encodes an instruction whose opcode embeds the register number
+/// (`opcode + reg`), followed by `imm`.
+fn plus_reg(opcode: u8, reg: Gp, size: usize, imm: &[u8]) -> EncodeResult {
+    let mut bytes = Vec::with_capacity(10);
+    if size == 2 {
+        bytes.push(0x66);
+    }
+    let rex = u8::from(size == 8) << 3 | u8::from(reg.num & 8 != 0);
+    if rex != 0 || reg.needs_rex {
+        if reg.high_byte {
+            return Err("AH, BH, CH and DH cannot be used with a REX prefix".to_string());
+        }
+        bytes.push(0x40 | rex);
+    }
+    bytes.push(opcode + (reg.num & 7));
+    bytes.extend_from_slice(imm);
+    Ok(EncodedInstruction { bytes, fixup: None })
+}
+
+/// Encodes a `reg, r/m` instruction sized by the destination register.
+fn reg_rm(mandatory: Option<u8>, opcode: &[u8], dest: &Operand, src: &Operand) -> EncodeResult {
+    let reg = gp(dest).ok_or_else(|| format!("destination `{dest}` must be a general purpose register"))?;
+    let (rm, rm_reg) = rm_of(src)?;
+    let mut form = Form::new(opcode, reg.num, rm).sized(reg.size);
+    form.mandatory = mandatory;
+    form.byte_regs = [Some(reg), rm_reg];
+    form.encode()
+}
+
+fn extend(dest: &Operand, src: &Operand, signed: bool) -> EncodeResult {
+    let opcode: &[u8] = match (operand_size(src)?, signed) {
+        (1, true) => &[0x0F, 0xBE],
+        (2, true) => &[0x0F, 0xBF],
+        (1, false) => &[0x0F, 0xB6],
+        (2, false) => &[0x0F, 0xB7],
+        (size, _) => return Err(format!("cannot extend a {size}-byte operand")),
+    };
+    reg_rm(None, opcode, dest, src)
+}
+
+fn movsxd(dest: &Operand, src: &Operand) -> EncodeResult {
+    if operand_size(src)? != 4 {
+        return Err("source of `movsxd` must be 32 bits wide".to_string());
+    }
+    reg_rm(None, &[0x63], dest, src)
+}
+
+fn lea(dest: &Operand, src: &Operand) -> EncodeResult {
+    if !matches!(src, Operand::Memory(_) | Operand::Label(_)) {
+        return Err(format!("`{src}` is not an address"));
+    }
+    reg_rm(None, &[0x8D], dest, src)
+}
+
+fn push(src: &Operand) -> EncodeResult {
+    if let Some(value) = imm_value(src) {
+        let mut bytes = if i8::try_from(value).is_ok() { vec![0x6A] } else { vec![0x68] };
+        bytes.extend(sign_extended_imm(value, if bytes[0] == 0x6A { 1 } else { 8 })?);
+        return Ok(plain(&bytes));
+    }
+    match gp(src) {
+        Some(reg) if reg.size == 8 => plus_reg(0x50, reg, 4, &[]),
+        Some(_) => Err("only 64-bit registers can be pushed".to_string()),
+        None => Form::new(&[0xFF], 6, rm_of(src)?.0).encode(),
+    }
+}
+
+fn pop(dest: &Operand) -> EncodeResult {
+    match gp(dest) {
+        Some(reg) if reg.size == 8 => plus_reg(0x58, reg, 4, &[]),
+        Some(_) => Err("only 64-bit registers can be popped".to_string()),
+        None => Form::new(&[0x8F], 0, rm_of(dest)?.0).encode(),
+    }
+}
+
+fn xchg(op1: &Operand, op2: &Operand) -> EncodeResult {
+    if gp(op2).is_some() {
+        rm_reg_pair([0x86, 0x87], [0x86, 0x87], op1, op2)
+    } else {
+        rm_reg_pair([0x86, 0x87], [0x86, 0x87], op2, op1)
+    }
+}
+
+/// Encodes a single-operand instruction of the `0xF6`/`0xF7` or
+/// `0xFE`/`0xFF` groups; `byte_opcode + 1` is the word form.
+fn group(byte_opcode: u8, ext: u8, op: &Operand) -> EncodeResult {
+    let size = operand_size(op)?;
+    let (rm, reg) = rm_of(op)?;
+    let opcode = [if size == 1 { byte_opcode } else { byte_opcode + 1 }];
+    let mut form = Form::new(&opcode, ext, rm).sized(size);
+    form.byte_regs = [reg, None];
+    form.encode()
+}
+
+fn imul(dest: Option<&Operand>, src1: &Operand, src2: Option<&Operand>) -> EncodeResult {
+    match (dest, src2) {
+        (None, None) => group(0xF6, 5, src1),
+        (Some(dest), None) => reg_rm(None, &[0x0F, 0xAF], dest, src1),
+        (Some(dest), Some(src2)) => {
+            let value = imm_value(src2).ok_or("third operand of `imul` must be an immediate")?;
+            let reg = gp(dest).ok_or_else(|| format!("destination `{dest}` must be a general purpose register"))?;
+            let (rm, _) = rm_of(src1)?;
+            let (opcode, imm): (&[u8], _) = if i8::try_from(value).is_ok() {
+                (&[0x6B], imm_bytes(value, 1)?)
+            } else {
+                (&[0x69], sign_extended_imm(value, reg.size)?)
+            };
+            let mut form = Form::new(opcode, reg.num, rm).sized(reg.size);
+            form.imm = imm;
+            form.encode()
+        }
+        (None, Some(_)) => Err("`imul` with two sources needs a destination".to_string()),
+    }
+}
+
+fn shift(ext: u8, dest: &Operand, count: &Operand) -> EncodeResult {
+    let size = operand_size(dest)?;
+    let (rm, reg) = rm_of(dest)?;
+    let word = u8::from(size != 1);
+    let (opcode, imm) = match (count, imm_value(count)) {
+        (Operand::Register(X86Register::GP8(GPRegister8::Cl)), _) => (0xD2 + word, Vec::new()),
+        (_, Some(1)) => (0xD0 + word, Vec::new()),
+        (_, Some(value)) => (0xC0 + word, imm_bytes(value, 1)?),
+        _ => return Err(format!("shift count `{count}` must be CL or an immediate")),
+    };
+    let opcode = [opcode];
+    let mut form = Form::new(&opcode, ext, rm).sized(size);
+    form.imm = imm;
+    form.byte_regs = [reg, None];
+    form.encode()
+}
+
+fn bit_test(ext: u8, dest: &Operand, src: &Operand) -> EncodeResult {
+    let size = operand_size(dest)?;
+    if let Some(value) = imm_value(src) {
+        let mut form = Form::new(&[0x0F, 0xBA], ext, rm_of(dest)?.0).sized(size);
+        form.imm = imm_bytes(value, 1)?;
+        return form.encode();
+    }
+    let opcode = [0x0F, 0xA3 + (ext - 4) * 8];
+    let reg = gp(src).ok_or_else(|| format!("bit index `{src}` must be a register or an immediate"))?;
+    Form::new(&opcode, reg.num, rm_of(dest)?.0).sized(size).encode()
+}
+
+fn setcc(cond: Cond, dest: &Operand) -> EncodeResult {
+    if operand_size(dest)? != 1 {
+        return Err(format!("`{dest}` must be a byte operand"));
+    }
+    let opcode = [0x0F, 0x90 + cond as u8];
+    let (rm, reg) = rm_of(dest)?;
+    let mut form = Form::new(&opcode, 0, rm);
+    form.byte_regs = [reg, None];
+    form.encode()
+}
+
+fn cmovcc(cond: Cond, dest: &Operand, src: &Operand) -> EncodeResult {
+    reg_rm(None, &[0x0F, 0x40 + cond as u8], dest, src)
+}
+
+/// Encodes `JMP` or `CALL`: a 32-bit relative form for labels, an indirect
+/// `0xFF /ext` form for registers and memory.
+fn branch(rel_opcode: &[u8], ext: u8, target: &Operand, kind: RelocationKind) -> EncodeResult {
+    if let Operand::Label(label) = target {
+        return Ok(relative(rel_opcode, label, kind));
+    }
+    if gp(target).is_some_and(|reg| reg.size != 8) {
+        return Err(format!("indirect target `{target}` must be a 64-bit register"));
+    }
+    Form::new(&[0xFF], ext, rm_of(target)?.0).encode()
+}
+
+fn jcc(cond: Cond, target: &Operand) -> EncodeResult {
+    match target {
+        Operand::Label(label) => Ok(relative(&[0x0F, 0x80 + cond as u8], label, RelocationKind::Pc32)),
+        _ => Err(format!("conditional jump target `{target}` must be a label")),
+    }
+}
+
+fn relative(opcode: &[u8], label: &str, kind: RelocationKind) -> EncodedInstruction {
+    let mut bytes = opcode.to_vec();
+    let offset = bytes.len();
+    bytes.extend([0; 4]);
+    EncodedInstruction { bytes, fixup: Some(LabelFixup { offset, symbol: label.to_string(), kind, addend: -4 }) }
+}
+
+// ---------------------------------------------------------------------------
+// SSE instructions
+// ---------------------------------------------------------------------------
+
+/// Encodes an SSE move with a load form (`xmm, xmm/m`) and a store form
+/// (`m, xmm`).
+fn sse_move(prefix: Option<u8>, load: u8, store: u8, dest: &Operand, src: &Operand) -> EncodeResult {
+    if xmm(dest).is_some() {
+        return sse_op(prefix, load, dest, src);
+    }
+    let reg = xmm(src).ok_or_else(|| format!("unsupported operands `{dest}`, `{src}`"))?;
+    let opcode = [0x0F, store];
+    let mut form = Form::new(&opcode, reg, rm_of(dest)?.0);
+    form.mandatory = prefix;
+    form.encode()
+}
+
+/// Encodes an SSE instruction of the form `xmm, xmm/m`.
+fn sse_op(prefix: Option<u8>, opcode: u8, dest: &Operand, src: &Operand) -> EncodeResult {
+    let reg = xmm(dest).ok_or_else(|| format!("destination `{dest}` must be an XMM register"))?;
+    if gp(src).is_some() {
+        return Err(format!("source `{src}` must be an XMM register or memory"));
+    }
+    let opcode = [0x0F, opcode];
+    let mut form = Form::new(&opcode, reg, rm_of(src)?.0);
+    form.mandatory = prefix;
+    form.encode()
+}
+
+fn movq(dest: &Operand, src: &Operand) -> EncodeResult {
+    match (xmm(dest), xmm(src)) {
+        (Some(reg), None) if gp(src).is_some() => {
+            let mut form = Form::new(&[0x0F, 0x6E], reg, rm_of(src)?.0);
+            form.mandatory = Some(0x66);
+            form.rex_w = true;
+            form.encode()
+        }
+        (Some(_), _) => sse_op(Some(0xF3), 0x7E, dest, src),
+        (None, Some(reg)) if gp(dest).is_some() => {
+            let mut form = Form::new(&[0x0F, 0x7E], reg, rm_of(dest)?.0);
+            form.mandatory = Some(0x66);
+            form.rex_w = true;
+            form.encode()
+        }
+        (None, Some(reg)) => {
+            let mut form = Form::new(&[0x0F, 0xD6], reg, rm_of(dest)?.0);
+            form.mandatory = Some(0x66);
+            form.encode()
+        }
+        (None, None) => Err(format!("unsupported operands `{dest}`, `{src}`")),
+    }
+}
+
+fn int_to_float(prefix: u8, dest: &Operand, src: &Operand) -> EncodeResult {
+    let reg = xmm(dest).ok_or_else(|| format!("destination `{dest}` must be an XMM register"))?;
+    let size = operand_size(src)?;
+    let mut form = Form::new(&[0x0F, 0x2A], reg, rm_of(src)?.0);
+    form.mandatory = Some(prefix);
+    form.rex_w = size == 8;
+    form.encode()
+}
+
+fn float_to_int(prefix: u8, dest: &Operand, src: &Operand) -> EncodeResult {
+    let reg = gp(dest).ok_or_else(|| format!("destination `{dest}` must be a general purpose register"))?;
+    if gp(src).is_some() {
+        return Err(format!("source `{src}` must be an XMM register or memory"));
+    }
+    let mut form = Form::new(&[0x0F, 0x2C], reg.num, rm_of(src)?.0);
+    form.mandatory = Some(prefix);
+    form.rex_w = reg.size == 8;
+    form.encode()
+}
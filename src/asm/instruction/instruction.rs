@@ -89,6 +89,7 @@ pub enum Instruction {
     Movsd { dest: Operand, src: Operand },
     Movdqa { dest: Operand, src: Operand },
     Movdqu { dest: Operand, src: Operand },
+    Movq { dest: Operand, src: Operand },
 
     // === Istruzioni SSE/AVX - Aritmetiche ===
     Addps { dest: Operand, src: Operand },
@@ -126,6 +127,10 @@ pub enum Instruction {
     Cvtsi2ss { dest: Operand, src: Operand },
     Cvtsi2sd { dest: Operand, src: Operand },
 
+    // === Istruzioni SSE/AVX - Confronto ===
+    Ucomiss { op1: Operand, op2: Operand },
+    Ucomisd { op1: Operand, op2: Operand },
+
     // === Istruzioni AVX ===
     Vaddps { dest: Operand, src1: Operand, src2: Operand },
     Vaddpd { dest: Operand, src1: Operand, src2: Operand },
@@ -185,6 +190,8 @@ pub enum Instruction {
     Setae { dest: Operand },
     Setb { dest: Operand },
     Setbe { dest: Operand },
+    Setp { dest: Operand },
+    Setnp { dest: Operand },
 
     // === Istruzioni di Controllo ===
     Nop,
@@ -285,6 +292,7 @@ impl Instruction {
             Self::Movsd { .. } | Self::MovsdString => "movsd",
             Self::Movdqa { .. } => "movdqa",
             Self::Movdqu { .. } => "movdqu",
+            Self::Movq { .. } => "movq",
             Self::Addps { .. } => "addps",
             Self::Addpd { .. } => "addpd",
             Self::Addss { .. } => "addss",
@@ -315,6 +323,8 @@ impl Instruction {
             Self::Cvttsd2si { .. } => "cvttsd2si",
             Self::Cvtsi2ss { .. } => "cvtsi2ss",
             Self::Cvtsi2sd { .. } => "cvtsi2sd",
+            Self::Ucomiss { .. } => "ucomiss",
+            Self::Ucomisd { .. } => "ucomisd",
             Self::Vaddps { .. } => "vaddps",
             Self::Vaddpd { .. } => "vaddpd",
             Self::Vaddss { .. } => "vaddss",
@@ -365,6 +375,8 @@ impl Instruction {
             Self::Setae { .. } => "setae",
             Self::Setb { .. } => "setb",
             Self::Setbe { .. } => "setbe",
+            Self::Setp { .. } => "setp",
+            Self::Setnp { .. } => "setnp",
             Self::Movsb => "movsb",
             Self::Movsw => "movsw",
             Self::Movsq => "movsq",
@@ -457,6 +469,7 @@ impl fmt::Display for Instruction {
             | Self::Movsd { dest, src }
             | Self::Movdqa { dest, src }
             | Self::Movdqu { dest, src }
+            | Self::Movq { dest, src }
             | Self::Addps { dest, src }
             | Self::Addpd { dest, src }
             | Self::Addss { dest, src }
@@ -522,7 +535,11 @@ impl fmt::Display for Instruction {
             }
 
             // Istruzioni con due operandi (op1, op2)
-            Self::Test { op1, op2 } | Self::Cmp { op1, op2 } | Self::Xchg { op1, op2 } => {
+            Self::Test { op1, op2 }
+            | Self::Cmp { op1, op2 }
+            | Self::Xchg { op1, op2 }
+            | Self::Ucomiss { op1, op2 }
+            | Self::Ucomisd { op1, op2 } => {
                 write!(f, "{} {}, {}", self.mnemonic(), op1, op2)
             }
 
@@ -616,7 +633,9 @@ impl fmt::Display for Instruction {
             | Self::Seta { dest }
             | Self::Setae { dest }
             | Self::Setb { dest }
-            | Self::Setbe { dest } => {
+            | Self::Setbe { dest }
+            | Self::Setp { dest }
+            | Self::Setnp { dest } => {
                 write!(f, "{} {}", self.mnemonic(), dest)
             }
 
@@ -11,6 +11,8 @@
 mod abi;
 mod assembly_file;
 mod data_directive;
+mod elf;
+mod encoder;
 mod instruction;
 mod platform;
 mod register;
@@ -21,6 +23,8 @@ pub use abi::*;
 pub use assembly_file::*;
 #[allow(unused_imports)]
 pub use data_directive::*;
+pub use elf::*;
+pub use encoder::*;
 pub use instruction::*;
 pub use platform::*;
 pub use register::*;
@@ -0,0 +1,50 @@
+// run --package jsavrs --bin jsavrs-opt -- -i input.nir --passes=sccp,dce
+use clap::Parser;
+use console::style;
+use jsavrs::cli::OptArgs;
+use jsavrs::error::compile_error::CompileError;
+use jsavrs::error::error_reporter::ErrorReporter;
+use jsavrs::ir::optimizer::{OptLevel, PassManager, phase_by_name};
+use jsavrs::ir::parse_module;
+use jsavrs::location::line_tracker::LineTracker;
+use std::{fs, process};
+
+// Reports an I/O failure on stderr and exits with code 1
+fn io_failure(error: &std::io::Error) -> ! {
+    eprintln!("{} {}: {}\n", style("ERROR:").red().bold(), style("I/O").red(), style(error).yellow());
+    process::exit(1);
+}
+
+fn main() {
+    let args = OptArgs::parse();
+    let file_path = args.input.to_string_lossy();
+
+    let source = fs::read_to_string(&args.input).unwrap_or_else(|e| io_failure(&e));
+    let error_reporter = ErrorReporter::new(LineTracker::new(&file_path, source.clone()));
+
+    let mut module = parse_module(&file_path, &source).unwrap_or_else(|e: CompileError| {
+        eprintln!("{}", error_reporter.report_errors(vec![e]));
+        process::exit(1);
+    });
+
+    let mut pass_manager = PassManager::for_level(args.opt_level.unwrap_or(OptLevel::O0), args.verbose);
+    // Names are validated by clap, so every lookup succeeds.
+    for name in &args.passes {
+        if let Some(phase) = phase_by_name(name, args.verbose) {
+            pass_manager.add_pass(name, phase);
+        }
+    }
+    pass_manager.set_verify(args.verify);
+    let report = pass_manager.run(&mut module).unwrap_or_else(|errors| {
+        eprintln!("{}", error_reporter.report_errors(errors));
+        process::exit(1);
+    });
+    if args.time_passes {
+        eprintln!("{report}");
+    }
+
+    match &args.output {
+        Some(path) => fs::write(path, module.to_string()).unwrap_or_else(|e| io_failure(&e)),
+        None => println!("{module}"),
+    }
+}
@@ -0,0 +1,39 @@
+// run --package jsavrs --bin jsavrs-test -- tests/vn
+use clap::Parser;
+use console::style;
+use jsavrs::cli::TestArgs;
+use jsavrs::test_runner::{check_file, collect_tests};
+use std::process;
+
+fn main() {
+    let args = TestArgs::parse();
+    let tests = collect_tests(&args.paths).unwrap_or_else(|e| {
+        eprintln!("{} {}: {}\n", style("ERROR:").red().bold(), style("I/O").red(), style(e).yellow());
+        process::exit(1);
+    });
+
+    let mut failed = 0;
+    for path in &tests {
+        let failures = check_file(path);
+        if failures.is_empty() {
+            if args.verbose {
+                println!("{} {}", style("ok").green(), path.display());
+            }
+            continue;
+        }
+        failed += 1;
+        println!("{} {}", style("FAILED").red().bold(), path.display());
+        for failure in &failures {
+            for line in failure.to_string().lines() {
+                println!("    {line}");
+            }
+        }
+    }
+
+    let passed = tests.len() - failed;
+    let verdict = if failed == 0 { style("ok").green() } else { style("FAILED").red().bold() };
+    println!("test result: {verdict}. {passed} passed; {failed} failed");
+    if failed > 0 {
+        process::exit(1);
+    }
+}
@@ -9,6 +9,13 @@
 //! - Custom ANSI color schemes for help output
 //! - File path validation ensuring `.vn` extension
 //! - Verbose mode flag for detailed compilation output
+//! - Output path and artefact selection (`--output`, `--emit`)
+//! - Assembler selection for object files and executables (`--assembler`)
+//! - Optimization level and pass selection (`-O`, `--disable-pass`)
+//! - `run` command interpreting the program instead of compiling it
+//! - Machine-readable error output (`--error-format`)
+//! - Arguments of the `jsavrs-opt` pass runner ([`OptArgs`])
+//! - Arguments of the `jsavrs-test` `.vn` test runner ([`TestArgs`])
 //!
 //! # Examples
 //!
@@ -21,10 +28,13 @@
 //! ```
 
 // src/cli.rs
+use crate::driver::Assembler;
+use crate::error::error_reporter::ErrorFormat;
+use crate::ir::optimizer::{OptLevel, PASS_NAMES};
 use clap::{
-    Parser, ValueHint,
+    Parser, ValueEnum, ValueHint,
     builder::{
-        Styles,
+        PossibleValuesParser, Styles,
         styling::{AnsiColor, Effects},
     },
 };
@@ -106,6 +116,120 @@ fn parse_vn_file(s: &str) -> Result<PathBuf, String> {
     if is_vn { Ok(p) } else { Err("expected a path to a .vn file".into()) }
 }
 
+/// Parses the optimization level given with `-O`.
+///
+/// Accepts `0` to `3`, so that `-O2` and `-O 2` select [`OptLevel::O2`].
+fn parse_opt_level(s: &str) -> Result<OptLevel, String> {
+    match s {
+        "0" => Ok(OptLevel::O0),
+        "1" => Ok(OptLevel::O1),
+        "2" => Ok(OptLevel::O2),
+        "3" => Ok(OptLevel::O3),
+        _ => Err("expected an optimization level from 0 to 3".into()),
+    }
+}
+
+/// Parses the error output format given with `--error-format`.
+///
+/// Accepts `human`, `json` and `sarif`.
+fn parse_error_format(s: &str) -> Result<ErrorFormat, String> {
+    match s {
+        "human" => Ok(ErrorFormat::Human),
+        "json" => Ok(ErrorFormat::Json),
+        "sarif" => Ok(ErrorFormat::Sarif),
+        _ => Err("expected one of human, json, sarif".into()),
+    }
+}
+
+/// Artefact written by the compiler, selected with `--emit`.
+///
+/// Compilation stops right after the phase that produces the requested
+/// artefact.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmitKind {
+    /// Linked native executable.
+    Exe,
+    /// ELF64 object file assembled from the generated code.
+    Obj,
+    /// NASM assembly produced by the code generator.
+    #[default]
+    Asm,
+    /// Optimized NIR module.
+    Ir,
+    /// Abstract syntax tree of the parsed statements.
+    Ast,
+    /// Token stream produced by the lexer.
+    Tokens,
+}
+
+impl EmitKind {
+    /// Returns the file extension used for the artefact when no output path
+    /// is given. Executables have no extension.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jsavrs::cli::EmitKind;
+    ///
+    /// assert_eq!(EmitKind::Exe.extension(), "");
+    /// assert_eq!(EmitKind::Asm.extension(), "asm");
+    /// assert_eq!(EmitKind::Ir.extension(), "nir");
+    /// ```
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Exe => "",
+            Self::Obj => "o",
+            Self::Asm => "asm",
+            Self::Ir => "nir",
+            Self::Ast => "ast",
+            Self::Tokens => "tokens",
+        }
+    }
+}
+
+/// Assembler turning the generated code into object files, selected with
+/// `--assembler`.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AssemblerKind {
+    /// The built-in `x86_64` encoder and ELF64 writer.
+    #[default]
+    Builtin,
+    /// `nasm -f elf64`, found on the `PATH`.
+    Nasm,
+}
+
+impl AssemblerKind {
+    /// Returns the [`Assembler`] of the toolchain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jsavrs::cli::AssemblerKind;
+    /// use jsavrs::driver::Assembler;
+    ///
+    /// assert_eq!(AssemblerKind::Nasm.assembler(), Assembler::Nasm("nasm".into()));
+    /// ```
+    #[must_use]
+    pub fn assembler(self) -> Assembler {
+        match self {
+            Self::Builtin => Assembler::Builtin,
+            Self::Nasm => Assembler::Nasm(PathBuf::from("nasm")),
+        }
+    }
+}
+
+/// Action performed on the input file, given as the first positional argument.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Command {
+    /// Compile the program to the artefact selected with `--emit`.
+    #[default]
+    Build,
+    /// Interpret the optimized IR of the program and exit with the status
+    /// returned by `main`.
+    Run,
+}
+
 /// Command-line arguments structure for the jsavrs compiler.
 ///
 /// This struct defines all command-line options accepted by the jsavrs binary,
@@ -113,8 +237,17 @@ fn parse_vn_file(s: &str) -> Result<PathBuf, String> {
 ///
 /// # Fields
 ///
+/// * `command` - Whether to build or run the program, `build` by default
 /// * `input` - Path to the input `.vn` source file (required)
 /// * `verbose` - Flag to enable verbose compilation output (optional)
+/// * `output` - Path of the emitted artefact (optional)
+/// * `emit` - Kind of artefact to emit, assembly by default
+/// * `assembler` - Assembler used for `--emit=obj` and `--emit=exe`, the built-in one by default
+/// * `verify_ir` - Flag to run the IR verifier around every optimization pass
+/// * `opt_level` - Preset optimization pipeline, `-O1` by default
+/// * `disable_pass` - Passes removed from the preset pipeline
+/// * `time_passes` - Flag to print per-pass timing and statistics
+/// * `error_format` - Format of the reported errors, text by default
 ///
 /// # Examples
 ///
@@ -127,6 +260,9 @@ fn parse_vn_file(s: &str) -> Result<PathBuf, String> {
 ///
 /// // Parse from custom args
 /// let args = Args::try_parse_from(["jsavrs", "-i", "program.vn", "-v"]);
+///
+/// // Interpret the program instead of compiling it
+/// let args = Args::try_parse_from(["jsavrs", "run", "-i", "program.vn"]);
 /// ```
 #[derive(Parser, Debug)]
 #[command(
@@ -138,6 +274,10 @@ fn parse_vn_file(s: &str) -> Result<PathBuf, String> {
     styles = custom_styles()
 )]
 pub struct Args {
+    /// Whether to compile the program or to interpret it
+    #[arg(value_enum, value_name = "COMMAND", default_value_t = Command::Build)]
+    pub command: Command,
+
     /// Input file for compilation (a .vn file is required)
     #[arg(
         short,
@@ -151,4 +291,173 @@ pub struct Args {
     /// Show verbose output
     #[arg(short, long)]
     pub verbose: bool,
+
+    /// Output file (defaults to the input path with the extension of the emitted artefact)
+    #[arg(short, long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub output: Option<PathBuf>,
+
+    /// Kind of artefact to emit
+    #[arg(long, value_enum, value_name = "KIND", default_value_t = EmitKind::Asm)]
+    pub emit: EmitKind,
+
+    /// Assembler used to produce object files and executables
+    #[arg(long, value_enum, value_name = "ASSEMBLER", default_value_t = AssemblerKind::Builtin)]
+    pub assembler: AssemblerKind,
+
+    /// Verify the IR before optimization and after every pass
+    #[arg(long)]
+    pub verify_ir: bool,
+
+    /// Optimization level, from 0 to 3
+    #[arg(short = 'O', value_name = "LEVEL", value_parser = parse_opt_level, default_value = "1")]
+    pub opt_level: OptLevel,
+
+    /// Passes to skip, even if the optimization level enables them
+    #[arg(
+        long,
+        value_name = "PASS",
+        value_delimiter = ',',
+        value_parser = PossibleValuesParser::new(PASS_NAMES)
+    )]
+    pub disable_pass: Vec<String>,
+
+    /// Print the time spent in each pass and the optimization statistics
+    #[arg(long)]
+    pub time_passes: bool,
+
+    /// Format of the reported errors: human, json (one object per line) or sarif
+    #[arg(long, value_name = "FORMAT", value_parser = parse_error_format, default_value = "human")]
+    pub error_format: ErrorFormat,
+}
+
+impl Args {
+    /// Returns the path the emitted artefact is written to.
+    ///
+    /// This is the `--output` path if given, otherwise the input path with
+    /// the extension of the selected [`EmitKind`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jsavrs::cli::Args;
+    /// use clap::Parser;
+    /// use std::path::PathBuf;
+    ///
+    /// let args = Args::try_parse_from(["jsavrs", "-i", "prog.vn", "--emit", "ir"]).unwrap();
+    /// assert_eq!(args.output_path(), PathBuf::from("prog.nir"));
+    /// ```
+    #[must_use]
+    pub fn output_path(&self) -> PathBuf {
+        self.output.clone().unwrap_or_else(|| self.input.with_extension(self.emit.extension()))
+    }
+}
+
+/// Command-line arguments of the `jsavrs-opt` binary.
+///
+/// `jsavrs-opt` reads a textual NIR module, runs the preset pipeline selected
+/// with `-O` (none by default) followed by the optimization passes named in
+/// `--passes`, and prints the resulting module.
+///
+/// # Fields
+///
+/// * `input` - Path to the textual IR module (required)
+/// * `passes` - Comma-separated pass names, see [`PASS_NAMES`]
+/// * `verbose` - Flag to report the passes as they run, with their statistics (optional)
+/// * `verify` - Flag to run the IR verifier around every pass (optional)
+/// * `opt_level` - Preset pipeline run before `passes` (optional)
+/// * `time_passes` - Flag to print per-pass timing and statistics (optional)
+/// * `output` - File the module is written to instead of stdout (optional)
+///
+/// # Examples
+///
+/// ```
+/// use jsavrs::cli::OptArgs;
+/// use clap::Parser;
+///
+/// let args = OptArgs::try_parse_from(["jsavrs-opt", "-i", "bug.nir", "--passes=sccp,dce"]).unwrap();
+/// assert_eq!(args.passes, ["sccp", "dce"]);
+/// ```
+#[derive(Parser, Debug)]
+#[command(
+    name = "jsavrs-opt",
+    version = clap::crate_version!(),
+    author = clap::crate_authors!("\n"),
+    about = "Runs named optimization passes on a textual NIR module",
+    long_about = None,
+    help_template = HELP_STR,
+    styles = custom_styles()
+)]
+pub struct OptArgs {
+    /// Textual IR module to optimize
+    #[arg(short, long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub input: PathBuf,
+
+    /// Passes to run, in order
+    #[arg(
+        short,
+        long,
+        value_name = "PASS",
+        value_delimiter = ',',
+        value_parser = PossibleValuesParser::new(PASS_NAMES)
+    )]
+    pub passes: Vec<String>,
+
+    /// Show the passes as they run and their statistics
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Verify the module before the first pass and after every pass
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Run the preset pipeline of this optimization level before `--passes`
+    #[arg(short = 'O', value_name = "LEVEL", value_parser = parse_opt_level)]
+    pub opt_level: Option<OptLevel>,
+
+    /// Print the time spent in each pass and the optimization statistics
+    #[arg(long)]
+    pub time_passes: bool,
+
+    /// Output file (defaults to stdout)
+    #[arg(short, long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+    pub output: Option<PathBuf>,
+}
+
+/// Command-line arguments of the `jsavrs-test` binary.
+///
+/// `jsavrs-test` checks annotated `.vn` files with [`crate::test_runner`] and
+/// exits with status 1 when any of them fails.
+///
+/// # Fields
+///
+/// * `paths` - Test files, or directories searched recursively for `.vn` files (required)
+/// * `verbose` - Flag to also list the files that pass (optional)
+///
+/// # Examples
+///
+/// ```
+/// use jsavrs::cli::TestArgs;
+/// use clap::Parser;
+///
+/// let args = TestArgs::try_parse_from(["jsavrs-test", "tests/vn", "extra.vn"]).unwrap();
+/// assert_eq!(args.paths.len(), 2);
+/// ```
+#[derive(Parser, Debug)]
+#[command(
+    name = "jsavrs-test",
+    version = clap::crate_version!(),
+    author = clap::crate_authors!("\n"),
+    about = "Checks annotated .vn files against their expected errors, IR and results",
+    long_about = None,
+    help_template = HELP_STR,
+    styles = custom_styles()
+)]
+pub struct TestArgs {
+    /// Test files, or directories searched for `.vn` files
+    #[arg(required = true, value_name = "PATH", value_hint = ValueHint::AnyPath)]
+    pub paths: Vec<PathBuf>,
+
+    /// List the files that pass as well
+    #[arg(short, long)]
+    pub verbose: bool,
 }
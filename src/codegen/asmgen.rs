@@ -1,7 +1,12 @@
 use crate::{
-    asm::{Abi, AssemblyFile},
+    asm::{Abi, AssemblyFile, DataDirective},
+    codegen::{
+        frame::size_of,
+        isel::{FunctionLowering, literal_bits},
+        target::resolve_abi,
+    },
     error::compile_error::CompileError,
-    ir::{Function, Module, TargetTriple},
+    ir::{Function, GlobalVariable, IrType, Module, Value},
 };
 
 #[allow(dead_code)]
@@ -35,29 +40,26 @@ pub struct AsmGen {
     errors: Vec<CompileError>,
     /// The output assembly file being constructed, configured for the target ABI.
     assembly_file: AssemblyFile,
+    /// Number of string literals emitted so far, used to name their data labels.
+    string_counter: usize,
+    /// Whether the module targets an architecture the backend can generate code for.
+    target_supported: bool,
 }
 
 impl AsmGen {
+    /// Creates a generator for `ir`, configured for the ABI of its target triple.
+    ///
+    /// Modules targeting an architecture other than `x86_64` produce an
+    /// [`ErrorCode::E4004`](crate::error::error_code::ErrorCode::E4004) error
+    /// from [`AsmGen::gen_asm`] instead of assembly.
     #[must_use]
     pub fn new(ir: Module) -> Self {
-        let target_triple = ir.target_triple;
-        Self { ir, errors: Vec::new(), assembly_file: AssemblyFile::new(Self::target_triple_to_abi(target_triple)) }
-    }
-
-    fn target_triple_to_abi(target_triple: TargetTriple) -> Abi {
-        match target_triple {
-            TargetTriple::X86_64UnknownLinuxGnu => Abi::SYSTEM_V_LINUX,
-            TargetTriple::X86_64PcWindowsGnu => Abi::WINDOWS,
-            TargetTriple::X86_64AppleDarwin => Abi::SYSTEM_V_MACOS,
-            TargetTriple::AArch64UnknownLinuxGnu
-            | TargetTriple::AArch64AppleDarwin
-            | TargetTriple::AArch64PcWindowsGnu
-            | TargetTriple::I686PcWindowsGnu
-            | TargetTriple::I686UnknownLinuxGnu
-            | TargetTriple::Wasm32UnknownEmscripten => {
-                todo!("ABI for target triple {:?} not supported yet", target_triple)
-            }
-        }
+        let (abi, errors) = match resolve_abi(ir.target_triple) {
+            Ok(abi) => (abi, Vec::new()),
+            Err(error) => (Abi::SYSTEM_V_LINUX, vec![error]),
+        };
+        let target_supported = errors.is_empty();
+        Self { ir, errors, assembly_file: AssemblyFile::new(abi), string_counter: 0, target_supported }
     }
 
     /// Consumes the generator and produces the final assembly output.
@@ -90,17 +92,53 @@ impl AsmGen {
     pub fn gen_asm(mut self) -> (AssemblyFile, Vec<CompileError>) {
         //println!("Generating assembly for abi: {:?}", self.assembly_file.abi());
         let functions = std::mem::take(&mut self.ir.functions);
-        for func in &functions {
-            self.gen_function(func);
+        if self.target_supported {
+            for global in std::mem::take(&mut self.ir.globals) {
+                self.gen_global(&global);
+            }
+            for func in &functions {
+                self.gen_function(func, &functions);
+            }
         }
         (self.assembly_file.clone(), self.errors)
     }
 
-    fn gen_function(&mut self, func: &Function) {
-        let func_name = &func.name;
-        if func_name.as_ref() == "main" {
-            self.assembly_file.text_sec_add_global_label(func_name.to_string());
+    /// Emits a global variable under its own name: in `.bss` when it is
+    /// zero-initialized and the target has one, in `.data` otherwise.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn gen_global(&mut self, global: &GlobalVariable) {
+        let (element_ty, count) = match &global.ty {
+            IrType::Array(element_ty, count) => (element_ty.as_ref(), *count),
+            ty => (ty, 1),
+        };
+        let bits: Vec<i64> = global
+            .initializer
+            .iter()
+            .map(|value| literal_bits(&Value::new_literal(*value)).unwrap_or_default())
+            .collect();
+        let label = global.name.to_string();
+        let size = size_of(element_ty);
+        if bits.iter().all(|&bits| bits == 0) && self.assembly_file.bss_section().is_some() {
+            let directive = match size {
+                1 => DataDirective::Resb(count),
+                2 => DataDirective::Resw(count),
+                4 => DataDirective::Resd(count),
+                _ => DataDirective::Resq(count),
+            };
+            self.assembly_file.bss_sec_add_data(label, directive);
+            return;
         }
-        self.assembly_file.text_sec_add_label(func_name.to_string());
+        let directive = match size {
+            1 => DataDirective::Db(bits.iter().map(|&bits| bits as u8).collect()),
+            2 => DataDirective::Dw(bits.iter().map(|&bits| bits as u16).collect()),
+            4 => DataDirective::Dd(bits.iter().map(|&bits| bits as u32).collect()),
+            _ => DataDirective::Dq(bits.iter().map(|&bits| bits as u64).collect()),
+        };
+        self.assembly_file.data_sec_add_data(label, directive);
+    }
+
+    fn gen_function(&mut self, func: &Function, functions: &[Function]) {
+        FunctionLowering::new(func, functions, &mut self.assembly_file, &mut self.errors, &mut self.string_counter)
+            .lower();
     }
 }
@@ -0,0 +1,312 @@
+//! # Stack Frame Layout
+//!
+//! Computes where every IR value of a [`Function`] lives while the function
+//! executes. Values that received a register from the
+//! [`RegisterAllocation`] stay there; everything else lives in the frame,
+//! which is frame-pointer based: all stack locations are expressed as signed
+//! displacements from `RBP`, growing towards lower addresses.
+//!
+//! Two kinds of storage are handed out:
+//!
+//! - **Value slots**: one 8-byte slot for every spilled SSA value (parameters,
+//!   instruction results, phi results and store-bound temporaries). Integers
+//!   are kept normalized to 64 bits (sign- or zero-extended according to their
+//!   IR type), floating-point values keep their raw IEEE-754 bits in the low
+//!   bytes of the slot. Registers follow the same convention.
+//! - **Alloca areas**: storage reserved for `alloca` instructions. The result
+//!   of an `alloca` has no slot of its own: its value *is* the address of the
+//!   area, see [`ValueLocation::FrameAddress`].
+//!
+//! ## Frame shape
+//!
+//! ```text
+//! [rbp + 16 ...]   stack-passed parameters (after the shadow space on Windows)
+//! [rbp + 8]        return address
+//! [rbp]            saved RBP
+//! [rbp - 8 ...]    preserved callee-saved registers
+//!                  value slots, alloca areas, saved XMM registers
+//! [rsp ...]        outgoing arguments (shadow space and stack-passed arguments)
+//! ```
+//!
+//! `RSP` is kept 16-byte aligned after the prologue. On System V, leaf
+//! functions whose locals fit in the red zone do not adjust `RSP` at all.
+//!
+//! ## Store semantics
+//!
+//! After SSA construction a `store` may either write through a pointer or bind
+//! a fresh SSA name (`store 10i32 to t5 (x)` where `t5` is defined nowhere
+//! else). The layout records which temporaries are bound this way so that the
+//! instruction selector can lower those stores to plain copies.
+
+use super::param::{classify_arguments, stack_slot_count};
+use super::prologue::{callee_saved_to_preserve, callee_saved_xmm_to_preserve};
+use super::regalloc::RegisterAllocation;
+use super::regalloc::interval::store_bound_temporaries;
+use crate::asm::{Abi, GPRegister64, XMMRegister};
+use crate::ir::{Function, InstructionKind, IrType, Value, ValueKind};
+use std::collections::{HashMap, HashSet};
+
+/// Size in bytes of a value slot.
+pub const SLOT_SIZE: i32 = 8;
+
+/// Size in bytes of the save area of an XMM register.
+const XMM_SAVE_SIZE: i32 = 16;
+
+/// Where the value of an IR [`Value`] can be found at run time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueLocation {
+    /// The value is stored in the 8-byte slot at `[rbp + offset]`.
+    Stack(i32),
+    /// The value is the address `rbp + offset` itself (result of an `alloca`).
+    FrameAddress(i32),
+    /// The value is held in a general purpose register.
+    Gp(GPRegister64),
+    /// The value is held in an XMM register.
+    Xmm(XMMRegister),
+}
+
+/// Frame layout of a single function.
+///
+/// # Examples
+///
+/// ```ignore
+/// let frame = StackFrame::layout(&function, &Abi::SYSTEM_V_LINUX, &[], &[]);
+/// let size = frame.frame_size(); // bytes to subtract from RSP in the prologue
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StackFrame {
+    locations: HashMap<ValueKind, ValueLocation>,
+    bound_temporaries: HashSet<ValueKind>,
+    preserved_gp: Vec<GPRegister64>,
+    preserved_xmm: Vec<(XMMRegister, i32)>,
+    shadow_space: u32,
+    frame_size: u32,
+    is_leaf: bool,
+    uses_red_zone: bool,
+}
+
+impl StackFrame {
+    /// Computes the frame layout of `func` with every value in a stack slot.
+    ///
+    /// Parameters are assigned first, followed by every value defined in the
+    /// function body in block order.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The IR function to lay out.
+    /// * `abi` - The calling convention of the target.
+    /// * `preserved_gp` - Callee-saved general purpose registers modified by the
+    ///   function, pushed right after the frame pointer.
+    /// * `preserved_xmm` - Callee-saved XMM registers modified by the function,
+    ///   saved in the frame (Microsoft x64 only).
+    ///
+    /// # Returns
+    ///
+    /// A [`StackFrame`] describing the location of every value defined in `func`.
+    #[must_use]
+    pub fn layout(func: &Function, abi: &Abi, preserved_gp: &[GPRegister64], preserved_xmm: &[XMMRegister]) -> Self {
+        Self::build(func, *abi, preserved_gp, preserved_xmm, &RegisterAllocation::default())
+    }
+
+    /// Computes the frame layout of `func` for the registers chosen by
+    /// `allocation`.
+    ///
+    /// Only spilled values receive a stack slot. The callee-saved registers
+    /// used by the allocation are preserved by the prologue.
+    #[must_use]
+    pub fn with_allocation(func: &Function, abi: &Abi, allocation: &RegisterAllocation) -> Self {
+        let preserved_gp = callee_saved_to_preserve(abi, allocation.used_gp_registers());
+        let preserved_xmm = callee_saved_xmm_to_preserve(abi, allocation.used_xmm_registers());
+        Self::build(func, *abi, &preserved_gp, &preserved_xmm, allocation)
+    }
+
+    fn build(
+        func: &Function, abi: Abi, preserved_gp: &[GPRegister64], preserved_xmm: &[XMMRegister],
+        allocation: &RegisterAllocation,
+    ) -> Self {
+        let mut frame = Self {
+            preserved_gp: preserved_gp.to_vec(),
+            bound_temporaries: store_bound_temporaries(func),
+            is_leaf: true,
+            ..Self::default()
+        };
+        let pushed = i32::try_from(preserved_gp.len()).unwrap_or(0) * SLOT_SIZE;
+        let mut used = pushed;
+        let mut assign = |frame: &mut Self, value: &Value| {
+            let location = allocation.location(value).unwrap_or_else(|| {
+                used += SLOT_SIZE;
+                ValueLocation::Stack(-used)
+            });
+            frame.locations.insert(value.kind.clone(), location);
+        };
+
+        for param in &func.parameters {
+            assign(&mut frame, &Value::new_local(param.name.clone(), param.ty.clone()));
+        }
+
+        let mut outgoing_slots = 0;
+        let mut allocas = Vec::new();
+
+        for block in func.cfg.blocks() {
+            for inst in &block.instructions {
+                if let InstructionKind::Call { args, .. } = &inst.kind {
+                    frame.is_leaf = false;
+                    let types: Vec<&IrType> = args.iter().map(|arg| value_type(&arg.ty)).collect();
+                    if let Ok(locations) = classify_arguments(&types, &abi) {
+                        outgoing_slots = outgoing_slots.max(stack_slot_count(&locations));
+                    }
+                }
+                match (&inst.kind, &inst.result) {
+                    (InstructionKind::Alloca { ty }, Some(result)) => allocas.push((result, ty)),
+                    (InstructionKind::Store { dest, .. }, None)
+                        if frame.bound_temporaries.contains(&dest.kind)
+                            && !frame.locations.contains_key(&dest.kind) =>
+                    {
+                        assign(&mut frame, dest);
+                    }
+                    (_, Some(result)) if !frame.locations.contains_key(&result.kind) => assign(&mut frame, result),
+                    _ => {}
+                }
+            }
+        }
+
+        for (result, ty) in allocas {
+            let align = i32::try_from(align_of(ty).max(1)).unwrap_or(SLOT_SIZE);
+            let size = i32::try_from(size_of(ty).max(1)).unwrap_or(i32::MAX);
+            used = align_up(used + size, align.max(SLOT_SIZE));
+            frame.locations.insert(result.kind.clone(), ValueLocation::FrameAddress(-used));
+        }
+
+        for reg in preserved_xmm {
+            used += XMM_SAVE_SIZE;
+            frame.preserved_xmm.push((*reg, -used));
+        }
+
+        frame.shadow_space = abi.shadow_space();
+        let locals = used - pushed;
+        let red_zone = i32::try_from(abi.red_zone()).unwrap_or(0);
+        frame.uses_red_zone = frame.is_leaf && frame.shadow_space == 0 && red_zone > 0 && locals <= red_zone;
+
+        let outgoing =
+            i32::try_from(frame.shadow_space).unwrap_or(0) + i32::try_from(outgoing_slots).unwrap_or(0) * SLOT_SIZE;
+        frame.frame_size = if frame.uses_red_zone {
+            0
+        } else {
+            // After `push rbp` RSP is 16-byte aligned: the pushes of callee-saved
+            // registers and the allocation must add up to a multiple of 16.
+            u32::try_from(align_up(used + outgoing, 16) - pushed).unwrap_or(u32::MAX)
+        };
+        frame
+    }
+
+    /// Returns the location of `value`, if it is a value owned by this frame.
+    #[must_use]
+    pub fn location(&self, value: &Value) -> Option<ValueLocation> {
+        self.locations.get(&value.kind).copied()
+    }
+
+    /// Returns `true` if a `store` to `dest` binds a new SSA name instead of
+    /// writing through a pointer.
+    #[must_use]
+    pub fn is_bound_by_store(&self, dest: &Value) -> bool {
+        self.bound_temporaries.contains(&dest.kind)
+    }
+
+    /// Returns the callee-saved general purpose registers pushed by the prologue,
+    /// in push order.
+    #[must_use]
+    pub fn preserved_gp(&self) -> &[GPRegister64] {
+        &self.preserved_gp
+    }
+
+    /// Returns the callee-saved XMM registers saved by the prologue together with
+    /// the RBP-relative offset of their save area.
+    #[must_use]
+    pub fn preserved_xmm(&self) -> &[(XMMRegister, i32)] {
+        &self.preserved_xmm
+    }
+
+    /// Returns the shadow space reserved for outgoing calls (32 bytes on Windows).
+    #[must_use]
+    pub const fn shadow_space(&self) -> u32 {
+        self.shadow_space
+    }
+
+    /// Returns `true` if the function contains no calls.
+    #[must_use]
+    pub const fn is_leaf(&self) -> bool {
+        self.is_leaf
+    }
+
+    /// Returns `true` if locals live in the System V red zone and the prologue
+    /// does not adjust `RSP`.
+    #[must_use]
+    pub const fn uses_red_zone(&self) -> bool {
+        self.uses_red_zone
+    }
+
+    /// Returns the number of bytes subtracted from `RSP` after the callee-saved
+    /// registers have been pushed.
+    ///
+    /// The size keeps `RSP` 16-byte aligned after the prologue and is zero when
+    /// the function uses the red zone.
+    #[must_use]
+    pub const fn frame_size(&self) -> u32 {
+        self.frame_size
+    }
+}
+
+/// Strips the pointer wrapper the IR generator puts on store-bound variables.
+///
+/// Values produced by reading a `var` after SSA construction keep the pointer
+/// type of the original `alloca` (`*i32`) although they hold the scalar itself.
+pub(crate) fn value_type(ty: &IrType) -> &IrType {
+    match ty {
+        IrType::Pointer(inner)
+            if !matches!(inner.as_ref(), IrType::Pointer(_) | IrType::Array(..) | IrType::Struct(..)) =>
+        {
+            inner
+        }
+        other => other,
+    }
+}
+
+const fn align_up(value: i32, align: i32) -> i32 {
+    (value + align - 1) / align * align
+}
+
+/// Returns the size in bytes of `ty` on `x86_64`.
+///
+/// Strings are represented by a pointer to their first byte. Aggregates follow
+/// the usual C layout rules.
+#[must_use]
+pub fn size_of(ty: &IrType) -> usize {
+    match ty {
+        IrType::I8 | IrType::U8 | IrType::Bool => 1,
+        IrType::I16 | IrType::U16 => 2,
+        IrType::I32 | IrType::U32 | IrType::F32 | IrType::Char => 4,
+        IrType::I64 | IrType::U64 | IrType::F64 | IrType::String | IrType::Pointer(_) | IrType::Custom(..) => 8,
+        IrType::Void => 0,
+        IrType::Array(element, count) => size_of(element) * count,
+        IrType::Struct(_, fields, _) => {
+            let mut offset: usize = 0;
+            for (_, field_ty) in fields {
+                let align = align_of(field_ty).max(1);
+                offset = offset.div_ceil(align) * align + size_of(field_ty);
+            }
+            let align = align_of(ty).max(1);
+            offset.div_ceil(align) * align
+        }
+    }
+}
+
+/// Returns the alignment in bytes of `ty` on `x86_64`.
+#[must_use]
+pub fn align_of(ty: &IrType) -> usize {
+    match ty {
+        IrType::Array(element, _) => align_of(element),
+        IrType::Struct(_, fields, _) => fields.iter().map(|(_, field_ty)| align_of(field_ty)).max().unwrap_or(1),
+        IrType::Void => 1,
+        other => size_of(other),
+    }
+}
@@ -0,0 +1,1007 @@
+//! # Instruction Selection
+//!
+//! Lowers the body of an IR [`Function`] into `x86_64` [`Instruction`]s and
+//! appends them to the text section of an [`AssemblyFile`].
+//!
+//! The selector follows a simple and predictable scheme: every IR value lives in
+//! the register chosen by the [`RegisterAllocation`] or, once spilled, in the
+//! stack slot assigned by [`StackFrame`]. Each IR instruction loads its
+//! operands into fixed scratch registers, computes its result and writes it
+//! back to the location of the result.
+//!
+//! | Register  | Role                                              |
+//! |-----------|---------------------------------------------------|
+//! | `RAX`     | first operand / result of integer operations      |
+//! | `RCX`     | second operand, pointers for memory accesses      |
+//! | `RDX`     | division remainder, aggregate copies              |
+//! | `R11`     | indirect call target                              |
+//! | `XMM0/1`  | operands and result of floating-point operations  |
+//!
+//! Scratch registers are never handed out by the allocator, so they can be
+//! overwritten freely between IR instructions.
+//!
+//! Phi nodes are resolved on the incoming edges: the copies required by an edge
+//! are emitted at the end of the predecessor (or in a dedicated edge stub when
+//! the predecessor has several successors) and performed as a parallel copy,
+//! using `RAX` to break cycles.
+
+use super::frame::{StackFrame, ValueLocation, size_of, value_type};
+use super::param::{
+    ParamAssignment, ParamLocation, ReturnLocation, classify_arguments, classify_parameters, return_location,
+};
+use super::prologue::{gen_epilogue, gen_prologue};
+use super::regalloc::RegisterAllocation;
+use super::regalloc::interval::block_order;
+use super::regalloc::parallel_copy::sequentialize;
+use crate::asm::{
+    Abi, AssemblyFile, DataDirective, GPRegister8, GPRegister16, GPRegister32, GPRegister64, Instruction,
+    MemoryOperand, Operand, XMMRegister,
+};
+use crate::error::compile_error::CompileError;
+use crate::error::error_code::ErrorCode;
+use crate::ir::{
+    BasicBlock, CastKind, Function, IrBinaryOp, IrConstantValue, IrLiteralValue, IrType, IrUnaryOp, Terminator,
+    TerminatorKind, Value, ValueKind,
+};
+use crate::ir::{Instruction as IrInstruction, InstructionKind};
+use std::sync::Arc;
+
+const RAX: GPRegister64 = GPRegister64::Rax;
+const RCX: GPRegister64 = GPRegister64::Rcx;
+const RDX: GPRegister64 = GPRegister64::Rdx;
+const RBP: GPRegister64 = GPRegister64::Rbp;
+const RSP: GPRegister64 = GPRegister64::Rsp;
+const R11: GPRegister64 = GPRegister64::R11;
+
+/// Lowers a single IR function into assembly.
+///
+/// A `FunctionLowering` borrows the output [`AssemblyFile`] and the error list of
+/// the owning [`AsmGen`](super::asmgen::AsmGen) for the duration of one
+/// function.
+pub struct FunctionLowering<'a> {
+    func: &'a Function,
+    functions: &'a [Function],
+    abi: Abi,
+    frame: StackFrame,
+    params: Vec<ParamAssignment>,
+    ret: Option<ReturnLocation>,
+    asm: &'a mut AssemblyFile,
+    errors: &'a mut Vec<CompileError>,
+    string_counter: &'a mut usize,
+}
+
+impl<'a> FunctionLowering<'a> {
+    /// Creates a lowering context for `func`.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The IR function to lower.
+    /// * `functions` - Every function of the module, whose signatures drive argument passing.
+    /// * `asm` - The assembly file receiving the generated code.
+    /// * `errors` - Error list collecting unsupported constructs.
+    /// * `string_counter` - Module-wide counter used to name string literals.
+    pub fn new(
+        func: &'a Function, functions: &'a [Function], asm: &'a mut AssemblyFile, errors: &'a mut Vec<CompileError>,
+        string_counter: &'a mut usize,
+    ) -> Self {
+        let abi = *asm.abi();
+        let allocation = RegisterAllocation::allocate(func, &abi);
+        let frame = StackFrame::with_allocation(func, &abi, &allocation);
+        Self { func, functions, abi, frame, params: Vec::new(), ret: None, asm, errors, string_counter }
+    }
+
+    /// Emits the whole function: label, prologue, blocks and edge stubs.
+    ///
+    /// Functions whose signature cannot be mapped onto the calling convention
+    /// are reported and skipped without emitting any code.
+    pub fn lower(mut self) {
+        let signature = classify_parameters(&self.func.name, &self.func.parameters, &self.abi)
+            .and_then(|params| Ok((params, return_location(&self.func.return_type, &self.abi)?)));
+        match signature {
+            Ok((params, ret)) => {
+                self.params = params;
+                self.ret = ret;
+            }
+            Err(error) => {
+                self.errors.push(error);
+                return;
+            }
+        }
+
+        let name = self.func.name.to_string();
+        if name == "main" {
+            self.asm.text_sec_add_global_label(name.clone());
+        }
+        self.asm.text_sec_add_label(name);
+        self.emit_prologue();
+
+        let blocks = block_order(self.func);
+        for (i, block) in blocks.iter().enumerate() {
+            let next = blocks.get(i + 1).map(|b| b.label.as_ref());
+            self.lower_block(block, next);
+        }
+    }
+
+    fn emit(&mut self, instr: Instruction) {
+        self.asm.text_sec_add_instruction(instr);
+    }
+
+    fn error(&mut self, message: impl AsRef<str>) {
+        self.errors.push(CompileError::AsmGeneratorError {
+            code: Some(ErrorCode::E4001),
+            message: Arc::from(format!("in function '{}': {}", self.func.name, message.as_ref())),
+        });
+    }
+
+    fn block_label(&self, label: &str) -> String {
+        format!("{}.{label}", self.func.name)
+    }
+
+    fn edge_label(&self, pred: &str, succ: &str) -> String {
+        format!("{}.{pred}.to.{succ}", self.func.name)
+    }
+
+    // ---------------------------------------------------------------------
+    // Prologue / epilogue
+    // ---------------------------------------------------------------------
+
+    fn emit_prologue(&mut self) {
+        for instr in gen_prologue(&self.frame) {
+            self.emit(instr);
+        }
+
+        for param in self.params.clone() {
+            let value = Value::new_local(param.name.clone(), param.ir_type.clone());
+            match param.location {
+                ParamLocation::GpRegister(reg) => {
+                    self.emit(Instruction::Mov { dest: Operand::reg64(RAX), src: Operand::reg64(reg) });
+                    self.normalize(&param.ir_type);
+                    self.store_rax(&value);
+                }
+                ParamLocation::XmmRegister(reg) => self.store_xmm(reg, &value, &param.ir_type),
+                ParamLocation::Stack { offset } => {
+                    self.emit(Instruction::Mov { dest: Operand::reg64(RAX), src: slot(offset) });
+                    self.normalize(&param.ir_type);
+                    self.store_rax(&value);
+                }
+            }
+        }
+    }
+
+    fn emit_epilogue(&mut self) {
+        for instr in gen_epilogue(&self.frame) {
+            self.emit(instr);
+        }
+    }
+
+    // ---------------------------------------------------------------------
+    // Blocks
+    // ---------------------------------------------------------------------
+
+    fn lower_block(&mut self, block: &BasicBlock, next: Option<&str>) {
+        self.asm.text_sec_add_label(self.block_label(&block.label));
+        for inst in &block.instructions {
+            self.lower_instruction(inst);
+        }
+        let stubs = self.lower_terminator(&block.label, block.terminator(), next);
+        for (stub, succ) in stubs {
+            self.asm.text_sec_add_label(stub);
+            self.emit_edge_copies(&block.label, &succ);
+            let target = self.block_label(&succ);
+            self.emit(Instruction::Jmp { target: Operand::label(target) });
+        }
+    }
+
+    fn lower_instruction(&mut self, inst: &IrInstruction) {
+        match &inst.kind {
+            // Allocas are materialized by the frame layout, phis on the incoming edges.
+            InstructionKind::Alloca { .. } | InstructionKind::Phi { .. } => {}
+            InstructionKind::Store { value, dest } => self.lower_store(value, dest),
+            InstructionKind::Load { src, ty } => self.lower_load(src, ty, inst.result.as_ref()),
+            InstructionKind::Binary { op, left, right, ty } => {
+                self.lower_binary(*op, left, right, ty, inst.result.as_ref());
+            }
+            InstructionKind::Unary { op, operand, ty } => self.lower_unary(*op, operand, ty, inst.result.as_ref()),
+            InstructionKind::Call { func, args, ty } => self.lower_call(func, args, ty, inst.result.as_ref()),
+            InstructionKind::GetElementPtr { base, index, element_ty } => {
+                self.load_gp(base, RAX);
+                self.load_gp(index, RCX);
+                let size = i64::try_from(size_of(element_ty)).unwrap_or(i64::MAX);
+                if size != 1 {
+                    self.emit(Instruction::Imul {
+                        dest: Some(Operand::reg64(RCX)),
+                        src1: Operand::reg64(RCX),
+                        src2: Some(imm(size)),
+                    });
+                }
+                self.emit(Instruction::Add { dest: Operand::reg64(RAX), src: Operand::reg64(RCX) });
+                self.store_result(inst.result.as_ref());
+            }
+            InstructionKind::Cast { kind, value, from_ty, to_ty } => {
+                self.lower_cast(*kind, value, from_ty, to_ty, inst.result.as_ref());
+            }
+            InstructionKind::Vector { .. } => self.error("vector instructions are not supported by the x86-64 backend"),
+        }
+    }
+
+    // ---------------------------------------------------------------------
+    // Memory
+    // ---------------------------------------------------------------------
+
+    fn lower_store(&mut self, value: &Value, dest: &Value) {
+        if self.frame.is_bound_by_store(dest) {
+            self.load_gp(value, RAX);
+            self.store_rax(dest);
+            return;
+        }
+
+        let pointee = match &dest.ty {
+            IrType::Pointer(inner) => inner.as_ref().clone(),
+            _ => value_type(&value.ty).clone(),
+        };
+        if is_aggregate(&pointee) {
+            self.load_gp(value, RAX);
+            self.load_gp(dest, RCX);
+            self.copy_bytes(size_of(&pointee));
+            return;
+        }
+
+        self.load_gp(dest, RCX);
+        self.load_gp(value, RAX);
+        let size = size_of(&pointee);
+        let src = match size {
+            1 => Operand::reg8(GPRegister8::Al),
+            2 => Operand::reg16(GPRegister16::Ax),
+            4 => Operand::reg32(GPRegister32::Eax),
+            _ => Operand::reg64(RAX),
+        };
+        self.emit(Instruction::Mov { dest: mem(RCX, 0, size.clamp(1, 8)), src });
+    }
+
+    /// Copies `size` bytes from the address in `RAX` to the address in `RCX`.
+    fn copy_bytes(&mut self, size: usize) {
+        let mut offset = 0;
+        for (chunk, reg) in [
+            (8, Operand::reg64(RDX)),
+            (4, Operand::reg32(GPRegister32::Edx)),
+            (2, Operand::reg16(GPRegister16::Dx)),
+            (1, Operand::reg8(GPRegister8::Dl)),
+        ] {
+            while size - offset >= chunk {
+                let disp = i32::try_from(offset).unwrap_or(i32::MAX);
+                self.emit(Instruction::Mov { dest: reg.clone(), src: mem(RAX, disp, chunk) });
+                self.emit(Instruction::Mov { dest: mem(RCX, disp, chunk), src: reg.clone() });
+                offset += chunk;
+            }
+        }
+    }
+
+    fn lower_load(&mut self, src: &Value, ty: &IrType, result: Option<&Value>) {
+        self.load_gp(src, RCX);
+        let ty = value_type(ty);
+        let rax = Operand::reg64(RAX);
+        let instr = match (size_of(ty), ty.is_signed_integer()) {
+            _ if is_aggregate(ty) => Instruction::Mov { dest: rax, src: Operand::reg64(RCX) },
+            (1, true) => Instruction::Movsx { dest: rax, src: mem(RCX, 0, 1) },
+            (1, false) => Instruction::Movzx { dest: rax, src: mem(RCX, 0, 1) },
+            (2, true) => Instruction::Movsx { dest: rax, src: mem(RCX, 0, 2) },
+            (2, false) => Instruction::Movzx { dest: rax, src: mem(RCX, 0, 2) },
+            (4, true) => Instruction::Movsxd { dest: rax, src: mem(RCX, 0, 4) },
+            (4, false) => Instruction::Mov { dest: Operand::reg32(GPRegister32::Eax), src: mem(RCX, 0, 4) },
+            _ => Instruction::Mov { dest: rax, src: mem(RCX, 0, 8) },
+        };
+        self.emit(instr);
+        self.store_result(result);
+    }
+
+    // ---------------------------------------------------------------------
+    // Arithmetic
+    // ---------------------------------------------------------------------
+
+    fn lower_binary(&mut self, op: IrBinaryOp, left: &Value, right: &Value, ty: &IrType, result: Option<&Value>) {
+        let operand_ty = value_type(&left.ty).clone();
+        if is_float(&operand_ty) {
+            self.lower_float_binary(op, left, right, &operand_ty, result);
+            return;
+        }
+
+        self.load_gp(left, RAX);
+        self.load_gp(right, RCX);
+        let (rax, rcx) = (Operand::reg64(RAX), Operand::reg64(RCX));
+        let signed = operand_ty.is_signed_integer();
+        match op {
+            IrBinaryOp::Add => self.emit(Instruction::Add { dest: rax, src: rcx }),
+            IrBinaryOp::Subtract => self.emit(Instruction::Sub { dest: rax, src: rcx }),
+            IrBinaryOp::Multiply => self.emit(Instruction::Imul { dest: Some(rax), src1: rcx, src2: None }),
+            IrBinaryOp::Divide | IrBinaryOp::Modulo => {
+                if signed {
+                    self.emit(Instruction::Cqo);
+                    self.emit(Instruction::Idiv { src: rcx });
+                } else {
+                    let edx = Operand::reg32(GPRegister32::Edx);
+                    self.emit(Instruction::Xor { dest: edx.clone(), src: edx });
+                    self.emit(Instruction::Div { src: rcx });
+                }
+                if op == IrBinaryOp::Modulo {
+                    self.emit(Instruction::Mov { dest: rax, src: Operand::reg64(RDX) });
+                }
+            }
+            IrBinaryOp::And | IrBinaryOp::BitwiseAnd => self.emit(Instruction::And { dest: rax, src: rcx }),
+            IrBinaryOp::Or | IrBinaryOp::BitwiseOr => self.emit(Instruction::Or { dest: rax, src: rcx }),
+            IrBinaryOp::BitwiseXor => self.emit(Instruction::Xor { dest: rax, src: rcx }),
+            IrBinaryOp::ShiftLeft | IrBinaryOp::ShiftRight => {
+                let mask = i64::from(operand_ty.get_bit_width().min(64) - 1);
+                self.emit(Instruction::And { dest: Operand::reg32(GPRegister32::Ecx), src: imm(mask) });
+                let count = Operand::reg8(GPRegister8::Cl);
+                self.emit(match op {
+                    IrBinaryOp::ShiftLeft => Instruction::Shl { dest: rax, count },
+                    _ if signed => Instruction::Sar { dest: rax, count },
+                    _ => Instruction::Shr { dest: rax, count },
+                });
+            }
+            IrBinaryOp::Equal
+            | IrBinaryOp::NotEqual
+            | IrBinaryOp::Less
+            | IrBinaryOp::LessEqual
+            | IrBinaryOp::Greater
+            | IrBinaryOp::GreaterEqual => {
+                self.emit(Instruction::Cmp { op1: rax, op2: rcx });
+                let al = Operand::reg8(GPRegister8::Al);
+                self.emit(int_condition(op, signed, al));
+                self.zero_extend_al();
+                self.store_result(result);
+                return;
+            }
+        }
+        self.normalize(ty);
+        self.store_result(result);
+    }
+
+    fn lower_float_binary(&mut self, op: IrBinaryOp, left: &Value, right: &Value, ty: &IrType, result: Option<&Value>) {
+        let single = *ty == IrType::F32;
+        self.load_xmm(left, XMMRegister::Xmm0, ty);
+        self.load_xmm(right, XMMRegister::Xmm1, ty);
+        let (xmm0, xmm1) = (Operand::xmm(XMMRegister::Xmm0), Operand::xmm(XMMRegister::Xmm1));
+        let arith = match (op, single) {
+            (IrBinaryOp::Add, true) => Instruction::Addss { dest: xmm0, src: xmm1 },
+            (IrBinaryOp::Add, false) => Instruction::Addsd { dest: xmm0, src: xmm1 },
+            (IrBinaryOp::Subtract, true) => Instruction::Subss { dest: xmm0, src: xmm1 },
+            (IrBinaryOp::Subtract, false) => Instruction::Subsd { dest: xmm0, src: xmm1 },
+            (IrBinaryOp::Multiply, true) => Instruction::Mulss { dest: xmm0, src: xmm1 },
+            (IrBinaryOp::Multiply, false) => Instruction::Mulsd { dest: xmm0, src: xmm1 },
+            (IrBinaryOp::Divide, true) => Instruction::Divss { dest: xmm0, src: xmm1 },
+            (IrBinaryOp::Divide, false) => Instruction::Divsd { dest: xmm0, src: xmm1 },
+            (
+                IrBinaryOp::Equal
+                | IrBinaryOp::NotEqual
+                | IrBinaryOp::Less
+                | IrBinaryOp::LessEqual
+                | IrBinaryOp::Greater
+                | IrBinaryOp::GreaterEqual,
+                _,
+            ) => {
+                self.lower_float_compare(op, single);
+                self.store_result(result);
+                return;
+            }
+            _ => {
+                self.error(format!("operator {op:?} is not supported on floating-point operands"));
+                return;
+            }
+        };
+        self.emit(arith);
+        if let Some(result) = result {
+            self.store_xmm(XMMRegister::Xmm0, result, ty);
+        }
+    }
+
+    /// Compares `XMM0` with `XMM1` and leaves the boolean outcome in `RAX`.
+    ///
+    /// Unordered operands (NaN) compare unequal to everything.
+    fn lower_float_compare(&mut self, op: IrBinaryOp, single: bool) {
+        let (xmm0, xmm1) = (Operand::xmm(XMMRegister::Xmm0), Operand::xmm(XMMRegister::Xmm1));
+        // `<` and `<=` are evaluated as `>` and `>=` with swapped operands so that
+        // the unordered case yields false through the carry/zero flags.
+        let (op1, op2) = match op {
+            IrBinaryOp::Less | IrBinaryOp::LessEqual => (xmm1, xmm0),
+            _ => (xmm0, xmm1),
+        };
+        self.emit(if single { Instruction::Ucomiss { op1, op2 } } else { Instruction::Ucomisd { op1, op2 } });
+
+        let (al, cl) = (Operand::reg8(GPRegister8::Al), Operand::reg8(GPRegister8::Cl));
+        match op {
+            IrBinaryOp::Equal => {
+                self.emit(Instruction::Sete { dest: al.clone() });
+                self.emit(Instruction::Setnp { dest: cl.clone() });
+                self.emit(Instruction::And { dest: al, src: cl });
+            }
+            IrBinaryOp::NotEqual => {
+                self.emit(Instruction::Setne { dest: al.clone() });
+                self.emit(Instruction::Setp { dest: cl.clone() });
+                self.emit(Instruction::Or { dest: al, src: cl });
+            }
+            IrBinaryOp::Greater | IrBinaryOp::Less => self.emit(Instruction::Seta { dest: al }),
+            _ => self.emit(Instruction::Setae { dest: al }),
+        }
+        self.zero_extend_al();
+    }
+
+    fn lower_unary(&mut self, op: IrUnaryOp, operand: &Value, ty: &IrType, result: Option<&Value>) {
+        let ty = value_type(ty);
+        self.load_gp(operand, RAX);
+        let rax = Operand::reg64(RAX);
+        match op {
+            // Flipping the sign bit negates IEEE-754 values, including zeros and NaNs.
+            IrUnaryOp::Negate if is_float(ty) => {
+                let sign_bit = if *ty == IrType::F32 { 31 } else { 63 };
+                self.emit(Instruction::Btc { dest: rax, src: Operand::imm8(sign_bit) });
+            }
+            IrUnaryOp::Negate => self.emit(Instruction::Neg { dest: rax }),
+            IrUnaryOp::Not if *ty == IrType::Bool => self.emit(Instruction::Xor { dest: rax, src: imm(1) }),
+            IrUnaryOp::Not => self.emit(Instruction::Not { dest: rax }),
+        }
+        self.normalize(ty);
+        self.store_result(result);
+    }
+
+    fn lower_cast(&mut self, kind: CastKind, value: &Value, from_ty: &IrType, to_ty: &IrType, result: Option<&Value>) {
+        // Casts between pointers, such as the byte address of a struct field
+        // turned into a `*i32`, keep all 64 bits of the address.
+        let to_pointer = matches!(to_ty, IrType::Pointer(_));
+        let (from_ty, to_ty) = (value_type(from_ty), value_type(to_ty));
+        match kind {
+            CastKind::IntBitcast | CastKind::Bitcast if to_pointer => self.load_gp(value, RAX),
+            CastKind::IntZeroExtend | CastKind::BoolToInt | CastKind::CharToInt => {
+                self.load_gp(value, RAX);
+                self.zero_extend_from(from_ty);
+                self.normalize(to_ty);
+            }
+            CastKind::IntSignExtend => {
+                self.load_gp(value, RAX);
+                self.sign_extend_from(from_ty);
+                self.normalize(to_ty);
+            }
+            CastKind::IntTruncate | CastKind::IntBitcast | CastKind::IntToChar | CastKind::Bitcast => {
+                self.load_gp(value, RAX);
+                self.normalize(to_ty);
+            }
+            CastKind::IntToBool => {
+                self.load_gp(value, RAX);
+                self.emit(Instruction::Test { op1: Operand::reg64(RAX), op2: Operand::reg64(RAX) });
+                self.emit(Instruction::Setne { dest: Operand::reg8(GPRegister8::Al) });
+                self.zero_extend_al();
+            }
+            CastKind::IntToFloat | CastKind::BoolToFloat => {
+                self.load_gp(value, RAX);
+                let (xmm0, rax) = (Operand::xmm(XMMRegister::Xmm0), Operand::reg64(RAX));
+                self.emit(if *to_ty == IrType::F32 {
+                    Instruction::Cvtsi2ss { dest: xmm0, src: rax }
+                } else {
+                    Instruction::Cvtsi2sd { dest: xmm0, src: rax }
+                });
+                if let Some(result) = result {
+                    self.store_xmm(XMMRegister::Xmm0, result, to_ty);
+                }
+                return;
+            }
+            CastKind::FloatToInt => {
+                self.load_xmm(value, XMMRegister::Xmm0, from_ty);
+                let (xmm0, rax) = (Operand::xmm(XMMRegister::Xmm0), Operand::reg64(RAX));
+                self.emit(if *from_ty == IrType::F32 {
+                    Instruction::Cvttss2si { dest: rax, src: xmm0 }
+                } else {
+                    Instruction::Cvttsd2si { dest: rax, src: xmm0 }
+                });
+                self.normalize(to_ty);
+            }
+            CastKind::FloatExtend | CastKind::FloatTruncate => {
+                self.load_xmm(value, XMMRegister::Xmm0, from_ty);
+                let xmm0 = Operand::xmm(XMMRegister::Xmm0);
+                self.emit(if kind == CastKind::FloatExtend {
+                    Instruction::Cvtss2sd { dest: xmm0.clone(), src: xmm0 }
+                } else {
+                    Instruction::Cvtsd2ss { dest: xmm0.clone(), src: xmm0 }
+                });
+                if let Some(result) = result {
+                    self.store_xmm(XMMRegister::Xmm0, result, to_ty);
+                }
+                return;
+            }
+            CastKind::FloatToBool => {
+                self.load_xmm(value, XMMRegister::Xmm0, from_ty);
+                let xmm1 = Operand::xmm(XMMRegister::Xmm1);
+                self.emit(Instruction::Xorps { dest: xmm1.clone(), src: xmm1 });
+                self.lower_float_compare(IrBinaryOp::NotEqual, *from_ty == IrType::F32);
+            }
+            CastKind::CharToString
+            | CastKind::StringToChar
+            | CastKind::StringToInt
+            | CastKind::StringToFloat
+            | CastKind::StringToBool
+            | CastKind::IntToString
+            | CastKind::FloatToString
+            | CastKind::BoolToString => {
+                self.error(format!("cast {kind:?} requires runtime support that is not available"));
+                return;
+            }
+        }
+        self.store_result(result);
+    }
+
+    // ---------------------------------------------------------------------
+    // Calls
+    // ---------------------------------------------------------------------
+
+    fn lower_call(&mut self, callee: &Value, args: &[Value], ty: &IrType, result: Option<&Value>) {
+        // Arguments travel where the callee expects its parameters, whatever the type of the argument value.
+        let params = match &callee.kind {
+            ValueKind::Global(name) => {
+                self.functions.iter().find(|func| func.name == *name).map(|func| &func.parameters)
+            }
+            _ => None,
+        };
+        let types: Vec<&IrType> = args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| value_type(params.and_then(|params| params.get(i)).map_or(&arg.ty, |param| &param.ty)))
+            .collect();
+        let locations = match classify_arguments(&types, &self.abi) {
+            Ok(locations) => locations,
+            Err(error) => {
+                self.errors.push(error);
+                return;
+            }
+        };
+
+        // Stack arguments go first: loading them uses RAX, which never carries an argument.
+        for (arg, location) in args.iter().zip(&locations) {
+            if let Some(offset) = location.outgoing_offset() {
+                self.load_gp(arg, RAX);
+                self.emit(Instruction::Mov { dest: Operand::mem_disp(RSP, offset), src: Operand::reg64(RAX) });
+            }
+        }
+        for ((arg, location), arg_ty) in args.iter().zip(&locations).zip(&types) {
+            match location {
+                ParamLocation::GpRegister(reg) => self.load_gp(arg, *reg),
+                ParamLocation::XmmRegister(reg) => self.load_xmm(arg, *reg, arg_ty),
+                ParamLocation::Stack { .. } => {}
+            }
+        }
+
+        let target = if let ValueKind::Global(name) = &callee.kind {
+            Operand::label(name.to_string())
+        } else {
+            self.load_gp(callee, R11);
+            Operand::reg64(R11)
+        };
+        self.emit(Instruction::Call { target });
+
+        let ret_ty = value_type(ty);
+        match result {
+            Some(result) if is_float(ret_ty) => self.store_xmm(XMMRegister::Xmm0, result, ret_ty),
+            Some(result) if *ret_ty != IrType::Void => {
+                self.normalize(ret_ty);
+                self.store_rax(result);
+            }
+            _ => {}
+        }
+    }
+
+    // ---------------------------------------------------------------------
+    // Terminators
+    // ---------------------------------------------------------------------
+
+    /// Lowers a terminator and returns the edge stubs that must be emitted after it.
+    fn lower_terminator(&mut self, pred: &str, term: &Terminator, next: Option<&str>) -> Vec<(String, Arc<str>)> {
+        let mut stubs = Vec::new();
+        match &term.kind {
+            TerminatorKind::Return { value, ty } => {
+                match self.ret {
+                    Some(ReturnLocation::Gp(reg)) => self.load_gp(value, reg),
+                    Some(ReturnLocation::Xmm(reg)) => self.load_xmm(value, reg, value_type(ty)),
+                    // `main` hands its exit status to the C runtime even when declared without a result.
+                    None if self.func.name.as_ref() == "main" => self.load_gp(value, RAX),
+                    None => {}
+                }
+                self.emit_epilogue();
+            }
+            TerminatorKind::Branch { label } => {
+                self.emit_edge_copies(pred, label);
+                if next != Some(label.as_ref()) {
+                    let target = self.block_label(label);
+                    self.emit(Instruction::Jmp { target: Operand::label(target) });
+                }
+            }
+            TerminatorKind::ConditionalBranch { condition, true_label, false_label } => {
+                self.load_gp(condition, RAX);
+                self.emit(Instruction::Test { op1: Operand::reg64(RAX), op2: Operand::reg64(RAX) });
+                let target = self.edge_target(pred, true_label, &mut stubs);
+                self.emit(Instruction::Jne { target: Operand::label(target) });
+                let target = self.edge_target(pred, false_label, &mut stubs);
+                if !stubs.is_empty() || next != Some(false_label.as_ref()) {
+                    self.emit(Instruction::Jmp { target: Operand::label(target) });
+                }
+            }
+            TerminatorKind::Switch { value, default_label, cases, .. } => {
+                self.load_gp(value, RAX);
+                let mut targets = Vec::with_capacity(cases.len());
+                for (case, label) in cases {
+                    match literal_bits(case) {
+                        Some(bits) => targets.push((bits, label)),
+                        None => self.error(format!("switch case value {case} is not a constant")),
+                    }
+                }
+                if let Some((min, len)) = jump_table_range(&targets) {
+                    self.emit_jump_table(pred, &targets, min, len, default_label, &mut stubs);
+                } else {
+                    self.emit_compare_chain(pred, &targets, default_label, &mut stubs);
+                }
+            }
+            TerminatorKind::IndirectBranch { address, .. } => {
+                self.load_gp(address, RAX);
+                self.emit(Instruction::Jmp { target: Operand::reg64(RAX) });
+            }
+            TerminatorKind::Unreachable => {
+                self.asm.text_sec_add_instruction_with_comment(Instruction::Hlt, "unreachable");
+            }
+        }
+        stubs
+    }
+
+    /// Compares the switch value in `RAX` with each case in turn.
+    fn emit_compare_chain(
+        &mut self, pred: &str, targets: &[(i64, &Arc<str>)], default_label: &Arc<str>,
+        stubs: &mut Vec<(String, Arc<str>)>,
+    ) {
+        for &(bits, label) in targets {
+            if i32::try_from(bits).is_ok() {
+                self.emit(Instruction::Cmp { op1: Operand::reg64(RAX), op2: imm(bits) });
+            } else {
+                self.emit(Instruction::Mov { dest: Operand::reg64(RCX), src: imm(bits) });
+                self.emit(Instruction::Cmp { op1: Operand::reg64(RAX), op2: Operand::reg64(RCX) });
+            }
+            let target = self.edge_target(pred, label, stubs);
+            self.emit(Instruction::Je { target: Operand::label(target) });
+        }
+        let target = self.edge_target(pred, default_label, stubs);
+        self.emit(Instruction::Jmp { target: Operand::label(target) });
+    }
+
+    /// Jumps through a table of `len` block offsets indexed by the switch
+    /// value in `RAX` minus `min`; values outside the table and holes go to
+    /// the default block.
+    ///
+    /// The table follows the jump in the text section and stores offsets from
+    /// its own start, so it needs no relocations.
+    fn emit_jump_table(
+        &mut self, pred: &str, targets: &[(i64, &Arc<str>)], min: i64, len: usize, default_label: &Arc<str>,
+        stubs: &mut Vec<(String, Arc<str>)>,
+    ) {
+        if i32::try_from(min).is_ok() {
+            if min != 0 {
+                self.emit(Instruction::Sub { dest: Operand::reg64(RAX), src: imm(min) });
+            }
+        } else {
+            self.emit(Instruction::Mov { dest: Operand::reg64(RCX), src: imm(min) });
+            self.emit(Instruction::Sub { dest: Operand::reg64(RAX), src: Operand::reg64(RCX) });
+        }
+        let last = i64::try_from(len - 1).unwrap_or(i64::MAX);
+        self.emit(Instruction::Cmp { op1: Operand::reg64(RAX), op2: imm(last) });
+        let default = self.edge_target(pred, default_label, stubs);
+        self.emit(Instruction::Ja { target: Operand::label(default.clone()) });
+
+        let table = format!("{}.{pred}.table", self.func.name);
+        let entry = MemoryOperand::new(Some(RCX)).with_index(RAX, 4).with_size(4);
+        self.emit(Instruction::Mov { dest: Operand::reg64(RCX), src: Operand::label(table.clone()) });
+        self.emit(Instruction::Movsxd { dest: Operand::reg64(RAX), src: Operand::Memory(entry) });
+        self.emit(Instruction::Add { dest: Operand::reg64(RAX), src: Operand::reg64(RCX) });
+        self.emit(Instruction::Jmp { target: Operand::reg64(RAX) });
+
+        let mut entries = vec![default; len];
+        // The first of duplicate cases wins, as in the compare chain
+        for &(bits, label) in targets.iter().rev() {
+            let index = usize::try_from(i128::from(bits) - i128::from(min)).unwrap_or_default();
+            entries[index] = self.edge_target(pred, label, stubs);
+        }
+        self.asm.text_sec_add_data(table, DataDirective::LabelOffsets(entries));
+    }
+
+    /// Returns the jump target for the edge `pred -> succ`, registering an edge
+    /// stub when the successor has phi nodes to resolve.
+    fn edge_target(&self, pred: &str, succ: &Arc<str>, stubs: &mut Vec<(String, Arc<str>)>) -> String {
+        if self.edge_copies(pred, succ).is_empty() {
+            return self.block_label(succ);
+        }
+        let stub = self.edge_label(pred, succ);
+        if !stubs.iter().any(|(label, _)| *label == stub) {
+            stubs.push((stub.clone(), succ.clone()));
+        }
+        stub
+    }
+
+    /// Collects the `(phi result, incoming value)` pairs of the edge `pred -> succ`.
+    fn edge_copies(&self, pred: &str, succ: &str) -> Vec<(&'a Value, &'a Value)> {
+        let Some(block) = self.func.cfg.get_block(succ) else {
+            return Vec::new();
+        };
+        block
+            .instructions
+            .iter()
+            .filter_map(|inst| match (&inst.kind, &inst.result) {
+                (InstructionKind::Phi { incoming, .. }, Some(result)) => {
+                    incoming.iter().find(|(_, label)| label == pred).map(|(value, _)| (result, value))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Performs the phi copies of the edge `pred -> succ` as a parallel copy.
+    ///
+    /// Copies between registers and stack slots are ordered by
+    /// [`sequentialize`] so that phis reading each other observe the old
+    /// values; constants read no location and are materialized last.
+    fn emit_edge_copies(&mut self, pred: &str, succ: &str) {
+        let mut moves = Vec::new();
+        let mut constants = Vec::new();
+        for (dest, src) in self.edge_copies(pred, succ) {
+            let Some(dest_location) = self.frame.location(dest) else {
+                self.error(format!("phi result {dest} has no location"));
+                continue;
+            };
+            match self.frame.location(src) {
+                Some(location @ (ValueLocation::Gp(_) | ValueLocation::Xmm(_) | ValueLocation::Stack(_))) => {
+                    moves.push((dest_location, location));
+                }
+                _ => constants.push((dest, src)),
+            }
+        }
+        for (dest, src) in sequentialize(&moves, ValueLocation::Gp(RAX)) {
+            self.emit_move(dest, src);
+        }
+        for (dest, src) in constants {
+            self.load_gp(src, RAX);
+            self.store_rax(dest);
+        }
+    }
+
+    /// Copies the 64-bit contents of `src` to `dest`.
+    fn emit_move(&mut self, dest: ValueLocation, src: ValueLocation) {
+        use ValueLocation::{FrameAddress, Gp, Stack, Xmm};
+        let instr = match (dest, src) {
+            (Gp(d), Gp(s)) => Instruction::Mov { dest: Operand::reg64(d), src: Operand::reg64(s) },
+            (Gp(d), Stack(s)) => Instruction::Mov { dest: Operand::reg64(d), src: slot(s) },
+            (Stack(d), Gp(s)) => Instruction::Mov { dest: slot(d), src: Operand::reg64(s) },
+            (Xmm(d), Xmm(s)) => Instruction::Movaps { dest: Operand::xmm(d), src: Operand::xmm(s) },
+            (Xmm(d), Stack(s)) => Instruction::Movsd { dest: Operand::xmm(d), src: slot(s) },
+            (Stack(d), Xmm(s)) => Instruction::Movsd { dest: slot(d), src: Operand::xmm(s) },
+            (Xmm(d), Gp(s)) => Instruction::Movq { dest: Operand::xmm(d), src: Operand::reg64(s) },
+            (Gp(d), Xmm(s)) => Instruction::Movq { dest: Operand::reg64(d), src: Operand::xmm(s) },
+            (Stack(d), Stack(s)) => {
+                // RAX may hold the value saved to break a cycle.
+                self.emit(Instruction::Mov { dest: Operand::reg64(RCX), src: slot(s) });
+                Instruction::Mov { dest: slot(d), src: Operand::reg64(RCX) }
+            }
+            (FrameAddress(_), _) | (_, FrameAddress(_)) => {
+                self.error("frame addresses cannot take part in a phi copy");
+                return;
+            }
+        };
+        self.emit(instr);
+    }
+
+    // ---------------------------------------------------------------------
+    // Value access
+    // ---------------------------------------------------------------------
+
+    /// Loads `value` into the 64-bit general purpose register `reg`.
+    fn load_gp(&mut self, value: &Value, reg: GPRegister64) {
+        let dest = Operand::reg64(reg);
+        match &value.kind {
+            ValueKind::Literal(_) => {
+                let bits = literal_bits(value).unwrap_or_default();
+                self.emit(Instruction::Mov { dest, src: imm(bits) });
+            }
+            ValueKind::Constant(IrConstantValue::String { string }) => {
+                let label = format!("str.{}", *self.string_counter);
+                *self.string_counter += 1;
+                self.asm.data_sec_add_data(label.clone(), DataDirective::new_asciz(string.to_string()));
+                self.emit(Instruction::Mov { dest, src: Operand::label(label) });
+            }
+            ValueKind::Constant(_) => self.error(format!("aggregate constant {value} cannot be used as an operand")),
+            ValueKind::Global(name) => self.emit(Instruction::Mov { dest, src: Operand::label(name.to_string()) }),
+            ValueKind::Local(_) | ValueKind::Temporary(_) => match self.frame.location(value) {
+                Some(ValueLocation::Stack(offset)) => self.emit(Instruction::Mov { dest, src: slot(offset) }),
+                Some(ValueLocation::FrameAddress(offset)) => {
+                    self.emit(Instruction::Lea { dest, src: slot(offset) });
+                }
+                Some(ValueLocation::Gp(src)) if src != reg => {
+                    self.emit(Instruction::Mov { dest, src: Operand::reg64(src) });
+                }
+                Some(ValueLocation::Gp(_)) => {}
+                Some(ValueLocation::Xmm(src)) => self.emit(Instruction::Movq { dest, src: Operand::xmm(src) }),
+                None => self.error(format!("value {value} is used but never defined")),
+            },
+        }
+    }
+
+    /// Loads the floating-point `value` of type `ty` into `reg`.
+    fn load_xmm(&mut self, value: &Value, reg: XMMRegister, ty: &IrType) {
+        let dest = Operand::xmm(reg);
+        match self.frame.location(value) {
+            Some(ValueLocation::Stack(offset)) => self.emit(if *ty == IrType::F32 {
+                Instruction::Movss { dest, src: sized_slot(offset, 4) }
+            } else {
+                Instruction::Movsd { dest, src: slot(offset) }
+            }),
+            Some(ValueLocation::Xmm(src)) if src != reg => {
+                self.emit(Instruction::Movaps { dest, src: Operand::xmm(src) });
+            }
+            Some(ValueLocation::Xmm(_)) => {}
+            Some(ValueLocation::Gp(src)) => self.emit(Instruction::Movq { dest, src: Operand::reg64(src) }),
+            _ => {
+                self.load_gp(value, RAX);
+                self.emit(Instruction::Movq { dest, src: Operand::reg64(RAX) });
+            }
+        }
+    }
+
+    /// Stores `RAX` into the location of `value`.
+    fn store_rax(&mut self, value: &Value) {
+        let rax = Operand::reg64(RAX);
+        match self.frame.location(value) {
+            Some(ValueLocation::Stack(offset)) => self.emit(Instruction::Mov { dest: slot(offset), src: rax }),
+            Some(ValueLocation::Gp(reg)) => self.emit(Instruction::Mov { dest: Operand::reg64(reg), src: rax }),
+            Some(ValueLocation::Xmm(reg)) => self.emit(Instruction::Movq { dest: Operand::xmm(reg), src: rax }),
+            _ => self.error(format!("value {value} has no location")),
+        }
+    }
+
+    fn store_result(&mut self, result: Option<&Value>) {
+        if let Some(result) = result {
+            self.store_rax(result);
+        }
+    }
+
+    /// Stores the floating-point register `reg` holding a value of type `ty` into the location of `value`.
+    fn store_xmm(&mut self, reg: XMMRegister, value: &Value, ty: &IrType) {
+        let src = Operand::xmm(reg);
+        match self.frame.location(value) {
+            Some(ValueLocation::Stack(offset)) => self.emit(if *ty == IrType::F32 {
+                Instruction::Movss { dest: sized_slot(offset, 4), src }
+            } else {
+                Instruction::Movsd { dest: slot(offset), src }
+            }),
+            Some(ValueLocation::Xmm(dest)) if dest != reg => {
+                self.emit(Instruction::Movaps { dest: Operand::xmm(dest), src });
+            }
+            Some(ValueLocation::Xmm(_)) => {}
+            Some(ValueLocation::Gp(dest)) => self.emit(Instruction::Movq { dest: Operand::reg64(dest), src }),
+            _ => self.error(format!("value {value} has no location")),
+        }
+    }
+
+    // ---------------------------------------------------------------------
+    // Integer normalization
+    // ---------------------------------------------------------------------
+
+    /// Re-establishes the canonical 64-bit form of a `ty` value held in `RAX`.
+    fn normalize(&mut self, ty: &IrType) {
+        match value_type(ty) {
+            IrType::I8 | IrType::I16 | IrType::I32 => self.sign_extend_from(value_type(ty)),
+            IrType::U8 | IrType::U16 | IrType::U32 | IrType::Bool | IrType::Char => {
+                self.zero_extend_from(value_type(ty));
+            }
+            _ => {}
+        }
+    }
+
+    fn sign_extend_from(&mut self, ty: &IrType) {
+        let rax = Operand::reg64(RAX);
+        match size_of(ty) {
+            1 => self.emit(Instruction::Movsx { dest: rax, src: Operand::reg8(GPRegister8::Al) }),
+            2 => self.emit(Instruction::Movsx { dest: rax, src: Operand::reg16(GPRegister16::Ax) }),
+            4 => self.emit(Instruction::Movsxd { dest: rax, src: Operand::reg32(GPRegister32::Eax) }),
+            _ => {}
+        }
+    }
+
+    fn zero_extend_from(&mut self, ty: &IrType) {
+        let rax = Operand::reg64(RAX);
+        match size_of(ty) {
+            1 => self.emit(Instruction::Movzx { dest: rax, src: Operand::reg8(GPRegister8::Al) }),
+            2 => self.emit(Instruction::Movzx { dest: rax, src: Operand::reg16(GPRegister16::Ax) }),
+            4 => {
+                let eax = Operand::reg32(GPRegister32::Eax);
+                self.emit(Instruction::Mov { dest: eax.clone(), src: eax });
+            }
+            _ => {}
+        }
+    }
+
+    fn zero_extend_al(&mut self) {
+        self.emit(Instruction::Movzx { dest: Operand::reg64(RAX), src: Operand::reg8(GPRegister8::Al) });
+    }
+}
+
+/// Returns the `SETcc` instruction implementing the integer comparison `op`.
+const fn int_condition(op: IrBinaryOp, signed: bool, dest: Operand) -> Instruction {
+    match (op, signed) {
+        (IrBinaryOp::Equal, _) => Instruction::Sete { dest },
+        (IrBinaryOp::NotEqual, _) => Instruction::Setne { dest },
+        (IrBinaryOp::Less, true) => Instruction::Setl { dest },
+        (IrBinaryOp::Less, false) => Instruction::Setb { dest },
+        (IrBinaryOp::LessEqual, true) => Instruction::Setle { dest },
+        (IrBinaryOp::LessEqual, false) => Instruction::Setbe { dest },
+        (IrBinaryOp::Greater, true) => Instruction::Setg { dest },
+        (IrBinaryOp::Greater, false) => Instruction::Seta { dest },
+        (IrBinaryOp::GreaterEqual, true) => Instruction::Setge { dest },
+        _ => Instruction::Setae { dest },
+    }
+}
+
+/// Returns the canonical 64-bit pattern of a literal value.
+///
+/// Signed integers are sign-extended, unsigned integers, booleans and chars are
+/// zero-extended and floating-point values are returned as their IEEE-754 bits.
+#[allow(clippy::cast_possible_wrap)]
+pub(super) fn literal_bits(value: &Value) -> Option<i64> {
+    let ValueKind::Literal(lit) = &value.kind else {
+        return None;
+    };
+    Some(match lit {
+        IrLiteralValue::I8(v) => i64::from(*v),
+        IrLiteralValue::I16(v) => i64::from(*v),
+        IrLiteralValue::I32(v) => i64::from(*v),
+        IrLiteralValue::I64(v) => *v,
+        IrLiteralValue::U8(v) => i64::from(*v),
+        IrLiteralValue::U16(v) => i64::from(*v),
+        IrLiteralValue::U32(v) => i64::from(*v),
+        IrLiteralValue::U64(v) => *v as i64,
+        IrLiteralValue::F32(v) => i64::from(v.to_bits()),
+        IrLiteralValue::F64(v) => v.to_bits() as i64,
+        IrLiteralValue::Bool(v) => i64::from(*v),
+        IrLiteralValue::Char(v) => i64::from(u32::from(*v)),
+    })
+}
+
+/// Minimum number of cases for a switch to be lowered to a jump table.
+const JUMP_TABLE_MIN_CASES: usize = 4;
+
+/// Returns the smallest case value and the table length when a switch has
+/// enough cases, dense enough (at least 40% of the table entries naming a
+/// case), to be lowered to a jump table rather than a compare chain.
+fn jump_table_range(targets: &[(i64, &Arc<str>)]) -> Option<(i64, usize)> {
+    if targets.len() < JUMP_TABLE_MIN_CASES {
+        return None;
+    }
+    let min = targets.iter().map(|&(bits, _)| bits).min()?;
+    let max = targets.iter().map(|&(bits, _)| bits).max()?;
+    let len = u32::try_from(i128::from(max) - i128::from(min) + 1).ok()? as usize;
+    (len * 2 <= targets.len() * 5).then_some((min, len))
+}
+
+/// Returns the smallest immediate operand able to hold `value`.
+fn imm(value: i64) -> Operand {
+    i32::try_from(value).map_or_else(|_| Operand::imm64(value), Operand::imm32)
+}
+
+const fn slot(offset: i32) -> Operand {
+    Operand::mem_disp(RBP, offset)
+}
+
+const fn sized_slot(offset: i32, size: usize) -> Operand {
+    mem(RBP, offset, size)
+}
+
+const fn mem(base: GPRegister64, displacement: i32, size: usize) -> Operand {
+    Operand::Memory(MemoryOperand::new(Some(base)).with_displacement(displacement).with_size(size))
+}
+
+const fn is_float(ty: &IrType) -> bool {
+    matches!(ty, IrType::F32 | IrType::F64)
+}
+
+const fn is_aggregate(ty: &IrType) -> bool {
+    matches!(ty, IrType::Array(..) | IrType::Struct(..))
+}
@@ -55,3 +55,38 @@
 /// Provides infrastructure for emitting assembly instructions, managing labels,
 /// and formatting output for various target architectures.
 pub mod asmgen;
+
+/// Stack frame layout submodule.
+///
+/// Assigns an RBP-relative location to every IR value of a function and
+/// provides the size and alignment rules of IR types on `x86_64`.
+pub mod frame;
+
+/// Instruction selection submodule.
+///
+/// Lowers IR instructions and terminators into `x86_64` instructions using the
+/// registers chosen by [`regalloc`] and the locations computed by [`frame`].
+pub mod isel;
+
+/// Parameter passing submodule.
+///
+/// Classifies parameters, call arguments and return values according to the
+/// System V and Microsoft x64 calling conventions.
+pub mod param;
+
+/// Prologue and epilogue submodule.
+///
+/// Generates frame setup and teardown, including the preservation of
+/// callee-saved registers.
+pub mod prologue;
+
+/// Register allocation submodule.
+///
+/// Computes live intervals and assigns physical registers to SSA values with
+/// linear scan, spilling to the stack frame under register pressure.
+pub mod regalloc;
+
+/// Target resolution submodule.
+///
+/// Maps module target triples to the ABI used by the backend.
+pub mod target;
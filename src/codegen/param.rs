@@ -0,0 +1,213 @@
+//! # Parameter Passing
+//!
+//! Classifies function parameters and return values according to the `x86_64`
+//! calling conventions supported by the backend.
+//!
+//! | Convention     | Integer registers            | Float registers | First stack slot |
+//! |----------------|------------------------------|-----------------|------------------|
+//! | System V AMD64 | RDI, RSI, RDX, RCX, R8, R9   | XMM0–XMM7       | `[rbp + 16]`     |
+//! | Microsoft x64  | RCX, RDX, R8, R9             | XMM0–XMM3       | `[rbp + 48]`     |
+//!
+//! System V hands out integer and floating-point registers from two independent
+//! sequences, while Microsoft x64 uses positional slots: the N-th parameter
+//! always uses the N-th register of its class. Parameters that do not fit in a
+//! register are passed on the stack in 8-byte slots, in declaration order.
+//!
+//! `Pointer`, `Bool` and `Char` values are passed like integers; strings and
+//! aggregates are not supported yet and are reported as ABI errors.
+
+use crate::asm::{Abi, AbiKind, GPRegister64, XMMRegister};
+use crate::error::compile_error::CompileError;
+use crate::error::error_code::ErrorCode;
+use crate::ir::{IrParameter, IrType};
+use std::sync::Arc;
+
+/// Size in bytes of a stack-passed argument slot.
+const STACK_SLOT_SIZE: i32 = 8;
+
+/// Register class of a parameter or return value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamClass {
+    /// Passed in general purpose registers.
+    Integer,
+    /// Passed in XMM registers.
+    Float,
+}
+
+/// Location of a parameter on entry to the callee.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamLocation {
+    /// Passed in a general purpose register.
+    GpRegister(GPRegister64),
+    /// Passed in an XMM register.
+    XmmRegister(XMMRegister),
+    /// Passed on the stack at `[rbp + offset]` once the callee has set up its frame.
+    Stack { offset: i32 },
+}
+
+impl ParamLocation {
+    /// Returns the offset of a stack argument relative to `RSP` at the call site.
+    ///
+    /// The caller writes outgoing arguments before `call` pushes the return
+    /// address and the callee pushes `RBP`, so the two views differ by 16 bytes.
+    #[must_use]
+    pub const fn outgoing_offset(&self) -> Option<i32> {
+        match self {
+            Self::Stack { offset } => Some(*offset - 16),
+            Self::GpRegister(_) | Self::XmmRegister(_) => None,
+        }
+    }
+}
+
+/// A parameter paired with its ABI location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamAssignment {
+    /// Zero-based position in the function signature.
+    pub index: usize,
+    /// Parameter name.
+    pub name: Arc<str>,
+    /// IR type of the parameter.
+    pub ir_type: IrType,
+    /// Location assigned by the calling convention.
+    pub location: ParamLocation,
+}
+
+/// Register holding a return value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturnLocation {
+    /// Returned in `RAX` (or one of its sub-registers).
+    Gp(GPRegister64),
+    /// Returned in `XMM0`.
+    Xmm(XMMRegister),
+}
+
+/// Classifies an IR type for register assignment.
+///
+/// # Errors
+///
+/// Returns [`ErrorCode::E4005`] for types that cannot be passed in a register
+/// (structs, which would be passed by value, and `void`).
+pub fn classify_param_type(ir_type: &IrType) -> Result<ParamClass, CompileError> {
+    match ir_type {
+        IrType::I8
+        | IrType::I16
+        | IrType::I32
+        | IrType::I64
+        | IrType::U8
+        | IrType::U16
+        | IrType::U32
+        | IrType::U64
+        | IrType::Bool
+        | IrType::Char
+        | IrType::Pointer(_)
+        // Strings and arrays are passed by address.
+        | IrType::String
+        | IrType::Array(..)
+        | IrType::Custom(..) => Ok(ParamClass::Integer),
+        IrType::F32 | IrType::F64 => Ok(ParamClass::Float),
+        IrType::Struct(..) | IrType::Void => {
+            Err(abi_error(format!("unsupported parameter type '{ir_type}' for ABI parameter passing")))
+        }
+    }
+}
+
+/// Assigns a location to every parameter of a function.
+///
+/// # Errors
+///
+/// Returns [`ErrorCode::E4005`] if a parameter has a type that cannot be passed
+/// in a register; the message names the offending function and parameter.
+pub fn classify_parameters(
+    func_name: &str, params: &[IrParameter], abi: &Abi,
+) -> Result<Vec<ParamAssignment>, CompileError> {
+    let types: Vec<&IrType> = params.iter().map(|param| &param.ty).collect();
+    let locations = assign_locations(&types, *abi).map_err(|(index, _)| {
+        let param = &params[index];
+        abi_error(format!(
+            "unsupported parameter type '{}' for ABI parameter passing in function '{func_name}' (parameter '{}')",
+            param.ty, param.name
+        ))
+    })?;
+    Ok(params
+        .iter()
+        .zip(locations)
+        .enumerate()
+        .map(|(index, (param, location))| ParamAssignment {
+            index,
+            name: param.name.clone(),
+            ir_type: param.ty.clone(),
+            location,
+        })
+        .collect())
+}
+
+/// Assigns a location to every argument of a call, given the argument types.
+///
+/// # Errors
+///
+/// Returns [`ErrorCode::E4005`] if an argument has a type that cannot be passed
+/// in a register.
+pub fn classify_arguments(types: &[&IrType], abi: &Abi) -> Result<Vec<ParamLocation>, CompileError> {
+    assign_locations(types, *abi).map_err(|(_, error)| error)
+}
+
+/// Returns the register holding a value of type `return_type` on return, or
+/// `None` for `void` functions.
+///
+/// # Errors
+///
+/// Returns [`ErrorCode::E4005`] for return types that need a hidden return
+/// pointer (`string`, arrays, structs and custom types).
+pub fn return_location(return_type: &IrType, abi: &Abi) -> Result<Option<ReturnLocation>, CompileError> {
+    if *return_type == IrType::Void {
+        return Ok(None);
+    }
+    match classify_param_type(return_type) {
+        Ok(ParamClass::Integer) => Ok(abi.int_return_registers().first().map(|reg| ReturnLocation::Gp(*reg))),
+        Ok(ParamClass::Float) => Ok(abi.float_return_registers().first().map(|reg| ReturnLocation::Xmm(*reg))),
+        Err(_) => Err(abi_error(format!("unsupported return type '{return_type}' for ABI return value placement"))),
+    }
+}
+
+/// Returns the number of 8-byte stack slots used by stack-passed arguments.
+#[must_use]
+pub fn stack_slot_count(locations: &[ParamLocation]) -> usize {
+    locations.iter().filter(|location| matches!(location, ParamLocation::Stack { .. })).count()
+}
+
+fn assign_locations(types: &[&IrType], abi: Abi) -> Result<Vec<ParamLocation>, (usize, CompileError)> {
+    let int_regs = abi.int_param_registers();
+    let float_regs = abi.float_param_registers();
+    let first_stack_offset = 16 + i32::try_from(abi.shadow_space()).unwrap_or(0);
+
+    let (mut next_int, mut next_float, mut next_stack) = (0, 0, first_stack_offset);
+    let mut locations = Vec::with_capacity(types.len());
+    for (index, ty) in types.iter().enumerate() {
+        let class = classify_param_type(ty).map_err(|error| (index, error))?;
+        // Microsoft x64 consumes one positional slot per parameter, whatever its class.
+        let (int_slot, float_slot) = match abi.kind {
+            AbiKind::Windows => (index, index),
+            AbiKind::SystemV => (next_int, next_float),
+        };
+        let location = match class {
+            ParamClass::Integer => int_regs.get(int_slot).map(|reg| ParamLocation::GpRegister(*reg)),
+            ParamClass::Float => float_regs.get(float_slot).map(|reg| ParamLocation::XmmRegister(*reg)),
+        };
+        let location = location.unwrap_or_else(|| {
+            let offset = next_stack;
+            next_stack += STACK_SLOT_SIZE;
+            ParamLocation::Stack { offset }
+        });
+        match location {
+            ParamLocation::GpRegister(_) => next_int += 1,
+            ParamLocation::XmmRegister(_) => next_float += 1,
+            ParamLocation::Stack { .. } => {}
+        }
+        locations.push(location);
+    }
+    Ok(locations)
+}
+
+fn abi_error(message: String) -> CompileError {
+    CompileError::AsmGeneratorError { code: Some(ErrorCode::E4005), message: Arc::from(message) }
+}
@@ -0,0 +1,88 @@
+//! # Prologue and Epilogue
+//!
+//! Generates the instruction sequences that set up and tear down the stack
+//! frame described by a [`StackFrame`].
+//!
+//! ```text
+//! prologue:                      epilogue:
+//!     push rbp                       movdqu xmm6, [rbp - N]   ; Windows only
+//!     mov rbp, rsp                   lea rsp, [rbp - 8 * K]   ; or mov rsp, rbp
+//!     push rbx                       pop r12
+//!     push r12                       pop rbx
+//!     sub rsp, SIZE                  pop rbp
+//!     movdqu [rbp - N], xmm6         ret
+//! ```
+//!
+//! The frame pointer is always set up, so the epilogue restores `RSP` from `RBP`
+//! regardless of any adjustment made in the function body.
+
+use super::frame::{SLOT_SIZE, StackFrame};
+use crate::asm::{Abi, GPRegister64, Instruction, MemoryOperand, Operand, XMMRegister};
+
+/// Returns the callee-saved general purpose registers that must be preserved by
+/// a function whose body writes to `clobbered`, in push order.
+///
+/// `RBP` is never included: it is always saved as the frame pointer.
+#[must_use]
+pub fn callee_saved_to_preserve(abi: &Abi, clobbered: &[GPRegister64]) -> Vec<GPRegister64> {
+    abi.callee_saved_gp_registers()
+        .iter()
+        .copied()
+        .filter(|reg| *reg != GPRegister64::Rbp && clobbered.contains(reg))
+        .collect()
+}
+
+/// Returns the callee-saved XMM registers that must be preserved by a function
+/// whose body writes to `clobbered` (only Microsoft x64 has any).
+#[must_use]
+pub fn callee_saved_xmm_to_preserve(abi: &Abi, clobbered: &[XMMRegister]) -> Vec<XMMRegister> {
+    abi.callee_saved_xmm_registers().iter().copied().filter(|reg| clobbered.contains(reg)).collect()
+}
+
+/// Generates the function prologue for `frame`.
+#[must_use]
+pub fn gen_prologue(frame: &StackFrame) -> Vec<Instruction> {
+    let rbp = Operand::reg64(GPRegister64::Rbp);
+    let rsp = Operand::reg64(GPRegister64::Rsp);
+    let mut instructions =
+        vec![Instruction::Push { src: rbp.clone() }, Instruction::Mov { dest: rbp, src: rsp.clone() }];
+    instructions.extend(frame.preserved_gp().iter().map(|reg| Instruction::Push { src: Operand::reg64(*reg) }));
+    if frame.frame_size() > 0 {
+        let size = i32::try_from(frame.frame_size()).unwrap_or(i32::MAX);
+        instructions.push(Instruction::Sub { dest: rsp, src: Operand::imm32(size) });
+    }
+    instructions.extend(
+        frame
+            .preserved_xmm()
+            .iter()
+            .map(|(reg, offset)| Instruction::Movdqu { dest: xmm_save_area(*offset), src: Operand::xmm(*reg) }),
+    );
+    instructions
+}
+
+/// Generates the function epilogue for `frame`, ending with `ret`.
+#[must_use]
+pub fn gen_epilogue(frame: &StackFrame) -> Vec<Instruction> {
+    let mut instructions: Vec<Instruction> = frame
+        .preserved_xmm()
+        .iter()
+        .map(|(reg, offset)| Instruction::Movdqu { dest: Operand::xmm(*reg), src: xmm_save_area(*offset) })
+        .collect();
+
+    let rsp = Operand::reg64(GPRegister64::Rsp);
+    let preserved = frame.preserved_gp();
+    if preserved.is_empty() {
+        instructions.push(Instruction::Mov { dest: rsp, src: Operand::reg64(GPRegister64::Rbp) });
+    } else {
+        let pushed = i32::try_from(preserved.len()).unwrap_or(0) * SLOT_SIZE;
+        instructions.push(Instruction::Lea { dest: rsp, src: Operand::mem_disp(GPRegister64::Rbp, -pushed) });
+    }
+    instructions.extend(preserved.iter().rev().map(|reg| Instruction::Pop { dest: Operand::reg64(*reg) }));
+    instructions.push(Instruction::Pop { dest: Operand::reg64(GPRegister64::Rbp) });
+    instructions.push(Instruction::Ret);
+    instructions
+}
+
+const fn xmm_save_area(offset: i32) -> Operand {
+    Operand::Memory(MemoryOperand::new(Some(GPRegister64::Rbp)).with_displacement(offset).with_size(16))
+}
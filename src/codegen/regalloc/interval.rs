@@ -0,0 +1,411 @@
+//! # Live Intervals
+//!
+//! Numbers the instructions of a function in emission order and computes, for
+//! every SSA value, the range of positions where the value is live.
+//!
+//! Block-level liveness comes from the [`LivenessAnalyzer`] of dead code
+//! elimination. Its def-use chains only know instruction results, so values
+//! bound by a `store` look live from the function entry, and phi operands look
+//! live on every incoming edge. A value is therefore only kept live on entry
+//! to the blocks reachable from its definition, and on exit from those and
+//! from the defining block itself. Unlike dominance, reachability stays sound
+//! when a use is not dominated by its definition.
+//!
+//! ## Numbering
+//!
+//! ```text
+//! 0                 prologue: parameters are defined
+//! start(B)          phi results of B are defined
+//! start(B) + 1 ...  one position per instruction
+//! end(B) - 1        terminator
+//! end(B)            phi copies on the outgoing edges of B
+//! ```
+//!
+//! Intervals are conservative: an interval spans from the first to the last
+//! position where its value is live, ignoring lifetime holes.
+
+use super::super::frame::value_type;
+use crate::ir::optimizer::dead_code_elimination::LivenessAnalyzer;
+use crate::ir::value::ValueId;
+use crate::ir::{BasicBlock, Function, InstructionKind, IrBinaryOp, IrType, TerminatorKind, Value, ValueKind};
+use petgraph::graph::NodeIndex;
+use std::collections::{HashMap, HashSet};
+
+/// Register file a value is allocated in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegisterClass {
+    /// Integers, booleans, characters and pointers.
+    General,
+    /// `f32` and `f64` values.
+    Vector,
+}
+
+impl RegisterClass {
+    /// Returns the register class of values of type `ty`.
+    #[must_use]
+    pub fn of(ty: &IrType) -> Self {
+        match value_type(ty) {
+            IrType::F32 | IrType::F64 => Self::Vector,
+            _ => Self::General,
+        }
+    }
+}
+
+/// Live range of a single SSA value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveInterval {
+    /// The value the interval belongs to.
+    pub value: ValueKind,
+    /// Register class of the value.
+    pub class: RegisterClass,
+    /// First position where the value is live (its definition).
+    pub start: usize,
+    /// Last position where the value is live.
+    pub end: usize,
+}
+
+impl LiveInterval {
+    /// Returns `true` if the value must survive the instruction at `position`.
+    ///
+    /// Operands consumed by the instruction and its own result do not count.
+    #[must_use]
+    pub const fn spans(&self, position: usize) -> bool {
+        self.start < position && position < self.end
+    }
+
+    /// Returns `true` if the two intervals share at least one position.
+    #[must_use]
+    pub const fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    const fn extend(&mut self, position: usize) {
+        if position < self.start {
+            self.start = position;
+        }
+        if position > self.end {
+            self.end = position;
+        }
+    }
+}
+
+/// Positions delimiting a basic block in the linear order.
+#[derive(Debug, Clone, Copy)]
+struct BlockRange {
+    start: usize,
+    end: usize,
+}
+
+/// Live intervals of all allocatable values of a function.
+#[derive(Debug, Clone, Default)]
+pub struct LiveIntervals {
+    intervals: Vec<LiveInterval>,
+    index: HashMap<ValueKind, usize>,
+    call_positions: Vec<usize>,
+}
+
+impl LiveIntervals {
+    /// Computes the live intervals of `func`.
+    ///
+    /// Allocatable values are parameters, instruction results other than
+    /// `alloca` and temporaries bound by a `store`. Blocks are numbered in
+    /// the order returned by [`block_order`].
+    #[must_use]
+    pub fn compute(func: &Function) -> Self {
+        let mut intervals = Self::default();
+        let mut scan = Scan::default();
+        let ranges = intervals.number(func, &mut scan);
+
+        for (kind, position) in scan.uses {
+            intervals.extend(&kind, position);
+        }
+        // Phi copies happen at the end of the predecessor: both the incoming
+        // value and the phi result must be live there.
+        for (result, value, pred) in scan.phi_edges {
+            if let Some(range) = func.cfg.find_block_by_label(&pred).and_then(|node| ranges.get(&node)) {
+                intervals.extend(&result, range.end);
+                intervals.extend(&value, range.end);
+            }
+        }
+
+        let mut analyzer = LivenessAnalyzer::new();
+        analyzer.build_def_use_chains(func);
+        analyzer.compute_gen_kill_sets(func);
+        if analyzer.analyze(func) {
+            intervals.apply_block_liveness(func, &analyzer, &ranges, &scan.ids, &scan.def_blocks);
+        } else {
+            // Without a fixed point the live sets may be incomplete: keep every
+            // value alive for the whole function.
+            let last = ranges.values().map(|range| range.end).max().unwrap_or_default();
+            for interval in &mut intervals.intervals {
+                interval.extend(0);
+                interval.extend(last);
+            }
+        }
+        intervals
+    }
+
+    /// Returns all intervals, in definition order.
+    #[must_use]
+    pub fn intervals(&self) -> &[LiveInterval] {
+        &self.intervals
+    }
+
+    /// Returns the interval of `value`, if it is an allocatable value.
+    #[must_use]
+    pub fn get(&self, value: &Value) -> Option<&LiveInterval> {
+        self.index.get(&value.kind).map(|&i| &self.intervals[i])
+    }
+
+    /// Returns the positions of all call instructions, in increasing order.
+    #[must_use]
+    pub fn call_positions(&self) -> &[usize] {
+        &self.call_positions
+    }
+
+    /// Returns `true` if `interval` must survive a call, and therefore cannot
+    /// live in a caller-saved register.
+    #[must_use]
+    pub fn crosses_call(&self, interval: &LiveInterval) -> bool {
+        self.call_positions.iter().any(|&position| interval.spans(position))
+    }
+
+    /// Assigns positions to every block, creates an interval for every
+    /// definition and records the uses in `scan`.
+    fn number(&mut self, func: &Function, scan: &mut Scan) -> HashMap<NodeIndex, BlockRange> {
+        let stored = store_bound_temporaries(func);
+        let entry = func.cfg.get_entry_block_index();
+        for param in &func.parameters {
+            let value = Value::new_local(param.name.clone(), param.ty.clone());
+            self.define(&value, 0);
+            scan.def(&value, entry);
+        }
+
+        let order = block_order(func);
+        let mut ranges = HashMap::with_capacity(order.len());
+        let mut position = 1;
+        for block in order {
+            let node = func.cfg.find_block_by_label(&block.label);
+            let start = position;
+            for inst in &block.instructions {
+                position += 1;
+                match (&inst.kind, &inst.result) {
+                    (InstructionKind::Alloca { .. }, _) => {}
+                    (InstructionKind::Phi { incoming, .. }, Some(result)) => {
+                        self.define(result, start);
+                        scan.def(result, node);
+                        for (value, pred) in incoming {
+                            scan.ids.insert(value.id, value.kind.clone());
+                            scan.phi_edges.push((result.kind.clone(), value.kind.clone(), pred.clone()));
+                        }
+                    }
+                    (InstructionKind::Store { value, dest }, None) if stored.contains(&dest.kind) => {
+                        scan.use_value(value, position);
+                        self.define(dest, position);
+                        scan.def(dest, node);
+                    }
+                    (kind, result) => {
+                        for_each_operand(kind, |value| scan.use_value(value, position));
+                        if matches!(kind, InstructionKind::Call { .. }) {
+                            self.call_positions.push(position);
+                        }
+                        if let Some(result) = result {
+                            self.define_as(result, result_class(kind, result), position);
+                            scan.def(result, node);
+                        }
+                    }
+                }
+            }
+            position += 1;
+            for_each_terminator_operand(&block.terminator().kind, |value| scan.use_value(value, position));
+            position += 1;
+            if let Some(node) = node {
+                ranges.insert(node, BlockRange { start, end: position });
+            }
+            position += 1;
+        }
+        ranges
+    }
+
+    /// Extends the intervals over the blocks where their values are live on
+    /// entry or exit.
+    fn apply_block_liveness(
+        &mut self, func: &Function, analyzer: &LivenessAnalyzer, ranges: &HashMap<NodeIndex, BlockRange>,
+        ids: &HashMap<ValueId, ValueKind>, def_blocks: &HashMap<ValueKind, NodeIndex>,
+    ) {
+        let graph = func.cfg.graph();
+        let mut reachable: HashMap<NodeIndex, HashSet<NodeIndex>> = HashMap::new();
+        let mut live_here = |kind: &ValueKind, node: NodeIndex, on_entry: bool| {
+            let Some(&def_block) = def_blocks.get(kind) else {
+                return true;
+            };
+            if !on_entry && def_block == node {
+                return true;
+            }
+            reachable
+                .entry(def_block)
+                .or_insert_with(|| {
+                    let mut seen = HashSet::new();
+                    let mut stack: Vec<NodeIndex> = graph.neighbors(def_block).collect();
+                    while let Some(next) = stack.pop() {
+                        if seen.insert(next) {
+                            stack.extend(graph.neighbors(next));
+                        }
+                    }
+                    seen
+                })
+                .contains(&node)
+        };
+
+        for (&node, range) in ranges {
+            let sets = [(analyzer.live_in(node), range.start, true), (analyzer.live_out(node), range.end, false)];
+            for (set, position, on_entry) in sets {
+                for id in set.into_iter().flatten() {
+                    if let Some(kind) = ids.get(id)
+                        && live_here(kind, node, on_entry)
+                    {
+                        self.extend(&kind.clone(), position);
+                    }
+                }
+            }
+        }
+    }
+
+    fn define(&mut self, value: &Value, position: usize) {
+        self.define_as(value, RegisterClass::of(&value.ty), position);
+    }
+
+    fn define_as(&mut self, value: &Value, class: RegisterClass, position: usize) {
+        if let Some(&i) = self.index.get(&value.kind) {
+            self.intervals[i].extend(position);
+            return;
+        }
+        self.index.insert(value.kind.clone(), self.intervals.len());
+        self.intervals.push(LiveInterval { value: value.kind.clone(), class, start: position, end: position });
+    }
+
+    fn extend(&mut self, kind: &ValueKind, position: usize) {
+        if let Some(&i) = self.index.get(kind) {
+            self.intervals[i].extend(position);
+        }
+    }
+}
+
+/// Definitions and uses collected while numbering a function.
+#[derive(Default)]
+struct Scan {
+    uses: Vec<(ValueKind, usize)>,
+    phi_edges: Vec<(ValueKind, ValueKind, String)>,
+    ids: HashMap<ValueId, ValueKind>,
+    def_blocks: HashMap<ValueKind, NodeIndex>,
+}
+
+impl Scan {
+    fn def(&mut self, value: &Value, node: Option<NodeIndex>) {
+        self.ids.insert(value.id, value.kind.clone());
+        if let Some(node) = node {
+            self.def_blocks.entry(value.kind.clone()).or_insert(node);
+        }
+    }
+
+    fn use_value(&mut self, value: &Value, position: usize) {
+        if matches!(value.kind, ValueKind::Local(_) | ValueKind::Temporary(_)) {
+            self.ids.insert(value.id, value.kind.clone());
+            self.uses.push((value.kind.clone(), position));
+        }
+    }
+}
+
+/// Returns the blocks of `func` in emission order: the entry block first,
+/// followed by the remaining blocks in CFG order.
+#[must_use]
+pub fn block_order(func: &Function) -> Vec<&BasicBlock> {
+    let entry = func.cfg.entry_label();
+    func.cfg
+        .get_entry_block()
+        .into_iter()
+        .chain(func.cfg.blocks().filter(|block| block.label.as_ref() != entry))
+        .collect()
+}
+
+/// Returns the temporaries that receive their value from a `store` instead of
+/// an instruction result.
+///
+/// After SSA construction `store 10i32 to t5` binds the fresh name `t5`, while
+/// a store to a temporary defined elsewhere (an `alloca` or a pointer
+/// computation) writes through memory.
+#[must_use]
+pub fn store_bound_temporaries(func: &Function) -> HashSet<ValueKind> {
+    let mut defined: HashSet<ValueKind> =
+        func.parameters.iter().map(|param| ValueKind::Local(param.name.clone())).collect();
+    for block in func.cfg.blocks() {
+        defined.extend(block.instructions.iter().filter_map(|inst| inst.result.as_ref().map(|r| r.kind.clone())));
+    }
+    func.cfg
+        .blocks()
+        .flat_map(|block| &block.instructions)
+        .filter_map(|inst| match (&inst.kind, &inst.result) {
+            (InstructionKind::Store { dest, .. }, None)
+                if matches!(dest.kind, ValueKind::Temporary(_)) && !defined.contains(&dest.kind) =>
+            {
+                Some(dest.kind.clone())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the register class of the result of `kind`.
+///
+/// Comparisons carry the type of their operands, but always produce a boolean.
+fn result_class(kind: &InstructionKind, result: &Value) -> RegisterClass {
+    match kind {
+        InstructionKind::Binary {
+            op:
+                IrBinaryOp::Equal
+                | IrBinaryOp::NotEqual
+                | IrBinaryOp::Less
+                | IrBinaryOp::LessEqual
+                | IrBinaryOp::Greater
+                | IrBinaryOp::GreaterEqual,
+            ..
+        } => RegisterClass::General,
+        _ => RegisterClass::of(&result.ty),
+    }
+}
+
+fn for_each_operand(kind: &InstructionKind, mut f: impl FnMut(&Value)) {
+    match kind {
+        InstructionKind::Binary { left, right, .. } => {
+            f(left);
+            f(right);
+        }
+        InstructionKind::Unary { operand, .. } => f(operand),
+        InstructionKind::Load { src, .. } => f(src),
+        InstructionKind::Store { value, dest } => {
+            f(value);
+            f(dest);
+        }
+        InstructionKind::Call { func, args, .. } => {
+            f(func);
+            args.iter().for_each(f);
+        }
+        InstructionKind::GetElementPtr { base, index, .. } => {
+            f(base);
+            f(index);
+        }
+        InstructionKind::Cast { value, .. } => f(value),
+        InstructionKind::Vector { operands, .. } => operands.iter().for_each(f),
+        InstructionKind::Phi { incoming, .. } => incoming.iter().for_each(|(value, _)| f(value)),
+        InstructionKind::Alloca { .. } => {}
+    }
+}
+
+fn for_each_terminator_operand(kind: &TerminatorKind, mut f: impl FnMut(&Value)) {
+    match kind {
+        TerminatorKind::Return { value, .. } | TerminatorKind::Switch { value, .. } => f(value),
+        TerminatorKind::ConditionalBranch { condition, .. } => f(condition),
+        TerminatorKind::IndirectBranch { address, .. } => f(address),
+        TerminatorKind::Branch { .. } | TerminatorKind::Unreachable => {}
+    }
+}
@@ -0,0 +1,206 @@
+//! # Register Allocation
+//!
+//! Assigns physical registers to the SSA values of a function with the
+//! linear-scan algorithm of Poletto and Sarkar.
+//!
+//! Intervals computed by [`LiveIntervals`] are visited by increasing start
+//! position. An interval takes a free register of its class; when none is
+//! left, the active interval that ends last is spilled, and its register
+//! handed over, if it outlives the current one. Spilled values live in the
+//! stack slots of the [`StackFrame`](super::frame::StackFrame).
+//!
+//! ## Register pools
+//!
+//! The instruction selector keeps `RAX`, `RCX`, `RDX`, `R11`, `XMM0` and
+//! `XMM1` as scratch registers and loads call arguments directly into the
+//! parameter registers, so none of them is allocatable.
+//!
+//! | ABI      | Caller-saved       | Callee-saved                       |
+//! |----------|--------------------|------------------------------------|
+//! | System V | `R10`, `XMM8-15`   | `RBX`, `R12-R15`                   |
+//! | Windows  | `R10`, `XMM4-5`    | `RBX`, `RSI`, `RDI`, `R12-R15`, `XMM6-15` |
+//!
+//! Caller-saved registers (per [`Abi::caller_saved_gp_registers`]) are
+//! preferred since they cost nothing to use, but intervals that span a call
+//! only receive callee-saved registers, which the prologue preserves.
+
+pub mod interval;
+pub mod parallel_copy;
+
+pub use interval::{LiveInterval, LiveIntervals, RegisterClass};
+
+use super::frame::ValueLocation;
+use crate::asm::{Abi, GPRegister64, XMMRegister};
+use crate::ir::{Function, Value, ValueKind};
+use std::collections::{HashMap, HashSet};
+
+/// General purpose registers reserved by the instruction selector.
+const RESERVED_GP: [GPRegister64; 6] =
+    [GPRegister64::Rax, GPRegister64::Rcx, GPRegister64::Rdx, GPRegister64::R11, GPRegister64::Rbp, GPRegister64::Rsp];
+
+/// XMM registers reserved by the instruction selector.
+const RESERVED_XMM: [XMMRegister; 2] = [XMMRegister::Xmm0, XMMRegister::Xmm1];
+
+/// Allocatable registers of one class, in order of preference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterPool<R> {
+    /// Registers that may be clobbered by calls.
+    pub caller_saved: Vec<R>,
+    /// Registers preserved across calls.
+    pub callee_saved: Vec<R>,
+}
+
+impl<R: Copy + Eq> RegisterPool<R> {
+    fn candidates(&self, crosses_call: bool) -> impl Iterator<Item = R> + '_ {
+        let caller_saved = if crosses_call { &[][..] } else { &self.caller_saved[..] };
+        caller_saved.iter().chain(&self.callee_saved).copied()
+    }
+
+    fn is_callee_saved(&self, reg: R) -> bool {
+        self.callee_saved.contains(&reg)
+    }
+}
+
+/// Returns the general purpose registers available to the allocator under `abi`.
+#[must_use]
+pub fn gp_register_pool(abi: &Abi) -> RegisterPool<GPRegister64> {
+    let available = |reg: &&GPRegister64| !RESERVED_GP.contains(reg) && !abi.int_param_registers().contains(reg);
+    RegisterPool {
+        caller_saved: abi.caller_saved_gp_registers().iter().filter(available).copied().collect(),
+        callee_saved: abi.callee_saved_gp_registers().iter().filter(available).copied().collect(),
+    }
+}
+
+/// Returns the XMM registers available to the allocator under `abi`.
+#[must_use]
+pub fn xmm_register_pool(abi: &Abi) -> RegisterPool<XMMRegister> {
+    let available = |reg: &&XMMRegister| !RESERVED_XMM.contains(reg) && !abi.float_param_registers().contains(reg);
+    RegisterPool {
+        caller_saved: abi.caller_saved_xmm_registers().iter().filter(available).copied().collect(),
+        callee_saved: abi.callee_saved_xmm_registers().iter().filter(available).copied().collect(),
+    }
+}
+
+/// Result of register allocation for a single function.
+#[derive(Debug, Clone, Default)]
+pub struct RegisterAllocation {
+    registers: HashMap<ValueKind, ValueLocation>,
+    spilled: HashSet<ValueKind>,
+    used_gp: Vec<GPRegister64>,
+    used_xmm: Vec<XMMRegister>,
+}
+
+impl RegisterAllocation {
+    /// Allocates registers for every value of `func` under `abi`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let allocation = RegisterAllocation::allocate(&function, &Abi::SYSTEM_V_LINUX);
+    /// let frame = StackFrame::with_allocation(&function, &Abi::SYSTEM_V_LINUX, &allocation);
+    /// ```
+    #[must_use]
+    pub fn allocate(func: &Function, abi: &Abi) -> Self {
+        let intervals = LiveIntervals::compute(func);
+        let mut allocation = Self::default();
+        let (gp, xmm): (Vec<&LiveInterval>, Vec<&LiveInterval>) =
+            intervals.intervals().iter().partition(|interval| interval.class == RegisterClass::General);
+
+        for (value, reg) in linear_scan(&gp, &intervals, &gp_register_pool(abi), &mut allocation.spilled) {
+            if !allocation.used_gp.contains(&reg) {
+                allocation.used_gp.push(reg);
+            }
+            allocation.registers.insert(value, ValueLocation::Gp(reg));
+        }
+        for (value, reg) in linear_scan(&xmm, &intervals, &xmm_register_pool(abi), &mut allocation.spilled) {
+            if !allocation.used_xmm.contains(&reg) {
+                allocation.used_xmm.push(reg);
+            }
+            allocation.registers.insert(value, ValueLocation::Xmm(reg));
+        }
+        allocation
+    }
+
+    /// Returns the register assigned to `value`, or `None` if the value was
+    /// spilled or is not allocatable.
+    #[must_use]
+    pub fn location(&self, value: &Value) -> Option<ValueLocation> {
+        self.registers.get(&value.kind).copied()
+    }
+
+    /// Returns `true` if `value` was spilled to the stack.
+    #[must_use]
+    pub fn is_spilled(&self, value: &Value) -> bool {
+        self.spilled.contains(&value.kind)
+    }
+
+    /// Returns the number of spilled values.
+    #[must_use]
+    pub fn spill_count(&self) -> usize {
+        self.spilled.len()
+    }
+
+    /// Returns the general purpose registers assigned to at least one value.
+    #[must_use]
+    pub fn used_gp_registers(&self) -> &[GPRegister64] {
+        &self.used_gp
+    }
+
+    /// Returns the XMM registers assigned to at least one value.
+    #[must_use]
+    pub fn used_xmm_registers(&self) -> &[XMMRegister] {
+        &self.used_xmm
+    }
+}
+
+/// Active interval holding a register.
+struct Active<R> {
+    value: ValueKind,
+    end: usize,
+    reg: R,
+}
+
+/// Runs linear scan over `class_intervals`, returning the value/register
+/// assignments and recording spilled values in `spilled`.
+fn linear_scan<R: Copy + Eq>(
+    class_intervals: &[&LiveInterval], intervals: &LiveIntervals, pool: &RegisterPool<R>,
+    spilled: &mut HashSet<ValueKind>,
+) -> Vec<(ValueKind, R)> {
+    let mut sorted = class_intervals.to_vec();
+    sorted.sort_by_key(|interval| interval.start);
+
+    let mut assigned: Vec<(ValueKind, R)> = Vec::with_capacity(sorted.len());
+    let mut active: Vec<Active<R>> = Vec::new();
+    for interval in sorted {
+        active.retain(|a| a.end > interval.start);
+
+        let crosses_call = intervals.crosses_call(interval);
+        let free = pool.candidates(crosses_call).find(|reg| active.iter().all(|a| a.reg != *reg));
+        if let Some(reg) = free {
+            active.push(Active { value: interval.value.clone(), end: interval.end, reg });
+            assigned.push((interval.value.clone(), reg));
+            continue;
+        }
+
+        let victim = active
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| !crosses_call || pool.is_callee_saved(a.reg))
+            .max_by_key(|(_, a)| a.end)
+            .map(|(i, _)| i);
+        match victim {
+            Some(i) if active[i].end > interval.end => {
+                let reg = active[i].reg;
+                let evicted =
+                    std::mem::replace(&mut active[i], Active { value: interval.value.clone(), end: interval.end, reg });
+                assigned.retain(|(value, _)| *value != evicted.value);
+                spilled.insert(evicted.value);
+                assigned.push((interval.value.clone(), reg));
+            }
+            _ => {
+                spilled.insert(interval.value.clone());
+            }
+        }
+    }
+    assigned
+}
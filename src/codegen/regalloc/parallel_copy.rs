@@ -0,0 +1,53 @@
+//! # Parallel Copies
+//!
+//! Phi nodes of a block read their incoming values simultaneously: on the edge
+//! `pred -> succ` all copies `phi_i <- v_i` form a *parallel copy*. Emitting
+//! them one after the other is wrong when a destination is also the source
+//! of another copy, as in the swap `a <- b, b <- a`.
+//!
+//! [`sequentialize`] orders the copies so that every location is read before
+//! it is overwritten, and breaks the remaining cycles through a temporary
+//! location.
+
+/// Turns the parallel copy `copies` (pairs of `(dest, src)`) into an
+/// equivalent sequence of moves.
+///
+/// Copies whose source and destination coincide are dropped. `temp` must not
+/// appear in `copies` and destinations must be distinct.
+///
+/// # Examples
+///
+/// ```
+/// use jsavrs::codegen::regalloc::parallel_copy::sequentialize;
+///
+/// // Swap a and b through t.
+/// let moves = sequentialize(&[('a', 'b'), ('b', 'a')], 't');
+/// assert_eq!(moves, vec![('t', 'b'), ('b', 'a'), ('a', 't')]);
+/// ```
+#[must_use]
+pub fn sequentialize<L: Copy + Eq>(copies: &[(L, L)], temp: L) -> Vec<(L, L)> {
+    let mut pending: Vec<(L, L)> = copies.iter().copied().filter(|(dest, src)| dest != src).collect();
+    let mut moves = Vec::with_capacity(pending.len() + 1);
+
+    while !pending.is_empty() {
+        let ready = pending.iter().position(|(dest, _)| !pending.iter().any(|(_, src)| src == dest));
+        if let Some(i) = ready {
+            moves.push(pending.remove(i));
+            continue;
+        }
+
+        // Every destination is still read by another copy, so the remaining
+        // copies contain a cycle: save one of its locations in `temp` and
+        // redirect its readers there.
+        let Some(&(_, saved)) = pending.iter().find(|(_, src)| pending.iter().any(|(dest, _)| dest == src)) else {
+            break;
+        };
+        moves.push((temp, saved));
+        for copy in &mut pending {
+            if copy.1 == saved {
+                copy.1 = temp;
+            }
+        }
+    }
+    moves
+}
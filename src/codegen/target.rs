@@ -0,0 +1,56 @@
+//! # Target Resolution
+//!
+//! Maps the [`TargetTriple`] of an IR module to the calling convention used by
+//! the `x86_64` backend.
+
+use crate::asm::Abi;
+use crate::error::compile_error::CompileError;
+use crate::error::error_code::ErrorCode;
+use crate::ir::TargetTriple;
+use std::sync::Arc;
+
+/// Resolves the [`Abi`] used to generate code for `target_triple`.
+///
+/// # Arguments
+///
+/// * `target_triple` - The target of the module being compiled.
+///
+/// # Returns
+///
+/// The System V ABI for Linux and macOS targets, the Microsoft x64 ABI for
+/// Windows targets.
+///
+/// # Errors
+///
+/// Returns a [`CompileError::AsmGeneratorError`] with code
+/// [`ErrorCode::E4004`] for targets that are not `x86_64`.
+///
+/// # Examples
+///
+/// ```
+/// use jsavrs::asm::Abi;
+/// use jsavrs::codegen::target::resolve_abi;
+/// use jsavrs::ir::TargetTriple;
+///
+/// assert_eq!(resolve_abi(TargetTriple::X86_64PcWindowsGnu).unwrap(), Abi::WINDOWS);
+/// assert!(resolve_abi(TargetTriple::Wasm32UnknownEmscripten).is_err());
+/// ```
+pub fn resolve_abi(target_triple: TargetTriple) -> Result<Abi, CompileError> {
+    match target_triple {
+        TargetTriple::X86_64UnknownLinuxGnu => Ok(Abi::SYSTEM_V_LINUX),
+        TargetTriple::X86_64PcWindowsGnu => Ok(Abi::WINDOWS),
+        TargetTriple::X86_64AppleDarwin => Ok(Abi::SYSTEM_V_MACOS),
+        TargetTriple::AArch64UnknownLinuxGnu
+        | TargetTriple::AArch64AppleDarwin
+        | TargetTriple::AArch64PcWindowsGnu
+        | TargetTriple::I686PcWindowsGnu
+        | TargetTriple::I686UnknownLinuxGnu
+        | TargetTriple::Wasm32UnknownEmscripten => Err(CompileError::AsmGeneratorError {
+            code: Some(ErrorCode::E4004),
+            message: Arc::from(format!(
+                "unsupported target triple '{}' for assembly generation; only x86_64 targets are supported",
+                target_triple.as_str()
+            )),
+        }),
+    }
+}
@@ -0,0 +1,295 @@
+//! # Native Toolchain Driver
+//!
+//! Turns an [`AssemblyFile`] into an ELF64 object file or a Linux executable.
+//! Objects are produced by the built-in encoder ([`ObjectFile`]) or by an
+//! external `nasm`; executables are linked with a locally installed `cc` or
+//! `ld`.
+//!
+//! When NASM is used, the assembly text is written to a temporary `.asm` file
+//! that is removed once the tools have run. Failures are reported as
+//! [`CompileError`] values:
+//!
+//! | Code | Cause |
+//! |------|-------|
+//! | [`ErrorCode::E4001`] | Instruction not supported by the built-in encoder |
+//! | [`ErrorCode::E5004`] | Object or temporary file could not be written |
+//! | [`ErrorCode::E5006`] | Assembler or linker is not installed |
+//! | [`ErrorCode::E5007`] | Assembler or linker exited with an error |
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use jsavrs::driver::Toolchain;
+//! use std::path::Path;
+//!
+//! let toolchain = Toolchain::default();
+//! toolchain.build_executable(&assembly_file, Path::new("prog"))?;
+//! ```
+
+use crate::asm::{Abi, AssemblyFile, GPRegister64, Immediate, Instruction, ObjectFile, Operand, Platform};
+use crate::error::compile_error::CompileError;
+use crate::error::error_code::ErrorCode;
+use std::ffi::OsStr;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// NASM prelude prepended to the generated assembly.
+///
+/// The assembly printer uses MASM-style size specifiers (`QWORD PTR [...]`),
+/// which NASM accepts once its `masm` macro package is loaded.
+const NASM_PRELUDE: &str = "%use masm\n";
+
+/// Startup code linked in when the program is linked with `ld` directly and
+/// assembled with NASM; see [`start_stub`] for the built-in equivalent.
+///
+/// Calls `main` and passes its return value to the `exit` system call.
+const START_STUB: &str = "\
+section .text
+    global _start
+    extern main
+_start:
+    xor rbp, rbp
+    call main
+    mov rdi, rax
+    mov rax, 60
+    syscall
+";
+
+/// Assembler used to produce object files.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Assembler {
+    /// The built-in `x86_64` encoder and ELF64 writer.
+    #[default]
+    Builtin,
+    /// An external NASM executable, given by path or name.
+    Nasm(PathBuf),
+}
+
+/// Linker used to produce executables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LinkerFlavor {
+    /// A C compiler driver (`cc`, `gcc`, `clang`), which links the C runtime
+    /// that calls `main`.
+    #[default]
+    Cc,
+    /// The system linker, used with a built-in `_start` stub.
+    Ld,
+}
+
+/// Tools used to assemble and link generated code.
+///
+/// The default toolchain uses the built-in assembler and runs `cc` from the
+/// `PATH`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toolchain {
+    /// How object files are produced.
+    pub assembler: Assembler,
+    /// Path or name of the linker executable.
+    pub linker: PathBuf,
+    /// How the linker is invoked.
+    pub linker_flavor: LinkerFlavor,
+}
+
+impl Default for Toolchain {
+    fn default() -> Self {
+        Self::new(Assembler::Builtin, "cc", LinkerFlavor::Cc)
+    }
+}
+
+/// Temporary file removed when dropped.
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Reserves a uniquely named path in the system temporary directory.
+    fn new(extension: &str) -> Self {
+        Self { path: std::env::temp_dir().join(format!("jsavrs-{}.{extension}", Uuid::new_v4())) }
+    }
+
+    /// Creates the file with `contents`.
+    fn with_contents(extension: &str, contents: &str) -> Result<Self, CompileError> {
+        let file = Self::new(extension);
+        fs::write(&file.path, contents).map_err(|e| CompileError::SystemError {
+            code: Some(ErrorCode::E5004),
+            message: Arc::from(format!("cannot write temporary file {}: {e}", file.path.display())),
+            help: None,
+        })?;
+        Ok(file)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl Toolchain {
+    /// Creates a toolchain from an assembler and an explicit linker path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jsavrs::driver::{Assembler, LinkerFlavor, Toolchain};
+    ///
+    /// let toolchain = Toolchain::new(Assembler::Nasm("/usr/local/bin/nasm".into()), "ld", LinkerFlavor::Ld);
+    /// assert_eq!(toolchain.linker_flavor, LinkerFlavor::Ld);
+    /// ```
+    #[must_use]
+    pub fn new(assembler: Assembler, linker: impl Into<PathBuf>, linker_flavor: LinkerFlavor) -> Self {
+        Self { assembler, linker: linker.into(), linker_flavor }
+    }
+
+    /// Replaces the assembler, keeping the linker.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jsavrs::driver::{Assembler, Toolchain};
+    ///
+    /// let toolchain = Toolchain::default().with_assembler(Assembler::Nasm("nasm".into()));
+    /// assert_eq!(toolchain.assembler, Assembler::Nasm("nasm".into()));
+    /// ```
+    #[must_use]
+    pub fn with_assembler(mut self, assembler: Assembler) -> Self {
+        self.assembler = assembler;
+        self
+    }
+
+    /// Assembles `assembly` into the ELF64 object file `object`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorCode::E4004`] error for assembly not targeting Linux,
+    /// and the errors listed in the [module documentation](self) when the
+    /// assembler fails or the object cannot be written.
+    pub fn assemble(&self, assembly: &AssemblyFile, object: &Path) -> Result<(), CompileError> {
+        if assembly.abi().platform != Platform::Linux {
+            return Err(CompileError::AsmGeneratorError {
+                code: Some(ErrorCode::E4004),
+                message: Arc::from(format!(
+                    "cannot produce an ELF64 object for {}; only Linux targets are supported",
+                    assembly.abi()
+                )),
+            });
+        }
+        match &self.assembler {
+            Assembler::Builtin => write_object(assembly, object),
+            Assembler::Nasm(nasm) => {
+                let source = TempFile::with_contents("asm", &format!("{NASM_PRELUDE}{assembly}"))?;
+                run_nasm(nasm, &source.path, object)
+            }
+        }
+    }
+
+    /// Links the object files `objects` into the executable `executable`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors listed in the [module documentation](self) when the
+    /// linker (or, for [`LinkerFlavor::Ld`], the assembler building the
+    /// startup code) cannot be run or fails.
+    pub fn link(&self, objects: &[PathBuf], executable: &Path) -> Result<(), CompileError> {
+        let mut args: Vec<&OsStr> = vec![OsStr::new("-o"), executable.as_os_str()];
+        match self.linker_flavor {
+            LinkerFlavor::Cc => {
+                args.push(OsStr::new("-no-pie"));
+                args.extend(objects.iter().map(OsStr::new));
+                run_tool(&self.linker, &args)
+            }
+            LinkerFlavor::Ld => {
+                let start_object = TempFile::new("o");
+                match &self.assembler {
+                    Assembler::Builtin => write_object(&start_stub(), &start_object.path)?,
+                    Assembler::Nasm(nasm) => {
+                        let start_source = TempFile::with_contents("asm", START_STUB)?;
+                        run_nasm(nasm, &start_source.path, &start_object.path)?;
+                    }
+                }
+                args.push(start_object.path.as_os_str());
+                args.extend(objects.iter().map(OsStr::new));
+                run_tool(&self.linker, &args)
+            }
+        }
+    }
+
+    /// Assembles and links `assembly` into the executable `executable`.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error produced by [`Toolchain::assemble`] or
+    /// [`Toolchain::link`].
+    pub fn build_executable(&self, assembly: &AssemblyFile, executable: &Path) -> Result<(), CompileError> {
+        let object = TempFile::new("o");
+        self.assemble(assembly, &object.path)?;
+        self.link(std::slice::from_ref(&object.path), executable)
+    }
+}
+
+/// Assembles `assembly` with the built-in encoder and writes the ELF64 object
+/// to `object`.
+fn write_object(assembly: &AssemblyFile, object: &Path) -> Result<(), CompileError> {
+    let bytes = ObjectFile::from_assembly(assembly)?.to_elf_bytes();
+    fs::write(object, bytes).map_err(|e| CompileError::SystemError {
+        code: Some(ErrorCode::E5004),
+        message: Arc::from(format!("cannot write object file {}: {e}", object.display())),
+        help: None,
+    })
+}
+
+/// Builds the startup code of [`START_STUB`] for the built-in assembler.
+///
+/// `main` stays undefined in the resulting object and is resolved by the
+/// linker.
+fn start_stub() -> AssemblyFile {
+    let mut stub = AssemblyFile::new(Abi::SYSTEM_V_LINUX);
+    stub.text_sec_add_global_label("_start");
+    stub.text_sec_add_label("_start");
+    for instr in [
+        Instruction::Xor { dest: Operand::reg64(GPRegister64::Rbp), src: Operand::reg64(GPRegister64::Rbp) },
+        Instruction::Call { target: Operand::label("main") },
+        Instruction::Mov { dest: Operand::reg64(GPRegister64::Rdi), src: Operand::reg64(GPRegister64::Rax) },
+        Instruction::Mov { dest: Operand::reg64(GPRegister64::Rax), src: Operand::Immediate(Immediate::Imm32(60)) },
+        Instruction::Syscall,
+    ] {
+        stub.text_sec_add_instruction(instr);
+    }
+    stub
+}
+
+/// Runs NASM to assemble `source` into the ELF64 object `object`.
+fn run_nasm(nasm: &Path, source: &Path, object: &Path) -> Result<(), CompileError> {
+    let args = [OsStr::new("-f"), OsStr::new("elf64"), OsStr::new("-o"), object.as_os_str(), source.as_os_str()];
+    run_tool(nasm, &args)
+}
+
+/// Runs `program` with `args`, mapping spawn failures and non-zero exit codes
+/// to [`CompileError::SystemError`].
+fn run_tool(program: &Path, args: &[&OsStr]) -> Result<(), CompileError> {
+    let output = Command::new(program).args(args).output().map_err(|e| {
+        let (code, help) = if e.kind() == ErrorKind::NotFound {
+            (ErrorCode::E5006, Some(format!("install `{}` or make sure it is on the PATH", program.display())))
+        } else {
+            (ErrorCode::E5007, None)
+        };
+        CompileError::SystemError {
+            code: Some(code),
+            message: Arc::from(format!("cannot run `{}`: {e}", program.display())),
+            help,
+        }
+    })?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(CompileError::SystemError {
+        code: Some(ErrorCode::E5007),
+        message: Arc::from(format!("`{}` failed ({}): {}", program.display(), output.status, stderr.trim())),
+        help: None,
+    })
+}
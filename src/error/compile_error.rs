@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 // src/error/compile_error.rs
-use crate::error::error_code::ErrorCode;
+use crate::error::error_code::{CompilerPhase, ErrorCode, Severity};
 use crate::location::source_span::SourceSpan;
 use thiserror::Error;
 
@@ -12,7 +12,9 @@ use thiserror::Error;
 /// - Syntax parsing errors
 /// - Type checking errors
 /// - Intermediate representation generation errors
+/// - Runtime errors raised while interpreting the IR
 /// - Assembly generation errors
+/// - System errors raised by external tools
 /// - General I/O errors
 ///
 /// Each variant carries context-specific information about the error's nature and location.
@@ -71,6 +73,19 @@ pub enum CompileError {
     )]
     IrGeneratorError { code: Option<ErrorCode>, message: Arc<str>, span: SourceSpan, help: Option<String> },
 
+    /// Error raised while interpreting the IR of a program.
+    ///
+    /// Contains:
+    /// - `code`: Optional standardized error code (E3014 or E3015)
+    /// - `message`: Description of the runtime failure
+    /// - `span`: Location of the instruction that failed
+    /// - `help`: Optional guidance for fixing the error
+    #[error("{}Runtime error: {message} at {span}{}",
+        .code.map_or(String::new(), |c| format!("[{}] ", c.code())),
+        .help.as_ref().map_or(String::new(), |h| format!("\nhelp: {h}"))
+    )]
+    RuntimeError { code: Option<ErrorCode>, message: Arc<str>, span: SourceSpan, help: Option<String> },
+
     /// Error during assembly code generation.
     ///
     /// Contains:
@@ -81,6 +96,18 @@ pub enum CompileError {
     )]
     AsmGeneratorError { code: Option<ErrorCode>, message: Arc<str> },
 
+    /// Error raised by the system or by an external tool (assembler, linker).
+    ///
+    /// Contains:
+    /// - `code`: Optional standardized error code (E5xxx range)
+    /// - `message`: Description of the failure, including the tool output
+    /// - `help`: Optional guidance for fixing the error
+    #[error("{}System error: {message}{}",
+        .code.map_or(String::new(), |c| format!("[{}] ", c.code())),
+        .help.as_ref().map_or(String::new(), |h| format!("\nhelp: {h}"))
+    )]
+    SystemError { code: Option<ErrorCode>, message: Arc<str>, help: Option<String> },
+
     /// I/O operation failure during compilation (e.g., file access issues).
     ///
     /// Wraps the standard [`std::io::Error`] for seamless error propagation.
@@ -116,11 +143,65 @@ impl CompileError {
             | Self::SyntaxError { code, .. }
             | Self::TypeError { code, .. }
             | Self::IrGeneratorError { code, .. }
-            | Self::AsmGeneratorError { code, .. } => code.as_ref(),
+            | Self::RuntimeError { code, .. }
+            | Self::AsmGeneratorError { code, .. }
+            | Self::SystemError { code, .. } => code.as_ref(),
             Self::IoError(_) => None,
         }
     }
 
+    /// Returns the severity of the error, taken from its code.
+    ///
+    /// Errors without a code are [`Severity::Error`].
+    ///
+    /// # Examples
+    /// ```
+    /// use jsavrs::error::compile_error::CompileError;
+    /// use jsavrs::error::error_code::{ErrorCode, Severity};
+    /// use jsavrs::location::source_span::SourceSpan;
+    /// use std::sync::Arc;
+    /// let err = CompileError::SyntaxError {
+    ///     code: Some(ErrorCode::E1013),
+    ///     message: Arc::from("Missing semicolon"),
+    ///     span: SourceSpan::default(),
+    ///     help: None,
+    /// };
+    /// assert_eq!(err.severity(), Severity::Warning);
+    /// ```
+    #[must_use]
+    pub const fn severity(&self) -> Severity {
+        match self.error_code() {
+            Some(code) => code.severity(),
+            None => Severity::Error,
+        }
+    }
+
+    /// Returns the compiler phase that reported the error, taken from its
+    /// code or, for errors without a code, from the variant.
+    ///
+    /// # Examples
+    /// ```
+    /// use jsavrs::error::compile_error::CompileError;
+    /// use jsavrs::error::error_code::CompilerPhase;
+    /// let err = CompileError::AsmGeneratorError { code: None, message: "unsupported".into() };
+    /// assert_eq!(err.phase(), CompilerPhase::CodeGeneration);
+    /// ```
+    #[must_use]
+    pub const fn phase(&self) -> CompilerPhase {
+        if let Some(code) = self.error_code() {
+            return code.phase();
+        }
+        match self {
+            Self::LexerError { .. } => CompilerPhase::Lexer,
+            Self::SyntaxError { .. } => CompilerPhase::Parser,
+            Self::TypeError { .. } => CompilerPhase::Semantic,
+            Self::IrGeneratorError { .. } => CompilerPhase::IrGeneration,
+            Self::RuntimeError { .. } => CompilerPhase::Runtime,
+            Self::AsmGeneratorError { .. } => CompilerPhase::CodeGeneration,
+            Self::SystemError { .. } | Self::IoError(_) => CompilerPhase::System,
+        }
+    }
+
     /// Returns the error message for variants that carry messages.
     ///
     /// Returns:
@@ -147,7 +228,9 @@ impl CompileError {
             | Self::SyntaxError { message, .. }
             | Self::TypeError { message, .. }
             | Self::IrGeneratorError { message, .. }
-            | Self::AsmGeneratorError { message, .. } => Some(message),
+            | Self::RuntimeError { message, .. }
+            | Self::AsmGeneratorError { message, .. }
+            | Self::SystemError { message, .. } => Some(message),
             Self::IoError(_) => None,
         }
     }
@@ -179,7 +262,8 @@ impl CompileError {
             Self::LexerError { span, .. }
             | Self::SyntaxError { span, .. }
             | Self::TypeError { span, .. }
-            | Self::IrGeneratorError { span, .. } => Some(span),
+            | Self::IrGeneratorError { span, .. }
+            | Self::RuntimeError { span, .. } => Some(span),
             _ => None,
         }
     }
@@ -209,7 +293,9 @@ impl CompileError {
             Self::LexerError { help, .. }
             | Self::SyntaxError { help, .. }
             | Self::TypeError { help, .. }
-            | Self::IrGeneratorError { help, .. } => help.as_deref(),
+            | Self::IrGeneratorError { help, .. }
+            | Self::RuntimeError { help, .. }
+            | Self::SystemError { help, .. } => help.as_deref(),
             _ => None,
         }
     }
@@ -241,7 +327,9 @@ impl CompileError {
             | Self::SyntaxError { message, .. }
             | Self::TypeError { message, .. }
             | Self::IrGeneratorError { message, .. }
-            | Self::AsmGeneratorError { message, .. } => *message = new_message,
+            | Self::RuntimeError { message, .. }
+            | Self::AsmGeneratorError { message, .. }
+            | Self::SystemError { message, .. } => *message = new_message,
             Self::IoError(_) => {}
         }
     }
@@ -274,7 +362,8 @@ impl CompileError {
             Self::LexerError { span, .. }
             | Self::SyntaxError { span, .. }
             | Self::TypeError { span, .. }
-            | Self::IrGeneratorError { span, .. } => *span = new_span,
+            | Self::IrGeneratorError { span, .. }
+            | Self::RuntimeError { span, .. } => *span = new_span,
             _ => {}
         }
     }
@@ -305,7 +394,9 @@ impl CompileError {
             Self::LexerError { help, .. }
             | Self::SyntaxError { help, .. }
             | Self::TypeError { help, .. }
-            | Self::IrGeneratorError { help, .. } => *help = new_help,
+            | Self::IrGeneratorError { help, .. }
+            | Self::RuntimeError { help, .. }
+            | Self::SystemError { help, .. } => *help = new_help,
             _ => {}
         }
     }
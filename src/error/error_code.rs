@@ -75,6 +75,10 @@ pub enum CompilerPhase {
     CodeGeneration = 4,
     /// I/O and system operations
     System = 5,
+    /// IR verification between optimization passes
+    Verification = 6,
+    /// Interpretation of the IR with `jsavrs run`
+    Runtime = 7,
 }
 
 impl fmt::Display for CompilerPhase {
@@ -86,6 +90,8 @@ impl fmt::Display for CompilerPhase {
             Self::IrGeneration => write!(f, "ir-gen"),
             Self::CodeGeneration => write!(f, "codegen"),
             Self::System => write!(f, "system"),
+            Self::Verification => write!(f, "verify"),
+            Self::Runtime => write!(f, "runtime"),
         }
     }
 }
@@ -205,7 +211,7 @@ pub enum ErrorCode {
     /// Error E0007: Invalid escape sequence
     ///
     /// The escape sequence is not recognized. Valid escapes include:
-    /// `\n`, `\r`, `\t`, `\\`, `\'`, `\"`, `\0`, `\u{XXXX}`.
+    /// `\n`, `\r`, `\t`, `\\`, `\'`, `\"`, `\0`, `\xNN`, `\u{XXXX}`.
     ///
     /// # Example
     /// ```compile_fail
@@ -257,6 +263,48 @@ pub enum ErrorCode {
     /// Use a larger type or reduce the value.
     E0010,
 
+    /// Error E0011: Invalid hexadecimal escape
+    ///
+    /// A `\x` escape must be followed by exactly two hexadecimal digits
+    /// denoting an ASCII character, at most `\x7F`.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// var s: string = "\xG1"  // G is not a hexadecimal digit
+    /// ```
+    ///
+    /// # Solution
+    /// Write two hex digits, or use `\u{...}` for characters above `\x7F`.
+    E0011,
+
+    /// Error E0012: Invalid Unicode escape
+    ///
+    /// A `\u` escape must be written as `\u{...}` with one to six
+    /// hexadecimal digits denoting a Unicode scalar value.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// var c: char = '\u{D800}'  // surrogate code points are not characters
+    /// ```
+    ///
+    /// # Solution
+    /// Use the code point of a valid character, e.g. `\u{1F600}`.
+    E0012,
+
+    /// Error E0013: Invalid character literal
+    ///
+    /// A character literal must contain exactly one character after its
+    /// escapes are decoded.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// var c: char = 'ab'
+    /// ```
+    ///
+    /// # Solution
+    /// Use a string literal for text longer than one character.
+    E0013,
+
     // =========================================================================
     // PARSING ERRORS (E1001-E1999)
     // =========================================================================
@@ -291,7 +339,7 @@ pub enum ErrorCode {
 
     /// Error E1003: Invalid assignment target
     ///
-    /// Only variables and array elements can be assigned to.
+    /// Only variables, array elements and struct fields can be assigned to.
     ///
     /// # Example
     /// ```compile_fail
@@ -801,6 +849,193 @@ pub enum ErrorCode {
     /// Use a different name or remove the duplicate.
     E2032,
 
+    /// Error E2033: Undefined type
+    ///
+    /// A custom type name does not refer to a declared struct or enum.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// var p: Point = nullptr  // Point never declared
+    /// ```
+    ///
+    /// # Solution
+    /// Declare the type with `struct` or `enum` before using its name as a type.
+    E2033,
+
+    /// Error E2034: Unknown field
+    ///
+    /// The field does not exist on the struct, or the accessed value is not a
+    /// struct.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// struct Point { x: i32, y: i32 }
+    /// var p: Point = Point { x: 1, y: 2 }
+    /// p.z = 3  // Point has no field z
+    /// ```
+    ///
+    /// # Solution
+    /// Use one of the fields listed in the struct declaration.
+    E2034,
+
+    /// Error E2035: Missing field in struct literal
+    ///
+    /// A struct literal must initialize every field of the struct.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// struct Point { x: i32, y: i32 }
+    /// var p: Point = Point { x: 1 }  // y not initialized
+    /// ```
+    ///
+    /// # Solution
+    /// Provide a value for every field.
+    E2035,
+
+    /// Error E2036: Invalid cast
+    ///
+    /// An `as` expression converts between two types that have no conversion
+    /// rule, such as a struct and an integer.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// struct Point { x: i32, y: i32 }
+    /// var p: Point = Point { x: 1, y: 2 }
+    /// var n: i32 = p as i32  // structs cannot be cast
+    /// ```
+    ///
+    /// # Solution
+    /// Cast only between primitive types, or convert the fields one by one.
+    E2036,
+
+    /// Error E2037: Circular import
+    ///
+    /// A file imports itself, directly or through other imported files.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// // a.vn
+    /// import "b.vn"
+    /// // b.vn
+    /// import "a.vn"  // a.vn -> b.vn -> a.vn
+    /// ```
+    ///
+    /// # Solution
+    /// Move the shared functions into a third file imported by both.
+    E2037,
+
+    /// Error E2038: Unresolved import
+    ///
+    /// The imported file cannot be found or read, or the `import` statement
+    /// is not at the top level of the file.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// import "missing.vn"  // no such file next to the importer
+    /// ```
+    ///
+    /// # Solution
+    /// Use a path relative to the importing file and place imports at the
+    /// top level.
+    E2038,
+
+    /// Error E2039: Invalid global variable
+    ///
+    /// A variable or constant declared at the top level of a file is not
+    /// initialized with a constant expression, or its type cannot be stored
+    /// in a data section.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// fun seed(): i32 { return 4 }
+    /// var counter: i32 = seed()  // calls are evaluated at run time
+    /// ```
+    ///
+    /// # Solution
+    /// Initialize globals with literals, other constants and operators, and
+    /// give them a number, `bool` or `char` type, or an array of one. Strings
+    /// and structs cannot be globals; declare them in a function instead.
+    E2039,
+
+    /// Error E2040: Invalid switch case
+    ///
+    /// A switch statement matches on a value that is not an integer, enum,
+    /// `char` or `bool`, or one of its cases is not a constant of that type.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// switch (x) {       // x: i32
+    ///     case y: ...    // y is a variable
+    /// }
+    /// ```
+    ///
+    /// # Solution
+    /// Use literals or constants of the switch value's type as case values.
+    E2040,
+
+    /// Error E2041: Duplicate switch case
+    ///
+    /// Two cases of the same switch statement have the same value, so the
+    /// second one could never run.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// switch (x) {
+    ///     case 1: ...
+    ///     case 1: ...
+    /// }
+    /// ```
+    ///
+    /// # Solution
+    /// Remove the duplicate case or merge its statements into the first one.
+    E2041,
+
+    /// Error E2042: Invalid enum declaration
+    ///
+    /// The underlying type of an enum is not an integer type, or a variant's
+    /// discriminant is not an integer constant, does not fit in the
+    /// underlying type or repeats another variant's.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// enum Level: u8 { Low = 0, High = 300 }  // 300 does not fit in u8
+    /// ```
+    ///
+    /// # Solution
+    /// Use an integer underlying type and distinct integer constants within
+    /// its range.
+    E2042,
+
+    /// Error E2043: Unknown enum variant
+    ///
+    /// The variant named after the enum does not exist.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// enum Color { Red, Green, Blue }
+    /// var c: Color = Color.Purple
+    /// ```
+    ///
+    /// # Solution
+    /// Use one of the variants listed in the enum declaration.
+    E2043,
+
+    /// Error E2044: Invalid return type
+    ///
+    /// A function returns a struct or an array. Aggregates live in the stack
+    /// frame of the function that creates them, which is gone once it returns.
+    ///
+    /// # Example
+    /// ```compile_fail
+    /// struct Point { x: i32, y: i32 }
+    /// fun origin(): Point { return Point { x: 0, y: 0 } }
+    /// ```
+    ///
+    /// # Solution
+    /// Return the fields one at a time, or let the caller pass the struct or
+    /// array and fill it in.
+    E2044,
+
     // =========================================================================
     // IR GENERATION ERRORS (E3001-E3999)
     // =========================================================================
@@ -844,6 +1079,49 @@ pub enum ErrorCode {
     /// Error during Control Flow Graph construction.
     E3008,
 
+    /// Error E3009: Malformed IR text
+    ///
+    /// Textual IR could not be parsed back into a module.
+    E3009,
+
+    /// Error E3010: Use not dominated by its definition
+    ///
+    /// An instruction result is used where its definition does not dominate the use.
+    E3010,
+
+    /// Error E3011: Phi incoming blocks do not match predecessors
+    ///
+    /// A phi node lists blocks that are not predecessors of its block, or misses one.
+    E3011,
+
+    /// Error E3012: IR operand type mismatch
+    ///
+    /// An operand of a binary or cast instruction does not have the type the instruction declares.
+    E3012,
+
+    /// Error E3013: Reachable block without terminator
+    ///
+    /// A block reachable from the function entry ends in `unreachable`.
+    E3013,
+
+    /// Error E3014: Runtime trap
+    ///
+    /// The interpreted program divided by zero, accessed memory out of bounds
+    /// or reached another failure the native program would crash on.
+    E3014,
+
+    /// Error E3015: IR cannot be interpreted
+    ///
+    /// The interpreter met a construct it cannot execute, such as an undefined
+    /// value or an indirect branch.
+    E3015,
+
+    /// Error E3016: Optimization changed program behaviour
+    ///
+    /// The program returned a different result, trapped differently or left
+    /// different values in its globals after an optimization pass.
+    E3016,
+
     // =========================================================================
     // CODE GENERATION ERRORS (E4001-E4999)
     // =========================================================================
@@ -899,6 +1177,16 @@ pub enum ErrorCode {
     ///
     /// Error reading input file.
     E5005,
+
+    /// Error E5006: External tool not found
+    ///
+    /// An assembler or linker required by the driver is not installed.
+    E5006,
+
+    /// Error E5007: External tool failed
+    ///
+    /// An assembler or linker invoked by the driver exited with an error.
+    E5007,
 }
 
 impl ErrorCode {
@@ -913,6 +1201,7 @@ impl ErrorCode {
     /// assert_eq!(ErrorCode::E2023.code(), "E2023");
     /// ```
     #[must_use]
+    #[allow(clippy::too_many_lines)]
     pub const fn code(&self) -> &'static str {
         match self {
             // Lexical errors
@@ -926,6 +1215,9 @@ impl ErrorCode {
             Self::E0008 => "E0008",
             Self::E0009 => "E0009",
             Self::E0010 => "E0010",
+            Self::E0011 => "E0011",
+            Self::E0012 => "E0012",
+            Self::E0013 => "E0013",
 
             // Parser errors
             Self::E1001 => "E1001",
@@ -977,6 +1269,18 @@ impl ErrorCode {
             Self::E2030 => "E2030",
             Self::E2031 => "E2031",
             Self::E2032 => "E2032",
+            Self::E2033 => "E2033",
+            Self::E2034 => "E2034",
+            Self::E2035 => "E2035",
+            Self::E2036 => "E2036",
+            Self::E2037 => "E2037",
+            Self::E2038 => "E2038",
+            Self::E2039 => "E2039",
+            Self::E2040 => "E2040",
+            Self::E2041 => "E2041",
+            Self::E2042 => "E2042",
+            Self::E2043 => "E2043",
+            Self::E2044 => "E2044",
 
             // IR errors
             Self::E3001 => "E3001",
@@ -987,6 +1291,14 @@ impl ErrorCode {
             Self::E3006 => "E3006",
             Self::E3007 => "E3007",
             Self::E3008 => "E3008",
+            Self::E3009 => "E3009",
+            Self::E3010 => "E3010",
+            Self::E3011 => "E3011",
+            Self::E3012 => "E3012",
+            Self::E3013 => "E3013",
+            Self::E3014 => "E3014",
+            Self::E3015 => "E3015",
+            Self::E3016 => "E3016",
 
             // Code gen errors
             Self::E4001 => "E4001",
@@ -1001,6 +1313,8 @@ impl ErrorCode {
             Self::E5003 => "E5003",
             Self::E5004 => "E5004",
             Self::E5005 => "E5005",
+            Self::E5006 => "E5006",
+            Self::E5007 => "E5007",
         }
     }
 
@@ -1015,6 +1329,7 @@ impl ErrorCode {
     /// assert_eq!(ErrorCode::E2023.numeric_code(), 2023);
     /// ```
     #[must_use]
+    #[allow(clippy::too_many_lines)]
     pub const fn numeric_code(&self) -> u16 {
         match self {
             // Lexical errors (0001-0999)
@@ -1028,6 +1343,9 @@ impl ErrorCode {
             Self::E0008 => 8,
             Self::E0009 => 9,
             Self::E0010 => 10,
+            Self::E0011 => 11,
+            Self::E0012 => 12,
+            Self::E0013 => 13,
 
             // Parser errors (1001-1999)
             Self::E1001 => 1001,
@@ -1079,6 +1397,18 @@ impl ErrorCode {
             Self::E2030 => 2030,
             Self::E2031 => 2031,
             Self::E2032 => 2032,
+            Self::E2033 => 2033,
+            Self::E2034 => 2034,
+            Self::E2035 => 2035,
+            Self::E2036 => 2036,
+            Self::E2037 => 2037,
+            Self::E2038 => 2038,
+            Self::E2039 => 2039,
+            Self::E2040 => 2040,
+            Self::E2041 => 2041,
+            Self::E2042 => 2042,
+            Self::E2043 => 2043,
+            Self::E2044 => 2044,
 
             // IR errors (3001-3999)
             Self::E3001 => 3001,
@@ -1089,6 +1419,14 @@ impl ErrorCode {
             Self::E3006 => 3006,
             Self::E3007 => 3007,
             Self::E3008 => 3008,
+            Self::E3009 => 3009,
+            Self::E3010 => 3010,
+            Self::E3011 => 3011,
+            Self::E3012 => 3012,
+            Self::E3013 => 3013,
+            Self::E3014 => 3014,
+            Self::E3015 => 3015,
+            Self::E3016 => 3016,
 
             // Code gen errors (4001-4999)
             Self::E4001 => 4001,
@@ -1103,6 +1441,8 @@ impl ErrorCode {
             Self::E5003 => 5003,
             Self::E5004 => 5004,
             Self::E5005 => 5005,
+            Self::E5006 => 5006,
+            Self::E5007 => 5007,
         }
     }
 
@@ -1137,6 +1477,8 @@ impl ErrorCode {
     /// assert_eq!(ErrorCode::E0001.phase(), CompilerPhase::Lexer);
     /// assert_eq!(ErrorCode::E1001.phase(), CompilerPhase::Parser);
     /// assert_eq!(ErrorCode::E2023.phase(), CompilerPhase::Semantic);
+    /// assert_eq!(ErrorCode::E3012.phase(), CompilerPhase::Verification);
+    /// assert_eq!(ErrorCode::E3014.phase(), CompilerPhase::Runtime);
     /// ```
     #[must_use]
     pub const fn phase(&self) -> CompilerPhase {
@@ -1144,6 +1486,8 @@ impl ErrorCode {
             1..=999 => CompilerPhase::Lexer,
             1001..=1999 => CompilerPhase::Parser,
             2001..=2999 => CompilerPhase::Semantic,
+            3010..=3013 => CompilerPhase::Verification,
+            3014..=3015 => CompilerPhase::Runtime,
             3001..=3999 => CompilerPhase::IrGeneration,
             4001..=4999 => CompilerPhase::CodeGeneration,
             // 5001..=5999 and any unknown codes default to System
@@ -1166,6 +1510,7 @@ impl ErrorCode {
     /// );
     /// ```
     #[must_use]
+    #[allow(clippy::too_many_lines)]
     pub const fn message(&self) -> &'static str {
         match self {
             // Lexical errors
@@ -1179,6 +1524,9 @@ impl ErrorCode {
             Self::E0008 => "unterminated multi-line comment",
             Self::E0009 => "invalid number suffix",
             Self::E0010 => "number literal overflow",
+            Self::E0011 => "invalid hexadecimal escape",
+            Self::E0012 => "invalid unicode escape",
+            Self::E0013 => "invalid character literal",
 
             // Parser errors
             Self::E1001 => "maximum recursion depth exceeded",
@@ -1230,6 +1578,18 @@ impl ErrorCode {
             Self::E2030 => "array index must be integer",
             Self::E2031 => "cannot index non-array type",
             Self::E2032 => "duplicate declaration",
+            Self::E2033 => "undefined type",
+            Self::E2034 => "unknown field",
+            Self::E2035 => "missing field in struct literal",
+            Self::E2036 => "invalid cast",
+            Self::E2037 => "circular import",
+            Self::E2038 => "unresolved import",
+            Self::E2039 => "invalid global variable",
+            Self::E2040 => "invalid switch case",
+            Self::E2041 => "duplicate switch case",
+            Self::E2042 => "invalid enum declaration",
+            Self::E2043 => "unknown enum variant",
+            Self::E2044 => "invalid return type",
 
             // IR errors
             Self::E3001 => "break outside loop in IR",
@@ -1240,6 +1600,14 @@ impl ErrorCode {
             Self::E3006 => "invalid block terminator",
             Self::E3007 => "SSA transformation error",
             Self::E3008 => "CFG construction error",
+            Self::E3009 => "malformed IR text",
+            Self::E3010 => "use not dominated by its definition",
+            Self::E3011 => "phi incoming blocks do not match predecessors",
+            Self::E3012 => "IR operand type mismatch",
+            Self::E3013 => "reachable block without terminator",
+            Self::E3014 => "runtime trap",
+            Self::E3015 => "IR cannot be interpreted",
+            Self::E3016 => "optimization changed program behaviour",
 
             // Code gen errors
             Self::E4001 => "invalid assembly instruction",
@@ -1254,6 +1622,8 @@ impl ErrorCode {
             Self::E5003 => "invalid file extension",
             Self::E5004 => "write error",
             Self::E5005 => "read error",
+            Self::E5006 => "external tool not found",
+            Self::E5007 => "external tool failed",
         }
     }
 
@@ -1268,6 +1638,7 @@ impl ErrorCode {
     /// assert!(explanation.contains("declare"));
     /// ```
     #[must_use]
+    #[allow(clippy::too_many_lines)]
     pub const fn explanation(&self) -> &'static str {
         match self {
             Self::E0001 => {
@@ -1298,7 +1669,8 @@ impl ErrorCode {
             Self::E0007 => {
                 "The escape sequence is not recognized. Valid escape sequences include:\n\
                 \\n (newline), \\r (carriage return), \\t (tab), \\\\ (backslash),\n\
-                \\' (single quote), \\\" (double quote), \\0 (null), \\u{XXXX} (unicode)."
+                \\' (single quote), \\\" (double quote), \\0 (null), \\xNN (ASCII)\n\
+                and \\u{XXXX} (unicode)."
             }
             Self::E0008 => {
                 "Multi-line comments opened with `/*` must be closed with `*/`.\n\
@@ -1312,6 +1684,18 @@ impl ErrorCode {
                 "The numeric value exceeds the range of the target type.\n\
                 Use a larger type or reduce the value."
             }
+            Self::E0011 => {
+                "A `\\x` escape takes exactly two hexadecimal digits and denotes an ASCII character.\n\
+                Example: `\"\\x41\"` for `A`; use `\\u{...}` for characters above `\\x7F`."
+            }
+            Self::E0012 => {
+                "A unicode escape is written `\\u{...}` with one to six hexadecimal digits.\n\
+                The code point must be a Unicode scalar value (not a surrogate, at most 10FFFF)."
+            }
+            Self::E0013 => {
+                "A character literal must hold exactly one character after escapes are decoded.\n\
+                Use a string literal for longer text: `\"ab\"` instead of `'ab'`."
+            }
             Self::E1001 => {
                 "The parser has exceeded its recursion limit due to deeply nested expressions.\n\
                 Simplify the expression or break it into smaller parts."
@@ -1322,8 +1706,8 @@ impl ErrorCode {
                 or custom type identifiers."
             }
             Self::E1003 => {
-                "Only variables and array elements can be assigned to.\n\
-                Examples: `x = 5` or `arr[0] = 1`."
+                "Only variables, array elements and struct fields can be assigned to.\n\
+                Examples: `x = 5`, `arr[0] = 1` or `p.x = 1`."
             }
             Self::E2023 => {
                 "The variable has not been declared in the current scope or any outer scope.\n\
@@ -1341,6 +1725,46 @@ impl ErrorCode {
                 "The number of arguments provided doesn't match the function's parameter count.\n\
                 Check the function definition and provide the correct number of arguments."
             }
+            Self::E3009 => {
+                "Textual IR must follow the format printed by the compiler's IR dump.\n\
+                The message points at the line that could not be parsed."
+            }
+            Self::E3010 => {
+                "Every use of an instruction result must be dominated by the instruction that defines it.\n\
+                A pass moved or removed a definition, or introduced a use on a path that bypasses it."
+            }
+            Self::E3011 => {
+                "A phi node needs exactly one incoming value per predecessor of its block.\n\
+                Update the phi when edges are added to or removed from the control flow graph."
+            }
+            Self::E3012 => {
+                "Binary operands must have the instruction type and cast operands its source type.\n\
+                Insert an explicit cast where the types differ."
+            }
+            Self::E3013 => {
+                "Every block reachable from the function entry must end in a branch, switch or return.\n\
+                Only blocks that control flow can never reach may end in `unreachable`."
+            }
+            Self::E3014 => {
+                "The program failed while it was interpreted, for example by dividing by zero or indexing past the end of an array.\n\
+                The native program would crash or misbehave at the same point."
+            }
+            Self::E3015 => {
+                "The interpreter executes the IR the compiler generates; this module uses a construct it does not support.\n\
+                Report the program that produced it as a compiler bug."
+            }
+            Self::E3016 => {
+                "An optimization pass must preserve the result of `main`, its traps and the final value of every global.\n\
+                Save the IR before the pass and reproduce the change with `jsavrs-opt --passes=<pass>`."
+            }
+            Self::E5006 => {
+                "Producing object files and executables requires `nasm` and a linker (`cc` or `ld`).\n\
+                Install the missing tool or make sure it is on the PATH."
+            }
+            Self::E5007 => {
+                "The assembler or linker reported an error while processing the generated code.\n\
+                The tool output is included in the error message."
+            }
             // Default explanation for other errors
             _ => "See the error message for details.",
         }
@@ -1409,6 +1833,8 @@ mod tests {
         assert_eq!(ErrorCode::E1001.phase(), CompilerPhase::Parser);
         assert_eq!(ErrorCode::E2023.phase(), CompilerPhase::Semantic);
         assert_eq!(ErrorCode::E3001.phase(), CompilerPhase::IrGeneration);
+        assert_eq!(ErrorCode::E3011.phase(), CompilerPhase::Verification);
+        assert_eq!(ErrorCode::E3014.phase(), CompilerPhase::Runtime);
         assert_eq!(ErrorCode::E4001.phase(), CompilerPhase::CodeGeneration);
         assert_eq!(ErrorCode::E5001.phase(), CompilerPhase::System);
     }
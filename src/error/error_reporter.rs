@@ -1,13 +1,30 @@
 use crate::error::compile_error::CompileError;
-use crate::error::error_code::ErrorCode;
+use crate::error::error_code::{CompilerPhase, ErrorCode, Severity};
 use crate::location::line_tracker::LineTracker;
 use crate::location::source_span::SourceSpan;
 use console::style;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
 use std::fmt::Write;
 
+/// Output format of the reported errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorFormat {
+    /// Coloured text showing the source line of every error.
+    #[default]
+    Human,
+    /// One JSON object per error and per line.
+    Json,
+    /// A SARIF 2.1.0 log, as read by GitHub code scanning.
+    Sarif,
+}
+
 /// Enhanced error reporter with source context display
 pub struct ErrorReporter {
     line_tracker: LineTracker,
+    /// Sources of imported files, selected by the file path of a span
+    imported: Vec<LineTracker>,
+    format: ErrorFormat,
 }
 
 fn format_simple_error(error_type: &str, message: impl std::fmt::Display, code: Option<ErrorCode>) -> String {
@@ -23,14 +40,49 @@ fn format_simple_error(error_type: &str, message: impl std::fmt::Display, code:
 impl ErrorReporter {
     #[must_use]
     pub const fn new(line_tracker: LineTracker) -> Self {
-        Self { line_tracker }
+        Self { line_tracker, imported: Vec::new(), format: ErrorFormat::Human }
+    }
+
+    /// Selects the format of [`report_errors`](Self::report_errors)
+    #[must_use]
+    pub const fn with_format(mut self, format: ErrorFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Adds the source of an imported file, so that errors located in it
+    /// show its lines
+    pub fn add_source(&mut self, line_tracker: LineTracker) {
+        self.imported.push(line_tracker);
+    }
+
+    /// Returns the tracker of the file `span` points into, falling back to
+    /// the main source file
+    fn line_tracker_for(&self, span: &SourceSpan) -> &LineTracker {
+        self.imported.iter().find(|tracker| tracker.file_path() == &*span.file_path).unwrap_or(&self.line_tracker)
     }
 
-    /// Returns a formatted string containing all compile errors with source context
+    /// Returns a formatted string containing all compile errors, in the
+    /// selected [`ErrorFormat`]
+    ///
+    /// The JSON and SARIF output does not end with a newline, like the last
+    /// line of the text output.
     #[must_use]
     pub fn report_errors(&self, errors: Vec<CompileError>) -> String {
+        match self.format {
+            ErrorFormat::Human => self.report_human(errors),
+            ErrorFormat::Json => {
+                errors.iter().map(|error| error_to_json(error).to_string()).collect::<Vec<_>>().join("\n")
+            }
+            ErrorFormat::Sarif => self.errors_to_sarif(&errors).to_string(),
+        }
+    }
+
+    /// Formats the errors as text with source context
+    fn report_human(&self, errors: Vec<CompileError>) -> String {
         let mut output = String::with_capacity(errors.len() * 500);
         for error in errors {
+            let phase = error.phase();
             let formatted = match error {
                 CompileError::LexerError { message, span, help, code } => {
                     self.format_error("LEX", &message, &span, help.as_deref(), code)
@@ -42,9 +94,24 @@ impl ErrorReporter {
                     self.format_error("TYPE", &message, &span, help.as_deref(), code)
                 }
                 CompileError::IrGeneratorError { message, span, help, code } => {
-                    self.format_error("IR GEN", &message, &span, help.as_deref(), code)
+                    let category = match phase {
+                        CompilerPhase::Verification => "VERIFY",
+                        CompilerPhase::Runtime => "RUNTIME",
+                        _ => "IR GEN",
+                    };
+                    self.format_error(category, &message, &span, help.as_deref(), code)
+                }
+                CompileError::RuntimeError { message, span, help, code } => {
+                    self.format_error("RUNTIME", &message, &span, help.as_deref(), code)
                 }
                 CompileError::AsmGeneratorError { message, code } => format_simple_error("ASM GEN", &message, code),
+                CompileError::SystemError { message, code, help } => {
+                    let mut formatted = format_simple_error("SYSTEM", &message, code);
+                    if let Some(help) = help {
+                        let _ = writeln!(&mut formatted, "{} {}", style("help:").blue().bold(), style(help).green());
+                    }
+                    formatted
+                }
                 CompileError::IoError(e) => format_simple_error("I/O", &e, None),
             };
             output.push_str(&formatted);
@@ -52,6 +119,90 @@ impl ErrorReporter {
         output
     }
 
+    /// Builds a SARIF log with a single run, whose rules are the error codes
+    /// that occur in `errors`
+    fn errors_to_sarif(&self, errors: &[CompileError]) -> Value {
+        let codes: BTreeMap<&str, &ErrorCode> =
+            errors.iter().filter_map(CompileError::error_code).map(|code| (code.code(), code)).collect();
+        let rule_index = |code: &ErrorCode| codes.keys().position(|id| *id == code.code());
+        let rules: Vec<Value> = codes
+            .values()
+            .map(|code| {
+                let mut rule = json!({
+                    "id": code.code(),
+                    "shortDescription": { "text": code.message() },
+                    "fullDescription": { "text": code.explanation() },
+                    "defaultConfiguration": { "level": sarif_level(code.severity()) },
+                });
+                if !code.suggestions().is_empty() {
+                    rule["help"] = json!({ "text": code.suggestions().join("\n") });
+                }
+                rule
+            })
+            .collect();
+        let results: Vec<Value> = errors
+            .iter()
+            .map(|error| {
+                let mut result = json!({
+                    "level": sarif_level(error.severity()),
+                    "message": { "text": message_of(error) },
+                    "properties": { "phase": error.phase().to_string() },
+                });
+                if let Some(code) = error.error_code() {
+                    result["ruleId"] = json!(code.code());
+                    result["ruleIndex"] = json!(rule_index(code));
+                }
+                if let Some(help) = error.help() {
+                    result["properties"]["help"] = json!(help);
+                }
+                if let Some(span) = error.span() {
+                    result["locations"] = json!([self.sarif_location(span)]);
+                }
+                result
+            })
+            .collect();
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules,
+                    }
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": results,
+            }]
+        })
+    }
+
+    /// Converts a span into a SARIF physical location. SARIF counts columns in
+    /// code points rather than bytes, so the columns are recomputed from the
+    /// source line when it is available
+    fn sarif_location(&self, span: &SourceSpan) -> Value {
+        let tracker = self.line_tracker_for(span);
+        let column = |line: usize, column: usize| {
+            tracker
+                .get_line(line)
+                .and_then(|text| text.get(..column.saturating_sub(1)).map(|prefix| prefix.chars().count() + 1))
+                .unwrap_or(column)
+        };
+        json!({
+            "physicalLocation": {
+                "artifactLocation": { "uri": sarif_uri(&span.file_path) },
+                "region": {
+                    "startLine": span.start.line,
+                    "startColumn": column(span.start.line, span.start.column),
+                    "endLine": span.end.line,
+                    "endColumn": column(span.end.line, span.end.column),
+                }
+            }
+        })
+    }
+
     /// Formats an error with source context and visual indicators
     fn format_error(
         &self, category: &str, message: &str, span: &SourceSpan, help: Option<&str>, code: Option<ErrorCode>,
@@ -62,7 +213,7 @@ impl ErrorReporter {
         let end_col = span.end.column;
 
         // Get the source line where the error starts
-        let source_line = self.line_tracker.get_line(start_line).unwrap_or_default();
+        let source_line = self.line_tracker_for(span).get_line(start_line).unwrap_or_default();
 
         let estimated_capacity =
             100 + message.len() + category.len() + source_line.len() + help.map_or(0, |h| h.len() + 20) + 50;
@@ -116,3 +267,50 @@ impl ErrorReporter {
         output
     }
 }
+
+/// Describes an error as a JSON object with its code, severity, phase,
+/// message, help and span. Columns count bytes, like [`SourceSpan`].
+fn error_to_json(error: &CompileError) -> Value {
+    let span = error.span().map(|span| {
+        json!({
+            "file": &*span.file_path,
+            "start": { "line": span.start.line, "column": span.start.column },
+            "end": { "line": span.end.line, "column": span.end.column },
+        })
+    });
+    json!({
+        "code": error.error_code().map(ErrorCode::code),
+        "severity": error.severity().to_string(),
+        "phase": error.phase().to_string(),
+        "message": message_of(error),
+        "help": error.help(),
+        "span": span,
+    })
+}
+
+/// The message of `error`, which for I/O errors is the error itself
+fn message_of(error: &CompileError) -> String {
+    error.message().map_or_else(|| error.to_string(), str::to_owned)
+}
+
+/// Turns a file path into a URI: relative paths stay relative to the working
+/// directory, absolute ones become `file` URIs
+fn sarif_uri(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{path}")
+    } else if path.as_bytes().get(1) == Some(&b':') {
+        format!("file:///{path}")
+    } else {
+        path
+    }
+}
+
+/// SARIF has no fatal level; fatal errors are reported as errors
+const fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Note => "note",
+        Severity::Warning => "warning",
+        Severity::Error | Severity::Fatal => "error",
+    }
+}
@@ -8,7 +8,7 @@
 //!
 //! * [`compile_error`]: Main error type enum for all compilation errors
 //! * [`error_code`]: Standardized error codes for identification and documentation
-//! * [`error_reporter`]: Formatted error output with source context
+//! * [`error_reporter`]: Formatted error output with source context, or as JSON lines and SARIF
 //!
 //! ## Phase-specific responsibilities:
 //! * Initialization: Sets up error type definitions and reporting infrastructure
@@ -0,0 +1,528 @@
+//! Random generation of well-typed programs.
+//!
+//! [`ProgramGenerator`] builds the statements of a complete program: a few
+//! globals, helper functions and a `main` block. Expressions mix every integer
+//! width through explicit casts, control flow nests `if`, `while`, `for` and
+//! `switch` statements with `break` and `continue`, and helpers call the
+//! helpers declared before them. The generated trees have the shape the parser
+//! produces, so [`print_source`](crate::printers::source_printer::print_source)
+//! turns them into `.vn` source that parses back to the same program.
+//!
+//! Every program terminates: loops count a dedicated variable up to a small
+//! bound and no statement assigns it, and calls never recurse. Divisors are
+//! forced to be odd, so the only trap left is the signed 64-bit `MIN / -1`.
+
+use crate::parser::ast::{BinaryOp, Expr, Parameter, Stmt, SwitchCase, Type, UnaryOp};
+use crate::utils::{
+    assign_expr, binary_expr, call_expr, cast_expr, compound_assign_expr, dummy_span, function_declaration, num_lit_i8,
+    num_lit_i16, num_lit_i32, num_lit_i64, num_lit_u8, num_lit_u16, num_lit_u32, num_lit_unsigned, unary_expr,
+    var_declaration, variable_expr,
+};
+use std::sync::Arc;
+
+/// Integer types the generator declares variables of.
+pub const INTEGER_TYPES: [Type; 8] =
+    [Type::I8, Type::I16, Type::I32, Type::I64, Type::U8, Type::U16, Type::U32, Type::U64];
+
+/// Small xorshift generator, so that a program is reproducible from its seed
+/// on every platform.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a generator; every seed, including zero, gives a distinct
+    /// non-degenerate sequence.
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    /// Returns the next 64 random bits.
+    pub const fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Returns a number in `0..n`. `n` must not be zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        // The remainder is smaller than `n`, so it fits in a `usize`.
+        usize::try_from(self.next_u64() % n as u64).unwrap_or_default()
+    }
+
+    /// Returns `true` with probability `numerator / denominator`.
+    pub fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    /// Returns a random element of a non-empty slice.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+/// Size limits of generated programs.
+#[derive(Debug, Clone)]
+pub struct GeneratorConfig {
+    /// Maximum number of helper functions besides `main`.
+    pub max_functions: usize,
+    /// Maximum number of globals; at least one is always declared.
+    pub max_globals: usize,
+    /// Maximum nesting of compound statements.
+    pub max_nesting: u32,
+    /// Maximum number of statements in a block.
+    pub max_block_len: usize,
+    /// Maximum depth of an expression tree.
+    pub max_expr_depth: u32,
+    /// Maximum number of iterations of a loop.
+    pub max_loop_iterations: u8,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            max_functions: 3,
+            max_globals: 3,
+            max_nesting: 3,
+            max_block_len: 4,
+            max_expr_depth: 3,
+            max_loop_iterations: 4,
+        }
+    }
+}
+
+/// A variable in scope.
+#[derive(Debug, Clone)]
+struct Variable {
+    name: Arc<str>,
+    ty: Type,
+    /// Loop counters and parameters are never assigned.
+    assignable: bool,
+}
+
+/// Signature of a generated helper function.
+#[derive(Debug, Clone)]
+struct Signature {
+    name: Arc<str>,
+    parameters: Vec<Type>,
+    return_type: Type,
+}
+
+/// Generates random well-typed programs.
+///
+/// # Examples
+///
+/// ```
+/// use jsavrs::fuzz::generator::ProgramGenerator;
+/// use jsavrs::printers::source_printer::print_source;
+///
+/// let program = ProgramGenerator::new(7).generate();
+/// assert_eq!(print_source(&program), print_source(&ProgramGenerator::new(7).generate()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProgramGenerator {
+    rng: Rng,
+    config: GeneratorConfig,
+    scope: Vec<Variable>,
+    /// Functions a call may target: those declared before the current one.
+    callable: Vec<Signature>,
+    loop_depth: u32,
+    next_name: usize,
+}
+
+impl ProgramGenerator {
+    /// Creates a generator with the default limits.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            config: GeneratorConfig::default(),
+            scope: Vec::new(),
+            callable: Vec::new(),
+            loop_depth: 0,
+            next_name: 0,
+        }
+    }
+
+    /// Replaces the size limits.
+    #[must_use]
+    pub const fn with_config(mut self, config: GeneratorConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Generates the statements of a complete program: globals, helper
+    /// functions and `main`.
+    pub fn generate(&mut self) -> Vec<Stmt> {
+        self.scope.clear();
+        self.callable.clear();
+        let mut program = Vec::new();
+        for _ in 0..=self.rng.below(self.config.max_globals.max(1)) {
+            let ty = self.integer_type();
+            let name = self.fresh_name("g");
+            program.push(var_declaration(vec![name.clone()], ty.clone(), true, vec![self.literal(&ty)]));
+            self.scope.push(Variable { name, ty, assignable: true });
+        }
+        for _ in 0..self.rng.below(self.config.max_functions + 1) {
+            program.push(self.function());
+        }
+
+        let globals = self.scope.len();
+        let mut body = self.block(self.config.max_nesting);
+        // Fold the locals of `main` into a global so that their values are observable.
+        let target = self.scope[self.rng.below(globals)].clone();
+        for local in self.scope.split_off(globals) {
+            let value = convert(variable_expr(&local.name), &local.ty, &target.ty);
+            let folded = binary_expr(variable_expr(&target.name), BinaryOp::BitwiseXor, value);
+            body.push(Stmt::Expression { expr: assign_expr(variable_expr(&target.name), folded) });
+        }
+        program.push(Stmt::MainFunction { body: vec![block_stmt(body)], span: dummy_span() });
+        program
+    }
+
+    /// A name no other declaration uses. Prefixes must not form a type name
+    /// with the number, as `i` and `f` would.
+    fn fresh_name(&mut self, prefix: &str) -> Arc<str> {
+        self.next_name += 1;
+        format!("{prefix}{}", self.next_name).into()
+    }
+
+    fn integer_type(&mut self) -> Type {
+        self.rng.pick(&INTEGER_TYPES).clone()
+    }
+
+    fn function(&mut self) -> Stmt {
+        let name = self.fresh_name("helper");
+        let globals = self.scope.len();
+        let mut parameters = Vec::new();
+        for _ in 0..self.rng.below(4) {
+            let ty = self.integer_type();
+            let parameter = self.fresh_name("p");
+            parameters.push(Parameter { name: parameter.clone(), type_annotation: ty.clone(), span: dummy_span() });
+            self.scope.push(Variable { name: parameter, ty, assignable: false });
+        }
+        let return_type = self.integer_type();
+
+        // An early return adds an exit in the middle of the function. It is
+        // built first so that it only uses parameters and globals.
+        let early_return = self.rng.chance(1, 3).then(|| {
+            let condition = self.condition(1);
+            if_stmt(condition, vec![return_stmt(self.expr(&return_type, 1))], None)
+        });
+        let mut body = self.block(self.config.max_nesting.saturating_sub(1));
+        if let Some(early_return) = early_return {
+            let index = self.rng.below(body.len() + 1);
+            body.insert(index, early_return);
+        }
+        body.push(return_stmt(self.expr(&return_type, self.config.max_expr_depth)));
+        self.scope.truncate(globals);
+
+        let types = parameters.iter().map(|parameter| parameter.type_annotation.clone()).collect();
+        self.callable.push(Signature { name: name.clone(), parameters: types, return_type: return_type.clone() });
+        function_declaration(name, parameters, return_type, vec![block_stmt(body)])
+    }
+
+    /// Generates a block; variables it declares go out of scope at its end,
+    /// except in the outermost block of `main`.
+    fn block(&mut self, nesting: u32) -> Vec<Stmt> {
+        let mut statements = Vec::new();
+        for _ in 0..=self.rng.below(self.config.max_block_len) {
+            statements.push(self.stmt(nesting));
+        }
+        statements
+    }
+
+    fn scoped_block(&mut self, nesting: u32) -> Vec<Stmt> {
+        let scope = self.scope.len();
+        let statements = self.block(nesting);
+        self.scope.truncate(scope);
+        statements
+    }
+
+    fn stmt(&mut self, nesting: u32) -> Stmt {
+        let choices = if nesting == 0 { 4 } else { 9 };
+        match self.rng.below(choices) {
+            0 => self.declaration(),
+            1 | 2 => self.assignment(),
+            3 => self.loop_exit().unwrap_or_else(|| self.assignment()),
+            4 | 5 => {
+                let condition = self.condition(2);
+                let then_branch = self.scoped_block(nesting - 1);
+                let else_branch = self.rng.chance(1, 2).then(|| self.scoped_block(nesting - 1));
+                if_stmt(condition, then_branch, else_branch)
+            }
+            6 => self.while_loop(nesting - 1),
+            7 => self.for_loop(nesting - 1),
+            _ => self.switch(nesting - 1),
+        }
+    }
+
+    fn declaration(&mut self) -> Stmt {
+        let ty = self.integer_type();
+        let initializer = self.expr(&ty, self.config.max_expr_depth);
+        let name = self.fresh_name("v");
+        self.scope.push(Variable { name: name.clone(), ty: ty.clone(), assignable: true });
+        var_declaration(vec![name], ty, true, vec![initializer])
+    }
+
+    fn assignment(&mut self) -> Stmt {
+        let targets: Vec<_> = self.scope.iter().filter(|variable| variable.assignable).cloned().collect();
+        if targets.is_empty() {
+            return self.declaration();
+        }
+        let target = self.rng.pick(&targets).clone();
+        let expr = if self.rng.chance(1, 4) {
+            let op =
+                self.rng.pick(&[BinaryOp::Add, BinaryOp::Subtract, BinaryOp::BitwiseXor, BinaryOp::Modulo]).clone();
+            let mut value = self.expr(&target.ty, self.config.max_expr_depth - 1);
+            if op == BinaryOp::Modulo {
+                value = odd(value, &target.ty);
+            }
+            compound_assign_expr(variable_expr(&target.name), op, value)
+        } else {
+            assign_expr(variable_expr(&target.name), self.expr(&target.ty, self.config.max_expr_depth))
+        };
+        Stmt::Expression { expr }
+    }
+
+    /// A conditional `break` or `continue`, when inside a loop.
+    fn loop_exit(&mut self) -> Option<Stmt> {
+        if self.loop_depth == 0 {
+            return None;
+        }
+        let exit = if self.rng.chance(1, 2) {
+            Stmt::Break { span: dummy_span() }
+        } else {
+            Stmt::Continue { span: dummy_span() }
+        };
+        Some(if_stmt(self.condition(1), vec![exit], None))
+    }
+
+    /// Declares a counter of a random type and the comparison bounding it.
+    fn counter(&mut self) -> (Variable, Expr) {
+        let ty = self.integer_type();
+        let counter = Variable { name: self.fresh_name("k"), ty: ty.clone(), assignable: false };
+        let bound = self.rng.below(usize::from(self.config.max_loop_iterations) + 1);
+        let condition = binary_expr(variable_expr(&counter.name), BinaryOp::Less, small_literal(&ty, bound));
+        (counter, condition)
+    }
+
+    fn increment(counter: &Variable) -> Expr {
+        let next = binary_expr(variable_expr(&counter.name), BinaryOp::Add, small_literal(&counter.ty, 1));
+        assign_expr(variable_expr(&counter.name), next)
+    }
+
+    /// `var i = 0; while (i < n) { i = i + 1; ... }`, incrementing first so
+    /// that `continue` cannot skip it.
+    fn while_loop(&mut self, nesting: u32) -> Stmt {
+        let (counter, condition) = self.counter();
+        let declaration =
+            var_declaration(vec![counter.name.clone()], counter.ty.clone(), true, vec![small_literal(&counter.ty, 0)]);
+        let increment = Stmt::Expression { expr: Self::increment(&counter) };
+        self.scope.push(counter);
+        let body = self.loop_body(nesting);
+        self.scope.pop();
+        let mut statements = vec![increment];
+        statements.extend(body);
+        block_stmt(vec![declaration, Stmt::While { condition, body: vec![block_stmt(statements)], span: dummy_span() }])
+    }
+
+    fn for_loop(&mut self, nesting: u32) -> Stmt {
+        let (counter, condition) = self.counter();
+        let initializer =
+            var_declaration(vec![counter.name.clone()], counter.ty.clone(), true, vec![small_literal(&counter.ty, 0)]);
+        let increment = Self::increment(&counter);
+        self.scope.push(counter);
+        let body = self.loop_body(nesting);
+        self.scope.pop();
+        Stmt::For {
+            initializer: Some(Box::new(initializer)),
+            condition: Some(condition),
+            increment: Some(increment),
+            body,
+            span: dummy_span(),
+        }
+    }
+
+    fn loop_body(&mut self, nesting: u32) -> Vec<Stmt> {
+        self.loop_depth += 1;
+        let body = self.scoped_block(nesting);
+        self.loop_depth -= 1;
+        body
+    }
+
+    fn switch(&mut self, nesting: u32) -> Stmt {
+        let ty = self.rng.pick(&[Type::I32, Type::I64]).clone();
+        let scrutinee = self.expr(&ty, 1);
+        let mut values: Vec<usize> = (0..8).collect();
+        let mut cases = Vec::new();
+        for _ in 0..=self.rng.below(3) {
+            let value = values.swap_remove(self.rng.below(values.len()));
+            let body = self.scoped_block(nesting);
+            cases.push(SwitchCase { value: small_literal(&ty, value), body, span: dummy_span() });
+        }
+        let default = self.rng.chance(1, 2).then(|| self.scoped_block(nesting));
+        Stmt::Switch { scrutinee, cases, default, span: dummy_span() }
+    }
+
+    /// Generates an expression of integer type `ty`.
+    fn expr(&mut self, ty: &Type, depth: u32) -> Expr {
+        if depth == 0 {
+            return self.leaf(ty);
+        }
+        match self.rng.below(10) {
+            0 | 1 => self.leaf(ty),
+            2 => {
+                let from = self.integer_type();
+                let value = self.expr(&from, depth - 1);
+                convert(value, &from, ty)
+            }
+            3 if is_signed(ty) => unary_expr(UnaryOp::Negate, self.expr(ty, depth - 1)),
+            3 | 4 => self.call(ty, depth).unwrap_or_else(|| self.leaf(ty)),
+            _ => {
+                let op = self
+                    .rng
+                    .pick(&[
+                        BinaryOp::Add,
+                        BinaryOp::Subtract,
+                        BinaryOp::Multiply,
+                        BinaryOp::Divide,
+                        BinaryOp::Modulo,
+                        BinaryOp::BitwiseAnd,
+                        BinaryOp::BitwiseOr,
+                        BinaryOp::BitwiseXor,
+                        BinaryOp::ShiftLeft,
+                        BinaryOp::ShiftRight,
+                    ])
+                    .clone();
+                let left = self.expr(ty, depth - 1);
+                let mut right = self.expr(ty, depth - 1);
+                if matches!(op, BinaryOp::Divide | BinaryOp::Modulo) {
+                    right = odd(right, ty);
+                }
+                binary_expr(left, op, right)
+            }
+        }
+    }
+
+    /// A variable of type `ty` in scope, or a literal.
+    fn leaf(&mut self, ty: &Type) -> Expr {
+        let candidates: Vec<_> = self.scope.iter().filter(|variable| variable.ty == *ty).collect();
+        if candidates.is_empty() || self.rng.chance(1, 3) {
+            return self.literal(ty);
+        }
+        variable_expr(&self.rng.pick(&candidates).name)
+    }
+
+    fn call(&mut self, ty: &Type, depth: u32) -> Option<Expr> {
+        let candidates: Vec<_> =
+            self.callable.iter().filter(|signature| signature.return_type == *ty).cloned().collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let signature = self.rng.pick(&candidates).clone();
+        let arguments = signature.parameters.iter().map(|parameter| self.expr(parameter, depth - 1)).collect();
+        Some(call_expr(variable_expr(&signature.name), arguments))
+    }
+
+    /// A literal of type `ty`, usually small and sometimes at the edge of its range.
+    fn literal(&mut self, ty: &Type) -> Expr {
+        if self.rng.chance(1, 8) {
+            return max_literal(ty);
+        }
+        let value = self.rng.below(20);
+        small_literal(ty, value)
+    }
+
+    /// Generates a boolean condition.
+    fn condition(&mut self, depth: u32) -> Expr {
+        match self.rng.below(if depth == 0 { 1 } else { 5 }) {
+            0 | 1 => {
+                let ty = self.integer_type();
+                let op = self
+                    .rng
+                    .pick(&[
+                        BinaryOp::Less,
+                        BinaryOp::LessEqual,
+                        BinaryOp::Greater,
+                        BinaryOp::GreaterEqual,
+                        BinaryOp::Equal,
+                        BinaryOp::NotEqual,
+                    ])
+                    .clone();
+                let left = self.expr(&ty, 1);
+                let right = self.expr(&ty, 1);
+                binary_expr(left, op, right)
+            }
+            2 => unary_expr(UnaryOp::Not, self.condition(depth - 1)),
+            _ => {
+                let op = self.rng.pick(&[BinaryOp::And, BinaryOp::Or]).clone();
+                let left = self.condition(depth - 1);
+                let right = self.condition(depth - 1);
+                binary_expr(left, op, right)
+            }
+        }
+    }
+}
+
+/// Casts `value` from `from` to `to` unless the types are equal.
+fn convert(value: Expr, from: &Type, to: &Type) -> Expr {
+    if from == to { value } else { cast_expr(value, to.clone()) }
+}
+
+/// Forces a divisor to be odd, and therefore not zero.
+fn odd(value: Expr, ty: &Type) -> Expr {
+    binary_expr(value, BinaryOp::BitwiseOr, small_literal(ty, 1))
+}
+
+const fn is_signed(ty: &Type) -> bool {
+    matches!(ty, Type::I8 | Type::I16 | Type::I32 | Type::I64)
+}
+
+/// A literal of integer type `ty` with a value below 128.
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+fn small_literal(ty: &Type, value: usize) -> Expr {
+    let value = value.min(127);
+    match ty {
+        Type::I8 => num_lit_i8(value as i8),
+        Type::I16 => num_lit_i16(value as i16),
+        Type::I32 => num_lit_i32(value as i32),
+        Type::U8 => num_lit_u8(value as u8),
+        Type::U16 => num_lit_u16(value as u16),
+        Type::U32 => num_lit_u32(value as u32),
+        Type::U64 => num_lit_unsigned(value as u64),
+        _ => num_lit_i64(value as i64),
+    }
+}
+
+/// The largest value of integer type `ty`.
+fn max_literal(ty: &Type) -> Expr {
+    match ty {
+        Type::I8 => num_lit_i8(i8::MAX),
+        Type::I16 => num_lit_i16(i16::MAX),
+        Type::I32 => num_lit_i32(i32::MAX),
+        Type::U8 => num_lit_u8(u8::MAX),
+        Type::U16 => num_lit_u16(u16::MAX),
+        Type::U32 => num_lit_u32(u32::MAX),
+        Type::U64 => num_lit_unsigned(u64::MAX),
+        _ => num_lit_i64(i64::MAX),
+    }
+}
+
+fn block_stmt(statements: Vec<Stmt>) -> Stmt {
+    Stmt::Block { statements, span: dummy_span() }
+}
+
+fn if_stmt(condition: Expr, then_branch: Vec<Stmt>, else_branch: Option<Vec<Stmt>>) -> Stmt {
+    Stmt::If {
+        condition,
+        then_branch: vec![block_stmt(then_branch)],
+        else_branch: else_branch.map(|statements| vec![block_stmt(statements)]),
+        span: dummy_span(),
+    }
+}
+
+fn return_stmt(value: Expr) -> Stmt {
+    Stmt::Return { value: Some(value), span: dummy_span() }
+}
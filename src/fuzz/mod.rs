@@ -0,0 +1,17 @@
+//! # Fuzzing Module
+//!
+//! Random programs for testing the compiler pipeline end to end. The
+//! [`generator`] builds well-typed ASTs exercising unusual control flow, which
+//! [`print_source`](crate::printers::source_printer::print_source) turns into
+//! `.vn` source, and [`shrink`] reduces a program that makes some check fail
+//! to a minimal reproducer.
+//!
+//! ## Phase-specific responsibilities:
+//! * Initialization: Seeds the generator so that every program is reproducible
+//! * Runtime: Generates programs and prunes failing ones statement by statement
+//! * Termination: Returns the reduced program for printing
+pub mod generator;
+pub mod shrink;
+
+pub use generator::{GeneratorConfig, ProgramGenerator, Rng};
+pub use shrink::shrink;
@@ -0,0 +1,124 @@
+//! Reduction of failing programs to small reproducers.
+//!
+//! [`shrink`] repeatedly prunes the program: it removes single statements and
+//! replaces compound statements by the statements they contain, keeping every
+//! change after which the caller's predicate still reports the failure. The
+//! result is minimal in the sense that no single further pruning keeps the
+//! failure.
+
+use crate::parser::ast::Stmt;
+
+/// A single pruning of the statement at some pre-order position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pruning {
+    /// Drop the statement.
+    Remove,
+    /// Replace an `if`, loop or block by its body.
+    Inline,
+}
+
+/// Shrinks `program` while `still_fails` holds for it.
+///
+/// The predicate receives each candidate and must return `true` only when the
+/// candidate exhibits the original failure. Candidates may be ill-typed, for
+/// example when a declaration was removed but its variable is still used, and
+/// should then be rejected.
+pub fn shrink(program: Vec<Stmt>, mut still_fails: impl FnMut(&[Stmt]) -> bool) -> Vec<Stmt> {
+    let mut current = program;
+    loop {
+        let mut changed = false;
+        let mut position = 0;
+        while position < count_statements(&current) {
+            let mut pruned = false;
+            for pruning in [Pruning::Remove, Pruning::Inline] {
+                let mut candidate = current.clone();
+                if prune(&mut candidate, &mut position.clone(), pruning) == Some(true) && still_fails(&candidate) {
+                    current = candidate;
+                    pruned = true;
+                    break;
+                }
+            }
+            if pruned {
+                changed = true;
+            } else {
+                position += 1;
+            }
+        }
+        if !changed {
+            return current;
+        }
+    }
+}
+
+/// Number of statements in `statements`, counting nested ones.
+#[must_use]
+pub fn count_statements(statements: &[Stmt]) -> usize {
+    statements.iter().map(|stmt| 1 + children(stmt).iter().map(|body| count_statements(body)).sum::<usize>()).sum()
+}
+
+/// Statement lists nested directly in `stmt`.
+fn children(stmt: &Stmt) -> Vec<&Vec<Stmt>> {
+    match stmt {
+        Stmt::Function { body, .. }
+        | Stmt::MainFunction { body, .. }
+        | Stmt::While { body, .. }
+        | Stmt::For { body, .. }
+        | Stmt::Block { statements: body, .. } => vec![body],
+        Stmt::If { then_branch, else_branch, .. } => std::iter::once(then_branch).chain(else_branch).collect(),
+        Stmt::Switch { cases, default, .. } => cases.iter().map(|case| &case.body).chain(default).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn children_mut(stmt: &mut Stmt) -> Vec<&mut Vec<Stmt>> {
+    match stmt {
+        Stmt::Function { body, .. }
+        | Stmt::MainFunction { body, .. }
+        | Stmt::While { body, .. }
+        | Stmt::For { body, .. }
+        | Stmt::Block { statements: body, .. } => vec![body],
+        Stmt::If { then_branch, else_branch, .. } => std::iter::once(then_branch).chain(else_branch).collect(),
+        Stmt::Switch { cases, default, .. } => cases.iter_mut().map(|case| &mut case.body).chain(default).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The statements replacing `stmt` when it is inlined, or `None` when it
+/// cannot be.
+fn inlined(stmt: &Stmt) -> Option<Vec<Stmt>> {
+    match stmt {
+        Stmt::If { then_branch, .. } => Some(then_branch.clone()),
+        Stmt::While { body, .. } | Stmt::Block { statements: body, .. } => Some(body.clone()),
+        Stmt::For { initializer, body, .. } => {
+            Some(initializer.iter().map(|init| (**init).clone()).chain(body.iter().cloned()).collect())
+        }
+        _ => None,
+    }
+}
+
+/// Applies `pruning` to the statement at pre-order `position`. Returns `None`
+/// when the position is past the end of `statements`, which then holds the
+/// remaining distance, and whether the pruning applied otherwise.
+fn prune(statements: &mut Vec<Stmt>, position: &mut usize, pruning: Pruning) -> Option<bool> {
+    for index in 0..statements.len() {
+        if *position == 0 {
+            return Some(match pruning {
+                Pruning::Remove => {
+                    statements.remove(index);
+                    true
+                }
+                Pruning::Inline => inlined(&statements[index]).is_some_and(|body| {
+                    statements.splice(index..=index, body);
+                    true
+                }),
+            });
+        }
+        *position -= 1;
+        for body in children_mut(&mut statements[index]) {
+            if let Some(applied) = prune(body, position, pruning) {
+                return Some(applied);
+            }
+        }
+    }
+    None
+}
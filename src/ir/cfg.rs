@@ -116,6 +116,22 @@ impl ControlFlowGraph {
         }
     }
 
+    /// Removes the edge between two blocks.
+    /// Returns true if the edge existed and was removed, false otherwise.
+    pub fn disconnect_blocks(&mut self, from_label: &str, to_label: &str) -> bool {
+        let edge = self
+            .find_block_by_label(from_label)
+            .zip(self.find_block_by_label(to_label))
+            .and_then(|(from_idx, to_idx)| self.graph.find_edge(from_idx, to_idx));
+        if let Some(edge) = edge {
+            self.graph.remove_edge(edge);
+            self.reverse_post_order_valid = false;
+            true
+        } else {
+            false
+        }
+    }
+
     pub fn blocks(&self) -> impl Iterator<Item = &BasicBlock> {
         self.graph.node_weights()
     }
@@ -0,0 +1,223 @@
+// src/ir/const_eval.rs
+//! Evaluation of IR operations on literal operands.
+//!
+//! The results are the ones the `x86_64` backend computes at run time:
+//!
+//! * integer arithmetic wraps to the width of its type and shift counts are
+//!   masked to that width
+//! * integer to float conversions read the operand as a signed 64-bit value
+//! * float to integer conversions truncate, and produce `i64::MIN` truncated
+//!   to the target width for NaN and out-of-range values
+//! * comparisons involving NaN are false, except `!=`
+//!
+//! Operations that trap at run time, division by zero and the 64-bit
+//! `i64::MIN / -1`, are reported as [`EvalError`]s.
+
+use super::{IrBinaryOp, IrLiteralValue, IrType, IrUnaryOp};
+use std::fmt;
+
+/// Reason an operation could not be evaluated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    /// Integer division or remainder by zero.
+    DivisionByZero,
+    /// Signed 64-bit division of `i64::MIN` by `-1`.
+    Overflow,
+    /// The operation is not defined for its operand types.
+    Unsupported(String),
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DivisionByZero => f.write_str("division by zero"),
+            Self::Overflow => f.write_str("arithmetic overflow"),
+            Self::Unsupported(message) => f.write_str(message),
+        }
+    }
+}
+
+/// Applies `op` to two operands of the same type.
+///
+/// # Errors
+///
+/// Returns an [`EvalError`] for traps and for operand types the operator does
+/// not apply to.
+pub fn eval_binary(op: IrBinaryOp, left: &IrLiteralValue, right: &IrLiteralValue) -> Result<IrLiteralValue, EvalError> {
+    let ty = IrType::from(left);
+    if IrType::from(right) != ty {
+        return Err(unsupported(format!("operands of {op:?} have types {ty} and {}", IrType::from(right))));
+    }
+    match (*left, *right) {
+        (IrLiteralValue::F32(l), IrLiteralValue::F32(r)) => {
+            float_binary(op, f64::from(l), f64::from(r)).map(|result| narrow_float(result, &ty))
+        }
+        (IrLiteralValue::F64(l), IrLiteralValue::F64(r)) => float_binary(op, l, r),
+        _ => int_binary(op, bits(left), bits(right), &ty),
+    }
+}
+
+/// Applies `op` to an operand.
+///
+/// # Errors
+///
+/// Returns an [`EvalError`] if the result is not a valid value of the operand
+/// type, such as the complement of a `char`.
+pub fn eval_unary(op: IrUnaryOp, operand: &IrLiteralValue) -> Result<IrLiteralValue, EvalError> {
+    let ty = IrType::from(operand);
+    match (op, *operand) {
+        (IrUnaryOp::Negate, IrLiteralValue::F32(value)) => Ok(IrLiteralValue::F32(-value)),
+        (IrUnaryOp::Negate, IrLiteralValue::F64(value)) => Ok(IrLiteralValue::F64(-value)),
+        (IrUnaryOp::Not, IrLiteralValue::Bool(value)) => Ok(IrLiteralValue::Bool(!value)),
+        (IrUnaryOp::Not, IrLiteralValue::F32(_) | IrLiteralValue::F64(_)) => {
+            Err(unsupported(format!("operator Not is not defined on {ty}")))
+        }
+        (IrUnaryOp::Negate, _) => from_bits(bits(operand).wrapping_neg(), &ty),
+        (IrUnaryOp::Not, _) => from_bits(!bits(operand), &ty),
+    }
+}
+
+/// Converts `value` to `to`.
+///
+/// # Errors
+///
+/// Returns an [`EvalError`] if `to` is not a primitive scalar type or the
+/// converted integer is not a valid `char`.
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+pub fn eval_cast(value: &IrLiteralValue, to: &IrType) -> Result<IrLiteralValue, EvalError> {
+    let float = match *value {
+        IrLiteralValue::F32(value) => Some(f64::from(value)),
+        IrLiteralValue::F64(value) => Some(value),
+        _ => None,
+    };
+    match (float, to) {
+        (Some(value), IrType::F32 | IrType::F64) => Ok(narrow_float(IrLiteralValue::F64(value), to)),
+        (Some(value), IrType::Bool) => Ok(IrLiteralValue::Bool(value != 0.0)),
+        (Some(value), _) => from_bits(truncate_float(value), to),
+        (None, IrType::F32) => Ok(IrLiteralValue::F32(bits(value) as f32)),
+        (None, IrType::F64) => Ok(IrLiteralValue::F64(bits(value) as f64)),
+        (None, _) => from_bits(bits(value), to),
+    }
+}
+
+/// The canonical 64-bit register form of a non-float value: sign-extended for
+/// signed integers, zero-extended otherwise.
+fn bits(value: &IrLiteralValue) -> i64 {
+    match *value {
+        IrLiteralValue::I8(value) => i64::from(value),
+        IrLiteralValue::I16(value) => i64::from(value),
+        IrLiteralValue::I32(value) => i64::from(value),
+        IrLiteralValue::I64(value) => value,
+        IrLiteralValue::U8(value) => i64::from(value),
+        IrLiteralValue::U16(value) => i64::from(value),
+        IrLiteralValue::U32(value) => i64::from(value),
+        IrLiteralValue::U64(value) => value.cast_signed(),
+        IrLiteralValue::F32(value) => i64::from(value.to_bits()),
+        IrLiteralValue::F64(value) => value.to_bits().cast_signed(),
+        IrLiteralValue::Bool(value) => i64::from(value),
+        IrLiteralValue::Char(value) => i64::from(u32::from(value)),
+    }
+}
+
+/// Truncates the register form `bits` to a value of type `ty`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn from_bits(bits: i64, ty: &IrType) -> Result<IrLiteralValue, EvalError> {
+    Ok(match ty {
+        IrType::I8 => IrLiteralValue::I8(bits as i8),
+        IrType::I16 => IrLiteralValue::I16(bits as i16),
+        IrType::I32 => IrLiteralValue::I32(bits as i32),
+        IrType::I64 => IrLiteralValue::I64(bits),
+        IrType::U8 => IrLiteralValue::U8(bits as u8),
+        IrType::U16 => IrLiteralValue::U16(bits as u16),
+        IrType::U32 => IrLiteralValue::U32(bits as u32),
+        IrType::U64 => IrLiteralValue::U64(bits as u64),
+        IrType::Bool => IrLiteralValue::Bool(bits != 0),
+        IrType::Char => {
+            let code = bits as u32;
+            IrLiteralValue::Char(
+                char::from_u32(code).ok_or_else(|| unsupported(format!("{code:#x} is not a valid char")))?,
+            )
+        }
+        other => return Err(unsupported(format!("cannot produce a value of type {other}"))),
+    })
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn int_binary(op: IrBinaryOp, left: i64, right: i64, ty: &IrType) -> Result<IrLiteralValue, EvalError> {
+    let signed = ty.is_signed_integer();
+    let (unsigned_left, unsigned_right) = (left.cast_unsigned(), right.cast_unsigned());
+    let compare = |result: bool| Ok(IrLiteralValue::Bool(result));
+    let result = match op {
+        IrBinaryOp::Add => left.wrapping_add(right),
+        IrBinaryOp::Subtract => left.wrapping_sub(right),
+        IrBinaryOp::Multiply => left.wrapping_mul(right),
+        IrBinaryOp::Divide | IrBinaryOp::Modulo if right == 0 => return Err(EvalError::DivisionByZero),
+        IrBinaryOp::Divide if signed => left.checked_div(right).ok_or(EvalError::Overflow)?,
+        IrBinaryOp::Modulo if signed => left.checked_rem(right).ok_or(EvalError::Overflow)?,
+        IrBinaryOp::Divide => (unsigned_left / unsigned_right).cast_signed(),
+        IrBinaryOp::Modulo => (unsigned_left % unsigned_right).cast_signed(),
+        IrBinaryOp::And | IrBinaryOp::BitwiseAnd => left & right,
+        IrBinaryOp::Or | IrBinaryOp::BitwiseOr => left | right,
+        IrBinaryOp::BitwiseXor => left ^ right,
+        IrBinaryOp::ShiftLeft | IrBinaryOp::ShiftRight => {
+            let count = (right & i64::from(ty.get_bit_width().min(64) - 1)).cast_unsigned() as u32;
+            match op {
+                IrBinaryOp::ShiftLeft => left.wrapping_shl(count),
+                _ if signed => left.wrapping_shr(count),
+                _ => unsigned_left.wrapping_shr(count).cast_signed(),
+            }
+        }
+        IrBinaryOp::Equal => return compare(left == right),
+        IrBinaryOp::NotEqual => return compare(left != right),
+        IrBinaryOp::Less if signed => return compare(left < right),
+        IrBinaryOp::LessEqual if signed => return compare(left <= right),
+        IrBinaryOp::Greater if signed => return compare(left > right),
+        IrBinaryOp::GreaterEqual if signed => return compare(left >= right),
+        IrBinaryOp::Less => return compare(unsigned_left < unsigned_right),
+        IrBinaryOp::LessEqual => return compare(unsigned_left <= unsigned_right),
+        IrBinaryOp::Greater => return compare(unsigned_left > unsigned_right),
+        IrBinaryOp::GreaterEqual => return compare(unsigned_left >= unsigned_right),
+    };
+    from_bits(result, ty)
+}
+
+/// Evaluates a float operation in double precision; callers narrow `f32`
+/// results, which is exact for `+`, `-`, `*` and `/`.
+#[allow(clippy::float_cmp)]
+fn float_binary(op: IrBinaryOp, left: f64, right: f64) -> Result<IrLiteralValue, EvalError> {
+    let compare = |result: bool| Ok(IrLiteralValue::Bool(result));
+    Ok(IrLiteralValue::F64(match op {
+        IrBinaryOp::Add => left + right,
+        IrBinaryOp::Subtract => left - right,
+        IrBinaryOp::Multiply => left * right,
+        IrBinaryOp::Divide => left / right,
+        IrBinaryOp::Equal => return compare(left == right),
+        IrBinaryOp::NotEqual => return compare(left != right),
+        IrBinaryOp::Less => return compare(left < right),
+        IrBinaryOp::LessEqual => return compare(left <= right),
+        IrBinaryOp::Greater => return compare(left > right),
+        IrBinaryOp::GreaterEqual => return compare(left >= right),
+        _ => return Err(unsupported(format!("operator {op:?} is not defined on floating-point operands"))),
+    }))
+}
+
+/// Rounds a double-precision result to `ty` when `ty` is `f32`.
+#[allow(clippy::cast_possible_truncation)]
+const fn narrow_float(value: IrLiteralValue, ty: &IrType) -> IrLiteralValue {
+    match (value, ty) {
+        (IrLiteralValue::F64(value), IrType::F32) => IrLiteralValue::F32(value as f32),
+        (value, _) => value,
+    }
+}
+
+/// Truncates toward zero like `cvttsd2si`, which yields `i64::MIN` for NaN and
+/// out-of-range values.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn truncate_float(value: f64) -> i64 {
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0; // 2^63
+    if (-LIMIT..LIMIT).contains(&value) { value as i64 } else { i64::MIN }
+}
+
+const fn unsupported(message: String) -> EvalError {
+    EvalError::Unsupported(message)
+}
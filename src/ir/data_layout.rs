@@ -1,3 +1,4 @@
+use super::types::IrType;
 use std::fmt;
 
 /// Endianness (E / e)
@@ -95,6 +96,22 @@ pub struct NativeIntWidths {
     pub widths_bits: Vec<u32>,
 }
 
+/// Memory layout of a struct: field offsets, total size and alignment.
+///
+/// All values are in bytes. The size includes the tail padding needed to keep
+/// consecutive elements of an array of the struct aligned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLayout {
+    /// Offset of each field from the start of the struct, in declaration order
+    pub field_offsets: Vec<u64>,
+
+    /// Total size including tail padding
+    pub size: u64,
+
+    /// ABI alignment
+    pub align: u64,
+}
+
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
@@ -154,6 +171,79 @@ impl ParsedDataLayout {
         self.stack_align_bits
     }
 
+    /// Returns the number of bytes occupied by a value of type `ty`, including
+    /// the padding of aggregates.
+    ///
+    /// Strings and unresolved custom types are stored as pointers.
+    #[must_use]
+    pub fn size_of(&self, ty: &IrType) -> u64 {
+        match ty {
+            IrType::I8 | IrType::U8 | IrType::Bool => 1,
+            IrType::I16 | IrType::U16 => 2,
+            IrType::I32 | IrType::U32 | IrType::F32 | IrType::Char => 4,
+            IrType::I64 | IrType::U64 | IrType::F64 => 8,
+            IrType::String | IrType::Pointer(_) | IrType::Custom(..) => u64::from(self.pointer_size_bits() / 8),
+            IrType::Void => 0,
+            IrType::Array(element, count) => self.size_of(element) * *count as u64,
+            IrType::Struct(_, fields, _) => self.struct_layout(fields).size,
+        }
+    }
+
+    /// Returns the ABI alignment of `ty` in bytes.
+    ///
+    /// Integer, float and pointer alignments come from the layout string; types
+    /// it does not mention use LLVM's defaults (`i64:32`, `f64:64`, `p:64:64`).
+    #[must_use]
+    pub fn abi_align_of(&self, ty: &IrType) -> u64 {
+        let bits = match ty {
+            IrType::I8 | IrType::U8 | IrType::Bool => self.integer_align_bits(8),
+            IrType::I16 | IrType::U16 => self.integer_align_bits(16),
+            IrType::I32 | IrType::U32 | IrType::Char => self.integer_align_bits(32),
+            IrType::I64 | IrType::U64 => self.integer_align_bits(64),
+            IrType::F32 => self.float_layouts.get(&32).map_or(32, |layout| layout.abi_align_bits),
+            IrType::F64 => self.float_layouts.get(&64).map_or(64, |layout| layout.abi_align_bits),
+            IrType::String | IrType::Pointer(_) | IrType::Custom(..) => {
+                self.pointer_layouts.get(&0).map_or(64, |layout| layout.abi_align_bits)
+            }
+            IrType::Void => 8,
+            IrType::Array(element, _) => return self.abi_align_of(element),
+            IrType::Struct(_, fields, _) => return self.struct_layout(fields).align,
+        };
+        u64::from(bits / 8).max(1)
+    }
+
+    /// Computes the layout of a struct with `fields`, placing each field at the
+    /// next offset aligned to its ABI alignment.
+    ///
+    /// The struct is aligned to its most aligned field, raised to the aggregate
+    /// ABI alignment (`a:<abi>`) when the layout string specifies one.
+    #[must_use]
+    pub fn struct_layout(&self, fields: &[(String, IrType)]) -> StructLayout {
+        let mut field_offsets = Vec::with_capacity(fields.len());
+        let mut offset: u64 = 0;
+        let mut align = self.aggregate_layout.as_ref().map_or(1, |layout| u64::from(layout.abi_align_bits / 8).max(1));
+        for (_, field_ty) in fields {
+            let field_align = self.abi_align_of(field_ty);
+            offset = offset.next_multiple_of(field_align);
+            field_offsets.push(offset);
+            offset += self.size_of(field_ty);
+            align = align.max(field_align);
+        }
+        StructLayout { field_offsets, size: offset.next_multiple_of(align), align }
+    }
+
+    fn pointer_size_bits(&self) -> u32 {
+        self.pointer_layouts.get(&0).map_or(64, |layout| layout.size_bits)
+    }
+
+    fn integer_align_bits(&self, size_bits: u32) -> u32 {
+        self.integer_layouts.get(&size_bits).map_or(
+            // LLVM aligns i64 to 32 bits unless the layout says otherwise
+            if size_bits == 64 { 32 } else { size_bits },
+            |layout| layout.abi_align_bits,
+        )
+    }
+
     /// Parse a data layout string into a `ParsedDataLayout`
     ///
     /// # Errors
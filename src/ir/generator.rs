@@ -1,16 +1,19 @@
 // src/ir/generator.rs
+use super::const_eval::{EvalError, eval_binary, eval_cast, eval_unary};
+use super::data_layout::ParsedDataLayout;
+use super::platform::platform_config_for;
 use super::ssa::SsaTransformer;
 use super::{
-    BasicBlock, Function, Instruction, InstructionKind, IrBinaryOp, IrConstantValue, IrLiteralValue, IrParameter,
-    IrType, IrUnaryOp, Module, ParamAttributes, ScopeId, ScopeManager, Terminator, TerminatorKind, TypePromotionEngine,
-    Value,
+    BasicBlock, CastKind, Function, GlobalVariable, Instruction, InstructionKind, IrBinaryOp, IrConstantValue,
+    IrLiteralValue, IrParameter, IrType, IrUnaryOp, Module, ParamAttributes, PromotionRule, ScopeId, ScopeManager,
+    Terminator, TerminatorKind, TypePromotionEngine, Value, ValueKind,
 };
 use crate::error::compile_error::CompileError;
 use crate::error::error_code::ErrorCode;
 use crate::location::source_span::{HasSpan, SourceSpan};
-use crate::parser::ast::{BinaryOp, Expr, LiteralValue, Parameter, Stmt, Type, UnaryOp};
+use crate::parser::ast::{BinaryOp, EnumVariant, Expr, LiteralValue, Parameter, Stmt, SwitchCase, Type, UnaryOp};
 use crate::tokens::number::Number;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::sync::Arc;
 
@@ -137,8 +140,17 @@ pub struct IrGenerator {
     apply_ssa: bool,
     /// Reusable string buffer for formatting to reduce allocations
     format_buffer: String,
+    /// Target data layout used to compute struct field offsets
+    data_layout: ParsedDataLayout,
+    /// Names of the top-level variables, whose symbols hold their address
+    global_variables: HashSet<Arc<str>>,
+    /// Temporaries holding the `alloca` of a mutable local variable
+    local_variables: HashSet<u64>,
 }
 
+/// The variants of an enum in declaration order with their discriminants.
+type EnumVariants = Vec<(Arc<str>, IrLiteralValue)>;
+
 /// Context for managing type information during IR generation.
 ///
 /// Maintains mappings of struct definitions and type aliases that are referenced
@@ -148,6 +160,7 @@ pub struct IrGenerator {
 /// # Fields
 ///
 /// * `structs` - Map of struct names to their field lists and source locations
+/// * `enums` - Map of enum names to their underlying IR type and variant values
 /// * `aliases` - Map of type alias names to their underlying IR types
 ///
 /// # Examples
@@ -164,6 +177,8 @@ pub struct IrGenerator {
 struct TypeContext {
     /// Map of struct names to field definitions and source spans
     structs: HashMap<String, (Vec<(String, IrType)>, SourceSpan)>,
+    /// Map of enum names to their underlying IR type and variants in declaration order
+    enums: HashMap<String, (IrType, EnumVariants)>,
     /// Map of type alias names to their underlying IR types
     aliases: HashMap<String, IrType>,
 }
@@ -194,6 +209,9 @@ impl IrGenerator {
             root_scope: scope_manager.root_scope(),
             apply_ssa: true,                          // Enable SSA by default
             format_buffer: String::with_capacity(64), // Pre-allocate buffer for labels
+            data_layout: platform_config_for(std::env::consts::OS, std::env::consts::ARCH).data_layout.parsed(),
+            global_variables: HashSet::new(),
+            local_variables: HashSet::new(),
         }
     }
 
@@ -233,7 +251,9 @@ impl IrGenerator {
     /// Generates intermediate representation for a module of statements
     ///
     /// This is the main entry point for IR generation. It processes the AST in two passes:
-    /// 1. Declaration pass: Creates function declarations and adds them to the symbol table
+    /// 1. Declaration pass: Registers enums and structs, evaluates the initializers of
+    ///    top-level variables and constants, and creates function declarations in the
+    ///    symbol table
     /// 2. Generation pass: Generates code for function bodies and other statements
     ///
     /// # Parameters
@@ -247,7 +267,28 @@ impl IrGenerator {
     pub fn generate(&mut self, stmts: Vec<Stmt>, module_name: &str) -> (Module, Vec<CompileError>) {
         let mut module = Module::new(module_name, self.root_scope);
 
-        // First pass: create all functions and add them to the symbol table
+        // Enums, struct layouts and globals are collected first so that function
+        // signatures may use types declared later in the file. Enums and globals
+        // are taken in source order since their initializers may refer to each other
+        for stmt in &stmts {
+            match stmt {
+                Stmt::Enum { name, underlying_type, variants, .. } => {
+                    self.declare_enum(name, underlying_type, variants);
+                }
+                Stmt::VarDeclaration { variables, type_annotation, initializers, span, is_mutable } => {
+                    self.declare_globals(&mut module, variables, type_annotation, initializers, *is_mutable, span);
+                }
+                _ => {}
+            }
+        }
+        for stmt in &stmts {
+            if let Stmt::Struct { name, fields, span } = stmt {
+                self.declare_struct(name, fields, span);
+            }
+        }
+
+        // First pass: create all functions and add them to the symbol table,
+        // so that calls resolve to functions declared anywhere in the module
         for stmt in &stmts {
             match stmt {
                 Stmt::Function { name, parameters: _, return_type, body: _, span } => {
@@ -281,6 +322,9 @@ impl IrGenerator {
                     self.generate_function_body(&mut func, body, span);
                     module.add_function(func);
                 }
+                // Types and globals are registered in the first pass; imported
+                // files are linked in by the module resolver
+                Stmt::Struct { .. } | Stmt::Enum { .. } | Stmt::VarDeclaration { .. } | Stmt::Import { .. } => {}
                 other => {
                     self.new_error(
                         Some(ErrorCode::E3003),
@@ -442,6 +486,8 @@ impl IrGenerator {
             Type::Custom(name) => {
                 if let Some((fields, span)) = self.type_context.structs.get(name.as_ref()) {
                     IrType::Struct(name.clone(), fields.clone(), span.clone())
+                } else if let Some((ty, _)) = self.type_context.enums.get(name.as_ref()) {
+                    ty.clone()
                 } else {
                     IrType::Custom(name.clone(), SourceSpan::default())
                 }
@@ -460,6 +506,170 @@ impl IrGenerator {
         }
     }
 
+    /// Registers a struct declaration so that `map_type` resolves its name to
+    /// an [`IrType::Struct`].
+    fn declare_struct(&mut self, name: &str, fields: &[Parameter], span: &SourceSpan) {
+        let fields =
+            fields.iter().map(|field| (field.name.to_string(), self.map_type(&field.type_annotation))).collect();
+        self.type_context.structs.insert(name.to_string(), (fields, span.clone()));
+    }
+
+    /// Registers an enum declaration: `map_type` resolves its name to the
+    /// underlying type and its variants evaluate to their discriminants.
+    fn declare_enum(&mut self, name: &str, underlying_type: &Type, variants: &[EnumVariant]) {
+        let ty = self.map_type(underlying_type);
+        let mut values: EnumVariants = Vec::with_capacity(variants.len());
+        for variant in variants {
+            let value = match (&variant.value, values.last()) {
+                (Some(expr), _) => self.eval_constant(expr).and_then(|value| eval_cast(&value, &ty)),
+                (None, Some((_, previous))) => one_of_type(&ty)
+                    .ok_or_else(|| EvalError::Unsupported(format!("enum '{name}' has type {ty}")))
+                    .and_then(|one| eval_binary(IrBinaryOp::Add, previous, &one)),
+                (None, None) => eval_cast(&IrLiteralValue::I64(0), &ty),
+            };
+            match value {
+                Ok(value) => {
+                    if let Some((other, _)) = values.iter().find(|(_, existing)| *existing == value) {
+                        self.new_error(
+                            Some(ErrorCode::E3003),
+                            Arc::from(format!(
+                                "Discriminant {value} of variant '{}' is already used by '{other}'",
+                                variant.name
+                            )),
+                            variant.span.clone(),
+                        );
+                    }
+                    values.push((variant.name.clone(), value));
+                }
+                Err(error) => self.new_error(
+                    Some(ErrorCode::E3003),
+                    Arc::from(format!("Cannot evaluate the discriminant of variant '{}': {error}", variant.name)),
+                    variant.span.clone(),
+                ),
+            }
+        }
+        self.type_context.enums.insert(name.to_string(), (ty, values));
+    }
+
+    /// The discriminant named by `object.field` when `object` is the name of
+    /// an enum rather than of a variable.
+    fn enum_variant(&self, object: &Expr, field: &str) -> Option<Result<IrLiteralValue, EvalError>> {
+        let Expr::Variable { name, .. } = object else {
+            return None;
+        };
+        if self.scope_manager.lookup(name).is_some() {
+            return None;
+        }
+        let (_, variants) = self.type_context.enums.get(name.as_ref())?;
+        Some(
+            variants
+                .iter()
+                .find(|(variant, _)| &**variant == field)
+                .map(|(_, value)| *value)
+                .ok_or_else(|| EvalError::Unsupported(format!("Enum '{name}' has no variant '{field}'"))),
+        )
+    }
+
+    /// Declares top-level variables and constants.
+    ///
+    /// Initializers are evaluated at compile time. Variables and arrays become
+    /// module globals whose symbol is their address `@name`; scalar constants
+    /// have no storage and their symbol is the value itself.
+    fn declare_globals(
+        &mut self, module: &mut Module, variables: &[Arc<str>], type_annotation: &Type, initializers: &[Expr],
+        is_mutable: bool, span: &SourceSpan,
+    ) {
+        let ty = self.map_type(type_annotation);
+
+        for (i, var) in variables.iter().enumerate() {
+            let Some(init) = initializers.get(i) else {
+                self.new_error(
+                    Some(ErrorCode::E3003),
+                    Arc::from(format!("Global '{var}' must be initialized")),
+                    span.clone(),
+                );
+                continue;
+            };
+            let initializer = match self.eval_global_initializer(init, &ty) {
+                Ok(initializer) => initializer,
+                Err(error) => {
+                    self.new_error(
+                        Some(ErrorCode::E3003),
+                        Arc::from(format!("Cannot evaluate the initializer of global '{var}': {error}")),
+                        init.span().clone(),
+                    );
+                    continue;
+                }
+            };
+
+            match initializer.as_slice() {
+                [value] if !is_mutable && !matches!(ty, IrType::Array(..)) => {
+                    let value = Value::new_literal(*value).with_debug_info(Some(var.clone()), span.clone());
+                    self.scope_manager.add_symbol(var.clone(), value);
+                }
+                _ => {
+                    let address = Value::new_global(var.clone(), IrType::Pointer(Box::new(ty.clone())))
+                        .with_debug_info(Some(var.clone()), span.clone());
+                    module.add_global(GlobalVariable { name: var.clone(), ty: ty.clone(), initializer });
+                    self.global_variables.insert(var.clone());
+                    self.scope_manager.add_symbol(var.clone(), address);
+                }
+            }
+        }
+    }
+
+    /// Evaluates the initializer of a global of type `ty`: a single value, or
+    /// every element of an array.
+    fn eval_global_initializer(&self, init: &Expr, ty: &IrType) -> Result<Vec<IrLiteralValue>, EvalError> {
+        match (ty, init) {
+            (IrType::Array(element_ty, _), Expr::ArrayLiteral { elements, .. }) => {
+                elements.iter().map(|element| eval_cast(&self.eval_constant(element)?, element_ty)).collect()
+            }
+            _ => Ok(vec![eval_cast(&self.eval_constant(init)?, ty)?]),
+        }
+    }
+
+    /// Evaluates a constant expression: literals, constants, enum variants and
+    /// operators or casts applied to them.
+    fn eval_constant(&self, expr: &Expr) -> Result<IrLiteralValue, EvalError> {
+        let not_constant = || EvalError::Unsupported("not a constant expression".to_string());
+        match expr {
+            Expr::Literal { value, span } => match self.generate_literal(value.clone(), span.clone()).kind {
+                ValueKind::Literal(literal) => Ok(literal),
+                _ => Err(not_constant()),
+            },
+            Expr::Variable { name, .. } => match self.scope_manager.lookup(name).map(|value| &value.kind) {
+                Some(ValueKind::Literal(literal)) => Ok(*literal),
+                _ => Err(EvalError::Unsupported(format!("'{name}' is not a constant"))),
+            },
+            Expr::Grouping { expr, .. } => self.eval_constant(expr),
+            Expr::Unary { op, expr, .. } => eval_unary(op.clone().into(), &self.eval_constant(expr)?),
+            Expr::Cast { expr, target_type, .. } => eval_cast(&self.eval_constant(expr)?, &self.map_type(target_type)),
+            Expr::FieldAccess { object, field, .. } => {
+                self.enum_variant(object, field).unwrap_or_else(|| Err(not_constant()))
+            }
+            Expr::Binary { left, op, right, span } => {
+                let ir_op: IrBinaryOp = op.clone().into();
+                let mut left = self.eval_constant(left)?;
+                let mut right = self.eval_constant(right)?;
+                let promotion = TypePromotionEngine::new().analyze_binary_promotion(
+                    &IrType::from(&left),
+                    &IrType::from(&right),
+                    ir_op,
+                    span.clone(),
+                );
+                if let Some(cast) = &promotion.left_cast {
+                    left = eval_cast(&left, &cast.to_type)?;
+                }
+                if let Some(cast) = &promotion.right_cast {
+                    right = eval_cast(&right, &cast.to_type)?;
+                }
+                eval_binary(ir_op, &left, &right)
+            }
+            _ => Err(not_constant()),
+        }
+    }
+
     /// Finalizes the current basic block and transfers it to the function's CFG.
     ///
     /// Takes the current block being constructed, transfers its instructions and
@@ -561,9 +771,21 @@ impl IrGenerator {
 
         // Add function parameters to symbol table
         for param in &func.parameters {
+            let param_span = param.attributes.source_span.clone().unwrap_or_default();
             let value = Value::new_local(param.name.clone(), param.ty.clone())
-                .with_debug_info(Some(param.name.clone()), param.attributes.source_span.clone().unwrap_or_default());
-            self.scope_manager.add_symbol(param.name.clone(), value.clone());
+                .with_debug_info(Some(param.name.clone()), param_span.clone());
+            if matches!(param.ty, IrType::Struct(..)) {
+                // Struct parameters live in one slot, so that writes to their
+                // fields are seen by every later access.
+                let slot = self.new_local_slot(param.ty.clone(), Some(param.name.clone()), &param_span);
+                self.add_instruction(Instruction::new(
+                    InstructionKind::Store { value, dest: slot.clone() },
+                    param_span,
+                ));
+                self.scope_manager.add_symbol(param.name.clone(), slot);
+            } else {
+                self.scope_manager.add_symbol(param.name.clone(), value);
+            }
         }
 
         // Process all statements
@@ -657,6 +879,13 @@ impl IrGenerator {
             Stmt::Continue { span } => {
                 self.handle_loop_control(func, span, LoopControl::Continue);
             }
+            Stmt::Struct { name, fields, span } => self.declare_struct(&name, &fields, &span),
+            Stmt::Enum { name, underlying_type, variants, .. } => {
+                self.declare_enum(&name, &underlying_type, &variants);
+            }
+            Stmt::Switch { scrutinee, cases, default, span } => {
+                self.generate_switch(func, scrutinee, cases, default, span);
+            }
             other => self.new_error(
                 Some(ErrorCode::E3003),
                 Arc::from(format!("Unsupported statement: {other:?}")),
@@ -707,14 +936,7 @@ impl IrGenerator {
 
         for (i, var) in variables.iter().enumerate() {
             if is_mutable {
-                let temp_id = self.new_temp();
-                let ptr_ty = IrType::Pointer(Box::new(ty.clone()));
-                let ptr_value = Value::new_temporary(temp_id, ptr_ty).with_debug_info(Some(var.clone()), span.clone());
-
-                let alloca_inst = Instruction::new(InstructionKind::Alloca { ty: ty.clone() }, span.clone())
-                    .with_result(ptr_value.clone());
-
-                self.add_instruction(alloca_inst);
+                let ptr_value = self.new_local_slot(ty.clone(), Some(var.clone()), &span);
 
                 if let Some(init) = initializers.get(i) {
                     let value_val = self.generate_expr(func, init.clone());
@@ -729,6 +951,15 @@ impl IrGenerator {
             } else {
                 if let Some(init) = initializers.get(i) {
                     let value = self.generate_expr(func, init.clone());
+                    if self.is_local_read(&value) {
+                        // Later stores to the variable read must not change the constant.
+                        let slot = self.new_local_slot(ty.clone(), Some(var.clone()), &span);
+                        let store_inst =
+                            Instruction::new(InstructionKind::Store { value, dest: slot.clone() }, span.clone());
+                        self.add_instruction(store_inst);
+                        self.scope_manager.add_symbol(var.clone(), slot);
+                        continue;
+                    }
                     self.scope_manager.add_symbol(var.clone(), value.with_debug_info(Some(var.clone()), span.clone()));
                 } else {
                     self.new_error(
@@ -741,6 +972,19 @@ impl IrGenerator {
         }
     }
 
+    /// Emits the `alloca` of a mutable local and returns its address.
+    fn new_local_slot(&mut self, ty: IrType, name: Option<Arc<str>>, span: &SourceSpan) -> Value {
+        let temp_id = self.new_temp();
+        let ptr_ty = IrType::Pointer(Box::new(ty.clone()));
+        let ptr_value = Value::new_temporary(temp_id, ptr_ty).with_debug_info(name, span.clone());
+
+        let alloca_inst = Instruction::new(InstructionKind::Alloca { ty }, span.clone()).with_result(ptr_value.clone());
+
+        self.add_instruction(alloca_inst);
+        self.local_variables.insert(temp_id);
+        ptr_value
+    }
+
     /// Generates IR code for a return statement.
     ///
     /// Creates a return terminator with the specified value (or a default value
@@ -834,6 +1078,93 @@ impl IrGenerator {
         self.start_block(func, &merge_label, span);
     }
 
+    /// Generates IR code for a switch statement.
+    ///
+    /// Evaluates the switch value once and ends the current block with a
+    /// [`TerminatorKind::Switch`] over the case values, folded to constants of
+    /// the value's type. Each arm gets its own block that continues at the
+    /// merge block; `break` inside an arm jumps there as well. Without a
+    /// `default` arm, unmatched values go straight to the merge block.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function containing the switch
+    /// * `scrutinee` - The value being matched
+    /// * `cases` - The `case` arms, in source order
+    /// * `default` - Statements of the `default` arm, if any
+    /// * `span` - Source location for debugging
+    fn generate_switch(
+        &mut self, func: &mut Function, scrutinee: Expr, cases: Vec<SwitchCase>, default: Option<Vec<Stmt>>,
+        span: SourceSpan,
+    ) {
+        let value = self.generate_expr(func, scrutinee);
+        // Mutable variables evaluate to their stack slot; match on the value it holds
+        let ty = match &value.ty {
+            IrType::Pointer(inner) => inner.as_ref().clone(),
+            other => other.clone(),
+        };
+        let merge_label: Arc<str> = Arc::from(self.new_block_label("switch_end"));
+        let default_label: Arc<str> =
+            if default.is_some() { Arc::from(self.new_block_label("switch_default")) } else { merge_label.clone() };
+
+        let mut targets: Vec<(IrLiteralValue, Arc<str>)> = Vec::with_capacity(cases.len());
+        let mut arms = Vec::with_capacity(cases.len());
+        for case in cases {
+            let case_value = match self.eval_constant(&case.value).and_then(|literal| eval_cast(&literal, &ty)) {
+                Ok(case_value) => case_value,
+                Err(error) => {
+                    self.new_error(
+                        Some(ErrorCode::E3003),
+                        Arc::from(format!("Cannot evaluate switch case: {error}")),
+                        case.value.span().clone(),
+                    );
+                    continue;
+                }
+            };
+            if targets.iter().any(|(existing, _)| *existing == case_value) {
+                self.new_error(
+                    Some(ErrorCode::E3003),
+                    Arc::from(format!("Duplicate case value {case_value} in switch")),
+                    case.value.span().clone(),
+                );
+                continue;
+            }
+            let label: Arc<str> = Arc::from(self.new_block_label("switch_case"));
+            targets.push((case_value, label.clone()));
+            arms.push((label, case.body));
+        }
+
+        self.add_terminator(
+            func,
+            Terminator::new(
+                TerminatorKind::Switch {
+                    value,
+                    ty,
+                    default_label: default_label.clone(),
+                    cases: targets
+                        .into_iter()
+                        .map(|(case_value, label)| (Value::new_literal(case_value), label))
+                        .collect(),
+                },
+                span.clone(),
+            ),
+        );
+
+        self.control_flow_stack.break_stack.push(merge_label.clone());
+        for (label, body) in arms.into_iter().chain(default.map(|body| (default_label, body))) {
+            self.start_block(func, &label, span.clone());
+            self.scope_manager.enter_scope();
+            for stmt in body {
+                self.generate_stmt(func, stmt);
+            }
+            self.scope_manager.exit_scope();
+            self.add_branch_if_needed(func, &merge_label, span.clone());
+        }
+        self.control_flow_stack.break_stack.pop();
+
+        self.start_block(func, &merge_label, span);
+    }
+
     /// Generates IR code for a while loop.
     ///
     /// Creates a control flow graph with separate blocks for the loop condition,
@@ -1035,10 +1366,17 @@ impl IrGenerator {
             Expr::Unary { op, expr, span } => self.generate_unary(func, op, *expr, span),
             Expr::Variable { name, span } => self.generate_variable(name, span),
             Expr::Assign { target, value, span } => self.generate_assign(func, *target, *value, span),
+            Expr::CompoundAssign { target, op, value, span } => {
+                self.generate_compound_assign(func, *target, op, *value, span)
+            }
+            Expr::Update { target, op, prefix, span } => self.generate_update(func, *target, op, prefix, span),
             Expr::Grouping { expr, span: _ } => self.generate_expr(func, *expr),
             Expr::ArrayLiteral { elements, span } => self.generate_array_literal(func, elements, span),
             Expr::ArrayAccess { array, index, span } => self.generate_array_access(func, *array, *index, span),
             Expr::Call { callee, arguments, span } => self.generate_call(func, *callee, arguments, span),
+            Expr::StructLiteral { name, fields, span } => self.generate_struct_literal(func, &name, fields, span),
+            Expr::FieldAccess { object, field, span } => self.generate_field_access(func, *object, &field, span),
+            Expr::Cast { expr, target_type, span } => self.generate_cast(func, *expr, &target_type, span),
             other => {
                 self.new_error(Some(ErrorCode::E3003), Arc::from("Unsupported expression type"), other.span().clone());
                 Value::new_literal(IrLiteralValue::I32(0))
@@ -1183,6 +1521,198 @@ impl IrGenerator {
         array_ptr
     }
 
+    /// Generates IR code for a struct literal.
+    ///
+    /// Allocates the struct on the stack and stores each field value at its
+    /// offset, in the order the fields are written in the literal.
+    ///
+    /// # Returns
+    ///
+    /// A [`Value`] representing a pointer to the allocated struct.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Point { x: 1, y: 2 }
+    /// let point_ptr = generator.generate_struct_literal(func, "Point", fields, span);
+    /// ```
+    fn generate_struct_literal(
+        &mut self, func: &mut Function, name: &str, fields: Vec<(Arc<str>, Expr)>, span: SourceSpan,
+    ) -> Value {
+        let struct_ty = self.map_type(&Type::Custom(name.into()));
+        if !matches!(struct_ty, IrType::Struct(..)) {
+            self.new_error(Some(ErrorCode::E3003), Arc::from(format!("Undefined struct '{name}'")), span);
+            return Value::new_literal(IrLiteralValue::I64(0));
+        }
+
+        let struct_temp = self.new_temp();
+        let alloca_inst = Instruction::new(InstructionKind::Alloca { ty: struct_ty.clone() }, span.clone())
+            .with_result(Value::new_temporary(struct_temp, IrType::Pointer(Box::new(struct_ty))));
+        self.add_instruction(alloca_inst.clone());
+        let struct_ptr = alloca_inst.result.unwrap();
+
+        for (field, value) in fields {
+            let value_val = self.generate_expr(func, value);
+            let field_ptr = self.field_pointer(&struct_ptr, &field, &span);
+            let store_inst =
+                Instruction::new(InstructionKind::Store { value: value_val, dest: field_ptr }, span.clone());
+            self.add_instruction(store_inst);
+        }
+
+        struct_ptr
+    }
+
+    /// Generates IR code for reading a struct field or an enum variant.
+    ///
+    /// Scalar fields are loaded; array and struct fields evaluate to their
+    /// address, like array and struct literals do. Enum variants are
+    /// constants of the enum's underlying type.
+    fn generate_field_access(&mut self, func: &mut Function, object: Expr, field: &str, span: SourceSpan) -> Value {
+        match self.enum_variant(&object, field) {
+            Some(Ok(value)) => return Value::new_literal(value),
+            Some(Err(error)) => {
+                self.new_error(Some(ErrorCode::E3003), Arc::from(error.to_string()), span);
+                return Value::new_literal(IrLiteralValue::I32(0));
+            }
+            None => {}
+        }
+        let field_ptr = self.generate_field_address(func, object, field, &span);
+        let field_ty = match &field_ptr.ty {
+            IrType::Pointer(inner) if !matches!(**inner, IrType::Array(..) | IrType::Struct(..)) => *inner.clone(),
+            _ => return field_ptr,
+        };
+
+        let tmp = self.new_temp();
+        let load_inst = Instruction::new(InstructionKind::Load { src: field_ptr, ty: field_ty.clone() }, span)
+            .with_result(Value::new_temporary(tmp, field_ty));
+        self.add_instruction(load_inst.clone());
+        load_inst.result.unwrap()
+    }
+
+    /// Returns a pointer to `field` of the struct that `object` evaluates to.
+    ///
+    /// Struct values that are not already in memory are first spilled to a
+    /// stack slot.
+    fn generate_field_address(&mut self, func: &mut Function, object: Expr, field: &str, span: &SourceSpan) -> Value {
+        let object_val = self.generate_expr(func, object);
+        let struct_ptr = match &object_val.ty {
+            IrType::Pointer(inner) if matches!(**inner, IrType::Struct(..)) => object_val,
+            IrType::Struct(..) => {
+                let ptr_ty = IrType::Pointer(Box::new(object_val.ty.clone()));
+                let tmp = self.new_temp();
+                let alloca_inst = Instruction::new(InstructionKind::Alloca { ty: object_val.ty.clone() }, span.clone())
+                    .with_result(Value::new_temporary(tmp, ptr_ty));
+                self.add_instruction(alloca_inst.clone());
+                let slot = alloca_inst.result.unwrap();
+                self.add_instruction(Instruction::new(
+                    InstructionKind::Store { value: object_val, dest: slot.clone() },
+                    span.clone(),
+                ));
+                slot
+            }
+            other => {
+                self.new_error(
+                    Some(ErrorCode::E3003),
+                    Arc::from(format!("Field access on non-struct type {other}")),
+                    span.clone(),
+                );
+                return Value::new_literal(IrLiteralValue::I64(0));
+            }
+        };
+        self.field_pointer(&struct_ptr, field, span)
+    }
+
+    /// Computes the address of `field` from a pointer to a struct.
+    ///
+    /// The field offset comes from the target data layout. The address is
+    /// computed by a byte-wise `GetElementPtr` followed, unless the field is a
+    /// `u8`, by a bitcast to a pointer to the field type.
+    #[allow(clippy::cast_possible_wrap)]
+    fn field_pointer(&mut self, struct_ptr: &Value, field: &str, span: &SourceSpan) -> Value {
+        let pointee = if let IrType::Pointer(inner) = &struct_ptr.ty { inner.as_ref() } else { &struct_ptr.ty };
+        let IrType::Struct(struct_name, fields, _) = pointee.clone() else {
+            self.new_error(
+                Some(ErrorCode::E3003),
+                Arc::from(format!("Field access on non-struct type {}", struct_ptr.ty)),
+                span.clone(),
+            );
+            return Value::new_literal(IrLiteralValue::I64(0));
+        };
+        let Some(index) = fields.iter().position(|(name, _)| name == field) else {
+            self.new_error(
+                Some(ErrorCode::E3003),
+                Arc::from(format!("Struct '{struct_name}' has no field '{field}'")),
+                span.clone(),
+            );
+            return Value::new_literal(IrLiteralValue::I64(0));
+        };
+        let offset = self.data_layout.struct_layout(&fields).field_offsets[index];
+        let field_ptr_ty = IrType::Pointer(Box::new(fields[index].1.clone()));
+        let byte_ptr_ty = IrType::Pointer(Box::new(IrType::U8));
+
+        let gep_temp = self.new_temp();
+        let gep_inst = Instruction::new(
+            InstructionKind::GetElementPtr {
+                base: struct_ptr.clone(),
+                index: Value::new_literal(IrLiteralValue::I64(offset as i64)),
+                element_ty: IrType::U8,
+            },
+            span.clone(),
+        )
+        .with_result(Value::new_temporary(gep_temp, byte_ptr_ty));
+        self.add_instruction(gep_inst.clone());
+
+        let byte_ptr = gep_inst.result.unwrap();
+        if field_ptr_ty == byte_ptr.ty {
+            return byte_ptr;
+        }
+        self.emit_cast(byte_ptr, CastKind::Bitcast, field_ptr_ty, span)
+    }
+
+    /// Generates IR code for an `expr as T` cast.
+    ///
+    /// The cast kind comes from the promotion matrix; conversions that go
+    /// through an intermediate type emit two casts.
+    fn generate_cast(&mut self, func: &mut Function, expr: Expr, target_type: &Type, span: SourceSpan) -> Value {
+        let value = self.generate_expr(func, expr);
+        let to_ty = self.map_type(target_type);
+        // Mutable variables evaluate to their stack slot; convert the value it holds
+        let from_ty = match &value.ty {
+            IrType::Pointer(inner)
+                if !matches!(**inner, IrType::Pointer(_) | IrType::Array(..) | IrType::Struct(..)) =>
+            {
+                inner.as_ref().clone()
+            }
+            other => other.clone(),
+        };
+        if from_ty == to_ty {
+            return value;
+        }
+        match TypePromotionEngine::new().explicit_cast_rule(&from_ty, &to_ty) {
+            Some(PromotionRule::Direct { cast_kind, .. }) => self.emit_cast(value, *cast_kind, to_ty, &span),
+            Some(PromotionRule::Indirect { intermediate_type, first_cast, second_cast, .. }) => {
+                let intermediate = self.emit_cast(value, *first_cast, intermediate_type.clone(), &span);
+                self.emit_cast(intermediate, *second_cast, to_ty, &span)
+            }
+            _ => {
+                self.new_error(Some(ErrorCode::E3003), Arc::from(format!("Cannot cast {} to {to_ty}", value.ty)), span);
+                value
+            }
+        }
+    }
+
+    /// Emits a `kind` cast of `value` to `to_ty` and returns its result.
+    fn emit_cast(&mut self, value: Value, kind: CastKind, to_ty: IrType, span: &SourceSpan) -> Value {
+        let tmp = self.new_temp();
+        let cast_inst = Instruction::new(
+            InstructionKind::Cast { kind, from_ty: value.ty.clone(), value, to_ty: to_ty.clone() },
+            span.clone(),
+        )
+        .with_result(Value::new_temporary(tmp, to_ty));
+        self.add_instruction(cast_inst.clone());
+        cast_inst.result.unwrap()
+    }
+
     /// Generates IR code for a literal value.
     ///
     /// Converts AST literal values to IR literal values, handling all numeric types,
@@ -1241,7 +1771,8 @@ impl IrGenerator {
     ///
     /// Evaluates both operands, applies type promotion if necessary, and creates
     /// a binary instruction with the promoted operands. The type promotion engine
-    /// ensures operands have compatible types for the operation.
+    /// ensures operands have compatible types for the operation. Logical `&&`
+    /// and `||` are lowered by [`generate_logical`](Self::generate_logical).
     ///
     /// # Arguments
     ///
@@ -1265,10 +1796,71 @@ impl IrGenerator {
     fn generate_binary(
         &mut self, func: &mut Function, left: Expr, op: BinaryOp, right: Expr, span: SourceSpan,
     ) -> Value {
+        if matches!(op, BinaryOp::And | BinaryOp::Or) {
+            return self.generate_logical(func, left, op == BinaryOp::And, right, span);
+        }
         let ir_op: IrBinaryOp = op.into();
         let left_val = self.generate_expr(func, left);
         let right_val = self.generate_expr(func, right);
+        self.emit_binary(func, ir_op, left_val, right_val, span)
+    }
+
+    /// Generates IR code for a short-circuiting `&&` (`is_and`) or `||`.
+    ///
+    /// The right operand is evaluated in its own block, entered only when the
+    /// left operand does not decide the result; a phi in the join block merges
+    /// the decided value with the value of the right operand.
+    ///
+    /// ```text
+    /// entry:  br left ? rhs : end        (`||`: br left ? end : rhs)
+    /// rhs:    t = <right>; br end
+    /// end:    result = phi bool [ [false, entry], [t, rhs] ]
+    /// ```
+    fn generate_logical(
+        &mut self, func: &mut Function, left: Expr, is_and: bool, right: Expr, span: SourceSpan,
+    ) -> Value {
+        let left_val = self.generate_expr(func, left);
+        let left_label = self.current_block_label.clone().unwrap_or_default();
+
+        let rhs_label = self.new_block_label(if is_and { "and_rhs" } else { "or_rhs" });
+        let end_label = self.new_block_label(if is_and { "and_end" } else { "or_end" });
+        let (true_label, false_label) = if is_and { (&rhs_label, &end_label) } else { (&end_label, &rhs_label) };
+        self.add_terminator(
+            func,
+            Terminator::new(
+                TerminatorKind::ConditionalBranch {
+                    condition: left_val,
+                    true_label: true_label.as_str().into(),
+                    false_label: false_label.as_str().into(),
+                },
+                span.clone(),
+            ),
+        );
+
+        self.start_block(func, &rhs_label, span.clone());
+        let right_val = self.generate_expr(func, right);
+        // The right operand may have opened blocks of its own
+        let right_label = self.current_block_label.clone().unwrap_or_default();
+        self.add_branch_if_needed(func, &end_label, span.clone());
+
+        self.start_block(func, &end_label, span.clone());
+        let decided = Value::new_literal(IrLiteralValue::Bool(!is_and));
+        let dest_id = self.new_temp();
+        let phi_inst = Instruction::new(
+            InstructionKind::Phi { ty: IrType::Bool, incoming: vec![(decided, left_label), (right_val, right_label)] },
+            span,
+        )
+        .with_result(Value::new_temporary(dest_id, IrType::Bool));
+
+        self.add_instruction(phi_inst.clone());
+        phi_inst.result.unwrap()
+    }
 
+    /// Emits a binary instruction on already evaluated operands, inserting
+    /// promotion casts when their types differ.
+    fn emit_binary(
+        &mut self, func: &mut Function, ir_op: IrBinaryOp, left_val: Value, right_val: Value, span: SourceSpan,
+    ) -> Value {
         // Initialize type promotion engine
         let promotion_engine = TypePromotionEngine::new();
 
@@ -1324,8 +1916,11 @@ impl IrGenerator {
     #[allow(clippy::needless_pass_by_value)]
     fn generate_unary(&mut self, func: &mut Function, op: UnaryOp, expr: Expr, span: SourceSpan) -> Value {
         let ir_op: IrUnaryOp = op.into();
+        // Comparisons are typed like their operands but yield 0 or 1; negating
+        // one as a `bool` keeps the `!` logical instead of flipping every bit.
+        let negates_comparison = ir_op == IrUnaryOp::Not && is_comparison(&expr);
         let operand = self.generate_expr(func, expr);
-        let ty = operand.ty.clone();
+        let ty = if negates_comparison { IrType::Bool } else { operand.ty.clone() };
         let dest_id = self.new_temp();
 
         let unary_inst = Instruction::new(InstructionKind::Unary { op: ir_op, operand, ty: ty.clone() }, span)
@@ -1360,12 +1955,64 @@ impl IrGenerator {
     /// ```
     #[allow(clippy::needless_pass_by_value)]
     fn generate_variable(&mut self, name: Arc<str>, span: SourceSpan) -> Value {
-        self.scope_manager.lookup(&name).cloned().unwrap_or_else(|| {
+        let value = self.lookup_variable(&name, &span);
+        match &value.ty {
+            // Global arrays are used through their address, like local ones.
+            IrType::Pointer(inner) if self.is_global_variable(&value) && !matches!(**inner, IrType::Array(..)) => {
+                self.load_assign_target(&value, &span)
+            }
+            _ => self.read_local(value),
+        }
+    }
+
+    /// Looks up the symbol of a variable: its value, or its address for
+    /// mutable locals and globals.
+    fn lookup_variable(&mut self, name: &str, span: &SourceSpan) -> Value {
+        self.scope_manager.lookup(name).cloned().unwrap_or_else(|| {
             self.new_error(Some(ErrorCode::E3004), Arc::from(format!("Undefined variable '{name}'")), span.clone());
-            Value::new_literal(IrLiteralValue::I32(0)).with_debug_info(None, span)
+            Value::new_literal(IrLiteralValue::I32(0)).with_debug_info(None, span.clone())
         })
     }
 
+    /// Reads a local variable.
+    ///
+    /// The symbol of a mutable local is its `alloca`, which SSA construction
+    /// replaces with the current value of the variable: reads of scalar
+    /// variables are typed as that value. Aggregates are used through their
+    /// address.
+    fn read_local(&self, value: Value) -> Value {
+        match self.local_scalar_type(&value) {
+            Some(ty) => Value { ty: ty.clone(), ..value },
+            None => value,
+        }
+    }
+
+    /// Returns the value type of a scalar mutable local given its `alloca`.
+    fn local_scalar_type<'v>(&self, value: &'v Value) -> Option<&'v IrType> {
+        match (&value.kind, &value.ty) {
+            (ValueKind::Temporary(id), IrType::Pointer(inner))
+                if self.local_variables.contains(id) && !matches!(**inner, IrType::Array(..) | IrType::Struct(..)) =>
+            {
+                Some(inner)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether `value` is a read of a scalar mutable local, which SSA
+    /// construction resolves to the value of the variable where it is used
+    /// rather than where it was read.
+    fn is_local_read(&self, value: &Value) -> bool {
+        matches!(value.kind, ValueKind::Temporary(id) if self.local_variables.contains(&id))
+            && !matches!(value.ty, IrType::Pointer(_))
+    }
+
+    /// Whether `value` is the address of a top-level variable rather than a
+    /// function or a local.
+    fn is_global_variable(&self, value: &Value) -> bool {
+        matches!(&value.kind, ValueKind::Global(name) if self.global_variables.contains(name))
+    }
+
     /// Generates IR code for an assignment expression.
     ///
     /// Evaluates the target expression (variable or array element), evaluates
@@ -1390,12 +2037,7 @@ impl IrGenerator {
     /// let value = generator.generate_assign(func, x_expr, value_expr, span);
     /// ```
     fn generate_assign(&mut self, func: &mut Function, target: Expr, value: Expr, span: SourceSpan) -> Value {
-        let target_val = match target {
-            Expr::ArrayAccess { array, index, span: access_span } => {
-                self.generate_array_access_target(func, *array, *index, access_span)
-            }
-            _ => self.generate_expr(func, target),
-        };
+        let target_val = self.generate_assign_target(func, target);
 
         let value_val = self.generate_expr(func, value);
 
@@ -1405,6 +2047,132 @@ impl IrGenerator {
         value_val
     }
 
+    /// Generates IR code for a compound assignment such as `x += 1`.
+    ///
+    /// Computes the target address once, reads the current value, applies the
+    /// operator with the evaluated right-hand side and stores the result back.
+    ///
+    /// # Returns
+    ///
+    /// A [`Value`] containing the stored result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // x += 2
+    /// let value = generator.generate_compound_assign(func, x_expr, BinaryOp::Add, two_expr, span);
+    /// ```
+    #[allow(clippy::needless_pass_by_value)]
+    fn generate_compound_assign(
+        &mut self, func: &mut Function, target: Expr, op: BinaryOp, value: Expr, span: SourceSpan,
+    ) -> Value {
+        let target_val = self.generate_assign_target(func, target);
+        let current = self.read_assign_target(&target_val, &span);
+        let value_val = self.generate_expr(func, value);
+        let result = self.emit_binary(func, op.into(), current, value_val, span.clone());
+
+        let store_inst = Instruction::new(InstructionKind::Store { value: result.clone(), dest: target_val }, span);
+        self.add_instruction(store_inst);
+
+        result
+    }
+
+    /// Generates IR code for `++x`, `--x`, `x++` and `x--`.
+    ///
+    /// Reads the target, adds or subtracts a one of the target type and stores
+    /// the result back.
+    ///
+    /// # Returns
+    ///
+    /// The updated value for prefix forms and the value before the update for
+    /// postfix forms.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // i++
+    /// let old = generator.generate_update(func, i_expr, BinaryOp::Add, false, span);
+    /// ```
+    #[allow(clippy::needless_pass_by_value)]
+    fn generate_update(
+        &mut self, func: &mut Function, target: Expr, op: BinaryOp, prefix: bool, span: SourceSpan,
+    ) -> Value {
+        let target_val = self.generate_assign_target(func, target);
+        let current = self.read_assign_target(&target_val, &span);
+        let one = one_of_type(&current.ty).unwrap_or_else(|| {
+            self.new_error(
+                Some(ErrorCode::E3003),
+                Arc::from(format!("Cannot increment or decrement a value of type {}", current.ty)),
+                span.clone(),
+            );
+            IrLiteralValue::I32(1)
+        });
+        let one = Value::new_literal(one).with_debug_info(None, span.clone());
+        let result = self.emit_binary(func, op.into(), current.clone(), one, span.clone());
+        let old = if !prefix && self.is_local_read(&current) {
+            // Keep the value from before the update in a slot of its own.
+            let slot = self.new_local_slot(current.ty.clone(), None, &span);
+            let store_inst =
+                Instruction::new(InstructionKind::Store { value: current, dest: slot.clone() }, span.clone());
+            self.add_instruction(store_inst);
+            self.read_local(slot)
+        } else {
+            current
+        };
+
+        let store_inst = Instruction::new(InstructionKind::Store { value: result.clone(), dest: target_val }, span);
+        self.add_instruction(store_inst);
+
+        if prefix { result } else { old }
+    }
+
+    /// Returns the address written by an assignment to `target`: the variable's
+    /// stack slot or global address, or a pointer to the array element or
+    /// struct field.
+    fn generate_assign_target(&mut self, func: &mut Function, target: Expr) -> Value {
+        match target {
+            Expr::ArrayAccess { array, index, span: access_span } => {
+                self.generate_array_access_target(func, *array, *index, access_span)
+            }
+            Expr::FieldAccess { object, field, span: access_span } => {
+                self.generate_field_address(func, *object, &field, &access_span)
+            }
+            Expr::Variable { name, span } => self.lookup_variable(&name, &span),
+            _ => self.generate_expr(func, target),
+        }
+    }
+
+    /// Reads the current value of an assignment target: the value of a local
+    /// variable, or a load from a global, array element or struct field.
+    fn read_assign_target(&mut self, target_val: &Value, span: &SourceSpan) -> Value {
+        if self.local_scalar_type(target_val).is_some() {
+            self.read_local(target_val.clone())
+        } else {
+            self.load_assign_target(target_val, span)
+        }
+    }
+
+    /// Loads the current value stored at an assignment target address.
+    fn load_assign_target(&mut self, target_val: &Value, span: &SourceSpan) -> Value {
+        let ty = if let IrType::Pointer(inner) = &target_val.ty {
+            *inner.clone()
+        } else {
+            self.new_error(
+                Some(ErrorCode::E3003),
+                Arc::from(format!("Assignment target of type {} is not addressable", target_val.ty)),
+                span.clone(),
+            );
+            target_val.ty.clone()
+        };
+
+        let tmp = self.new_temp();
+        let load_inst =
+            Instruction::new(InstructionKind::Load { src: target_val.clone(), ty: ty.clone() }, span.clone())
+                .with_result(Value::new_temporary(tmp, ty));
+        self.add_instruction(load_inst.clone());
+        load_inst.result.unwrap()
+    }
+
     /// Generates a unique temporary variable ID.
     ///
     /// Increments the internal counter and returns a unique identifier for
@@ -1693,3 +2461,37 @@ impl Default for IrGenerator {
         Self::new()
     }
 }
+
+/// The literal `1` of a numeric IR type, used by increment and decrement.
+const fn one_of_type(ty: &IrType) -> Option<IrLiteralValue> {
+    Some(match ty {
+        IrType::I8 => IrLiteralValue::I8(1),
+        IrType::I16 => IrLiteralValue::I16(1),
+        IrType::I32 => IrLiteralValue::I32(1),
+        IrType::I64 => IrLiteralValue::I64(1),
+        IrType::U8 => IrLiteralValue::U8(1),
+        IrType::U16 => IrLiteralValue::U16(1),
+        IrType::U32 => IrLiteralValue::U32(1),
+        IrType::U64 => IrLiteralValue::U64(1),
+        IrType::F32 => IrLiteralValue::F32(1.0),
+        IrType::F64 => IrLiteralValue::F64(1.0),
+        _ => return None,
+    })
+}
+
+/// Whether `expr`, ignoring parentheses, is a comparison.
+fn is_comparison(expr: &Expr) -> bool {
+    match expr {
+        Expr::Grouping { expr, .. } => is_comparison(expr),
+        Expr::Binary { op, .. } => matches!(
+            op,
+            BinaryOp::Equal
+                | BinaryOp::NotEqual
+                | BinaryOp::Less
+                | BinaryOp::LessEqual
+                | BinaryOp::Greater
+                | BinaryOp::GreaterEqual
+        ),
+        _ => false,
+    }
+}
@@ -0,0 +1,1058 @@
+// src/ir/interpreter.rs
+//! Interpreter executing NIR modules without a backend.
+//!
+//! The interpreter walks the [`ControlFlowGraph`](super::ControlFlowGraph) of
+//! each [`Function`] and gives every instruction the meaning the `x86_64`
+//! backend gives it:
+//!
+//! * arithmetic, comparisons and numeric casts are evaluated by
+//!   [`const_eval`](super::const_eval), which mirrors the machine instructions
+//! * memory is byte addressed: every `alloca`, global and string literal owns
+//!   an allocation laid out with the module's data layout, and a pointer is an
+//!   allocation plus a byte offset
+//! * after SSA construction a `store` to a temporary defined nowhere else binds
+//!   that temporary, and values read from a `var` keep the pointer type of its
+//!   `alloca`, as the instruction selector assumes
+//! * phi nodes are resolved when control enters their block, from the edge it
+//!   came through; a phi without a defined value on that edge is undefined and
+//!   traps only when it is used
+//!
+//! Errors the program hits at run time, such as a division by zero or an
+//! out-of-bounds access, stop execution with a [`Trap`] located at the span of
+//! the instruction's debug information.
+
+use super::const_eval::{EvalError, eval_binary, eval_cast, eval_unary};
+use super::data_layout::ParsedDataLayout;
+use super::{
+    BasicBlock, CastKind, Function, Instruction, InstructionKind, IrBinaryOp, IrConstantValue, IrLiteralValue, IrType,
+    Module, TerminatorKind, Value, ValueKind,
+};
+use crate::error::compile_error::CompileError;
+use crate::error::error_code::ErrorCode;
+use crate::location::source_span::SourceSpan;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+/// Number of nested calls after which execution traps with a stack overflow.
+pub const MAX_CALL_DEPTH: usize = 10_000;
+
+/// An address in the memory of the interpreter.
+///
+/// The null pointer belongs to no allocation. Pointers stored in memory are
+/// encoded in 8 bytes: the allocation number in the high half and the offset
+/// in the low half.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pointer {
+    allocation: u32,
+    offset: i64,
+}
+
+impl Pointer {
+    /// The null pointer.
+    pub const NULL: Self = Self { allocation: 0, offset: 0 };
+
+    /// Returns `true` for the null pointer.
+    #[must_use]
+    pub const fn is_null(&self) -> bool {
+        self.allocation == 0
+    }
+
+    /// Returns the byte offset of the pointer inside its allocation.
+    #[must_use]
+    pub const fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    const fn encode(self) -> u64 {
+        ((self.allocation as u64) << 32) | (self.offset as u32 as u64)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    const fn decode(raw: u64) -> Self {
+        Self { allocation: (raw >> 32) as u32, offset: raw as u32 as i32 as i64 }
+    }
+}
+
+impl fmt::Display for Pointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_null() { f.write_str("null") } else { write!(f, "&{}+{}", self.allocation, self.offset) }
+    }
+}
+
+/// A value computed by the interpreter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuntimeValue {
+    /// A number, `bool` or `char`.
+    Scalar(IrLiteralValue),
+    /// An address in interpreter memory.
+    Pointer(Pointer),
+    /// The address of a function of the module.
+    Function(Arc<str>),
+}
+
+impl RuntimeValue {
+    /// Returns the scalar held by the value, if it is one.
+    #[must_use]
+    pub const fn as_scalar(&self) -> Option<&IrLiteralValue> {
+        match self {
+            Self::Scalar(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The exit status a process returning this value from `main` gets, which
+    /// is zero for values that are not integers.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn exit_status(&self) -> i32 {
+        match self {
+            Self::Scalar(IrLiteralValue::F32(_) | IrLiteralValue::F64(_)) | Self::Pointer(_) | Self::Function(_) => 0,
+            Self::Scalar(value) => integer(value) as i32,
+        }
+    }
+}
+
+impl fmt::Display for RuntimeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scalar(value) => value.fmt(f),
+            Self::Pointer(pointer) => pointer.fmt(f),
+            Self::Function(name) => write!(f, "@{name}"),
+        }
+    }
+}
+
+/// An error that stops the execution of a program.
+///
+/// Traps carry [`ErrorCode::E3014`] when the program itself fails, and
+/// [`ErrorCode::E3015`] when the IR cannot be executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trap {
+    /// Code classifying the trap.
+    pub code: ErrorCode,
+    /// Description of the failure.
+    pub message: String,
+    /// Source location of the instruction that trapped.
+    pub span: SourceSpan,
+}
+
+impl Trap {
+    fn runtime(message: impl Into<String>, span: &SourceSpan) -> Self {
+        Self { code: ErrorCode::E3014, message: message.into(), span: span.clone() }
+    }
+
+    fn unsupported(message: impl Into<String>, span: &SourceSpan) -> Self {
+        Self { code: ErrorCode::E3015, message: message.into(), span: span.clone() }
+    }
+
+    fn eval(error: EvalError, span: &SourceSpan) -> Self {
+        match error {
+            EvalError::Unsupported(message) => Self::unsupported(message, span),
+            error => Self::runtime(error.to_string(), span),
+        }
+    }
+}
+
+impl fmt::Display for Trap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {} at {}", self.code.code(), self.message, self.span)
+    }
+}
+
+impl From<Trap> for CompileError {
+    fn from(trap: Trap) -> Self {
+        Self::RuntimeError { code: Some(trap.code), message: trap.message.into(), span: trap.span, help: None }
+    }
+}
+
+/// A block of interpreter memory.
+struct Allocation {
+    bytes: Vec<u8>,
+    /// Cleared when the function owning a stack allocation returns.
+    live: bool,
+    /// Cleared for string literals.
+    writable: bool,
+}
+
+/// Blocks of a function indexed by label, and the temporaries its stores bind.
+struct FunctionInfo<'m> {
+    function: &'m Function,
+    blocks: HashMap<&'m str, &'m BasicBlock>,
+    bound_by_store: HashSet<&'m ValueKind>,
+}
+
+impl<'m> FunctionInfo<'m> {
+    fn new(function: &'m Function) -> Self {
+        let blocks = function.cfg.blocks().map(|block| (block.label.as_ref(), block)).collect();
+        let mut defined: HashSet<ValueKind> =
+            function.parameters.iter().map(|param| ValueKind::Local(param.name.clone())).collect();
+        let instructions = || function.cfg.blocks().flat_map(|block| &block.instructions);
+        defined.extend(instructions().filter_map(|inst| inst.result.as_ref().map(|result| result.kind.clone())));
+        let bound_by_store = instructions()
+            .filter_map(|inst| match (&inst.kind, &inst.result) {
+                (InstructionKind::Store { dest, .. }, None)
+                    if matches!(dest.kind, ValueKind::Temporary(_)) && !defined.contains(&dest.kind) =>
+                {
+                    Some(&dest.kind)
+                }
+                _ => None,
+            })
+            .collect();
+        Self { function, blocks, bound_by_store }
+    }
+}
+
+/// Activation record of a function being executed.
+struct Frame<'m> {
+    function: usize,
+    values: HashMap<ValueKind, RuntimeValue>,
+    block: &'m BasicBlock,
+    /// Index of the next instruction of `block` to execute.
+    next: usize,
+    /// Allocations made by the `alloca`s of the function.
+    allocas: Vec<u32>,
+    /// Value of the caller receiving the result of the call.
+    result: Option<&'m Value>,
+}
+
+/// Executes the functions of an IR [`Module`].
+///
+/// Globals are allocated and initialized when the interpreter is created and
+/// keep their values across calls.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut interpreter = Interpreter::new(&module);
+/// let status = interpreter.run_main()?.exit_status();
+/// ```
+pub struct Interpreter<'m> {
+    layout: ParsedDataLayout,
+    functions: Vec<FunctionInfo<'m>>,
+    function_index: HashMap<&'m str, usize>,
+    globals: HashMap<&'m str, (Pointer, &'m IrType)>,
+    strings: HashMap<Arc<str>, Pointer>,
+    allocations: Vec<Allocation>,
+    frames: Vec<Frame<'m>>,
+    steps: u64,
+    step_limit: Option<u64>,
+}
+
+impl<'m> Interpreter<'m> {
+    /// Creates an interpreter for `module` and initializes its globals.
+    #[must_use]
+    pub fn new(module: &'m Module) -> Self {
+        let functions: Vec<FunctionInfo<'m>> = module.functions.iter().map(FunctionInfo::new).collect();
+        let function_index = functions.iter().enumerate().map(|(i, info)| (info.function.name.as_ref(), i)).collect();
+        let mut interpreter = Self {
+            layout: module.data_layout.parsed(),
+            functions,
+            function_index,
+            globals: HashMap::new(),
+            strings: HashMap::new(),
+            allocations: Vec::new(),
+            frames: Vec::new(),
+            steps: 0,
+            step_limit: None,
+        };
+        for global in &module.globals {
+            let pointer = interpreter.allocate(interpreter.size_of(&global.ty), true);
+            let element_ty = if let IrType::Array(element_ty, _) = &global.ty { element_ty } else { &global.ty };
+            let stride = interpreter.size_of(element_ty);
+            let size = stride.min(8);
+            let bytes = &mut interpreter.allocations[pointer.allocation as usize - 1].bytes;
+            for (value, element) in global.initializer.iter().zip(bytes.chunks_mut(stride)) {
+                element[..size].copy_from_slice(&encode_scalar(value)[..size]);
+            }
+            interpreter.globals.insert(global.name.as_ref(), (pointer, &global.ty));
+        }
+        interpreter
+    }
+
+    /// Limits the number of instructions and terminators executed, so that
+    /// programs that do not terminate trap instead of running forever.
+    #[must_use]
+    pub const fn with_step_limit(mut self, limit: u64) -> Self {
+        self.step_limit = Some(limit);
+        self
+    }
+
+    /// Returns the number of instructions and terminators executed so far.
+    #[must_use]
+    pub const fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Returns the current value of the global `name`.
+    #[must_use]
+    pub fn global(&self, name: &str) -> Option<RuntimeValue> {
+        let (pointer, ty) = self.globals.get(name)?;
+        let allocation = &self.allocations[pointer.allocation as usize - 1];
+        let mut raw = [0; 8];
+        let size = self.size_of(ty).min(8);
+        raw[..size].copy_from_slice(&allocation.bytes[..size]);
+        decode_scalar(u64::from_le_bytes(raw), ty, &SourceSpan::default()).ok()
+    }
+
+    /// Returns the current values of the elements of the global array `name`,
+    /// or `None` when there is no such global or it is not an array.
+    #[must_use]
+    pub fn global_elements(&self, name: &str) -> Option<Vec<RuntimeValue>> {
+        let (pointer, IrType::Array(element_ty, _)) = self.globals.get(name)? else {
+            return None;
+        };
+        let allocation = &self.allocations[pointer.allocation as usize - 1];
+        let stride = self.size_of(element_ty);
+        let size = stride.min(8);
+        allocation
+            .bytes
+            .chunks(stride)
+            .map(|element| {
+                let mut raw = [0; 8];
+                raw[..size].copy_from_slice(&element[..size]);
+                decode_scalar(u64::from_le_bytes(raw), element_ty, &SourceSpan::default()).ok()
+            })
+            .collect()
+    }
+
+    /// Executes `main`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`Trap`] that stopped the program.
+    pub fn run_main(&mut self) -> Result<RuntimeValue, Trap> {
+        self.call("main", Vec::new())
+    }
+
+    /// Calls the function `name` with `args` and returns its result.
+    ///
+    /// Functions declared without a result return the value of their `ret`
+    /// terminator, which is `0i32` for IR produced by the generator.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`Trap`] that stopped the program. The interpreter can be
+    /// used again after a trap; globals keep the values they had.
+    pub fn call(&mut self, name: &str, args: Vec<RuntimeValue>) -> Result<RuntimeValue, Trap> {
+        let span = SourceSpan::default();
+        let index =
+            *self.function_index.get(name).ok_or_else(|| Trap::unsupported(format!("no function '{name}'"), &span))?;
+        let result = self.enter(index, args, None, &span).and_then(|()| self.execute());
+        if result.is_err() {
+            while let Some(frame) = self.frames.pop() {
+                self.release(&frame);
+            }
+        }
+        result
+    }
+
+    /// Runs until the outermost frame returns.
+    fn execute(&mut self) -> Result<RuntimeValue, Trap> {
+        loop {
+            let frame = self.frames.last().expect("execute runs with a frame");
+            let (block, next) = (frame.block, frame.next);
+            self.tick(
+                block
+                    .instructions
+                    .get(next)
+                    .map_or_else(|| &block.terminator().debug_info.source_span, |inst| &inst.debug_info.source_span),
+            )?;
+            if let Some(inst) = block.instructions.get(next) {
+                self.frames.last_mut().expect("frame").next += 1;
+                self.execute_instruction(inst)?;
+            } else if let Some(value) = self.execute_terminator(block)? {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn tick(&mut self, span: &SourceSpan) -> Result<(), Trap> {
+        self.steps += 1;
+        match self.step_limit {
+            Some(limit) if self.steps > limit => {
+                Err(Trap::runtime(format!("step limit of {limit} executed instructions exceeded"), span))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Pushes the frame of a call to function `index`.
+    fn enter(
+        &mut self, index: usize, args: Vec<RuntimeValue>, result: Option<&'m Value>, span: &SourceSpan,
+    ) -> Result<(), Trap> {
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            return Err(Trap::runtime(format!("stack overflow: more than {MAX_CALL_DEPTH} nested calls"), span));
+        }
+        let function = self.functions[index].function;
+        if args.len() != function.parameters.len() {
+            return Err(Trap::unsupported(
+                format!(
+                    "function '{}' takes {} arguments but {} were given",
+                    function.name,
+                    function.parameters.len(),
+                    args.len()
+                ),
+                span,
+            ));
+        }
+        let block = function
+            .cfg
+            .get_entry_block()
+            .ok_or_else(|| Trap::unsupported(format!("function '{}' has no entry block", function.name), span))?;
+        let values = function.parameters.iter().map(|param| ValueKind::Local(param.name.clone())).zip(args).collect();
+        self.frames.push(Frame { function: index, values, block, next: 0, allocas: Vec::new(), result });
+        Ok(())
+    }
+
+    fn release(&mut self, frame: &Frame<'m>) {
+        for &allocation in &frame.allocas {
+            let allocation = &mut self.allocations[allocation as usize - 1];
+            allocation.live = false;
+            allocation.bytes = Vec::new();
+        }
+    }
+
+    fn frame(&self) -> &Frame<'m> {
+        self.frames.last().expect("instructions execute inside a frame")
+    }
+
+    fn frame_mut(&mut self) -> &mut Frame<'m> {
+        self.frames.last_mut().expect("instructions execute inside a frame")
+    }
+
+    fn define(&mut self, result: Option<&Value>, value: RuntimeValue) {
+        if let Some(result) = result {
+            self.frame_mut().values.insert(result.kind.clone(), value);
+        }
+    }
+
+    // ---------------------------------------------------------------------
+    // Instructions
+    // ---------------------------------------------------------------------
+
+    fn execute_instruction(&mut self, inst: &'m Instruction) -> Result<(), Trap> {
+        let span = &inst.debug_info.source_span;
+        let result = inst.result.as_ref();
+        match &inst.kind {
+            InstructionKind::Alloca { ty } => {
+                let pointer = self.allocate(self.size_of(ty), true);
+                self.frame_mut().allocas.push(pointer.allocation);
+                self.define(result, RuntimeValue::Pointer(pointer));
+            }
+            InstructionKind::Store { value, dest } => self.store(value, dest, span)?,
+            InstructionKind::Load { src, ty } => {
+                let src = self.pointer(src, span)?;
+                let ty = value_type(ty);
+                let value = if is_aggregate(ty) { RuntimeValue::Pointer(src) } else { self.read(src, ty, span)? };
+                self.define(result, value);
+            }
+            InstructionKind::Binary { op, left, right, ty } => {
+                let left = self.scalar(left, span)?;
+                let mut right = self.scalar(right, span)?;
+                if IrType::from(&right) != IrType::from(&left) {
+                    right = eval_cast(&right, &IrType::from(&left)).map_err(|e| Trap::eval(e, span))?;
+                }
+                let mut value = eval_binary(*op, &left, &right).map_err(|e| Trap::eval(e, span))?;
+                if !is_comparison(*op) {
+                    value = normalize(value, value_type(ty), span)?;
+                }
+                self.define(result, RuntimeValue::Scalar(value));
+            }
+            InstructionKind::Unary { op, operand, ty } => {
+                let operand = self.scalar(operand, span)?;
+                let value = eval_unary(*op, &operand).map_err(|e| Trap::eval(e, span))?;
+                self.define(result, RuntimeValue::Scalar(normalize(value, value_type(ty), span)?));
+            }
+            InstructionKind::Call { func, args, .. } => {
+                let callee = match self.operand(func, span)? {
+                    RuntimeValue::Function(name) => name,
+                    other => {
+                        return Err(Trap::unsupported(format!("call through {other}, which is not a function"), span));
+                    }
+                };
+                let index = *self
+                    .function_index
+                    .get(callee.as_ref())
+                    .ok_or_else(|| Trap::unsupported(format!("no function '{callee}'"), span))?;
+                let args = args.iter().map(|arg| self.operand(arg, span)).collect::<Result<Vec<_>, _>>()?;
+                self.enter(index, args, result, span)?;
+            }
+            InstructionKind::GetElementPtr { base, index, element_ty } => {
+                let base = self.pointer(base, span)?;
+                let index = integer(&self.scalar(index, span)?);
+                let size = i64::try_from(self.size_of(element_ty)).unwrap_or(i64::MAX);
+                let pointer = Pointer { offset: base.offset.wrapping_add(index.wrapping_mul(size)), ..base };
+                self.define(result, RuntimeValue::Pointer(pointer));
+            }
+            InstructionKind::Cast { kind, value, to_ty, .. } => {
+                let value = match self.operand(value, span)? {
+                    // Field accesses cast the `*u8` of a byte offset to the field's pointer type.
+                    RuntimeValue::Pointer(pointer)
+                        if matches!(to_ty, IrType::Pointer(_)) && !is_string_parse(*kind) =>
+                    {
+                        RuntimeValue::Pointer(pointer)
+                    }
+                    value => self.cast(*kind, value, value_type(to_ty), span)?,
+                };
+                self.define(result, value);
+            }
+            InstructionKind::Phi { .. } => {} // resolved when the block is entered
+            InstructionKind::Vector { op, .. } => {
+                return Err(Trap::unsupported(format!("vector instruction {op:?} is not supported"), span));
+            }
+        }
+        Ok(())
+    }
+
+    fn store(&mut self, value: &Value, dest: &'m Value, span: &SourceSpan) -> Result<(), Trap> {
+        let stored = self.operand(value, span)?;
+        if self.functions[self.frame().function].bound_by_store.contains(&dest.kind) {
+            self.frame_mut().values.insert(dest.kind.clone(), stored);
+            return Ok(());
+        }
+        let pointee = match &dest.ty {
+            IrType::Pointer(inner) => inner.as_ref(),
+            _ => value_type(&value.ty),
+        };
+        let dest = self.pointer(dest, span)?;
+        let size = self.size_of(pointee);
+        if is_aggregate(pointee) {
+            let RuntimeValue::Pointer(src) = stored else {
+                return Err(Trap::unsupported(format!("aggregate store of {stored}, which is not an address"), span));
+            };
+            let bytes = self.bytes(src, size, span)?.to_vec();
+            self.bytes_mut(dest, size, span)?.copy_from_slice(&bytes);
+            return Ok(());
+        }
+        let raw = match stored {
+            RuntimeValue::Scalar(value) => encode_scalar(&value),
+            RuntimeValue::Pointer(address) => address.encode().to_le_bytes(),
+            RuntimeValue::Function(name) => {
+                return Err(Trap::unsupported(format!("cannot store the address of function '{name}'"), span));
+            }
+        };
+        let size = size.min(8);
+        self.bytes_mut(dest, size, span)?.copy_from_slice(&raw[..size]);
+        Ok(())
+    }
+
+    fn cast(
+        &mut self, kind: CastKind, value: RuntimeValue, to: &IrType, span: &SourceSpan,
+    ) -> Result<RuntimeValue, Trap> {
+        let scalar = match value {
+            RuntimeValue::Pointer(pointer) if is_string_parse(kind) => {
+                return self.parse_string(kind, pointer, to, span);
+            }
+            RuntimeValue::Pointer(pointer) if is_pointer_like(to) => return Ok(RuntimeValue::Pointer(pointer)),
+            RuntimeValue::Pointer(pointer) => IrLiteralValue::U64(pointer.encode()),
+            RuntimeValue::Function(name) => {
+                return Err(Trap::unsupported(format!("cannot cast the address of function '{name}'"), span));
+            }
+            RuntimeValue::Scalar(value) if is_pointer_like(to) && !is_string_cast(kind) => {
+                return Ok(RuntimeValue::Pointer(Pointer::decode(integer(&value).cast_unsigned())));
+            }
+            RuntimeValue::Scalar(value) => value,
+        };
+        let eval = |value: &IrLiteralValue, to: &IrType| eval_cast(value, to).map_err(|e| Trap::eval(e, span));
+        let value = match kind {
+            CastKind::IntZeroExtend | CastKind::BoolToInt | CastKind::CharToInt => {
+                eval(&IrLiteralValue::U64(zero_extend(&scalar)), to)?
+            }
+            CastKind::IntSignExtend => eval(&IrLiteralValue::I64(sign_extend(&scalar)), to)?,
+            CastKind::IntToChar => {
+                let code = zero_extend(&scalar);
+                let char = u32::try_from(code)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| Trap::runtime(format!("{scalar} is not the code of a valid char"), span))?;
+                eval(&IrLiteralValue::Char(char), to)?
+            }
+            CastKind::Bitcast => match (scalar, to) {
+                (IrLiteralValue::F32(value), _) => eval(&IrLiteralValue::U32(value.to_bits()), to)?,
+                (IrLiteralValue::F64(value), _) => eval(&IrLiteralValue::U64(value.to_bits()), to)?,
+                #[allow(clippy::cast_possible_truncation)]
+                (value, IrType::F32) => IrLiteralValue::F32(f32::from_bits(zero_extend(&value) as u32)),
+                (value, IrType::F64) => IrLiteralValue::F64(f64::from_bits(zero_extend(&value))),
+                (value, _) => eval(&value, to)?,
+            },
+            CastKind::IntTruncate
+            | CastKind::IntBitcast
+            | CastKind::IntToFloat
+            | CastKind::FloatToInt
+            | CastKind::FloatTruncate
+            | CastKind::FloatExtend
+            | CastKind::IntToBool
+            | CastKind::BoolToFloat
+            | CastKind::FloatToBool => eval(&scalar, to)?,
+            CastKind::CharToString | CastKind::IntToString | CastKind::FloatToString | CastKind::BoolToString => {
+                let text = match scalar {
+                    IrLiteralValue::I8(v) => v.to_string(),
+                    IrLiteralValue::I16(v) => v.to_string(),
+                    IrLiteralValue::I32(v) => v.to_string(),
+                    IrLiteralValue::I64(v) => v.to_string(),
+                    IrLiteralValue::U8(v) => v.to_string(),
+                    IrLiteralValue::U16(v) => v.to_string(),
+                    IrLiteralValue::U32(v) => v.to_string(),
+                    IrLiteralValue::U64(v) => v.to_string(),
+                    IrLiteralValue::F32(v) => v.to_string(),
+                    IrLiteralValue::F64(v) => v.to_string(),
+                    IrLiteralValue::Bool(v) => v.to_string(),
+                    IrLiteralValue::Char(v) => v.to_string(),
+                };
+                return Ok(RuntimeValue::Pointer(self.new_string(&text)));
+            }
+            CastKind::StringToChar | CastKind::StringToInt | CastKind::StringToFloat | CastKind::StringToBool => {
+                return Err(Trap::unsupported(format!("cast {kind:?} of {scalar}, which is not a string"), span));
+            }
+        };
+        Ok(RuntimeValue::Scalar(value))
+    }
+
+    /// Converts the string at `pointer` to `to` for the `StringTo*` casts.
+    fn parse_string(
+        &self, kind: CastKind, pointer: Pointer, to: &IrType, span: &SourceSpan,
+    ) -> Result<RuntimeValue, Trap> {
+        let text = self.read_string(pointer, span)?;
+        let invalid = || Trap::runtime(format!("cannot convert \"{}\" to {to}", text.escape_default()), span);
+        let value = match kind {
+            CastKind::StringToChar => {
+                let mut chars = text.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(char), None) => IrLiteralValue::Char(char),
+                    _ => return Err(invalid()),
+                }
+            }
+            CastKind::StringToBool => IrLiteralValue::Bool(text.parse().map_err(|_| invalid())?),
+            CastKind::StringToFloat => {
+                let value: f64 = text.trim().parse().map_err(|_| invalid())?;
+                eval_cast(&IrLiteralValue::F64(value), to).map_err(|_| invalid())?
+            }
+            _ => {
+                let text = text.trim();
+                let value = if to.is_unsigned_integer() {
+                    IrLiteralValue::U64(text.parse().map_err(|_| invalid())?)
+                } else {
+                    IrLiteralValue::I64(text.parse().map_err(|_| invalid())?)
+                };
+                let converted = eval_cast(&value, to).map_err(|_| invalid())?;
+                // Reject values the target type cannot represent instead of wrapping them.
+                if eval_cast(&converted, &IrType::from(&value)).ok() != Some(value) {
+                    return Err(invalid());
+                }
+                converted
+            }
+        };
+        Ok(RuntimeValue::Scalar(value))
+    }
+
+    // ---------------------------------------------------------------------
+    // Terminators
+    // ---------------------------------------------------------------------
+
+    /// Executes the terminator of `block` and returns the result of the
+    /// outermost call once it returns.
+    fn execute_terminator(&mut self, block: &'m BasicBlock) -> Result<Option<RuntimeValue>, Trap> {
+        let terminator = block.terminator();
+        let span = &terminator.debug_info.source_span;
+        let target = match &terminator.kind {
+            TerminatorKind::Return { value, .. } => {
+                let value = self.operand(value, span)?;
+                let frame = self.frames.pop().expect("returning from a frame");
+                self.release(&frame);
+                if self.frames.is_empty() {
+                    return Ok(Some(value));
+                }
+                self.define(frame.result, value);
+                return Ok(None);
+            }
+            TerminatorKind::Branch { label } => label,
+            TerminatorKind::ConditionalBranch { condition, true_label, false_label } => {
+                let condition = self.scalar(condition, span)?;
+                if integer(&condition) != 0 { true_label } else { false_label }
+            }
+            TerminatorKind::Switch { value, default_label, cases, .. } => {
+                let value = integer(&self.scalar(value, span)?);
+                let mut target = default_label;
+                for (case, label) in cases {
+                    if integer(&self.scalar(case, span)?) == value {
+                        target = label;
+                        break;
+                    }
+                }
+                target
+            }
+            TerminatorKind::IndirectBranch { .. } => {
+                return Err(Trap::unsupported("indirect branches are not supported", span));
+            }
+            TerminatorKind::Unreachable => {
+                return Err(Trap::runtime(
+                    format!("reached the end of block '{}', marked unreachable", block.label),
+                    span,
+                ));
+            }
+        };
+        self.jump(&block.label, target, span)?;
+        Ok(None)
+    }
+
+    /// Moves to the block `target`, assigning its phis the values they take on
+    /// the edge coming from `pred`.
+    fn jump(&mut self, pred: &str, target: &str, span: &SourceSpan) -> Result<(), Trap> {
+        let function = &self.functions[self.frame().function];
+        let block = *function.blocks.get(target).ok_or_else(|| {
+            Trap::unsupported(
+                format!("branch to '{target}', which is not a block of '{}'", function.function.name),
+                span,
+            )
+        })?;
+        let mut assignments = Vec::new();
+        for inst in &block.instructions {
+            let InstructionKind::Phi { incoming, .. } = &inst.kind else { continue };
+            // SSA construction leaves phis of variables not yet declared on some
+            // edges without an incoming value; they stay undefined, like an
+            // undefined incoming value, and trap only if they are used.
+            let value = match incoming.iter().find(|(_, label)| label == pred) {
+                Some((value, _)) if !self.is_undefined(value) => {
+                    Some(self.operand(value, &inst.debug_info.source_span)?)
+                }
+                _ => None,
+            };
+            assignments.push((inst.result.as_ref(), value));
+        }
+        for (result, value) in assignments {
+            match (result, value) {
+                (Some(result), None) => {
+                    self.frame_mut().values.remove(&result.kind);
+                }
+                (result, Some(value)) => self.define(result, value),
+                (None, None) => {}
+            }
+        }
+        let frame = self.frame_mut();
+        frame.block = block;
+        frame.next = 0;
+        Ok(())
+    }
+
+    // ---------------------------------------------------------------------
+    // Operands
+    // ---------------------------------------------------------------------
+
+    fn operand(&mut self, value: &Value, span: &SourceSpan) -> Result<RuntimeValue, Trap> {
+        match &value.kind {
+            ValueKind::Literal(literal) => Ok(RuntimeValue::Scalar(*literal)),
+            ValueKind::Constant(constant) => self.materialize(constant, &value.ty, span),
+            ValueKind::Global(name) => {
+                if let Some((pointer, _)) = self.globals.get(name.as_ref()) {
+                    Ok(RuntimeValue::Pointer(*pointer))
+                } else if self.function_index.contains_key(name.as_ref()) {
+                    Ok(RuntimeValue::Function(name.clone()))
+                } else {
+                    Err(Trap::unsupported(format!("@{name} is neither a global nor a function"), span))
+                }
+            }
+            ValueKind::Local(_) | ValueKind::Temporary(_) => self
+                .frame()
+                .values
+                .get(&value.kind)
+                .cloned()
+                .ok_or_else(|| Trap::unsupported(format!("{value} is used before it is defined"), span)),
+        }
+    }
+
+    fn is_undefined(&self, value: &Value) -> bool {
+        matches!(value.kind, ValueKind::Local(_) | ValueKind::Temporary(_))
+            && !self.frame().values.contains_key(&value.kind)
+    }
+
+    fn scalar(&mut self, value: &Value, span: &SourceSpan) -> Result<IrLiteralValue, Trap> {
+        match self.operand(value, span)? {
+            RuntimeValue::Scalar(scalar) => Ok(scalar),
+            RuntimeValue::Pointer(pointer) => Ok(IrLiteralValue::I64(pointer.encode().cast_signed())),
+            RuntimeValue::Function(name) => {
+                Err(Trap::unsupported(format!("the address of function '{name}' is used as a number"), span))
+            }
+        }
+    }
+
+    fn pointer(&mut self, value: &Value, span: &SourceSpan) -> Result<Pointer, Trap> {
+        match self.operand(value, span)? {
+            RuntimeValue::Pointer(pointer) => Ok(pointer),
+            RuntimeValue::Scalar(scalar) if integer(&scalar) == 0 => Ok(Pointer::NULL),
+            other => Err(Trap::unsupported(format!("{value} holds {other}, which is not an address"), span)),
+        }
+    }
+
+    /// Creates the value of an aggregate or string constant in memory.
+    fn materialize(
+        &mut self, constant: &IrConstantValue, ty: &IrType, span: &SourceSpan,
+    ) -> Result<RuntimeValue, Trap> {
+        let elements = match constant {
+            IrConstantValue::String { string } => {
+                if let Some(pointer) = self.strings.get(string) {
+                    return Ok(RuntimeValue::Pointer(*pointer));
+                }
+                let pointer = self.new_string(string);
+                self.allocations[pointer.allocation as usize - 1].writable = false;
+                self.strings.insert(string.clone(), pointer);
+                return Ok(RuntimeValue::Pointer(pointer));
+            }
+            IrConstantValue::Array { elements } | IrConstantValue::Struct { elements, .. } => elements,
+        };
+        let ty = value_type(ty);
+        let offsets: Vec<u64> = match ty {
+            IrType::Array(element, _) => {
+                let size = self.layout.size_of(element);
+                (0..elements.len() as u64).map(|i| i * size).collect()
+            }
+            IrType::Struct(_, fields, _) => self.layout.struct_layout(fields).field_offsets,
+            other => return Err(Trap::unsupported(format!("aggregate constant of type {other}"), span)),
+        };
+        let base = self.allocate(self.size_of(ty), true);
+        for (element, offset) in elements.iter().zip(offsets) {
+            let value = self.operand(element, span)?;
+            let dest = Pointer { offset: i64::try_from(offset).unwrap_or(i64::MAX), ..base };
+            let element_ty = value_type(&element.ty);
+            let size = self.size_of(element_ty);
+            match value {
+                RuntimeValue::Pointer(src) if is_aggregate(element_ty) => {
+                    let bytes = self.bytes(src, size, span)?.to_vec();
+                    self.bytes_mut(dest, size, span)?.copy_from_slice(&bytes);
+                }
+                RuntimeValue::Pointer(pointer) => {
+                    self.bytes_mut(dest, 8, span)?.copy_from_slice(&pointer.encode().to_le_bytes());
+                }
+                RuntimeValue::Scalar(scalar) => {
+                    let size = size.min(8);
+                    self.bytes_mut(dest, size, span)?.copy_from_slice(&encode_scalar(&scalar)[..size]);
+                }
+                RuntimeValue::Function(name) => {
+                    return Err(Trap::unsupported(format!("cannot store the address of function '{name}'"), span));
+                }
+            }
+        }
+        Ok(RuntimeValue::Pointer(base))
+    }
+
+    // ---------------------------------------------------------------------
+    // Memory
+    // ---------------------------------------------------------------------
+
+    fn size_of(&self, ty: &IrType) -> usize {
+        usize::try_from(self.layout.size_of(ty)).unwrap_or(usize::MAX)
+    }
+
+    fn allocate(&mut self, size: usize, writable: bool) -> Pointer {
+        self.allocations.push(Allocation { bytes: vec![0; size], live: true, writable });
+        Pointer { allocation: u32::try_from(self.allocations.len()).unwrap_or(u32::MAX), offset: 0 }
+    }
+
+    /// Allocates a NUL-terminated copy of `text`.
+    fn new_string(&mut self, text: &str) -> Pointer {
+        let pointer = self.allocate(text.len() + 1, true);
+        self.allocations[pointer.allocation as usize - 1].bytes[..text.len()].copy_from_slice(text.as_bytes());
+        pointer
+    }
+
+    fn read_string(&self, pointer: Pointer, span: &SourceSpan) -> Result<String, Trap> {
+        let allocation = self.allocation(pointer, span)?;
+        let start = usize::try_from(pointer.offset).ok().filter(|&start| start <= allocation.bytes.len());
+        let bytes = &allocation.bytes[start.ok_or_else(|| out_of_bounds(pointer, 1, allocation, span))?..];
+        let end = bytes
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or_else(|| Trap::runtime("string is not terminated by a NUL byte inside its allocation", span))?;
+        Ok(String::from_utf8_lossy(&bytes[..end]).into_owned())
+    }
+
+    fn allocation(&self, pointer: Pointer, span: &SourceSpan) -> Result<&Allocation, Trap> {
+        if pointer.is_null() {
+            return Err(Trap::runtime("null pointer dereference", span));
+        }
+        let allocation = self
+            .allocations
+            .get(pointer.allocation as usize - 1)
+            .ok_or_else(|| Trap::runtime(format!("access through the invalid address {pointer}"), span))?;
+        if !allocation.live {
+            return Err(Trap::runtime("access to the stack memory of a function that has returned", span));
+        }
+        Ok(allocation)
+    }
+
+    fn bytes(&self, pointer: Pointer, size: usize, span: &SourceSpan) -> Result<&[u8], Trap> {
+        let allocation = self.allocation(pointer, span)?;
+        let range =
+            byte_range(pointer, size, allocation).ok_or_else(|| out_of_bounds(pointer, size, allocation, span))?;
+        Ok(&allocation.bytes[range])
+    }
+
+    fn bytes_mut(&mut self, pointer: Pointer, size: usize, span: &SourceSpan) -> Result<&mut [u8], Trap> {
+        let allocation = self.allocation(pointer, span)?;
+        let range =
+            byte_range(pointer, size, allocation).ok_or_else(|| out_of_bounds(pointer, size, allocation, span))?;
+        if !allocation.writable {
+            return Err(Trap::runtime("write to a string literal", span));
+        }
+        Ok(&mut self.allocations[pointer.allocation as usize - 1].bytes[range])
+    }
+
+    /// Reads a scalar or pointer of type `ty` at `pointer`.
+    fn read(&self, pointer: Pointer, ty: &IrType, span: &SourceSpan) -> Result<RuntimeValue, Trap> {
+        let size = self.size_of(ty).min(8);
+        let mut raw = [0; 8];
+        raw[..size].copy_from_slice(self.bytes(pointer, size, span)?);
+        decode_scalar(u64::from_le_bytes(raw), ty, span)
+    }
+}
+
+fn byte_range(pointer: Pointer, size: usize, allocation: &Allocation) -> Option<std::ops::Range<usize>> {
+    let start = usize::try_from(pointer.offset).ok()?;
+    let end = start.checked_add(size)?;
+    (end <= allocation.bytes.len()).then_some(start..end)
+}
+
+fn out_of_bounds(pointer: Pointer, size: usize, allocation: &Allocation, span: &SourceSpan) -> Trap {
+    Trap::runtime(
+        format!(
+            "out-of-bounds access of {size} bytes at offset {} of a {}-byte allocation",
+            pointer.offset,
+            allocation.bytes.len()
+        ),
+        span,
+    )
+}
+
+/// Strips the pointer type that values read from a `var` keep after SSA
+/// construction, like the instruction selector does.
+fn value_type(ty: &IrType) -> &IrType {
+    match ty {
+        IrType::Pointer(inner)
+            if !matches!(inner.as_ref(), IrType::Pointer(_) | IrType::Array(..) | IrType::Struct(..)) =>
+        {
+            inner
+        }
+        other => other,
+    }
+}
+
+const fn is_aggregate(ty: &IrType) -> bool {
+    matches!(ty, IrType::Array(..) | IrType::Struct(..))
+}
+
+const fn is_pointer_like(ty: &IrType) -> bool {
+    matches!(ty, IrType::Pointer(_) | IrType::String | IrType::Custom(..) | IrType::Array(..) | IrType::Struct(..))
+}
+
+const fn is_string_cast(kind: CastKind) -> bool {
+    matches!(kind, CastKind::CharToString | CastKind::IntToString | CastKind::FloatToString | CastKind::BoolToString)
+}
+
+const fn is_string_parse(kind: CastKind) -> bool {
+    matches!(kind, CastKind::StringToChar | CastKind::StringToInt | CastKind::StringToFloat | CastKind::StringToBool)
+}
+
+const fn is_comparison(op: IrBinaryOp) -> bool {
+    matches!(
+        op,
+        IrBinaryOp::Equal
+            | IrBinaryOp::NotEqual
+            | IrBinaryOp::Less
+            | IrBinaryOp::LessEqual
+            | IrBinaryOp::Greater
+            | IrBinaryOp::GreaterEqual
+    )
+}
+
+/// Converts the result of an operation to the type the instruction declares,
+/// as the backend does when it writes the result register back.
+fn normalize(value: IrLiteralValue, ty: &IrType, span: &SourceSpan) -> Result<IrLiteralValue, Trap> {
+    let primitive = matches!(
+        ty,
+        IrType::I8
+            | IrType::I16
+            | IrType::I32
+            | IrType::I64
+            | IrType::U8
+            | IrType::U16
+            | IrType::U32
+            | IrType::U64
+            | IrType::F32
+            | IrType::F64
+            | IrType::Bool
+            | IrType::Char
+    );
+    if !primitive || IrType::from(&value) == *ty {
+        return Ok(value);
+    }
+    eval_cast(&value, ty).map_err(|e| Trap::eval(e, span))
+}
+
+/// The canonical 64-bit register form of a non-float value, sign-extended for
+/// signed integers.
+fn integer(value: &IrLiteralValue) -> i64 {
+    match *value {
+        IrLiteralValue::I8(v) => i64::from(v),
+        IrLiteralValue::I16(v) => i64::from(v),
+        IrLiteralValue::I32(v) => i64::from(v),
+        IrLiteralValue::I64(v) => v,
+        IrLiteralValue::U64(v) => v.cast_signed(),
+        IrLiteralValue::F32(v) => i64::from(v.to_bits()),
+        IrLiteralValue::F64(v) => v.to_bits().cast_signed(),
+        other => zero_extend(&other).cast_signed(),
+    }
+}
+
+/// The bits of a value, zero-extended from its own width.
+fn zero_extend(value: &IrLiteralValue) -> u64 {
+    match *value {
+        IrLiteralValue::I8(v) => u64::from(v.cast_unsigned()),
+        IrLiteralValue::I16(v) => u64::from(v.cast_unsigned()),
+        IrLiteralValue::I32(v) => u64::from(v.cast_unsigned()),
+        IrLiteralValue::I64(v) => v.cast_unsigned(),
+        IrLiteralValue::U8(v) => u64::from(v),
+        IrLiteralValue::U16(v) => u64::from(v),
+        IrLiteralValue::U32(v) => u64::from(v),
+        IrLiteralValue::U64(v) => v,
+        IrLiteralValue::F32(v) => u64::from(v.to_bits()),
+        IrLiteralValue::F64(v) => v.to_bits(),
+        IrLiteralValue::Bool(v) => u64::from(v),
+        IrLiteralValue::Char(v) => u64::from(u32::from(v)),
+    }
+}
+
+/// The bits of a value, sign-extended from its own width.
+#[allow(clippy::cast_possible_truncation)]
+fn sign_extend(value: &IrLiteralValue) -> i64 {
+    match *value {
+        IrLiteralValue::U8(v) => i64::from(v.cast_signed()),
+        IrLiteralValue::U16(v) => i64::from(v.cast_signed()),
+        IrLiteralValue::U32(v) => i64::from(v.cast_signed()),
+        IrLiteralValue::Char(v) => i64::from(u32::from(v).cast_signed()),
+        IrLiteralValue::Bool(v) => i64::from(v),
+        other => integer(&other),
+    }
+}
+
+/// Little-endian bytes of a scalar, as the backend stores it.
+fn encode_scalar(value: &IrLiteralValue) -> [u8; 8] {
+    zero_extend(value).to_le_bytes()
+}
+
+/// Decodes the `raw` bytes of a value of type `ty` read from memory.
+#[allow(clippy::cast_possible_truncation)]
+fn decode_scalar(raw: u64, ty: &IrType, span: &SourceSpan) -> Result<RuntimeValue, Trap> {
+    let value =
+        match ty {
+            IrType::F32 => IrLiteralValue::F32(f32::from_bits(raw as u32)),
+            IrType::F64 => IrLiteralValue::F64(f64::from_bits(raw)),
+            IrType::Bool => IrLiteralValue::Bool(raw & 0xff != 0),
+            IrType::Char => IrLiteralValue::Char(char::from_u32(raw as u32).ok_or_else(|| {
+                Trap::runtime(format!("{:#x} read from memory is not a valid char", raw as u32), span)
+            })?),
+            ty if is_pointer_like(ty) => return Ok(RuntimeValue::Pointer(Pointer::decode(raw))),
+            ty => eval_cast(&IrLiteralValue::U64(raw), ty).map_err(|e| Trap::eval(e, span))?,
+        };
+    Ok(RuntimeValue::Scalar(value))
+}
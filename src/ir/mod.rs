@@ -12,13 +12,16 @@
 mod access_control;
 pub mod basic_block;
 pub mod cfg;
+pub mod const_eval;
 pub mod data_layout;
 pub mod dominance;
 pub mod function;
 pub mod generator;
 pub mod instruction;
+pub mod interpreter;
 pub mod module;
 pub mod optimizer;
+pub mod parser;
 pub mod platform;
 pub mod scope;
 pub mod scope_manager;
@@ -28,6 +31,7 @@ pub mod type_promotion;
 pub mod type_promotion_engine;
 pub mod types;
 pub mod value;
+pub mod verifier;
 
 pub use access_control::{AccessController, AccessRules, Operation};
 pub use basic_block::BasicBlock;
@@ -36,8 +40,10 @@ pub use data_layout::DataLayout;
 pub use dominance::DominanceInfo;
 pub use function::{Function, FunctionAttributes, IrParameter, ParamAttributes};
 pub use instruction::{CastKind, Instruction, InstructionKind, IrBinaryOp, IrUnaryOp, VectorOp};
-pub use module::{Module, TargetTriple};
-pub use optimizer::{Phase, run_pipeline};
+pub use interpreter::{Interpreter, RuntimeValue, Trap};
+pub use module::{GlobalVariable, Module, TargetTriple};
+pub use optimizer::{Phase, run_pipeline, run_verified_pipeline};
+pub use parser::parse_module;
 pub use platform::platform_config_for;
 pub use scope::Scope;
 pub use scope_manager::ScopeManager;
@@ -50,3 +56,4 @@ pub use type_promotion::{
 pub use type_promotion_engine::TypePromotionEngine;
 pub use types::{IrType, ResourceId, ScopeId};
 pub use value::{IrConstantValue, IrLiteralValue, Value, ValueDebugInfo, ValueKind};
+pub use verifier::{IrVerifier, verify_module};
@@ -1,5 +1,5 @@
 // src/ir/module.rs
-use super::{Function, ScopeId};
+use super::{Function, IrLiteralValue, IrType, ScopeId};
 use crate::ir::data_layout::DataLayout;
 use std::fmt;
 use std::sync::Arc;
@@ -69,6 +69,40 @@ impl fmt::Display for TargetTriple {
     }
 }
 
+/// A variable declared at the top level of a program.
+///
+/// Globals live in the data sections of the generated code; functions access
+/// them through the address `@name`, of type `*ty`, with `load` and `store`,
+/// and index global arrays with `getelementptr`. Scalar top-level constants
+/// have no storage: their uses are replaced by their value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobalVariable {
+    pub name: Arc<str>,
+    pub ty: IrType,
+    /// Initial value, computed at compile time: one literal for a scalar, the
+    /// elements in order for an array.
+    pub initializer: Vec<IrLiteralValue>,
+}
+
+impl fmt::Display for GlobalVariable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "global @{}: {} = ", self.name, self.ty)?;
+        if !matches!(self.ty, IrType::Array(..))
+            && let Some(value) = self.initializer.first()
+        {
+            return value.fmt(f);
+        }
+        f.write_str("[")?;
+        for (i, value) in self.initializer.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            value.fmt(f)?;
+        }
+        f.write_str("]")
+    }
+}
+
 /// Represents an IR (Intermediate Representation) module.
 ///
 /// A module is the top-level container for all IR constructs including functions,
@@ -79,6 +113,7 @@ impl fmt::Display for TargetTriple {
 ///
 /// * `name` - Module identifier, typically the source file name
 /// * `functions` - All function definitions in this module
+/// * `globals` - Top-level variables, in declaration order
 /// * `root_scope` - Optional root scope ID for symbol resolution
 /// * `data_layout` - Target-specific data layout specification
 /// * `target_triple` - Target platform triple for code generation
@@ -101,6 +136,7 @@ impl fmt::Display for TargetTriple {
 pub struct Module {
     pub name: Arc<str>,
     pub functions: Vec<Function>,
+    pub globals: Vec<GlobalVariable>,
     root_scope: Option<ScopeId>, // Root scope ID for the module settable only at creation
     pub data_layout: DataLayout,
     pub target_triple: TargetTriple,
@@ -119,6 +155,7 @@ impl Module {
         Self {
             name: name.into(),
             functions: Vec::new(),
+            globals: Vec::new(),
             data_layout: platform.data_layout,
             target_triple: platform.target_triple,
             root_scope,
@@ -130,6 +167,17 @@ impl Module {
         self.functions.push(function);
     }
 
+    /// Adds a global variable to the module.
+    pub fn add_global(&mut self, global: GlobalVariable) {
+        self.globals.push(global);
+    }
+
+    /// Finds a global variable by name.
+    #[must_use]
+    pub fn get_global(&self, name: &str) -> Option<&GlobalVariable> {
+        self.globals.iter().find(|global| global.name.as_ref() == name)
+    }
+
     /// Sets the data layout.
     pub const fn set_data_layout(&mut self, layout: DataLayout) {
         self.data_layout = layout;
@@ -219,6 +267,9 @@ impl fmt::Display for Module {
         } else {
             writeln!(f, "  // root_scope: none")?;
         }*/
+        for global in &self.globals {
+            writeln!(f, "  {global}")?;
+        }
 
         if self.functions.is_empty() {
             writeln!(f, "  // No functions")?;
@@ -4,6 +4,7 @@
 //! compile-time value states during sparse conditional constant propagation.
 
 use crate::ir::IrType;
+use crate::ir::value::IrLiteralValue;
 
 /// Represents the compile-time state of an SSA value in the lattice
 ///
@@ -98,6 +99,25 @@ impl LatticeValue {
     }
 }
 
+impl From<&IrLiteralValue> for ConstantValue {
+    fn from(literal: &IrLiteralValue) -> Self {
+        match *literal {
+            IrLiteralValue::I8(v) => Self::I8(v),
+            IrLiteralValue::I16(v) => Self::I16(v),
+            IrLiteralValue::I32(v) => Self::I32(v),
+            IrLiteralValue::I64(v) => Self::I64(v),
+            IrLiteralValue::U8(v) => Self::U8(v),
+            IrLiteralValue::U16(v) => Self::U16(v),
+            IrLiteralValue::U32(v) => Self::U32(v),
+            IrLiteralValue::U64(v) => Self::U64(v),
+            IrLiteralValue::F32(v) => Self::F32(v),
+            IrLiteralValue::F64(v) => Self::F64(v),
+            IrLiteralValue::Bool(v) => Self::Bool(v),
+            IrLiteralValue::Char(v) => Self::Char(v),
+        }
+    }
+}
+
 impl ConstantValue {
     /// Gets the IR type of this constant value
     #[must_use]
@@ -1,4 +1,7 @@
-use crate::ir::{Function, Module, Phase};
+use crate::ir::optimizer::PassReport;
+use crate::ir::value::ValueId;
+use crate::ir::{Function, InstructionKind, Module, Phase, TerminatorKind, Value};
+use std::sync::Arc;
 
 use super::propagator::SCCPropagator;
 use super::rewriter::IRRewriter;
@@ -26,7 +29,8 @@ impl Default for SCCPConfig {
 /// Statistics tracked during SCCP optimization
 ///
 /// Provides metrics for evaluating optimization effectiveness.
-/// All counters are cumulative across multiple function optimizations.
+/// All counters are cumulative across the functions optimized since the
+/// last [`Phase::run`], which starts from zero.
 #[derive(Debug, Clone, Default)]
 pub struct OptimizationStats {
     /// Number of constants propagated and folded
@@ -123,10 +127,9 @@ impl ConstantFoldingOptimizer {
             .map_err(|e| format!("SCCP propagation failed: {e}"))?;
 
         // Phase 2: Rewrite IR based on SCCP results
-        let rewriter = IRRewriter::new();
-
-        // TODO: Implement actual IR transformation in next iteration
-        // For now, we just track that we ran the analysis
+        let mut rewriter = IRRewriter::new();
+        Self::resolve_branches(function, &propagator, &mut rewriter);
+        Self::simplify_phis(function, &propagator, &mut rewriter);
 
         // Update statistics
         let rewriter_stats = rewriter.into_stats();
@@ -140,19 +143,173 @@ impl ConstantFoldingOptimizer {
     }
 }
 
+impl ConstantFoldingOptimizer {
+    /// Turns conditional branches and switches on constant values into
+    /// unconditional branches, removing the edges not taken and their phi
+    /// operands.
+    ///
+    /// Blocks left without predecessors are removed by dead code elimination.
+    fn resolve_branches(function: &mut Function, propagator: &SCCPropagator, rewriter: &mut IRRewriter) {
+        let resolved: Vec<_> = function
+            .cfg
+            .blocks()
+            .filter_map(|block| {
+                let (taken, targets) = match &block.terminator().kind {
+                    TerminatorKind::ConditionalBranch { condition, true_label, false_label } => {
+                        let is_true = propagator.value_lattice(condition).as_constant()?.as_bool()?;
+                        let taken = if is_true { true_label } else { false_label };
+                        (taken, vec![true_label, false_label])
+                    }
+                    TerminatorKind::Switch { value, default_label, cases, .. } => {
+                        let selector = propagator.value_lattice(value).as_constant()?.clone();
+                        let taken = cases
+                            .iter()
+                            .find(|(case, _)| propagator.value_lattice(case).as_constant() == Some(&selector))
+                            .map_or(default_label, |(_, label)| label);
+                        (taken, cases.iter().map(|(_, label)| label).chain([default_label]).collect())
+                    }
+                    _ => return None,
+                };
+                let mut untaken: Vec<Arc<str>> = Vec::new();
+                for target in targets {
+                    if target != taken && !untaken.contains(target) {
+                        untaken.push(target.clone());
+                    }
+                }
+                Some((block.label.clone(), taken.clone(), untaken))
+            })
+            .collect();
+
+        for (label, taken, untaken) in resolved {
+            if let Some(block) = function.cfg.get_block_mut(&label) {
+                block.terminator_mut().kind = TerminatorKind::Branch { label: taken };
+            }
+            for target in untaken {
+                function.cfg.disconnect_blocks(&label, &target);
+                if let Some(block) = function.cfg.get_block_mut(&target) {
+                    for instruction in &mut block.instructions {
+                        if let InstructionKind::Phi { incoming, .. } = &mut instruction.kind {
+                            incoming.retain(|(_, predecessor)| **predecessor != *label);
+                        }
+                    }
+                }
+            }
+            rewriter.increment_branches_resolved();
+        }
+    }
+
+    /// Replaces the phi nodes of reachable blocks that evaluate to a constant,
+    /// or that are left with a single operand, by that value.
+    fn simplify_phis(function: &mut Function, propagator: &SCCPropagator, rewriter: &mut IRRewriter) {
+        let mut replacements: Vec<(ValueId, Value)> = Vec::new();
+        for node_idx in function.cfg.graph().node_indices() {
+            if !propagator.is_block_executable(function, node_idx.index()) {
+                continue;
+            }
+            for instruction in &function.cfg.graph()[node_idx].instructions {
+                let (InstructionKind::Phi { ty, incoming }, Some(result)) = (&instruction.kind, &instruction.result)
+                else {
+                    continue;
+                };
+                let constant = propagator
+                    .value_lattice(result)
+                    .as_constant()
+                    .and_then(|constant| rewriter.create_constant_value(constant, ty).ok());
+                let replacement = match (constant, incoming.as_slice()) {
+                    (Some(constant), _) => constant,
+                    (None, [(value, _)]) => value.clone(),
+                    (None, _) => continue,
+                };
+                rewriter.increment_phi_simplified();
+                replacements.push((result.id, replacement));
+            }
+        }
+
+        for i in 0..replacements.len() {
+            let (id, replacement) = replacements[i].clone();
+            // A phi may feed another simplified phi
+            for (_, later) in &mut replacements[i + 1..] {
+                if later.id == id {
+                    *later = replacement.clone();
+                }
+            }
+            replace_uses(function, id, &replacement);
+        }
+
+        for block in function.cfg.blocks_mut() {
+            block.instructions.retain(|instruction| {
+                instruction.result.as_ref().is_none_or(|result| replacements.iter().all(|(id, _)| *id != result.id))
+            });
+        }
+    }
+}
+
+/// Replaces every use of the value `id` in `function` with `replacement`.
+fn replace_uses(function: &mut Function, id: ValueId, replacement: &Value) {
+    let mut replace = |value: &mut Value| {
+        if value.id == id {
+            *value = replacement.clone();
+        }
+    };
+    for block in function.cfg.blocks_mut() {
+        for instruction in &mut block.instructions {
+            match &mut instruction.kind {
+                InstructionKind::Alloca { .. } => {}
+                InstructionKind::Store { value, dest } => {
+                    replace(value);
+                    replace(dest);
+                }
+                InstructionKind::Load { src: value, .. }
+                | InstructionKind::Unary { operand: value, .. }
+                | InstructionKind::Cast { value, .. } => replace(value),
+                InstructionKind::Binary { left, right, .. } => {
+                    replace(left);
+                    replace(right);
+                }
+                InstructionKind::GetElementPtr { base, index, .. } => {
+                    replace(base);
+                    replace(index);
+                }
+                InstructionKind::Call { func, args, .. } => std::iter::once(func).chain(args).for_each(&mut replace),
+                InstructionKind::Phi { incoming, .. } => incoming.iter_mut().for_each(|(value, _)| replace(value)),
+                InstructionKind::Vector { operands, .. } => operands.iter_mut().for_each(&mut replace),
+            }
+        }
+        match &mut block.terminator_mut().kind {
+            TerminatorKind::Return { value, .. }
+            | TerminatorKind::ConditionalBranch { condition: value, .. }
+            | TerminatorKind::IndirectBranch { address: value, .. } => replace(value),
+            TerminatorKind::Switch { value, cases, .. } => {
+                std::iter::once(value).chain(cases.iter_mut().map(|(case, _)| case)).for_each(&mut replace);
+            }
+            TerminatorKind::Branch { .. } | TerminatorKind::Unreachable => {}
+        }
+    }
+}
+
 impl Phase for ConstantFoldingOptimizer {
     fn name(&self) -> &'static str {
         "Constant Folding Optimizer (SCCP)"
     }
 
     fn run(&mut self, ir: &mut Module) {
+        self.stats = OptimizationStats::default();
+
         // Optimize each function in the module
         for function in &mut ir.functions {
-            if let Err(e) = self.optimize_function(function) {
+            if let Err(e) = self.optimize_function(function)
+                && self.config.verbose
+            {
                 eprintln!("Error optimizing function {}: {}", function.name, e);
             }
         }
 
-        println!("Total number of instructions after constant folding: {}", ir.count_instructions());
+        if self.config.verbose {
+            eprintln!("Total number of instructions after constant folding: {}", ir.count_instructions());
+        }
+    }
+
+    fn report_stats(&self, report: &mut PassReport) {
+        report.add_sccp(&self.stats);
     }
 }
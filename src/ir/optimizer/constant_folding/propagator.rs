@@ -347,13 +347,23 @@ impl SCCPropagator {
     ///
     /// Implements FR-002 through FR-008 for binary operations.
     fn visit_instruction(
-        &mut self, _function: &crate::ir::Function, block_idx: usize, _instr_idx: usize,
+        &mut self, function: &crate::ir::Function, block_idx: usize, _instr_idx: usize,
         instruction: &crate::ir::Instruction,
     ) -> Result<(), SCCPError> {
         use super::evaluator::ConstantEvaluator;
         use super::lattice::ConstantValue;
         use crate::ir::instruction::InstructionKind;
 
+        // After SSA renaming, a store to a temporary defines that temporary
+        if let InstructionKind::Store { value, dest } = &instruction.kind
+            && matches!(dest.kind, crate::ir::value::ValueKind::Temporary(_))
+        {
+            let dest_key = Self::value_id_to_key(&dest.id);
+            let stored = self.lattice.get(dest_key).meet(&self.value_lattice(value));
+            self.update_lattice_value(dest_key, stored);
+            return Ok(());
+        }
+
         // Only process instructions with results
         let Some(result_value) = &instruction.result else { return Ok(()) };
 
@@ -365,8 +375,8 @@ impl SCCPropagator {
                 use crate::ir::instruction::IrBinaryOp;
 
                 // Get lattice values for operands
-                let left_lattice = self.lattice.get(Self::value_id_to_key(&left.id));
-                let right_lattice = self.lattice.get(Self::value_id_to_key(&right.id));
+                let left_lattice = self.value_lattice(left);
+                let right_lattice = self.value_lattice(right);
 
                 // If either operand is Bottom, result is Bottom
                 if left_lattice.is_bottom() || right_lattice.is_bottom() {
@@ -439,11 +449,11 @@ impl SCCPropagator {
             }
             InstructionKind::Phi { incoming, .. } => {
                 // T068-T069: Phi node evaluation with executable edge filtering
-                self.eval_phi_node(block_idx, incoming)?
+                self.eval_phi_node(function, block_idx, incoming)?
             }
             _ => {
-                // Other instruction types not yet implemented
-                return Ok(());
+                // Other instruction types are not evaluated: their results vary at runtime
+                LatticeValue::Top
             }
         };
 
@@ -465,6 +475,13 @@ impl SCCPropagator {
                     self.visit_instruction(function, node_idx.index(), instr_idx, instruction)?;
                 }
             }
+
+            // Branches of reachable blocks may now take other edges
+            if self.is_block_executable(function, node_idx.index())
+                && Self::terminator_value(&block.terminator).is_some_and(|v| Self::value_id_to_key(&v.id) == value_id)
+            {
+                self.visit_terminator(function, node_idx.index(), &block.terminator)?;
+            }
         }
 
         Ok(())
@@ -506,7 +523,7 @@ impl SCCPropagator {
 
             // Conditional branch - evaluate condition (T053)
             TerminatorKind::ConditionalBranch { condition, true_label, false_label } => {
-                let cond_lattice = self.lattice.get(Self::value_id_to_key(&condition.id));
+                let cond_lattice = self.value_lattice(condition);
 
                 match cond_lattice {
                     LatticeValue::Bottom => {
@@ -551,7 +568,7 @@ impl SCCPropagator {
 
             // Switch statement - evaluate selector (T057-T058)
             TerminatorKind::Switch { value, default_label, cases, .. } => {
-                let value_lattice = self.lattice.get(Self::value_id_to_key(&value.id));
+                let value_lattice = self.value_lattice(value);
 
                 match value_lattice {
                     LatticeValue::Bottom => {
@@ -563,7 +580,7 @@ impl SCCPropagator {
                         let mut matched = false;
                         for (case_value, case_label) in cases {
                             // Compare constant values
-                            let case_lattice = self.lattice.get(Self::value_id_to_key(&case_value.id));
+                            let case_lattice = self.value_lattice(case_value);
                             if let LatticeValue::Constant(ref case_const) = case_lattice
                                 && const_val == case_const
                             {
@@ -635,29 +652,19 @@ impl SCCPropagator {
     /// The computed lattice value for this phi node
     #[allow(clippy::unnecessary_wraps)]
     fn eval_phi_node(
-        &self, block_id: usize, incoming: &[(crate::ir::Value, String)],
+        &self, function: &Function, block_id: usize, incoming: &[(crate::ir::Value, String)],
     ) -> Result<LatticeValue, SCCPError> {
         use super::lattice::LatticeValue;
 
         // Collect lattice values from executable predecessors only
-        let mut executable_values = Vec::new();
-
-        for (_value, _pred_label) in incoming {
-            // Check if the edge from this predecessor is executable
-            // We need to find the predecessor block index by label
-            // For now, we'll use a simplified approach: check if any edge to this block is executable
-            // from a block with a matching label
-
-            // This is a simplified implementation - a full implementation would need
-            // to map predecessor labels to block indices
-            let has_executable_pred = self.executable_edges.has_executable_predecessor(block_id);
-
-            if has_executable_pred {
-                // Get the lattice value for this incoming value
-                // Note: This is simplified - full implementation needs proper value tracking
-                executable_values.push(LatticeValue::Top);
-            }
-        }
+        let executable_values: Vec<LatticeValue> = incoming
+            .iter()
+            .filter(|(_, pred_label)| {
+                self.find_block_by_label(function, pred_label)
+                    .is_some_and(|pred_idx| self.executable_edges.is_executable(&CFGEdge::new(pred_idx, block_id)))
+            })
+            .map(|(value, _)| self.value_lattice(value))
+            .collect();
 
         // Compute result based on number of executable predecessors
         match executable_values.len() {
@@ -680,6 +687,43 @@ impl SCCPropagator {
         }
     }
 
+    /// Returns the lattice value of an operand.
+    ///
+    /// Literals are constants and temporaries carry the value computed for
+    /// their definition; parameters, globals and other named values vary at
+    /// runtime.
+    #[must_use]
+    pub fn value_lattice(&self, value: &crate::ir::Value) -> LatticeValue {
+        use super::lattice::ConstantValue;
+        use crate::ir::value::ValueKind;
+
+        match &value.kind {
+            ValueKind::Literal(literal) => LatticeValue::Constant(ConstantValue::from(literal)),
+            ValueKind::Temporary(_) => self.lattice.get(Self::value_id_to_key(&value.id)),
+            _ => LatticeValue::Top,
+        }
+    }
+
+    /// Whether the block is the entry block or has an executable incoming edge.
+    #[must_use]
+    pub fn is_block_executable(&self, function: &Function, block_id: usize) -> bool {
+        function.cfg.get_entry_block_index().is_some_and(|entry| entry.index() == block_id)
+            || self.executable_edges.has_executable_predecessor(block_id)
+    }
+
+    /// The value a terminator branches on or returns, if any.
+    const fn terminator_value(terminator: &Terminator) -> Option<&crate::ir::Value> {
+        use crate::ir::terminator::TerminatorKind;
+
+        match &terminator.kind {
+            TerminatorKind::Return { value, .. }
+            | TerminatorKind::ConditionalBranch { condition: value, .. }
+            | TerminatorKind::IndirectBranch { address: value, .. }
+            | TerminatorKind::Switch { value, .. } => Some(value),
+            TerminatorKind::Branch { .. } | TerminatorKind::Unreachable => None,
+        }
+    }
+
     /// Helper to find a basic block by its label
     #[allow(clippy::unused_self)]
     fn find_block_by_label(&self, function: &Function, label: &str) -> Option<usize> {
@@ -717,6 +761,9 @@ impl SCCPropagator {
                 Self::value_id_to_key(&value.id) == value_id || Self::value_id_to_key(&dest.id) == value_id
             }
             InstructionKind::Load { src, .. } => Self::value_id_to_key(&src.id) == value_id,
+            InstructionKind::Phi { incoming, .. } => {
+                incoming.iter().any(|(value, _)| Self::value_id_to_key(&value.id) == value_id)
+            }
             _ => false,
         }
     }
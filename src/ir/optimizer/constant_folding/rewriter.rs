@@ -239,7 +239,9 @@ impl IRRewriter {
 
     /// Creates a constant Value from a `ConstantValue`.
     #[allow(clippy::unused_self)]
-    fn create_constant_value(&self, const_val: &ConstantValue, ty: &crate::ir::IrType) -> Result<Value, RewriteError> {
+    pub(super) fn create_constant_value(
+        &self, const_val: &ConstantValue, ty: &crate::ir::IrType,
+    ) -> Result<Value, RewriteError> {
         use crate::ir::value::IrLiteralValue;
 
         // Verify type matches
@@ -197,6 +197,18 @@ impl LivenessAnalyzer {
         true
     }
 
+    /// Returns the ids of the values live at the start of `block_idx`.
+    #[must_use]
+    pub fn live_in(&self, block_idx: NodeIndex) -> Option<&HashSet<ValueId>> {
+        self.live_in.get(&block_idx)
+    }
+
+    /// Returns the ids of the values live at the end of `block_idx`.
+    #[must_use]
+    pub fn live_out(&self, block_idx: NodeIndex) -> Option<&HashSet<ValueId>> {
+        self.live_out.get(&block_idx)
+    }
+
     /// Checks if an instruction is dead (its result is never used).
     #[inline]
     pub fn is_instruction_dead(&self, inst_idx: &InstructionIndex) -> bool {
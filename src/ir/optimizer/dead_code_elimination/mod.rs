@@ -28,6 +28,7 @@ mod escape;
 mod optimizer;
 mod stats;
 
+pub(crate) use analyzer::LivenessAnalyzer;
 pub use optimizer::DeadCodeElimination;
 pub use stats::{ConservativeReason, ConservativeWarning, OptimizationStats};
 
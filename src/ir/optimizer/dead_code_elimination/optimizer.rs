@@ -1,5 +1,6 @@
 //! Main Dead Code Elimination optimizer implementation.
 
+use crate::ir::optimizer::PassReport;
 use crate::ir::{Function, InstructionKind, Module, Phase};
 use console::style;
 use petgraph::Direction;
@@ -426,8 +427,13 @@ impl Phase for DeadCodeElimination {
 
         if self.verbose {
             self.print_statistics();
+            eprintln!("Total number of instructions after DCE: {}", module.count_instructions());
         }
-        println!("Total number of instructions after DCE: {}", module.count_instructions());
+    }
+
+    /// Adds the statistics of the last run to the pass report.
+    fn report_stats(&self, report: &mut PassReport) {
+        report.add_dce(&self.last_stats);
     }
 }
 
@@ -452,9 +458,9 @@ impl DeadCodeElimination {
                 }
             }
 
-            print!("{output}");
+            eprint!("{output}");
         } else {
-            println!("{}", style("No dead code found - module already optimal").green());
+            eprintln!("{}", style("No dead code found - module already optimal").green());
         }
     }
 }
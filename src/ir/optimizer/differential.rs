@@ -0,0 +1,199 @@
+//! Differential testing of the optimization pipeline.
+//!
+//! [`check_pipeline`] executes a module with the [`Interpreter`] before
+//! optimization and again after every pass a [`PassManager`] runs. The
+//! observable [`Behaviour`] of a run is the value returned by `main`, or the
+//! trap that stopped it, together with the final value of every global. The
+//! first pass after which the behaviour differs from the unoptimized module is
+//! reported as a [`Divergence`], carrying the IR before and after that pass so
+//! the bug can be reproduced with `jsavrs-opt`.
+
+use super::PassManager;
+use crate::error::compile_error::CompileError;
+use crate::error::error_code::ErrorCode;
+use crate::ir::Module;
+use crate::ir::interpreter::{Interpreter, RuntimeValue};
+use crate::location::source_span::SourceSpan;
+use std::fmt;
+use std::sync::Arc;
+
+/// Step limit used when the caller has no better bound.
+pub const DEFAULT_STEP_LIMIT: u64 = 1_000_000;
+
+/// How a run of `main` ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// `main` returned this value.
+    Returned(RuntimeValue),
+    /// The program trapped. Traps compare by code and message; the span may
+    /// legitimately move when a pass rewrites the instruction.
+    Trapped { code: ErrorCode, message: String },
+    /// The run exceeded the step limit, so nothing can be concluded from it.
+    StepLimit,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Returned(value) => write!(f, "returned {value}"),
+            Self::Trapped { code, message } => write!(f, "trapped with [{}] {message}", code.code()),
+            Self::StepLimit => f.write_str("exceeded the step limit"),
+        }
+    }
+}
+
+/// Observable behaviour of one execution of a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Behaviour {
+    /// How `main` ended.
+    pub outcome: Outcome,
+    /// Final value of each global, in declaration order. Arrays contribute
+    /// one entry per element, named `name[index]`.
+    pub globals: Vec<(Arc<str>, Option<RuntimeValue>)>,
+}
+
+impl Behaviour {
+    /// Runs `main` of `module`, stopping after `step_limit` executed
+    /// instructions, and records what the program did.
+    #[must_use]
+    pub fn observe(module: &Module, step_limit: u64) -> Self {
+        let mut interpreter = Interpreter::new(module).with_step_limit(step_limit);
+        let outcome = match interpreter.run_main() {
+            Ok(value) => Outcome::Returned(value),
+            Err(_) if interpreter.steps() > step_limit => Outcome::StepLimit,
+            Err(trap) => Outcome::Trapped { code: trap.code, message: trap.message },
+        };
+        let mut globals = Vec::with_capacity(module.globals.len());
+        for global in &module.globals {
+            match interpreter.global_elements(&global.name) {
+                Some(elements) => globals.extend(
+                    elements
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, value)| (Arc::from(format!("{}[{index}]", global.name)), Some(value))),
+                ),
+                None => globals.push((global.name.clone(), interpreter.global(&global.name))),
+            }
+        }
+        Self { outcome, globals }
+    }
+}
+
+impl fmt::Display for Behaviour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "main {}", self.outcome)?;
+        for (name, value) in &self.globals {
+            match value {
+                Some(value) => write!(f, ", @{name} = {value}")?,
+                None => write!(f, ", @{name} unreadable")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The first pass after which a program stopped behaving like its
+/// unoptimized version.
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    /// Registry name of the pass, see [`PASS_NAMES`](super::PASS_NAMES).
+    pub pass: String,
+    /// Position of the pass among the passes run so far, starting at 1.
+    pub position: usize,
+    /// Behaviour of the unoptimized module.
+    pub expected: Behaviour,
+    /// Behaviour of the module produced by the pass.
+    pub actual: Behaviour,
+    /// Textual IR the pass received.
+    pub before: String,
+    /// Textual IR the pass produced.
+    pub after: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pass '{}' (pass {} of the pipeline) changed the behaviour of the program\n  expected: {}\n    actual: {}",
+            self.pass, self.position, self.expected, self.actual
+        )
+    }
+}
+
+impl From<Divergence> for CompileError {
+    fn from(divergence: Divergence) -> Self {
+        Self::IrGeneratorError {
+            code: Some(ErrorCode::E3016),
+            message: divergence.to_string().into(),
+            span: SourceSpan::default(),
+            help: Some(format!(
+                "save the IR before the pass and run `jsavrs-opt -i before.nir --passes={}`",
+                divergence.pass
+            )),
+        }
+    }
+}
+
+/// Failure of [`check_pipeline`].
+#[derive(Debug)]
+pub enum CheckError {
+    /// A pass changed the behaviour of the program.
+    Diverged(Box<Divergence>),
+    /// The pass manager stopped, for example because verification failed.
+    Pipeline(Vec<CompileError>),
+}
+
+impl From<CheckError> for Vec<CompileError> {
+    fn from(error: CheckError) -> Self {
+        match error {
+            CheckError::Diverged(divergence) => vec![(*divergence).into()],
+            CheckError::Pipeline(errors) => errors,
+        }
+    }
+}
+
+/// Optimizes a copy of `module` with `manager`, executing the module before
+/// optimization and after every pass, and checks that each pass preserved the
+/// observable behaviour.
+///
+/// When the unoptimized module exceeds `step_limit` the behaviours cannot be
+/// compared; the pipeline still runs and the returned behaviour has outcome
+/// [`Outcome::StepLimit`].
+///
+/// # Errors
+///
+/// Returns the first [`Divergence`], or the errors of the pass manager.
+pub fn check_pipeline(module: &Module, manager: &mut PassManager, step_limit: u64) -> Result<Behaviour, CheckError> {
+    let expected = Behaviour::observe(module, step_limit);
+    let mut optimized = module.clone();
+    let mut before = module.to_string();
+    let mut position = 0;
+    let mut divergence = None;
+    let result = manager.run_observed(&mut optimized, &mut |pass, module| {
+        position += 1;
+        let after = module.to_string();
+        if expected.outcome != Outcome::StepLimit {
+            let actual = Behaviour::observe(module, step_limit);
+            if actual != expected {
+                let found = Divergence {
+                    pass: pass.to_string(),
+                    position,
+                    expected: expected.clone(),
+                    actual,
+                    before: std::mem::take(&mut before),
+                    after,
+                };
+                let error = CompileError::from(found.clone());
+                divergence = Some(found);
+                return Err(vec![error]);
+            }
+        }
+        before = after;
+        Ok(())
+    });
+    match (result, divergence) {
+        (_, Some(divergence)) => Err(CheckError::Diverged(Box::new(divergence))),
+        (Err(errors), None) => Err(CheckError::Pipeline(errors)),
+        (Ok(_), None) => Ok(expected),
+    }
+}
@@ -1,7 +1,10 @@
 pub mod constant_folding;
 pub mod dead_code_elimination;
+pub mod differential;
+pub mod pass_manager;
 pub mod phase;
 
 pub use constant_folding::ConstantFoldingOptimizer;
 pub use dead_code_elimination::DeadCodeElimination;
-pub use phase::{Phase, run_pipeline};
+pub use pass_manager::{Observer, OptLevel, PassManager, PassReport, PassTiming};
+pub use phase::{PASS_NAMES, Phase, phase_by_name, run_pipeline, run_verified_pipeline};
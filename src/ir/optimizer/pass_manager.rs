@@ -0,0 +1,296 @@
+//! Pass manager that schedules, times and verifies optimization phases.
+//!
+//! A [`PassManager`] runs a sequence of stages. A stage is either a single
+//! phase or a group of phases repeated until a whole round leaves the module
+//! unchanged. Each phase is registered under a short name (see
+//! [`PASS_NAMES`]) so that it can be disabled from the command line.
+//!
+//! [`PassManager::for_level`] builds the preset pipelines selected with `-O`:
+//!
+//! | Level | Pipeline |
+//! |-------|----------|
+//! | `O0`  | no optimization |
+//! | `O1`  | `sccp`, `dce` once |
+//! | `O2`  | `sccp`, `dce` repeated to a fixed point, at most 4 rounds |
+//! | `O3`  | `sccp`, `dce` repeated to a fixed point, at most 16 rounds |
+
+use super::constant_folding::OptimizationStats as SccpStats;
+use super::dead_code_elimination::OptimizationStats as DceStats;
+use super::phase::verify_stage;
+use super::{PASS_NAMES, Phase, phase_by_name};
+use crate::error::compile_error::CompileError;
+use crate::ir::Module;
+use crate::time::timer::Timer;
+use crate::time::times::Times;
+use std::collections::HashSet;
+use std::fmt;
+use std::time::Duration;
+
+/// Preset optimization pipelines, selected with `-O0` to `-O3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum OptLevel {
+    /// No optimization.
+    O0,
+    /// Every pass once.
+    #[default]
+    O1,
+    /// Every pass repeated until the module stops changing.
+    O2,
+    /// Like `O2`, with a higher round limit.
+    O3,
+}
+
+impl OptLevel {
+    /// Maximum number of fixed-point rounds, `None` for levels that run each
+    /// pass at most once.
+    #[must_use]
+    pub const fn max_rounds(self) -> Option<usize> {
+        match self {
+            Self::O0 | Self::O1 => None,
+            Self::O2 => Some(4),
+            Self::O3 => Some(16),
+        }
+    }
+}
+
+/// Accumulated run time of one pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassTiming {
+    /// Registry name of the pass.
+    pub name: String,
+    /// Number of times the pass ran.
+    pub runs: usize,
+    /// Total time spent in the pass.
+    pub time: Duration,
+}
+
+/// Timing and statistics collected while running a [`PassManager`].
+#[derive(Debug, Clone, Default)]
+pub struct PassReport {
+    /// Per-pass timings, in the order the passes first ran.
+    pub timings: Vec<PassTiming>,
+    /// Rounds run by fixed-point stages.
+    pub rounds: usize,
+    /// Statistics of every SCCP run, summed.
+    pub sccp: SccpStats,
+    /// Statistics of every DCE run, summed.
+    pub dce: DceStats,
+}
+
+impl PassReport {
+    /// Total time spent in optimization passes.
+    #[must_use]
+    pub fn total_time(&self) -> Duration {
+        self.timings.iter().map(|timing| timing.time).sum()
+    }
+
+    /// Adds the statistics of one SCCP run.
+    pub const fn add_sccp(&mut self, stats: &SccpStats) {
+        self.sccp.constants_propagated += stats.constants_propagated;
+        self.sccp.branches_resolved += stats.branches_resolved;
+        self.sccp.phi_nodes_simplified += stats.phi_nodes_simplified;
+        self.sccp.blocks_marked_unreachable += stats.blocks_marked_unreachable;
+        self.sccp.iterations += stats.iterations;
+    }
+
+    /// Adds the statistics of one DCE run.
+    pub fn add_dce(&mut self, stats: &DceStats) {
+        self.dce.instructions_removed += stats.instructions_removed;
+        self.dce.blocks_removed += stats.blocks_removed;
+        self.dce.iterations += stats.iterations;
+        self.dce.conservative_warnings.extend(stats.conservative_warnings.iter().cloned());
+    }
+
+    fn record_time(&mut self, name: &str, time: Duration) {
+        if let Some(timing) = self.timings.iter_mut().find(|timing| timing.name == name) {
+            timing.runs += 1;
+            timing.time += time;
+        } else {
+            self.timings.push(PassTiming { name: name.to_string(), runs: 1, time });
+        }
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_duration(time: Duration) -> String {
+    Times::from_nanoseconds(time.as_nanos() as f64).get_relevant_timeframe().to_string()
+}
+
+impl fmt::Display for PassReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Pass execution timing report:")?;
+        for timing in &self.timings {
+            writeln!(f, "  {:<8} {:>3} run(s)  {}", timing.name, timing.runs, format_duration(timing.time))?;
+        }
+        write!(f, "  {:<8} {:>10}  {}", "total", "", format_duration(self.total_time()))?;
+        if self.rounds > 0 {
+            write!(f, " in {} fixed-point round(s)", self.rounds)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{}", self.sccp)?;
+        write!(
+            f,
+            "DCE Stats: {} instructions removed, {} blocks removed, {} iterations, {} conservative warnings",
+            self.dce.instructions_removed,
+            self.dce.blocks_removed,
+            self.dce.iterations,
+            self.dce.conservative_warnings.len()
+        )
+    }
+}
+
+/// Callback of [`PassManager::run_observed`], given the registry name of the
+/// pass that just ran and the module it produced.
+pub type Observer<'a> = dyn FnMut(&str, &Module) -> Result<(), Vec<CompileError>> + 'a;
+
+/// A phase together with the name it is registered under.
+struct NamedPhase {
+    name: String,
+    phase: Box<dyn Phase>,
+}
+
+enum Stage {
+    Once(NamedPhase),
+    FixedPoint { passes: Vec<NamedPhase>, max_rounds: usize },
+}
+
+/// Runs optimization phases in stages, timing each phase and collecting the
+/// statistics it reports.
+#[derive(Default)]
+pub struct PassManager {
+    stages: Vec<Stage>,
+    disabled: HashSet<String>,
+    verify: bool,
+    verbose: bool,
+}
+
+impl PassManager {
+    /// Creates a pass manager with no stages.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the preset pipeline for `level`; see the module documentation.
+    #[must_use]
+    pub fn for_level(level: OptLevel, verbose: bool) -> Self {
+        let mut manager = Self { verbose, ..Self::new() };
+        if level == OptLevel::O0 {
+            return manager;
+        }
+        let passes = PASS_NAMES.iter().filter_map(|&name| Some((name, phase_by_name(name, verbose)?)));
+        match level.max_rounds() {
+            None => {
+                for (name, phase) in passes {
+                    manager.add_pass(name, phase);
+                }
+            }
+            Some(max_rounds) => manager.add_fixed_point(passes.collect(), max_rounds),
+        }
+        manager
+    }
+
+    /// Appends a stage that runs `phase` once.
+    pub fn add_pass(&mut self, name: &str, phase: Box<dyn Phase>) {
+        self.stages.push(Stage::Once(NamedPhase { name: name.to_string(), phase }));
+    }
+
+    /// Appends a stage that runs `passes` in order, repeating the whole group
+    /// until a round leaves the module unchanged or `max_rounds` rounds ran.
+    pub fn add_fixed_point(&mut self, passes: Vec<(&str, Box<dyn Phase>)>, max_rounds: usize) {
+        let passes = passes.into_iter().map(|(name, phase)| NamedPhase { name: name.to_string(), phase }).collect();
+        self.stages.push(Stage::FixedPoint { passes, max_rounds });
+    }
+
+    /// Skips every pass registered under `name`.
+    pub fn disable_pass(&mut self, name: &str) {
+        self.disabled.insert(name.to_string());
+    }
+
+    /// Verifies the module before the first pass and after every pass.
+    pub const fn set_verify(&mut self, verify: bool) {
+        self.verify = verify;
+    }
+
+    /// Names of the passes that will run, in stage order.
+    #[must_use]
+    pub fn pass_names(&self) -> Vec<&str> {
+        self.stages
+            .iter()
+            .flat_map(|stage| match stage {
+                Stage::Once(pass) => std::slice::from_ref(pass),
+                Stage::FixedPoint { passes, .. } => passes.as_slice(),
+            })
+            .map(|pass| pass.name.as_str())
+            .filter(|name| !self.disabled.contains(*name))
+            .collect()
+    }
+
+    /// Runs every stage on `module`.
+    ///
+    /// # Errors
+    ///
+    /// With verification enabled, stops at the first invalid module and
+    /// returns the verifier errors, prefixed with the pass that produced it.
+    pub fn run(&mut self, module: &mut Module) -> Result<PassReport, Vec<CompileError>> {
+        self.run_observed(module, &mut |_, _| Ok(()))
+    }
+
+    /// Runs every stage on `module` like [`run`](Self::run), calling
+    /// `observer` with the registry name of each pass and the module it
+    /// produced, after verification.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first invalid module, or at the first error returned by
+    /// `observer`, and returns those errors.
+    pub fn run_observed(
+        &mut self, module: &mut Module, observer: &mut Observer,
+    ) -> Result<PassReport, Vec<CompileError>> {
+        let mut report = PassReport::default();
+        if self.verify {
+            verify_stage(module, "before optimization")?;
+        }
+
+        for stage in &mut self.stages {
+            match stage {
+                Stage::Once(pass) => {
+                    if !self.disabled.contains(&pass.name) {
+                        run_pass(pass, module, self.verify, self.verbose, &mut report)?;
+                        observer(&pass.name, module)?;
+                    }
+                }
+                Stage::FixedPoint { passes, max_rounds } => {
+                    for _ in 0..*max_rounds {
+                        report.rounds += 1;
+                        let before = module.to_string();
+                        for pass in passes.iter_mut().filter(|pass| !self.disabled.contains(&pass.name)) {
+                            run_pass(pass, module, self.verify, self.verbose, &mut report)?;
+                            observer(&pass.name, module)?;
+                        }
+                        if module.to_string() == before {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+fn run_pass(
+    pass: &mut NamedPhase, module: &mut Module, verify: bool, verbose: bool, report: &mut PassReport,
+) -> Result<(), Vec<CompileError>> {
+    if verbose {
+        eprintln!("Running phase: {}", pass.phase.name());
+    }
+    let timer = Timer::new(&pass.name);
+    pass.phase.run(module);
+    report.record_time(&pass.name, timer.elapsed());
+    pass.phase.report_stats(report);
+    if verify {
+        verify_stage(module, &format!("after {}", pass.phase.name()))?;
+    }
+    Ok(())
+}
@@ -1,12 +1,66 @@
-use crate::ir::Module;
+use crate::error::compile_error::CompileError;
+use crate::ir::optimizer::{ConstantFoldingOptimizer, DeadCodeElimination, PassReport};
+use crate::ir::{Module, verify_module};
+use std::sync::Arc;
+
 pub trait Phase {
     fn name(&self) -> &'static str;
     fn run(&mut self, ir: &mut Module);
+
+    /// Adds the statistics of the last run to `report`. Phases without
+    /// statistics keep the default, which adds nothing.
+    fn report_stats(&self, report: &mut PassReport) {
+        let _ = report;
+    }
+}
+
+/// Names accepted by [`phase_by_name`], in the order the default pipeline runs them.
+pub const PASS_NAMES: [&str; 2] = ["sccp", "dce"];
+
+/// Builds the optimization phase registered under `name`.
+///
+/// `sccp` maps to [`ConstantFoldingOptimizer`] and `dce` to
+/// [`DeadCodeElimination`], both configured like the compiler's own pipeline.
+/// Returns `None` for names not listed in [`PASS_NAMES`].
+#[must_use]
+pub fn phase_by_name(name: &str, verbose: bool) -> Option<Box<dyn Phase>> {
+    match name {
+        "sccp" => Some(Box::new(ConstantFoldingOptimizer::new(verbose, true))),
+        "dce" => Some(Box::new(DeadCodeElimination::with_config(10, true, verbose, false))),
+        _ => None,
+    }
 }
 
 pub fn run_pipeline(ir: &mut Module, phases: Vec<Box<dyn Phase>>) {
     for mut phase in phases {
-        println!("Running phase: {}", phase.name());
+        eprintln!("Running phase: {}", phase.name());
         phase.run(ir);
     }
 }
+
+/// Runs `phases` like [`run_pipeline`], verifying the module before the first
+/// phase and after each one with [`verify_module`].
+///
+/// # Errors
+///
+/// Stops at the first invalid module and returns the verifier errors, each
+/// message prefixed with the phase that produced the module.
+pub fn run_verified_pipeline(ir: &mut Module, phases: Vec<Box<dyn Phase>>) -> Result<(), Vec<CompileError>> {
+    verify_stage(ir, "before optimization")?;
+    for mut phase in phases {
+        eprintln!("Running phase: {}", phase.name());
+        phase.run(ir);
+        verify_stage(ir, &format!("after {}", phase.name()))?;
+    }
+    Ok(())
+}
+
+pub(crate) fn verify_stage(ir: &mut Module, stage: &str) -> Result<(), Vec<CompileError>> {
+    verify_module(ir).map_err(|mut errors| {
+        for error in &mut errors {
+            let message = format!("{stage}: {}", error.message().unwrap_or_default());
+            error.set_message(Arc::from(message));
+        }
+        errors
+    })
+}
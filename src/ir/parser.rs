@@ -0,0 +1,1012 @@
+//! Parser for the textual IR format.
+//!
+//! Reads back the text printed by the `Display` implementations of [`Module`],
+//! [`Function`], [`BasicBlock`], [`Instruction`] and [`Terminator`], so that IR can
+//! be written by hand (conventionally in `.nir` files) and compared before and
+//! after optimization passes.
+//!
+//! The printed format does not record everything the in-memory IR carries, so
+//! some information is reconstructed:
+//!
+//! * Operand types come from literals, instruction results and function
+//!   parameters; any other operand takes the type implied by its position.
+//! * Cast kinds are inferred from the source and target types.
+//! * Every occurrence of a named value within a function shares one
+//!   [`ValueId`](super::value::ValueId), as it does in generated IR.
+//! * CFG edges are rebuilt from block terminators, and the first block of a
+//!   function becomes its entry block.
+//!
+//! Indentation is not significant, and `//` lines other than `// Scope:`
+//! annotations are treated as comments. Malformed input is reported as error
+//! `E3009`, pointing at the offending line and column.
+
+use super::{
+    BasicBlock, CastKind, DataLayout, Function, GlobalVariable, Instruction, InstructionKind, IrBinaryOp,
+    IrConstantValue, IrLiteralValue, IrParameter, IrType, IrUnaryOp, Module, ParamAttributes, PromotionMatrix,
+    PromotionRule, ScopeId, TargetTriple, Terminator, TerminatorKind, Value, ValueKind, VectorOp,
+};
+use crate::error::compile_error::CompileError;
+use crate::error::error_code::ErrorCode;
+use crate::location::{source_location::SourceLocation, source_span::SourceSpan};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Type given to named operands until their definition or position provides one.
+const UNTYPED: IrType = IrType::Void;
+
+const DATA_LAYOUTS: [DataLayout; 8] = [
+    DataLayout::LinuxX86_64,
+    DataLayout::LinuxAArch64,
+    DataLayout::WindowsX86_64,
+    DataLayout::MacOSX86_64,
+    DataLayout::FreeBSDX86_64,
+    DataLayout::NetBSDX86_64,
+    DataLayout::OpenBSDX86_64,
+    DataLayout::DragonFlyX86_64,
+];
+
+const TARGET_TRIPLES: [TargetTriple; 9] = [
+    TargetTriple::X86_64UnknownLinuxGnu,
+    TargetTriple::X86_64PcWindowsGnu,
+    TargetTriple::X86_64AppleDarwin,
+    TargetTriple::AArch64UnknownLinuxGnu,
+    TargetTriple::AArch64AppleDarwin,
+    TargetTriple::AArch64PcWindowsGnu,
+    TargetTriple::I686PcWindowsGnu,
+    TargetTriple::I686UnknownLinuxGnu,
+    TargetTriple::Wasm32UnknownEmscripten,
+];
+
+const BINARY_OPS: [IrBinaryOp; 18] = [
+    IrBinaryOp::Add,
+    IrBinaryOp::Subtract,
+    IrBinaryOp::Multiply,
+    IrBinaryOp::Divide,
+    IrBinaryOp::Modulo,
+    IrBinaryOp::Equal,
+    IrBinaryOp::NotEqual,
+    IrBinaryOp::Less,
+    IrBinaryOp::LessEqual,
+    IrBinaryOp::Greater,
+    IrBinaryOp::GreaterEqual,
+    IrBinaryOp::And,
+    IrBinaryOp::Or,
+    IrBinaryOp::BitwiseAnd,
+    IrBinaryOp::BitwiseOr,
+    IrBinaryOp::BitwiseXor,
+    IrBinaryOp::ShiftLeft,
+    IrBinaryOp::ShiftRight,
+];
+
+const VECTOR_OPS: [VectorOp; 6] =
+    [VectorOp::Add, VectorOp::Sub, VectorOp::Mul, VectorOp::Div, VectorOp::DotProduct, VectorOp::Shuffle];
+
+/// Parses a module from the text printed by its `Display` implementation.
+///
+/// `file_path` is only used for the source spans of the parsed IR and of errors.
+///
+/// # Errors
+///
+/// Returns an `E3009` [`CompileError::IrGeneratorError`] for the first line that
+/// does not follow the textual IR format, a branch to an undefined block, or a
+/// missing closing `}`.
+///
+/// # Examples
+///
+/// ```
+/// use jsavrs::ir::parse_module;
+///
+/// let text = "module demo {\n  function main () -> i32:\n  entry_main:\n    ret 0i32 i32\n}";
+/// let module = parse_module("demo.nir", text).unwrap();
+/// assert_eq!(module.functions.len(), 1);
+/// ```
+pub fn parse_module(file_path: &str, source: &str) -> Result<Module, CompileError> {
+    let mut lines = Lines::new(file_path, source);
+    let Some(header) = lines.next() else {
+        return Err(lines.end_error("expected `module <name> {`"));
+    };
+    let mut parser = ModuleParser::new(header.module_header()?);
+
+    while let Some(cursor) = lines.next() {
+        if cursor.text == "}" {
+            parser.finish_function()?;
+            return match lines.next() {
+                Some(extra) => Err(extra.error("unexpected text after the end of the module")),
+                None => Ok(parser.module),
+            };
+        }
+        parser.parse_line(cursor)?;
+    }
+    Err(lines.end_error("expected `}` to close the module"))
+}
+
+/// Iterator over the non-blank lines of the source, each trimmed into a [`Cursor`].
+struct Lines<'a> {
+    file_path: Arc<str>,
+    source: &'a str,
+    offset: usize,
+    line: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(file_path: &str, source: &'a str) -> Self {
+        Self { file_path: file_path.into(), source, offset: 0, line: 0 }
+    }
+
+    /// Error located at the end of the input.
+    fn end_error(&self, message: &str) -> CompileError {
+        let location = SourceLocation::new(self.line.max(1), 1, self.offset);
+        parse_error(message, SourceSpan::new(self.file_path.clone(), location, location))
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Cursor<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.offset < self.source.len() {
+            let rest = &self.source[self.offset..];
+            let raw = rest.split('\n').next().unwrap_or_default();
+            let line_offset = self.offset;
+            self.offset = (self.offset + raw.len() + 1).min(self.source.len());
+            self.line += 1;
+
+            let raw = raw.strip_suffix('\r').unwrap_or(raw);
+            let text = raw.trim_start();
+            let indent = raw.len() - text.len();
+            let text = text.trim_end();
+            if !text.is_empty() {
+                return Some(Cursor {
+                    text,
+                    pos: 0,
+                    file_path: self.file_path.clone(),
+                    line: self.line,
+                    column: indent + 1,
+                    offset: line_offset + indent,
+                });
+            }
+        }
+        None
+    }
+}
+
+/// Line-oriented state machine that assembles functions and blocks.
+struct ModuleParser {
+    promotions: PromotionMatrix,
+    module: Module,
+    function: Option<Function>,
+    block: Option<BasicBlock>,
+    terminated: bool,
+    scope: Option<ScopeId>,
+    expects_block_marker: bool,
+}
+
+impl ModuleParser {
+    fn new(module: Module) -> Self {
+        Self {
+            promotions: PromotionMatrix::new(),
+            module,
+            function: None,
+            block: None,
+            terminated: false,
+            scope: None,
+            expects_block_marker: false,
+        }
+    }
+
+    fn parse_line(&mut self, mut cursor: Cursor<'_>) -> Result<(), CompileError> {
+        let text = cursor.text;
+        let expects_block_marker = std::mem::take(&mut self.expects_block_marker);
+
+        if cursor.eat_keyword("function") {
+            self.finish_function()?;
+            self.function = Some(cursor.function_header()?);
+            self.expects_block_marker = true;
+        } else if cursor.eat_keyword("global") {
+            if self.function.is_some() {
+                return Err(cursor.error("global declared after the first function"));
+            }
+            let global = cursor.global()?;
+            self.module.add_global(global);
+        } else if cursor.eat_keyword("data_layout") {
+            let layout = cursor.setting()?;
+            let layout = DATA_LAYOUTS
+                .into_iter()
+                .find(|candidate| candidate.as_str() == layout)
+                .ok_or_else(|| cursor.error(format!("unknown data layout \"{layout}\"")))?;
+            self.module.set_data_layout(layout);
+        } else if cursor.eat_keyword("target_triple") {
+            let triple = cursor.setting()?;
+            let triple = TARGET_TRIPLES
+                .into_iter()
+                .find(|candidate| candidate.as_str() == triple)
+                .ok_or_else(|| cursor.error(format!("unknown target triple \"{triple}\"")))?;
+            self.module.set_target_triple(triple);
+        } else if expects_block_marker && matches!(text, "<empty>" | "block:" | "blocks:") {
+            return Ok(());
+        } else if let Some(scope) = text.strip_prefix("// Scope:") {
+            let scope = ScopeId::from_str(scope.trim())
+                .map_err(|_| cursor.error(format!("invalid scope id `{}`", scope.trim())))?;
+            self.scope = Some(scope);
+            return Ok(());
+        } else if text.starts_with("//") {
+            return Ok(());
+        } else if let Some(label) = text.strip_suffix(':')
+            && !label.is_empty()
+            && !label.contains(char::is_whitespace)
+        {
+            return self.start_block(label, &cursor);
+        } else {
+            let Some(block) = self.block.as_mut() else {
+                return Err(cursor.error("instruction outside of a block"));
+            };
+            if self.terminated {
+                return Err(cursor.error(format!("block `{}` already has a terminator", block.label)));
+            }
+            if cursor.at_terminator() {
+                block.set_terminator(cursor.terminator()?);
+                self.terminated = true;
+            } else {
+                block.instructions.push(cursor.instruction(&self.promotions)?);
+            }
+        }
+
+        cursor.finish()
+    }
+
+    fn start_block(&mut self, label: &str, cursor: &Cursor<'_>) -> Result<(), CompileError> {
+        self.flush_block();
+        let Some(function) = self.function.as_ref() else {
+            return Err(cursor.error(format!("block `{label}` outside of a function")));
+        };
+        if function.cfg.find_block_by_label(label).is_some() {
+            return Err(cursor.error(format!("duplicate block `{label}` in function `{}`", function.name)));
+        }
+
+        let mut block = BasicBlock::new(label, cursor.span());
+        if let Some(scope) = self.scope.take() {
+            block = block.with_scope(scope);
+        }
+        self.block = Some(block);
+        self.terminated = false;
+        Ok(())
+    }
+
+    fn flush_block(&mut self) {
+        if let Some(block) = self.block.take()
+            && let Some(function) = self.function.as_mut()
+        {
+            function.cfg.add_block(block);
+        }
+    }
+
+    fn finish_function(&mut self) -> Result<(), CompileError> {
+        self.flush_block();
+        self.scope = None;
+        let Some(mut function) = self.function.take() else {
+            return Ok(());
+        };
+
+        link_blocks(&mut function)?;
+        resolve_types(&mut function, &self.module.globals);
+        share_value_ids(&mut function);
+        self.module.add_function(function);
+        Ok(())
+    }
+}
+
+/// Makes the first block the entry and adds an edge for every terminator target.
+fn link_blocks(function: &mut Function) -> Result<(), CompileError> {
+    let first = function.cfg.blocks().next().map(|block| block.label.clone());
+    if let Some(first) = first {
+        function.cfg.entry_label = first;
+    }
+
+    let edges: Vec<_> = function
+        .cfg
+        .blocks()
+        .flat_map(|block| {
+            let terminator = block.terminator();
+            terminator
+                .get_targets()
+                .into_iter()
+                .map(move |target| (block.label.clone(), target, terminator.debug_info.source_span.clone()))
+        })
+        .collect();
+
+    for (from, to, span) in edges {
+        if !function.cfg.connect_blocks(&from, &to) {
+            return Err(parse_error(format!("branch to undefined block `{to}`"), span));
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the positional types of named operands with the types of their
+/// definitions; globals are addresses of their declared type.
+fn resolve_types(function: &mut Function, globals: &[GlobalVariable]) {
+    let mut types: HashMap<ValueKind, IrType> =
+        function.parameters.iter().map(|param| (ValueKind::Local(param.name.clone()), param.ty.clone())).collect();
+    for global in globals {
+        types.insert(ValueKind::Global(global.name.clone()), IrType::Pointer(Box::new(global.ty.clone())));
+    }
+    for instruction in function.cfg.blocks().flat_map(|block| &block.instructions) {
+        if let Some(result) = &instruction.result {
+            types.insert(result.kind.clone(), result.ty.clone());
+        }
+    }
+
+    let resolve = |value: &mut Value| types.get(&value.kind).map(|ty| value.ty = ty.clone()).is_some();
+
+    for block in function.cfg.blocks_mut() {
+        for instruction in &mut block.instructions {
+            if let InstructionKind::Store { value, dest } = &mut instruction.kind {
+                resolve(value);
+                if !resolve(dest) && is_named(dest) {
+                    dest.ty = IrType::Pointer(Box::new(value.ty.clone()));
+                }
+                continue;
+            }
+            for operand in instruction_operands(&mut instruction.kind) {
+                resolve(operand);
+            }
+        }
+        for operand in terminator_operands(&mut block.terminator_mut().kind) {
+            resolve(operand);
+        }
+    }
+}
+
+/// Gives every occurrence of a named value the id of its first occurrence, so
+/// that passes tracking values by id see definitions and uses as one value.
+fn share_value_ids(function: &mut Function) {
+    let mut ids = HashMap::new();
+    let mut share = |value: &mut Value| {
+        if is_named(value) {
+            value.id = *ids.entry(value.kind.clone()).or_insert(value.id);
+        }
+    };
+
+    for block in function.cfg.blocks_mut() {
+        for instruction in &mut block.instructions {
+            instruction.result.as_mut().map(&mut share);
+            instruction_operands(&mut instruction.kind).into_iter().for_each(&mut share);
+        }
+        terminator_operands(&mut block.terminator_mut().kind).into_iter().for_each(&mut share);
+    }
+}
+
+fn terminator_operands(kind: &mut TerminatorKind) -> Vec<&mut Value> {
+    match kind {
+        TerminatorKind::Return { value, .. }
+        | TerminatorKind::ConditionalBranch { condition: value, .. }
+        | TerminatorKind::IndirectBranch { address: value, .. } => vec![value],
+        TerminatorKind::Switch { value, cases, .. } => {
+            std::iter::once(value).chain(cases.iter_mut().map(|(case, _)| case)).collect()
+        }
+        TerminatorKind::Branch { .. } | TerminatorKind::Unreachable => Vec::new(),
+    }
+}
+
+fn instruction_operands(kind: &mut InstructionKind) -> Vec<&mut Value> {
+    match kind {
+        InstructionKind::Alloca { .. } => Vec::new(),
+        InstructionKind::Store { value, dest } => vec![value, dest],
+        InstructionKind::Load { src: value, .. }
+        | InstructionKind::Unary { operand: value, .. }
+        | InstructionKind::Cast { value, .. } => vec![value],
+        InstructionKind::Binary { left, right, .. } => vec![left, right],
+        InstructionKind::GetElementPtr { base, index, .. } => vec![base, index],
+        InstructionKind::Call { func, args, .. } => std::iter::once(func).chain(args.iter_mut()).collect(),
+        InstructionKind::Phi { incoming, .. } => incoming.iter_mut().map(|(value, _)| value).collect(),
+        InstructionKind::Vector { operands, .. } => operands.iter_mut().collect(),
+    }
+}
+
+const fn is_named(value: &Value) -> bool {
+    matches!(value.kind, ValueKind::Local(_) | ValueKind::Global(_) | ValueKind::Temporary(_))
+}
+
+/// Gives a named operand the type implied by its position in an instruction.
+fn assume_type(value: &mut Value, ty: &IrType) {
+    if is_named(value) {
+        value.ty = ty.clone();
+    }
+}
+
+fn cast_kind(promotions: &PromotionMatrix, from: &IrType, to: &IrType) -> CastKind {
+    match promotions.get_promotion_rule(from, to) {
+        Some(PromotionRule::Direct { cast_kind, .. }) => *cast_kind,
+        _ => CastKind::Bitcast,
+    }
+}
+
+fn parse_error(message: impl Into<Arc<str>>, span: SourceSpan) -> CompileError {
+    CompileError::IrGeneratorError { code: Some(ErrorCode::E3009), message: message.into(), span, help: None }
+}
+
+const fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.'
+}
+
+/// Scanner over the text of a single line.
+struct Cursor<'a> {
+    text: &'a str,
+    pos: usize,
+    file_path: Arc<str>,
+    line: usize,
+    column: usize,
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    const fn location(&self, pos: usize) -> SourceLocation {
+        SourceLocation::new(self.line, self.column + pos, self.offset + pos)
+    }
+
+    /// Span covering the whole line.
+    fn span(&self) -> SourceSpan {
+        SourceSpan::new(self.file_path.clone(), self.location(0), self.location(self.text.len()))
+    }
+
+    /// Error spanning from the current position to the end of the line.
+    fn error(&self, message: impl Into<Arc<str>>) -> CompileError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: impl Into<Arc<str>>) -> CompileError {
+        parse_error(
+            message,
+            SourceSpan::new(self.file_path.clone(), self.location(pos), self.location(self.text.len())),
+        )
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = self.text.len() - self.rest().trim_start().len();
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), CompileError> {
+        if self.eat(token) { Ok(()) } else { Err(self.error(format!("expected `{token}`"))) }
+    }
+
+    /// Consumes `keyword` if it is followed by whitespace or the end of the line.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.skip_whitespace();
+        let rest = self.rest();
+        if rest.strip_prefix(keyword).is_some_and(|after| after.is_empty() || after.starts_with(char::is_whitespace)) {
+            self.pos += keyword.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), CompileError> {
+        if self.eat_keyword(keyword) { Ok(()) } else { Err(self.error(format!("expected `{keyword}`"))) }
+    }
+
+    fn name(&mut self, what: &str) -> Result<&'a str, CompileError> {
+        self.skip_whitespace();
+        let name = self.take_while(is_name_char);
+        if name.is_empty() { Err(self.error(format!("expected {what}"))) } else { Ok(name) }
+    }
+
+    fn label(&mut self) -> Result<Arc<str>, CompileError> {
+        self.skip_whitespace();
+        let label = self.take_while(|c| !c.is_whitespace() && !matches!(c, ',' | '[' | ']'));
+        if label.is_empty() { Err(self.error("expected a block label")) } else { Ok(label.into()) }
+    }
+
+    fn integer<T: FromStr>(&mut self) -> Result<T, CompileError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        digits.parse().map_err(|_| self.error_at(start, "expected an integer"))
+    }
+
+    fn finish(&self) -> Result<(), CompileError> {
+        let rest = self.rest().trim_start();
+        if rest.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error_at(self.text.len() - rest.len(), format!("unexpected `{rest}`")))
+        }
+    }
+
+    fn module_header(&self) -> Result<Module, CompileError> {
+        let name = self.text.strip_prefix("module ").and_then(|rest| rest.strip_suffix('{')).map(str::trim);
+        match name {
+            Some(name) if !name.is_empty() => Ok(Module::new(name, None)),
+            _ => Err(self.error("expected `module <name> {`")),
+        }
+    }
+
+    /// Parses the `= "<value>";` tail of a module setting.
+    fn setting(&mut self) -> Result<&'a str, CompileError> {
+        self.expect("=")?;
+        self.expect("\"")?;
+        let value = self.take_while(|c| c != '"');
+        self.expect("\"")?;
+        self.expect(";")?;
+        Ok(value)
+    }
+
+    fn function_header(&mut self) -> Result<Function, CompileError> {
+        self.skip_whitespace();
+        let name = self.take_while(|c| !c.is_whitespace() && c != '(');
+        if name.is_empty() {
+            return Err(self.error("expected a function name"));
+        }
+
+        self.expect("(")?;
+        let mut params = Vec::new();
+        while !self.eat(")") {
+            if !params.is_empty() {
+                self.expect(",")?;
+            }
+            let param_name = self.name("a parameter name")?;
+            self.expect(":")?;
+            let ty = self.ty()?;
+            params.push(IrParameter { name: param_name.into(), ty, attributes: ParamAttributes::default() });
+        }
+        self.expect("->")?;
+        let return_type = self.ty()?;
+        self.expect(":")?;
+
+        Ok(Function::new(name, params, return_type))
+    }
+
+    /// Parses the `@name: type = literal` tail of a global declaration; the
+    /// initializer of an array is a list of element literals.
+    fn global(&mut self) -> Result<GlobalVariable, CompileError> {
+        self.expect("@")?;
+        let name = self.name("a global name")?;
+        self.expect(":")?;
+        let ty = self.ty()?;
+        self.expect("=")?;
+        self.skip_whitespace();
+        let start = self.pos;
+        let value = self.value()?;
+        let initializer = match (value.kind, &ty) {
+            (ValueKind::Literal(literal), _) if value.ty == ty => Some(vec![literal]),
+            (ValueKind::Constant(IrConstantValue::Array { elements }), IrType::Array(element_ty, size))
+                if elements.len() == *size =>
+            {
+                elements
+                    .into_iter()
+                    .map(|element| match element.kind {
+                        ValueKind::Literal(literal) if element.ty == **element_ty => Some(literal),
+                        _ => None,
+                    })
+                    .collect()
+            }
+            _ => None,
+        };
+        initializer
+            .map(|initializer| GlobalVariable { name: name.into(), ty: ty.clone(), initializer })
+            .ok_or_else(|| self.error_at(start, format!("initializer of `@{name}` is not a {ty} literal")))
+    }
+
+    fn ty(&mut self) -> Result<IrType, CompileError> {
+        if self.eat("*") {
+            return Ok(IrType::Pointer(Box::new(self.ty()?)));
+        }
+        if self.eat("[") {
+            let element = self.ty()?;
+            self.expect(";")?;
+            let len = self.integer()?;
+            self.expect("]")?;
+            return Ok(IrType::Array(Box::new(element), len));
+        }
+
+        let ty = match self.name("a type")? {
+            "i8" => IrType::I8,
+            "i16" => IrType::I16,
+            "i32" => IrType::I32,
+            "i64" => IrType::I64,
+            "u8" => IrType::U8,
+            "u16" => IrType::U16,
+            "u32" => IrType::U32,
+            "u64" => IrType::U64,
+            "f32" => IrType::F32,
+            "f64" => IrType::F64,
+            "bool" => IrType::Bool,
+            "char" => IrType::Char,
+            "string" => IrType::String,
+            "void" => IrType::Void,
+            "struct" => {
+                let name = self.name("a struct name")?;
+                self.expect("{")?;
+                let mut fields = Vec::new();
+                while !self.eat("}") {
+                    if !fields.is_empty() {
+                        self.expect(",")?;
+                    }
+                    let field = self.name("a field name")?;
+                    self.expect(":")?;
+                    fields.push((field.to_string(), self.ty()?));
+                }
+                IrType::Struct(name.into(), fields, self.span())
+            }
+            name => IrType::Custom(name.into(), self.span()),
+        };
+        Ok(ty)
+    }
+
+    /// Parses a value; named values get [`UNTYPED`] until their context is known.
+    fn value(&mut self) -> Result<Value, CompileError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let value = match self.peek() {
+            Some('%') => {
+                self.pos += 1;
+                Value::new_local(self.name("a local name")?.into(), UNTYPED)
+            }
+            Some('@') => {
+                self.pos += 1;
+                Value::new_global(self.name("a global name")?.into(), UNTYPED)
+            }
+            Some('"') => {
+                self.pos += 1;
+                let mut string = String::new();
+                loop {
+                    match self.bump() {
+                        Some('"') => break,
+                        Some('\\') => string.push(self.escape()?),
+                        Some(c) => string.push(c),
+                        None => return Err(self.error_at(start, "unterminated string constant")),
+                    }
+                }
+                Value::new_constant(IrConstantValue::String { string: string.into() }, IrType::String)
+            }
+            Some('\'') => {
+                self.pos += 1;
+                let c = match self.bump() {
+                    Some('\\') => self.escape()?,
+                    Some(c) => c,
+                    None => return Err(self.error_at(start, "unterminated character literal")),
+                };
+                if self.bump() != Some('\'') {
+                    return Err(self.error_at(start, "unterminated character literal"));
+                }
+                Value::new_literal(IrLiteralValue::Char(c))
+            }
+            Some('[') => {
+                self.pos += 1;
+                let elements = self.values_until("]")?;
+                let element_ty = elements.first().map_or(UNTYPED, |element| element.ty.clone());
+                let ty = IrType::Array(Box::new(element_ty), elements.len());
+                Value::new_constant(IrConstantValue::Array { elements }, ty)
+            }
+            _ => {
+                let word = self.take_while(|c| is_name_char(c) || c == '-');
+                if word.is_empty() {
+                    return Err(self.error("expected a value"));
+                }
+                if self.rest().starts_with('<') {
+                    self.pos += 1;
+                    let elements = self.values_until(">")?;
+                    let ty = IrType::Custom(word.into(), self.span());
+                    Value::new_constant(IrConstantValue::Struct { name: word.into(), elements }, ty)
+                } else {
+                    Self::word_value(word).ok_or_else(|| self.error_at(start, format!("invalid value `{word}`")))?
+                }
+            }
+        };
+
+        if let Some(debug) = self.rest().strip_prefix(" (")
+            && let Some(end) = debug.find(')')
+        {
+            self.pos += 2 + end + 1;
+            return Ok(value.with_debug_info(Some(debug[..end].into()), self.span()));
+        }
+        Ok(value)
+    }
+
+    /// Parses a comma-separated list of values up to and including `close`.
+    fn values_until(&mut self, close: &str) -> Result<Vec<Value>, CompileError> {
+        let mut values = Vec::new();
+        while !self.eat(close) {
+            if !values.is_empty() {
+                self.expect(",")?;
+            }
+            values.push(self.value()?);
+        }
+        Ok(values)
+    }
+
+    /// Decodes the escape following a backslash, as written by `char::escape_default`.
+    fn escape(&mut self) -> Result<char, CompileError> {
+        let start = self.pos.saturating_sub(1);
+        let escaped = match self.bump() {
+            Some('t') => Some('\t'),
+            Some('r') => Some('\r'),
+            Some('n') => Some('\n'),
+            Some('0') => Some('\0'),
+            Some(c @ ('\\' | '\'' | '"')) => Some(c),
+            Some('u') if self.eat("{") => {
+                let hex = self.take_while(|c| c.is_ascii_hexdigit());
+                let code = u32::from_str_radix(hex, 16).ok();
+                if self.eat("}") { code.and_then(char::from_u32) } else { None }
+            }
+            _ => None,
+        };
+        escaped.ok_or_else(|| self.error_at(start, "invalid escape sequence"))
+    }
+
+    /// Interprets a bare word as a boolean, a temporary or a suffixed number.
+    fn word_value(word: &str) -> Option<Value> {
+        let literal = match word {
+            "true" => IrLiteralValue::Bool(true),
+            "false" => IrLiteralValue::Bool(false),
+            _ => {
+                if let Some(id) = word.strip_prefix('t')
+                    && !id.is_empty()
+                    && id.bytes().all(|b| b.is_ascii_digit())
+                {
+                    return id.parse().ok().map(|id| Value::new_temporary(id, UNTYPED));
+                }
+                number_literal(word)?
+            }
+        };
+        Some(Value::new_literal(literal))
+    }
+
+    fn at_terminator(&self) -> bool {
+        matches!(self.rest().split_whitespace().next(), Some("ret" | "br" | "switch" | "ibr" | "unreachable"))
+    }
+
+    fn terminator(&mut self) -> Result<Terminator, CompileError> {
+        let span = self.span();
+        let opcode = self.name("a terminator")?;
+        let kind = match opcode {
+            "ret" => {
+                let mut value = self.value()?;
+                let ty = self.ty()?;
+                assume_type(&mut value, &ty);
+                TerminatorKind::Return { value, ty }
+            }
+            "br" if self.rest().contains(" ? ") => {
+                let mut condition = self.value()?;
+                assume_type(&mut condition, &IrType::Bool);
+                self.expect("?")?;
+                let true_label = self.label()?;
+                self.expect(":")?;
+                let false_label = self.label()?;
+                TerminatorKind::ConditionalBranch { condition, true_label, false_label }
+            }
+            "br" => TerminatorKind::Branch { label: self.label()? },
+            "switch" => {
+                let mut value = self.value()?;
+                let ty = self.ty()?;
+                assume_type(&mut value, &ty);
+                self.expect(":")?;
+                let mut cases = Vec::new();
+                loop {
+                    if self.eat(",") && self.eat_keyword("default") {
+                        break;
+                    }
+                    let mut case = self.value()?;
+                    assume_type(&mut case, &ty);
+                    self.expect("=>")?;
+                    cases.push((case, self.label()?));
+                }
+                let default_label = self.label()?;
+                TerminatorKind::Switch { value, ty, default_label, cases }
+            }
+            "ibr" => {
+                let mut address = self.value()?;
+                assume_type(&mut address, &IrType::Pointer(Box::new(IrType::I8)));
+                self.expect("[")?;
+                let mut possible_labels = Vec::new();
+                while !self.eat("]") {
+                    if !possible_labels.is_empty() {
+                        self.expect(",")?;
+                    }
+                    possible_labels.push(self.label()?);
+                }
+                TerminatorKind::IndirectBranch { address, possible_labels }
+            }
+            _ => TerminatorKind::Unreachable,
+        };
+        Ok(Terminator::new(kind, span))
+    }
+
+    fn instruction(&mut self, promotions: &PromotionMatrix) -> Result<Instruction, CompileError> {
+        let span = self.span();
+        let starts_with_value = self.rest().starts_with(['%', '@'])
+            || self.rest().strip_prefix('t').is_some_and(|id| id.starts_with(|c: char| c.is_ascii_digit()));
+        let mut result = if starts_with_value {
+            let result = self.value()?;
+            self.expect("=")?;
+            Some(result)
+        } else {
+            None
+        };
+
+        self.skip_whitespace();
+        let opcode_pos = self.pos;
+        let opcode = self.name("an instruction")?;
+        let kind = match opcode {
+            "alloca" => InstructionKind::Alloca { ty: self.ty()? },
+            "store" => {
+                let value = self.value()?;
+                self.expect_keyword("to")?;
+                InstructionKind::Store { value, dest: self.value()? }
+            }
+            "load" => {
+                let ty = self.ty()?;
+                self.expect_keyword("from")?;
+                let mut src = self.value()?;
+                assume_type(&mut src, &IrType::Pointer(Box::new(ty.clone())));
+                InstructionKind::Load { src, ty }
+            }
+            "call" => {
+                let mut func = self.value()?;
+                self.expect("(")?;
+                let args = self.values_until(")")?;
+                self.expect(":")?;
+                let ty = self.ty()?;
+                assume_type(&mut func, &IrType::Pointer(Box::new(ty.clone())));
+                InstructionKind::Call { func, args, ty }
+            }
+            "getelementptr" => {
+                let mut base = self.value()?;
+                self.expect(",")?;
+                let mut index = self.value()?;
+                self.expect(":")?;
+                let element_ty = self.ty()?;
+                assume_type(&mut base, &IrType::Pointer(Box::new(element_ty.clone())));
+                assume_type(&mut index, &IrType::I64);
+                InstructionKind::GetElementPtr { base, index, element_ty }
+            }
+            "cast" => {
+                let mut value = self.value()?;
+                self.expect_keyword("from")?;
+                let from_ty = self.ty()?;
+                self.expect_keyword("to")?;
+                let to_ty = self.ty()?;
+                assume_type(&mut value, &from_ty);
+                InstructionKind::Cast { kind: cast_kind(promotions, &from_ty, &to_ty), value, from_ty, to_ty }
+            }
+            "phi" => self.phi()?,
+            _ => self.operation(opcode, opcode_pos)?,
+        };
+
+        if let Some(result) = result.as_mut() {
+            result.ty = result_type(&kind);
+        }
+
+        let instruction = Instruction::new(kind, span);
+        Ok(match result {
+            Some(result) => instruction.with_result(result),
+            None => instruction,
+        })
+    }
+
+    fn phi(&mut self) -> Result<InstructionKind, CompileError> {
+        let ty = self.ty()?;
+        self.expect("[")?;
+        let mut incoming = Vec::new();
+        while !self.eat("]") {
+            if !incoming.is_empty() {
+                self.expect(",")?;
+            }
+            self.expect("[")?;
+            let mut value = self.value()?;
+            assume_type(&mut value, &ty);
+            self.expect(",")?;
+            let label = self.label()?;
+            self.expect("]")?;
+            incoming.push((value, label.to_string()));
+        }
+        Ok(InstructionKind::Phi { ty, incoming })
+    }
+
+    /// Parses the operands of a vector, binary or unary instruction.
+    fn operation(&mut self, opcode: &str, opcode_pos: usize) -> Result<InstructionKind, CompileError> {
+        if let Some(op) = opcode.strip_prefix("vector.") {
+            let op = VECTOR_OPS
+                .into_iter()
+                .find(|candidate| candidate.to_string() == op)
+                .ok_or_else(|| self.error_at(opcode_pos, format!("unknown vector operation `{op}`")))?;
+            let mut operands = Vec::new();
+            while !self.eat(":") {
+                if !operands.is_empty() {
+                    self.expect(",")?;
+                }
+                operands.push(self.value()?);
+            }
+            let ty = self.ty()?;
+            for operand in &mut operands {
+                assume_type(operand, &ty);
+            }
+            Ok(InstructionKind::Vector { op, operands, ty })
+        } else if let Some(op) = BINARY_OPS.into_iter().find(|candidate| candidate.to_string() == opcode) {
+            let mut left = self.value()?;
+            let mut right = self.value()?;
+            self.expect(",")?;
+            let ty = self.ty()?;
+            assume_type(&mut left, &ty);
+            assume_type(&mut right, &ty);
+            Ok(InstructionKind::Binary { op, left, right, ty })
+        } else if let Some(op) =
+            [IrUnaryOp::Negate, IrUnaryOp::Not].into_iter().find(|candidate| candidate.to_string() == opcode)
+        {
+            let mut operand = self.value()?;
+            let ty = self.ty()?;
+            assume_type(&mut operand, &ty);
+            Ok(InstructionKind::Unary { op, operand, ty })
+        } else {
+            Err(self.error_at(opcode_pos, format!("unknown instruction `{opcode}`")))
+        }
+    }
+}
+
+/// Type of the value an instruction defines.
+fn result_type(kind: &InstructionKind) -> IrType {
+    match kind {
+        InstructionKind::Alloca { ty } => IrType::Pointer(Box::new(ty.clone())),
+        InstructionKind::GetElementPtr { element_ty, .. } => IrType::Pointer(Box::new(element_ty.clone())),
+        InstructionKind::Cast { to_ty, .. } => to_ty.clone(),
+        InstructionKind::Load { ty, .. }
+        | InstructionKind::Binary { ty, .. }
+        | InstructionKind::Unary { ty, .. }
+        | InstructionKind::Call { ty, .. }
+        | InstructionKind::Phi { ty, .. }
+        | InstructionKind::Vector { ty, .. } => ty.clone(),
+        InstructionKind::Store { .. } => UNTYPED,
+    }
+}
+
+/// Parses a numeric literal such as `-3i8`, `42u64` or `1.5f32`.
+fn number_literal(word: &str) -> Option<IrLiteralValue> {
+    macro_rules! suffixed {
+        ($($suffix:literal => $variant:ident),* $(,)?) => {
+            $(
+                if let Some(number) = word.strip_suffix($suffix) {
+                    return number.parse().ok().map(IrLiteralValue::$variant);
+                }
+            )*
+        };
+    }
+
+    suffixed!(
+        "i8" => I8, "i16" => I16, "i32" => I32, "i64" => I64,
+        "u8" => U8, "u16" => U16, "u32" => U32, "u64" => U64,
+        "f32" => F32, "f64" => F64,
+    );
+    None
+}
@@ -5,9 +5,12 @@ use super::cfg::ControlFlowGraph;
 use super::dominance::DominanceInfo;
 use super::function::Function;
 use super::instruction::{Instruction, InstructionKind};
+use super::terminator::TerminatorKind;
 use super::types::IrType;
 use super::value::{Value, ValueKind};
+use petgraph::Direction;
 use petgraph::graph::NodeIndex;
+use petgraph::visit::Dfs;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::sync::Arc;
@@ -27,6 +30,8 @@ pub struct SsaTransformer {
     value_stack: HashMap<Arc<str>, Vec<Value>>,
     /// Map to store the type of each variable
     variable_types: HashMap<Arc<str>, IrType>,
+    /// Temporary IDs of the scalar `alloca`s promoted to SSA values
+    promoted: HashSet<u64>,
     /// Reusable buffer for string formatting to reduce allocations
     format_buffer: String,
 }
@@ -47,6 +52,7 @@ impl SsaTransformer {
             phi_variables: HashSet::with_capacity(ESTIMATED_VARIABLES),
             value_stack: HashMap::with_capacity(ESTIMATED_VARIABLES),
             variable_types: HashMap::with_capacity(ESTIMATED_VARIABLES),
+            promoted: HashSet::with_capacity(ESTIMATED_VARIABLES),
             format_buffer: String::with_capacity(32),
         }
     }
@@ -97,6 +103,9 @@ impl SsaTransformer {
         // Rename variables using recursive dominator tree traversal
         self.rename_variables_recursive(func, entry_idx);
 
+        // Drop the phi-functions of variables that are out of scope at their join
+        Self::remove_incomplete_phis(func, entry_idx);
+
         // Verify the transformation produced valid SSA form
         self.verify_ssa_form(func).map_err(|e| format!("SSA verification failed: {e}"))?;
 
@@ -109,6 +118,7 @@ impl SsaTransformer {
         self.phi_variables.clear();
         self.value_stack.clear();
         self.variable_types.clear();
+        self.promoted.clear();
         // Don't reset temp_counter here - it should be unique across the entire module
     }
 
@@ -127,57 +137,56 @@ impl SsaTransformer {
     }
 
     /// Identifies variables that need phi-functions by analyzing definitions.
+    ///
+    /// Only `alloca`s of scalar types are promoted: arrays and structs stay in
+    /// memory, and stores through their element or field addresses are left
+    /// alone.
     fn identify_phi_variables(&mut self, cfg: &ControlFlowGraph) {
         self.phi_variables.clear();
         self.var_defs.clear();
         self.variable_types.clear();
+        self.promoted.clear();
+
+        for block in cfg.blocks() {
+            for instruction in &block.instructions {
+                if let InstructionKind::Alloca { ty } = &instruction.kind
+                    && !matches!(ty, IrType::Array(..) | IrType::Struct(..))
+                    && let Some(Value { kind: ValueKind::Temporary(temp_id), .. }) = &instruction.result
+                {
+                    self.promoted.insert(*temp_id);
+                }
+            }
+        }
 
         // For each block, find variable definitions
         for node_idx in cfg.graph().node_indices() {
             if let Some(block) = cfg.get_block(&cfg.graph()[node_idx].label) {
                 for instruction in &block.instructions {
-                    match &instruction.kind {
+                    let (value, temp_id, ty) = match (&instruction.kind, &instruction.result) {
                         // Check for store instructions that define variables
-                        InstructionKind::Store { value: _, dest } => {
-                            if let ValueKind::Temporary(temp_id) = &dest.kind {
-                                // Get the variable name from debug info if available
-                                let var_name = self.extract_variable_name(dest, *temp_id);
-                                // Store the variable type
-                                if let IrType::Pointer(inner_ty) = &dest.ty {
-                                    self.variable_types.insert(var_name.clone(), (**inner_ty).clone());
-                                }
-
-                                // Record this definition
-                                self.var_defs
-                                    .entry(var_name.clone())
-                                    .or_default()
-                                    .entry(node_idx)
-                                    .or_default()
-                                    .push(var_name);
+                        (InstructionKind::Store { value: _, dest }, _) => match (&dest.kind, &dest.ty) {
+                            (ValueKind::Temporary(temp_id), IrType::Pointer(inner_ty)) => {
+                                (dest, *temp_id, (**inner_ty).clone())
                             }
-                        }
+                            _ => continue,
+                        },
                         // Check for alloca instructions that define variables
-                        InstructionKind::Alloca { ty } => {
-                            if let Some(result) = &instruction.result
-                                && let ValueKind::Temporary(temp_id) = &result.kind
-                            {
-                                // Get the variable name from debug info if available
-                                let var_name = self.extract_variable_name(result, *temp_id);
-
-                                // Store the variable type
-                                self.variable_types.insert(var_name.clone(), ty.clone());
-
-                                // Record this definition
-                                self.var_defs
-                                    .entry(var_name.clone())
-                                    .or_default()
-                                    .entry(node_idx)
-                                    .or_default()
-                                    .push(var_name);
-                            }
-                        }
-                        _ => {}
+                        (InstructionKind::Alloca { ty }, Some(result)) => match &result.kind {
+                            ValueKind::Temporary(temp_id) => (result, *temp_id, ty.clone()),
+                            _ => continue,
+                        },
+                        _ => continue,
+                    };
+                    if !self.promoted.contains(&temp_id) {
+                        continue;
                     }
+
+                    // Get the variable name from debug info if available
+                    let var_name = self.extract_variable_name(value, temp_id);
+                    self.variable_types.insert(var_name.clone(), ty);
+
+                    // Record this definition
+                    self.var_defs.entry(var_name.clone()).or_default().entry(node_idx).or_default().push(var_name);
                 }
             }
         }
@@ -267,7 +276,9 @@ impl SsaTransformer {
 
     /// Replaces a value with the current SSA value from the stack if it's a variable
     fn replace_value_with_current_ssa(&mut self, value: &mut Value) {
-        if let ValueKind::Temporary(temp_id) = &value.kind {
+        if let ValueKind::Temporary(temp_id) = &value.kind
+            && self.promoted.contains(temp_id)
+        {
             // Get the variable name from debug info if available
             let var_name = self.extract_variable_name(value, *temp_id);
 
@@ -282,6 +293,11 @@ impl SsaTransformer {
 
     /// Renames variables to ensure single assignment using recursive dominator tree traversal.
     fn rename_variables_recursive(&mut self, func: &mut Function, block_idx: NodeIndex) {
+        // Remember the depth of every stack, so that the definitions made by
+        // this block and its dominator subtree can be discarded afterwards
+        let depths: HashMap<Arc<str>, usize> =
+            self.value_stack.iter().map(|(var_name, stack)| (var_name.clone(), stack.len())).collect();
+
         // Process this block
         self.process_block(func, block_idx);
 
@@ -294,42 +310,9 @@ impl SsaTransformer {
             self.rename_variables_recursive(func, child_idx);
         }
 
-        // Pop values from stack after processing children (to maintain proper scoping)
-        self.pop_block_values(block_idx, func);
-    }
-
-    /// Pops values from the stack after processing a block's children
-    fn pop_block_values(&mut self, block_idx: NodeIndex, func: &Function) {
-        // Get block label
-        let block_label = {
-            let graph = func.cfg.graph();
-            if let Some(block) = graph.node_weight(block_idx) {
-                block.label.to_string()
-            } else {
-                return;
-            }
-        };
-
-        // Get block
-        let Some(block) = func.cfg.get_block(&block_label) else {
-            return;
-        };
-
-        // For each instruction in the block, if it defines a variable, pop it from the stack
-        for instruction in &block.instructions {
-            if let Some(result) = &instruction.result
-                && let ValueKind::Temporary(_) = &result.kind
-            {
-                // Get the variable name from debug info if available
-                if let Some(debug_info) = &result.debug_info
-                    && let Some(var_name) = &debug_info.name
-                {
-                    // Pop the value from the stack - use Arc<str> directly
-                    if let Some(stack) = self.value_stack.get_mut(var_name) {
-                        stack.pop();
-                    }
-                }
-            }
+        // Pop the values defined below this block (to maintain proper scoping)
+        for (var_name, stack) in &mut self.value_stack {
+            stack.truncate(depths.get(var_name).copied().unwrap_or(0));
         }
     }
 
@@ -391,13 +374,16 @@ impl SsaTransformer {
                     // For store instructions, we need to replace the value with current SSA value
                     self.replace_value_with_current_ssa(value);
 
-                    // For store instructions, we need to replace the destination with a new SSA value
-                    if let ValueKind::Temporary(temp_id) = &dest.kind {
+                    // For stores to a promoted variable, we need to replace the destination with a new SSA value
+                    if let ValueKind::Temporary(temp_id) = &dest.kind
+                        && self.promoted.contains(temp_id)
+                    {
                         // Get the variable name from debug info if available
                         let var_name = self.extract_variable_name(dest, *temp_id);
 
-                        // Create a new unique name for this definition
-                        let ty = dest.ty.clone();
+                        // Create a new unique name for this definition, typed as the
+                        // value of the variable rather than as its address
+                        let ty = self.variable_types.get(&var_name).cloned().unwrap_or_else(|| dest.ty.clone());
                         let new_value = Value::new_temporary(self.temp_counter, ty).with_debug_info(
                             Some(var_name.clone()),
                             dest.debug_info.as_ref().map(|d| d.source_span.clone()).unwrap_or_default(),
@@ -424,6 +410,15 @@ impl SsaTransformer {
                     // For unary operations, replace operand with current SSA value
                     self.replace_value_with_current_ssa(operand);
                 }
+                InstructionKind::Cast { value, .. } => {
+                    // For casts, replace the converted value with current SSA value
+                    self.replace_value_with_current_ssa(value);
+                }
+                InstructionKind::GetElementPtr { base, index, .. } => {
+                    // Element addresses are computed from the current index
+                    self.replace_value_with_current_ssa(base);
+                    self.replace_value_with_current_ssa(index);
+                }
                 InstructionKind::Call { func: callee, args, .. } => {
                     // For call instructions, we need to replace the function and arguments with current SSA values
                     self.replace_value_with_current_ssa(callee);
@@ -438,10 +433,45 @@ impl SsaTransformer {
             }
         }
 
+        // Terminators read variables too
+        match &mut block.terminator.kind {
+            TerminatorKind::Return { value, .. }
+            | TerminatorKind::ConditionalBranch { condition: value, .. }
+            | TerminatorKind::IndirectBranch { address: value, .. }
+            | TerminatorKind::Switch { value, .. } => self.replace_value_with_current_ssa(value),
+            _ => {}
+        }
+
         // Process successors and add incoming edges to their phi-functions
         let successors: Vec<NodeIndex> =
             func.cfg.graph().neighbors_directed(block_idx, petgraph::Direction::Outgoing).collect();
 
+        // Phi-functions emitted by the generator merge expression values rather
+        // than variables: rename the operands flowing in from this block
+        for &succ_idx in &successors {
+            let Some(succ_block) = func.cfg.graph_mut().node_weight_mut(succ_idx) else {
+                continue;
+            };
+            for instruction in &mut succ_block.instructions {
+                let InstructionKind::Phi { incoming, .. } = &mut instruction.kind else {
+                    continue;
+                };
+                if instruction
+                    .result
+                    .as_ref()
+                    .and_then(|result| result.debug_info.as_ref())
+                    .is_some_and(|d| d.name.is_some())
+                {
+                    continue;
+                }
+                for (value, pred_label) in incoming.iter_mut() {
+                    if *pred_label == block_label {
+                        self.replace_value_with_current_ssa(value);
+                    }
+                }
+            }
+        }
+
         // For each variable that needs phi-functions, add its current value to phi-functions in successor blocks
         for var_name in &self.phi_variables {
             // Get the current value of this variable from the stack
@@ -486,6 +516,75 @@ impl SsaTransformer {
         }
     }
 
+    /// Removes variable phi-functions that lack a value for some reachable
+    /// predecessor.
+    ///
+    /// A variable declared inside a loop body or a branch is not defined on
+    /// every edge into the join, so it is out of scope there and its
+    /// phi-function is never used. Phi-functions that merge the result of a
+    /// removed one are removed too, until no more change.
+    fn remove_incomplete_phis(func: &mut Function, entry_idx: NodeIndex) {
+        let graph = func.cfg.graph();
+        let mut reachable = HashSet::new();
+        let mut dfs = Dfs::new(graph, entry_idx);
+        while let Some(node) = dfs.next(graph) {
+            reachable.insert(node);
+        }
+        let predecessors: HashMap<Arc<str>, Vec<Arc<str>>> = reachable
+            .iter()
+            .map(|&node| {
+                let preds = graph
+                    .neighbors_directed(node, Direction::Incoming)
+                    .filter(|pred| reachable.contains(pred))
+                    .map(|pred| graph[pred].label.clone())
+                    .collect();
+                (graph[node].label.clone(), preds)
+            })
+            .collect();
+
+        let mut removed: HashSet<u64> = HashSet::new();
+        loop {
+            let before = removed.len();
+            for block in func.cfg.blocks() {
+                let Some(preds) = predecessors.get(&block.label) else { continue };
+                for instruction in &block.instructions {
+                    let (InstructionKind::Phi { incoming, .. }, Some(result)) =
+                        (&instruction.kind, &instruction.result)
+                    else {
+                        continue;
+                    };
+                    let ValueKind::Temporary(temp_id) = result.kind else { continue };
+                    if result.debug_info.as_ref().is_none_or(|d| d.name.is_none()) || removed.contains(&temp_id) {
+                        continue;
+                    }
+                    let incomplete = preds.iter().any(|pred| !incoming.iter().any(|(_, label)| **label == **pred));
+                    let merges_removed = incoming
+                        .iter()
+                        .any(|(value, _)| matches!(value.kind, ValueKind::Temporary(id) if removed.contains(&id)));
+                    if incomplete || merges_removed {
+                        removed.insert(temp_id);
+                    }
+                }
+            }
+            if removed.len() == before {
+                break;
+            }
+        }
+
+        if removed.is_empty() {
+            return;
+        }
+        for block in func.cfg.blocks_mut() {
+            block.instructions.retain(|instruction| {
+                !matches!(instruction.kind, InstructionKind::Phi { .. })
+                    || !matches!(
+                        instruction.result.as_ref().map(|result| &result.kind),
+                        Some(ValueKind::Temporary(id)) if removed.contains(id)
+                    )
+            });
+        }
+    }
+
     /// Verifies that the function is in proper SSA form.
     /// In SSA form, each temporary ID should be unique across the entire function.
     ///
@@ -161,6 +161,17 @@ impl TypePromotionEngine {
         }
     }
 
+    /// Looks up the rule for an explicit conversion from `from` to `to`.
+    ///
+    /// Returns `None` when the promotion matrix has no rule for the pair or
+    /// forbids the conversion.
+    #[must_use]
+    pub fn explicit_cast_rule(&self, from: &IrType, to: &IrType) -> Option<&PromotionRule> {
+        self.get_promotion_matrix()
+            .get_promotion_rule(from, to)
+            .filter(|rule| !matches!(rule, PromotionRule::Forbidden { .. }))
+    }
+
     /// Inserts promotion casts for binary operations
     pub fn insert_promotion_casts(
         &self,
@@ -78,6 +78,15 @@ impl Default for ScopeId {
     }
 }
 
+impl std::str::FromStr for ScopeId {
+    type Err = uuid::Error;
+
+    /// Parses a `ScopeId` from the UUID text produced by its `Display` implementation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::parse_str(s).map(Self)
+    }
+}
+
 /// A globally unique identifier representing a resource within the IR system.
 ///
 /// This can refer to various high-level resources such as types, modules,
@@ -0,0 +1,260 @@
+// src/ir/verifier.rs
+//! Structural verifier for IR modules.
+//!
+//! [`IrVerifier`] is a [`Phase`] that checks the invariants optimization passes
+//! rely on and reports every violation as a [`CompileError`]:
+//!
+//! * every use of an instruction result is dominated by its definition
+//!   (`E3010`); operands without a defining instruction, such as parameters,
+//!   globals and variable slots, are not checked
+//! * phi incoming labels match the predecessors of their block (`E3011`);
+//!   values from unreachable predecessors may be omitted
+//! * `Binary` and `Cast` operands have the type the instruction declares
+//!   (`E3012`)
+//! * every block reachable from the entry ends in a terminator other than
+//!   `unreachable` (`E3013`)
+//!
+//! [`run_verified_pipeline`](super::optimizer::run_verified_pipeline) runs the
+//! verifier after every optimization pass.
+
+use super::optimizer::Phase;
+use super::value::ValueId;
+use super::{BasicBlock, DominanceInfo, Function, InstructionKind, IrType, Module, Value};
+use crate::error::compile_error::CompileError;
+use crate::error::error_code::ErrorCode;
+use crate::location::source_span::SourceSpan;
+use petgraph::Direction;
+use petgraph::graph::NodeIndex;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Position of an instruction: its block and its index within the block.
+type Site = (NodeIndex, usize);
+
+/// Phase that verifies a module and records the violations it finds.
+#[derive(Debug, Default)]
+pub struct IrVerifier {
+    errors: Vec<CompileError>,
+}
+
+impl IrVerifier {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Errors found by the last run, empty if the module was valid.
+    #[must_use]
+    pub fn errors(&self) -> &[CompileError] {
+        &self.errors
+    }
+
+    /// Takes the errors found by the last run, leaving the verifier empty.
+    pub fn take_errors(&mut self) -> Vec<CompileError> {
+        std::mem::take(&mut self.errors)
+    }
+}
+
+impl Phase for IrVerifier {
+    fn name(&self) -> &'static str {
+        "IR Verifier"
+    }
+
+    fn run(&mut self, ir: &mut Module) {
+        self.errors = verify_module(ir).err().unwrap_or_default();
+    }
+}
+
+/// Verifies every function of `module`.
+///
+/// # Errors
+///
+/// Returns every violation found, in function and block order.
+pub fn verify_module(module: &mut Module) -> Result<(), Vec<CompileError>> {
+    let errors: Vec<CompileError> = module.functions.iter_mut().flat_map(verify_function).collect();
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+/// Verifies a single function, returning the violations found.
+///
+/// Takes the function mutably because dominance is computed from the cached
+/// reverse post-order of its CFG.
+pub fn verify_function(function: &mut Function) -> Vec<CompileError> {
+    if function.cfg.get_entry_block_index().is_none() {
+        let span = function.attributes.source_span.clone().unwrap_or_default();
+        let message = format!("function `{}` has no entry block `{}`", function.name, function.cfg.entry_label);
+        return vec![verifier_error(ErrorCode::E3008, message, span)];
+    }
+    let mut dominance = DominanceInfo::new();
+    if let Err(message) = dominance.compute_dominators(&mut function.cfg) {
+        return vec![verifier_error(ErrorCode::E3008, message, SourceSpan::default())];
+    }
+
+    let function = &*function;
+    let reachable: HashSet<NodeIndex> = function.cfg.dfs_post_order().collect();
+    let checker = Checker { function, dominance, definitions: definitions(function), reachable };
+    let mut errors = Vec::new();
+    for index in function.cfg.graph().node_indices() {
+        if checker.reachable.contains(&index) {
+            checker.check_block(index, &mut errors);
+        }
+    }
+    errors
+}
+
+/// Maps every instruction result to the site that defines it.
+fn definitions(function: &Function) -> HashMap<ValueId, Site> {
+    let graph = function.cfg.graph();
+    graph
+        .node_indices()
+        .flat_map(|index| {
+            graph[index].instructions.iter().enumerate().filter_map(move |(position, instruction)| {
+                instruction.result.as_ref().map(|result| (result.id, (index, position)))
+            })
+        })
+        .collect()
+}
+
+struct Checker<'a> {
+    function: &'a Function,
+    dominance: DominanceInfo,
+    definitions: HashMap<ValueId, Site>,
+    reachable: HashSet<NodeIndex>,
+}
+
+impl Checker<'_> {
+    fn block(&self, index: NodeIndex) -> &BasicBlock {
+        &self.function.cfg.graph()[index]
+    }
+
+    /// Checks a block reachable from the entry.
+    fn check_block(&self, index: NodeIndex, errors: &mut Vec<CompileError>) {
+        let block = self.block(index);
+        for (position, instruction) in block.instructions.iter().enumerate() {
+            let span = &instruction.debug_info.source_span;
+            match &instruction.kind {
+                InstructionKind::Phi { incoming, .. } => {
+                    self.check_phi(index, incoming, span, errors);
+                    continue;
+                }
+                InstructionKind::Binary { left, right, ty, .. } => {
+                    self.check_type(left, ty, "binary", span, errors);
+                    self.check_type(right, ty, "binary", span, errors);
+                }
+                InstructionKind::Cast { value, from_ty, .. } => self.check_type(value, from_ty, "cast", span, errors),
+                _ => {}
+            }
+            for operand in instruction_operands(&instruction.kind) {
+                self.check_dominance(operand, (index, position), span, errors);
+            }
+        }
+
+        let terminator = block.terminator();
+        let span = &terminator.debug_info.source_span;
+        for operand in terminator.get_used_values() {
+            self.check_dominance(operand, (index, block.instructions.len()), span, errors);
+        }
+        if !terminator.is_terminator() {
+            let message = format!(
+                "block `{}` in function `{}` is reachable but ends in `unreachable`",
+                block.label, self.function.name
+            );
+            errors.push(verifier_error(ErrorCode::E3013, message, span.clone()));
+        }
+    }
+
+    /// Reports a use at `site` that its definition does not dominate.
+    fn check_dominance(&self, value: &Value, site: Site, span: &SourceSpan, errors: &mut Vec<CompileError>) {
+        let Some(&definition) = self.definitions.get(&value.id) else {
+            return;
+        };
+        if !self.defined_before(definition, site) {
+            let message = format!(
+                "`{value}` is used in block `{}` of function `{}` where its definition in block `{}` does not dominate it",
+                self.block(site.0).label,
+                self.function.name,
+                self.block(definition.0).label
+            );
+            errors.push(verifier_error(ErrorCode::E3010, message, span.clone()));
+        }
+    }
+
+    fn defined_before(&self, (def_block, def_position): Site, (use_block, use_position): Site) -> bool {
+        if def_block == use_block {
+            def_position < use_position
+        } else {
+            self.dominance.dominates(def_block, use_block)
+        }
+    }
+
+    /// Checks that the incoming labels of a phi are exactly the reachable
+    /// predecessors of its block, plus possibly unreachable ones, and that each
+    /// incoming value is available at the end of its predecessor.
+    fn check_phi(
+        &self, index: NodeIndex, incoming: &[(Value, String)], span: &SourceSpan, errors: &mut Vec<CompileError>,
+    ) {
+        let graph = self.function.cfg.graph();
+        let block = self.block(index);
+        let predecessors: HashMap<&str, NodeIndex> = graph
+            .neighbors_directed(index, Direction::Incoming)
+            .map(|predecessor| (graph[predecessor].label.as_ref(), predecessor))
+            .collect();
+        let labels: HashSet<&str> = incoming.iter().map(|(_, label)| label.as_str()).collect();
+
+        let mut mismatch = |message: String| errors.push(verifier_error(ErrorCode::E3011, message, span.clone()));
+        for label in labels.iter().filter(|label| !predecessors.contains_key(*label)) {
+            mismatch(format!(
+                "phi in block `{}` has an incoming value from `{label}`, which is not a predecessor",
+                block.label
+            ));
+        }
+        // No value flows in from an unreachable predecessor.
+        let mut missing: Vec<&&str> = predecessors
+            .iter()
+            .filter(|(label, predecessor)| !labels.contains(*label) && self.reachable.contains(predecessor))
+            .map(|(label, _)| label)
+            .collect();
+        missing.sort();
+        for label in missing {
+            mismatch(format!("phi in block `{}` has no incoming value from predecessor `{label}`", block.label));
+        }
+
+        for (value, label) in incoming {
+            if let Some(&predecessor) = predecessors.get(label.as_str()) {
+                let end = (predecessor, self.block(predecessor).instructions.len());
+                self.check_dominance(value, end, span, errors);
+            }
+        }
+    }
+
+    fn check_type(
+        &self, operand: &Value, expected: &IrType, instruction: &str, span: &SourceSpan, errors: &mut Vec<CompileError>,
+    ) {
+        if operand.ty != *expected {
+            let message = format!(
+                "{instruction} operand `{operand}` in function `{}` has type {} but the instruction expects {expected}",
+                self.function.name, operand.ty
+            );
+            errors.push(verifier_error(ErrorCode::E3012, message, span.clone()));
+        }
+    }
+}
+
+/// Operands of an instruction other than phi incoming values.
+fn instruction_operands(kind: &InstructionKind) -> Vec<&Value> {
+    match kind {
+        InstructionKind::Alloca { .. } | InstructionKind::Phi { .. } => Vec::new(),
+        InstructionKind::Store { value, dest } => vec![value, dest],
+        InstructionKind::Load { src: value, .. }
+        | InstructionKind::Unary { operand: value, .. }
+        | InstructionKind::Cast { value, .. } => vec![value],
+        InstructionKind::Binary { left, right, .. } => vec![left, right],
+        InstructionKind::GetElementPtr { base, index, .. } => vec![base, index],
+        InstructionKind::Call { func, args, .. } => std::iter::once(func).chain(args).collect(),
+        InstructionKind::Vector { operands, .. } => operands.iter().collect(),
+    }
+}
+
+fn verifier_error(code: ErrorCode, message: impl Into<Arc<str>>, span: SourceSpan) -> CompileError {
+    CompileError::IrGeneratorError { code: Some(code), message: message.into(), span, help: None }
+}
@@ -13,10 +13,14 @@ use crate::{
     error::compile_error::CompileError,
     error::error_code::ErrorCode,
     location::line_tracker::LineTracker,
-    tokens::{token::Token, token_kind::TokenKind},
+    tokens::{parsers::escape::decode_escapes, token::Token, token_kind::TokenKind},
 };
 use logos::Logos;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::Range,
+    sync::Arc,
+};
 
 /// The Lexer struct handles the tokenization of source code.
 ///
@@ -28,6 +32,7 @@ pub struct Lexer<'a> {
     inner: logos::Lexer<'a, TokenKind>,
     line_tracker: LineTracker,
     source_len: usize, // Move before bool
+    pending: VecDeque<Result<Token, CompileError>>,
     eof_emitted: bool,
 }
 
@@ -57,7 +62,7 @@ impl<'a> Lexer<'a> {
         let line_tracker = LineTracker::new(file_path, source.to_owned());
         let inner = TokenKind::lexer(source);
         let source_len = source.len();
-        Lexer { inner, line_tracker, eof_emitted: false, source_len }
+        Lexer { inner, line_tracker, eof_emitted: false, source_len, pending: VecDeque::new() }
     }
 
     /// Returns a reference to the line tracker containing position information.
@@ -97,6 +102,9 @@ impl<'a> Lexer<'a> {
     /// ```
     #[inline]
     pub fn next_token(&mut self) -> Option<Result<Token, CompileError>> {
+        if let Some(queued) = self.pending.pop_front() {
+            return Some(queued);
+        }
         if self.eof_emitted {
             return None;
         }
@@ -109,8 +117,29 @@ impl<'a> Lexer<'a> {
             (Ok(TokenKind::Eof), eof_range)
         };
 
+        let start = range.start;
         let span = self.line_tracker.span_for(range);
         Some(match kind_result {
+            Ok(TokenKind::StringLiteral(raw)) => {
+                let text = self.decode_literal(&raw, start);
+                self.pending.push_back(Ok(Token { kind: TokenKind::StringLiteral(Arc::from(text)), span }));
+                return self.pending.pop_front();
+            }
+            Ok(TokenKind::CharLiteral(raw)) => {
+                let text = self.decode_literal(&raw, start);
+                if self.pending.is_empty() && text.chars().count() != 1 {
+                    self.pending.push_back(Err(CompileError::LexerError {
+                        code: Some(ErrorCode::E0013),
+                        message: Arc::from(format!(
+                            "Character literal must contain exactly one character, found '{raw}'"
+                        )),
+                        span: span.clone(),
+                        help: None,
+                    }));
+                }
+                self.pending.push_back(Ok(Token { kind: TokenKind::CharLiteral(Arc::from(text)), span }));
+                return self.pending.pop_front();
+            }
             Ok(kind) => Ok(Token { kind, span }),
             Err(()) => Err(CompileError::LexerError {
                 code: Some(ErrorCode::E0001),
@@ -120,6 +149,25 @@ impl<'a> Lexer<'a> {
             }),
         })
     }
+
+    /// Decodes the escapes of a string or char literal whose opening quote sits at `start`.
+    ///
+    /// Malformed escapes are queued as errors spanning just the escape; the
+    /// caller emits the token right after them with the best-effort text.
+    fn decode_literal(&mut self, raw: &str, start: usize) -> String {
+        let (text, errors) = decode_escapes(raw);
+        let content_start = start + 1;
+        for error in errors {
+            let range: Range<usize> = content_start + error.range.start..content_start + error.range.end;
+            self.pending.push_back(Err(CompileError::LexerError {
+                code: Some(error.code),
+                message: Arc::from(error.message),
+                span: self.line_tracker.span_for(range),
+                help: None,
+            }));
+        }
+        text
+    }
 }
 
 impl Iterator for Lexer<'_> {
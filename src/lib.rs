@@ -14,25 +14,33 @@
 /// ## Important modules:
 /// * `lexer` - Performs lexical analysis, converting source text to tokens
 /// * `parser` - Converts tokens into abstract syntax trees (AST)
+/// * `resolver` - Loads the files named by `import` statements
 /// * `semantic` - Performs semantic analysis and type checking
 /// * `ir` - Manages intermediate representation of the code
 /// * `printers` - Handles pretty-printing and formatted output of AST/IR for debugging
 /// * `asm` - Manages assembly code generation
 /// * `fmtlike` - Provides utilities for formatting and pretty-printing
 /// * `codegen` - Orchestrates code generation, dispatching to backend-specific emitters (e.g., asm)
+/// * `driver` - Assembles and links generated code with the native toolchain
 /// * `error` - Defines error types and error handling utilities
+/// * `fuzz` - Generates random well-typed programs and shrinks failing ones
+/// * `test_runner` - Checks annotated `.vn` files against their expected errors, IR and results
 /// * `cli` - Provides command-line interface functionality
 pub mod asm;
 pub mod cli;
 pub mod codegen;
+pub mod driver;
 pub mod error;
 pub mod fmtlike;
+pub mod fuzz;
 pub mod ir;
 pub mod lexer;
 pub mod location;
 pub mod parser;
 pub mod printers;
+pub mod resolver;
 pub mod semantic;
+pub mod test_runner;
 pub mod time;
 pub mod tokens;
 pub mod utils;
@@ -126,6 +126,12 @@ impl LineTracker {
         SourceSpan::new(self.file_path.clone(), self.location_for(range.start), self.location_for(range.end))
     }
 
+    /// Path of the tracked source file
+    #[must_use]
+    pub fn file_path(&self) -> &str {
+        &self.file_path
+    }
+
     /// Gets a specific line from the source (1-indexed)
     #[must_use]
     pub fn get_line(&self, line_number: usize) -> Option<&str> {
@@ -1,18 +1,22 @@
 // run --package jsavrs --bin jsavrs -- -i C:/dev/visualStudio/transpiler/Vandior/input.vn -v
 use clap::Parser;
-use console::style;
 // use jsavrs::asm::{Abi, AssemblyFile, DataDirective, GPRegister64, Immediate, Instruction, Operand, X86Register};
-use jsavrs::cli::Args;
+use jsavrs::asm::AssemblyFile;
+use jsavrs::cli::{Args, AssemblerKind, Command, EmitKind};
 use jsavrs::codegen::asmgen::AsmGen;
-use jsavrs::error::error_reporter::ErrorReporter;
-use jsavrs::ir::optimizer::constant_folding::optimizer::ConstantFoldingOptimizer;
-use jsavrs::ir::{Phase, generator::IrGenerator, optimizer::DeadCodeElimination, run_pipeline};
+use jsavrs::driver::Toolchain;
+use jsavrs::error::error_reporter::{ErrorFormat, ErrorReporter};
+use jsavrs::ir::generator::IrGenerator;
+use jsavrs::ir::interpreter::Interpreter;
+use jsavrs::ir::optimizer::PassManager;
 use jsavrs::lexer::Lexer;
+use jsavrs::location::line_tracker::LineTracker;
 use jsavrs::parser::jsav_parser::JsavParser;
-//use jsavrs::printers::ast_printer::pretty_print_stmt;
-use jsavrs::semantic::type_checker::TypeChecker;
+use jsavrs::printers::ast_printer::pretty_print_stmt;
+use jsavrs::resolver::ModuleResolver;
 use jsavrs::time::timer::{AutoTimer, Timer};
 use jsavrs::{error::compile_error::CompileError, lexer::lexer_tokenize_with_errors};
+use std::fmt::Write as _;
 use std::process;
 use std::{
     fs,
@@ -25,10 +29,41 @@ use std::{
 #[global_allocator]
 static ALLOC: dhat::Alloc = dhat::Alloc;
 
-// Helper function per gestire e stampare errori I/O
-fn handle_io_error<T: std::fmt::Display>(error_type: &str, e: T) {
-    eprintln!("{} {}: {}\n", style("ERROR:").red().bold(), style(error_type).red(), style(e).yellow());
+// Helper function per gestire e stampare errori I/O nel formato scelto con --error-format
+fn handle_io_error(e: std::io::Error, file_path: &Path, format: ErrorFormat) -> ! {
+    let line_tracker = LineTracker::new(&file_path.to_string_lossy(), String::new());
+    let error_reporter = ErrorReporter::new(line_tracker).with_format(format);
+    eprintln!("{}", error_reporter.report_errors(vec![CompileError::IoError(e)]));
+    process::exit(1); // esce con codice 1
 }
+
+// Writes the artefact selected with --emit, reporting failures as CompileError::IoError
+fn write_artifact(path: &Path, contents: &str, error_reporter: &ErrorReporter) {
+    if let Err(e) = fs::write(path, contents) {
+        let error = std::io::Error::new(e.kind(), format!("cannot write {}: {e}", path.display()));
+        eprintln!("{}", error_reporter.report_errors(vec![CompileError::IoError(error)]));
+        process::exit(1);
+    }
+    println!("output written to {}", path.display());
+}
+
+// Assembles (and for --emit=exe links) the generated code with the native toolchain
+fn build_native(
+    emit: EmitKind, assembler: AssemblerKind, assembly_file: &AssemblyFile, path: &Path, error_reporter: &ErrorReporter,
+) {
+    let toolchain = Toolchain::default().with_assembler(assembler.assembler());
+    let result = if emit == EmitKind::Obj {
+        toolchain.assemble(assembly_file, path)
+    } else {
+        toolchain.build_executable(assembly_file, path)
+    };
+    if let Err(e) = result {
+        eprintln!("{}", error_reporter.report_errors(vec![e]));
+        process::exit(1);
+    }
+    println!("output written to {}", path.display());
+}
+
 const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
 const UNIT_LEN: usize = UNITS.len() - 1;
 
@@ -46,23 +81,21 @@ fn format_size(bytes: usize) -> (f64, &'static str) {
     (size, UNITS[unit])
 }
 
-#[allow(clippy::explicit_auto_deref, clippy::unused_unit, clippy::unnecessary_wraps)]
+#[allow(clippy::explicit_auto_deref, clippy::unused_unit, clippy::unnecessary_wraps, clippy::too_many_lines)]
 fn main() -> Result<(), CompileError> {
     #[cfg(feature = "dhat-heaps")]
     let _dhat = dhat::Profiler::new_heap();
 
     let args = Args::parse();
     let file_path: &Path = args.input.as_path();
+    let output_path = args.output_path();
     let read_file_timer_name = format!("reading file {}", file_path.display());
 
     // Read input file with error styling
     let input = {
         let _io_timer = AutoTimer::new(&read_file_timer_name);
 
-        fs::read_to_string(file_path).unwrap_or_else(|e| {
-            handle_io_error("I/O", e);
-            process::exit(1); // esce con codice 1
-        })
+        fs::read_to_string(file_path).unwrap_or_else(|e| handle_io_error(e, file_path, args.error_format))
     };
 
     let size_bytes = input.len();
@@ -70,14 +103,14 @@ fn main() -> Result<(), CompileError> {
     println!("total of bytes read: {size} {unit}");
 
     let file_path_str: &str = file_path.to_str().unwrap_or_else(|| {
-        handle_io_error("I/O", std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid file path"));
-        process::exit(1);
+        let error = std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid file path");
+        handle_io_error(error, file_path, args.error_format)
     });
 
     let _total_timer = AutoTimer::new("Total Execution"); // Timer totale
     let mut lexer = Lexer::new(file_path_str, &input);
-    let line_tracker = lexer.get_line_tracker();
-    let error_reporter: ErrorReporter = ErrorReporter::new(line_tracker.clone());
+    let line_tracker = lexer.get_line_tracker().clone();
+    let mut error_reporter: ErrorReporter = ErrorReporter::new(line_tracker.clone()).with_format(args.error_format);
     let lexer_timer = Timer::new("Lexer Tokenization");
     let (tokens, lexer_errors) = lexer_tokenize_with_errors(&mut lexer);
     println!("{lexer_timer}");
@@ -88,6 +121,14 @@ fn main() -> Result<(), CompileError> {
 
     // Print tokens with color if verbose
     println!("{} tokens found", tokens.len());
+    if args.emit == EmitKind::Tokens {
+        let listing = tokens.iter().fold(String::new(), |mut listing, token| {
+            let _ = writeln!(listing, "{}\t{}", token.span, token.kind);
+            listing
+        });
+        write_artifact(&output_path, &listing, &error_reporter);
+        return Ok(());
+    }
 
     let parse = JsavParser::new(&tokens);
     let parse_timer = Timer::new("Parser");
@@ -104,10 +145,29 @@ fn main() -> Result<(), CompileError> {
 
     //Print statements
     println!("{num_statements_str}");
+    if args.emit == EmitKind::Ast {
+        let tree: String =
+            statements.iter().map(|stmt| console::strip_ansi_codes(&pretty_print_stmt(stmt)).into_owned()).collect();
+        write_artifact(&output_path, &tree, &error_reporter);
+        return Ok(());
+    }
+
+    let resolve_timer = Timer::new("Import Resolution");
+    let mut resolver = ModuleResolver::new();
+    resolver.resolve(file_path_str, line_tracker, statements);
+    println!("{resolve_timer}");
+    for file in resolver.files() {
+        error_reporter.add_source(file.line_tracker.clone());
+    }
+    let resolve_errors = resolver.take_errors();
+    if !resolve_errors.is_empty() {
+        eprintln!("{}", error_reporter.report_errors(resolve_errors));
+        process::exit(1);
+    }
+    println!("{} source files loaded", resolver.files().len());
 
     let type_check_timer = Timer::new("Type Checking");
-    let mut type_checkr = TypeChecker::new();
-    let type_check_errors = type_checkr.check(&*statements);
+    let type_check_errors = resolver.check_types();
     println!("{type_check_timer}");
     println!("type checking done");
     if !type_check_errors.is_empty() {
@@ -118,7 +178,7 @@ fn main() -> Result<(), CompileError> {
     // Extract type information from the type checker to guide IR generation
     let mut generator = IrGenerator::new();
     let nir_timer = Timer::new("NIR Generation");
-    let (irmodule, ir_errors) = generator.generate(statements.clone(), file_path.to_str().unwrap());
+    let (irmodule, ir_errors) = generator.generate(resolver.linked_statements(), file_path.to_str().unwrap());
     println!("{nir_timer}");
 
     if !ir_errors.is_empty() {
@@ -128,19 +188,54 @@ fn main() -> Result<(), CompileError> {
 
     println!("NIR generation done");
 
-    let pipeline: Vec<Box<dyn Phase>> = vec![
-        Box::new(ConstantFoldingOptimizer::new(args.verbose, true)),
-        Box::new(DeadCodeElimination::with_config(10, true, args.verbose, false)),
-    ];
+    let mut pass_manager = PassManager::for_level(args.opt_level, args.verbose);
+    for name in &args.disable_pass {
+        pass_manager.disable_pass(name);
+    }
+    pass_manager.set_verify(args.verify_ir);
     if args.verbose {
         println!("Generated NIR Module:\n{irmodule}");
     }
     let mut module = irmodule;
 
     let optimization_timer = Timer::new("IR Optimization Pipeline");
-    run_pipeline(&mut module, pipeline);
+    let report = pass_manager.run(&mut module).unwrap_or_else(|errors| {
+        eprintln!("{}", error_reporter.report_errors(errors));
+        process::exit(1);
+    });
     println!("{optimization_timer}");
+    if args.time_passes {
+        println!("{report}");
+    }
     println!("IR optimization done");
+    if args.command == Command::Run {
+        let run_timer = Timer::new("IR Interpretation");
+        let mut interpreter = Interpreter::new(&module);
+        let result = interpreter.run_main();
+        println!("{run_timer}");
+        match result {
+            Ok(value) => {
+                if args.verbose {
+                    for global in &module.globals {
+                        if let Some(value) = interpreter.global(&global.name) {
+                            println!("@{} = {value}", global.name);
+                        }
+                    }
+                }
+                let status = value.exit_status();
+                println!("program exited with status {status}");
+                process::exit(status);
+            }
+            Err(trap) => {
+                eprintln!("{}", error_reporter.report_errors(vec![trap.into()]));
+                process::exit(1);
+            }
+        }
+    }
+    if args.emit == EmitKind::Ir {
+        write_artifact(&output_path, &module.to_string(), &error_reporter);
+        return Ok(());
+    }
     /*if args.verbose {
         println!("optimized NIR Module:\n{}", module);
     }*/
@@ -166,12 +261,17 @@ fn main() -> Result<(), CompileError> {
 
     */
     let asm_gen: AsmGen = AsmGen::new(module);
-    let (_assembly_file, asm_errors) = asm_gen.gen_asm();
+    let (assembly_file, asm_errors) = asm_gen.gen_asm();
     if !asm_errors.is_empty() {
         eprintln!("{}", error_reporter.report_errors(asm_errors));
         process::exit(1);
     }
-    //println!("file:\n{assembly_file}");
+    match args.emit {
+        EmitKind::Obj | EmitKind::Exe => {
+            build_native(args.emit, args.assembler, &assembly_file, &output_path, &error_reporter);
+        }
+        _ => write_artifact(&output_path, &assembly_file.to_string(), &error_reporter),
+    }
 
     Ok(())
 }
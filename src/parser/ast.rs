@@ -18,8 +18,13 @@ pub enum Expr {
 
     Variable { name: Arc<str>, span: SourceSpan },
     Assign { target: Box<Self>, value: Box<Self>, span: SourceSpan },
+    CompoundAssign { target: Box<Self>, op: BinaryOp, value: Box<Self>, span: SourceSpan },
+    Update { target: Box<Self>, op: BinaryOp, prefix: bool, span: SourceSpan },
     Call { callee: Box<Self>, arguments: Vec<Self>, span: SourceSpan },
     ArrayAccess { array: Box<Self>, index: Box<Self>, span: SourceSpan },
+    StructLiteral { name: Arc<str>, fields: Vec<(Arc<str>, Self)>, span: SourceSpan },
+    FieldAccess { object: Box<Self>, field: Arc<str>, span: SourceSpan },
+    Cast { expr: Box<Self>, target_type: Type, span: SourceSpan },
     // Additional expressions as needed
 }
 
@@ -128,6 +133,27 @@ pub enum Stmt {
         body: Vec<Self>,
         span: SourceSpan,
     },
+    Struct {
+        name: Arc<str>,
+        fields: Vec<Parameter>,
+        span: SourceSpan,
+    },
+    Import {
+        path: Arc<str>,
+        span: SourceSpan,
+    },
+    Switch {
+        scrutinee: Expr,
+        cases: Vec<SwitchCase>,
+        default: Option<Vec<Self>>,
+        span: SourceSpan,
+    },
+    Enum {
+        name: Arc<str>,
+        underlying_type: Type,
+        variants: Vec<EnumVariant>,
+        span: SourceSpan,
+    },
 }
 
 impl Expr {
@@ -173,8 +199,13 @@ impl HasSpan for Expr {
             | Self::ArrayLiteral { span, .. }
             | Self::Variable { span, .. }
             | Self::Assign { span, .. }
+            | Self::CompoundAssign { span, .. }
+            | Self::Update { span, .. }
             | Self::Call { span, .. }
-            | Self::ArrayAccess { span, .. } => span,
+            | Self::ArrayAccess { span, .. }
+            | Self::StructLiteral { span, .. }
+            | Self::FieldAccess { span, .. }
+            | Self::Cast { span, .. } => span,
         }
     }
 }
@@ -192,7 +223,11 @@ impl HasSpan for Stmt {
             | Self::Return { span, .. }
             | Self::Break { span, .. }
             | Self::Continue { span, .. }
-            | Self::MainFunction { span, .. } => span,
+            | Self::MainFunction { span, .. }
+            | Self::Struct { span, .. }
+            | Self::Import { span, .. }
+            | Self::Switch { span, .. }
+            | Self::Enum { span, .. } => span,
         }
     }
 }
@@ -234,6 +269,19 @@ impl BinaryOp {
         };
         Ok(op)
     }
+
+    /// Returns the operator applied by a compound assignment (`+=`, `-=`, `%=`,
+    /// `^=`) or an increment/decrement (`++`, `--`) token.
+    #[must_use]
+    pub const fn from_update_token(kind: &TokenKind) -> Option<Self> {
+        match kind {
+            TokenKind::PlusEqual | TokenKind::PlusPlus => Some(Self::Add),
+            TokenKind::MinusEqual | TokenKind::MinusMinus => Some(Self::Subtract),
+            TokenKind::PercentEqual => Some(Self::Modulo),
+            TokenKind::XorEqual => Some(Self::BitwiseXor),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -243,6 +291,23 @@ pub struct Parameter {
     pub span: SourceSpan,
 }
 
+/// A `case value:` arm of a switch statement. Arms do not fall through.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct SwitchCase {
+    pub value: Expr,
+    pub body: Vec<Stmt>,
+    pub span: SourceSpan,
+}
+
+/// A variant of an enum declaration, with its explicit discriminant if any.
+/// Variants without one take the previous discriminant plus one, or zero.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct EnumVariant {
+    pub name: Arc<str>,
+    pub value: Option<Expr>,
+    pub span: SourceSpan,
+}
+
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub enum Type {
     I8,
@@ -2,7 +2,7 @@
 use crate::error::compile_error::CompileError;
 use crate::error::error_code::ErrorCode;
 use crate::location::source_span::{HasSpan, SourceSpan};
-use crate::parser::ast::{BinaryOp, Expr, Parameter, Stmt, Type, UnaryOp};
+use crate::parser::ast::{BinaryOp, EnumVariant, Expr, Parameter, Stmt, SwitchCase, Type, UnaryOp};
 use crate::parser::precedence::{binding_power, unary_binding_power};
 use crate::tokens::token::Token;
 use crate::tokens::token_kind::TokenKind;
@@ -78,6 +78,10 @@ impl<'a> JsavParser<'a> {
             TokenKind::KeywordFor => self.parse_for(),
             TokenKind::KeywordBreak => self.parse_break(),
             TokenKind::KeywordContinue => self.parse_continue(),
+            TokenKind::KeywordStruct => self.parse_struct(),
+            TokenKind::KeywordImport => self.parse_import(),
+            TokenKind::KeywordSwitch => self.parse_switch(),
+            TokenKind::KeywordEnum => self.parse_enum(),
             TokenKind::OpenBrace => self.parse_block_stmt(),
             _ => self.parse_expression_stmt(),
         }
@@ -192,6 +196,76 @@ impl<'a> JsavParser<'a> {
         })
     }
 
+    /// Parses `import "path.vn"`.
+    fn parse_import(&mut self) -> Option<Stmt> {
+        let start_token = self.advance()?.clone(); // 'import'
+        let token = self.peek()?.clone();
+        let TokenKind::StringLiteral(path) = &token.kind else {
+            self.syntax_error(
+                "Expected file path after 'import'",
+                &token,
+                Some("Write the path of the imported file as a string, e.g. import \"math.vn\""),
+                Some(ErrorCode::E1004),
+            );
+            return None;
+        };
+        self.advance();
+        Some(Stmt::Import { path: path.clone(), span: self.merged_span(&start_token) })
+    }
+
+    /// Parses a struct declaration: `struct Name { field: type, ... }`.
+    ///
+    /// Fields may be separated by commas; a trailing comma is allowed.
+    fn parse_struct(&mut self) -> Option<Stmt> {
+        let start_token = self.advance()?.clone(); // 'struct'
+        let name = self.consume_identifier()?;
+
+        self.expect(&TokenKind::OpenBrace, "after struct name");
+        let mut fields = Vec::new();
+        while !self.check(&TokenKind::CloseBrace) && !self.is_at_end() {
+            let field_start = self.peek()?.clone();
+            let field_name = self.consume_identifier()?;
+            self.expect(&TokenKind::Colon, "after field name");
+            let type_ann = self.parse_type()?;
+            fields.push(Parameter {
+                name: field_name,
+                type_annotation: type_ann,
+                span: self.merged_span(&field_start),
+            });
+            self.match_token(&TokenKind::Comma);
+        }
+        fields.shrink_to_fit();
+        self.expect(&TokenKind::CloseBrace, "end of struct declaration");
+
+        Some(Stmt::Struct { name, fields, span: self.merged_span(&start_token) })
+    }
+
+    /// Parses an enum declaration: `enum Name: type { Variant, Variant = value, ... }`.
+    ///
+    /// The underlying type defaults to `i32`. Variants are separated by
+    /// commas; a trailing comma is allowed.
+    fn parse_enum(&mut self) -> Option<Stmt> {
+        let start_token = self.advance()?.clone(); // 'enum'
+        let name = self.consume_identifier()?;
+        let underlying_type = if self.match_token(&TokenKind::Colon) { self.parse_type()? } else { Type::I32 };
+
+        self.expect(&TokenKind::OpenBrace, "after enum name");
+        let mut variants = Vec::new();
+        while !self.check(&TokenKind::CloseBrace) && !self.is_at_end() {
+            let variant_start = self.peek()?.clone();
+            let variant_name = self.consume_identifier()?;
+            let value = if self.match_token(&TokenKind::Equal) { Some(self.parse_expr(0)?) } else { None };
+            variants.push(EnumVariant { name: variant_name, value, span: self.merged_span(&variant_start) });
+            if !self.match_token(&TokenKind::Comma) {
+                break;
+            }
+        }
+        variants.shrink_to_fit();
+        self.expect(&TokenKind::CloseBrace, "end of enum declaration");
+
+        Some(Stmt::Enum { name, underlying_type, variants, span: self.merged_span(&start_token) })
+    }
+
     /// Parses a condition for constructs like if, while, for
     fn parse_condition(&mut self, keword: &str) -> Option<Expr> {
         self.expect(&TokenKind::OpenParen, format!("after '{keword}'").as_str());
@@ -219,6 +293,76 @@ impl<'a> JsavParser<'a> {
         Some(Stmt::While { condition, body: vec![body], span: function_span })
     }
 
+    /// Parses `switch (expr) { case value: ... default: ... }`.
+    ///
+    /// The statements of an arm run until the next `case`, `default` or the
+    /// closing brace; arms do not fall through.
+    fn parse_switch(&mut self) -> Option<Stmt> {
+        let start_token = self.advance()?.clone(); // 'switch'
+        let scrutinee = self.parse_condition("switch")?;
+        self.expect(&TokenKind::OpenBrace, "after switch value");
+
+        let mut cases = Vec::new();
+        let mut default = None;
+        while !self.check(&TokenKind::CloseBrace) && !self.is_at_end() {
+            let arm_token = self.peek()?.clone();
+            match arm_token.kind {
+                TokenKind::KeywordCase => {
+                    self.advance();
+                    let value = self.parse_expr(0)?;
+                    self.expect(&TokenKind::Colon, "after case value");
+                    let body = self.parse_switch_arm();
+                    cases.push(SwitchCase { value, body, span: self.merged_span(&arm_token) });
+                }
+                TokenKind::KeywordDefault => {
+                    self.advance();
+                    self.expect(&TokenKind::Colon, "after 'default'");
+                    let body = self.parse_switch_arm();
+                    if default.is_some() {
+                        self.syntax_error(
+                            "Duplicate default arm in switch",
+                            &arm_token,
+                            Some("A switch statement can have at most one 'default' arm"),
+                            Some(ErrorCode::E1004),
+                        );
+                    } else {
+                        default = Some(body);
+                    }
+                }
+                _ => {
+                    self.syntax_error(
+                        "Expected 'case' or 'default' in switch",
+                        &arm_token,
+                        Some("Start each arm of a switch with 'case value:' or 'default:'"),
+                        Some(ErrorCode::E1004),
+                    );
+                    self.advance();
+                }
+            }
+        }
+        cases.shrink_to_fit();
+        self.expect(&TokenKind::CloseBrace, "end of switch");
+
+        Some(Stmt::Switch { scrutinee, cases, default, span: self.merged_span(&start_token) })
+    }
+
+    /// Parses the statements of a switch arm.
+    fn parse_switch_arm(&mut self) -> Vec<Stmt> {
+        let mut statements = Vec::new();
+        while !self.is_at_end()
+            && !self.check(&TokenKind::CloseBrace)
+            && !self.check(&TokenKind::KeywordCase)
+            && !self.check(&TokenKind::KeywordDefault)
+        {
+            if let Some(stmt) = self.parse_stmt() {
+                statements.push(stmt);
+            } else {
+                self.advance();
+            }
+        }
+        statements
+    }
+
     fn parse_for_initializer(&mut self) -> Option<Box<Stmt>> {
         if self.match_token(&TokenKind::Semicolon) {
             // Empty initializer
@@ -460,7 +604,7 @@ impl<'a> JsavParser<'a> {
 
         while let Some(token) = self.peek() {
             let (lbp, _) = binding_power(token);
-            if lbp <= min_bp {
+            if lbp <= min_bp || self.at_line_start_update() {
                 break;
             }
             left = self.led(left)?;
@@ -469,6 +613,18 @@ impl<'a> JsavParser<'a> {
         Some(left)
     }
 
+    /// Checks whether the next token is a `++` or `--` on a later line than
+    /// the previous token: it starts a prefix update in a new statement
+    /// rather than a postfix update of the expression before it.
+    fn at_line_start_update(&self) -> bool {
+        match (self.previous(), self.peek()) {
+            (Some(prev), Some(next)) if matches!(next.kind, TokenKind::PlusPlus | TokenKind::MinusMinus) => {
+                next.span.start.line > prev.span.end.line
+            }
+            _ => false,
+        }
+    }
+
     fn nud(&mut self) -> Option<Expr> {
         let token = self.advance()?.clone();
         match token.kind {
@@ -481,11 +637,15 @@ impl<'a> JsavParser<'a> {
             // Unary operators
             TokenKind::Minus => Some(self.parse_unary(UnaryOp::Negate, token)),
             TokenKind::Not => Some(self.parse_unary(UnaryOp::Not, token)),
+            TokenKind::PlusPlus | TokenKind::MinusMinus => self.parse_prefix_update(&token),
             // Grouping
             TokenKind::OpenBrace => self.parse_array_literal(&token),
             TokenKind::OpenParen => self.parse_grouping(&token),
             // Variables
             TokenKind::IdentifierAscii(name) | TokenKind::IdentifierUnicode(name) => {
+                if self.at_struct_literal() {
+                    return self.parse_struct_literal(name, &token.span);
+                }
                 Some(Expr::Variable { name, span: token.span })
             }
             _ => {
@@ -528,11 +688,21 @@ impl<'a> JsavParser<'a> {
             | TokenKind::ShiftRight => self.parse_binary(left, token),
 
             // Assignment
-            TokenKind::Equal => self.parse_assignment(left, token),
+            TokenKind::Equal
+            | TokenKind::PlusEqual
+            | TokenKind::MinusEqual
+            | TokenKind::PercentEqual
+            | TokenKind::XorEqual => self.parse_assignment(left, token),
+            // Postfix increment/decrement
+            TokenKind::PlusPlus | TokenKind::MinusMinus => self.parse_postfix_update(left, &token),
             // Function call
             TokenKind::OpenParen => self.parse_call(left, &token),
             // Array access
             TokenKind::OpenBracket => self.parse_array_access(left, &token),
+            // Field access
+            TokenKind::Dot => self.parse_field_access(left),
+            // Cast
+            TokenKind::KeywordAs => self.parse_cast(left),
             _ => {
                 self.syntax_error(
                     "Unexpected operator",
@@ -563,6 +733,41 @@ impl<'a> JsavParser<'a> {
         Some(Expr::ArrayLiteral { elements, span: self.merged_span(start_token) })
     }
 
+    /// Checks whether the identifier just consumed starts a struct literal,
+    /// that is whether it is followed by `{` and then `}` or `field:`.
+    fn at_struct_literal(&self) -> bool {
+        if !self.check(&TokenKind::OpenBrace) {
+            return false;
+        }
+        match self.peek_nth(1).map(|t| &t.kind) {
+            Some(TokenKind::CloseBrace) => true,
+            Some(TokenKind::IdentifierAscii(_) | TokenKind::IdentifierUnicode(_)) => {
+                self.peek_nth(2).is_some_and(|t| t.kind == TokenKind::Colon)
+            }
+            _ => false,
+        }
+    }
+
+    fn parse_struct_literal(&mut self, name: Arc<str>, start: &SourceSpan) -> Option<Expr> {
+        self.advance(); // '{'
+        let mut fields = Vec::new();
+        while !self.check(&TokenKind::CloseBrace) && !self.is_at_end() {
+            let field = self.consume_identifier()?;
+            self.expect(&TokenKind::Colon, "after field name");
+            let value = self.parse_expr(0)?;
+            fields.push((field, value));
+            if !self.match_token(&TokenKind::Comma) {
+                break;
+            }
+        }
+        if !self.expect(&TokenKind::CloseBrace, "end of struct literal") {
+            return None;
+        }
+        fields.shrink_to_fit();
+        let span = self.previous().and_then(|end| start.merged(&end.span)).unwrap_or_else(|| start.clone());
+        Some(Expr::StructLiteral { name, fields, span })
+    }
+
     fn extract_elements(&mut self, kind: &TokenKind, elements: &mut Vec<Expr>) {
         while !self.check(&kind.clone()) && !self.is_at_end() {
             if let Some(expr) = self.parse_expr(0) {
@@ -600,22 +805,52 @@ impl<'a> JsavParser<'a> {
 
         let span = left.span().merged(value.span()).unwrap_or(token.span);
 
-        // Check if left is valid l-value (variable or array access)
-        let valid = matches!(&left, Expr::Variable { .. } | Expr::ArrayAccess { .. });
+        if !self.check_lvalue(&left, "Invalid left-hand side in assignment") {
+            return None;
+        }
 
-        if !valid {
-            let help_msg = "Only variables and array elements can be assigned to. Consider using a variable name or an array access expression.";
+        let (target, value) = (Box::new(left), Box::new(value));
+        Some(match BinaryOp::from_update_token(&token.kind) {
+            Some(op) => Expr::CompoundAssign { target, op, value, span },
+            None => Expr::Assign { target, value, span },
+        })
+    }
 
-            self.errors.push(CompileError::SyntaxError {
-                code: Some(ErrorCode::E1003),
-                message: "Invalid left-hand side in assignment".into(),
-                span: left.span().clone(),
-                help: Some(help_msg.to_string()),
-            });
+    fn parse_prefix_update(&mut self, token: &Token) -> Option<Expr> {
+        let (_, rbp) = unary_binding_power(token);
+        let target = self.parse_expr(rbp)?;
+        let span = token.span.merged(target.span()).unwrap_or_else(|| token.span.clone());
+        if !self.check_lvalue(&target, &format!("Invalid operand for prefix {}", token.kind)) {
+            return None;
+        }
+        let op = BinaryOp::from_update_token(&token.kind)?;
+        Some(Expr::Update { target: Box::new(target), op, prefix: true, span })
+    }
+
+    fn parse_postfix_update(&mut self, target: Expr, token: &Token) -> Option<Expr> {
+        let span = target.span().merged(&token.span).unwrap_or_else(|| token.span.clone());
+        if !self.check_lvalue(&target, &format!("Invalid operand for postfix {}", token.kind)) {
             return None;
         }
+        let op = BinaryOp::from_update_token(&token.kind)?;
+        Some(Expr::Update { target: Box::new(target), op, prefix: false, span })
+    }
+
+    /// Reports an E1003 error unless `target` is a variable, an array element
+    /// or a struct field.
+    fn check_lvalue(&mut self, target: &Expr, message: &str) -> bool {
+        if matches!(target, Expr::Variable { .. } | Expr::ArrayAccess { .. } | Expr::FieldAccess { .. }) {
+            return true;
+        }
+        let help_msg = "Only variables, array elements and struct fields can be assigned to. Consider using a variable name, an array access or a field access expression.";
 
-        Some(Expr::Assign { target: Box::new(left), value: Box::new(value), span })
+        self.errors.push(CompileError::SyntaxError {
+            code: Some(ErrorCode::E1003),
+            message: message.into(),
+            span: target.span().clone(),
+            help: Some(help_msg.to_string()),
+        });
+        false
     }
 
     fn parse_call(&mut self, callee: Expr, start_token: &Token) -> Option<Expr> {
@@ -640,6 +875,19 @@ impl<'a> JsavParser<'a> {
         Some(Expr::ArrayAccess { array: Box::new(array), index: Box::new(index), span: self.merged_span(start_token) })
     }
 
+    fn parse_field_access(&mut self, object: Expr) -> Option<Expr> {
+        let field = self.consume_identifier()?;
+        let span =
+            self.previous().and_then(|end| object.span().merged(&end.span)).unwrap_or_else(|| object.span().clone());
+        Some(Expr::FieldAccess { object: Box::new(object), field, span })
+    }
+
+    fn parse_cast(&mut self, expr: Expr) -> Option<Expr> {
+        let target_type = self.parse_type()?;
+        let span = self.previous().and_then(|end| expr.span().merged(&end.span)).unwrap_or_else(|| expr.span().clone());
+        Some(Expr::Cast { expr: Box::new(expr), target_type, span })
+    }
+
     #[inline]
     fn merged_span(&self, start_token: &Token) -> SourceSpan {
         self.previous().and_then(|end| start_token.span.merged(&end.span)).unwrap_or_else(|| start_token.span.clone()) // Only clone when necessary
@@ -708,6 +956,11 @@ impl<'a> JsavParser<'a> {
         self.tokens.get(self.current)
     }
 
+    #[inline]
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.current + n)
+    }
+
     /// Checks if the current token matches the given kind.
     #[inline]
     fn check(&self, kind: &TokenKind) -> bool {
@@ -41,8 +41,15 @@ pub const fn binding_power(token: &Token) -> (u8, u8) {
         // Multiply/Divide/Mod (left-associative)
         TokenKind::Star | TokenKind::Slash | TokenKind::Percent => (22, 21),
 
-        // Function call, array access, member access
-        TokenKind::OpenParen | TokenKind::OpenBracket | TokenKind::Dot => (27, 26),
+        // Cast (binds tighter than binary operators, looser than unary ones)
+        TokenKind::KeywordAs => (23, 22),
+
+        // Function call, array access, member access, postfix increment/decrement
+        TokenKind::OpenParen
+        | TokenKind::OpenBracket
+        | TokenKind::Dot
+        | TokenKind::PlusPlus
+        | TokenKind::MinusMinus => (27, 26),
 
         _ => (0, 0),
     }
@@ -51,7 +58,7 @@ pub const fn binding_power(token: &Token) -> (u8, u8) {
 #[must_use]
 pub const fn unary_binding_power(token: &Token) -> (u8, u8) {
     match token.kind {
-        TokenKind::Not | TokenKind::Minus => (24, 23),
+        TokenKind::Not | TokenKind::Minus | TokenKind::PlusPlus | TokenKind::MinusMinus => (24, 23),
         _ => (0, 0),
     }
 }
@@ -1,4 +1,4 @@
-use crate::parser::ast::{Expr, Stmt};
+use crate::parser::ast::{BinaryOp, Expr, Stmt};
 use crate::printers::branch_type::{BranchConfig, BranchType, StyleManager, append_line, get_indent, print_children};
 
 const EXPR_CAPACITY_PER_NODE: usize = 45;
@@ -32,10 +32,14 @@ fn count_expr_nodes(expr: &Expr) -> usize {
                 stack.push(left);
                 stack.push(right);
             }
-            Expr::Unary { expr, .. } | Expr::Grouping { expr, .. } => {
+            Expr::Unary { expr, .. }
+            | Expr::Grouping { expr, .. }
+            | Expr::Update { target: expr, .. }
+            | Expr::FieldAccess { object: expr, .. }
+            | Expr::Cast { expr, .. } => {
                 stack.push(expr);
             }
-            Expr::Assign { target, value, .. } => {
+            Expr::Assign { target, value, .. } | Expr::CompoundAssign { target, value, .. } => {
                 stack.push(target);
                 stack.push(value);
             }
@@ -54,6 +58,11 @@ fn count_expr_nodes(expr: &Expr) -> usize {
                     stack.push(elem);
                 }
             }
+            Expr::StructLiteral { fields, .. } => {
+                for (_, value) in fields {
+                    stack.push(value);
+                }
+            }
             Expr::Literal { .. } | Expr::Variable { .. } => {}
         }
     }
@@ -72,6 +81,7 @@ fn print_branch(
 }
 
 /// Prints an expression with the given indentation and branch type.
+#[allow(clippy::too_many_lines)]
 fn print_expr(expr: &Expr, indent: &str, branch_type: BranchType, output: &mut String, styles: &StyleManager) {
     match expr {
         Expr::Binary { left, op, right, .. } => {
@@ -122,13 +132,24 @@ fn print_expr(expr: &Expr, indent: &str, branch_type: BranchType, output: &mut S
             append_line(output, indent, branch_type, &styles.variable.clone(), &format!("Variable '{name}'"));
         }
         Expr::Assign { target, value, .. } => {
-            append_line(output, indent, branch_type, &styles.clone().variable, "Assignment");
-            let new_indent = get_indent(indent, &branch_type);
-            // Target
-            append_line(output, &new_indent, BranchType::Middle, &styles.structure.clone(), "Target:");
-            print_expr(target, &get_indent(&new_indent, &BranchType::Middle), BranchType::Last, output, styles);
-            append_line(output, &new_indent, BranchType::Last, &styles.structure.clone(), "Value:");
-            print_expr(value, &get_indent(&new_indent, &BranchType::Last), BranchType::Last, output, styles);
+            print_assignment("Assignment", target, value, indent, branch_type, output, styles);
+        }
+        Expr::CompoundAssign { target, op, value, .. } => {
+            let label = format!("Compound Assignment {op:?}");
+            print_assignment(&label, target, value, indent, branch_type, output, styles);
+        }
+        Expr::Update { target, op, prefix, .. } => {
+            let fixity = if *prefix { "Prefix" } else { "Postfix" };
+            let operator = if *op == BinaryOp::Add { "Increment" } else { "Decrement" };
+            append_line(output, indent, branch_type, &styles.operator.clone(), &format!("{fixity} {operator}"));
+            print_branch(
+                "Target:",
+                target,
+                indent,
+                &BranchConfig::new(branch_type, BranchType::Last, BranchType::Last),
+                output,
+                styles,
+            );
         }
         Expr::Call { callee, arguments, .. } => {
             append_line(output, indent, branch_type, &styles.punctuation.clone(), "Function Call");
@@ -174,9 +195,60 @@ fn print_expr(expr: &Expr, indent: &str, branch_type: BranchType, output: &mut S
             append_line(output, &new_indent, BranchType::Last, &styles.structure.clone(), "Elements:");
             print_children(elements, &get_indent(&new_indent, &BranchType::Last), output, styles, print_expr);
         }
+        Expr::StructLiteral { name, fields, .. } => {
+            append_line(output, indent, branch_type, &styles.punctuation.clone(), &format!("Struct Literal '{name}'"));
+            let new_indent = get_indent(indent, &branch_type);
+            for (i, (field, value)) in fields.iter().enumerate() {
+                let field_branch_type = if i == fields.len() - 1 { BranchType::Last } else { BranchType::Middle };
+                append_line(
+                    output,
+                    &new_indent,
+                    field_branch_type,
+                    &styles.structure.clone(),
+                    &format!("Field '{field}':"),
+                );
+                print_expr(value, &get_indent(&new_indent, &field_branch_type), BranchType::Last, output, styles);
+            }
+        }
+        Expr::FieldAccess { object, field, .. } => {
+            append_line(output, indent, branch_type, &styles.punctuation.clone(), &format!("Field Access '{field}'"));
+            print_branch(
+                "Object:",
+                object,
+                indent,
+                &BranchConfig::new(branch_type, BranchType::Last, BranchType::Last),
+                output,
+                styles,
+            );
+        }
+        Expr::Cast { expr, target_type, .. } => {
+            append_line(output, indent, branch_type, &styles.operator.clone(), &format!("Cast to {target_type}"));
+            print_branch(
+                "Expr:",
+                expr,
+                indent,
+                &BranchConfig::new(branch_type, BranchType::Last, BranchType::Last),
+                output,
+                styles,
+            );
+        }
     }
 }
 
+/// Prints an assignment node with its target and value.
+fn print_assignment(
+    label: &str, target: &Expr, value: &Expr, indent: &str, branch_type: BranchType, output: &mut String,
+    styles: &StyleManager,
+) {
+    append_line(output, indent, branch_type, &styles.clone().variable, label);
+    let new_indent = get_indent(indent, &branch_type);
+    // Target
+    append_line(output, &new_indent, BranchType::Middle, &styles.structure.clone(), "Target:");
+    print_expr(target, &get_indent(&new_indent, &BranchType::Middle), BranchType::Last, output, styles);
+    append_line(output, &new_indent, BranchType::Last, &styles.structure.clone(), "Value:");
+    print_expr(value, &get_indent(&new_indent, &BranchType::Last), BranchType::Last, output, styles);
+}
+
 /// Pretty-print a single statement AST into a styled, tree-like string.
 /// Mirrors `pretty_print` for expressions.
 #[must_use]
@@ -227,7 +299,21 @@ fn count_stmt_nodes(stmt: &Stmt) -> usize {
                 + increment.as_ref().map_or(0, count_expr_nodes)
                 + body.iter().map(count_stmt_nodes).sum::<usize>()
         }
-        Stmt::Break { .. } | Stmt::Continue { .. } => 0,
+        Stmt::Switch { scrutinee, cases, default, .. } => {
+            count_expr_nodes(scrutinee)
+                + cases
+                    .iter()
+                    .map(|case| {
+                        1 + count_expr_nodes(&case.value) + case.body.iter().map(count_stmt_nodes).sum::<usize>()
+                    })
+                    .sum::<usize>()
+                + default.as_ref().map_or(0, |body| body.iter().map(count_stmt_nodes).sum::<usize>())
+        }
+        Stmt::Struct { fields, .. } => fields.len(),
+        Stmt::Enum { variants, .. } => {
+            variants.iter().map(|variant| 1 + variant.value.as_ref().map_or(0, count_expr_nodes)).sum::<usize>()
+        }
+        Stmt::Break { .. } | Stmt::Continue { .. } | Stmt::Import { .. } => 0,
     }
 }
 
@@ -365,6 +451,34 @@ fn print_stmt(stmt: &Stmt, indent: &str, branch_type: BranchType, output: &mut S
             append_line(output, &new_indent, BranchType::Last, &styles.structure.clone(), "Body:");
             print_children(body, &get_indent(&new_indent, &BranchType::Last), output, styles, print_stmt);
         }
+        Stmt::Switch { scrutinee, cases, default, span: _ } => {
+            append_line(output, indent, branch_type, &styles.clone().keyword, "Switch");
+            let new_indent = get_indent(indent, &branch_type);
+            let value_branch_type =
+                if cases.is_empty() && default.is_none() { BranchType::Last } else { BranchType::Middle };
+            append_line(output, &new_indent, value_branch_type, &styles.structure.clone(), "Value:");
+            print_expr(scrutinee, &get_indent(&new_indent, &value_branch_type), BranchType::Last, output, styles);
+            for (i, case) in cases.iter().enumerate() {
+                let case_branch_type =
+                    if i == cases.len() - 1 && default.is_none() { BranchType::Last } else { BranchType::Middle };
+                append_line(output, &new_indent, case_branch_type, &styles.keyword.clone(), "Case");
+                let case_indent = get_indent(&new_indent, &case_branch_type);
+                append_line(output, &case_indent, BranchType::Middle, &styles.structure.clone(), "Value:");
+                print_expr(
+                    &case.value,
+                    &get_indent(&case_indent, &BranchType::Middle),
+                    BranchType::Last,
+                    output,
+                    styles,
+                );
+                append_line(output, &case_indent, BranchType::Last, &styles.structure.clone(), "Body:");
+                print_children(&case.body, &get_indent(&case_indent, &BranchType::Last), output, styles, print_stmt);
+            }
+            if let Some(default) = default {
+                append_line(output, &new_indent, BranchType::Last, &styles.keyword.clone(), "Default");
+                print_children(default, &get_indent(&new_indent, &BranchType::Last), output, styles, print_stmt);
+            }
+        }
         Stmt::For { initializer, condition, increment, body, span: _ } => {
             append_line(output, indent, branch_type, &styles.keyword.clone(), "For");
             let new_indent = get_indent(indent, &branch_type);
@@ -392,5 +506,46 @@ fn print_stmt(stmt: &Stmt, indent: &str, branch_type: BranchType, output: &mut S
         }
         Stmt::Break { .. } => append_line(output, indent, branch_type, &styles.keyword.clone(), "Break"),
         Stmt::Continue { .. } => append_line(output, indent, branch_type, &styles.keyword.clone(), "Continue"),
+        Stmt::Import { path, .. } => {
+            append_line(output, indent, branch_type, &styles.keyword.clone(), &format!("Import \"{path}\""));
+        }
+        Stmt::Struct { name, fields, .. } => {
+            append_line(output, indent, branch_type, &styles.keyword.clone(), &format!("Struct '{name}'"));
+            let new_indent = get_indent(indent, &branch_type);
+            for (i, field) in fields.iter().enumerate() {
+                let field_branch_type = if i == fields.len() - 1 { BranchType::Last } else { BranchType::Middle };
+                append_line(
+                    output,
+                    &new_indent,
+                    field_branch_type,
+                    &styles.type_style.clone(),
+                    &format!("Field '{}': {}", field.name, field.type_annotation),
+                );
+            }
+        }
+        Stmt::Enum { name, underlying_type, variants, .. } => {
+            append_line(
+                output,
+                indent,
+                branch_type,
+                &styles.keyword.clone(),
+                &format!("Enum '{name}': {underlying_type}"),
+            );
+            let new_indent = get_indent(indent, &branch_type);
+            for (i, variant) in variants.iter().enumerate() {
+                let variant_branch_type = if i == variants.len() - 1 { BranchType::Last } else { BranchType::Middle };
+                append_line(
+                    output,
+                    &new_indent,
+                    variant_branch_type,
+                    &styles.type_style.clone(),
+                    &format!("Variant '{}'", variant.name),
+                );
+                if let Some(value) = &variant.value {
+                    let value_indent = get_indent(&new_indent, &variant_branch_type);
+                    print_expr(value, &value_indent, BranchType::Last, output, styles);
+                }
+            }
+        }
     }
 }
@@ -10,3 +10,4 @@
 //! * Termination: Finalizes output with complete program representation
 pub mod ast_printer;
 pub mod branch_type;
+pub mod source_printer;
@@ -0,0 +1,418 @@
+//! Prints an AST back to `.vn` source.
+//!
+//! The output parses back to the same tree, up to spans and to the grouping
+//! nodes the parser creates for parentheses: binary expressions, unary
+//! expressions and casts are always parenthesized, so operator precedence
+//! never has to be reconstructed. Printing the reparsed tree therefore yields
+//! the same text again.
+
+use crate::parser::ast::{BinaryOp, Expr, LiteralValue, Parameter, Stmt, Type, UnaryOp};
+use crate::tokens::number::Number;
+use std::fmt::Write;
+
+const INDENT: &str = "    ";
+
+/// Prints a sequence of statements as `.vn` source, one statement per line.
+#[must_use]
+pub fn print_source(statements: &[Stmt]) -> String {
+    let mut printer = SourcePrinter::default();
+    printer.statements(statements);
+    printer.out
+}
+
+/// Prints a single expression as `.vn` source. A binary expression at the
+/// top is printed without its parentheses.
+#[must_use]
+pub fn print_expr_source(expr: &Expr) -> String {
+    let mut out = String::new();
+    match expr {
+        Expr::Binary { left, op, right, .. } => write_binary(&mut out, left, op, right),
+        _ => write_expr(&mut out, expr),
+    }
+    out
+}
+
+/// Prints a type the way it is written in declarations, e.g. `i64[4]`.
+#[must_use]
+pub fn print_type_source(ty: &Type) -> String {
+    let mut out = String::new();
+    write_type(&mut out, ty);
+    out
+}
+
+#[derive(Default)]
+struct SourcePrinter {
+    out: String,
+    depth: usize,
+}
+
+impl SourcePrinter {
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.depth {
+            self.out.push_str(INDENT);
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn statements(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            self.stmt(stmt);
+        }
+    }
+
+    /// Prints `header {`, the body one level deeper and the closing brace.
+    fn braced(&mut self, header: &str, body: &[Stmt]) {
+        self.line(format!("{header} {{").trim_start());
+        self.indented(unwrap_block(body));
+        self.line("}");
+    }
+
+    fn indented(&mut self, statements: &[Stmt]) {
+        self.depth += 1;
+        self.statements(statements);
+        self.depth -= 1;
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Expression { expr } => self.line(&print_expr_source(expr)),
+            Stmt::VarDeclaration { .. } => self.line(&inline_stmt(stmt)),
+            Stmt::Function { name, parameters, return_type, body, .. } => {
+                let mut header = format!("fun {name}({})", print_parameters(parameters));
+                if *return_type != Type::Void {
+                    let _ = write!(header, ": {}", print_type_source(return_type));
+                }
+                self.braced(&header, body);
+            }
+            Stmt::If { condition, then_branch, else_branch, .. } => {
+                self.if_stmt("if", condition, then_branch, else_branch.as_deref());
+            }
+            Stmt::While { condition, body, .. } => {
+                self.braced(&format!("while ({})", print_expr_source(condition)), body);
+            }
+            Stmt::For { initializer, condition, increment, body, .. } => {
+                let initializer = initializer.as_deref().map(inline_stmt).unwrap_or_default();
+                let condition = condition.as_ref().map(print_expr_source).unwrap_or_default();
+                let increment = increment.as_ref().map(print_expr_source).unwrap_or_default();
+                self.braced(&format!("for ({initializer}; {condition}; {increment})"), body);
+            }
+            Stmt::Block { statements, .. } => self.braced("", statements),
+            Stmt::Return { value: Some(value), .. } => self.line(&format!("return {}", print_expr_source(value))),
+            Stmt::Return { value: None, .. } => self.line("return"),
+            Stmt::Break { .. } => self.line("break"),
+            Stmt::Continue { .. } => self.line("continue"),
+            Stmt::MainFunction { body, .. } => self.braced("main", body),
+            Stmt::Struct { name, fields, .. } => {
+                self.line(&format!("struct {name} {{ {} }}", print_parameters(fields)));
+            }
+            Stmt::Import { path, .. } => self.line(&format!("import \"{}\"", escape(path, '"'))),
+            Stmt::Switch { scrutinee, cases, default, .. } => {
+                self.line(&format!("switch ({}) {{", print_expr_source(scrutinee)));
+                self.depth += 1;
+                for case in cases {
+                    self.line(&format!("case {}:", print_expr_source(&case.value)));
+                    self.indented(&case.body);
+                }
+                if let Some(default) = default {
+                    self.line("default:");
+                    self.indented(default);
+                }
+                self.depth -= 1;
+                self.line("}");
+            }
+            Stmt::Enum { name, underlying_type, variants, .. } => {
+                let variants: Vec<_> = variants
+                    .iter()
+                    .map(|variant| {
+                        variant.value.as_ref().map_or_else(
+                            || variant.name.to_string(),
+                            |value| format!("{} = {}", variant.name, print_expr_source(value)),
+                        )
+                    })
+                    .collect();
+                self.line(&format!(
+                    "enum {name}: {} {{ {} }}",
+                    print_type_source(underlying_type),
+                    variants.join(", ")
+                ));
+            }
+        }
+    }
+
+    /// Prints an `if`, continuing `else if` chains on the closing brace.
+    fn if_stmt(&mut self, keyword: &str, condition: &Expr, then_branch: &[Stmt], else_branch: Option<&[Stmt]>) {
+        self.line(&format!("{keyword} ({}) {{", print_expr_source(condition)));
+        self.indented(unwrap_block(then_branch));
+        match else_branch {
+            None => self.line("}"),
+            Some([Stmt::If { condition, then_branch, else_branch, .. }]) => {
+                self.if_stmt("} else if", condition, then_branch, else_branch.as_deref());
+            }
+            Some(else_branch) => {
+                self.line("} else {");
+                self.indented(unwrap_block(else_branch));
+                self.line("}");
+            }
+        }
+    }
+}
+
+/// Bodies produced by the parser are a single block; print its statements.
+fn unwrap_block(body: &[Stmt]) -> &[Stmt] {
+    match body {
+        [Stmt::Block { statements, .. }] => statements,
+        _ => body,
+    }
+}
+
+/// Prints a statement that appears inside a line, such as a `for` initializer.
+fn inline_stmt(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::VarDeclaration { variables, type_annotation, is_mutable, initializers, .. } => {
+            let initializers: Vec<_> = initializers.iter().map(print_expr_source).collect();
+            format!(
+                "{} {}: {} = {}",
+                if *is_mutable { "var" } else { "const" },
+                variables.join(", "),
+                print_type_source(type_annotation),
+                initializers.join(", ")
+            )
+        }
+        Stmt::Expression { expr } => print_expr_source(expr),
+        other => print_source(std::slice::from_ref(other)).trim_end().to_string(),
+    }
+}
+
+fn print_parameters(parameters: &[Parameter]) -> String {
+    let parameters: Vec<_> = parameters
+        .iter()
+        .map(|parameter| format!("{}: {}", parameter.name, print_type_source(&parameter.type_annotation)))
+        .collect();
+    parameters.join(", ")
+}
+
+fn write_type(out: &mut String, ty: &Type) {
+    match ty {
+        Type::Array(..) => {
+            // `i64[2][3]` is an array of two arrays of three elements.
+            let mut dimensions = Vec::new();
+            let mut element = ty;
+            while let Type::Array(inner, size) = element {
+                dimensions.push(size);
+                element = inner;
+            }
+            write_type(out, element);
+            for size in dimensions {
+                out.push('[');
+                write_expr(out, size);
+                out.push(']');
+            }
+        }
+        Type::Vector(element) => {
+            out.push_str("vector<");
+            write_type(out, element);
+            out.push('>');
+        }
+        other => {
+            let _ = write!(out, "{other}");
+        }
+    }
+}
+
+fn write_expr(out: &mut String, expr: &Expr) {
+    match expr {
+        Expr::Binary { left, op, right, .. } => {
+            out.push('(');
+            write_binary(out, left, op, right);
+            out.push(')');
+        }
+        Expr::Unary { op, expr, .. } => {
+            out.push_str(match op {
+                UnaryOp::Negate => "(-",
+                UnaryOp::Not => "(!",
+            });
+            write_operand(out, expr);
+            out.push(')');
+        }
+        // Binary, unary and cast expressions carry their own parentheses.
+        Expr::Grouping { expr, .. }
+            if matches!(**expr, Expr::Binary { .. } | Expr::Unary { .. } | Expr::Cast { .. }) =>
+        {
+            write_expr(out, expr);
+        }
+        Expr::Grouping { expr, .. } => {
+            out.push('(');
+            write_expr(out, expr);
+            out.push(')');
+        }
+        Expr::Literal { value, .. } => write_literal(out, value),
+        Expr::ArrayLiteral { elements, .. } => {
+            out.push('{');
+            write_list(out, elements);
+            out.push('}');
+        }
+        Expr::Variable { name, .. } => out.push_str(name),
+        Expr::Assign { target, value, .. } => {
+            write_operand(out, target);
+            out.push_str(" = ");
+            write_operand(out, value);
+        }
+        Expr::CompoundAssign { target, op, value, .. } => {
+            write_operand(out, target);
+            let _ = write!(out, " {}= ", binary_operator(op));
+            write_operand(out, value);
+        }
+        Expr::Update { target, op, prefix, .. } => {
+            let operator = if *op == BinaryOp::Subtract { "--" } else { "++" };
+            if *prefix {
+                out.push_str(operator);
+                write_operand(out, target);
+            } else {
+                write_operand(out, target);
+                out.push_str(operator);
+            }
+        }
+        Expr::Call { callee, arguments, .. } => {
+            write_operand(out, callee);
+            out.push('(');
+            write_list(out, arguments);
+            out.push(')');
+        }
+        Expr::ArrayAccess { array, index, .. } => {
+            write_operand(out, array);
+            out.push('[');
+            write_expr(out, index);
+            out.push(']');
+        }
+        Expr::StructLiteral { name, fields, .. } => {
+            let _ = write!(out, "{name} {{ ");
+            for (i, (field, value)) in fields.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                let _ = write!(out, "{field}: ");
+                write_expr(out, value);
+            }
+            out.push_str(" }");
+        }
+        Expr::FieldAccess { object, field, .. } => {
+            write_operand(out, object);
+            let _ = write!(out, ".{field}");
+        }
+        Expr::Cast { expr, target_type, .. } => {
+            out.push('(');
+            write_operand(out, expr);
+            out.push_str(" as ");
+            write_type(out, target_type);
+            out.push(')');
+        }
+    }
+}
+
+fn write_binary(out: &mut String, left: &Expr, op: &BinaryOp, right: &Expr) {
+    write_operand(out, left);
+    let _ = write!(out, " {} ", binary_operator(op));
+    write_operand(out, right);
+}
+
+/// Writes a subexpression, parenthesizing assignments so that they do not
+/// absorb the surrounding operator.
+fn write_operand(out: &mut String, expr: &Expr) {
+    if matches!(expr, Expr::Assign { .. } | Expr::CompoundAssign { .. }) {
+        out.push('(');
+        write_expr(out, expr);
+        out.push(')');
+    } else {
+        write_expr(out, expr);
+    }
+}
+
+fn write_list(out: &mut String, exprs: &[Expr]) {
+    for (i, expr) in exprs.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write_operand(out, expr);
+    }
+}
+
+fn write_literal(out: &mut String, value: &LiteralValue) {
+    match value {
+        LiteralValue::Number(number) => write_number(out, number),
+        LiteralValue::StringLit(text) => {
+            let _ = write!(out, "\"{}\"", escape(text, '"'));
+        }
+        LiteralValue::CharLit(text) => {
+            let _ = write!(out, "'{}'", escape(text, '\''));
+        }
+        LiteralValue::Bool(value) => {
+            let _ = write!(out, "{value}");
+        }
+        LiteralValue::Nullptr => out.push_str("nullptr"),
+    }
+}
+
+/// Writes a number with the suffix that gives it back its type.
+fn write_number(out: &mut String, number: &Number) {
+    let _ = match number {
+        Number::I8(value) => write!(out, "{value}i8"),
+        Number::I16(value) => write!(out, "{value}i16"),
+        Number::I32(value) => write!(out, "{value}i32"),
+        Number::Integer(value) => write!(out, "{value}"),
+        Number::U8(value) => write!(out, "{value}u8"),
+        Number::U16(value) => write!(out, "{value}u16"),
+        Number::U32(value) => write!(out, "{value}u32"),
+        Number::UnsignedInteger(value) => write!(out, "{value}u"),
+        Number::Float32(value) => write!(out, "{value:?}f"),
+        Number::Float64(value) => write!(out, "{value:?}"),
+        Number::Scientific32(base, exponent) => write!(out, "{base:?}e{exponent}f"),
+        Number::Scientific64(base, exponent) => write!(out, "{base:?}e{exponent}"),
+    };
+}
+
+/// Escapes the contents of a literal delimited by `quote`.
+fn escape(text: &str, quote: char) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\0' => escaped.push_str("\\0"),
+            '\\' => escaped.push_str("\\\\"),
+            c if c == quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{{{:x}}}", u32::from(c));
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const fn binary_operator(op: &BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Add => "+",
+        BinaryOp::Subtract => "-",
+        BinaryOp::Multiply => "*",
+        BinaryOp::Divide => "/",
+        BinaryOp::Modulo => "%",
+        BinaryOp::Equal => "==",
+        BinaryOp::NotEqual => "!=",
+        BinaryOp::Less => "<",
+        BinaryOp::LessEqual => "<=",
+        BinaryOp::Greater => ">",
+        BinaryOp::GreaterEqual => ">=",
+        BinaryOp::And => "&&",
+        BinaryOp::Or => "||",
+        BinaryOp::BitwiseAnd => "&",
+        BinaryOp::BitwiseOr => "|",
+        BinaryOp::BitwiseXor => "^",
+        BinaryOp::ShiftLeft => "<<",
+        BinaryOp::ShiftRight => ">>",
+    }
+}
@@ -0,0 +1,240 @@
+//! # Module Resolver
+//!
+//! Loads the files named by top-level `import "path.vn"` statements. Paths are
+//! relative to the importing file. Each imported file is lexed and parsed once,
+//! even when several files import it, and its tokens keep its own path so that
+//! errors point into the right file.
+//!
+//! Files are stored dependencies first: every file comes after all the files
+//! it imports, and the entry file comes last. Failures are reported as
+//! [`CompileError`] values:
+//!
+//! | Code | Cause |
+//! |------|-------|
+//! | [`ErrorCode::E2032`] | A top-level name is declared in more than one file |
+//! | [`ErrorCode::E2037`] | A file imports itself, directly or indirectly |
+//! | [`ErrorCode::E2038`] | An imported file cannot be found or read |
+//!
+//! Lexer and syntax errors of imported files are reported unchanged. Each file
+//! is type checked with the top-level functions, structs, enums and globals of
+//! the files it imports directly in scope.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use jsavrs::resolver::ModuleResolver;
+//!
+//! let mut resolver = ModuleResolver::new();
+//! let entry = resolver.resolve("main.vn", line_tracker, statements);
+//! errors.extend(resolver.check_types());
+//! let program = resolver.linked_statements();
+//! ```
+
+use crate::error::compile_error::CompileError;
+use crate::error::error_code::ErrorCode;
+use crate::lexer::{Lexer, lexer_tokenize_with_errors};
+use crate::location::line_tracker::LineTracker;
+use crate::location::source_span::SourceSpan;
+use crate::parser::ast::Stmt;
+use crate::parser::jsav_parser::JsavParser;
+use crate::semantic::symbol_table::Symbol;
+use crate::semantic::type_checker::TypeChecker;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A parsed source file together with the files it imports.
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    /// Path of the file, as used in the spans of its tokens
+    pub path: Arc<str>,
+    /// Source text of the file, for error reporting
+    pub line_tracker: LineTracker,
+    /// Parsed statements, including the `import` statements
+    pub statements: Vec<Stmt>,
+    /// Indices into [`ModuleResolver::files`] of the directly imported files
+    pub imports: Vec<usize>,
+}
+
+/// Loads a program split across several files; see the module documentation.
+#[derive(Debug, Default)]
+pub struct ModuleResolver {
+    files: Vec<SourceFile>,
+    loaded: HashMap<PathBuf, usize>,
+    /// Files whose imports are being loaded, outermost first
+    in_progress: Vec<(PathBuf, Arc<str>)>,
+    errors: Vec<CompileError>,
+}
+
+impl ModuleResolver {
+    /// Creates a resolver with no files.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the already parsed entry file and loads, transitively, every file
+    /// it imports. Returns the index of the entry file.
+    pub fn resolve(&mut self, path: &str, line_tracker: LineTracker, statements: Vec<Stmt>) -> usize {
+        let key = fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+        let entry = self.add_file(key, Arc::from(path), line_tracker, statements);
+        self.check_duplicate_declarations();
+        entry
+    }
+
+    /// Loaded files, each after the files it imports.
+    #[must_use]
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// Errors found while loading imported files.
+    #[must_use]
+    pub fn errors(&self) -> &[CompileError] {
+        &self.errors
+    }
+
+    /// Takes the errors found while loading imported files.
+    pub fn take_errors(&mut self) -> Vec<CompileError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Type checks every file, dependencies first, with the top-level
+    /// declarations of the files it imports directly in scope.
+    #[must_use]
+    pub fn check_types(&self) -> Vec<CompileError> {
+        let mut errors = Vec::new();
+        let mut exports: Vec<Vec<(Arc<str>, Symbol)>> = Vec::with_capacity(self.files.len());
+        for file in &self.files {
+            let mut checker = TypeChecker::new();
+            checker.declare_imports(file.imports.iter().flat_map(|&index| exports[index].iter().cloned()));
+            errors.extend(checker.check(&file.statements));
+            exports.push(checker.exported_symbols(&file.statements));
+        }
+        errors
+    }
+
+    /// Statements of the whole program, for IR generation: the entry file
+    /// (the last one loaded) and the functions, structs, enums and globals of
+    /// every other file.
+    #[must_use]
+    pub fn linked_statements(&self) -> Vec<Stmt> {
+        let Some((entry, imported)) = self.files.split_last() else {
+            return Vec::new();
+        };
+        imported
+            .iter()
+            .flat_map(|file| &file.statements)
+            .filter(|stmt| {
+                matches!(
+                    stmt,
+                    Stmt::Function { .. } | Stmt::Struct { .. } | Stmt::Enum { .. } | Stmt::VarDeclaration { .. }
+                )
+            })
+            .chain(&entry.statements)
+            .cloned()
+            .collect()
+    }
+
+    fn add_file(&mut self, key: PathBuf, path: Arc<str>, line_tracker: LineTracker, statements: Vec<Stmt>) -> usize {
+        self.in_progress.push((key.clone(), path.clone()));
+        let mut imports = Vec::new();
+        for stmt in &statements {
+            if let Stmt::Import { path: import_path, span } = stmt
+                && let Some(index) = self.load(&path, import_path, span)
+            {
+                imports.push(index);
+            }
+        }
+        self.in_progress.pop();
+
+        let index = self.files.len();
+        self.files.push(SourceFile { path, line_tracker, statements, imports });
+        self.loaded.insert(key, index);
+        index
+    }
+
+    /// Loads `import_path`, imported by the file at `importer`, unless it is
+    /// already loaded.
+    fn load(&mut self, importer: &str, import_path: &str, span: &SourceSpan) -> Option<usize> {
+        let path = Path::new(importer).parent().unwrap_or_else(|| Path::new("")).join(import_path);
+        let key = match fs::canonicalize(&path) {
+            Ok(key) => key,
+            Err(e) => {
+                self.import_error(ErrorCode::E2038, format!("Cannot find imported file '{import_path}': {e}"), span);
+                return None;
+            }
+        };
+        if let Some(&index) = self.loaded.get(&key) {
+            return Some(index);
+        }
+        if let Some(start) = self.in_progress.iter().position(|(loading, _)| *loading == key) {
+            let path = path.to_string_lossy();
+            let mut cycle: Vec<&str> = self.in_progress[start..].iter().map(|(_, path)| path.as_ref()).collect();
+            cycle.push(&path);
+            let message = format!("Circular import: {}", cycle.join(" -> "));
+            self.import_error(ErrorCode::E2037, message, span);
+            return None;
+        }
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                self.import_error(ErrorCode::E2038, format!("Cannot read imported file '{import_path}': {e}"), span);
+                return None;
+            }
+        };
+
+        let path = path.to_string_lossy();
+        let mut lexer = Lexer::new(&path, &source);
+        let (tokens, lexer_errors) = lexer_tokenize_with_errors(&mut lexer);
+        let line_tracker = lexer.get_line_tracker().clone();
+        let statements = if lexer_errors.is_empty() {
+            let (statements, parser_errors) = JsavParser::new(&tokens).parse();
+            self.errors.extend(parser_errors);
+            statements
+        } else {
+            self.errors.extend(lexer_errors);
+            Vec::new()
+        };
+        Some(self.add_file(key, Arc::from(path.as_ref()), line_tracker, statements))
+    }
+
+    /// Reports top-level names declared in more than one file: all files
+    /// are linked into one program, where they would clash.
+    fn check_duplicate_declarations(&mut self) {
+        let mut declared: HashMap<Arc<str>, Arc<str>> = HashMap::new();
+        let mut duplicates = Vec::new();
+        for file in &self.files {
+            for (name, span) in file.statements.iter().flat_map(top_level_names) {
+                let path = declared.entry(name.clone()).or_insert_with(|| file.path.clone());
+                if *path != file.path {
+                    duplicates.push((format!("Identifier '{name}' is already declared in '{path}'"), span.clone()));
+                }
+            }
+        }
+        for (message, span) in duplicates {
+            self.import_error(ErrorCode::E2032, message, &span);
+        }
+    }
+
+    fn import_error(&mut self, code: ErrorCode, message: String, span: &SourceSpan) {
+        self.errors.push(CompileError::TypeError {
+            code: Some(code),
+            message: message.into(),
+            span: span.clone(),
+            help: None,
+        });
+    }
+}
+
+/// Names declared by a top-level statement, with the span of the declaration.
+fn top_level_names(stmt: &Stmt) -> Vec<(&Arc<str>, &SourceSpan)> {
+    match stmt {
+        Stmt::Function { name, span, .. } | Stmt::Struct { name, span, .. } | Stmt::Enum { name, span, .. } => {
+            vec![(name, span)]
+        }
+        Stmt::VarDeclaration { variables, span, .. } => variables.iter().map(|name| (name, span)).collect(),
+        _ => Vec::new(),
+    }
+}
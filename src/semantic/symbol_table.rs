@@ -8,7 +8,7 @@ use std::sync::Arc;
 
 /// Represents a symbol in the symbol table.
 ///
-/// Symbols can be variables, functions, or type aliases. Each symbol type
+/// Symbols can be variables, functions, structs, enums, or type aliases. Each symbol type
 /// carries specific metadata relevant to semantic analysis and code generation.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Symbol {
@@ -16,6 +16,10 @@ pub enum Symbol {
     Variable(VariableSymbol),
     /// A function symbol with signature information
     Function(FunctionSymbol),
+    /// A struct type with its fields
+    Struct(StructSymbol),
+    /// An enum type with its variants
+    Enum(EnumSymbol),
     /// A type alias mapping to an underlying type
     TypeAlias(Type),
 }
@@ -55,6 +59,54 @@ pub struct FunctionSymbol {
     pub defined_at: SourceSpan,
 }
 
+/// Metadata for a struct symbol.
+///
+/// Lists the fields in declaration order, which is also the order of their
+/// storage in memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructSymbol {
+    /// The name of the struct
+    pub name: Arc<str>,
+    /// The fields of the struct with their types
+    pub fields: Vec<Parameter>,
+    /// Source location where the struct was defined
+    pub defined_at: SourceSpan,
+}
+
+impl StructSymbol {
+    /// Returns the type of the field called `name`, if the struct has one.
+    #[must_use]
+    pub fn field_type(&self, name: &str) -> Option<&Type> {
+        self.fields.iter().find(|field| &*field.name == name).map(|field| &field.type_annotation)
+    }
+}
+
+/// Metadata for an enum symbol.
+///
+/// Each variant is listed with its discriminant when it can be computed from
+/// literals alone; discriminants naming constants are only known to the IR
+/// generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumSymbol {
+    /// The name of the enum
+    pub name: Arc<str>,
+    /// The integer type the variants are stored as
+    pub underlying_type: Type,
+    /// The variants in declaration order with their discriminants, if known
+    pub variants: Vec<(Arc<str>, Option<i128>)>,
+    /// Source location where the enum was defined
+    pub defined_at: SourceSpan,
+}
+
+impl EnumSymbol {
+    /// Returns the discriminant of the variant called `name`: `None` if the
+    /// enum has no such variant, `Some(None)` if its value is not known.
+    #[must_use]
+    pub fn variant(&self, name: &str) -> Option<Option<i128>> {
+        self.variants.iter().find(|(variant, _)| &**variant == name).map(|(_, value)| *value)
+    }
+}
+
 /// Represents the different kinds of scopes in the program.
 ///
 /// The scope kind determines visibility rules and what operations are valid.
@@ -241,6 +293,8 @@ impl SymbolTable {
                 span: match current_scope.symbols.get(name) {
                     Some(Symbol::Variable(v)) => v.defined_at.clone(),
                     Some(Symbol::Function(f)) => f.defined_at.clone(),
+                    Some(Symbol::Struct(s)) => s.defined_at.clone(),
+                    Some(Symbol::Enum(e)) => e.defined_at.clone(),
                     _ => SourceSpan::default(),
                 },
                 help: None,
@@ -335,6 +389,42 @@ impl SymbolTable {
         })
     }
 
+    /// Looks up a struct symbol by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the struct to look up
+    ///
+    /// # Returns
+    ///
+    /// An optional clone of the struct symbol if found and is a struct,
+    /// or `None` if not found or not a struct.
+    #[must_use]
+    pub fn lookup_struct(&self, name: &str) -> Option<StructSymbol> {
+        self.find_symbol(name, |sym| match sym {
+            Symbol::Struct(s) => Some(s.clone()),
+            _ => None,
+        })
+    }
+
+    /// Looks up an enum symbol by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the enum to look up
+    ///
+    /// # Returns
+    ///
+    /// An optional clone of the enum symbol if found and is an enum,
+    /// or `None` if not found or not an enum.
+    #[must_use]
+    pub fn lookup_enum(&self, name: &str) -> Option<EnumSymbol> {
+        self.find_symbol(name, |sym| match sym {
+            Symbol::Enum(e) => Some(e.clone()),
+            _ => None,
+        })
+    }
+
     /// Sets the current function context.
     ///
     /// This is used to track which function is currently being analyzed,
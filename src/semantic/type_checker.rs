@@ -34,11 +34,14 @@
 // src/semantic/type_checker.rs
 use crate::error::compile_error::CompileError;
 use crate::error::error_code::ErrorCode;
+use crate::ir::{IrType, TypePromotionEngine};
 use crate::location::source_span::{HasSpan, SourceSpan};
-use crate::parser::ast::{BinaryOp, Expr, LiteralValue, Parameter, Stmt, Type, UnaryOp};
-use crate::semantic::symbol_table::{FunctionSymbol, ScopeKind, Symbol, SymbolTable, VariableSymbol};
+use crate::parser::ast::{BinaryOp, EnumVariant, Expr, LiteralValue, Parameter, Stmt, SwitchCase, Type, UnaryOp};
+use crate::semantic::symbol_table::{
+    EnumSymbol, FunctionSymbol, ScopeKind, StructSymbol, Symbol, SymbolTable, VariableSymbol,
+};
 use crate::tokens::number::Number;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex, OnceLock};
 
 /// Type checker for semantic analysis of jsavrs programs.
@@ -51,6 +54,7 @@ use std::sync::{Arc, Mutex, OnceLock};
 /// # Fields
 ///
 /// * `in_loop` - Tracks whether currently inside a loop (for break/continue validation)
+/// * `in_switch` - Tracks whether currently inside a switch arm (for break validation)
 /// * `return_type_stack` - Stack of expected return types for nested functions
 /// * `errors` - Accumulated type errors found during checking
 /// * `symbol_table` - Symbol table for variable and function declarations
@@ -71,9 +75,12 @@ use std::sync::{Arc, Mutex, OnceLock};
 /// ```
 pub struct TypeChecker {
     in_loop: bool,
+    in_switch: bool,
     return_type_stack: Vec<Type>,
     errors: Vec<CompileError>,
     symbol_table: SymbolTable,
+    /// Names declared by [`declare_imports`](Self::declare_imports)
+    imported: HashSet<Arc<str>>,
 }
 
 // Gerarchia per la promozione dei tipi numerici
@@ -86,7 +93,7 @@ static TYPE_PROMOTION_CACHE: OnceLock<Mutex<HashMap<(Type, Type), Type>>> = Once
 // Precomputed type promotion lookup table for better performance
 static TYPE_PROMOTION_TABLE: OnceLock<[u8; 100]> = OnceLock::new();
 
-#[allow(clippy::collapsible_if)]
+#[allow(clippy::collapsible_if, clippy::collapsible_match)]
 impl TypeChecker {
     /// Creates a new type checker with empty state.
     ///
@@ -104,7 +111,14 @@ impl TypeChecker {
     /// ```
     #[must_use]
     pub fn new() -> Self {
-        Self { symbol_table: SymbolTable::new(), errors: Vec::new(), in_loop: false, return_type_stack: Vec::new() }
+        Self {
+            symbol_table: SymbolTable::new(),
+            errors: Vec::new(),
+            in_loop: false,
+            in_switch: false,
+            return_type_stack: Vec::new(),
+            imported: HashSet::new(),
+        }
     }
 
     /// Records a type error with an optional error code.
@@ -146,13 +160,65 @@ impl TypeChecker {
     /// }
     /// ```
     pub fn check(&mut self, statements: &[Stmt]) -> Vec<CompileError> {
+        self.declare_functions(statements);
         self.visit_statements(statements);
         std::mem::take(&mut self.errors)
     }
 
+    /// Declares the symbols exported by imported files in the global scope.
+    ///
+    /// Call before [`check`](Self::check). Clashes between imported names and
+    /// the top-level declarations of the checked file are reported by the
+    /// [`ModuleResolver`](crate::resolver::ModuleResolver), not by `check`.
+    pub fn declare_imports(&mut self, symbols: impl IntoIterator<Item = (Arc<str>, Symbol)>) {
+        for (name, symbol) in symbols {
+            // A clash between two imports is reported by the resolver too.
+            let _ = self.symbol_table.declare(&name, symbol);
+            self.imported.insert(name);
+        }
+    }
+
+    /// Symbols of the top-level functions, structs, enums and globals of
+    /// `statements`, as declared by [`check`](Self::check), for the files
+    /// that import them.
+    #[must_use]
+    pub fn exported_symbols(&self, statements: &[Stmt]) -> Vec<(Arc<str>, Symbol)> {
+        let names = statements.iter().flat_map(|stmt| match stmt {
+            Stmt::Function { name, .. } | Stmt::Struct { name, .. } | Stmt::Enum { name, .. } => {
+                std::slice::from_ref(name)
+            }
+            Stmt::VarDeclaration { variables, .. } => variables.as_slice(),
+            _ => &[],
+        });
+        names.filter_map(|name| Some((name.clone(), self.symbol_table.lookup(name)?))).collect()
+    }
+
+    /// Declares the signature of every top-level function before any body is
+    /// checked, so that calls may refer to functions declared later in the
+    /// file and functions may be mutually recursive.
+    fn declare_functions(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            if let Stmt::Function { name, parameters, return_type, span, .. } = stmt {
+                self.declare_function(name, parameters, return_type, span);
+            }
+        }
+    }
+
+    fn declare_function(&mut self, name: &str, parameters: &[Parameter], return_type: &Type, span: &SourceSpan) {
+        let func_symbol = FunctionSymbol {
+            name: name.into(),
+            parameters: parameters.to_vec(),
+            return_type: return_type.clone(),
+            defined_at: span.clone(),
+        };
+        self.declare_symbol(name, Symbol::Function(func_symbol));
+    }
+
     // Helper method per dichiarare simboli
     fn declare_symbol(&mut self, name: &str, symbol: Symbol) {
-        if let Err(e) = self.symbol_table.declare(name, symbol) {
+        if let Err(e) = self.symbol_table.declare(name, symbol)
+            && !(self.imported.contains(name) && self.symbol_table.current_scope_kind() == Some(ScopeKind::Global))
+        {
             self.errors.push(e);
         }
     }
@@ -172,6 +238,10 @@ impl TypeChecker {
                 self.visit_var_declaration(variables, type_annotation, *is_mutable, initializers, span);
             }
             Stmt::Function { name, parameters, return_type, body, span } => {
+                // Top-level functions are declared by `declare_functions`
+                if self.symbol_table.current_scope_kind() != Some(ScopeKind::Global) {
+                    self.declare_function(name, parameters, return_type, span);
+                }
                 self.visit_function(name, parameters, return_type, body, span);
             }
             Stmt::If { condition, then_branch, else_branch, span } => {
@@ -186,6 +256,167 @@ impl TypeChecker {
             Stmt::Break { span } => self.visit_break(span),
             Stmt::Continue { span } => self.visit_continue(span),
             Stmt::MainFunction { body, span } => self.visit_main_function(body, span),
+            Stmt::Struct { name, fields, span } => self.visit_struct_declaration(name, fields, span),
+            Stmt::Import { path, span } => self.visit_import(path, span),
+            Stmt::Switch { scrutinee, cases, default, span } => {
+                self.visit_switch(scrutinee, cases, default.as_deref(), span);
+            }
+            Stmt::Enum { name, underlying_type, variants, span } => {
+                self.visit_enum_declaration(name, underlying_type, variants, span);
+            }
+        }
+    }
+
+    /// Imported files are loaded by the module resolver before type checking;
+    /// only top-level imports are resolved.
+    fn visit_import(&mut self, path: &str, span: &SourceSpan) {
+        if self.symbol_table.current_scope_kind() != Some(ScopeKind::Global) {
+            self.type_error_with_code(
+                Some(ErrorCode::E2038),
+                format!("Import of '{path}' must be at the top level of the file"),
+                span,
+            );
+        }
+    }
+
+    /// Reports an E2033 error for every custom type name in `ty` that does
+    /// not refer to a declared struct or enum.
+    fn check_type_defined(&mut self, ty: &Type, span: &SourceSpan) {
+        match ty {
+            Type::Custom(name)
+                if self.symbol_table.lookup_struct(name).is_none() && self.symbol_table.lookup_enum(name).is_none() =>
+            {
+                self.type_error_with_code(Some(ErrorCode::E2033), format!("Undefined type '{name}'"), span);
+            }
+            Type::Array(element_type, _) | Type::Vector(element_type) => self.check_type_defined(element_type, span),
+            _ => {}
+        }
+    }
+
+    /// Reports functions returning a struct or an array: they would return
+    /// the address of their own, already released, stack memory.
+    fn check_return_type(&mut self, name: &str, return_type: &Type, span: &SourceSpan) {
+        let aggregate = match return_type {
+            Type::Array(..) => true,
+            Type::Custom(type_name) => self.symbol_table.lookup_struct(type_name).is_some(),
+            _ => false,
+        };
+        if aggregate {
+            self.type_error_with_code(
+                Some(ErrorCode::E2044),
+                format!("Function '{name}' returns {return_type}; functions cannot return structs or arrays"),
+                span,
+            );
+        }
+    }
+
+    fn visit_struct_declaration(&mut self, name: &Arc<str>, fields: &[Parameter], span: &SourceSpan) {
+        for (i, field) in fields.iter().enumerate() {
+            if fields[..i].iter().any(|previous| previous.name == field.name) {
+                self.type_error_with_code(
+                    Some(ErrorCode::E2032),
+                    format!("Field '{}' already declared in struct '{name}'", field.name),
+                    &field.span,
+                );
+            }
+            // The struct is declared after its fields, so it cannot contain itself.
+            self.check_type_defined(&field.type_annotation, &field.span);
+        }
+        self.declare_symbol(
+            name,
+            Symbol::Struct(StructSymbol { name: name.clone(), fields: fields.to_vec(), defined_at: span.clone() }),
+        );
+    }
+
+    /// Checks that an enum has an integer underlying type and that its
+    /// discriminants are distinct integer constants that fit in it.
+    fn visit_enum_declaration(
+        &mut self, name: &Arc<str>, underlying_type: &Type, variants: &[EnumVariant], span: &SourceSpan,
+    ) {
+        if !Self::is_integer_type(underlying_type) {
+            self.type_error_with_code(
+                Some(ErrorCode::E2042),
+                format!("Underlying type of enum '{name}' must be an integer type, found {underlying_type}"),
+                span,
+            );
+        }
+
+        let mut symbols: Vec<(Arc<str>, Option<i128>)> = Vec::with_capacity(variants.len());
+        for variant in variants {
+            if symbols.iter().any(|(previous, _)| *previous == variant.name) {
+                self.type_error_with_code(
+                    Some(ErrorCode::E2032),
+                    format!("Variant '{}' already declared in enum '{name}'", variant.name),
+                    &variant.span,
+                );
+                continue;
+            }
+            let value = variant.value.as_ref().map_or_else(
+                || symbols.last().map_or(Some(0), |(_, previous)| previous.map(|value| value + 1)),
+                |expr| self.visit_discriminant(&variant.name, expr),
+            );
+            if let Some(value) = value {
+                if let Some((min, max)) = Self::integer_range(underlying_type)
+                    && !(min..=max).contains(&value)
+                {
+                    self.type_error_with_code(
+                        Some(ErrorCode::E2042),
+                        format!("Discriminant {value} of variant '{}' does not fit in {underlying_type}", variant.name),
+                        &variant.span,
+                    );
+                } else if let Some((other, _)) = symbols.iter().find(|(_, previous)| *previous == Some(value)) {
+                    self.type_error_with_code(
+                        Some(ErrorCode::E2042),
+                        format!("Discriminant {value} of variant '{}' is already used by '{other}'", variant.name),
+                        &variant.span,
+                    );
+                }
+            }
+            symbols.push((variant.name.clone(), value));
+        }
+
+        self.declare_symbol(
+            name,
+            Symbol::Enum(EnumSymbol {
+                name: name.clone(),
+                underlying_type: underlying_type.clone(),
+                variants: symbols,
+                defined_at: span.clone(),
+            }),
+        );
+    }
+
+    /// Checks that an explicit discriminant is an integer constant and returns
+    /// its value if it is written with literals.
+    fn visit_discriminant(&mut self, variant: &str, expr: &Expr) -> Option<i128> {
+        let value_type = self.visit_expr(expr);
+        if !self.is_constant_expr(expr) || value_type.as_ref().is_some_and(|ty| !Self::is_integer_type(ty)) {
+            self.type_error_with_code(
+                Some(ErrorCode::E2042),
+                format!("Discriminant of variant '{variant}' is not an integer constant"),
+                expr.span(),
+            );
+        }
+        self.case_key(expr)
+    }
+
+    /// The enum whose variant `object.variant` names, when `object` is the
+    /// name of an enum rather than of a variable.
+    fn variant_access_enum(&self, object: &Expr) -> Option<EnumSymbol> {
+        match object {
+            Expr::Variable { name, .. } if self.symbol_table.lookup_variable(name).is_none() => {
+                self.symbol_table.lookup_enum(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// The type that values of `ty` are stored as: the underlying type for
+    /// enums, `ty` itself otherwise.
+    fn storage_type(&self, ty: &Type) -> Type {
+        match ty {
+            Type::Custom(name) => self.symbol_table.lookup_enum(name).map_or_else(|| ty.clone(), |e| e.underlying_type),
+            _ => ty.clone(),
         }
     }
 
@@ -205,6 +436,8 @@ impl TypeChecker {
             );
             return;
         }
+        self.check_type_defined(type_annotation, span);
+        let is_global = self.symbol_table.current_scope_kind() == Some(ScopeKind::Global);
 
         for (var_name, init_expr) in variables.iter().zip(initializers) {
             let init_type = self.visit_expr(init_expr);
@@ -216,6 +449,8 @@ impl TypeChecker {
                         format!("Cannot assign {init_type} to {type_annotation} for variable '{var_name}'"),
                         init_expr.span(),
                     );
+                } else if is_global {
+                    self.check_global(var_name, type_annotation, init_expr);
                 }
             }
 
@@ -232,19 +467,56 @@ impl TypeChecker {
         }
     }
 
+    /// Reports an E2039 error for a top-level variable that cannot be stored
+    /// in a data section or whose initializer is not a constant expression.
+    ///
+    /// Globals hold a number, `bool` or `char`, or an array of them; strings
+    /// and structs cannot be globals.
+    fn check_global(&mut self, name: &str, ty: &Type, initializer: &Expr) {
+        let element_type = if let Type::Array(element_type, _) = ty { element_type } else { ty };
+        if matches!(Self::primitive_ir_type(&self.storage_type(element_type)), None | Some(IrType::String)) {
+            self.type_error_with_code(
+                Some(ErrorCode::E2039),
+                format!(
+                    "Global '{name}' has type {ty}; globals must have a number, bool or char type, or be arrays of them"
+                ),
+                initializer.span(),
+            );
+        } else if !self.is_constant_expr(initializer) {
+            self.type_error_with_code(
+                Some(ErrorCode::E2039),
+                format!("Initializer of global '{name}' is not a constant expression"),
+                initializer.span(),
+            );
+        }
+    }
+
+    /// Whether `expr` can be evaluated at compile time: literals, constants
+    /// enum variants, operators or casts applied to them and array literals
+    /// of such expressions.
+    fn is_constant_expr(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Literal { value, .. } => !matches!(value, LiteralValue::StringLit(_) | LiteralValue::Nullptr),
+            Expr::Variable { name, .. } => self.symbol_table.lookup_variable(name).is_some_and(|var| !var.mutable),
+            Expr::Grouping { expr, .. } | Expr::Unary { expr, .. } | Expr::Cast { expr, .. } => {
+                self.is_constant_expr(expr)
+            }
+            Expr::Binary { left, right, .. } => self.is_constant_expr(left) && self.is_constant_expr(right),
+            Expr::ArrayLiteral { elements, .. } => elements.iter().all(|element| self.is_constant_expr(element)),
+            Expr::FieldAccess { object, .. } => self.variant_access_enum(object).is_some(),
+            _ => false,
+        }
+    }
+
     fn visit_function(
         &mut self, name: &str, parameters: &[Parameter], return_type: &Type, body: &[Stmt], span: &SourceSpan,
     ) {
-        let func_symbol = FunctionSymbol {
-            name: name.into(),
-            parameters: parameters.to_vec(),
-            return_type: return_type.clone(),
-            defined_at: span.clone(),
-        };
-        self.declare_symbol(name, Symbol::Function(func_symbol));
+        self.check_type_defined(return_type, span);
+        self.check_return_type(name, return_type, span);
         self.symbol_table.push_scope(ScopeKind::Function, Some(span.clone()));
         self.return_type_stack.push(return_type.clone());
         for param in parameters {
+            self.check_type_defined(&param.type_annotation, &param.span);
             self.declare_symbol(
                 &param.name,
                 Symbol::Variable(VariableSymbol {
@@ -271,6 +543,7 @@ impl TypeChecker {
     }
 
     fn visit_main_function(&mut self, body: &[Stmt], span: &SourceSpan) {
+        self.declare_function("main", &[], &Type::Void, span);
         self.visit_function("main", &[], &Type::Void, body, span);
     }
 
@@ -308,6 +581,84 @@ impl TypeChecker {
         self.in_loop = was_in_loop;
     }
 
+    fn visit_switch(&mut self, scrutinee: &Expr, cases: &[SwitchCase], default: Option<&[Stmt]>, _span: &SourceSpan) {
+        let scrutinee_type = self.visit_expr(scrutinee);
+        if let Some(ty) = &scrutinee_type
+            && !Self::is_integer_type(&self.storage_type(ty))
+            && !matches!(ty, Type::Char | Type::Bool)
+        {
+            self.type_error_with_code(
+                Some(ErrorCode::E2040),
+                format!("Cannot switch on a value of type {ty}; expected an integer, enum, char or bool"),
+                scrutinee.span(),
+            );
+        }
+
+        let mut seen: Vec<i128> = Vec::with_capacity(cases.len());
+        for case in cases {
+            let case_type = self.visit_expr(&case.value);
+            if !self.is_constant_expr(&case.value) {
+                self.type_error_with_code(
+                    Some(ErrorCode::E2040),
+                    "Case value is not a constant expression",
+                    case.value.span(),
+                );
+            } else if let (Some(case_type), Some(scrutinee_type)) = (&case_type, &scrutinee_type)
+                && !self.is_assignable(case_type, scrutinee_type)
+            {
+                self.type_error_with_code(
+                    Some(ErrorCode::E2040),
+                    format!("Case value of type {case_type} does not match switch value of type {scrutinee_type}"),
+                    case.value.span(),
+                );
+            } else if let Some(key) = self.case_key(&case.value) {
+                if seen.contains(&key) {
+                    self.type_error_with_code(
+                        Some(ErrorCode::E2041),
+                        "Duplicate case value in switch",
+                        case.value.span(),
+                    );
+                } else {
+                    seen.push(key);
+                }
+            }
+        }
+
+        let was_in_switch = self.in_switch;
+        self.in_switch = true;
+        for body in cases.iter().map(|case| case.body.as_slice()).chain(default) {
+            self.symbol_table.push_scope(ScopeKind::Block, Some(scrutinee.span().clone()));
+            self.visit_statements(body);
+            self.symbol_table.pop_scope();
+        }
+        self.in_switch = was_in_switch;
+    }
+
+    /// Value of a case or discriminant written as a literal or enum variant,
+    /// possibly negated or parenthesized, used to report duplicates. Values
+    /// naming constants are checked by the IR generator, which knows them.
+    fn case_key(&self, expr: &Expr) -> Option<i128> {
+        match expr {
+            Expr::Literal { value: LiteralValue::Number(number), .. } => match *number {
+                Number::I8(n) => Some(n.into()),
+                Number::I16(n) => Some(n.into()),
+                Number::I32(n) => Some(n.into()),
+                Number::Integer(n) => Some(n.into()),
+                Number::U8(n) => Some(n.into()),
+                Number::U16(n) => Some(n.into()),
+                Number::U32(n) => Some(n.into()),
+                Number::UnsignedInteger(n) => Some(n.into()),
+                _ => None,
+            },
+            Expr::Literal { value: LiteralValue::Bool(b), .. } => Some((*b).into()),
+            Expr::Literal { value: LiteralValue::CharLit(c), .. } => c.chars().next().map(|c| u32::from(c).into()),
+            Expr::Grouping { expr, .. } => self.case_key(expr),
+            Expr::Unary { op: UnaryOp::Negate, expr, .. } => self.case_key(expr).map(|value| -value),
+            Expr::FieldAccess { object, field, .. } => self.variant_access_enum(object)?.variant(field)?,
+            _ => None,
+        }
+    }
+
     #[allow(clippy::ref_option)]
     fn visit_for(
         &mut self, initializer: &Option<Box<Stmt>>, condition: &Option<Expr>, increment: &Option<Expr>, body: &[Stmt],
@@ -373,7 +724,7 @@ impl TypeChecker {
     }
 
     fn visit_break(&mut self, span: &SourceSpan) {
-        if !self.in_loop {
+        if !self.in_loop && !self.in_switch {
             self.type_error_with_code(Some(ErrorCode::E2009), "Break statement outside loop", span);
         }
     }
@@ -393,8 +744,13 @@ impl TypeChecker {
             Expr::ArrayLiteral { elements, span } => self.visit_array_literal(elements, span),
             Expr::Variable { name, span } => self.visit_variable(name, span),
             Expr::Assign { target, value, span } => self.visit_assign(target, value, span),
+            Expr::CompoundAssign { target, op, value, span } => self.visit_compound_assign(target, op, value, span),
+            Expr::Update { target, op, span, .. } => self.visit_update(target, op, span),
             Expr::Call { callee, arguments, span } => self.visit_call(callee, arguments, span),
             Expr::ArrayAccess { array, index, span } => self.visit_array_access(array, index, span),
+            Expr::StructLiteral { name, fields, span } => self.visit_struct_literal(name, fields, span),
+            Expr::FieldAccess { object, field, span } => self.visit_field_access(object, field, span),
+            Expr::Cast { expr, target_type, span } => self.visit_cast(expr, target_type, span),
         }
     }
 
@@ -666,7 +1022,60 @@ impl TypeChecker {
     }
 
     fn visit_assign(&mut self, target: &Expr, value: &Expr, _span: &SourceSpan) -> Option<Type> {
-        let target_type = match target {
+        let target_type = self.visit_assign_target(target)?;
+        let value_type = self.visit_expr(value)?;
+        self.check_assigned_value(target, &target_type, value, &value_type);
+        Some(target_type)
+    }
+
+    /// Checks `target op= value`: the target must be a mutable lvalue whose type
+    /// supports `op`, and the value must be assignable to it.
+    fn visit_compound_assign(&mut self, target: &Expr, op: &BinaryOp, value: &Expr, span: &SourceSpan) -> Option<Type> {
+        let target_type = self.visit_assign_target(target)?;
+        let value_type = self.visit_expr(value)?;
+        if *op == BinaryOp::BitwiseXor {
+            if !Self::is_integer_type(&target_type) || !Self::is_integer_type(&value_type) {
+                self.type_error_with_code(
+                    Some(ErrorCode::E2011),
+                    format!(
+                        "Bitwise operator '{op:?}' require integer operand types, found {target_type} and {value_type}"
+                    ),
+                    span,
+                );
+                return None;
+            }
+        } else if !Self::is_numeric(&target_type) || !Self::is_numeric(&value_type) {
+            self.type_error_with_code(
+                Some(ErrorCode::E2013),
+                format!("Binary operator '{op:?}' requires numeric operands, found {target_type} and {value_type}"),
+                span,
+            );
+            return None;
+        }
+        self.check_assigned_value(target, &target_type, value, &value_type);
+        Some(target_type)
+    }
+
+    /// Checks `++target`, `--target`, `target++` and `target--`: the target must
+    /// be a mutable numeric lvalue.
+    fn visit_update(&mut self, target: &Expr, op: &BinaryOp, span: &SourceSpan) -> Option<Type> {
+        let target_type = self.visit_assign_target(target)?;
+        if !Self::is_numeric(&target_type) {
+            let operator = if *op == BinaryOp::Add { "Increment" } else { "Decrement" };
+            self.type_error_with_code(
+                Some(ErrorCode::E2016),
+                format!("{operator} requires numeric type operand, found {target_type}"),
+                span,
+            );
+            return None;
+        }
+        Some(target_type)
+    }
+
+    /// Returns the type of an assignment target, reporting immutable and
+    /// undefined variables.
+    fn visit_assign_target(&mut self, target: &Expr) -> Option<Type> {
+        match target {
             Expr::Variable { name, span } => {
                 if let Some(var) = self.symbol_table.lookup_variable(name) {
                     if !var.mutable {
@@ -677,32 +1086,67 @@ impl TypeChecker {
                         );
                         return None;
                     }
-                    var.ty
+                    Some(var.ty)
                 } else {
                     self.type_error_with_code(Some(ErrorCode::E2025), format!("Undefined variable '{name}'"), span);
-                    return None;
+                    None
                 }
             }
             Expr::ArrayAccess { array, index, span } => {
                 // Delegate to visit_array_access to check both array and index
-                self.visit_array_access(array, index, span)?
+                self.visit_array_access(array, index, span)
             }
-            _ => {
-                return None;
+            Expr::FieldAccess { object, field, span } => {
+                if let Some(symbol) = self.variant_access_enum(object) {
+                    self.type_error_with_code(
+                        Some(ErrorCode::E2024),
+                        format!("Cannot assign to variant '{field}' of enum '{}'", symbol.name),
+                        span,
+                    );
+                    return None;
+                }
+                if let Some(var) = Self::root_variable(object).and_then(|name| self.symbol_table.lookup_variable(name))
+                {
+                    if !var.mutable {
+                        self.type_error_with_code(
+                            Some(ErrorCode::E2024),
+                            format!("Cannot assign to field '{field}' of immutable variable '{}'", var.name),
+                            span,
+                        );
+                        return None;
+                    }
+                }
+                self.visit_field_access(object, field, span)
             }
-        };
-        let value_type = self.visit_expr(value)?;
-        if !self.is_assignable(&value_type, &target_type) {
+            _ => None,
+        }
+    }
+
+    /// Returns the variable whose storage holds the field or element `expr`.
+    fn root_variable(expr: &Expr) -> Option<&str> {
+        match expr {
+            Expr::Variable { name, .. } => Some(name),
+            Expr::FieldAccess { object: inner, .. }
+            | Expr::ArrayAccess { array: inner, .. }
+            | Expr::Grouping { expr: inner, .. } => Self::root_variable(inner),
+            _ => None,
+        }
+    }
+
+    fn check_assigned_value(&mut self, target: &Expr, target_type: &Type, value: &Expr, value_type: &Type) {
+        if !self.is_assignable(value_type, target_type) {
             // Create specific error message for array elements
             let message = match target {
                 Expr::ArrayAccess { .. } => {
                     format!("Cannot assign {value_type} to array element of type {target_type}")
                 }
+                Expr::FieldAccess { field, .. } => {
+                    format!("Cannot assign {value_type} to field '{field}' of type {target_type}")
+                }
                 _ => format!("Cannot assign {value_type} to {target_type}"),
             };
             self.type_error_with_code(Some(ErrorCode::E2002), message, value.span());
         }
-        Some(target_type)
     }
 
     #[allow(clippy::manual_let_else)]
@@ -781,6 +1225,140 @@ impl TypeChecker {
         }
     }
 
+    /// Checks that a struct literal names a declared struct and initializes
+    /// each of its fields exactly once with a value of the field's type.
+    fn visit_struct_literal(&mut self, name: &str, fields: &[(Arc<str>, Expr)], span: &SourceSpan) -> Option<Type> {
+        let Some(symbol) = self.symbol_table.lookup_struct(name) else {
+            self.type_error_with_code(Some(ErrorCode::E2033), format!("Undefined type '{name}'"), span);
+            for (_, value) in fields {
+                self.visit_expr(value);
+            }
+            return None;
+        };
+
+        for (i, (field, value)) in fields.iter().enumerate() {
+            let value_type = self.visit_expr(value);
+            if fields[..i].iter().any(|(previous, _)| previous == field) {
+                self.type_error_with_code(
+                    Some(ErrorCode::E2032),
+                    format!("Field '{field}' specified more than once in literal of struct '{name}'"),
+                    value.span(),
+                );
+                continue;
+            }
+            let Some(field_type) = symbol.field_type(field) else {
+                self.type_error_with_code(
+                    Some(ErrorCode::E2034),
+                    format!("Struct '{name}' has no field '{field}'"),
+                    value.span(),
+                );
+                continue;
+            };
+            if let Some(value_type) = value_type {
+                if !self.is_assignable(&value_type, field_type) {
+                    self.type_error_with_code(
+                        Some(ErrorCode::E2002),
+                        format!("Cannot assign {value_type} to field '{field}' of type {field_type}"),
+                        value.span(),
+                    );
+                }
+            }
+        }
+
+        for field in &symbol.fields {
+            if !fields.iter().any(|(given, _)| *given == field.name) {
+                self.type_error_with_code(
+                    Some(ErrorCode::E2035),
+                    format!("Missing field '{}' in literal of struct '{name}'", field.name),
+                    span,
+                );
+            }
+        }
+        Some(Type::Custom(symbol.name))
+    }
+
+    fn visit_field_access(&mut self, object: &Expr, field: &str, span: &SourceSpan) -> Option<Type> {
+        if let Some(symbol) = self.variant_access_enum(object) {
+            if symbol.variant(field).is_none() {
+                self.type_error_with_code(
+                    Some(ErrorCode::E2043),
+                    format!("Enum '{}' has no variant '{field}'", symbol.name),
+                    span,
+                );
+            }
+            return Some(Type::Custom(symbol.name));
+        }
+        let object_type = self.visit_expr(object)?;
+        let symbol = match &object_type {
+            Type::Custom(name) => self.symbol_table.lookup_struct(name),
+            _ => None,
+        };
+        let Some(symbol) = symbol else {
+            self.type_error_with_code(
+                Some(ErrorCode::E2034),
+                format!("Cannot access field '{field}' on non-struct type {object_type}"),
+                span,
+            );
+            return None;
+        };
+        let field_type = symbol.field_type(field).cloned();
+        if field_type.is_none() {
+            self.type_error_with_code(
+                Some(ErrorCode::E2034),
+                format!("Struct '{}' has no field '{field}'", symbol.name),
+                span,
+            );
+        }
+        field_type
+    }
+
+    /// Checks an `expr as T` conversion against the promotion matrix. Enums
+    /// convert to integer types through their underlying type.
+    ///
+    /// The cast has the target type even when it is rejected, so that the
+    /// surrounding expression is still checked.
+    fn visit_cast(&mut self, expr: &Expr, target_type: &Type, span: &SourceSpan) -> Option<Type> {
+        let source_type = self.visit_expr(expr)?;
+        if self.is_same_type(&source_type, target_type) {
+            return Some(target_type.clone());
+        }
+        let from_type =
+            if Self::is_integer_type(target_type) { self.storage_type(&source_type) } else { source_type.clone() };
+        let allowed = match (Self::primitive_ir_type(&from_type), Self::primitive_ir_type(target_type)) {
+            (Some(from), Some(to)) => TypePromotionEngine::new().explicit_cast_rule(&from, &to).is_some(),
+            _ => false,
+        };
+        if !allowed {
+            self.type_error_with_code(
+                Some(ErrorCode::E2036),
+                format!("Cannot cast {source_type} to {target_type}"),
+                span,
+            );
+        }
+        Some(target_type.clone())
+    }
+
+    /// Maps a primitive type to its IR counterpart, `None` for arrays, vectors,
+    /// structs, `void` and `nullptr`.
+    const fn primitive_ir_type(ty: &Type) -> Option<IrType> {
+        Some(match ty {
+            Type::I8 => IrType::I8,
+            Type::I16 => IrType::I16,
+            Type::I32 => IrType::I32,
+            Type::I64 => IrType::I64,
+            Type::U8 => IrType::U8,
+            Type::U16 => IrType::U16,
+            Type::U32 => IrType::U32,
+            Type::U64 => IrType::U64,
+            Type::F32 => IrType::F32,
+            Type::F64 => IrType::F64,
+            Type::Char => IrType::Char,
+            Type::String => IrType::String,
+            Type::Bool => IrType::Bool,
+            _ => return None,
+        })
+    }
+
     // Funzione per la promozione automatica dei tipi numerici
     #[inline]
     #[allow(clippy::missing_panics_doc)]
@@ -881,6 +1459,8 @@ impl TypeChecker {
     #[allow(clippy::unnested_or_patterns)]
     pub fn is_assignable(&self, source: &Type, target: &Type) -> bool {
         match (source, target) {
+        // Enums are values, not pointers
+        (Type::NullPtr, Type::Custom(name)) if self.symbol_table.lookup_enum(name).is_some() => false,
         // Numeric promotions
         (Type::I8, Type::I16 | Type::I32 | Type::I64 | Type::F32 | Type::F64)
         | (Type::I16, Type::I32 | Type::I64 | Type::F32 | Type::F64)
@@ -923,6 +1503,21 @@ impl TypeChecker {
         matches!(ty, Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::U8 | Type::U16 | Type::U32 | Type::U64)
     }
 
+    /// The range of values of an integer type.
+    const fn integer_range(ty: &Type) -> Option<(i128, i128)> {
+        Some(match ty {
+            Type::I8 => (i8::MIN as i128, i8::MAX as i128),
+            Type::I16 => (i16::MIN as i128, i16::MAX as i128),
+            Type::I32 => (i32::MIN as i128, i32::MAX as i128),
+            Type::I64 => (i64::MIN as i128, i64::MAX as i128),
+            Type::U8 => (0, u8::MAX as i128),
+            Type::U16 => (0, u16::MAX as i128),
+            Type::U32 => (0, u32::MAX as i128),
+            Type::U64 => (0, u64::MAX as i128),
+            _ => return None,
+        })
+    }
+
     /// Checks if two types are compatible (either can be assigned to the other).
     fn are_compatible(&self, t1: &Type, t2: &Type) -> bool {
         self.is_assignable(t1, t2) || self.is_assignable(t2, t1)
@@ -951,6 +1546,13 @@ impl TypeChecker {
                         return true;
                     }
                 }
+                Stmt::Switch { cases, default: Some(default), .. } => {
+                    if self.function_has_return(default)
+                        && cases.iter().all(|case| self.function_has_return(&case.body))
+                    {
+                        return true;
+                    }
+                }
                 Stmt::While { body: loop_body, .. } | Stmt::For { body: loop_body, .. } => {
                     if self.function_has_return(loop_body) {
                         // Considera solo loop con corpo che ritorna
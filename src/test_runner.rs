@@ -0,0 +1,373 @@
+//! # `.vn` Test Runner
+//!
+//! Runs `.vn` files that state, in line comments, how the compiler must treat
+//! them. Each file goes through the [`Lexer`], the [`JsavParser`], the
+//! [`TypeChecker`] and the [`IrGenerator`], and the outcome is matched against
+//! its annotations:
+//!
+//! | Annotation | Checks |
+//! |------------|--------|
+//! | `// ERROR: E2023` | An error with this code is reported; after code, on this line |
+//! | `// CHECK-IR: add` | The unoptimized IR contains the text, after the previous match |
+//! | `// RUN-RESULT: 0` | `main` exits with this status in the [`Interpreter`] |
+//! | `// RUN-RESULT: total = 42` | The global holds this value after `main`, type suffix optional |
+//! | `// RUN-RESULT: trap E3014` | The program traps with this code |
+//!
+//! A file without `ERROR` annotations must compile without errors. A file with
+//! them must report exactly the annotated errors, and cannot carry the other
+//! annotations. Every mismatch becomes a [`Failure`] whose display shows the
+//! expected (`-`) and the actual (`+`) outcome.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use jsavrs::test_runner::{check_file, collect_tests};
+//!
+//! for path in collect_tests(&[PathBuf::from("tests/vn")])? {
+//!     for failure in check_file(&path) {
+//!         println!("{}: {failure}", path.display());
+//!     }
+//! }
+//! ```
+
+use crate::error::compile_error::CompileError;
+use crate::ir::Module;
+use crate::ir::generator::IrGenerator;
+use crate::ir::interpreter::{Interpreter, RuntimeValue, Trap};
+use crate::lexer::{Lexer, lexer_tokenize_with_errors};
+use crate::parser::jsav_parser::JsavParser;
+use crate::semantic::type_checker::TypeChecker;
+use regex::Regex;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Number of instructions a `RUN-RESULT` test may execute, so that a program
+/// that does not terminate fails instead of hanging the run.
+pub const STEP_LIMIT: u64 = 1_000_000;
+
+static ANNOTATION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"//\s*(ERROR|CHECK-IR|RUN-RESULT):(.*)$").expect("annotation regex pattern is valid"));
+
+/// What a single annotation expects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expectation {
+    /// An error with this code, on `line` when the annotation follows code.
+    Error { code: String, line: Option<usize> },
+    /// Text of the unoptimized IR.
+    CheckIr(String),
+    /// The exit status of `main`.
+    Status(i32),
+    /// The final value of a global.
+    Global { name: String, value: String },
+    /// A trap with this code.
+    Trap(String),
+}
+
+/// An annotation and the line it is on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub line: usize,
+    pub expectation: Expectation,
+}
+
+/// A line of the expected-versus-actual listing of a [`Failure`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Expected and found.
+    Same(String),
+    /// Expected but not found.
+    Expected(String),
+    /// Found but not expected.
+    Actual(String),
+}
+
+impl fmt::Display for DiffLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Same(text) => write!(f, "  {text}"),
+            Self::Expected(text) => write!(f, "- {text}"),
+            Self::Actual(text) => write!(f, "+ {text}"),
+        }
+    }
+}
+
+/// Why a test file failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    /// The file cannot be read.
+    Io(String),
+    /// An annotation that cannot be understood, or that cannot be checked.
+    Annotation { line: usize, message: String },
+    /// The reported errors differ from the `ERROR` annotations.
+    Errors(Vec<DiffLine>),
+    /// A `CHECK-IR` text that is missing from the rest of the IR.
+    CheckIr { line: usize, pattern: String, rest: String },
+    /// A `RUN-RESULT` that does not match the execution.
+    RunResult { line: usize, expected: String, actual: String },
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(message) => write!(f, "cannot read the file: {message}"),
+            Self::Annotation { line, message } => write!(f, "line {line}: {message}"),
+            Self::Errors(diff) => {
+                write!(f, "the reported errors do not match the ERROR annotations")?;
+                diff.iter().try_for_each(|line| write!(f, "\n{line}"))
+            }
+            Self::CheckIr { line, pattern, rest } => {
+                write!(f, "line {line}: CHECK-IR `{pattern}` not found in the rest of the IR:")?;
+                rest.lines().try_for_each(|ir_line| write!(f, "\n| {ir_line}"))
+            }
+            Self::RunResult { line, expected, actual } => {
+                write!(f, "line {line}: RUN-RESULT does not match\n- {expected}\n+ {actual}")
+            }
+        }
+    }
+}
+
+/// Reads the annotations of `source`.
+///
+/// # Errors
+///
+/// Returns a [`Failure::Annotation`] for the first annotation that cannot be
+/// understood.
+pub fn parse_annotations(source: &str) -> Result<Vec<Annotation>, Failure> {
+    let mut annotations = Vec::new();
+    for (index, text) in source.lines().enumerate() {
+        let Some(captures) = ANNOTATION_REGEX.captures(text) else { continue };
+        let line = index + 1;
+        let argument = captures[2].trim();
+        let invalid = |message: &str| Failure::Annotation { line, message: format!("{message}: `{argument}`") };
+        let expectation = match &captures[1] {
+            "ERROR" => {
+                if !is_error_code(argument) {
+                    return Err(invalid("expected an error code such as E2023"));
+                }
+                let after_code = !text[..captures.get(0).map_or(0, |m| m.start())].trim().is_empty();
+                Expectation::Error { code: argument.to_string(), line: after_code.then_some(line) }
+            }
+            "CHECK-IR" if argument.is_empty() => return Err(invalid("expected the IR text to look for")),
+            "CHECK-IR" => Expectation::CheckIr(argument.to_string()),
+            _ => parse_run_result(argument)
+                .ok_or_else(|| invalid("expected an exit status, `name = value` or `trap <code>`"))?,
+        };
+        annotations.push(Annotation { line, expectation });
+    }
+    Ok(annotations)
+}
+
+fn is_error_code(text: &str) -> bool {
+    text.len() == 5 && text.starts_with('E') && text[1..].bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn parse_run_result(argument: &str) -> Option<Expectation> {
+    if let Some(code) = argument.strip_prefix("trap ") {
+        return is_error_code(code.trim()).then(|| Expectation::Trap(code.trim().to_string()));
+    }
+    if let Some((name, value)) = argument.split_once('=') {
+        let (name, value) = (name.trim(), value.trim());
+        return (!name.is_empty() && !value.is_empty())
+            .then(|| Expectation::Global { name: name.to_string(), value: value.to_string() });
+    }
+    argument.parse().ok().map(Expectation::Status)
+}
+
+/// Checks `source` against its annotations; `path` names it in the spans of
+/// the reported errors.
+#[must_use]
+pub fn check_source(path: &str, source: &str) -> Vec<Failure> {
+    let annotations = match parse_annotations(source) {
+        Ok(annotations) => annotations,
+        Err(failure) => return vec![failure],
+    };
+    let (expected_errors, checks): (Vec<_>, Vec<_>) =
+        annotations.iter().partition(|annotation| matches!(annotation.expectation, Expectation::Error { .. }));
+
+    let compiled = compile(path, source);
+    if !expected_errors.is_empty() {
+        if let Some(check) = checks.first() {
+            let message = "CHECK-IR and RUN-RESULT cannot be combined with ERROR annotations".to_string();
+            return vec![Failure::Annotation { line: check.line, message }];
+        }
+        let errors = compiled.err().unwrap_or_default();
+        return match_errors(&expected_errors, &errors).into_iter().collect();
+    }
+    let module = match compiled {
+        Ok(module) => module,
+        Err(errors) => return match_errors(&[], &errors).into_iter().collect(),
+    };
+
+    let mut failures = check_ir(&module, &checks);
+    failures.extend(check_run(&module, &checks));
+    failures
+}
+
+/// Checks the `.vn` file at `path` against its annotations.
+#[must_use]
+pub fn check_file(path: &Path) -> Vec<Failure> {
+    match fs::read_to_string(path) {
+        Ok(source) => check_source(&path.to_string_lossy(), &source),
+        Err(error) => vec![Failure::Io(error.to_string())],
+    }
+}
+
+/// Lists the `.vn` files named by `paths`, searching directories recursively.
+///
+/// Files named directly are kept whatever their extension; the files of each
+/// directory are sorted by path.
+///
+/// # Errors
+///
+/// Returns the error of the first path or directory that cannot be read.
+pub fn collect_tests(paths: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut tests = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            collect_dir(path, &mut tests)?;
+        } else {
+            fs::metadata(path)?;
+            tests.push(path.clone());
+        }
+    }
+    Ok(tests)
+}
+
+fn collect_dir(dir: &Path, tests: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries =
+        fs::read_dir(dir)?.map(|entry| entry.map(|entry| entry.path())).collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_dir(&path, tests)?;
+        } else if path.extension().is_some_and(|ext| ext == "vn") {
+            tests.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Runs the front end and the IR generator on `source`, stopping at the
+/// first phase that reports errors; `path` names it in the spans.
+///
+/// # Errors
+///
+/// Returns the errors of that phase: lexing, parsing, type checking or IR
+/// generation.
+pub fn compile(path: &str, source: &str) -> Result<Module, Vec<CompileError>> {
+    let mut lexer = Lexer::new(path, source);
+    let (tokens, errors) = lexer_tokenize_with_errors(&mut lexer);
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let (statements, errors) = JsavParser::new(&tokens).parse();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let errors = TypeChecker::new().check(&statements);
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let (module, errors) = IrGenerator::new().generate(statements, path);
+    if errors.is_empty() { Ok(module) } else { Err(errors) }
+}
+
+fn describe_error(error: &CompileError) -> String {
+    let code = error.error_code().map_or("error without code", |code| code.code());
+    let line = error.span().map_or(String::new(), |span| format!(" at line {}", span.start.line));
+    format!("{code}{line}: {}", error.message().unwrap_or_default())
+}
+
+/// Pairs the expected errors with the reported ones; annotations bound to a
+/// line are matched first, so that unbound ones do not take their errors.
+fn match_errors(expected: &[&Annotation], errors: &[CompileError]) -> Option<Failure> {
+    let mut unmatched: Vec<Option<&CompileError>> = errors.iter().map(Some).collect();
+    let mut matches = vec![None; expected.len()];
+    for bound in [true, false] {
+        for (index, annotation) in expected.iter().enumerate() {
+            let Expectation::Error { code, line } = &annotation.expectation else { continue };
+            if line.is_some() != bound {
+                continue;
+            }
+            let found = unmatched.iter_mut().find(|error| {
+                error.is_some_and(|error| {
+                    error.error_code().is_some_and(|actual| actual.code() == code)
+                        && line.is_none_or(|line| error.span().is_some_and(|span| span.start.line == line))
+                })
+            });
+            matches[index] = found.and_then(Option::take);
+        }
+    }
+
+    let mut diff = Vec::new();
+    for (annotation, found) in expected.iter().zip(&matches) {
+        let Expectation::Error { code, line } = &annotation.expectation else { continue };
+        diff.push(found.map_or_else(
+            || DiffLine::Expected(line.map_or_else(|| code.clone(), |line| format!("{code} at line {line}"))),
+            |error| DiffLine::Same(describe_error(error)),
+        ));
+    }
+    diff.extend(unmatched.into_iter().flatten().map(|error| DiffLine::Actual(describe_error(error))));
+    diff.iter().any(|line| !matches!(line, DiffLine::Same(_))).then_some(Failure::Errors(diff))
+}
+
+fn check_ir(module: &Module, checks: &[&Annotation]) -> Vec<Failure> {
+    let ir = module.to_string();
+    let mut position = 0;
+    let mut failures = Vec::new();
+    for annotation in checks {
+        let Expectation::CheckIr(pattern) = &annotation.expectation else { continue };
+        match ir[position..].find(pattern.as_str()) {
+            Some(offset) => position += offset + pattern.len(),
+            None => failures.push(Failure::CheckIr {
+                line: annotation.line,
+                pattern: pattern.clone(),
+                rest: ir[position..].to_string(),
+            }),
+        }
+    }
+    failures
+}
+
+fn check_run(module: &Module, checks: &[&Annotation]) -> Vec<Failure> {
+    if checks.iter().all(|annotation| matches!(annotation.expectation, Expectation::CheckIr(_))) {
+        return Vec::new();
+    }
+    let mut interpreter = Interpreter::new(module).with_step_limit(STEP_LIMIT);
+    let result = interpreter.run_main();
+    let describe_trap = |trap: &Trap| format!("trap {}: {}", trap.code.code(), trap.message);
+    let mut failures = Vec::new();
+    for annotation in checks {
+        let (expected, actual) = match (&annotation.expectation, &result) {
+            (Expectation::CheckIr(_), _) => continue,
+            (Expectation::Status(status), Ok(value)) if value.exit_status() == *status => continue,
+            (Expectation::Status(status), Ok(value)) => (status.to_string(), value.exit_status().to_string()),
+            (Expectation::Status(status), Err(trap)) => (status.to_string(), describe_trap(trap)),
+            (Expectation::Global { name, value }, Ok(_)) => match interpreter.global(name) {
+                Some(actual) if value_matches(value, &actual) => continue,
+                Some(actual) => (format!("{name} = {value}"), format!("{name} = {actual}")),
+                None => (format!("{name} = {value}"), format!("no global named `{name}`")),
+            },
+            (Expectation::Global { name, value }, Err(trap)) => (format!("{name} = {value}"), describe_trap(trap)),
+            (Expectation::Trap(code), Err(trap)) if trap.code.code() == code => continue,
+            (Expectation::Trap(code), Err(trap)) => (format!("trap {code}"), describe_trap(trap)),
+            (Expectation::Trap(code), Ok(value)) => {
+                (format!("trap {code}"), format!("exit status {}", value.exit_status()))
+            }
+            (Expectation::Error { .. }, _) => unreachable!("ERROR annotations are checked before running"),
+        };
+        failures.push(Failure::RunResult { line: annotation.line, expected, actual });
+    }
+    failures
+}
+
+/// Whether the printed `actual` value equals `expected`, which may leave out
+/// the type suffix.
+fn value_matches(expected: &str, actual: &RuntimeValue) -> bool {
+    const SUFFIXES: [&str; 10] = ["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64", "f32", "f64"];
+    let printed = actual.to_string();
+    printed == expected || SUFFIXES.iter().any(|suffix| printed.strip_suffix(suffix) == Some(expected))
+}
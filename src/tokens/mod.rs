@@ -8,10 +8,11 @@
 //! - `number`: Numeric literal type definitions
 //! - `token`: Token structure combining kind and location
 //! - `token_kind`: Token type enumeration and classification
-//! - `parsers`: Numeric literal parsing logic
+//! - `parsers`: Literal parsing logic
 //!   - `numeric`: Core decimal number parsing
 //!   - `suffix`: Type suffix detection and routing
 //!   - `base`: Binary, octal, and hexadecimal parsing
+//!   - `escape`: Escape sequence decoding in string and char literals
 //!
 //! ## Phase-specific responsibilities:
 //! * Initialization: Defines all possible token types for the language
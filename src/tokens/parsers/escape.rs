@@ -0,0 +1,173 @@
+// src/tokens/parsers/escape.rs
+//! Escape sequence decoding for string and character literals.
+//!
+//! The lexer captures literal contents verbatim; this module turns them into
+//! the characters they denote. Supported escapes are `\n`, `\t`, `\r`, `\\`,
+//! `\"`, `\'`, `\0`, `\xNN` (ASCII, at most `\x7F`) and `\u{N..}` (one to six
+//! hexadecimal digits naming a Unicode scalar value).
+
+use crate::error::error_code::ErrorCode;
+use std::ops::Range;
+
+/// A malformed escape sequence found while decoding a literal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EscapeError {
+    /// Error code classifying the malformed escape.
+    pub code: ErrorCode,
+    /// Human readable description of the problem.
+    pub message: String,
+    /// Byte range of the offending escape, relative to the literal contents.
+    pub range: Range<usize>,
+}
+
+/// Decodes every escape sequence in the contents of a string or char literal.
+///
+/// Decoding never stops at the first problem: a malformed escape is reported
+/// and copied verbatim into the output, so the caller still obtains a
+/// best-effort value and every error in the literal.
+///
+/// # Arguments
+///
+/// * `content` - The literal contents, without the surrounding quotes
+///
+/// # Returns
+///
+/// The decoded text together with the errors found, in source order.
+///
+/// # Examples
+///
+/// ```
+/// # use jsavrs::tokens::parsers::escape::decode_escapes;
+/// let (text, errors) = decode_escapes(r"a\tb\u{1F600}\x41");
+/// assert_eq!(text, "a\tb\u{1F600}A");
+/// assert!(errors.is_empty());
+/// ```
+#[must_use]
+pub fn decode_escapes(content: &str) -> (String, Vec<EscapeError>) {
+    let mut out = String::with_capacity(content.len());
+    let mut errors = Vec::new();
+    let mut chars = content.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        let Some((_, kind)) = chars.next() else {
+            // The token regexes never end a literal on a lone backslash.
+            out.push('\\');
+            break;
+        };
+        let simple = match kind {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            '\\' => Some('\\'),
+            '"' => Some('"'),
+            '\'' => Some('\''),
+            '0' => Some('\0'),
+            _ => None,
+        };
+        if let Some(decoded) = simple {
+            out.push(decoded);
+            continue;
+        }
+
+        let result = match kind {
+            'x' => decode_hex(content, start, &mut chars),
+            'u' => decode_unicode(content, start, &mut chars),
+            _ => {
+                let end = start + 1 + kind.len_utf8();
+                Err(EscapeError {
+                    code: ErrorCode::E0007,
+                    message: format!("Unknown escape sequence '{}'", &content[start..end]),
+                    range: start..end,
+                })
+            }
+        };
+        match result {
+            Ok(decoded) => out.push(decoded),
+            Err(error) => {
+                out.push_str(&content[error.range.clone()]);
+                errors.push(error);
+            }
+        }
+    }
+
+    (out, errors)
+}
+
+type CharIter<'a> = std::iter::Peekable<std::str::CharIndices<'a>>;
+
+/// Byte offset just past whatever `chars` has consumed so far.
+fn position(content: &str, chars: &mut CharIter) -> usize {
+    chars.peek().map_or(content.len(), |&(i, _)| i)
+}
+
+/// Decodes `\xNN` once the `\x` prefix at `start` has been consumed.
+fn decode_hex(content: &str, start: usize, chars: &mut CharIter) -> Result<char, EscapeError> {
+    let mut value = 0u8;
+    for _ in 0..2 {
+        match chars.peek() {
+            Some(&(_, d)) if d.is_ascii_hexdigit() => {
+                value = value * 16 + d.to_digit(16).and_then(|v| u8::try_from(v).ok()).unwrap_or(0);
+                chars.next();
+            }
+            _ => {
+                let end = position(content, chars);
+                return Err(EscapeError {
+                    code: ErrorCode::E0011,
+                    message: format!(
+                        "Invalid hexadecimal escape '{}': expected exactly two hexadecimal digits",
+                        &content[start..end]
+                    ),
+                    range: start..end,
+                });
+            }
+        }
+    }
+    let end = position(content, chars);
+    if value > 0x7F {
+        return Err(EscapeError {
+            code: ErrorCode::E0011,
+            message: format!(
+                "Hexadecimal escape '{}' is out of range: must be at most \\x7F, use \\u{{{value:X}}} instead",
+                &content[start..end]
+            ),
+            range: start..end,
+        });
+    }
+    Ok(char::from(value))
+}
+
+/// Decodes `\u{N..}` once the `\u` prefix at `start` has been consumed.
+fn decode_unicode(content: &str, start: usize, chars: &mut CharIter) -> Result<char, EscapeError> {
+    let error = |end: usize, reason: &str| EscapeError {
+        code: ErrorCode::E0012,
+        message: format!("Invalid unicode escape '{}': {reason}", &content[start..end]),
+        range: start..end,
+    };
+
+    if chars.next_if(|&(_, c)| c == '{').is_none() {
+        return Err(error(position(content, chars), "expected '{' after \\u"));
+    }
+    let digits_start = position(content, chars);
+    while chars.next_if(|&(_, c)| c.is_ascii_hexdigit()).is_some() {}
+    let digits_end = position(content, chars);
+    if chars.next_if(|&(_, c)| c == '}').is_none() {
+        return Err(error(digits_end, "missing closing '}'"));
+    }
+    let end = position(content, chars);
+
+    let digits = &content[digits_start..digits_end];
+    if digits.is_empty() {
+        return Err(error(end, "expected at least one hexadecimal digit"));
+    }
+    if digits.len() > 6 {
+        return Err(error(end, "at most six hexadecimal digits are allowed"));
+    }
+    u32::from_str_radix(digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| error(end, "not a valid Unicode scalar value"))
+}
@@ -1,19 +1,23 @@
 // src/tokens/parsers/mod.rs
-//! Numeric literal parsing modules.
+//! Literal parsing modules.
 //!
 //! This module contains all parsing logic for converting string representations
-//! of numeric literals into structured `Number` types during lexical analysis.
+//! of numeric literals into structured `Number` types during lexical analysis,
+//! and for decoding escape sequences in string and character literals.
 //!
 //! # Overview
 //!
 //! The parsers module provides three complementary submodules for parsing numeric
-//! literals: base detection, suffix parsing, and numeric value extraction.
+//! literals: base detection, suffix parsing, and numeric value extraction. A fourth
+//! submodule decodes the escapes of textual literals.
 //!
 //! # Submodules
 //!
 //! - [`numeric`]: Core numeric literal parsing logic
 //! - [`suffix`]: Type suffix parsing (e.g., `u32`, `f64`)
 //! - [`base`]: Numeric base detection (binary, octal, decimal, hexadecimal)
+//! - [`escape`]: Escape sequence decoding for string and character literals
 pub mod base;
+pub mod escape;
 pub mod numeric;
 pub mod suffix;
@@ -113,6 +113,20 @@ pub enum TokenKind {
     KeywordBreak,
     #[token("continue")]
     KeywordContinue,
+    #[token("struct")]
+    KeywordStruct,
+    #[token("as")]
+    KeywordAs,
+    #[token("import")]
+    KeywordImport,
+    #[token("switch")]
+    KeywordSwitch,
+    #[token("case")]
+    KeywordCase,
+    #[token("default")]
+    KeywordDefault,
+    #[token("enum")]
+    KeywordEnum,
 
     // Boolean literals (captures value)
     #[token("false", |_| false)]
@@ -148,12 +162,14 @@ pub enum TokenKind {
     #[regex(r"#x[0-9a-fA-F]+[uU]?", parse_hex, priority = 2)]
     Hexadecimal(Number),
 
-    /// String literals (captures content without quotes)
+    /// String literals (captures content without quotes; escapes are decoded
+    /// by the lexer)
     #[regex(r#""([^"\\]|\\.)*""#, |lex| Arc::from(&lex.slice()[1..lex.slice().len()-1]))]
     StringLiteral(Arc<str>),
 
-    /// Character literals (captures content without quotes)
-    #[regex(r#"'([^'\\]|\\.)'"#, |lex| {
+    /// Character literals (captures content without quotes; the lexer checks
+    /// that it decodes to exactly one character)
+    #[regex(r#"'([^'\\\n]|\\[^\n])+'"#, |lex| {
         let s = lex.slice();
         Arc::from(&s[1..s.len()-1])
     })]
@@ -293,6 +309,13 @@ impl fmt::Display for TokenKind {
             Self::KeywordNullptr => f.write_str("'nullptr'"),
             Self::KeywordBreak => f.write_str("'break'"),
             Self::KeywordContinue => f.write_str("'continue'"),
+            Self::KeywordStruct => f.write_str("'struct'"),
+            Self::KeywordAs => f.write_str("'as'"),
+            Self::KeywordImport => f.write_str("'import'"),
+            Self::KeywordSwitch => f.write_str("'switch'"),
+            Self::KeywordCase => f.write_str("'case'"),
+            Self::KeywordDefault => f.write_str("'default'"),
+            Self::KeywordEnum => f.write_str("'enum'"),
             Self::KeywordBool(b) => write!(f, "boolean '{b}'"),
 
             // Identifiers
@@ -294,6 +294,16 @@ pub fn assign_expr(target: Expr, value: Expr) -> Expr {
     Expr::Assign { target: Box::new(target), value: Box::new(value), span: dummy_span() }
 }
 
+#[must_use]
+pub fn compound_assign_expr(target: Expr, op: BinaryOp, value: Expr) -> Expr {
+    Expr::CompoundAssign { target: Box::new(target), op, value: Box::new(value), span: dummy_span() }
+}
+
+#[must_use]
+pub fn update_expr(target: Expr, op: BinaryOp, prefix: bool) -> Expr {
+    Expr::Update { target: Box::new(target), op, prefix, span: dummy_span() }
+}
+
 #[must_use]
 pub fn variable_expr(name: &str) -> Expr {
     Expr::Variable { name: name.into(), span: dummy_span() }
@@ -325,6 +335,25 @@ pub fn array_access_expr(array: Expr, index: Expr) -> Expr {
     Expr::ArrayAccess { array: Box::new(array), index: Box::new(index), span: dummy_span() }
 }
 
+// Generic helper function to create field access expressions
+#[must_use]
+pub fn field_access_expr(object: Expr, field: &str) -> Expr {
+    Expr::FieldAccess { object: Box::new(object), field: field.into(), span: dummy_span() }
+}
+
+// Generic helper function to create struct literal expressions
+#[must_use]
+pub fn struct_literal_expr(name: &str, fields: Vec<(&str, Expr)>) -> Expr {
+    let fields = fields.into_iter().map(|(field, value)| (field.into(), value)).collect();
+    Expr::StructLiteral { name: name.into(), fields, span: dummy_span() }
+}
+
+// Generic helper function to create cast expressions
+#[must_use]
+pub fn cast_expr(expr: Expr, target_type: Type) -> Expr {
+    Expr::Cast { expr: Box::new(expr), target_type, span: dummy_span() }
+}
+
 // Generic helper function to create tokens from token kinds
 #[must_use]
 pub fn create_tokens(kinds: Vec<TokenKind>) -> Vec<Token> {
@@ -741,7 +741,7 @@ fn test_variadic_info_display() {
 #[test]
 fn test_variadic_info_display_windows() {
     let windows_info = Abi::WINDOWS.variadic_info();
-    let display = format!("{windows_info}",);
+    let display = format!("{windows_info}");
 
     assert!(display.contains("supported: true"));
     assert!(display.contains("requires_va_list: true"));
@@ -225,6 +225,13 @@ fn test_data_directive_display_ascii() {
     assert_eq!(display, "db \"Hello\"");
 }
 
+#[test]
+fn test_data_directive_display_label_offsets() {
+    let directive = DataDirective::LabelOffsets(vec!["f.case_1".to_string(), "f.default".to_string()]);
+    let display = format!("{directive}");
+    assert_eq!(display, "dd f.case_1 - $, f.default - $");
+}
+
 #[test]
 fn test_data_directive_display_resb() {
     let directive = DataDirective::Resb(256);
@@ -310,11 +317,16 @@ fn test_data_directive_single_elements() {
 fn test_data_directive_with_special_chars_in_string() {
     let directive = DataDirective::Ascii("Hello\nWorld\t\"Test\"".to_string());
     let display = format!("{directive}");
-    assert_eq!(display, "db \"Hello\\\\nWorld\\\\t\\\"Test\\\"\"");
+    assert_eq!(display, "db `Hello\\nWorld\\t\"Test\"`");
 
+    // NASM takes double-quoted strings verbatim, so a lone backslash stays as is
     let directive2 = DataDirective::new_asciz("Test\\Backslash");
     let display2 = format!("{directive2}");
-    assert_eq!(display2, "db \"Test\\\\Backslash\", 0");
+    assert_eq!(display2, "db \"Test\\Backslash\", 0");
+
+    let directive3 = DataDirective::new_asciz("tick`\\\r\u{1}é");
+    let display3 = format!("{directive3}");
+    assert_eq!(display3, "db `tick\\`\\\\\\r\\x01\\xc3\\xa9`, 0");
 }
 
 #[test]
@@ -244,11 +244,12 @@ fn test_assembly_section_empty() {
 }
 
 #[test]
-fn test_escape_string_function() {
-    // Test the internal escape_string function behavior through the Ascii directive
+fn test_quote_string_function() {
+    // Test the internal quote_string function behavior through the Ascii directive
     let directive = DataDirective::Ascii("Hello\nWorld\t\"Test\"\\Backslash".to_string());
     let display = format!("{directive}");
-    assert!(display.contains("Hello\\\\nWorld"));
-    assert!(display.contains("\\\\t\\\"Test\\\""));
+    assert!(display.starts_with("db `"));
+    assert!(display.contains("Hello\\nWorld"));
+    assert!(display.contains("\\t\"Test\""));
     assert!(display.contains("\\\\Backslash"));
 }
@@ -0,0 +1,156 @@
+use jsavrs::asm::{
+    Abi, AssemblyFile, DataDirective, GPRegister64, Immediate, Instruction, ObjectFile, Operand, RelocationKind,
+    Section, SymbolDefinition,
+};
+use jsavrs::error::error_code::ErrorCode;
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// `main` loads a string, calls a local and an external function and loops.
+fn sample_file() -> AssemblyFile {
+    let mut file = AssemblyFile::new(Abi::SYSTEM_V_LINUX);
+    file.data_sec_add_data("str.0", DataDirective::new_asciz("hi"));
+    file.data_sec_add_data("str.0.len", DataDirective::new_equ_length_of("str.0"));
+    file.text_sec_add_global_label("main");
+    file.text_sec_add_label("helper");
+    file.text_sec_add_instruction(Instruction::Ret);
+    file.text_sec_add_label("main");
+    file.text_sec_add_instruction(Instruction::Mov {
+        dest: Operand::reg64(GPRegister64::Rdi),
+        src: Operand::label("str.0"),
+    });
+    file.text_sec_add_instruction(Instruction::Call { target: Operand::label("helper") });
+    file.text_sec_add_instruction(Instruction::Call { target: Operand::label("puts") });
+    file.text_sec_add_instruction(Instruction::Jmp { target: Operand::label("main") });
+    file
+}
+
+#[test]
+fn test_assembles_sections_and_symbols() {
+    let object = ObjectFile::from_assembly(&sample_file()).unwrap();
+
+    assert_eq!(object.section(&Section::Data).unwrap().bytes, b"hi\0");
+    assert_eq!(object.section(&Section::Text).unwrap().size, 1 + 7 + 5 + 5 + 5);
+
+    let main = object.symbol("main").unwrap();
+    assert_eq!(main.definition, SymbolDefinition::Section(Section::Text));
+    assert_eq!(main.value, 1);
+    assert!(main.global);
+    assert!(!object.symbol("helper").unwrap().global);
+
+    let len = object.symbol("str.0.len").unwrap();
+    assert_eq!(len.definition, SymbolDefinition::Absolute);
+    assert_eq!(len.value, 3);
+
+    let puts = object.symbol("puts").unwrap();
+    assert_eq!(puts.definition, SymbolDefinition::Undefined);
+    assert!(puts.global);
+}
+
+#[test]
+fn test_resolves_text_labels_and_relocates_the_rest() {
+    let object = ObjectFile::from_assembly(&sample_file()).unwrap();
+    let text = &object.section(&Section::Text).unwrap().bytes;
+
+    // `call helper` at 8 targets offset 0: 0 - (8 + 5) = -13.
+    assert_eq!(&text[8..13], &[0xE8, 0xF3, 0xFF, 0xFF, 0xFF]);
+    // `jmp main` at 18 targets offset 1: 1 - (18 + 5) = -22.
+    assert_eq!(&text[18..23], &[0xE9, 0xEA, 0xFF, 0xFF, 0xFF]);
+
+    let relocations = object.relocations();
+    assert_eq!(relocations.len(), 2);
+    assert_eq!(relocations[0].symbol, "str.0");
+    assert_eq!(relocations[0].offset, 4);
+    assert_eq!(relocations[0].kind, RelocationKind::Pc32);
+    assert_eq!(relocations[0].addend, -4);
+    assert_eq!(relocations[1].symbol, "puts");
+    assert_eq!(relocations[1].offset, 14);
+    assert_eq!(relocations[1].kind, RelocationKind::Plt32);
+}
+
+#[test]
+fn test_writes_elf64_relocatable_header() {
+    let bytes = ObjectFile::from_assembly(&sample_file()).unwrap().to_elf_bytes();
+
+    assert_eq!(&bytes[..7], &[0x7F, b'E', b'L', b'F', 2, 1, 1]);
+    assert_eq!(u16_at(&bytes, 16), 1, "ET_REL");
+    assert_eq!(u16_at(&bytes, 18), 62, "EM_X86_64");
+
+    let shoff = usize::try_from(u64_at(&bytes, 40)).unwrap();
+    let shnum = usize::from(u16_at(&bytes, 60));
+    assert_eq!(bytes.len(), shoff + shnum * 64);
+    assert!(usize::from(u16_at(&bytes, 62)) < shnum);
+}
+
+#[test]
+fn test_reserves_bss_space_without_file_contents() {
+    let mut file = AssemblyFile::new(Abi::SYSTEM_V_LINUX);
+    file.bss_sec_add_data("buffer", DataDirective::Resq(4));
+    file.data_sec_add_data("counter", DataDirective::Dq(vec![7]));
+    file.data_sec_add_data("pad", DataDirective::Resb(3));
+    let object = ObjectFile::from_assembly(&file).unwrap();
+
+    let bss = object.section(&Section::Bss).unwrap();
+    assert!(bss.bytes.is_empty());
+    assert_eq!(bss.size, 32);
+    assert_eq!(object.section(&Section::Data).unwrap().bytes, [7, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(object.symbol("pad").unwrap().value, 8);
+}
+
+#[test]
+fn test_initialized_data_in_bss_is_rejected() {
+    let mut file = AssemblyFile::new(Abi::SYSTEM_V_LINUX);
+    file.bss_sec_add_data("value", DataDirective::Dd(vec![1]));
+
+    let error = ObjectFile::from_assembly(&file).unwrap_err();
+    assert!(error.message().unwrap().contains("initialized data"));
+}
+
+#[test]
+fn test_label_offset_tables_are_resolved_within_their_section() {
+    let mut file = AssemblyFile::new(Abi::SYSTEM_V_LINUX);
+    file.text_sec_add_label("first");
+    file.text_sec_add_instruction(Instruction::Ret);
+    file.text_sec_add_data("table", DataDirective::LabelOffsets(vec!["second".into(), "first".into()]));
+    file.text_sec_add_label("second");
+    file.text_sec_add_instruction(Instruction::Ret);
+
+    let object = ObjectFile::from_assembly(&file).unwrap();
+    let text = &object.section(&Section::Text).unwrap().bytes;
+    // The table starts at 1; `second` follows it at 9 and `first` is at 0.
+    assert_eq!(&text[1..9], &[8, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF]);
+    assert!(object.relocations().is_empty());
+
+    let mut file = AssemblyFile::new(Abi::SYSTEM_V_LINUX);
+    file.text_sec_add_data("table", DataDirective::LabelOffsets(vec!["elsewhere".into()]));
+    file.data_sec_add_data("elsewhere", DataDirective::Db(vec![0]));
+    let error = ObjectFile::from_assembly(&file).unwrap_err();
+    assert!(error.message().unwrap().contains("`elsewhere`"));
+}
+
+#[test]
+fn test_duplicate_labels_are_rejected() {
+    let mut file = AssemblyFile::new(Abi::SYSTEM_V_LINUX);
+    file.text_sec_add_label("main");
+    file.text_sec_add_instruction(Instruction::Ret);
+    file.text_sec_add_label("main");
+
+    let error = ObjectFile::from_assembly(&file).unwrap_err();
+    assert_eq!(error.error_code(), Some(&ErrorCode::E4001));
+    assert!(error.message().unwrap().contains("`main`"));
+}
+
+#[test]
+fn test_unencodable_instructions_are_reported() {
+    let mut file = AssemblyFile::new(Abi::SYSTEM_V_LINUX);
+    file.text_sec_add_instruction(Instruction::Fld { src: Operand::Immediate(Immediate::Imm8(0)) });
+
+    let error = ObjectFile::from_assembly(&file).unwrap_err();
+    assert_eq!(error.error_code(), Some(&ErrorCode::E4001));
+}
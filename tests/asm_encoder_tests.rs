@@ -0,0 +1,199 @@
+use jsavrs::asm::{
+    EncodedInstruction, GPRegister8, GPRegister32, GPRegister64, Instruction, LabelFixup, MemoryOperand, Operand,
+    RelocationKind, XMMRegister, encode_instruction,
+};
+use jsavrs::error::compile_error::CompileError;
+use jsavrs::error::error_code::ErrorCode;
+
+fn bytes(instr: &Instruction) -> Vec<u8> {
+    encode_instruction(instr).unwrap_or_else(|e| panic!("{instr}: {e}")).bytes
+}
+
+fn encoded(instr: &Instruction) -> EncodedInstruction {
+    encode_instruction(instr).unwrap()
+}
+
+fn encode_error(instr: &Instruction) -> CompileError {
+    encode_instruction(instr).unwrap_err()
+}
+
+const RAX: Operand = Operand::reg64(GPRegister64::Rax);
+const RCX: Operand = Operand::reg64(GPRegister64::Rcx);
+const RBP: Operand = Operand::reg64(GPRegister64::Rbp);
+const RSP: Operand = Operand::reg64(GPRegister64::Rsp);
+const R12: Operand = Operand::reg64(GPRegister64::R12);
+const R15: Operand = Operand::reg64(GPRegister64::R15);
+const EAX: Operand = Operand::reg32(GPRegister32::Eax);
+const AL: Operand = Operand::reg8(GPRegister8::Al);
+const XMM0: Operand = Operand::xmm(XMMRegister::Xmm0);
+const XMM1: Operand = Operand::xmm(XMMRegister::Xmm1);
+const XMM8: Operand = Operand::xmm(XMMRegister::Xmm8);
+
+const fn mem(base: GPRegister64, disp: i32, size: usize) -> Operand {
+    Operand::Memory(MemoryOperand::new(Some(base)).with_displacement(disp).with_size(size))
+}
+
+#[test]
+fn test_encodes_alu_register_forms() {
+    assert_eq!(bytes(&Instruction::Add { dest: RAX, src: RCX }), [0x48, 0x01, 0xC8]);
+    assert_eq!(bytes(&Instruction::Xor { dest: EAX, src: EAX }), [0x31, 0xC0]);
+    assert_eq!(bytes(&Instruction::Cmp { op1: RAX, op2: R12 }), [0x4C, 0x39, 0xE0]);
+    assert_eq!(bytes(&Instruction::Sub { dest: RAX, src: mem(GPRegister64::Rbp, -8, 8) }), [0x48, 0x2B, 0x45, 0xF8]);
+}
+
+#[test]
+fn test_encodes_alu_immediates_with_shortest_form() {
+    let r8d = Operand::reg32(GPRegister32::R8d);
+    assert_eq!(bytes(&Instruction::Add { dest: r8d, src: Operand::imm8(1) }), [0x41, 0x83, 0xC0, 0x01]);
+    assert_eq!(
+        bytes(&Instruction::Sub { dest: RSP, src: Operand::imm32(256) }),
+        [0x48, 0x81, 0xEC, 0x00, 0x01, 0x00, 0x00]
+    );
+    assert_eq!(bytes(&Instruction::Cmp { op1: AL, op2: Operand::imm8(5) }), [0x80, 0xF8, 0x05]);
+    assert_eq!(bytes(&Instruction::Test { op1: RAX, op2: RAX }), [0x48, 0x85, 0xC0]);
+}
+
+#[test]
+fn test_encodes_memory_addressing_modes() {
+    let load = |src| bytes(&Instruction::Mov { dest: RAX, src });
+    assert_eq!(load(mem(GPRegister64::Rbp, -8, 8)), [0x48, 0x8B, 0x45, 0xF8]);
+    assert_eq!(load(mem(GPRegister64::R13, 0, 8)), [0x49, 0x8B, 0x45, 0x00]);
+    assert_eq!(load(mem(GPRegister64::R12, 0, 8)), [0x49, 0x8B, 0x04, 0x24]);
+    assert_eq!(load(mem(GPRegister64::Rbx, 0x100, 8)), [0x48, 0x8B, 0x83, 0x00, 0x01, 0x00, 0x00]);
+    let indexed = MemoryOperand::new(Some(GPRegister64::Rbx)).with_index(GPRegister64::Rcx, 8).with_displacement(16);
+    assert_eq!(load(Operand::Memory(indexed)), [0x48, 0x8B, 0x44, 0xCB, 0x10]);
+    assert_eq!(
+        bytes(&Instruction::Mov { dest: mem(GPRegister64::Rsp, 8, 8), src: Operand::reg64(GPRegister64::Rdi) }),
+        [0x48, 0x89, 0x7C, 0x24, 0x08]
+    );
+}
+
+#[test]
+fn test_encodes_mov_immediates() {
+    assert_eq!(bytes(&Instruction::Mov { dest: RAX, src: Operand::imm64(0) }), [0x48, 0xC7, 0xC0, 0, 0, 0, 0]);
+    assert_eq!(
+        bytes(&Instruction::Mov { dest: RAX, src: Operand::imm64(0xFFFF_FFFF) }),
+        [0xB8, 0xFF, 0xFF, 0xFF, 0xFF]
+    );
+    assert_eq!(
+        bytes(&Instruction::Mov { dest: R15, src: Operand::imm64(0x1_2345_6789) }),
+        [0x49, 0xBF, 0x89, 0x67, 0x45, 0x23, 0x01, 0x00, 0x00, 0x00]
+    );
+    assert_eq!(
+        bytes(&Instruction::Mov { dest: mem(GPRegister64::Rbp, -4, 4), src: Operand::imm32(7) }),
+        [0xC7, 0x45, 0xFC, 0x07, 0x00, 0x00, 0x00]
+    );
+    assert_eq!(
+        bytes(&Instruction::Mov { dest: Operand::reg8(GPRegister8::Sil), src: Operand::imm8(1) }),
+        [0x40, 0xB6, 0x01]
+    );
+}
+
+#[test]
+fn test_encodes_extensions_and_lea() {
+    assert_eq!(bytes(&Instruction::Movzx { dest: EAX, src: mem(GPRegister64::Rdi, 0, 1) }), [0x0F, 0xB6, 0x07]);
+    assert_eq!(bytes(&Instruction::Movsx { dest: RAX, src: AL }), [0x48, 0x0F, 0xBE, 0xC0]);
+    assert_eq!(bytes(&Instruction::Movsxd { dest: RAX, src: Operand::reg32(GPRegister32::Ecx) }), [0x48, 0x63, 0xC1]);
+    assert_eq!(bytes(&Instruction::Lea { dest: RSP, src: mem(GPRegister64::Rbp, -40, 8) }), [0x48, 0x8D, 0x65, 0xD8]);
+}
+
+#[test]
+fn test_encodes_stack_and_arithmetic_groups() {
+    assert_eq!(bytes(&Instruction::Push { src: RBP }), [0x55]);
+    assert_eq!(bytes(&Instruction::Push { src: R12 }), [0x41, 0x54]);
+    assert_eq!(bytes(&Instruction::Pop { dest: R15 }), [0x41, 0x5F]);
+    assert_eq!(bytes(&Instruction::Idiv { src: RCX }), [0x48, 0xF7, 0xF9]);
+    assert_eq!(bytes(&Instruction::Neg { dest: EAX }), [0xF7, 0xD8]);
+    assert_eq!(bytes(&Instruction::Imul { dest: Some(RAX), src1: RCX, src2: None }), [0x48, 0x0F, 0xAF, 0xC1]);
+    assert_eq!(
+        bytes(&Instruction::Imul { dest: Some(RAX), src1: RCX, src2: Some(Operand::imm8(10)) }),
+        [0x48, 0x6B, 0xC1, 0x0A]
+    );
+}
+
+#[test]
+fn test_encodes_shifts() {
+    assert_eq!(bytes(&Instruction::Shl { dest: RAX, count: Operand::imm8(3) }), [0x48, 0xC1, 0xE0, 0x03]);
+    assert_eq!(
+        bytes(&Instruction::Sar { dest: Operand::reg64(GPRegister64::Rdx), count: Operand::reg8(GPRegister8::Cl) }),
+        [0x48, 0xD3, 0xFA]
+    );
+    assert_eq!(bytes(&Instruction::Shr { dest: EAX, count: Operand::imm8(1) }), [0xD1, 0xE8]);
+}
+
+#[test]
+fn test_encodes_condition_code_instructions() {
+    assert_eq!(bytes(&Instruction::Sete { dest: AL }), [0x0F, 0x94, 0xC0]);
+    assert_eq!(bytes(&Instruction::Setl { dest: Operand::reg8(GPRegister8::Dil) }), [0x40, 0x0F, 0x9C, 0xC7]);
+    assert_eq!(bytes(&Instruction::Cmovl { dest: RAX, src: RCX }), [0x48, 0x0F, 0x4C, 0xC1]);
+}
+
+#[test]
+fn test_label_references_produce_fixups() {
+    let jmp = encoded(&Instruction::Jmp { target: Operand::label("main.exit") });
+    assert_eq!(jmp.bytes, [0xE9, 0, 0, 0, 0]);
+    assert_eq!(
+        jmp.fixup,
+        Some(LabelFixup { offset: 1, symbol: "main.exit".to_string(), kind: RelocationKind::Pc32, addend: -4 })
+    );
+
+    let jne = encoded(&Instruction::Jne { target: Operand::label("l") });
+    assert_eq!(jne.bytes, [0x0F, 0x85, 0, 0, 0, 0]);
+    assert_eq!(jne.fixup.unwrap().offset, 2);
+
+    let call = encoded(&Instruction::Call { target: Operand::label("printf") });
+    assert_eq!(call.bytes, [0xE8, 0, 0, 0, 0]);
+    assert_eq!(call.fixup.unwrap().kind, RelocationKind::Plt32);
+
+    let address = encoded(&Instruction::Mov { dest: RAX, src: Operand::label("str.0") });
+    assert_eq!(address.bytes, [0x48, 0x8D, 0x05, 0, 0, 0, 0]);
+    assert_eq!(
+        address.fixup,
+        Some(LabelFixup { offset: 3, symbol: "str.0".to_string(), kind: RelocationKind::Pc32, addend: -4 })
+    );
+}
+
+#[test]
+fn test_encodes_indirect_calls_and_fixed_instructions() {
+    assert_eq!(bytes(&Instruction::Call { target: Operand::reg64(GPRegister64::R11) }), [0x41, 0xFF, 0xD3]);
+    assert_eq!(bytes(&Instruction::Ret), [0xC3]);
+    assert_eq!(bytes(&Instruction::Cqo), [0x48, 0x99]);
+    assert_eq!(bytes(&Instruction::Syscall), [0x0F, 0x05]);
+    assert!(encode_instruction(&Instruction::Ret).unwrap().fixup.is_none());
+}
+
+#[test]
+fn test_encodes_sse_instructions() {
+    assert_eq!(bytes(&Instruction::Addsd { dest: XMM0, src: XMM1 }), [0xF2, 0x0F, 0x58, 0xC1]);
+    assert_eq!(bytes(&Instruction::Movaps { dest: XMM0, src: XMM8 }), [0x41, 0x0F, 0x28, 0xC0]);
+    assert_eq!(
+        bytes(&Instruction::Movsd { dest: XMM8, src: mem(GPRegister64::Rsp, 16, 8) }),
+        [0xF2, 0x44, 0x0F, 0x10, 0x44, 0x24, 0x10]
+    );
+    assert_eq!(bytes(&Instruction::Movq { dest: XMM8, src: RAX }), [0x66, 0x4C, 0x0F, 0x6E, 0xC0]);
+    assert_eq!(bytes(&Instruction::Movq { dest: RAX, src: XMM8 }), [0x66, 0x4C, 0x0F, 0x7E, 0xC0]);
+    assert_eq!(bytes(&Instruction::Cvtsi2sd { dest: XMM0, src: RAX }), [0xF2, 0x48, 0x0F, 0x2A, 0xC0]);
+    assert_eq!(bytes(&Instruction::Cvttsd2si { dest: EAX, src: XMM1 }), [0xF2, 0x0F, 0x2C, 0xC1]);
+    assert_eq!(bytes(&Instruction::Ucomisd { op1: XMM0, op2: XMM1 }), [0x66, 0x0F, 0x2E, 0xC1]);
+}
+
+#[test]
+fn test_rejects_unencodable_operands() {
+    let error =
+        encode_error(&Instruction::Mov { dest: Operand::reg8(GPRegister8::Ah), src: Operand::reg8(GPRegister8::Sil) });
+    assert_eq!(error.error_code(), Some(&ErrorCode::E4001));
+    assert!(error.message().unwrap().contains("REX"));
+
+    let rsp_index = MemoryOperand::new(Some(GPRegister64::Rax)).with_index(GPRegister64::Rsp, 1);
+    let error = encode_error(&Instruction::Mov { dest: RAX, src: Operand::Memory(rsp_index) });
+    assert!(error.message().unwrap().contains("index"));
+}
+
+#[test]
+fn test_rejects_unsupported_instructions() {
+    let error = encode_error(&Instruction::Vaddps { dest: XMM0, src1: XMM0, src2: XMM1 });
+
+    assert_eq!(error.error_code(), Some(&ErrorCode::E4001));
+    assert!(error.message().unwrap().contains("cannot encode"));
+    assert!(error.message().unwrap().contains("vaddps"));
+}
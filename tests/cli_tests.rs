@@ -1,7 +1,9 @@
 // tests/cli_tests.rs
 use clap::Parser;
 use clap::error::ErrorKind;
-use jsavrs::cli::Args;
+use jsavrs::cli::{Args, Command, EmitKind};
+use jsavrs::error::error_reporter::ErrorFormat;
+use jsavrs::ir::optimizer::OptLevel;
 use predicates::prelude::*;
 use std::path::PathBuf;
 
@@ -82,6 +84,7 @@ fn valid_file_with_short_flags() {
 
     // Cleanup
     std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(path.with_extension("asm")).unwrap();
 }
 
 #[test]
@@ -97,6 +100,7 @@ fn verbose_flag_works() {
 
     // Cleanup
     std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(path.with_extension("asm")).unwrap();
 }
 
 #[test]
@@ -111,6 +115,7 @@ fn test_relative_path_input() {
 
     // Cleanup
     std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(path.with_extension("asm")).unwrap();
 }
 
 #[test]
@@ -126,6 +131,7 @@ fn long_flag_works() {
 
     // Cleanup
     std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(path.with_extension("asm")).unwrap();
 }
 
 #[test]
@@ -141,6 +147,7 @@ fn long_verbose_flag_works() {
 
     // Cleanup
     std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(path.with_extension("asm")).unwrap();
 }
 
 #[test]
@@ -157,6 +164,7 @@ fn multiple_combinations_of_flags() {
 
     // Cleanup
     std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(path.with_extension("asm")).unwrap();
 }
 
 #[test]
@@ -264,6 +272,7 @@ fn test_empty_input_file() {
 
     // Cleanup
     std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(path.with_extension("asm")).unwrap();
 }
 
 #[test]
@@ -279,6 +288,7 @@ fn test_file_with_spaces_in_name() {
 
     // Cleanup
     std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(path.with_extension("asm")).unwrap();
 }
 
 #[test]
@@ -294,6 +304,7 @@ fn test_absolute_path_input() {
 
     // Cleanup
     std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(path.with_extension("asm")).unwrap();
 }
 
 #[test]
@@ -318,3 +329,246 @@ fn test_all_flag_combinations() {
     assert_eq!(args4.input, PathBuf::from("test.vn"));
     assert!(args4.verbose);
 }
+
+#[test]
+fn test_parse_output_and_emit_options() {
+    let args = Args::try_parse_from(["jsavrs", "-i", "test.vn", "-o", "out.s", "--emit", "tokens"]).unwrap();
+    assert_eq!(args.output, Some(PathBuf::from("out.s")));
+    assert_eq!(args.emit, EmitKind::Tokens);
+    assert_eq!(args.output_path(), PathBuf::from("out.s"));
+
+    let args = Args::try_parse_from(["jsavrs", "-i", "test.vn", "--output=a.nir", "--emit=ir"]).unwrap();
+    assert_eq!(args.output_path(), PathBuf::from("a.nir"));
+}
+
+#[test]
+fn test_parse_optimization_options() {
+    let args = Args::try_parse_from(["jsavrs", "-i", "test.vn"]).unwrap();
+    assert_eq!(args.opt_level, OptLevel::O1);
+    assert!(args.disable_pass.is_empty());
+    assert!(!args.time_passes);
+
+    let args =
+        Args::try_parse_from(["jsavrs", "-i", "test.vn", "-O3", "--disable-pass=sccp,dce", "--time-passes"]).unwrap();
+    assert_eq!(args.opt_level, OptLevel::O3);
+    assert_eq!(args.disable_pass, ["sccp", "dce"]);
+    assert!(args.time_passes);
+
+    assert_eq!(Args::try_parse_from(["jsavrs", "-i", "test.vn", "-O", "0"]).unwrap().opt_level, OptLevel::O0);
+    let error = Args::try_parse_from(["jsavrs", "-i", "test.vn", "-O4"]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ValueValidation);
+    let error = Args::try_parse_from(["jsavrs", "-i", "test.vn", "--disable-pass=licm"]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidValue);
+}
+
+#[test]
+fn test_parse_verify_ir_flag() {
+    assert!(!Args::try_parse_from(["jsavrs", "-i", "test.vn"]).unwrap().verify_ir);
+    assert!(Args::try_parse_from(["jsavrs", "-i", "test.vn", "--verify-ir"]).unwrap().verify_ir);
+}
+
+#[test]
+fn test_default_output_path_follows_emit_kind() {
+    let args = Args::try_parse_from(["jsavrs", "-i", "dir/prog.vn"]).unwrap();
+    assert_eq!(args.emit, EmitKind::Asm);
+    assert_eq!(args.output_path(), PathBuf::from("dir/prog.asm"));
+
+    for (kind, extension) in [("ir", "nir"), ("ast", "ast"), ("tokens", "tokens"), ("obj", "o")] {
+        let args = Args::try_parse_from(["jsavrs", "-i", "dir/prog.vn", "--emit", kind]).unwrap();
+        assert_eq!(args.output_path(), PathBuf::from(format!("dir/prog.{extension}")));
+    }
+    let args = Args::try_parse_from(["jsavrs", "-i", "dir/prog.vn", "--emit", "exe"]).unwrap();
+    assert_eq!(args.output_path(), PathBuf::from("dir/prog"));
+}
+
+#[test]
+fn test_parse_run_command() {
+    assert_eq!(Args::try_parse_from(["jsavrs", "-i", "test.vn"]).unwrap().command, Command::Build);
+    assert_eq!(Args::try_parse_from(["jsavrs", "run", "-i", "test.vn"]).unwrap().command, Command::Run);
+    assert_eq!(Args::try_parse_from(["jsavrs", "-i", "test.vn", "run"]).unwrap().command, Command::Run);
+    let error = Args::try_parse_from(["jsavrs", "exec", "-i", "test.vn"]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidValue);
+}
+
+#[test]
+fn run_interprets_the_program() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let input = dir.join("test_run.vn");
+    std::fs::write(&input, "var total: i64 = 0\nfun sq(n: i64): i64 { return n * n }\nmain { total = sq(7) }").unwrap();
+    cargo_bin_cmd!("jsavrs")
+        .arg("run")
+        .arg("-v")
+        .arg("-i")
+        .arg(&input)
+        .assert()
+        .success()
+        .stdout(contains("@total = 49i64"))
+        .stdout(contains("program exited with status 0"));
+
+    std::fs::write(&input, "var zero: i64 = 0\nmain {\n    var r: i64 = 10 / zero\n}").unwrap();
+    cargo_bin_cmd!("jsavrs")
+        .arg("run")
+        .arg("-i")
+        .arg(&input)
+        .assert()
+        .failure()
+        .stderr(contains("E3014"))
+        .stderr(contains("division by zero"))
+        .stderr(contains("line 3"));
+
+    std::fs::remove_file(&input).unwrap();
+}
+
+#[test]
+fn test_parse_error_format() {
+    assert_eq!(Args::try_parse_from(["jsavrs", "-i", "test.vn"]).unwrap().error_format, ErrorFormat::Human);
+    for (name, format) in [("human", ErrorFormat::Human), ("json", ErrorFormat::Json), ("sarif", ErrorFormat::Sarif)] {
+        let args = Args::try_parse_from(["jsavrs", "-i", "test.vn", &format!("--error-format={name}")]).unwrap();
+        assert_eq!(args.error_format, format);
+    }
+    let error = Args::try_parse_from(["jsavrs", "-i", "test.vn", "--error-format", "xml"]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::ValueValidation);
+}
+
+#[test]
+fn errors_are_reported_in_the_selected_format() {
+    let input = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_error_format.vn");
+    std::fs::write(
+        &input,
+        "main {
+    break
+}",
+    )
+    .unwrap();
+    let json = cargo_bin_cmd!("jsavrs").arg("-i").arg(&input).arg("--error-format=json").assert().failure();
+    let sarif = cargo_bin_cmd!("jsavrs").arg("-i").arg(&input).arg("--error-format=sarif").assert().failure();
+    std::fs::remove_file(&input).unwrap();
+
+    let stderr = String::from_utf8(json.get_output().stderr.clone()).unwrap();
+    let error: serde_json::Value = serde_json::from_str(stderr.trim_end()).unwrap();
+    assert_eq!(error["code"], "E2009");
+    assert_eq!(error["span"]["start"]["line"], 2);
+
+    let stderr = String::from_utf8(sarif.get_output().stderr.clone()).unwrap();
+    let log: serde_json::Value = serde_json::from_str(&stderr).unwrap();
+    assert_eq!(log["runs"][0]["results"][0]["ruleId"], "E2009");
+}
+
+#[test]
+fn json_errors_are_the_only_output_on_stderr() {
+    let trap = cargo_bin_cmd!("jsavrs")
+        .arg("run")
+        .arg("-i")
+        .arg(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/vn/run/division_by_zero.vn"))
+        .arg("--error-format=json")
+        .assert()
+        .failure();
+    let stderr = String::from_utf8(trap.get_output().stderr.clone()).unwrap();
+    let error: serde_json::Value = serde_json::from_str(stderr.trim_end()).unwrap();
+    assert_eq!(error["code"], "E3014");
+    assert_eq!(error["phase"], "runtime");
+
+    let missing =
+        cargo_bin_cmd!("jsavrs").arg("-i").arg("missing_input.vn").arg("--error-format=json").assert().failure();
+    let stderr = String::from_utf8(missing.get_output().stderr.clone()).unwrap();
+    let error: serde_json::Value = serde_json::from_str(stderr.trim_end()).unwrap();
+    assert_eq!(error["phase"], "system");
+}
+
+#[test]
+fn test_invalid_emit_kind() {
+    let result = Args::try_parse_from(["jsavrs", "-i", "test.vn", "--emit", "bin"]);
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidValue);
+}
+
+#[test]
+fn emit_writes_each_artefact_to_output() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let input = dir.join("test_emit.vn");
+    std::fs::write(&input, "fun add(a: i64, b: i64): i64 { return a + b }\nmain { var r: i64 = add(1, 2) }").unwrap();
+
+    for (kind, expected) in
+        [("asm", "section .text"), ("ir", "function add"), ("ast", "Function"), ("tokens", "identifier 'add'")]
+    {
+        let output = dir.join(format!("test_emit_out.{kind}"));
+        cargo_bin_cmd!("jsavrs")
+            .arg("-i")
+            .arg(&input)
+            .arg("--emit")
+            .arg(kind)
+            .arg("-o")
+            .arg(&output)
+            .assert()
+            .success();
+        let contents = std::fs::read_to_string(&output).unwrap();
+        assert!(contents.contains(expected), "{kind}: {contents}");
+        assert!(!contents.contains('\u{1b}'), "{kind} output contains escape codes");
+        std::fs::remove_file(&output).unwrap();
+    }
+
+    std::fs::remove_file(&input).unwrap();
+}
+
+#[test]
+fn unwritable_output_reports_io_error() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("test_unwritable.vn");
+    std::fs::write(&path, "fun a(num1: i8, num2: i8): i8 { return 0i8 }").unwrap();
+
+    let output = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("missing_dir").join("out.asm");
+    cargo_bin_cmd!("jsavrs")
+        .arg("-i")
+        .arg(&path)
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .failure()
+        .stderr(contains("I/O"))
+        .stderr(contains("cannot write"));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn missing_linker_reports_system_error() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("test_missing_linker.vn");
+    std::fs::write(&path, "main { var r: i64 = 1 }").unwrap();
+
+    let output = path.with_extension("");
+    cargo_bin_cmd!("jsavrs")
+        .env("PATH", "")
+        .arg("-i")
+        .arg(&path)
+        .arg("--emit=exe")
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .failure()
+        .stderr(contains("E5006"))
+        .stderr(contains("`cc`"));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn object_is_emitted_without_external_tools() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("test_builtin_object.vn");
+    std::fs::write(&path, "main { var r: i64 = 1 }").unwrap();
+
+    let output = path.with_extension("o");
+    cargo_bin_cmd!("jsavrs")
+        .env("PATH", "")
+        .arg("-i")
+        .arg(&path)
+        .arg("--emit=obj")
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success();
+
+    assert_eq!(&std::fs::read(&output).unwrap()[..4], b"\x7fELF");
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&output).unwrap();
+}
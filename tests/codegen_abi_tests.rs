@@ -0,0 +1,304 @@
+use jsavrs::asm::{Abi, GPRegister64, Instruction, Operand, XMMRegister};
+use jsavrs::codegen::asmgen::AsmGen;
+use jsavrs::codegen::frame::StackFrame;
+use jsavrs::codegen::param::{
+    ParamClass, ParamLocation, ReturnLocation, classify_param_type, classify_parameters, return_location,
+};
+use jsavrs::codegen::prologue::{callee_saved_to_preserve, gen_epilogue, gen_prologue};
+use jsavrs::codegen::target::resolve_abi;
+use jsavrs::error::error_code::ErrorCode;
+use jsavrs::ir::{Function, IrParameter, IrType, Module, ParamAttributes, TargetTriple};
+use jsavrs::utils::dummy_span;
+use std::sync::Arc;
+
+mod common;
+
+use common::build_module;
+
+fn param(name: &str, ty: IrType) -> IrParameter {
+    IrParameter { name: Arc::from(name), ty, attributes: ParamAttributes::default() }
+}
+
+fn locations(types: &[IrType], abi: Abi) -> Vec<ParamLocation> {
+    let params: Vec<IrParameter> =
+        types.iter().enumerate().map(|(i, ty)| param(&format!("p{i}"), ty.clone())).collect();
+    classify_parameters("f", &params, &abi).expect("supported parameters").into_iter().map(|a| a.location).collect()
+}
+
+fn compile_for(source: &str, triple: TargetTriple) -> String {
+    let mut module = build_module(source);
+    module.set_target_triple(triple);
+    let (assembly, errors) = AsmGen::new(module).gen_asm();
+    let text = assembly.to_string();
+    assert!(errors.is_empty(), "codegen errors: {errors:?}\n{text}");
+    text
+}
+
+#[test]
+fn test_resolve_abi_for_x86_64_targets() {
+    assert_eq!(resolve_abi(TargetTriple::X86_64UnknownLinuxGnu).unwrap(), Abi::SYSTEM_V_LINUX);
+    assert_eq!(resolve_abi(TargetTriple::X86_64AppleDarwin).unwrap(), Abi::SYSTEM_V_MACOS);
+    assert_eq!(resolve_abi(TargetTriple::X86_64PcWindowsGnu).unwrap(), Abi::WINDOWS);
+}
+
+#[test]
+fn test_resolve_abi_rejects_other_architectures() {
+    for triple in [
+        TargetTriple::AArch64UnknownLinuxGnu,
+        TargetTriple::AArch64AppleDarwin,
+        TargetTriple::AArch64PcWindowsGnu,
+        TargetTriple::I686PcWindowsGnu,
+        TargetTriple::I686UnknownLinuxGnu,
+        TargetTriple::Wasm32UnknownEmscripten,
+    ] {
+        let error = resolve_abi(triple).unwrap_err();
+        assert_eq!(error.error_code(), Some(&ErrorCode::E4004));
+        assert!(error.message().unwrap().contains(triple.as_str()));
+    }
+}
+
+#[test]
+fn test_unsupported_target_is_reported_without_panicking() {
+    let mut module = Module::new("m", None);
+    module.set_target_triple(TargetTriple::AArch64UnknownLinuxGnu);
+    module.add_function(Function::new("main", vec![], IrType::Void));
+    let (_, errors) = AsmGen::new(module).gen_asm();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error_code(), Some(&ErrorCode::E4004));
+}
+
+#[test]
+fn test_classify_param_types() {
+    for ty in [
+        IrType::I8,
+        IrType::U64,
+        IrType::Bool,
+        IrType::Char,
+        IrType::Pointer(Box::new(IrType::I32)),
+        IrType::String,
+        IrType::Array(Box::new(IrType::I32), 4),
+    ] {
+        assert_eq!(classify_param_type(&ty).unwrap(), ParamClass::Integer);
+    }
+    assert_eq!(classify_param_type(&IrType::F32).unwrap(), ParamClass::Float);
+    assert_eq!(classify_param_type(&IrType::F64).unwrap(), ParamClass::Float);
+    for ty in [IrType::Struct(Arc::from("S"), vec![], dummy_span()), IrType::Void] {
+        assert_eq!(classify_param_type(&ty).unwrap_err().error_code(), Some(&ErrorCode::E4005));
+    }
+}
+
+#[test]
+fn test_system_v_integer_parameters_spill_to_stack() {
+    let locations = locations(&vec![IrType::I64; 8], Abi::SYSTEM_V_LINUX);
+    assert_eq!(
+        &locations[..3],
+        &[
+            ParamLocation::GpRegister(GPRegister64::Rdi),
+            ParamLocation::GpRegister(GPRegister64::Rsi),
+            ParamLocation::GpRegister(GPRegister64::Rdx),
+        ]
+    );
+    assert_eq!(locations[5], ParamLocation::GpRegister(GPRegister64::R9));
+    assert_eq!(locations[6], ParamLocation::Stack { offset: 16 });
+    assert_eq!(locations[7], ParamLocation::Stack { offset: 24 });
+}
+
+#[test]
+fn test_windows_integer_parameters_spill_after_shadow_space() {
+    let locations = locations(&vec![IrType::I32; 6], Abi::WINDOWS);
+    assert_eq!(
+        &locations[..4],
+        &[
+            ParamLocation::GpRegister(GPRegister64::Rcx),
+            ParamLocation::GpRegister(GPRegister64::Rdx),
+            ParamLocation::GpRegister(GPRegister64::R8),
+            ParamLocation::GpRegister(GPRegister64::R9),
+        ]
+    );
+    assert_eq!(locations[4], ParamLocation::Stack { offset: 48 });
+    assert_eq!(locations[5], ParamLocation::Stack { offset: 56 });
+    assert_eq!(locations[4].outgoing_offset(), Some(32));
+}
+
+#[test]
+fn test_system_v_float_parameters() {
+    let locations = locations(&vec![IrType::F64; 10], Abi::SYSTEM_V_LINUX);
+    assert_eq!(locations[0], ParamLocation::XmmRegister(XMMRegister::Xmm0));
+    assert_eq!(locations[7], ParamLocation::XmmRegister(XMMRegister::Xmm7));
+    assert_eq!(locations[8], ParamLocation::Stack { offset: 16 });
+    assert_eq!(locations[9], ParamLocation::Stack { offset: 24 });
+}
+
+#[test]
+fn test_mixed_parameters_use_independent_sequences_on_system_v() {
+    let locations = locations(&[IrType::I32, IrType::F64, IrType::I64, IrType::F32], Abi::SYSTEM_V_LINUX);
+    assert_eq!(
+        locations,
+        vec![
+            ParamLocation::GpRegister(GPRegister64::Rdi),
+            ParamLocation::XmmRegister(XMMRegister::Xmm0),
+            ParamLocation::GpRegister(GPRegister64::Rsi),
+            ParamLocation::XmmRegister(XMMRegister::Xmm1),
+        ]
+    );
+}
+
+#[test]
+fn test_mixed_parameters_use_positional_slots_on_windows() {
+    let locations =
+        locations(&[IrType::I32, IrType::F64, IrType::I64, IrType::F32, IrType::F64, IrType::I8], Abi::WINDOWS);
+    assert_eq!(
+        locations,
+        vec![
+            ParamLocation::GpRegister(GPRegister64::Rcx),
+            ParamLocation::XmmRegister(XMMRegister::Xmm1),
+            ParamLocation::GpRegister(GPRegister64::R8),
+            ParamLocation::XmmRegister(XMMRegister::Xmm3),
+            ParamLocation::Stack { offset: 48 },
+            ParamLocation::Stack { offset: 56 },
+        ]
+    );
+}
+
+#[test]
+fn test_unsupported_parameter_type_names_function_and_parameter() {
+    let point = IrType::Struct(Arc::from("Point"), vec![], dummy_span());
+    let error = classify_parameters("draw", &[param("origin", point)], &Abi::SYSTEM_V_LINUX).unwrap_err();
+    assert_eq!(error.error_code(), Some(&ErrorCode::E4005));
+    let message = error.message().unwrap();
+    assert!(message.contains("draw") && message.contains("origin"), "{message}");
+}
+
+#[test]
+fn test_return_locations() {
+    let abi = Abi::SYSTEM_V_LINUX;
+    assert_eq!(return_location(&IrType::Void, &abi).unwrap(), None);
+    assert_eq!(return_location(&IrType::I32, &abi).unwrap(), Some(ReturnLocation::Gp(GPRegister64::Rax)));
+    assert_eq!(return_location(&IrType::U64, &abi).unwrap(), Some(ReturnLocation::Gp(GPRegister64::Rax)));
+    assert_eq!(return_location(&IrType::F32, &Abi::WINDOWS).unwrap(), Some(ReturnLocation::Xmm(XMMRegister::Xmm0)));
+    assert_eq!(return_location(&IrType::F64, &abi).unwrap(), Some(ReturnLocation::Xmm(XMMRegister::Xmm0)));
+    assert_eq!(return_location(&IrType::String, &abi).unwrap(), Some(ReturnLocation::Gp(GPRegister64::Rax)));
+    assert!(return_location(&IrType::Struct(Arc::from("S"), vec![], dummy_span()), &abi).is_err());
+}
+
+#[test]
+fn test_callee_saved_registers_to_preserve() {
+    let clobbered = [GPRegister64::Rax, GPRegister64::Rbx, GPRegister64::Rdi, GPRegister64::R12, GPRegister64::Rbp];
+    assert_eq!(callee_saved_to_preserve(&Abi::SYSTEM_V_LINUX, &clobbered), vec![GPRegister64::Rbx, GPRegister64::R12]);
+    assert_eq!(
+        callee_saved_to_preserve(&Abi::WINDOWS, &clobbered),
+        vec![GPRegister64::Rbx, GPRegister64::Rdi, GPRegister64::R12]
+    );
+}
+
+#[test]
+fn test_prologue_and_epilogue_preserve_registers_in_reverse_order() {
+    let func = Function::new("f", vec![param("a", IrType::I64)], IrType::I64);
+    let frame = StackFrame::layout(&func, &Abi::WINDOWS, &[GPRegister64::Rbx, GPRegister64::R12], &[]);
+    let prologue: Vec<String> = gen_prologue(&frame).iter().map(ToString::to_string).collect();
+    let epilogue: Vec<String> = gen_epilogue(&frame).iter().map(ToString::to_string).collect();
+    assert_eq!(&prologue[..4], &["push rbp", "mov rbp, rsp", "push rbx", "push r12"]);
+    assert!(prologue[4].starts_with("sub rsp, "));
+    assert_eq!(epilogue, vec!["lea rsp, QWORD PTR [rbp - 16]", "pop r12", "pop rbx", "pop rbp", "ret"]);
+}
+
+#[test]
+fn test_stack_stays_aligned_after_prologue() {
+    let func = Function::new("f", vec![param("a", IrType::I64), param("b", IrType::I64)], IrType::I64);
+    for preserved in [&[][..], &[GPRegister64::Rbx][..], &[GPRegister64::Rbx, GPRegister64::R12][..]] {
+        for abi in [Abi::SYSTEM_V_LINUX, Abi::WINDOWS] {
+            let frame = StackFrame::layout(&func, &abi, preserved, &[]);
+            if !frame.uses_red_zone() {
+                let pushed = 8 * u32::try_from(preserved.len()).unwrap();
+                assert_eq!((pushed + frame.frame_size()) % 16, 0, "{abi:?} {preserved:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_windows_frame_reserves_shadow_space() {
+    let func = Function::new("f", vec![], IrType::Void);
+    let frame = StackFrame::layout(&func, &Abi::WINDOWS, &[], &[]);
+    assert_eq!(frame.shadow_space(), 32);
+    assert!(!frame.uses_red_zone());
+    assert!(frame.frame_size() >= 32);
+}
+
+#[test]
+fn test_windows_xmm_registers_are_saved_in_frame() {
+    let func = Function::new("f", vec![], IrType::Void);
+    let frame = StackFrame::layout(&func, &Abi::WINDOWS, &[], &[XMMRegister::Xmm6]);
+    let prologue = gen_prologue(&frame);
+    let epilogue = gen_epilogue(&frame);
+    assert!(matches!(prologue.last(), Some(Instruction::Movdqu { src: Operand::Register(_), .. })));
+    assert!(matches!(epilogue.first(), Some(Instruction::Movdqu { dest: Operand::Register(_), .. })));
+}
+
+#[test]
+fn test_leaf_function_uses_red_zone_on_system_v() {
+    let text = compile_for(
+        "fun add(a: i64, b: i64): i64 { return a + b }
+         main { var r: i64 = add(1, 2) }",
+        TargetTriple::X86_64UnknownLinuxGnu,
+    );
+    let add = text.split("main:").next().unwrap();
+    assert!(!add.contains("sub rsp"), "{text}");
+    let main = text.split("main:").nth(1).unwrap();
+    assert!(main.contains("sub rsp"), "{text}");
+}
+
+#[test]
+fn test_stack_arguments_are_passed_and_read() {
+    let text = compile_for(
+        "fun f(a: i64, b: i64, c: i64, d: i64, e: i64, g: i64, h: i64, i: i64): i64 { return h + i }
+         main { var r: i64 = f(1, 2, 3, 4, 5, 6, 7, 8) }",
+        TargetTriple::X86_64UnknownLinuxGnu,
+    );
+    assert!(text.contains("mov rax, QWORD PTR [rbp + 16]"), "{text}");
+    assert!(text.contains("mov rax, QWORD PTR [rbp + 24]"), "{text}");
+    assert!(text.contains("mov QWORD PTR [rsp], rax"), "{text}");
+    assert!(text.contains("mov QWORD PTR [rsp + 8], rax"), "{text}");
+    assert!(text.contains("mov r9, 6"), "{text}");
+}
+
+#[test]
+fn test_windows_calls_use_windows_registers() {
+    let text = compile_for(
+        "fun f(a: i64, b: f64, c: i64, d: i64, e: i64): i64 { return e }
+         main { var r: i64 = f(1, 2.0d, 3, 4, 5) }",
+        TargetTriple::X86_64PcWindowsGnu,
+    );
+    assert!(text.contains("mov rax, rcx"), "{text}");
+    assert!(text.contains("movaps xmm4, xmm1"), "{text}");
+    assert!(text.contains("mov rax, QWORD PTR [rbp + 48]"), "{text}");
+    assert!(text.contains("mov QWORD PTR [rsp + 32], rax"), "{text}");
+    assert!(text.contains("mov r9, 4"), "{text}");
+}
+
+#[test]
+fn test_float_return_uses_xmm0() {
+    let text = compile_for(
+        "fun half(x: f64): f64 { return x / 2.0d }
+         main { var r: f64 = half(3.0d) }",
+        TargetTriple::X86_64UnknownLinuxGnu,
+    );
+    assert!(text.contains("divsd xmm0, xmm1"), "{text}");
+    assert!(text.contains("call half\n    movaps xmm8, xmm0"), "{text}");
+}
+
+#[test]
+fn test_arguments_follow_the_callee_parameter_types() {
+    // The comparison is typed after its `f64` operands in the IR, but `check`
+    // takes a `bool` and reads it from RDI.
+    let text = compile_for(
+        "var ok: bool = false
+         fun check(b: bool) { ok = b }
+         fun fsum(n: i64): f64 { return 2.0 }
+         main { check(fsum(4) == 2.0) }",
+        TargetTriple::X86_64UnknownLinuxGnu,
+    );
+    let main = text.split("\nmain:").nth(1).unwrap();
+    let call = main.split("call check").next().unwrap();
+    assert!(call.trim_end().lines().last().unwrap().contains("rdi"), "{text}");
+}
@@ -0,0 +1,288 @@
+use jsavrs::asm::Abi;
+use jsavrs::codegen::asmgen::AsmGen;
+use jsavrs::codegen::frame::{StackFrame, ValueLocation, align_of, size_of};
+use jsavrs::error::compile_error::CompileError;
+use jsavrs::ir::optimizer::DeadCodeElimination;
+use jsavrs::ir::optimizer::constant_folding::optimizer::ConstantFoldingOptimizer;
+use jsavrs::ir::{IrType, Module, Phase, Value, run_pipeline};
+use jsavrs::utils::dummy_span;
+use std::sync::Arc;
+
+mod common;
+
+fn build_module(source: &str, optimize: bool) -> Module {
+    let mut module = common::build_module(source);
+    if optimize {
+        let pipeline: Vec<Box<dyn Phase>> = vec![
+            Box::new(ConstantFoldingOptimizer::new(false, true)),
+            Box::new(DeadCodeElimination::with_config(10, true, false, false)),
+        ];
+        run_pipeline(&mut module, pipeline);
+    }
+    module
+}
+
+fn compile(source: &str) -> (String, Vec<CompileError>) {
+    let (assembly, errors) = AsmGen::new(build_module(source, false)).gen_asm();
+    (assembly.to_string(), errors)
+}
+
+fn compile_ok(source: &str) -> String {
+    let (text, errors) = compile(source);
+    assert!(errors.is_empty(), "codegen errors: {errors:?}\n{text}");
+    text
+}
+
+fn lines(text: &str) -> Vec<&str> {
+    text.lines().map(str::trim).collect()
+}
+
+#[test]
+fn test_main_is_global_and_has_prologue_and_epilogue() {
+    let text = compile_ok("main { var x: i32 = 1i32 }");
+    let lines = lines(&text);
+    assert!(text.contains("global main"), "{text}");
+    assert!(lines.contains(&"main:"));
+    assert!(lines.contains(&"push rbp"));
+    assert!(lines.contains(&"mov rbp, rsp"));
+    assert!(lines.contains(&"pop rbp"));
+    assert!(lines.contains(&"ret"));
+}
+
+#[test]
+fn test_arithmetic_is_lowered_with_normalization() {
+    let text = compile_ok(
+        "main {
+        var a: i32 = 6i32
+        var b: i32 = 7i32
+        var c: i32 = a * b - a / b
+    }",
+    );
+    let lines = lines(&text);
+    assert!(lines.contains(&"imul rax, rcx"), "{text}");
+    assert!(lines.contains(&"sub rax, rcx"));
+    assert!(lines.contains(&"cqo"));
+    assert!(lines.contains(&"idiv rcx"));
+    assert!(lines.contains(&"movsxd rax, eax"));
+}
+
+#[test]
+fn test_unsigned_division_and_comparison() {
+    let text = compile_ok(
+        "main {
+        var a: u32 = 6u32
+        var b: u32 = 4u32
+        var c: u32 = a % b
+        var d: bool = a < b
+    }",
+    );
+    let lines = lines(&text);
+    assert!(lines.contains(&"xor edx, edx"), "{text}");
+    assert!(lines.contains(&"div rcx"));
+    assert!(lines.contains(&"mov rax, rdx"));
+    assert!(lines.contains(&"setb al"));
+}
+
+#[test]
+fn test_function_parameters_and_calls_use_abi_registers() {
+    let text = compile_ok(
+        "fun add(a: i32, b: i32): i32 { return a + b }
+         main { var r: i32 = add(1i32, 2i32) }",
+    );
+    let lines = lines(&text);
+    assert!(lines.contains(&"add:"), "{text}");
+    assert!(lines.contains(&"mov rax, rdi"));
+    assert!(lines.contains(&"mov rax, rsi"));
+    assert!(lines.contains(&"mov rdi, 1"));
+    assert!(lines.contains(&"mov rsi, 2"));
+    assert!(lines.contains(&"call add"));
+    assert!(!text.contains("global add"));
+}
+
+#[test]
+fn test_control_flow_uses_function_scoped_block_labels() {
+    let text = compile_ok(
+        "main {
+            var x: i32 = 0i32
+            while (x < 10i32) { x = x + 1i32 }
+            if (x == 10i32) { x = 0i32 } else { x = 1i32 }
+        }",
+    );
+    let lines = lines(&text);
+    assert!(lines.iter().any(|l| l.starts_with("main.loop_start_")), "{text}");
+    assert!(lines.contains(&"test rax, rax"));
+    assert!(lines.iter().any(|l| l.starts_with("jne main.")));
+    assert!(lines.iter().any(|l| l.starts_with("jmp main.")));
+}
+
+#[test]
+fn test_switch_uses_jump_table_only_for_dense_cases() {
+    let text = compile_ok(
+        "fun dense(n: i32): i32 {
+            switch (n) {
+                case 10i32: return 1i32
+                case 11i32: return 2i32
+                case 13i32: return 3i32
+                case 14i32: return 4i32
+                default: return 0i32
+            }
+            return 0i32
+        }
+        fun sparse(n: i32): i32 {
+            switch (n) {
+                case 1i32: return 1i32
+                case 100i32: return 2i32
+                case 1000i32: return 3i32
+                case 10000i32: return 4i32
+            }
+            return 0i32
+        }
+        main { var r: i32 = dense(1i32) + sparse(1i32) }",
+    );
+    let lines = lines(&text);
+    let table = lines.iter().find(|l| l.starts_with("dense.entry_dense.table dd ")).expect("jump table");
+    assert_eq!(table.matches(" - $").count(), 5, "{text}");
+    assert!(table.contains("dense.switch_default_"), "holes go to the default block: {text}");
+    assert!(lines.contains(&"sub rax, 10"), "{text}");
+    assert!(lines.contains(&"cmp rax, 4"), "{text}");
+    assert!(lines.iter().any(|l| l.starts_with("ja dense.switch_default_")), "{text}");
+    assert!(lines.contains(&"jmp rax"), "{text}");
+
+    assert!(!lines.iter().any(|l| l.starts_with("sparse.") && l.contains(".table")), "{text}");
+    assert!(lines.contains(&"cmp rax, 10000"), "{text}");
+    assert_eq!(lines.iter().filter(|l| l.starts_with("je sparse.")).count(), 4, "{text}");
+}
+
+#[test]
+fn test_phi_nodes_are_resolved_on_edges() {
+    let module = build_module(
+        "fun count(n: i32): i32 {
+            var i: i32 = 0i32
+            var s: i32 = 0i32
+            while (i < n) {
+                s = s + i
+                i = i + 1i32
+            }
+            return s
+        }
+        main { var r: i32 = count(4i32) }",
+        true,
+    );
+    let (assembly, errors) = AsmGen::new(module).gen_asm();
+    let text = assembly.to_string();
+    assert!(errors.is_empty(), "codegen errors: {errors:?}\n{text}");
+    // Two phis meet at the loop header: the back edge ends with the parallel
+    // copy into their locations.
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let back_edge = lines.iter().position(|line| *line == "jmp count.loop_start_1").expect("back edge");
+    assert!(lines[back_edge - 2..back_edge].iter().all(|line| line.starts_with("mov ")), "{text}");
+}
+
+#[test]
+fn test_float_operations_use_sse() {
+    let text = compile_ok(
+        "main {
+        var a: f64 = 1.5d
+        var b: f64 = a * 2.0d
+        var c: bool = a < b
+        var d: f32 = 1.0f + 2.0f
+    }",
+    );
+    let lines = lines(&text);
+    assert!(lines.contains(&"mulsd xmm0, xmm1"), "{text}");
+    assert!(lines.contains(&"ucomisd xmm1, xmm0"));
+    assert!(lines.contains(&"seta al"));
+    assert!(lines.contains(&"addss xmm0, xmm1"));
+}
+
+#[test]
+fn test_string_literals_are_emitted_as_data() {
+    let text = compile_ok("main { var s: string = \"hello\" }");
+    assert!(text.contains("str.0"), "{text}");
+    assert!(text.contains("hello"));
+}
+
+#[test]
+fn test_globals_are_emitted_as_data_and_accessed_through_their_address() {
+    let text = compile_ok(
+        "const STEP: i32 = 3i32
+        var total: i64 = 40
+        var hits: i32 = 0i32
+        var scale: f64 = 1.5
+        var flag: bool = true
+        fun bump(): i64 {
+            total += 2
+            hits = hits + STEP
+            return total
+        }
+        main { }",
+    );
+    let lines = lines(&text);
+    assert!(lines.contains(&"total dq 0x0000000000000028"), "{text}");
+    assert!(lines.contains(&"scale dq 0x3ff8000000000000"));
+    assert!(lines.contains(&"flag db 0x01"));
+    assert!(lines.contains(&"hits resd 1"));
+    assert!(!text.contains("STEP"));
+
+    let bss = text.find("section .bss").unwrap();
+    assert!(text.find("hits resd 1").unwrap() > bss);
+    assert!(text.find("total dq").unwrap() < bss);
+
+    assert!(lines.contains(&"mov rcx, total"));
+    assert!(lines.contains(&"mov rax, QWORD PTR [rcx]"));
+    assert!(lines.contains(&"mov rcx, hits"));
+    assert!(lines.contains(&"mov DWORD PTR [rcx], eax"));
+}
+
+#[test]
+fn test_global_arrays_are_emitted_element_by_element() {
+    let text = compile_ok(
+        "var table: i16[3] = {1i16, -2i16, 3i16}
+        var empty: i64[4] = {0, 0, 0, 0}
+        const PRIMES: u8[2] = {2u8, 3u8}
+        fun pick(i: i64): i16 {
+            return table[i] + PRIMES[1] as i16
+        }
+        main { }",
+    );
+    let lines = lines(&text);
+    assert!(lines.contains(&"table dw 0x0001, 0xfffe, 0x0003"), "{text}");
+    assert!(lines.contains(&"empty resq 4"));
+    assert!(lines.contains(&"PRIMES db 0x02, 0x03"));
+    assert!(lines.contains(&"mov rax, table"));
+}
+
+#[test]
+fn test_stack_frame_layout_is_aligned() {
+    let module = build_module(
+        "fun f(a: i64, b: i64): i64 {
+            var t: i64 = a + b
+            return t
+        }
+        main { }",
+        false,
+    );
+    let func = module.get_function("f").expect("function f");
+    let frame = StackFrame::layout(func, &Abi::SYSTEM_V_LINUX, &[], &[]);
+    assert_eq!(frame.frame_size() % 16, 0);
+    let a = Value::new_local(Arc::from("a"), IrType::I64);
+    let b = Value::new_local(Arc::from("b"), IrType::I64);
+    assert_eq!(frame.location(&a), Some(ValueLocation::Stack(-8)));
+    assert_eq!(frame.location(&b), Some(ValueLocation::Stack(-16)));
+}
+
+#[test]
+fn test_type_sizes_and_alignment() {
+    assert_eq!(size_of(&IrType::I8), 1);
+    assert_eq!(size_of(&IrType::U16), 2);
+    assert_eq!(size_of(&IrType::F32), 4);
+    assert_eq!(size_of(&IrType::String), 8);
+    assert_eq!(size_of(&IrType::Array(Box::new(IrType::I32), 3)), 12);
+    let record = IrType::Struct(
+        Arc::from("S"),
+        vec![("a".to_string(), IrType::I8), ("b".to_string(), IrType::I64), ("c".to_string(), IrType::I16)],
+        dummy_span(),
+    );
+    assert_eq!(size_of(&record), 24);
+    assert_eq!(align_of(&record), 8);
+}
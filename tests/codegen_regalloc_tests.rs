@@ -0,0 +1,173 @@
+use jsavrs::asm::{Abi, GPRegister64, XMMRegister};
+use jsavrs::codegen::asmgen::AsmGen;
+use jsavrs::codegen::frame::ValueLocation;
+use jsavrs::codegen::regalloc::parallel_copy::sequentialize;
+use jsavrs::codegen::regalloc::{
+    LiveInterval, LiveIntervals, RegisterAllocation, RegisterClass, gp_register_pool, xmm_register_pool,
+};
+use jsavrs::ir::{Function, IrType, Value, ValueKind};
+use std::collections::HashMap;
+
+mod common;
+
+use common::build_module;
+
+fn value_of(interval: &LiveInterval) -> Value {
+    let ty = if interval.class == RegisterClass::Vector { IrType::F64 } else { IrType::I64 };
+    match &interval.value {
+        ValueKind::Temporary(id) => Value::new_temporary(*id, ty),
+        ValueKind::Local(name) => Value::new_local(name.clone(), ty),
+        other => panic!("unexpected allocatable value {other:?}"),
+    }
+}
+
+/// Runs `moves` over a location -> value map, as the emitted code would.
+fn simulate(moves: &[(char, char)], state: &mut HashMap<char, i32>) {
+    for (dest, src) in moves {
+        let value = state[src];
+        state.insert(*dest, value);
+    }
+}
+
+const LOOP_WITH_CALLS: &str = "fun g(x: i64): i64 { return x + 1 }
+     fun f(n: i64): i64 {
+         var a: i64 = n * 2
+         var b: i64 = n * 3
+         var i: i64 = 0
+         while (i < n) {
+             a = g(a) + b
+             b = b + i
+             i = i + 1
+         }
+         return a + b
+     }
+     main { var r: i64 = f(4) }";
+
+#[test]
+fn test_sequentialize_swap_uses_temporary() {
+    let moves = sequentialize(&[('a', 'b'), ('b', 'a')], 't');
+    assert_eq!(moves, vec![('t', 'b'), ('b', 'a'), ('a', 't')]);
+}
+
+#[test]
+fn test_sequentialize_orders_chains_and_drops_self_copies() {
+    let moves = sequentialize(&[('a', 'b'), ('b', 'c'), ('c', 'c')], 't');
+    assert_eq!(moves, vec![('a', 'b'), ('b', 'c')]);
+}
+
+#[test]
+fn test_sequentialize_preserves_parallel_semantics() {
+    let copies = [('a', 'b'), ('b', 'c'), ('c', 'a'), ('d', 'a'), ('e', 'd'), ('f', 'f')];
+    let initial: HashMap<char, i32> = ['a', 'b', 'c', 'd', 'e', 'f', 't'].into_iter().zip(0..).collect();
+
+    let mut state = initial.clone();
+    simulate(&sequentialize(&copies, 't'), &mut state);
+
+    for (dest, src) in copies {
+        assert_eq!(state[&dest], initial[&src], "{dest} <- {src}");
+    }
+}
+
+#[test]
+fn test_register_pools_exclude_reserved_and_parameter_registers() {
+    let sysv = gp_register_pool(&Abi::SYSTEM_V_LINUX);
+    assert_eq!(sysv.caller_saved, vec![GPRegister64::R10]);
+    assert_eq!(sysv.callee_saved.len(), 5);
+    assert!(sysv.callee_saved.contains(&GPRegister64::Rbx));
+    assert!(!sysv.callee_saved.contains(&GPRegister64::Rbp));
+
+    let windows = gp_register_pool(&Abi::WINDOWS);
+    assert_eq!(windows.caller_saved, vec![GPRegister64::R10]);
+    assert!(windows.callee_saved.contains(&GPRegister64::Rsi));
+    assert!(windows.callee_saved.contains(&GPRegister64::Rdi));
+
+    let sysv_xmm = xmm_register_pool(&Abi::SYSTEM_V_LINUX);
+    assert_eq!(sysv_xmm.caller_saved.len(), 8);
+    assert!(sysv_xmm.callee_saved.is_empty());
+
+    let windows_xmm = xmm_register_pool(&Abi::WINDOWS);
+    assert_eq!(windows_xmm.caller_saved, vec![XMMRegister::Xmm4, XMMRegister::Xmm5]);
+    assert_eq!(windows_xmm.callee_saved.len(), 10);
+}
+
+#[test]
+fn test_overlapping_intervals_get_distinct_registers() {
+    let module = build_module(LOOP_WITH_CALLS);
+    for func in module.functions() {
+        let intervals = LiveIntervals::compute(func);
+        let allocation = RegisterAllocation::allocate(func, &Abi::SYSTEM_V_LINUX);
+        let assigned: Vec<(&LiveInterval, ValueLocation)> = intervals
+            .intervals()
+            .iter()
+            .filter_map(|interval| allocation.location(&value_of(interval)).map(|location| (interval, location)))
+            .collect();
+
+        for (i, (a, reg_a)) in assigned.iter().enumerate() {
+            for (b, reg_b) in &assigned[i + 1..] {
+                let strictly_overlap = a.start < b.end && b.start < a.end;
+                assert!(!(strictly_overlap && reg_a == reg_b), "{a:?} and {b:?} share {reg_a:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_values_live_across_calls_use_callee_saved_registers() {
+    let module = build_module(LOOP_WITH_CALLS);
+    let func = module.get_function("f").expect("function f");
+    let intervals = LiveIntervals::compute(func);
+    let allocation = RegisterAllocation::allocate(func, &Abi::SYSTEM_V_LINUX);
+    let callee_saved = gp_register_pool(&Abi::SYSTEM_V_LINUX).callee_saved;
+
+    assert!(!intervals.call_positions().is_empty());
+    let mut crossing = 0;
+    for interval in intervals.intervals().iter().filter(|interval| intervals.crosses_call(interval)) {
+        crossing += 1;
+        match allocation.location(&value_of(interval)) {
+            Some(ValueLocation::Gp(reg)) => assert!(callee_saved.contains(&reg), "{interval:?} in {reg:?}"),
+            Some(other) => panic!("{interval:?} in {other:?}"),
+            None => assert!(allocation.is_spilled(&value_of(interval))),
+        }
+    }
+    assert!(crossing > 0);
+}
+
+#[test]
+fn test_register_pressure_causes_spills() {
+    let vars: Vec<String> = (0..12).map(|i| format!("var v{i}: i64 = n * {}", i + 2)).collect();
+    let sum: Vec<String> = (0..12).map(|i| format!("v{i}")).collect();
+    let source =
+        format!("fun f(n: i64): i64 {{ {} return {} }} main {{ var r: i64 = f(1) }}", vars.join("\n"), sum.join(" + "));
+    let module = build_module(&source);
+    let func: &Function = module.get_function("f").expect("function f");
+    let allocation = RegisterAllocation::allocate(func, &Abi::SYSTEM_V_LINUX);
+
+    assert!(allocation.spill_count() > 0);
+    assert!(allocation.used_gp_registers().len() <= gp_register_pool(&Abi::SYSTEM_V_LINUX).callee_saved.len() + 1);
+}
+
+#[test]
+fn test_small_functions_need_no_stack_slots() {
+    let module = build_module("fun add(a: i64, b: i64): i64 { return a + b } main { var r: i64 = add(1, 2) }");
+    let (assembly, errors) = AsmGen::new(module).gen_asm();
+    let text = assembly.to_string();
+    assert!(errors.is_empty(), "codegen errors: {errors:?}");
+
+    let add = text.split("add:").nth(1).and_then(|rest| rest.split("main:").next()).expect("add body");
+    assert!(!add.contains("sub rsp"), "{text}");
+    assert!(!add.contains("[rbp - "), "{text}");
+}
+
+#[test]
+fn test_float_comparisons_produce_general_purpose_values() {
+    let module = build_module("fun less(a: f64, b: f64): bool { return a < b } main { var r: bool = less(1.0, 2.0) }");
+    let func = module.get_function("less").expect("function less");
+    let intervals = LiveIntervals::compute(func);
+    let classes: Vec<RegisterClass> = intervals
+        .intervals()
+        .iter()
+        .filter(|interval| matches!(interval.value, ValueKind::Temporary(_)))
+        .map(|interval| interval.class)
+        .collect();
+    assert_eq!(classes, [RegisterClass::General]);
+}
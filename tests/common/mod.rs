@@ -0,0 +1,17 @@
+//! Fixtures shared by the integration tests.
+
+#![allow(dead_code)]
+
+use jsavrs::ir::Module;
+use jsavrs::test_runner::compile;
+
+/// Compiles `source`, named `test.vn`, to its unoptimized IR module.
+pub fn build_module(source: &str) -> Module {
+    build_module_at("test.vn", source)
+}
+
+/// Compiles `source`, named `path`, to its unoptimized IR module, panicking
+/// with the errors of the first phase that reports any.
+pub fn build_module_at(path: &str, source: &str) -> Module {
+    compile(path, source).unwrap_or_else(|errors| panic!("{path}: {errors:?}\n{source}"))
+}
@@ -0,0 +1,246 @@
+use clap::Parser;
+use jsavrs::asm::{Abi, AssemblyFile};
+use jsavrs::cli::{Args, AssemblerKind};
+use jsavrs::codegen::asmgen::AsmGen;
+use jsavrs::driver::{Assembler, LinkerFlavor, Toolchain};
+use jsavrs::error::compile_error::CompileError;
+use jsavrs::error::error_code::ErrorCode;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+mod common;
+
+fn assemble_source(source: &str) -> AssemblyFile {
+    let (assembly, asm_errors) = AsmGen::new(common::build_module(source)).gen_asm();
+    assert!(asm_errors.is_empty(), "asm errors: {asm_errors:?}");
+    assembly
+}
+
+fn output_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("jsavrs-driver-{}-{name}", std::process::id()))
+}
+
+/// Builds `source` into an executable with the default toolchain and runs it.
+fn run_native(name: &str, source: &str) -> ExitStatus {
+    let executable = output_path(name);
+    Toolchain::default().build_executable(&assemble_source(source), &executable).unwrap();
+    let status = Command::new(&executable).status().unwrap();
+    std::fs::remove_file(&executable).unwrap();
+    status
+}
+
+fn nasm(path: &str) -> Assembler {
+    Assembler::Nasm(PathBuf::from(path))
+}
+
+fn nasm_available() -> bool {
+    Command::new("nasm").arg("-v").output().is_ok_and(|output| output.status.success())
+}
+
+const fn system_error_code(error: &CompileError) -> Option<ErrorCode> {
+    match error {
+        CompileError::SystemError { code, .. } => *code,
+        _ => None,
+    }
+}
+
+#[test]
+fn test_default_toolchain_uses_builtin_assembler_and_cc() {
+    let toolchain = Toolchain::default();
+    assert_eq!(toolchain.assembler, Assembler::Builtin);
+    assert_eq!(toolchain.linker, Path::new("cc"));
+    assert_eq!(toolchain.linker_flavor, LinkerFlavor::Cc);
+}
+
+#[test]
+fn test_missing_assembler_is_reported_as_e5006() {
+    let toolchain = Toolchain::new(nasm("jsavrs-no-such-assembler"), "cc", LinkerFlavor::Cc);
+    let object = output_path("missing.o");
+    let error = toolchain.assemble(&assemble_source("main { }"), &object).unwrap_err();
+
+    assert_eq!(system_error_code(&error), Some(ErrorCode::E5006));
+    assert!(error.message().unwrap().contains("jsavrs-no-such-assembler"));
+    assert!(error.help().unwrap().contains("PATH"));
+    assert!(!object.exists());
+}
+
+#[test]
+fn test_failing_assembler_is_reported_as_e5007() {
+    let toolchain = Toolchain::new(nasm("false"), "cc", LinkerFlavor::Cc);
+    let error = toolchain.assemble(&assemble_source("main { }"), &output_path("failing.o")).unwrap_err();
+
+    assert_eq!(system_error_code(&error), Some(ErrorCode::E5007));
+    assert!(error.message().unwrap().contains("`false` failed"));
+}
+
+#[test]
+fn test_failing_linker_is_reported_as_e5007() {
+    let toolchain = Toolchain::new(Assembler::Builtin, "false", LinkerFlavor::Cc);
+    let error = toolchain.link(&[output_path("input.o")], &output_path("linked")).unwrap_err();
+
+    assert_eq!(system_error_code(&error), Some(ErrorCode::E5007));
+}
+
+#[test]
+fn test_ld_flavor_assembles_startup_code_first() {
+    let toolchain = Toolchain::new(nasm("jsavrs-no-such-assembler"), "ld", LinkerFlavor::Ld);
+    let error = toolchain.link(&[output_path("input.o")], &output_path("linked")).unwrap_err();
+
+    assert_eq!(system_error_code(&error), Some(ErrorCode::E5006));
+}
+
+#[test]
+fn test_builtin_assembler_writes_elf_object() {
+    let object = output_path("builtin.o");
+    Toolchain::default().assemble(&assemble_source("main { }"), &object).unwrap();
+
+    let bytes = std::fs::read(&object).unwrap();
+    assert_eq!(&bytes[..4], b"\x7fELF");
+    std::fs::remove_file(&object).unwrap();
+}
+
+#[test]
+fn test_unwritable_object_is_reported_as_e5004() {
+    let object = output_path("no-such-dir").join("out.o");
+    let error = Toolchain::default().assemble(&assemble_source("main { }"), &object).unwrap_err();
+
+    assert_eq!(system_error_code(&error), Some(ErrorCode::E5004));
+}
+
+#[test]
+fn test_non_linux_assembly_is_rejected() {
+    let error = Toolchain::default().assemble(&AssemblyFile::new(Abi::WINDOWS), &output_path("win.o")).unwrap_err();
+
+    assert_eq!(error.error_code(), Some(&ErrorCode::E4004));
+}
+
+#[test]
+fn test_builds_runnable_executable_with_builtin_assembler() {
+    let assembly = assemble_source(
+        "fun add(a: i64, b: i64): i64 { return a + b }\nmain { var r: i64 = add(1, 2)\n var s: string = \"hi\" }",
+    );
+
+    for flavor in [LinkerFlavor::Cc, LinkerFlavor::Ld] {
+        let linker = if flavor == LinkerFlavor::Cc { "cc" } else { "ld" };
+        let executable = output_path(&format!("builtin-{linker}"));
+        Toolchain::new(Assembler::Builtin, linker, flavor).build_executable(&assembly, &executable).unwrap();
+
+        let status = Command::new(&executable).status().unwrap();
+        assert!(status.success(), "{linker}: {status}");
+        std::fs::remove_file(&executable).unwrap();
+    }
+}
+
+#[test]
+fn test_builds_runnable_executable_with_nasm() {
+    if !nasm_available() {
+        eprintln!("nasm not installed, skipping");
+        return;
+    }
+    let assembly = assemble_source("fun add(a: i64, b: i64): i64 { return a + b }\nmain { var r: i64 = add(1, 2) }");
+
+    for flavor in [LinkerFlavor::Cc, LinkerFlavor::Ld] {
+        let linker = if flavor == LinkerFlavor::Cc { "cc" } else { "ld" };
+        let executable = output_path(&format!("prog-{linker}"));
+        Toolchain::new(nasm("nasm"), linker, flavor).build_executable(&assembly, &executable).unwrap();
+
+        let status = Command::new(&executable).status().unwrap();
+        assert!(status.success(), "{linker}: {status}");
+        std::fs::remove_file(&executable).unwrap();
+    }
+}
+
+#[test]
+fn test_negated_comparisons_run_natively() {
+    // `!` flipped every bit of the 0/1 comparison result, so it was always
+    // true. Each wrong outcome divides by zero and kills the program.
+    let status = run_native(
+        "not",
+        "var a: i16 = 3i16\nvar b: i16 = 5i16\nvar zero: i32 = 0i32\nvar r: i32 = 1i32
+        main {
+            if (!(a < b)) { r = r / zero }
+            if (!(a >= b)) { } else { r = r / zero }
+            var c: bool = !(a == b)
+            if (!c) { r = r / zero }
+        }",
+    );
+    assert!(status.success(), "{status}");
+}
+
+#[test]
+fn test_struct_fields_run_natively() {
+    // Field addresses were sign-extended from 32 bits by the pointer bitcast,
+    // so every field access crashed.
+    let status = run_native(
+        "fields",
+        "struct Point {\n    x: i32,\n    y: i64,\n    c: u8\n}
+        var zero: i32 = 0i32\nvar r: i32 = 1i32
+        main {
+            var p: Point = Point { x: 3i32, y: 4, c: 5u8 }
+            p.x = p.x + 10i32
+            if (p.x != 13i32) { r = r / zero }
+            if (p.y != 4) { r = r / zero }
+            if (p.c != 5u8) { r = r / zero }
+        }",
+    );
+    assert!(status.success(), "{status}");
+}
+
+#[test]
+fn test_string_and_array_parameters_run_natively() {
+    // Strings and arrays are passed by address; they used to be rejected with E4005.
+    let status = run_native(
+        "by-address",
+        "var zero: i32 = 0i32\nvar r: i32 = 1i32
+        fun second(values: i32[3]): i32 { return values[1] }
+        fun same(s: string, t: string): bool { return s == t }
+        main {
+            var a: i32[3] = {4i32, 5i32, 6i32}
+            if (second(a) != 5i32) { r = r / zero }
+            var s: string = \"hi\"
+            if (!same(s, s)) { r = r / zero }
+        }",
+    );
+    assert!(status.success(), "{status}");
+}
+
+#[test]
+fn test_assembler_option_selects_the_toolchain_assembler() {
+    let args = Args::try_parse_from(["jsavrs", "-i", "prog.vn", "--emit=obj"]).unwrap();
+    assert_eq!(args.assembler, AssemblerKind::Builtin);
+    assert_eq!(Toolchain::default().with_assembler(args.assembler.assembler()), Toolchain::default());
+
+    let args = Args::try_parse_from(["jsavrs", "-i", "prog.vn", "--emit=exe", "--assembler=nasm"]).unwrap();
+    assert_eq!(args.assembler, AssemblerKind::Nasm);
+    let toolchain = Toolchain::default().with_assembler(args.assembler.assembler());
+    assert_eq!(toolchain.assembler, nasm("nasm"));
+    assert_eq!(toolchain.linker, Path::new("cc"));
+
+    assert!(Args::try_parse_from(["jsavrs", "-i", "prog.vn", "--assembler=gas"]).is_err());
+}
+
+#[test]
+fn test_assembler_option_reaches_nasm() {
+    let input = output_path("nasm-option.vn");
+    let object = output_path("nasm-option.o");
+    std::fs::write(&input, "main { }").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_jsavrs"))
+        .arg("-i")
+        .arg(&input)
+        .arg("-o")
+        .arg(&object)
+        .args(["--emit=obj", "--assembler=nasm"])
+        .output()
+        .unwrap();
+    std::fs::remove_file(&input).unwrap();
+
+    if nasm_available() {
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert_eq!(&std::fs::read(&object).unwrap()[..4], b"\x7fELF");
+        std::fs::remove_file(&object).unwrap();
+    } else {
+        // Without nasm on the PATH, the failure shows that nasm was invoked.
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("E5006"));
+    }
+}
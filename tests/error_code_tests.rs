@@ -72,6 +72,8 @@ fn test_compiler_phase_display() {
     assert_eq!(format!("{}", CompilerPhase::Semantic), "semantic");
     assert_eq!(format!("{}", CompilerPhase::IrGeneration), "ir-gen");
     assert_eq!(format!("{}", CompilerPhase::CodeGeneration), "codegen");
+    assert_eq!(format!("{}", CompilerPhase::Verification), "verify");
+    assert_eq!(format!("{}", CompilerPhase::Runtime), "runtime");
     assert_eq!(format!("{}", CompilerPhase::System), "system");
 }
 
@@ -122,7 +124,7 @@ fn test_compiler_phase_hash() {
     assert!(set.contains(&CompilerPhase::System));
 }
 
-/// Test all lexical error codes (E0001-E0010).
+/// Test all lexical error codes (E0001-E0013).
 #[test]
 fn test_lexical_error_codes() {
     assert_eq!(ErrorCode::E0001.code(), "E0001");
@@ -135,6 +137,9 @@ fn test_lexical_error_codes() {
     assert_eq!(ErrorCode::E0008.code(), "E0008");
     assert_eq!(ErrorCode::E0009.code(), "E0009");
     assert_eq!(ErrorCode::E0010.code(), "E0010");
+    assert_eq!(ErrorCode::E0011.code(), "E0011");
+    assert_eq!(ErrorCode::E0012.code(), "E0012");
+    assert_eq!(ErrorCode::E0013.code(), "E0013");
 }
 
 /// Test all parser error codes (E1001-E1015).
@@ -157,7 +162,7 @@ fn test_parser_error_codes() {
     assert_eq!(ErrorCode::E1015.code(), "E1015");
 }
 
-/// Test all semantic/type error codes (E2001-E2032).
+/// Test all semantic/type error codes (E2001-E2044).
 #[test]
 fn test_semantic_error_codes() {
     assert_eq!(ErrorCode::E2001.code(), "E2001");
@@ -192,9 +197,21 @@ fn test_semantic_error_codes() {
     assert_eq!(ErrorCode::E2030.code(), "E2030");
     assert_eq!(ErrorCode::E2031.code(), "E2031");
     assert_eq!(ErrorCode::E2032.code(), "E2032");
-}
-
-/// Test all IR generation error codes (E3001-E3008).
+    assert_eq!(ErrorCode::E2033.code(), "E2033");
+    assert_eq!(ErrorCode::E2034.code(), "E2034");
+    assert_eq!(ErrorCode::E2035.code(), "E2035");
+    assert_eq!(ErrorCode::E2036.code(), "E2036");
+    assert_eq!(ErrorCode::E2037.code(), "E2037");
+    assert_eq!(ErrorCode::E2038.code(), "E2038");
+    assert_eq!(ErrorCode::E2039.code(), "E2039");
+    assert_eq!(ErrorCode::E2040.code(), "E2040");
+    assert_eq!(ErrorCode::E2041.code(), "E2041");
+    assert_eq!(ErrorCode::E2042.code(), "E2042");
+    assert_eq!(ErrorCode::E2043.code(), "E2043");
+    assert_eq!(ErrorCode::E2044.code(), "E2044");
+}
+
+/// Test all IR generation error codes (E3001-E3016).
 #[test]
 fn test_ir_error_codes() {
     assert_eq!(ErrorCode::E3001.code(), "E3001");
@@ -205,6 +222,14 @@ fn test_ir_error_codes() {
     assert_eq!(ErrorCode::E3006.code(), "E3006");
     assert_eq!(ErrorCode::E3007.code(), "E3007");
     assert_eq!(ErrorCode::E3008.code(), "E3008");
+    assert_eq!(ErrorCode::E3009.code(), "E3009");
+    assert_eq!(ErrorCode::E3010.code(), "E3010");
+    assert_eq!(ErrorCode::E3011.code(), "E3011");
+    assert_eq!(ErrorCode::E3012.code(), "E3012");
+    assert_eq!(ErrorCode::E3013.code(), "E3013");
+    assert_eq!(ErrorCode::E3014.code(), "E3014");
+    assert_eq!(ErrorCode::E3015.code(), "E3015");
+    assert_eq!(ErrorCode::E3016.code(), "E3016");
 }
 
 /// Test all code generation error codes (E4001-E4005).
@@ -217,7 +242,7 @@ fn test_codegen_error_codes() {
     assert_eq!(ErrorCode::E4005.code(), "E4005");
 }
 
-/// Test all I/O error codes (E5001-E5005).
+/// Test all I/O error codes (E5001-E5007).
 #[test]
 fn test_io_error_codes() {
     assert_eq!(ErrorCode::E5001.code(), "E5001");
@@ -225,6 +250,8 @@ fn test_io_error_codes() {
     assert_eq!(ErrorCode::E5003.code(), "E5003");
     assert_eq!(ErrorCode::E5004.code(), "E5004");
     assert_eq!(ErrorCode::E5005.code(), "E5005");
+    assert_eq!(ErrorCode::E5006.code(), "E5006");
+    assert_eq!(ErrorCode::E5007.code(), "E5007");
 }
 
 /// Test numeric codes for lexical errors.
@@ -240,6 +267,9 @@ fn test_lexical_numeric_codes() {
     assert_eq!(ErrorCode::E0008.numeric_code(), 8);
     assert_eq!(ErrorCode::E0009.numeric_code(), 9);
     assert_eq!(ErrorCode::E0010.numeric_code(), 10);
+    assert_eq!(ErrorCode::E0011.numeric_code(), 11);
+    assert_eq!(ErrorCode::E0012.numeric_code(), 12);
+    assert_eq!(ErrorCode::E0013.numeric_code(), 13);
 }
 
 /// Test numeric codes for parser errors.
@@ -259,6 +289,15 @@ fn test_semantic_numeric_codes() {
     assert_eq!(ErrorCode::E2001.numeric_code(), 2001);
     assert_eq!(ErrorCode::E2023.numeric_code(), 2023);
     assert_eq!(ErrorCode::E2032.numeric_code(), 2032);
+    assert_eq!(ErrorCode::E2035.numeric_code(), 2035);
+    assert_eq!(ErrorCode::E2036.numeric_code(), 2036);
+    assert_eq!(ErrorCode::E2038.numeric_code(), 2038);
+    assert_eq!(ErrorCode::E2039.numeric_code(), 2039);
+    assert_eq!(ErrorCode::E2040.numeric_code(), 2040);
+    assert_eq!(ErrorCode::E2041.numeric_code(), 2041);
+    assert_eq!(ErrorCode::E2042.numeric_code(), 2042);
+    assert_eq!(ErrorCode::E2043.numeric_code(), 2043);
+    assert_eq!(ErrorCode::E2044.numeric_code(), 2044);
 }
 
 /// Test numeric codes for IR errors.
@@ -310,6 +349,9 @@ fn test_lexical_errors_phase() {
         ErrorCode::E0008,
         ErrorCode::E0009,
         ErrorCode::E0010,
+        ErrorCode::E0011,
+        ErrorCode::E0012,
+        ErrorCode::E0013,
     ];
 
     for error in lexical_errors {
@@ -365,6 +407,8 @@ fn test_ir_errors_phase() {
         ErrorCode::E3006,
         ErrorCode::E3007,
         ErrorCode::E3008,
+        ErrorCode::E3009,
+        ErrorCode::E3016,
     ];
 
     for error in ir_errors {
@@ -372,6 +416,24 @@ fn test_ir_errors_phase() {
     }
 }
 
+/// Test that all IR verifier errors map to `Verification` phase.
+#[test]
+fn test_verification_errors_phase() {
+    let verification_errors = [ErrorCode::E3010, ErrorCode::E3011, ErrorCode::E3012, ErrorCode::E3013];
+
+    for error in verification_errors {
+        assert_eq!(error.phase(), CompilerPhase::Verification, "Error {error:?} should be in Verification phase");
+    }
+}
+
+/// Test that interpreter traps map to `Runtime` phase.
+#[test]
+fn test_runtime_errors_phase() {
+    for error in [ErrorCode::E3014, ErrorCode::E3015] {
+        assert_eq!(error.phase(), CompilerPhase::Runtime, "Error {error:?} should be in Runtime phase");
+    }
+}
+
 /// Test that all codegen errors map to `CodeGeneration` phase.
 #[test]
 fn test_codegen_errors_phase() {
@@ -385,7 +447,15 @@ fn test_codegen_errors_phase() {
 /// Test that all I/O errors map to System phase.
 #[test]
 fn test_io_errors_phase() {
-    let io_errors = [ErrorCode::E5001, ErrorCode::E5002, ErrorCode::E5003, ErrorCode::E5004, ErrorCode::E5005];
+    let io_errors = [
+        ErrorCode::E5001,
+        ErrorCode::E5002,
+        ErrorCode::E5003,
+        ErrorCode::E5004,
+        ErrorCode::E5005,
+        ErrorCode::E5006,
+        ErrorCode::E5007,
+    ];
 
     for error in io_errors {
         assert_eq!(error.phase(), CompilerPhase::System, "Error {error:?} should be in System phase");
@@ -446,6 +516,7 @@ fn test_parser_errors_severity_except_warning() {
 
 /// Test that all error codes have non-empty messages.
 #[test]
+#[allow(clippy::too_many_lines)]
 fn test_all_messages_non_empty() {
     let all_codes = [
         ErrorCode::E0001,
@@ -523,6 +594,31 @@ fn test_all_messages_non_empty() {
         ErrorCode::E5003,
         ErrorCode::E5004,
         ErrorCode::E5005,
+        ErrorCode::E0011,
+        ErrorCode::E0012,
+        ErrorCode::E0013,
+        ErrorCode::E2033,
+        ErrorCode::E2034,
+        ErrorCode::E2035,
+        ErrorCode::E2036,
+        ErrorCode::E2037,
+        ErrorCode::E2038,
+        ErrorCode::E2039,
+        ErrorCode::E2040,
+        ErrorCode::E2041,
+        ErrorCode::E2042,
+        ErrorCode::E2043,
+        ErrorCode::E2044,
+        ErrorCode::E3009,
+        ErrorCode::E3010,
+        ErrorCode::E3011,
+        ErrorCode::E3012,
+        ErrorCode::E3013,
+        ErrorCode::E3014,
+        ErrorCode::E3015,
+        ErrorCode::E3016,
+        ErrorCode::E5006,
+        ErrorCode::E5007,
     ];
 
     for code in all_codes {
@@ -805,6 +901,7 @@ fn test_phase_range_consistency() {
 
 /// Test that numeric codes are unique within their range.
 #[test]
+#[allow(clippy::too_many_lines)]
 fn test_unique_numeric_codes() {
     use std::collections::HashSet;
 
@@ -884,6 +981,31 @@ fn test_unique_numeric_codes() {
         ErrorCode::E5003,
         ErrorCode::E5004,
         ErrorCode::E5005,
+        ErrorCode::E0011,
+        ErrorCode::E0012,
+        ErrorCode::E0013,
+        ErrorCode::E2033,
+        ErrorCode::E2034,
+        ErrorCode::E2035,
+        ErrorCode::E2036,
+        ErrorCode::E2037,
+        ErrorCode::E2038,
+        ErrorCode::E2039,
+        ErrorCode::E2040,
+        ErrorCode::E2041,
+        ErrorCode::E2042,
+        ErrorCode::E2043,
+        ErrorCode::E2044,
+        ErrorCode::E3009,
+        ErrorCode::E3010,
+        ErrorCode::E3011,
+        ErrorCode::E3012,
+        ErrorCode::E3013,
+        ErrorCode::E3014,
+        ErrorCode::E3015,
+        ErrorCode::E3016,
+        ErrorCode::E5006,
+        ErrorCode::E5007,
     ];
 
     let mut numeric_set = HashSet::new();
@@ -1005,6 +1127,11 @@ fn test_phase_boundary_codes() {
 
     // Last semantic error
     assert_eq!(ErrorCode::E2032.phase(), CompilerPhase::Semantic);
+
+    // Codes appended to the lexer and semantic ranges
+    assert_eq!(ErrorCode::E0013.phase(), CompilerPhase::Lexer);
+    assert_eq!(ErrorCode::E2043.phase(), CompilerPhase::Semantic);
+    assert_eq!(ErrorCode::E2044.phase(), CompilerPhase::Semantic);
 }
 
 /// Test that cloning preserves all properties.
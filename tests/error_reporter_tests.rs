@@ -1,8 +1,10 @@
 use jsavrs::error::compile_error::CompileError;
-use jsavrs::error::error_reporter::ErrorReporter;
+use jsavrs::error::error_code::{CompilerPhase, ErrorCode, Severity};
+use jsavrs::error::error_reporter::{ErrorFormat, ErrorReporter};
 use jsavrs::lexer::{Lexer, lexer_tokenize_with_errors};
 use jsavrs::location::line_tracker::LineTracker;
 use jsavrs::utils::{create_span, strip_ansi_codes};
+use serde_json::{Value, json};
 use std::io;
 
 // Test: Errore Lexer su singola riga
@@ -319,3 +321,173 @@ Location: test:line 1:column 1 - line 1:column 2
 ";
     assert_eq!(stripped, expected);
 }
+
+#[test]
+fn errors_in_imported_files_show_their_source() {
+    let mut reporter = ErrorReporter::new(LineTracker::new("main.vn", "import \"lib.vn\"\nmain {\n}".to_string()));
+    reporter.add_source(LineTracker::new("lib.vn", "fun f(): i32 {\n    return true\n}".to_string()));
+
+    let errors = vec![
+        CompileError::TypeError {
+            code: None,
+            message: "Return type mismatch".into(),
+            span: create_span("lib.vn", 2, 12, 2, 16),
+            help: None,
+        },
+        CompileError::TypeError {
+            code: None,
+            message: "Unknown file".into(),
+            span: create_span("other.vn", 1, 1, 1, 7),
+            help: None,
+        },
+    ];
+
+    let report = strip_ansi_codes(&reporter.report_errors(errors));
+    let expected = "\
+ERROR TYPE: Return type mismatch
+Location: lib.vn:line 2:column 12 - line 2:column 16
+   2 │     return true
+     │            ^^^^
+ERROR TYPE: Unknown file
+Location: other.vn:line 1:column 1 - line 1:column 7
+   1 │ import \"lib.vn\"
+     │ ^^^^^^
+";
+    assert_eq!(report, expected);
+}
+
+fn sample_errors() -> Vec<CompileError> {
+    vec![
+        CompileError::TypeError {
+            code: Some(ErrorCode::E2009),
+            message: "Break statement outside loop".into(),
+            span: create_span("src/main.vn", 2, 19, 2, 24),
+            help: Some("Move it into a loop".to_string()),
+        },
+        CompileError::SyntaxError {
+            code: Some(ErrorCode::E1013),
+            message: "Missing semicolon".into(),
+            span: create_span("src/main.vn", 1, 1, 1, 5),
+            help: None,
+        },
+        CompileError::TypeError {
+            code: Some(ErrorCode::E2009),
+            message: "Break statement outside loop".into(),
+            span: create_span("src/main.vn", 1, 1, 1, 2),
+            help: None,
+        },
+        CompileError::IoError(io::Error::new(io::ErrorKind::NotFound, "missing.vn")),
+    ]
+}
+
+#[test]
+fn json_format_writes_one_object_per_line() {
+    let line_tracker = LineTracker::new("src/main.vn", "main {\n    var é: bool = break\n}".to_string());
+    let reporter = ErrorReporter::new(line_tracker).with_format(ErrorFormat::Json);
+
+    let report = reporter.report_errors(sample_errors());
+    assert!(!report.ends_with('\n'));
+    let lines: Vec<Value> = report.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(
+        lines[0],
+        json!({
+            "code": "E2009",
+            "severity": "error",
+            "phase": "semantic",
+            "message": "Break statement outside loop",
+            "help": "Move it into a loop",
+            "span": { "file": "src/main.vn", "start": { "line": 2, "column": 19 }, "end": { "line": 2, "column": 24 } },
+        })
+    );
+    assert_eq!(lines[1]["severity"], "warning");
+    assert_eq!(lines[1]["phase"], "parser");
+    assert_eq!(lines[1]["help"], Value::Null);
+    assert_eq!(
+        lines[3],
+        json!({
+            "code": null,
+            "severity": "error",
+            "phase": "system",
+            "message": "I/O error: missing.vn",
+            "help": null,
+            "span": null,
+        })
+    );
+}
+
+#[test]
+fn sarif_format_writes_a_log_for_code_scanning() {
+    let line_tracker = LineTracker::new("src/main.vn", "main {\n    var é: bool = break\n}".to_string());
+    let reporter = ErrorReporter::new(line_tracker).with_format(ErrorFormat::Sarif);
+
+    let log: Value = serde_json::from_str(&reporter.report_errors(sample_errors())).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "jsavrs");
+    assert_eq!(run["columnKind"], "unicodeCodePoints");
+
+    // One rule per code, in code order, referenced by index.
+    let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+    let ids: Vec<_> = rules.iter().map(|rule| rule["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, ["E1013", "E2009"]);
+    assert_eq!(rules[0]["defaultConfiguration"]["level"], "warning");
+    assert_eq!(rules[1]["shortDescription"]["text"], ErrorCode::E2009.message());
+    assert_eq!(rules[1]["help"]["text"], ErrorCode::E2009.suggestions().join("\n"));
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0]["ruleId"], "E2009");
+    assert_eq!(results[0]["ruleIndex"], 1);
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(results[0]["message"]["text"], "Break statement outside loop");
+    assert_eq!(results[0]["properties"], json!({ "phase": "semantic", "help": "Move it into a loop" }));
+    // The byte columns 19..24 of line 2 follow the two-byte `é`.
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"],
+        json!({
+            "artifactLocation": { "uri": "src/main.vn" },
+            "region": { "startLine": 2, "startColumn": 18, "endLine": 2, "endColumn": 23 },
+        })
+    );
+    assert_eq!(results[1]["level"], "warning");
+    assert!(results[3].get("ruleId").is_none() && results[3].get("locations").is_none());
+    assert_eq!(results[3]["message"]["text"], "I/O error: missing.vn");
+}
+
+#[test]
+fn sarif_uris_of_absolute_paths_are_file_uris() {
+    let reporter = ErrorReporter::new(LineTracker::new("/tmp/a.vn", String::new())).with_format(ErrorFormat::Sarif);
+    let uri = |path: &str| {
+        let errors = vec![CompileError::LexerError {
+            code: None,
+            message: "Invalid token".into(),
+            span: create_span(path, 1, 1, 1, 2),
+            help: None,
+        }];
+        let log: Value = serde_json::from_str(&reporter.report_errors(errors)).unwrap();
+        log["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"].clone()
+    };
+    assert_eq!(uri("/tmp/a.vn"), "file:///tmp/a.vn");
+    assert_eq!(uri("C:\\src\\a.vn"), "file:///C:/src/a.vn");
+    assert_eq!(uri("src\\a.vn"), "src/a.vn");
+}
+
+#[test]
+fn severity_and_phase_fall_back_to_the_variant() {
+    let errors = sample_errors();
+    assert_eq!(errors[1].severity(), Severity::Warning);
+    assert_eq!(errors[3].severity(), Severity::Error);
+    let runtime = CompileError::RuntimeError {
+        code: None,
+        message: "division by zero".into(),
+        span: create_span("a.vn", 1, 1, 1, 2),
+        help: None,
+    };
+    assert_eq!(runtime.phase(), CompilerPhase::Runtime);
+    assert_eq!(
+        CompileError::AsmGeneratorError { code: None, message: "x".into() }.phase(),
+        CompilerPhase::CodeGeneration
+    );
+    assert_eq!(errors[3].phase(), CompilerPhase::System);
+}
@@ -0,0 +1,150 @@
+use jsavrs::error::compile_error::CompileError;
+use jsavrs::fuzz::shrink::count_statements;
+use jsavrs::fuzz::{GeneratorConfig, ProgramGenerator, shrink};
+use jsavrs::lexer::{Lexer, lexer_tokenize_with_errors};
+use jsavrs::parser::ast::{Stmt, Type};
+use jsavrs::parser::jsav_parser::JsavParser;
+use jsavrs::printers::source_printer::print_source;
+use jsavrs::test_runner;
+use std::path::Path;
+
+fn parse(source: &str) -> Result<Vec<Stmt>, Vec<CompileError>> {
+    let mut lexer = Lexer::new("fuzz.vn", source);
+    let (tokens, mut errors) = lexer_tokenize_with_errors(&mut lexer);
+    let (statements, parse_errors) = JsavParser::new(&tokens).parse();
+    errors.extend(parse_errors);
+    if errors.is_empty() { Ok(statements) } else { Err(errors) }
+}
+
+/// Runs the front end and the IR generator on the printed program.
+fn compile(program: &[Stmt]) -> Result<(), Vec<CompileError>> {
+    test_runner::compile("fuzz.vn", &print_source(program)).map(drop)
+}
+
+fn contains(statements: &[Stmt], predicate: &impl Fn(&Stmt) -> bool) -> bool {
+    statements.iter().any(|stmt| predicate(stmt) || nested(stmt).iter().any(|body| contains(body, predicate)))
+}
+
+fn nested(stmt: &Stmt) -> Vec<&[Stmt]> {
+    match stmt {
+        Stmt::Function { body, .. }
+        | Stmt::MainFunction { body, .. }
+        | Stmt::While { body, .. }
+        | Stmt::For { body, .. }
+        | Stmt::Block { statements: body, .. } => vec![body],
+        Stmt::If { then_branch, else_branch, .. } => {
+            std::iter::once(then_branch.as_slice()).chain(else_branch.as_deref()).collect()
+        }
+        Stmt::Switch { cases, default, .. } => {
+            cases.iter().map(|case| case.body.as_slice()).chain(default.as_deref()).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+const fn is_loop(stmt: &Stmt) -> bool {
+    matches!(stmt, Stmt::While { .. } | Stmt::For { .. })
+}
+
+#[test]
+fn test_programs_are_reproducible_from_their_seed() {
+    let first = print_source(&ProgramGenerator::new(42).generate());
+    assert_eq!(first, print_source(&ProgramGenerator::new(42).generate()));
+    assert_ne!(first, print_source(&ProgramGenerator::new(43).generate()));
+}
+
+#[test]
+fn test_generated_programs_compile_and_print_back_to_themselves() {
+    for seed in 0..200 {
+        let program = ProgramGenerator::new(seed).generate();
+        let source = print_source(&program);
+        if let Err(errors) = compile(&program) {
+            panic!("seed {seed}: {errors:?}\n{source}");
+        }
+        let reparsed = parse(&source).unwrap();
+        assert_eq!(print_source(&reparsed), source, "seed {seed}");
+    }
+}
+
+#[test]
+fn test_generated_programs_cover_control_flow_calls_and_widths() {
+    let programs: Vec<_> = (0..50).map(|seed| ProgramGenerator::new(seed).generate()).collect();
+    let any = |predicate: &dyn Fn(&Stmt) -> bool| programs.iter().any(|program| contains(program, &predicate));
+
+    assert!(any(&|stmt| is_loop(stmt) && nested(stmt).iter().any(|body| contains(body, &is_loop))));
+    assert!(any(&|stmt| matches!(stmt, Stmt::Break { .. })));
+    assert!(any(&|stmt| matches!(stmt, Stmt::Continue { .. })));
+    assert!(any(&|stmt| matches!(stmt, Stmt::Switch { .. })));
+    assert!(any(&|stmt| matches!(stmt, Stmt::Function { .. })));
+    let sources: String = programs.iter().map(|program| print_source(program)).collect();
+    assert!(sources.lines().any(|line| line.contains("helper") && !line.contains("fun ")), "no calls generated");
+    for ty in ["i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64"] {
+        assert!(sources.contains(&format!(": {ty} = ")), "no {ty} variable generated");
+    }
+}
+
+#[test]
+fn test_config_bounds_program_size() {
+    let config =
+        GeneratorConfig { max_functions: 0, max_globals: 1, max_nesting: 0, max_block_len: 1, ..Default::default() };
+    for seed in 0..20 {
+        let program = ProgramGenerator::new(seed).with_config(config.clone()).generate();
+        assert!(matches!(program.as_slice(), [Stmt::VarDeclaration { .. }, Stmt::MainFunction { .. }]));
+        assert!(!contains(&program, &|stmt| is_loop(stmt) || matches!(stmt, Stmt::If { .. })));
+    }
+}
+
+#[test]
+fn test_shrink_reduces_to_a_minimal_reproducer() {
+    // Pretend that `break` crashes the compiler.
+    let crashes =
+        |program: &[Stmt]| compile(program).is_ok() && contains(program, &|stmt| matches!(stmt, Stmt::Break { .. }));
+    let seed = (0..1000).find(|&seed| crashes(&ProgramGenerator::new(seed).generate())).unwrap();
+    let program = ProgramGenerator::new(seed).generate();
+
+    let shrunk = shrink(program.clone(), crashes);
+    assert!(crashes(&shrunk));
+    assert!(count_statements(&shrunk) < count_statements(&program));
+    // `main`, its block, the loop, and at most a counter declaration, a block
+    // around the loop and the break.
+    assert!(count_statements(&shrunk) <= 6, "{}", print_source(&shrunk));
+    assert_eq!(print_source(&shrink(shrunk.clone(), crashes)), print_source(&shrunk));
+}
+
+#[test]
+fn test_shrink_keeps_a_program_that_only_fails_whole() {
+    let program = ProgramGenerator::new(3).generate();
+    let whole = count_statements(&program);
+    let shrunk = shrink(program, |candidate| count_statements(candidate) == whole);
+    assert_eq!(count_statements(&shrunk), whole);
+}
+
+#[test]
+fn test_vn_files_print_back_to_themselves() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("vn_files");
+    let mut printed = 0;
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "vn") {
+            continue;
+        }
+        let Ok(statements) = parse(&std::fs::read_to_string(&path).unwrap()) else { continue };
+        let source = print_source(&statements);
+        let reparsed = parse(&source).unwrap_or_else(|errors| panic!("{}: {errors:?}\n{source}", path.display()));
+        assert_eq!(print_source(&reparsed), source, "{}", path.display());
+        printed += 1;
+    }
+    assert!(printed >= 8, "only {printed} files were printed");
+}
+
+#[test]
+fn test_printed_types_and_literals_keep_their_meaning() {
+    let source = "var a: i64[2][3] = {{1, 2, 3}, {4, 5, 6}}\nvar b: u64 = 7u\nvar c: f32 = 1.5f\nvar s: string = \"a\\\"b\\n\"\nvar d: char = '\\''\n";
+    let statements = parse(source).unwrap();
+    let printed = print_source(&statements);
+    assert_eq!(printed, source);
+    let Stmt::VarDeclaration { type_annotation: Type::Array(inner, _), .. } = &parse(&printed).unwrap()[0] else {
+        panic!("not an array declaration");
+    };
+    assert!(matches!(**inner, Type::Array(..)));
+}
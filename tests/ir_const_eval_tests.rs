@@ -0,0 +1,106 @@
+use jsavrs::ir::const_eval::{EvalError, eval_binary, eval_cast, eval_unary};
+use jsavrs::ir::{IrBinaryOp, IrLiteralValue, IrType, IrUnaryOp};
+
+#[test]
+fn test_integer_arithmetic_wraps_to_the_operand_width() {
+    assert_eq!(
+        eval_binary(IrBinaryOp::Add, &IrLiteralValue::I8(120), &IrLiteralValue::I8(10)),
+        Ok(IrLiteralValue::I8(-126))
+    );
+    assert_eq!(
+        eval_binary(IrBinaryOp::Subtract, &IrLiteralValue::U16(1), &IrLiteralValue::U16(2)),
+        Ok(IrLiteralValue::U16(u16::MAX))
+    );
+    assert_eq!(
+        eval_binary(IrBinaryOp::Multiply, &IrLiteralValue::I64(i64::MAX), &IrLiteralValue::I64(2)),
+        Ok(IrLiteralValue::I64(-2))
+    );
+    assert_eq!(
+        eval_binary(IrBinaryOp::ShiftLeft, &IrLiteralValue::I32(1), &IrLiteralValue::I32(33)),
+        Ok(IrLiteralValue::I32(2))
+    );
+    assert_eq!(
+        eval_binary(IrBinaryOp::ShiftRight, &IrLiteralValue::I8(-8), &IrLiteralValue::I8(1)),
+        Ok(IrLiteralValue::I8(-4))
+    );
+}
+
+#[test]
+fn test_division_and_comparison_follow_signedness() {
+    assert_eq!(
+        eval_binary(IrBinaryOp::Divide, &IrLiteralValue::I32(-7), &IrLiteralValue::I32(2)),
+        Ok(IrLiteralValue::I32(-3))
+    );
+    assert_eq!(
+        eval_binary(IrBinaryOp::Modulo, &IrLiteralValue::U8(250), &IrLiteralValue::U8(7)),
+        Ok(IrLiteralValue::U8(5))
+    );
+    assert_eq!(
+        eval_binary(IrBinaryOp::Less, &IrLiteralValue::U64(u64::MAX), &IrLiteralValue::U64(1)),
+        Ok(IrLiteralValue::Bool(false))
+    );
+    assert_eq!(
+        eval_binary(IrBinaryOp::Less, &IrLiteralValue::I64(-1), &IrLiteralValue::I64(1)),
+        Ok(IrLiteralValue::Bool(true))
+    );
+}
+
+#[test]
+fn test_traps_and_mismatched_operands_are_errors() {
+    assert_eq!(
+        eval_binary(IrBinaryOp::Divide, &IrLiteralValue::I32(1), &IrLiteralValue::I32(0)),
+        Err(EvalError::DivisionByZero)
+    );
+    assert_eq!(
+        eval_binary(IrBinaryOp::Modulo, &IrLiteralValue::I64(i64::MIN), &IrLiteralValue::I64(-1)),
+        Err(EvalError::Overflow)
+    );
+    assert!(matches!(
+        eval_binary(IrBinaryOp::Add, &IrLiteralValue::I32(1), &IrLiteralValue::I64(1)),
+        Err(EvalError::Unsupported(_))
+    ));
+    assert!(matches!(
+        eval_binary(IrBinaryOp::Modulo, &IrLiteralValue::F64(1.0), &IrLiteralValue::F64(2.0)),
+        Err(EvalError::Unsupported(_))
+    ));
+    assert_eq!(EvalError::DivisionByZero.to_string(), "division by zero");
+}
+
+#[test]
+fn test_float_operations_round_to_the_operand_type() {
+    assert_eq!(
+        eval_binary(IrBinaryOp::Divide, &IrLiteralValue::F32(1.0), &IrLiteralValue::F32(3.0)),
+        Ok(IrLiteralValue::F32(1.0 / 3.0))
+    );
+    assert_eq!(
+        eval_binary(IrBinaryOp::NotEqual, &IrLiteralValue::F64(f64::NAN), &IrLiteralValue::F64(f64::NAN)),
+        Ok(IrLiteralValue::Bool(true))
+    );
+    assert_eq!(
+        eval_binary(IrBinaryOp::Equal, &IrLiteralValue::F64(f64::NAN), &IrLiteralValue::F64(f64::NAN)),
+        Ok(IrLiteralValue::Bool(false))
+    );
+}
+
+#[test]
+fn test_unary_operators() {
+    assert_eq!(eval_unary(IrUnaryOp::Negate, &IrLiteralValue::I8(i8::MIN)), Ok(IrLiteralValue::I8(i8::MIN)));
+    assert_eq!(eval_unary(IrUnaryOp::Not, &IrLiteralValue::U8(0x0F)), Ok(IrLiteralValue::U8(0xF0)));
+    assert_eq!(eval_unary(IrUnaryOp::Not, &IrLiteralValue::Bool(true)), Ok(IrLiteralValue::Bool(false)));
+    assert_eq!(eval_unary(IrUnaryOp::Negate, &IrLiteralValue::F64(0.0)), Ok(IrLiteralValue::F64(-0.0)));
+    assert!(eval_unary(IrUnaryOp::Not, &IrLiteralValue::F32(1.0)).is_err());
+    assert!(eval_unary(IrUnaryOp::Not, &IrLiteralValue::Char('a')).is_err());
+}
+
+#[test]
+fn test_casts_match_the_backend_conversions() {
+    assert_eq!(eval_cast(&IrLiteralValue::I64(300), &IrType::I8), Ok(IrLiteralValue::I8(44)));
+    assert_eq!(eval_cast(&IrLiteralValue::I8(-1), &IrType::U32), Ok(IrLiteralValue::U32(u32::MAX)));
+    assert_eq!(eval_cast(&IrLiteralValue::F64(-2.9), &IrType::I32), Ok(IrLiteralValue::I32(-2)));
+    assert_eq!(eval_cast(&IrLiteralValue::F64(f64::NAN), &IrType::I64), Ok(IrLiteralValue::I64(i64::MIN)));
+    assert_eq!(eval_cast(&IrLiteralValue::U8(3), &IrType::F32), Ok(IrLiteralValue::F32(3.0)));
+    assert_eq!(eval_cast(&IrLiteralValue::F32(0.5), &IrType::Bool), Ok(IrLiteralValue::Bool(true)));
+    assert_eq!(eval_cast(&IrLiteralValue::U32(98), &IrType::Char), Ok(IrLiteralValue::Char('b')));
+    assert!(eval_cast(&IrLiteralValue::U32(0xD800), &IrType::Char).is_err());
+    assert!(eval_cast(&IrLiteralValue::I32(1), &IrType::String).is_err());
+}
@@ -1,4 +1,6 @@
-use jsavrs::ir::data_layout::{DataLayout, Endianness, Mangling, ParsedDataLayout, PointerLayout};
+use jsavrs::ir::IrType;
+use jsavrs::ir::data_layout::{DataLayout, Endianness, Mangling, ParsedDataLayout, PointerLayout, StructLayout};
+use jsavrs::location::source_span::SourceSpan;
 
 // ============================================================================
 // BASIC ENUM TESTS
@@ -1007,3 +1009,39 @@ fn test_parse_with_special_chars_in_unknown_spec() {
     let result = ParsedDataLayout::parse("e-???:test-m:e");
     assert!(result.is_ok());
 }
+
+// ============================================================================
+// TYPE SIZE AND STRUCT LAYOUT TESTS
+// ============================================================================
+
+fn fields(types: &[IrType]) -> Vec<(String, IrType)> {
+    types.iter().enumerate().map(|(i, ty)| (format!("f{i}"), ty.clone())).collect()
+}
+
+#[test]
+fn test_struct_layout_pads_fields_to_their_alignment() {
+    let layout = DataLayout::LinuxX86_64.parsed();
+    let struct_layout = layout.struct_layout(&fields(&[IrType::I8, IrType::I64, IrType::I16, IrType::F32]));
+    assert_eq!(struct_layout, StructLayout { field_offsets: vec![0, 8, 16, 20], size: 24, align: 8 });
+
+    let nested = IrType::Struct("Inner".into(), fields(&[IrType::Bool, IrType::I32]), SourceSpan::default());
+    let struct_layout =
+        layout.struct_layout(&fields(&[IrType::I8, nested.clone(), IrType::Pointer(Box::new(IrType::I8))]));
+    assert_eq!(struct_layout.field_offsets, [0, 4, 16]);
+    assert_eq!(layout.size_of(&nested), 8);
+    assert_eq!(layout.abi_align_of(&nested), 4);
+    assert_eq!(layout.size_of(&IrType::Array(Box::new(nested), 3)), 24);
+}
+
+#[test]
+fn test_layout_defaults_apply_when_the_string_omits_a_type() {
+    // Without `i64:64`, LLVM aligns 64-bit integers to 32 bits.
+    let layout = ParsedDataLayout::parse("e-p:32:32").unwrap();
+    assert_eq!(layout.abi_align_of(&IrType::I64), 4);
+    assert_eq!(layout.size_of(&IrType::String), 4);
+    assert_eq!(layout.struct_layout(&fields(&[IrType::I8, IrType::I64])).field_offsets, [0, 4]);
+
+    let aligned = ParsedDataLayout::parse("e-i64:64-a:64").unwrap();
+    let struct_layout = aligned.struct_layout(&fields(&[IrType::I8]));
+    assert_eq!((struct_layout.size, struct_layout.align), (8, 8));
+}
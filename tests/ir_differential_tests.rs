@@ -0,0 +1,234 @@
+use jsavrs::error::compile_error::CompileError;
+use jsavrs::error::error_code::ErrorCode;
+use jsavrs::fuzz::{ProgramGenerator, shrink};
+use jsavrs::ir::interpreter::RuntimeValue;
+use jsavrs::ir::optimizer::differential::{Behaviour, CheckError, DEFAULT_STEP_LIMIT, Outcome, check_pipeline};
+use jsavrs::ir::optimizer::{OptLevel, PassManager, phase_by_name};
+use jsavrs::ir::{InstructionKind, IrBinaryOp, IrLiteralValue, Module, Phase};
+use jsavrs::parser::ast::Stmt;
+use jsavrs::printers::source_printer::print_source;
+use jsavrs::test_runner::compile;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+mod common;
+
+use common::{build_module, build_module_at};
+
+/// Generous for the test programs, and low enough to stop infinite loops quickly.
+const STEP_LIMIT: u64 = 100_000;
+
+fn assert_preserved(module: &Module, name: &str) -> Behaviour {
+    let mut behaviour = None;
+    for level in [OptLevel::O1, OptLevel::O2, OptLevel::O3] {
+        match check_pipeline(module, &mut PassManager::for_level(level, false), STEP_LIMIT) {
+            Ok(observed) => behaviour = Some(observed),
+            Err(CheckError::Diverged(divergence)) => {
+                panic!(
+                    "{name} at {level:?}: {divergence}\n--- before ---\n{}\n--- after ---\n{}",
+                    divergence.before, divergence.after
+                )
+            }
+            Err(CheckError::Pipeline(errors)) => panic!("{name} at {level:?}: {errors:?}"),
+        }
+    }
+    behaviour.unwrap()
+}
+
+/// Pass that turns every addition into a subtraction.
+struct AddToSub;
+
+impl Phase for AddToSub {
+    fn name(&self) -> &'static str {
+        "Add To Sub"
+    }
+
+    fn run(&mut self, ir: &mut Module) {
+        for function in &mut ir.functions {
+            for block in function.cfg.blocks_mut() {
+                for inst in &mut block.instructions {
+                    if let InstructionKind::Binary { op: op @ IrBinaryOp::Add, .. } = &mut inst.kind {
+                        *op = IrBinaryOp::Subtract;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_vn_files_behave_the_same_at_every_level() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("vn_files");
+    let mut checked = 0;
+    let mut paths: Vec<_> = std::fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    paths.sort();
+    for path in paths.iter().filter(|path| path.extension().is_some_and(|ext| ext == "vn")) {
+        let source = std::fs::read_to_string(path).unwrap();
+        let name = path.display().to_string();
+        // Some files exercise front-end diagnostics and never reach the optimizer.
+        let Some(module) = compile(&name, &source).ok() else { continue };
+        assert_preserved(&module, &name);
+        checked += 1;
+    }
+    assert!(checked >= 8, "only {checked} files were checked");
+}
+
+#[test]
+fn test_run_cases_keep_their_result_and_globals_at_every_level() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/vn/run");
+    let mut paths: Vec<_> = std::fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    paths.sort();
+    let mut checked = 0;
+    for path in paths.iter().filter(|path| path.extension().is_some_and(|ext| ext == "vn")) {
+        let name = path.display().to_string();
+        let module = build_module_at(&name, &std::fs::read_to_string(path).unwrap());
+        let behaviour = assert_preserved(&module, &name);
+        // The comparison only means something if the whole final state is observed.
+        assert_ne!(behaviour.outcome, Outcome::StepLimit, "{name}");
+        assert!(behaviour.globals.iter().all(|(_, value)| value.is_some()), "{name}: {behaviour}");
+        checked += 1;
+    }
+    assert!(checked >= 7, "only {checked} files were checked");
+}
+
+#[test]
+fn test_behaviour_includes_result_traps_and_globals() {
+    let module = build_module("var a: i64 = 1\nvar b: bool = false\nmain { a = a + 41\nb = true }");
+    let behaviour = assert_preserved(&module, "globals");
+    assert_eq!(behaviour.outcome, Outcome::Returned(RuntimeValue::Scalar(IrLiteralValue::I32(0))));
+    let globals: Vec<_> = behaviour.globals.iter().map(|(name, value)| (name.as_ref(), value.clone())).collect();
+    assert_eq!(
+        globals,
+        [
+            ("a", Some(RuntimeValue::Scalar(IrLiteralValue::I64(42)))),
+            ("b", Some(RuntimeValue::Scalar(IrLiteralValue::Bool(true)))),
+        ]
+    );
+
+    let module = build_module("var table: i64[2] = {1, 2}\nmain { table[1] = table[0] + 5 }");
+    let behaviour = assert_preserved(&module, "array globals");
+    let globals: Vec<_> = behaviour.globals.iter().map(|(name, value)| (name.as_ref(), value.clone())).collect();
+    assert_eq!(
+        globals,
+        [
+            ("table[0]", Some(RuntimeValue::Scalar(IrLiteralValue::I64(1)))),
+            ("table[1]", Some(RuntimeValue::Scalar(IrLiteralValue::I64(6)))),
+        ]
+    );
+
+    let module = build_module("var zero: i64 = 0\nvar r: i64 = 0\nmain { r = 7 / zero }");
+    let behaviour = assert_preserved(&module, "trap");
+    assert!(
+        matches!(&behaviour.outcome, Outcome::Trapped { code: ErrorCode::E3014, message } if message == "division by zero"),
+        "{behaviour}"
+    );
+
+    let module = build_module("main { while (true) { } }");
+    assert_eq!(assert_preserved(&module, "loop").outcome, Outcome::StepLimit);
+}
+
+#[test]
+fn test_first_pass_changing_behaviour_is_reported() {
+    let module =
+        build_module("var total: i64 = 0\nfun add(a: i64, b: i64): i64 { return a + b }\nmain { total = add(2, 3) }");
+    let mut manager = PassManager::new();
+    manager.add_pass("sccp", phase_by_name("sccp", false).unwrap());
+    manager.add_pass("dce", phase_by_name("dce", false).unwrap());
+    manager.add_pass("add-to-sub", Box::new(AddToSub));
+    manager.add_pass("dce", phase_by_name("dce", false).unwrap());
+
+    let Err(CheckError::Diverged(divergence)) = check_pipeline(&module, &mut manager, DEFAULT_STEP_LIMIT) else {
+        panic!("the broken pass was not detected");
+    };
+    assert_eq!(divergence.pass, "add-to-sub");
+    assert_eq!(divergence.position, 3);
+    assert_eq!(divergence.expected.globals[0].1, Some(RuntimeValue::Scalar(IrLiteralValue::I64(5))));
+    assert_eq!(divergence.actual.globals[0].1, Some(RuntimeValue::Scalar(IrLiteralValue::I64(-1))));
+    assert!(divergence.before.contains("add %a"), "{}", divergence.before);
+    assert!(divergence.after.contains("sub %a"), "{}", divergence.after);
+
+    let errors: Vec<CompileError> = CheckError::Diverged(divergence).into();
+    assert_eq!(errors[0].error_code(), Some(&ErrorCode::E3016));
+    assert!(errors[0].message().unwrap().starts_with("pass 'add-to-sub' (pass 3 of the pipeline)"));
+    assert!(errors[0].help().unwrap().contains("--passes=add-to-sub"));
+}
+
+#[test]
+fn test_verifier_errors_stop_the_check() {
+    let module = build_module("var total: i64 = 0\nmain { total = total + 1 }");
+    let mut manager = PassManager::new();
+    manager.set_verify(true);
+    let mut broken = module.clone();
+    broken.functions[0].cfg.blocks_mut().next().unwrap().instructions.swap(0, 1);
+    assert!(matches!(check_pipeline(&broken, &mut manager, 1000), Err(CheckError::Pipeline(_))));
+    assert!(check_pipeline(&module, &mut manager, 1000).is_ok());
+}
+
+#[test]
+fn test_variable_declared_in_a_folded_loop() {
+    // `v` is out of scope at the loop header, whose phi for it used to lack
+    // the edge from the entry block and broke DCE once SCCP removed the loop.
+    let module = build_module(
+        "var g: u32 = 16u32\nfun two(): u16 { return 2u16 }\nfun h(): u8 {
+            var k: i32 = 0i32
+            while (k < 0i32) {
+                var v: u64 = 4u
+                if (two() != 4u16) { }
+                v = v
+                if (g == 3u32) { continue }
+            }
+            return 16u8
+        }
+        main { g = h() as u32 }",
+    );
+    let behaviour = assert_preserved(&module, "folded loop");
+    assert_eq!(behaviour.globals[0].1, Some(RuntimeValue::Scalar(IrLiteralValue::U32(16))));
+}
+
+/// Describes how the optimizer mishandled a generated program: the pass that
+/// changed its behaviour, the pipeline errors, the panic message, or an
+/// unexpected outcome of the unoptimized program.
+fn failing_pass(program: &[Stmt]) -> Option<String> {
+    let module = compile("fuzz.vn", &print_source(program)).ok()?;
+    // The generator only builds programs that terminate and whose only trap
+    // is an overflowing division. Any other outcome means the unoptimized IR
+    // is already wrong, and comparing against it would prove nothing.
+    match Behaviour::observe(&module, STEP_LIMIT).outcome {
+        Outcome::Returned(_) => {}
+        Outcome::Trapped { code: ErrorCode::E3014, message } if message == "arithmetic overflow" => {}
+        outcome => return Some(format!("unoptimized: {outcome}")),
+    }
+    [OptLevel::O1, OptLevel::O2, OptLevel::O3].into_iter().find_map(|level| {
+        let checked = panic::catch_unwind(AssertUnwindSafe(|| {
+            check_pipeline(&module, &mut PassManager::for_level(level, false), STEP_LIMIT)
+        }));
+        match checked {
+            Ok(Ok(_)) => None,
+            Ok(Err(CheckError::Diverged(divergence))) => Some(format!("{level:?}: {divergence}")),
+            Ok(Err(CheckError::Pipeline(errors))) => Some(format!("{level:?}: {errors:?}")),
+            Err(payload) => Some(format!(
+                "{level:?}: panicked: {}",
+                payload.downcast_ref::<String>().map_or("<non-string payload>", String::as_str)
+            )),
+        }
+    })
+}
+
+/// Failures are the same when they differ only in numbers, such as block labels.
+fn same_failure(failure: &str, other: &str) -> bool {
+    let strip = |text: &str| text.lines().next().unwrap_or_default().replace(|c: char| c.is_ascii_digit(), "");
+    strip(failure) == strip(other)
+}
+
+#[test]
+fn test_random_programs_behave_the_same_after_every_pass() {
+    for seed in 0..40 {
+        let program = ProgramGenerator::new(seed).generate();
+        if let Some(failure) = failing_pass(&program) {
+            let shrunk = shrink(program, |candidate| {
+                failing_pass(candidate).is_some_and(|other| same_failure(&failure, &other))
+            });
+            panic!("seed {seed}: {failure}\nreduced program:\n{}", print_source(&shrunk));
+        }
+    }
+}
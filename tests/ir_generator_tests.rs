@@ -1,8 +1,9 @@
 use jsavrs::ir::generator::IrGenerator;
 use jsavrs::ir::{
-    InstructionKind, IrBinaryOp, IrConstantValue, IrLiteralValue, IrType, IrUnaryOp, TerminatorKind, ValueKind,
+    CastKind, InstructionKind, IrBinaryOp, IrConstantValue, IrLiteralValue, IrType, IrUnaryOp, TerminatorKind,
+    ValueKind,
 };
-use jsavrs::parser::ast::{BinaryOp, Expr, LiteralValue, Parameter, Stmt, Type, UnaryOp};
+use jsavrs::parser::ast::{BinaryOp, EnumVariant, Expr, LiteralValue, Parameter, Stmt, SwitchCase, Type, UnaryOp};
 use jsavrs::tokens::number::Number;
 use jsavrs::utils::*;
 use std::sync::Arc;
@@ -902,8 +903,6 @@ fn test_generate_binary_all_operations() {
         (BinaryOp::LessEqual, IrBinaryOp::LessEqual),
         (BinaryOp::Greater, IrBinaryOp::Greater),
         (BinaryOp::GreaterEqual, IrBinaryOp::GreaterEqual),
-        (BinaryOp::And, IrBinaryOp::And),
-        (BinaryOp::Or, IrBinaryOp::Or),
         (BinaryOp::BitwiseAnd, IrBinaryOp::BitwiseAnd),
         (BinaryOp::BitwiseOr, IrBinaryOp::BitwiseOr),
         (BinaryOp::BitwiseXor, IrBinaryOp::BitwiseXor),
@@ -941,6 +940,264 @@ fn test_generate_binary_all_operations() {
     }
 }
 
+#[test]
+fn test_generate_short_circuit_operators() {
+    for (op, rhs_label, end_label, true_label, decided) in [
+        (BinaryOp::And, "and_rhs_1", "and_end_2", "and_rhs_1", "false"),
+        (BinaryOp::Or, "or_rhs_1", "or_end_2", "or_end_2", "true"),
+    ] {
+        let ast = vec![function_declaration(
+            "test".into(),
+            vec![
+                Parameter { name: "a".into(), type_annotation: Type::Bool, span: dummy_span() },
+                Parameter { name: "b".into(), type_annotation: Type::Bool, span: dummy_span() },
+            ],
+            Type::Bool,
+            vec![Stmt::Return {
+                value: Some(binary_expr(variable_expr("a"), op, variable_expr("b"))),
+                span: dummy_span(),
+            }],
+        )];
+
+        let mut generator = IrGenerator::new_without_ssa();
+        let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+        assert!(ir_errors.is_empty(), "{ir_errors:?}");
+        let func = &module.functions[0];
+        assert_eq!(func.cfg.blocks().count(), 3);
+
+        // The right operand is only evaluated when the left one does not decide the result
+        let entry_block = func.cfg.get_block("entry_test").unwrap();
+        assert!(entry_block.instructions.is_empty());
+        match &entry_block.terminator().kind {
+            TerminatorKind::ConditionalBranch { true_label: taken, .. } => assert_eq!(taken.as_ref(), true_label),
+            other => panic!("Expected conditional branch, got {other:?}"),
+        }
+        let rhs_block = func.cfg.get_block(rhs_label).unwrap();
+        assert!(
+            matches!(&rhs_block.terminator().kind, TerminatorKind::Branch { label } if label.as_ref() == end_label)
+        );
+
+        let end_block = func.cfg.get_block(end_label).unwrap();
+        assert_eq!(
+            end_block.instructions[0].to_string(),
+            format!("t0 =  phi bool [ [ {decided}, entry_test ], [ %b (b), {rhs_label} ] ]")
+        );
+    }
+}
+
+#[test]
+fn test_generate_switch() {
+    let case = |value: Expr, body: Vec<Stmt>| SwitchCase { value, body, span: dummy_span() };
+    let ast = vec![function_declaration(
+        "test".into(),
+        vec![Parameter { name: "x".into(), type_annotation: Type::I32, span: dummy_span() }],
+        Type::I32,
+        vec![
+            Stmt::Switch {
+                scrutinee: variable_expr("x"),
+                cases: vec![
+                    case(num_lit_i32(1), vec![Stmt::Return { value: Some(num_lit_i32(10)), span: dummy_span() }]),
+                    case(num_lit_i32(-2), vec![Stmt::Break { span: dummy_span() }]),
+                ],
+                default: Some(vec![Stmt::Expression { expr: variable_expr("x") }]),
+                span: dummy_span(),
+            },
+            Stmt::Return { value: Some(num_lit_i32(0)), span: dummy_span() },
+        ],
+    )];
+
+    let mut generator = IrGenerator::new_without_ssa();
+    let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+    let func = &module.functions[0];
+
+    let entry_block = func.cfg.get_block("entry_test").unwrap();
+    match &entry_block.terminator().kind {
+        TerminatorKind::Switch { value, ty, default_label, cases } => {
+            assert_eq!(value.to_string(), "%x (x)");
+            assert_eq!(*ty, IrType::I32);
+            assert_eq!(default_label.as_ref(), "switch_default_2");
+            let cases: Vec<_> = cases.iter().map(|(value, label)| (value.to_string(), label.as_ref())).collect();
+            assert_eq!(cases, [("1i32".to_string(), "switch_case_3"), ("-2i32".to_string(), "switch_case_4")]);
+        }
+        other => panic!("Expected switch, got {other:?}"),
+    }
+
+    // Arms do not fall through: `break` and the end of an arm both jump past the switch
+    for label in ["switch_case_4", "switch_default_2"] {
+        let block = func.cfg.get_block(label).unwrap();
+        assert!(
+            matches!(&block.terminator().kind, TerminatorKind::Branch { label } if label.as_ref() == "switch_end_1"),
+            "{label}: {:?}",
+            block.terminator()
+        );
+    }
+    assert!(matches!(func.cfg.get_block("switch_case_3").unwrap().terminator().kind, TerminatorKind::Return { .. }));
+}
+
+#[test]
+fn test_generate_switch_reports_duplicate_constant_cases() {
+    let case = |value: Expr| SwitchCase { value, body: vec![], span: dummy_span() };
+    let ast = vec![function_declaration(
+        "test".into(),
+        vec![Parameter { name: "x".into(), type_annotation: Type::I32, span: dummy_span() }],
+        Type::Void,
+        vec![
+            var_declaration(vec!["ONE".into()], Type::I32, false, vec![num_lit_i32(1)]),
+            Stmt::Switch {
+                scrutinee: variable_expr("x"),
+                cases: vec![case(num_lit_i32(1)), case(variable_expr("ONE"))],
+                default: None,
+                span: dummy_span(),
+            },
+        ],
+    )];
+
+    let mut generator = IrGenerator::new_without_ssa();
+    let (_module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert_eq!(ir_errors.len(), 1, "{ir_errors:?}");
+    assert_eq!(ir_errors[0].message(), Some("Duplicate case value 1i32 in switch"));
+}
+
+#[test]
+fn test_generate_enum_variants_as_constants() {
+    let variant = |name: &str, value: Option<Expr>| EnumVariant { name: name.into(), value, span: dummy_span() };
+    let level = Type::Custom("Level".into());
+    let ast = vec![
+        function_declaration(
+            "test".into(),
+            vec![Parameter { name: "l".into(), type_annotation: level.clone(), span: dummy_span() }],
+            level,
+            vec![Stmt::Return { value: Some(field_access_expr(variable_expr("Level"), "Mid")), span: dummy_span() }],
+        ),
+        // Declared after its first use
+        Stmt::Enum {
+            name: "Level".into(),
+            underlying_type: Type::U8,
+            variants: vec![
+                variant("Low", Some(num_lit_i64(3))),
+                variant("Mid", None),
+                variant("High", Some(binary_expr(num_lit_i64(5), BinaryOp::Multiply, num_lit_i64(2)))),
+            ],
+            span: dummy_span(),
+        },
+    ];
+
+    let mut generator = IrGenerator::new_without_ssa();
+    let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+    let func = &module.functions[0];
+    assert_eq!(func.parameters[0].ty, IrType::U8);
+    assert_eq!(func.return_type, IrType::U8);
+    let entry_block = func.cfg.get_block("entry_test").unwrap();
+    assert!(entry_block.instructions.is_empty());
+    assert_return_literal!(entry_block, &IrType::U8, IrLiteralValue::U8(4));
+}
+
+#[test]
+fn test_generate_enum_reports_duplicate_discriminants_and_unknown_variants() {
+    let variant = |name: &str, value: Option<Expr>| EnumVariant { name: name.into(), value, span: dummy_span() };
+    let ast = vec![
+        var_declaration(vec!["BASE".into()], Type::I32, false, vec![num_lit_i32(1)]),
+        Stmt::Enum {
+            name: "Color".into(),
+            underlying_type: Type::I32,
+            variants: vec![variant("Red", None), variant("Green", Some(variable_expr("BASE"))), variant("Blue", None)],
+            span: dummy_span(),
+        },
+        Stmt::Enum {
+            name: "Dup".into(),
+            underlying_type: Type::I32,
+            variants: vec![
+                variant("A", None),
+                variant("B", Some(binary_expr(variable_expr("BASE"), BinaryOp::Subtract, num_lit_i32(1)))),
+            ],
+            span: dummy_span(),
+        },
+        function_declaration(
+            "test".into(),
+            vec![],
+            Type::Custom("Color".into()),
+            vec![Stmt::Return { value: Some(field_access_expr(variable_expr("Color"), "Purple")), span: dummy_span() }],
+        ),
+    ];
+
+    let mut generator = IrGenerator::new_without_ssa();
+    let (_module, ir_errors) = generator.generate(ast, "test_file.vn");
+    let messages: Vec<_> = ir_errors.iter().map(|e| e.message().unwrap()).collect();
+    assert_eq!(
+        messages,
+        ["Discriminant 0i32 of variant 'B' is already used by 'A'", "Enum 'Color' has no variant 'Purple'"]
+    );
+}
+
+#[test]
+fn test_ssa_renames_branch_conditions_and_merged_values() {
+    // var flag: bool = b; var r: i32 = 0; if (flag) { r = 1 } if (flag) { r = r + 2 } return r
+    let if_flag = |body: Expr| Stmt::If {
+        condition: variable_expr("flag"),
+        then_branch: vec![Stmt::Expression { expr: assign_expr(variable_expr("r"), body) }],
+        else_branch: None,
+        span: dummy_span(),
+    };
+    let ast = vec![function_declaration(
+        "test".into(),
+        vec![Parameter { name: "b".into(), type_annotation: Type::Bool, span: dummy_span() }],
+        Type::I32,
+        vec![
+            var_declaration(vec!["flag".into()], Type::Bool, true, vec![variable_expr("b")]),
+            var_declaration(vec!["r".into()], Type::I32, true, vec![num_lit_i32(0)]),
+            if_flag(num_lit_i32(1)),
+            if_flag(binary_expr(variable_expr("r"), BinaryOp::Add, num_lit_i32(2))),
+            Stmt::Return { value: Some(variable_expr("r")), span: dummy_span() },
+        ],
+    )];
+
+    let mut generator = IrGenerator::new();
+    let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+    let func = &module.functions[0];
+
+    let value_of = |label: &str, index: usize| {
+        let block = func.cfg.get_block(label).unwrap();
+        match &block.instructions[index].kind {
+            InstructionKind::Store { dest, .. } => dest.clone(),
+            _ => block.instructions[index].result.clone().unwrap(),
+        }
+    };
+    let flag = value_of("entry_test", 1);
+    let (r_entry, r_then_1, r_merge_3, r_then_4, r_merge_6) = (
+        value_of("entry_test", 3),
+        value_of("then_1", 0),
+        value_of("merge_3", 0),
+        value_of("then_4", 1),
+        value_of("merge_6", 0),
+    );
+
+    // Both branches read the stored flag rather than its stack slot
+    for label in ["entry_test", "merge_3"] {
+        match &func.cfg.get_block(label).unwrap().terminator().kind {
+            TerminatorKind::ConditionalBranch { condition, .. } => assert_eq!(condition.id, flag.id),
+            other => panic!("Expected conditional branch, got {other:?}"),
+        }
+    }
+    // Each phi merges the value reaching it along every edge
+    let incoming = |label: &str| match &func.cfg.get_block(label).unwrap().instructions[0].kind {
+        InstructionKind::Phi { incoming, .. } => {
+            let mut incoming: Vec<_> = incoming.iter().map(|(value, pred)| (value.id, pred.clone())).collect();
+            incoming.sort_by(|a, b| a.1.cmp(&b.1));
+            incoming
+        }
+        other => panic!("Expected phi, got {other:?}"),
+    };
+    assert_eq!(incoming("merge_3"), [(r_entry.id, "else_2".to_string()), (r_then_1.id, "then_1".to_string())]);
+    assert_eq!(incoming("merge_6"), [(r_merge_3.id, "else_5".to_string()), (r_then_4.id, "then_4".to_string())]);
+    match &func.cfg.get_block("merge_6").unwrap().terminator().kind {
+        TerminatorKind::Return { value, .. } => assert_eq!(value.id, r_merge_6.id),
+        other => panic!("Expected return, got {other:?}"),
+    }
+}
+
 #[test]
 fn test_generate_unary_expression() {
     let test_cases = vec![(UnaryOp::Negate, IrUnaryOp::Negate), (UnaryOp::Not, IrUnaryOp::Not)];
@@ -1361,3 +1618,420 @@ fn test_generate_recursive_function_call() {
         _ => panic!("Expected Call instruction"),
     }
 }
+
+#[test]
+fn test_generate_calls_to_functions_declared_later() {
+    let n = || Parameter { name: "n".into(), type_annotation: Type::I32, span: dummy_span() };
+    let recurse = |callee: &str| Stmt::Return {
+        value: Some(call_expr(
+            variable_expr(callee),
+            vec![binary_expr(variable_expr("n"), BinaryOp::Subtract, num_lit_i32(1))],
+        )),
+        span: dummy_span(),
+    };
+    let ast = vec![
+        function_declaration("is_even".into(), vec![n()], Type::Bool, vec![recurse("is_odd")]),
+        function_declaration("is_odd".into(), vec![n()], Type::Bool, vec![recurse("is_even")]),
+    ];
+
+    let mut generator = IrGenerator::new_without_ssa();
+    let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+
+    for (caller, callee) in [("is_even", "is_odd"), ("is_odd", "is_even")] {
+        let function = module.functions.iter().find(|f| f.name.as_ref() == caller).unwrap();
+        let entry_block = function.cfg.get_block(&format!("entry_{caller}")).unwrap();
+        match &entry_block.instructions[1].kind {
+            InstructionKind::Call { func, ty, .. } => {
+                assert_eq!(func.kind, ValueKind::Global(callee.into()));
+                assert_eq!(*ty, IrType::Bool);
+            }
+            other => panic!("Expected Call instruction, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_generate_compound_assignment() {
+    let ast = vec![function_declaration(
+        "test".into(),
+        vec![],
+        Type::Void,
+        vec![
+            var_declaration(vec!["x".into()], Type::I32, true, vec![num_lit_i32(10)]),
+            Stmt::Expression { expr: compound_assign_expr(variable_expr("x"), BinaryOp::Modulo, num_lit_i32(3)) },
+        ],
+    )];
+
+    let mut generator = IrGenerator::new_without_ssa();
+    let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+
+    let entry_block = module.functions[0].cfg.get_block("entry_test").unwrap();
+    let instructions: Vec<String> = entry_block.instructions.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        instructions,
+        ["t0 (x) = alloca i32", "store 10i32 to t0 (x)", "t1 = mod t0 (x) 3i32, i32", "store t1 to t0 (x)",]
+    );
+}
+
+#[test]
+fn test_generate_prefix_and_postfix_updates() {
+    let ast = vec![function_declaration(
+        "test".into(),
+        vec![],
+        Type::Void,
+        vec![
+            var_declaration(vec!["x".into()], Type::F64, true, vec![float_lit(1.5)]),
+            var_declaration(
+                vec!["old".into()],
+                Type::F64,
+                false,
+                vec![update_expr(variable_expr("x"), BinaryOp::Add, false)],
+            ),
+            var_declaration(
+                vec!["new".into()],
+                Type::F64,
+                false,
+                vec![update_expr(variable_expr("x"), BinaryOp::Subtract, true)],
+            ),
+            Stmt::Return {
+                value: Some(binary_expr(variable_expr("old"), BinaryOp::Add, variable_expr("new"))),
+                span: dummy_span(),
+            },
+        ],
+    )];
+
+    let mut generator = IrGenerator::new_without_ssa();
+    let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+
+    let entry_block = module.functions[0].cfg.get_block("entry_test").unwrap();
+    let instructions: Vec<String> = entry_block.instructions.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        instructions,
+        [
+            "t0 (x) = alloca f64",
+            "store 1.5f64 to t0 (x)",
+            "t1 = add t0 (x) 1.0f64, f64",
+            "t2 = alloca f64",
+            "store t0 (x) to t2",
+            "store t1 to t0 (x)",
+            "t3 (old) = alloca f64",
+            "store t2 to t3 (old)",
+            "t4 = sub t0 (x) 1.0f64, f64",
+            "store t4 to t0 (x)",
+            "t5 = add t3 (old) t4 (new), f64",
+        ]
+    );
+}
+
+#[test]
+fn test_generate_update_of_array_element() {
+    let ast = vec![function_declaration(
+        "test".into(),
+        vec![],
+        Type::Void,
+        vec![
+            var_declaration(vec!["a".into()], Type::Array(Box::new(Type::U8), Box::new(num_lit_i64(2))), true, vec![]),
+            Stmt::Expression {
+                expr: update_expr(array_access_expr(variable_expr("a"), num_lit_i64(1)), BinaryOp::Subtract, false),
+            },
+        ],
+    )];
+
+    let mut generator = IrGenerator::new_without_ssa();
+    let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+
+    let entry_block = module.functions[0].cfg.get_block("entry_test").unwrap();
+    let instructions: Vec<String> = entry_block.instructions.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        instructions,
+        [
+            "t0 (a) = alloca [u8; 2]",
+            "t1 =  getelementptr t0 (a), 1i64 : u8",
+            "t2 = load u8 from t1",
+            "t3 = sub t2 1u8, u8",
+            "store t3 to t1",
+        ]
+    );
+}
+
+#[test]
+fn test_generate_struct_literal_and_field_access() {
+    let fields = vec![
+        Parameter { name: "a".into(), type_annotation: Type::I8, span: dummy_span() },
+        Parameter { name: "b".into(), type_annotation: Type::I64, span: dummy_span() },
+        Parameter { name: "c".into(), type_annotation: Type::I32, span: dummy_span() },
+    ];
+    let ast = vec![
+        Stmt::Struct { name: "S".into(), fields, span: dummy_span() },
+        function_declaration(
+            "test".into(),
+            vec![],
+            Type::Void,
+            vec![
+                var_declaration(
+                    vec!["s".into()],
+                    Type::Custom("S".into()),
+                    true,
+                    vec![struct_literal_expr(
+                        "S",
+                        vec![("c", num_lit_i32(3)), ("a", num_lit_i8(1)), ("b", num_lit_i64(2))],
+                    )],
+                ),
+                Stmt::Expression {
+                    expr: compound_assign_expr(
+                        field_access_expr(variable_expr("s"), "b"),
+                        BinaryOp::Add,
+                        num_lit_i64(4),
+                    ),
+                },
+                Stmt::Return { value: Some(field_access_expr(variable_expr("s"), "c")), span: dummy_span() },
+            ],
+        ),
+    ];
+
+    let mut generator = IrGenerator::new_without_ssa();
+    let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+
+    let entry_block = module.functions[0].cfg.get_block("entry_test").unwrap();
+    let instructions: Vec<String> = entry_block.instructions.iter().map(ToString::to_string).collect();
+    let s = "struct S { a: i8, b: i64, c: i32 }";
+    assert_eq!(
+        instructions,
+        [
+            format!("t0 (s) = alloca {s}"),
+            format!("t1 = alloca {s}"),
+            "t2 =  getelementptr t1, 16i64 : u8".to_string(),
+            "t3 =  cast t2 from *u8 to *i32".to_string(),
+            "store 3i32 to t3".to_string(),
+            "t4 =  getelementptr t1, 0i64 : u8".to_string(),
+            "t5 =  cast t4 from *u8 to *i8".to_string(),
+            "store 1i8 to t5".to_string(),
+            "t6 =  getelementptr t1, 8i64 : u8".to_string(),
+            "t7 =  cast t6 from *u8 to *i64".to_string(),
+            "store 2i64 to t7".to_string(),
+            "store t1 to t0 (s)".to_string(),
+            "t8 =  getelementptr t0 (s), 8i64 : u8".to_string(),
+            "t9 =  cast t8 from *u8 to *i64".to_string(),
+            "t10 = load i64 from t9".to_string(),
+            "t11 = add t10 4i64, i64".to_string(),
+            "store t11 to t9".to_string(),
+            "t12 =  getelementptr t0 (s), 16i64 : u8".to_string(),
+            "t13 =  cast t12 from *u8 to *i32".to_string(),
+            "t14 = load i32 from t13".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_generate_field_access_on_non_struct() {
+    let ast = vec![function_declaration(
+        "test".into(),
+        vec![],
+        Type::Void,
+        vec![
+            var_declaration(vec!["x".into()], Type::I32, false, vec![num_lit_i32(1)]),
+            Stmt::Expression { expr: field_access_expr(variable_expr("x"), "y") },
+        ],
+    )];
+
+    let mut generator = IrGenerator::new_without_ssa();
+    let (_module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert_eq!(ir_errors.len(), 1);
+    assert_eq!(ir_errors[0].message(), Some("Field access on non-struct type i32"));
+}
+
+#[test]
+fn test_generate_explicit_casts() {
+    let ast = vec![function_declaration(
+        "test".into(),
+        vec![],
+        Type::Void,
+        vec![
+            Stmt::Expression { expr: cast_expr(num_lit_i64(300), Type::I8) },
+            Stmt::Expression { expr: cast_expr(cast_expr(num_lit_u32(66), Type::Char), Type::U64) },
+            Stmt::Expression { expr: cast_expr(num_lit_i32(1), Type::I32) },
+        ],
+    )];
+
+    let mut generator = IrGenerator::new_without_ssa();
+    let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+
+    let entry_block = module.functions[0].cfg.get_block("entry_test").unwrap();
+    let casts: Vec<(CastKind, String)> = entry_block
+        .instructions
+        .iter()
+        .map(|inst| match &inst.kind {
+            InstructionKind::Cast { kind, .. } => (*kind, inst.to_string()),
+            other => panic!("expected a cast, found {other:?}"),
+        })
+        .collect();
+    assert_eq!(
+        casts,
+        [
+            (CastKind::IntTruncate, "t0 =  cast 300i64 from i64 to i8".to_string()),
+            (CastKind::IntToChar, "t1 =  cast 66u32 from u32 to char".to_string()),
+            (CastKind::CharToInt, "t2 =  cast t1 from char to u32".to_string()),
+            (CastKind::IntZeroExtend, "t3 =  cast t2 from u32 to u64".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_generate_cast_of_mutable_variable_reads_current_value() {
+    let ast = vec![function_declaration(
+        "test".into(),
+        vec![],
+        Type::I8,
+        vec![
+            var_declaration(vec!["x".into()], Type::I64, true, vec![num_lit_i64(300)]),
+            Stmt::Return { value: Some(cast_expr(variable_expr("x"), Type::I8)), span: dummy_span() },
+        ],
+    )];
+
+    let mut generator = IrGenerator::new();
+    let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+
+    let entry_block = module.functions[0].cfg.get_block("entry_test").unwrap();
+    let store_dest = entry_block.instructions.iter().find_map(|inst| match &inst.kind {
+        InstructionKind::Store { dest, .. } => Some(dest.clone()),
+        _ => None,
+    });
+    let cast_value = entry_block.instructions.iter().find_map(|inst| match &inst.kind {
+        InstructionKind::Cast { value, from_ty, to_ty, .. } => {
+            assert_eq!((from_ty, to_ty), (&IrType::I64, &IrType::I8));
+            Some(value.clone())
+        }
+        _ => None,
+    });
+    assert!(store_dest.is_some());
+    assert_eq!(cast_value, store_dest);
+}
+
+#[test]
+fn test_generate_invalid_cast() {
+    let ast = vec![function_declaration(
+        "test".into(),
+        vec![],
+        Type::Void,
+        vec![Stmt::Expression {
+            expr: cast_expr(
+                Expr::ArrayLiteral { elements: vec![num_lit_i32(1), num_lit_i32(2)], span: dummy_span() },
+                Type::I64,
+            ),
+        }],
+    )];
+
+    let mut generator = IrGenerator::new_without_ssa();
+    let (_module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert_eq!(ir_errors.len(), 1);
+    assert_eq!(ir_errors[0].message(), Some("Cannot cast [i32; 2] to i64"));
+}
+
+#[test]
+fn test_generate_global_variables_and_constants() {
+    let ast = vec![
+        var_declaration(
+            vec!["SIZE".into()],
+            Type::I64,
+            false,
+            vec![binary_expr(num_lit_i64(4), BinaryOp::Multiply, num_lit_i64(8))],
+        ),
+        var_declaration(
+            vec!["counter".into()],
+            Type::I64,
+            true,
+            vec![binary_expr(variable_expr("SIZE"), BinaryOp::Subtract, num_lit_i64(2))],
+        ),
+        var_declaration(vec!["ready".into()], Type::Bool, true, vec![unary_expr(UnaryOp::Not, bool_lit(true))]),
+        function_declaration(
+            "bump".into(),
+            vec![],
+            Type::I64,
+            vec![
+                Stmt::Expression {
+                    expr: compound_assign_expr(variable_expr("counter"), BinaryOp::Add, variable_expr("SIZE")),
+                },
+                Stmt::Return { value: Some(variable_expr("counter")), span: dummy_span() },
+            ],
+        ),
+    ];
+
+    let mut generator = IrGenerator::new();
+    let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+
+    let globals: Vec<String> = module.globals.iter().map(ToString::to_string).collect();
+    assert_eq!(globals, ["global @counter: i64 = 30i64", "global @ready: bool = false"]);
+
+    let entry_block = module.functions[0].cfg.get_block("entry_bump").unwrap();
+    let instructions: Vec<String> = entry_block.instructions.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        instructions,
+        [
+            "t0 = load i64 from @counter (counter)",
+            "t1 = add t0 32i64 (SIZE), i64",
+            "store t1 to @counter (counter)",
+            "t2 = load i64 from @counter (counter)",
+        ]
+    );
+    assert!(
+        matches!(&entry_block.terminator().kind, TerminatorKind::Return { value, .. } if value.to_string() == "t2")
+    );
+}
+
+#[test]
+fn test_generate_global_arrays() {
+    let table = || Type::Array(Box::new(Type::I32), Box::new(num_lit_i64(2)));
+    let elements = || Expr::ArrayLiteral { elements: vec![num_lit_i32(7), num_lit_i32(-1)], span: dummy_span() };
+    let ast = vec![
+        var_declaration(vec!["table".into()], table(), true, vec![elements()]),
+        var_declaration(vec!["LIMITS".into()], table(), false, vec![elements()]),
+        function_declaration(
+            "second".into(),
+            vec![],
+            Type::I32,
+            vec![Stmt::Return {
+                value: Some(Expr::ArrayAccess {
+                    array: Box::new(variable_expr("LIMITS")),
+                    index: Box::new(num_lit_i64(1)),
+                    span: dummy_span(),
+                }),
+                span: dummy_span(),
+            }],
+        ),
+    ];
+
+    let mut generator = IrGenerator::new();
+    let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+
+    let globals: Vec<String> = module.globals.iter().map(ToString::to_string).collect();
+    assert_eq!(globals, ["global @table: [i32; 2] = [7i32, -1i32]", "global @LIMITS: [i32; 2] = [7i32, -1i32]"]);
+
+    let entry_block = module.functions[0].cfg.get_block("entry_second").unwrap();
+    let instructions: Vec<String> = entry_block.instructions.iter().map(ToString::to_string).collect();
+    assert_eq!(instructions[0], "t0 =  getelementptr @LIMITS (LIMITS), 1i64 : i32");
+}
+
+#[test]
+fn test_generate_global_with_failing_initializer() {
+    let ast = vec![var_declaration(
+        vec!["broken".into()],
+        Type::I32,
+        true,
+        vec![binary_expr(num_lit_i32(1), BinaryOp::Divide, num_lit_i32(0))],
+    )];
+
+    let mut generator = IrGenerator::new();
+    let (module, ir_errors) = generator.generate(ast, "test_file.vn");
+    assert!(module.globals.is_empty());
+    assert_eq!(ir_errors.len(), 1);
+    assert_eq!(ir_errors[0].message(), Some("Cannot evaluate the initializer of global 'broken': division by zero"));
+}
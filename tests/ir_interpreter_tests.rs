@@ -0,0 +1,277 @@
+use jsavrs::error::compile_error::CompileError;
+use jsavrs::error::error_code::ErrorCode;
+use jsavrs::ir::interpreter::{Interpreter, RuntimeValue, Trap};
+use jsavrs::ir::optimizer::{OptLevel, PassManager};
+use jsavrs::ir::{IrLiteralValue, Module, parse_module};
+
+mod common;
+
+const LEVELS: [OptLevel; 4] = [OptLevel::O0, OptLevel::O1, OptLevel::O2, OptLevel::O3];
+
+fn build_module(source: &str, level: OptLevel) -> Module {
+    let mut module = common::build_module(source);
+    PassManager::for_level(level, false).run(&mut module).expect("optimization pipeline");
+    module
+}
+
+/// Runs `main` at every optimization level and returns the final value of
+/// each global in `names`, checking that all levels agree.
+fn run_globals(source: &str, names: &[&str]) -> Vec<RuntimeValue> {
+    let mut expected: Option<Vec<RuntimeValue>> = None;
+    for level in LEVELS {
+        let module = build_module(source, level);
+        let mut interpreter = Interpreter::new(&module);
+        interpreter.run_main().unwrap_or_else(|trap| panic!("{level:?}: {trap}\n{module}"));
+        let values: Vec<_> = names.iter().map(|name| interpreter.global(name).expect("global")).collect();
+        if let Some(expected) = &expected {
+            assert_eq!(&values, expected, "{level:?} disagrees with O0\n{module}");
+        }
+        expected = Some(values);
+    }
+    expected.unwrap()
+}
+
+fn run_trap(source: &str, level: OptLevel) -> Trap {
+    let module = build_module(source, level);
+    Interpreter::new(&module).with_step_limit(1_000_000).run_main().expect_err("program should trap")
+}
+
+const fn int(value: i64) -> RuntimeValue {
+    RuntimeValue::Scalar(IrLiteralValue::I64(value))
+}
+
+#[test]
+fn test_loops_and_recursive_calls() {
+    let values = run_globals(
+        "var result: i64 = 0
+        fun fib(n: i64): i64 {
+            if (n < 2) { return n }
+            return fib(n - 1) + fib(n - 2)
+        }
+        main {
+            var s: i64 = 0
+            var i: i64 = 0
+            while (i < 10) {
+                s = s + i * i
+                i = i + 1
+            }
+            result = fib(15) + s
+        }",
+        &["result"],
+    );
+    assert_eq!(values, [int(610 + 285)]);
+}
+
+#[test]
+fn test_integer_arithmetic_wraps_at_each_width() {
+    let values = run_globals(
+        "var a: i8 = 0i8
+        var b: u8 = 0u8
+        var c: i16 = 0i16
+        var d: u32 = 0u32
+        var e: i64 = 0
+        var f: i32 = 0i32
+        fun add8(x: i8, y: i8): i8 { return x + y }
+        fun sub8(x: u8, y: u8): u8 { return x - y }
+        fun mul16(x: i16, y: i16): i16 { return x * y }
+        fun shl32(x: u32, y: u32): u32 { return x << y }
+        fun add64(x: i64, y: i64): i64 { return x + y }
+        fun rem32(x: i32, y: i32): i32 { return x % y }
+        main {
+            a = add8(127i8, 1i8)
+            b = sub8(0u8, 1u8)
+            c = mul16(300i16, 300i16)
+            d = shl32(3u32, 31u32)
+            e = add64(9223372036854775807, 1)
+            f = rem32(-7i32, 2i32)
+        }",
+        &["a", "b", "c", "d", "e", "f"],
+    );
+    assert_eq!(
+        values,
+        [
+            RuntimeValue::Scalar(IrLiteralValue::I8(-128)),
+            RuntimeValue::Scalar(IrLiteralValue::U8(255)),
+            RuntimeValue::Scalar(IrLiteralValue::I16(24464)),
+            RuntimeValue::Scalar(IrLiteralValue::U32(0x8000_0000)),
+            int(i64::MIN),
+            RuntimeValue::Scalar(IrLiteralValue::I32(-1)),
+        ]
+    );
+}
+
+#[test]
+fn test_floats_and_casts() {
+    let values = run_globals(
+        "var x: f64 = 0.0
+        var y: f64 = 0.0
+        var n: i32 = 0i32
+        var w: i64 = 0
+        var c: char = 'a'
+        fun half(v: f64): f64 { return v / 2.0 }
+        fun widen(v: i8): i64 { return v as i64 }
+        fun truncate(v: f64): i32 { return v as i32 }
+        fun next(v: char): char { return ((v as u32) + 1u32) as char }
+        main {
+            x = half(5.0)
+            y = ((x as f32) * 3.0f) as f64
+            n = truncate(-3.75)
+            w = widen(-5i8)
+            c = next('y')
+        }",
+        &["x", "y", "n", "w", "c"],
+    );
+    assert_eq!(
+        values,
+        [
+            RuntimeValue::Scalar(IrLiteralValue::F64(2.5)),
+            RuntimeValue::Scalar(IrLiteralValue::F64(7.5)),
+            RuntimeValue::Scalar(IrLiteralValue::I32(-3)),
+            int(-5),
+            RuntimeValue::Scalar(IrLiteralValue::Char('z')),
+        ]
+    );
+}
+
+#[test]
+fn test_switch_and_short_circuit_conditions() {
+    let values = run_globals(
+        "var total: i64 = 0
+        fun classify(n: i32): i64 {
+            switch (n) {
+                case 10i32: return 1
+                case 11i32: return 2
+                case 13i32: return 3
+                case 1000i32: return 4
+                default: return 100
+            }
+            return 0
+        }
+        fun both(a: i64, b: i64): bool { return a > 0 && b / a > 1 }
+        main {
+            var i: i32 = 9i32
+            while (i < 14i32) {
+                total = total + classify(i)
+                i = i + 1i32
+            }
+            total = total + classify(1000i32) * 1000
+            if (both(0, 5)) { total = total + 1000000 }
+            if (both(2, 5)) { total = total + 10000 }
+        }",
+        &["total"],
+    );
+    assert_eq!(values, [int(100 + 1 + 2 + 100 + 3 + 4000 + 10000)]);
+}
+
+#[test]
+fn test_compound_assignments_and_updates_of_locals() {
+    let values = run_globals(
+        "var before: i64 = 0
+        var after: i64 = 0
+        var kept: i64 = 0
+        main {
+            var x: i64 = 5
+            x += 1
+            x++
+            ++x
+            before = x--
+            const seen: i64 = x
+            var i: i64 = 0
+            while (i < 4) {
+                x -= i
+                i++
+            }
+            after = x
+            kept = seen
+        }",
+        &["before", "after", "kept"],
+    );
+    assert_eq!(values, [int(8), int(1), int(7)]);
+}
+
+#[test]
+fn test_call_arguments_and_results() {
+    let module =
+        build_module("fun mix(a: i32, b: u8): i64 { return (a as i64) * 10 + (b as i64) }\nmain { }", OptLevel::O2);
+    let mut interpreter = Interpreter::new(&module);
+    let args = vec![RuntimeValue::Scalar(IrLiteralValue::I32(-4)), RuntimeValue::Scalar(IrLiteralValue::U8(7))];
+    assert_eq!(interpreter.call("mix", args), Ok(int(-33)));
+    assert_eq!(interpreter.run_main().map(|value| value.exit_status()), Ok(0));
+    assert_eq!(interpreter.call("missing", Vec::new()).unwrap_err().code, ErrorCode::E3015);
+}
+
+#[test]
+fn test_textual_ir_with_memory_and_phis() {
+    let module = parse_module(
+        "sum.nir",
+        "module m {
+          function sum (n: i64) -> i64:
+          blocks:
+          entry:
+            t0 = alloca [i64; 4]
+            t1 = getelementptr t0, 2i64 : i64
+            store 40i64 to t1
+            br loop
+          loop:
+            t2 = phi i64 [ [ 0i64, entry ], [ t4, loop ] ]
+            t3 = phi i64 [ [ 0i64, entry ], [ t5, loop ] ]
+            t4 = add t2 1i64, i64
+            t5 = add t3 t2, i64
+            t6 = lt t4 %n, bool
+            br t6 ? loop : exit
+          exit:
+            t7 = load i64 from t1
+            t8 = add t5 t7, i64
+            ret t8 i64
+        }",
+    )
+    .expect("valid module");
+    let mut interpreter = Interpreter::new(&module);
+    assert_eq!(interpreter.call("sum", vec![int(5)]), Ok(int(10 + 40)));
+    assert_eq!(interpreter.steps(), 3 + 1 + 5 * 6 + 3);
+}
+
+#[test]
+fn test_division_by_zero_traps_at_instruction_span() {
+    let source = "var zero: i64 = 0
+        fun div(a: i64, b: i64): i64 { return a / b }
+        main { var r: i64 = div(1, zero) }";
+    for level in LEVELS {
+        let trap = run_trap(source, level);
+        assert_eq!(trap.code, ErrorCode::E3014, "{level:?}");
+        assert!(trap.message.contains("division by zero"), "{}", trap.message);
+        assert_eq!(trap.span.start.line, 2, "{trap}");
+    }
+}
+
+#[test]
+fn test_out_of_bounds_access_traps() {
+    let trap = run_trap("main { var a: i64[3] = {1, 2, 3}\nvar r: i64 = a[3] }", OptLevel::O0);
+    assert_eq!(trap.code, ErrorCode::E3014);
+    assert!(trap.message.contains("out-of-bounds access of 8 bytes at offset 24 of a 24-byte allocation"), "{trap}");
+    assert_eq!(trap.span.start.line, 2);
+}
+
+#[test]
+fn test_step_limit_stops_infinite_loops() {
+    let trap = run_trap("main { while (true) { } }", OptLevel::O1);
+    assert_eq!(trap.code, ErrorCode::E3014);
+    assert!(trap.message.contains("step limit of 1000000"), "{trap}");
+}
+
+#[test]
+fn test_unbounded_recursion_overflows_the_stack() {
+    let trap = run_trap("fun f(n: i64): i64 { return f(n + 1) }\nmain { var r: i64 = f(0) }", OptLevel::O0);
+    assert_eq!(trap.code, ErrorCode::E3014);
+    assert!(trap.message.starts_with("stack overflow"), "{trap}");
+}
+
+#[test]
+fn test_trap_converts_to_runtime_error() {
+    let trap = run_trap("var zero: i32 = 0i32\nmain { var r: i32 = 1i32 % zero }", OptLevel::O0);
+    let error = CompileError::from(trap.clone());
+    assert!(matches!(error, CompileError::RuntimeError { .. }));
+    assert_eq!(error.error_code(), Some(&ErrorCode::E3014));
+    assert_eq!(error.span(), Some(&trap.span));
+    assert!(error.to_string().starts_with("[E3014] Runtime error: division by zero at "), "{error}");
+}
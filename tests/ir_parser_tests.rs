@@ -0,0 +1,268 @@
+use jsavrs::error::error_code::ErrorCode;
+use jsavrs::ir::optimizer::DeadCodeElimination;
+use jsavrs::ir::optimizer::constant_folding::optimizer::ConstantFoldingOptimizer;
+use jsavrs::ir::{
+    CastKind, InstructionKind, IrLiteralValue, IrType, Module, Phase, TargetTriple, TerminatorKind, ValueKind,
+    parse_module, run_pipeline,
+};
+
+mod common;
+
+const PROGRAMS: [&str; 8] = [
+    "complex_ssa_test.vn",
+    "float_test.vn",
+    "input.vn",
+    "large_toy_program.vn",
+    "sccp_test.vn",
+    "simple_test.vn",
+    "ssa_test.vn",
+    "temp_test.vn",
+];
+
+const SAMPLE: &str = r#"module sample {
+  data_layout = "e-m:w-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128";
+  target_triple = "x86_64-pc-windows-gnu";
+  function pick (x: i8, flag: bool) -> i32:
+  blocks:
+  entry_pick:
+    t1 =  cast %x (x) from i8 to i32
+    br %flag ? wide : narrow
+
+  wide:
+    t2 = mul t1 2i32, i32
+    switch t2 i32: 0i32 => narrow, 4i32 => done, default done
+
+  narrow:
+    t3 = neg t1 i32
+    br done
+
+  done:
+    t4 =  phi i32 [ [ t2, wide ], [ t3, narrow ], [ t2, wide ] ]
+    ret t4 i32
+}"#;
+
+fn generate(path: &str, optimize: bool) -> Module {
+    let mut module = common::build_module_at(path, &std::fs::read_to_string(path).unwrap());
+    if optimize {
+        let pipeline: Vec<Box<dyn Phase>> = vec![
+            Box::new(ConstantFoldingOptimizer::new(false, true)),
+            Box::new(DeadCodeElimination::with_config(10, true, false, false)),
+        ];
+        run_pipeline(&mut module, pipeline);
+    }
+    module
+}
+
+fn parse_error_message(text: &str) -> (String, usize) {
+    let error = parse_module("broken.nir", text).unwrap_err();
+    assert_eq!(error.error_code(), Some(&ErrorCode::E3009));
+    (error.message().unwrap().to_string(), error.span().unwrap().start.line)
+}
+
+#[test]
+fn test_generated_modules_round_trip() {
+    for program in PROGRAMS {
+        for optimize in [false, true] {
+            let printed = generate(&format!("vn_files/{program}"), optimize).to_string();
+            let parsed = parse_module("round_trip.nir", &printed).unwrap();
+            assert_eq!(parsed.to_string(), printed, "{program} (optimized: {optimize})");
+        }
+    }
+}
+
+#[test]
+fn test_parses_module_settings_and_signatures() {
+    let module = parse_module("sample.nir", SAMPLE).unwrap();
+
+    assert_eq!(module.name(), "sample");
+    assert_eq!(*module.target_triple(), TargetTriple::X86_64PcWindowsGnu);
+    assert_eq!(module.data_layout().as_str(), "e-m:w-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128");
+
+    let function = module.get_function("pick").unwrap();
+    assert_eq!(function.parameters.len(), 2);
+    assert_eq!(function.parameters[1].ty, IrType::Bool);
+    assert_eq!(function.return_type, IrType::I32);
+    assert_eq!(module.to_string().lines().count(), SAMPLE.lines().count());
+}
+
+#[test]
+fn test_rebuilds_cfg_edges_from_terminators() {
+    let module = parse_module("sample.nir", SAMPLE).unwrap();
+    let cfg = &module.get_function("pick").unwrap().cfg;
+
+    assert_eq!(cfg.entry_label(), "entry_pick");
+    assert_eq!(cfg.blocks().count(), 4);
+    assert_eq!(cfg.graph().edge_count(), 2 + 3 + 1);
+    assert!(cfg.verify().is_ok());
+}
+
+#[test]
+fn test_infers_cast_kinds_and_operand_types() {
+    let module = parse_module("sample.nir", SAMPLE).unwrap();
+    let cfg = &module.get_function("pick").unwrap().cfg;
+
+    let entry = cfg.get_block("entry_pick").unwrap();
+    let InstructionKind::Cast { kind, value, .. } = &entry.instructions[0].kind else {
+        panic!("expected a cast, got {}", entry.instructions[0]);
+    };
+    assert_eq!(*kind, CastKind::IntSignExtend);
+    assert_eq!(value.ty, IrType::I8, "parameter types flow into uses");
+    let TerminatorKind::ConditionalBranch { condition, .. } = &entry.terminator().kind else {
+        panic!("expected a conditional branch");
+    };
+    assert_eq!(condition.ty, IrType::Bool);
+
+    let done = cfg.get_block("done").unwrap();
+    let InstructionKind::Phi { incoming, .. } = &done.instructions[0].kind else {
+        panic!("expected a phi");
+    };
+    let labels: Vec<&str> = incoming.iter().map(|(_, label)| label.as_str()).collect();
+    assert_eq!(labels, ["wide", "narrow", "wide"]);
+    assert!(incoming.iter().all(|(value, _)| value.ty == IrType::I32));
+}
+
+#[test]
+fn test_occurrences_of_a_value_share_its_id() {
+    let module = parse_module("sample.nir", SAMPLE).unwrap();
+    let cfg = &module.get_function("pick").unwrap().cfg;
+
+    let entry = cfg.get_block("entry_pick").unwrap();
+    let t1 = entry.instructions[0].result.as_ref().unwrap();
+    let InstructionKind::Unary { operand, .. } = &cfg.get_block("narrow").unwrap().instructions[0].kind else {
+        panic!("expected a unary instruction");
+    };
+    assert_eq!(operand.id, t1.id);
+
+    let InstructionKind::Phi { incoming, .. } = &cfg.get_block("done").unwrap().instructions[0].kind else {
+        panic!("expected a phi");
+    };
+    assert_eq!(incoming[0].0.id, incoming[2].0.id);
+    assert_ne!(incoming[0].0.id, incoming[1].0.id);
+}
+
+#[test]
+fn test_parses_switch_cases() {
+    let module = parse_module("sample.nir", SAMPLE).unwrap();
+    let wide = module.get_function("pick").unwrap().cfg.get_block("wide").unwrap();
+
+    let TerminatorKind::Switch { cases, default_label, ty, .. } = &wide.terminator().kind else {
+        panic!("expected a switch");
+    };
+    assert_eq!(*ty, IrType::I32);
+    assert_eq!(default_label.as_ref(), "done");
+    assert_eq!(cases.len(), 2);
+    assert_eq!(cases[1].0.kind, ValueKind::Literal(IrLiteralValue::I32(4)));
+    assert_eq!(cases[1].1.as_ref(), "done");
+}
+
+#[test]
+fn test_parses_indirect_branches_constants_and_memory() {
+    let text = r#"module memory {
+  data_layout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128";
+  target_triple = "x86_64-unknown-linux-gnu";
+  function f () -> void:
+  blocks:
+  entry:
+    t1 = alloca [i8; 4]
+    store [1i8, -2i8, 3i8, 4i8] to t1
+    t2 =  getelementptr t1, 2i64 : i8
+    t3 = load i8 from t2
+    store "a\"b\n" to @name
+    t4 =  call @g (g)('\'', 1.5f64, true) : *i8
+    ibr t4 [entry, exit]
+
+  exit:
+    unreachable
+}"#;
+    let module = parse_module("memory.nir", text).unwrap();
+    assert_eq!(module.to_string(), text.replace("\n\n", "\n  \n"));
+
+    let entry = module.get_function("f").unwrap().cfg.get_block("entry").unwrap();
+    assert_eq!(
+        entry.instructions[0].result.as_ref().unwrap().ty,
+        IrType::Pointer(Box::new(IrType::Array(Box::new(IrType::I8), 4)))
+    );
+    assert_eq!(entry.instructions[3].result.as_ref().unwrap().ty, IrType::I8);
+    let TerminatorKind::IndirectBranch { possible_labels, address } = &entry.terminator().kind else {
+        panic!("expected an indirect branch");
+    };
+    assert_eq!(possible_labels.len(), 2);
+    assert_eq!(address.ty, IrType::Pointer(Box::new(IrType::I8)));
+}
+
+#[test]
+fn test_parses_globals_and_types_their_uses() {
+    let text = r#"module globals {
+  data_layout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128";
+  target_triple = "x86_64-unknown-linux-gnu";
+  global @count: i32 = -3i32
+  global @letter: char = 'z'
+  global @table: [u16; 3] = [1u16, 2u16, 3u16]
+  function tick () -> i32:
+  block:
+  entry_tick:
+    t1 = load i32 from @count (count)
+    t2 = add t1 1i32, i32
+    store t2 to @count (count)
+    ret t2 i32
+}"#;
+    let module = parse_module("globals.nir", text).unwrap();
+    assert_eq!(module.to_string(), text);
+
+    let count = module.get_global("count").unwrap();
+    assert_eq!(count.ty, IrType::I32);
+    assert_eq!(count.initializer, [IrLiteralValue::I32(-3)]);
+    assert_eq!(module.get_global("letter").unwrap().initializer, [IrLiteralValue::Char('z')]);
+    assert_eq!(
+        module.get_global("table").unwrap().initializer,
+        [IrLiteralValue::U16(1), IrLiteralValue::U16(2), IrLiteralValue::U16(3)]
+    );
+
+    let entry = module.get_function("tick").unwrap().cfg.get_block("entry_tick").unwrap();
+    let InstructionKind::Load { src, .. } = &entry.instructions[0].kind else {
+        panic!("expected a load");
+    };
+    assert_eq!(src.ty, IrType::Pointer(Box::new(IrType::I32)));
+
+    let (message, line) = parse_error_message("module m {\n  global @g: i64 = 1i32\n}");
+    assert_eq!(message, "initializer of `@g` is not a i64 literal");
+    assert_eq!(line, 2);
+
+    let (message, _) = parse_error_message("module m {\n  global @g: [i8; 2] = [1i8]\n}");
+    assert_eq!(message, "initializer of `@g` is not a [i8; 2] literal");
+}
+
+#[test]
+fn test_unknown_instructions_are_reported_with_their_line() {
+    let (message, line) =
+        parse_error_message("module m {\n  function f () -> void:\n  entry_f:\n    t1 = frob 1i8\n    ret 0i8 void\n}");
+    assert_eq!(message, "unknown instruction `frob`");
+    assert_eq!(line, 4);
+}
+
+#[test]
+fn test_branches_to_undefined_blocks_are_rejected() {
+    let (message, line) = parse_error_message("module m {\n  function f () -> void:\n  entry_f:\n    br nowhere\n}");
+    assert_eq!(message, "branch to undefined block `nowhere`");
+    assert_eq!(line, 4);
+}
+
+#[test]
+fn test_structural_errors_are_rejected() {
+    let (message, _) = parse_error_message("module m {\n  function f () -> void:\n  entry_f:\n    ret 0i8 void");
+    assert_eq!(message, "expected `}` to close the module");
+
+    let (message, line) =
+        parse_error_message("module m {\n  function f () -> void:\n  entry_f:\n    br entry_f\n    br entry_f\n}");
+    assert_eq!(message, "block `entry_f` already has a terminator");
+    assert_eq!(line, 5);
+
+    let (message, _) = parse_error_message("module m {\n  function f () -> void:\n  a:\n    br a\n  a:\n    br a\n}");
+    assert_eq!(message, "duplicate block `a` in function `f`");
+
+    let (message, _) = parse_error_message("module m {\n  t1 = add 1i8 2i8, i8\n}");
+    assert_eq!(message, "instruction outside of a block");
+
+    let (message, _) = parse_error_message("function f () -> void:");
+    assert_eq!(message, "expected `module <name> {`");
+}
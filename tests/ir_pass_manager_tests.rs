@@ -0,0 +1,151 @@
+use jsavrs::ir::optimizer::{OptLevel, PassManager, PassReport, phase_by_name};
+use jsavrs::ir::{Module, Phase, parse_module};
+
+const DEAD_CODE: &str = "module dead {
+  function f (x: i32) -> i32:
+  entry_f:
+    t1 = mul %x (x) 4i32, i32
+    t2 = sub t1 1i32, i32
+    t3 = add t1 2i32, i32
+    ret t1 i32
+}";
+
+fn dead_code() -> Module {
+    parse_module("dead.nir", DEAD_CODE).unwrap()
+}
+
+/// Removes the last instruction of `f` on each of its first `budget` runs.
+struct TrimLast {
+    budget: usize,
+}
+
+impl Phase for TrimLast {
+    fn name(&self) -> &'static str {
+        "Trim Last"
+    }
+
+    fn run(&mut self, ir: &mut Module) {
+        if self.budget == 0 {
+            return;
+        }
+        self.budget -= 1;
+        let function = ir.get_function_mut("f").unwrap();
+        function.cfg.get_block_mut("entry_f").unwrap().instructions.pop();
+    }
+}
+
+/// Swaps the first two instructions of `f`, moving a use above its definition.
+struct SwapFirstTwo;
+
+impl Phase for SwapFirstTwo {
+    fn name(&self) -> &'static str {
+        "Swap First Two"
+    }
+
+    fn run(&mut self, ir: &mut Module) {
+        let function = ir.get_function_mut("f").unwrap();
+        function.cfg.get_block_mut("entry_f").unwrap().instructions.swap(0, 1);
+    }
+}
+
+#[test]
+fn test_presets_select_passes_by_level() {
+    assert!(PassManager::for_level(OptLevel::O0, false).pass_names().is_empty());
+    for level in [OptLevel::O1, OptLevel::O2, OptLevel::O3] {
+        assert_eq!(PassManager::for_level(level, false).pass_names(), ["sccp", "dce"], "{level:?}");
+    }
+    assert_eq!(OptLevel::default(), OptLevel::O1);
+    assert_eq!(OptLevel::O1.max_rounds(), None);
+    assert!(OptLevel::O2.max_rounds() < OptLevel::O3.max_rounds());
+}
+
+#[test]
+fn test_single_runs_are_timed_and_report_statistics() {
+    let mut module = dead_code();
+    let report = PassManager::for_level(OptLevel::O1, false).run(&mut module).unwrap();
+
+    let runs: Vec<(&str, usize)> = report.timings.iter().map(|timing| (timing.name.as_str(), timing.runs)).collect();
+    assert_eq!(runs, [("sccp", 1), ("dce", 1)]);
+    assert_eq!(report.total_time(), report.timings[0].time + report.timings[1].time);
+    assert_eq!(report.rounds, 0);
+    assert_eq!(report.dce.instructions_removed, 2);
+    assert_eq!(module.count_instructions(), 1);
+}
+
+#[test]
+fn test_fixed_point_groups_repeat_until_nothing_changes() {
+    let mut manager = PassManager::new();
+    manager.add_fixed_point(vec![("trim", Box::new(TrimLast { budget: 2 }))], 10);
+    let mut module = dead_code();
+    let report = manager.run(&mut module).unwrap();
+
+    assert_eq!(report.rounds, 3, "two changing rounds and one that confirms the fixed point");
+    assert_eq!(report.timings[0].runs, 3);
+    assert_eq!(module.count_instructions(), 1);
+}
+
+#[test]
+fn test_fixed_point_groups_stop_at_the_round_limit() {
+    let mut manager = PassManager::new();
+    manager.add_fixed_point(vec![("trim", Box::new(TrimLast { budget: 3 }))], 2);
+    let mut module = dead_code();
+    let report = manager.run(&mut module).unwrap();
+
+    assert_eq!(report.rounds, 2);
+    assert_eq!(module.count_instructions(), 1);
+}
+
+#[test]
+fn test_disabled_passes_do_not_run() {
+    let mut manager = PassManager::for_level(OptLevel::O2, false);
+    manager.disable_pass("dce");
+    assert_eq!(manager.pass_names(), ["sccp"]);
+
+    let mut module = dead_code();
+    let report = manager.run(&mut module).unwrap();
+    assert_eq!(report.timings.len(), 1);
+    assert_eq!(report.timings[0].name, "sccp");
+    assert_eq!(report.dce.instructions_removed, 0);
+    assert_eq!(module.count_instructions(), 3);
+}
+
+#[test]
+fn test_statistics_are_summed_across_runs() {
+    let mut manager = PassManager::new();
+    manager.add_pass("dce", phase_by_name("dce", false).unwrap());
+    manager.add_pass("trim", Box::new(TrimLast { budget: 1 }));
+    manager.add_pass("dce", phase_by_name("dce", false).unwrap());
+    let report = manager.run(&mut dead_code()).unwrap();
+
+    assert_eq!(report.timings.iter().map(|timing| timing.runs).collect::<Vec<_>>(), [2, 1]);
+    assert_eq!(report.dce.instructions_removed, 2);
+    assert_eq!(report.sccp.iterations, 0, "phases without statistics add nothing");
+}
+
+#[test]
+fn test_verification_names_the_failing_pass() {
+    let mut manager = PassManager::for_level(OptLevel::O1, false);
+    manager.set_verify(true);
+    assert!(manager.run(&mut dead_code()).is_ok());
+
+    let mut manager = PassManager::new();
+    manager.set_verify(true);
+    manager.add_pass("swap", Box::new(SwapFirstTwo));
+    let errors = manager.run(&mut dead_code()).unwrap_err();
+    assert!(errors[0].message().unwrap().starts_with("after Swap First Two: `t1` is used"), "{errors:?}");
+}
+
+#[test]
+fn test_report_lists_passes_and_statistics() {
+    let mut manager = PassManager::for_level(OptLevel::O2, false);
+    let report = manager.run(&mut dead_code()).unwrap();
+    let text = report.to_string();
+
+    assert!(text.starts_with("Pass execution timing report:\n  sccp       2 run(s)  "), "{text}");
+    assert!(text.contains("\n  dce        2 run(s)  "), "{text}");
+    assert!(text.contains(" in 2 fixed-point round(s)\n"), "{text}");
+    assert!(
+        text.ends_with("DCE Stats: 2 instructions removed, 0 blocks removed, 3 iterations, 0 conservative warnings")
+    );
+    assert!(PassReport::default().to_string().contains("total"));
+}
@@ -770,6 +770,165 @@ fn test_mixed_type_constant_expressions() {
     }
 }
 
+// ============================================================================
+// Short-circuit operators with a constant left operand
+// ============================================================================
+
+#[test]
+fn test_short_circuit_with_constant_left_operand_is_folded() {
+    use jsavrs::ir::generator::IrGenerator;
+    use jsavrs::ir::terminator::TerminatorKind;
+    use jsavrs::ir::value::{IrLiteralValue, ValueKind};
+    use jsavrs::parser::ast::{BinaryOp as AstBinaryOp, Parameter, Stmt, Type};
+    use jsavrs::utils::{binary_expr, bool_lit, dummy_span, function_declaration, variable_expr};
+
+    // `false && b` is false and `false || b` is b: the right operand block is
+    // dropped and the phi in the join block disappears
+    for (op, expected) in [(AstBinaryOp::And, Some(false)), (AstBinaryOp::Or, None)] {
+        let ast = vec![function_declaration(
+            "test".into(),
+            vec![Parameter { name: "b".into(), type_annotation: Type::Bool, span: dummy_span() }],
+            Type::Bool,
+            vec![Stmt::Return {
+                value: Some(binary_expr(bool_lit(false), op, variable_expr("b"))),
+                span: dummy_span(),
+            }],
+        )];
+        let (mut module, errors) = IrGenerator::new().generate(ast, "test.vn");
+        assert!(errors.is_empty(), "{errors:?}");
+        let function = &mut module.functions[0];
+
+        let mut optimizer = ConstantFoldingOptimizer::default();
+        let stats = optimizer.optimize_function(function).expect("SCCP should succeed");
+        assert_eq!(stats.branches_resolved, 1);
+        assert_eq!(stats.phi_nodes_simplified, 1);
+
+        let entry = function.cfg.get_entry_block().unwrap();
+        assert!(matches!(&entry.terminator().kind, TerminatorKind::Branch { .. }));
+        let returned: Vec<_> = function
+            .cfg
+            .blocks()
+            .filter_map(|block| match &block.terminator().kind {
+                TerminatorKind::Return { value, .. } => Some(value.kind.clone()),
+                _ => None,
+            })
+            .collect();
+        match expected {
+            Some(constant) => assert_eq!(returned, [ValueKind::Literal(IrLiteralValue::Bool(constant))]),
+            None => assert_eq!(returned, [ValueKind::Local("b".into())]),
+        }
+        assert!(
+            function.cfg.blocks().all(|block| block.instructions.is_empty()),
+            "no phi should be left in {function}"
+        );
+    }
+}
+
+#[test]
+fn test_switch_on_constant_is_resolved() {
+    use jsavrs::ir::generator::IrGenerator;
+    use jsavrs::ir::terminator::TerminatorKind;
+    use jsavrs::parser::ast::{Stmt, SwitchCase, Type};
+    use jsavrs::utils::{dummy_span, function_declaration, num_lit_i32};
+
+    let case = |value: i32| SwitchCase {
+        value: num_lit_i32(value),
+        body: vec![Stmt::Return { value: Some(num_lit_i32(value * 10)), span: dummy_span() }],
+        span: dummy_span(),
+    };
+    for (selector, expected_target) in [(2, "switch_case_4"), (7, "switch_default_2")] {
+        let ast = vec![function_declaration(
+            "test".into(),
+            vec![],
+            Type::I32,
+            vec![Stmt::Switch {
+                scrutinee: num_lit_i32(selector),
+                cases: vec![case(1), case(2), case(3)],
+                default: Some(vec![Stmt::Return { value: Some(num_lit_i32(0)), span: dummy_span() }]),
+                span: dummy_span(),
+            }],
+        )];
+        let (mut module, errors) = IrGenerator::new().generate(ast, "test.vn");
+        assert!(errors.is_empty(), "{errors:?}");
+        let function = &mut module.functions[0];
+
+        let mut optimizer = ConstantFoldingOptimizer::default();
+        let stats = optimizer.optimize_function(function).expect("SCCP should succeed");
+        assert_eq!(stats.branches_resolved, 1);
+
+        let entry = function.cfg.get_entry_block().unwrap();
+        assert!(
+            matches!(&entry.terminator().kind, TerminatorKind::Branch { label } if label.as_ref() == expected_target),
+            "{function}"
+        );
+        let entry_index = function.cfg.get_entry_block_index().unwrap();
+        let successors: Vec<_> = function
+            .cfg
+            .graph()
+            .neighbors(entry_index)
+            .map(|node| function.cfg.graph()[node].label.to_string())
+            .collect();
+        assert_eq!(successors, [expected_target], "{function}");
+    }
+}
+
+#[test]
+fn test_enum_variants_fold_to_constants() {
+    use jsavrs::ir::generator::IrGenerator;
+    use jsavrs::ir::terminator::TerminatorKind;
+    use jsavrs::parser::ast::{EnumVariant, Stmt, SwitchCase, Type};
+    use jsavrs::utils::{
+        dummy_span, field_access_expr, function_declaration, num_lit_i32, var_declaration, variable_expr,
+    };
+
+    let variant = |name: &str| EnumVariant { name: name.into(), value: None, span: dummy_span() };
+    let case = |name: &str, value: i32| SwitchCase {
+        value: field_access_expr(variable_expr("Color"), name),
+        body: vec![Stmt::Return { value: Some(num_lit_i32(value)), span: dummy_span() }],
+        span: dummy_span(),
+    };
+    let ast = vec![
+        Stmt::Enum {
+            name: "Color".into(),
+            underlying_type: Type::I32,
+            variants: vec![variant("Red"), variant("Green"), variant("Blue")],
+            span: dummy_span(),
+        },
+        function_declaration(
+            "test".into(),
+            vec![],
+            Type::I32,
+            vec![
+                var_declaration(
+                    vec!["c".into()],
+                    Type::Custom("Color".into()),
+                    false,
+                    vec![field_access_expr(variable_expr("Color"), "Green")],
+                ),
+                Stmt::Switch {
+                    scrutinee: variable_expr("c"),
+                    cases: vec![case("Red", 1), case("Green", 2)],
+                    default: Some(vec![Stmt::Return { value: Some(num_lit_i32(0)), span: dummy_span() }]),
+                    span: dummy_span(),
+                },
+            ],
+        ),
+    ];
+    let (mut module, errors) = IrGenerator::new().generate(ast, "test.vn");
+    assert!(errors.is_empty(), "{errors:?}");
+    let function = &mut module.functions[0];
+
+    let mut optimizer = ConstantFoldingOptimizer::default();
+    let stats = optimizer.optimize_function(function).expect("SCCP should succeed");
+    assert_eq!(stats.branches_resolved, 1);
+
+    let entry = function.cfg.get_entry_block().unwrap();
+    assert!(
+        matches!(&entry.terminator().kind, TerminatorKind::Branch { label } if label.as_ref() == "switch_case_4"),
+        "{function}"
+    );
+}
+
 // TODO: Add full integration tests when IR APIs are complete
 // Planned tests:
 // - test_simple_constant_propagation: x=5; y=10; z=x+y → z=15
@@ -779,3 +938,43 @@ fn test_mixed_type_constant_expressions() {
 // - test_convergence_on_complex_function: multi-block CFG convergence
 // - test_empty_function_handling: edge case for empty functions
 // - test_single_block_optimization: optimization within one basic block
+
+// ============================================================================
+// Rewriting branches and phis
+// ============================================================================
+
+#[test]
+fn test_constant_branches_are_resolved_and_their_phis_simplified() {
+    // The pass used to run the analysis only and leave the function unchanged.
+    use jsavrs::ir::terminator::TerminatorKind;
+
+    let mut module = jsavrs::ir::parse_module(
+        "sccp.nir",
+        "module sccp {
+function f (x: i32) -> i32:
+entry:
+  t1 = lt 1i32 2i32, bool
+  br t1 ? left : right
+left:
+  br done
+right:
+  br done
+done:
+  t2 = phi i32 [ [ 10i32, left ], [ %x (x), right ] ]
+  t3 = phi i32 [ [ t2, left ], [ t2, right ] ]
+  ret t3 i32
+}",
+    )
+    .unwrap();
+    let function = &mut module.functions[0];
+
+    let stats = ConstantFoldingOptimizer::default().optimize_function(function).expect("SCCP should succeed");
+    assert_eq!(stats.branches_resolved, 1);
+    assert_eq!(stats.phi_nodes_simplified, 2);
+
+    let entry = function.cfg.get_block("entry").unwrap();
+    assert!(matches!(&entry.terminator().kind, TerminatorKind::Branch { label } if &**label == "left"));
+    let done = function.cfg.get_block("done").unwrap();
+    assert!(done.instructions.is_empty(), "{function}");
+    assert_eq!(done.terminator().to_string(), "ret 10i32 i32");
+}
@@ -141,3 +141,93 @@ fn test_phi_evaluation_bool_values() {
     let result2 = bool_true.meet(&bool_false);
     assert_eq!(result2, LatticeValue::Top);
 }
+
+// ============================================================================
+// Propagation over whole functions
+// ============================================================================
+
+/// Runs the propagator over the only function of `body` and returns the
+/// lattice value of the result of instruction `index` of block `label`.
+fn propagated(body: &str, label: &str, index: usize) -> LatticeValue {
+    let module = jsavrs::ir::parse_module("sccp.nir", &format!("module sccp {{\n{body}\n}}")).unwrap();
+    let function = &module.functions[0];
+    let mut propagator = SCCPropagator::new_for_function(function);
+    propagator.propagate(function, 100).unwrap();
+    let result = function.cfg.get_block(label).unwrap().instructions[index].result.as_ref().unwrap();
+    propagator.value_lattice(result)
+}
+
+#[test]
+fn test_literal_operands_are_constants() {
+    // Literal operands used to be looked up in the lattice by value id, where
+    // they are never defined, so nothing was ever folded.
+    let body = "function f () -> i32:
+entry:
+  t1 = add 2i32 3i32, i32
+  t2 = mul t1 4i32, i32
+  ret t2 i32";
+    assert_eq!(propagated(body, "entry", 1), LatticeValue::Constant(ConstantValue::I32(20)));
+}
+
+#[test]
+fn test_phi_ignores_operands_from_non_executable_edges() {
+    // Phi operands used to count as soon as the block had any executable
+    // predecessor, and were all treated as varying.
+    let body = "function f () -> i32:
+entry:
+  t1 = lt 1i32 2i32, bool
+  br t1 ? left : right
+left:
+  br done
+right:
+  br done
+done:
+  t2 = phi i32 [ [ 10i32, left ], [ 20i32, right ] ]
+  ret t2 i32";
+    assert_eq!(propagated(body, "done", 0), LatticeValue::Constant(ConstantValue::I32(10)));
+}
+
+#[test]
+fn test_store_to_a_temporary_defines_it() {
+    // SSA construction turns stores to promoted variables into definitions of
+    // temporaries, which used to stay undefined in the lattice.
+    let body = "function f () -> i32:
+entry:
+  store 3i32 to t1 (x)
+  t2 = add t1 (x) 4i32, i32
+  ret t2 i32";
+    assert_eq!(propagated(body, "entry", 1), LatticeValue::Constant(ConstantValue::I32(7)));
+}
+
+#[test]
+fn test_branches_are_revisited_when_their_condition_changes() {
+    // The loop condition is first true, then varies once the back edge is
+    // executable. No new edge leads to the block of the branch, which used
+    // to keep only the edge into the loop body.
+    let module = jsavrs::ir::parse_module(
+        "sccp.nir",
+        "module sccp {
+function f () -> i32:
+entry:
+  br header
+header:
+  t1 = phi i32 [ [ 0i32, entry ], [ t3, body ] ]
+  br check
+check:
+  t2 = lt t1 10i32, bool
+  br t2 ? body : exit
+body:
+  t3 = add t1 1i32, i32
+  br header
+exit:
+  ret t1 i32
+}",
+    )
+    .unwrap();
+    let function = &module.functions[0];
+    let mut propagator = SCCPropagator::new_for_function(function);
+    propagator.propagate(function, 100).unwrap();
+
+    let exit = function.cfg.graph().node_indices().find(|&node| &*function.cfg.graph()[node].label == "exit").unwrap();
+    assert!(propagator.is_block_executable(function, exit.index()));
+}
@@ -1,4 +1,5 @@
 use jsavrs::ir::function::Function;
+use jsavrs::ir::interpreter::{Interpreter, RuntimeValue};
 use jsavrs::ir::ssa::SsaTransformer;
 //use jsavrs::ir::cfg::ControlFlowGraph;
 use jsavrs::ir::basic_block::BasicBlock;
@@ -6,9 +7,14 @@ use jsavrs::ir::instruction::{Instruction, InstructionKind};
 use jsavrs::ir::terminator::{Terminator, TerminatorKind};
 use jsavrs::ir::types::IrType;
 use jsavrs::ir::value::{/*ValueKind,*/ IrLiteralValue, Value};
+use jsavrs::ir::verify_module;
 use jsavrs::location::source_span::SourceSpan;
 use std::sync::Arc;
 
+mod common;
+
+use common::build_module;
+
 #[test]
 #[allow(clippy::assertions_on_constants)]
 fn test_ssa_transformer_new() {
@@ -245,3 +251,100 @@ fn test_loop_transformation() {
     }
     assert!(result.is_ok());
 }
+
+/// Builds `source` through SSA construction and calls `function` with `args`.
+fn call_after_ssa(source: &str, function: &str, args: Vec<RuntimeValue>) -> RuntimeValue {
+    let module = build_module(source);
+    Interpreter::new(&module).call(function, args).unwrap_or_else(|trap| panic!("{trap}\n{module}"))
+}
+
+const fn boolean(value: bool) -> RuntimeValue {
+    RuntimeValue::Scalar(IrLiteralValue::Bool(value))
+}
+
+#[test]
+fn test_definitions_of_a_branch_do_not_leak_into_its_sibling() {
+    // Leaving the `then` block used to keep its definition of `x` on the
+    // rename stack, so the `else` block read 2 instead of 1.
+    let source = "fun pick(flag: bool): i32 {
+        var x: i32 = 1i32
+        var seen: i32 = 0i32
+        if (flag) {
+            x = 2i32
+            seen = x
+        } else {
+            seen = x
+        }
+        return seen
+    }";
+    assert_eq!(call_after_ssa(source, "pick", vec![boolean(false)]), RuntimeValue::Scalar(IrLiteralValue::I32(1)));
+    assert_eq!(call_after_ssa(source, "pick", vec![boolean(true)]), RuntimeValue::Scalar(IrLiteralValue::I32(2)));
+}
+
+#[test]
+fn test_terminator_operands_are_renamed() {
+    // Returns and conditional branches used to keep reading the variable's
+    // slot instead of its current value.
+    let source = "fun last(): i32 {
+        var x: i32 = 1i32
+        x = 5i32
+        return x
+    }
+    fun count(): i32 {
+        var going: bool = true
+        var n: i32 = 0i32
+        while (going) {
+            n += 1i32
+            going = n < 3i32
+        }
+        return n
+    }";
+    assert_eq!(call_after_ssa(source, "last", vec![]), RuntimeValue::Scalar(IrLiteralValue::I32(5)));
+    assert_eq!(call_after_ssa(source, "count", vec![]), RuntimeValue::Scalar(IrLiteralValue::I32(3)));
+}
+
+#[test]
+fn test_operands_of_expression_phis_are_renamed() {
+    // The phi merging the operands of `||` is not a variable phi; its operand
+    // from the right operand block used to keep reading the slot of `a`.
+    let source = "fun either(b: bool): bool {
+        var a: bool = false
+        a = true
+        return b || a
+    }";
+    assert_eq!(call_after_ssa(source, "either", vec![boolean(false)]), boolean(true));
+    assert_eq!(call_after_ssa(source, "either", vec![boolean(true)]), boolean(true));
+}
+
+#[test]
+fn test_phis_of_variables_out_of_scope_at_the_join_are_removed() {
+    // `v` is declared in the loop body, so the loop header has no value for it
+    // on the edge from the entry block. Its phi there used to be kept with
+    // that operand missing.
+    let module = build_module(
+        "fun h(n: i32): i32 {
+            var k: i32 = 0i32
+            while (k < n) {
+                var v: i32 = k * 2i32
+                if (v > 2i32) { v = 0i32 }
+                k += 1i32
+            }
+            return k
+        }",
+    );
+    let function = &module.functions[0];
+    let header = function.cfg.blocks().find(|block| block.label.starts_with("loop_start")).expect("loop header");
+    let phis: Vec<String> = header
+        .instructions
+        .iter()
+        .filter(|instruction| matches!(instruction.kind, InstructionKind::Phi { .. }))
+        .map(ToString::to_string)
+        .collect();
+    assert!(phis.iter().all(|phi| !phi.contains("(v)")), "{function}");
+    assert!(phis.iter().any(|phi| phi.contains("(k)")), "{function}");
+    assert!(verify_module(&mut module.clone()).is_ok(), "{function}");
+    assert_eq!(
+        Interpreter::new(&module).call("h", vec![RuntimeValue::Scalar(IrLiteralValue::I32(3))]),
+        Ok(RuntimeValue::Scalar(IrLiteralValue::I32(3)))
+    );
+}
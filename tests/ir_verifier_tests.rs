@@ -0,0 +1,216 @@
+use jsavrs::error::compile_error::CompileError;
+use jsavrs::error::error_code::{CompilerPhase, ErrorCode};
+use jsavrs::error::error_reporter::ErrorReporter;
+use jsavrs::ir::optimizer::{PASS_NAMES, phase_by_name};
+use jsavrs::ir::{IrVerifier, Module, Phase, parse_module, run_verified_pipeline, verify_module};
+use jsavrs::location::line_tracker::LineTracker;
+use jsavrs::test_runner::compile;
+
+/// Wraps function bodies in a module header.
+fn module(functions: &str) -> Module {
+    parse_module("verify.nir", &format!("module verify {{\n{functions}\n}}")).unwrap()
+}
+
+fn errors(functions: &str) -> Vec<CompileError> {
+    verify_module(&mut module(functions)).unwrap_err()
+}
+
+fn single_error(functions: &str) -> (ErrorCode, String) {
+    let errors = errors(functions);
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    (*errors[0].error_code().unwrap(), errors[0].message().unwrap().to_string())
+}
+
+const DIAMOND: &str = "function pick (x: i32, flag: bool) -> i32:
+entry:
+  t1 = add %x (x) 1i32, i32
+  br %flag (flag) ? left : right
+left:
+  t2 = mul t1 2i32, i32
+  br done
+right:
+  br done
+done:
+  t3 = phi i32 [ [ t2, left ], [ t1, right ] ]
+  ret t3 i32";
+
+#[test]
+fn test_well_formed_module_verifies() {
+    assert!(verify_module(&mut module(DIAMOND)).is_ok());
+}
+
+#[test]
+fn test_use_outside_the_dominated_region_is_rejected() {
+    let (code, message) = single_error(&DIAMOND.replace("ret t3 i32", "ret t2 i32"));
+    assert_eq!(code, ErrorCode::E3010);
+    assert_eq!(
+        message,
+        "`t2` is used in block `done` of function `pick` where its definition in block `left` does not dominate it"
+    );
+}
+
+#[test]
+fn test_use_before_definition_in_the_same_block_is_rejected() {
+    let (code, message) = single_error(
+        "function f () -> i32:
+entry:
+  t2 = add t1 1i32, i32
+  t1 = add 2i32 3i32, i32
+  ret t2 i32",
+    );
+    assert_eq!(code, ErrorCode::E3010);
+    assert!(message.starts_with("`t1` is used in block `entry`"), "{message}");
+}
+
+#[test]
+fn test_phi_values_must_be_available_in_their_predecessor() {
+    let (code, _) = single_error(&DIAMOND.replace("[ t1, right ]", "[ t2, right ]"));
+    assert_eq!(code, ErrorCode::E3010);
+}
+
+#[test]
+fn test_phi_labels_must_match_predecessors() {
+    let errors = errors(&DIAMOND.replace("[ t1, right ]", "[ t1, entry ]"));
+    let messages: Vec<&str> = errors.iter().map(|error| error.message().unwrap()).collect();
+    assert_eq!(
+        messages,
+        [
+            "phi in block `done` has an incoming value from `entry`, which is not a predecessor",
+            "phi in block `done` has no incoming value from predecessor `right`",
+        ]
+    );
+    assert!(errors.iter().all(|error| error.error_code() == Some(&ErrorCode::E3011)));
+}
+
+#[test]
+fn test_phi_may_omit_unreachable_predecessors() {
+    let module_text = "function f (x: i32) -> i32:
+entry:
+  br header
+header:
+  t1 = phi i32 [ [ %x (x), entry ] ]
+  ret t1 i32
+latch:
+  br header";
+    assert!(verify_module(&mut module(module_text)).is_ok());
+}
+
+#[test]
+fn test_verifier_errors_belong_to_the_verification_phase() {
+    let errors = errors(&DIAMOND.replace("ret t3 i32", "ret t2 i32"));
+    assert_eq!(errors[0].phase(), CompilerPhase::Verification);
+    let report = ErrorReporter::new(LineTracker::new("verify.nir", String::new())).report_errors(errors);
+    assert!(report.contains("VERIFY"), "{report}");
+}
+
+#[test]
+fn test_binary_and_cast_operand_types_are_checked() {
+    let errors = errors(
+        "function f (x: i8) -> i64:
+entry:
+  t1 = add %x (x) 1i64, i64
+  t2 =  cast 7i16 from i32 to i64
+  t3 =  cast %x (x) from i8 to i64
+  ret t3 i64",
+    );
+    let messages: Vec<&str> = errors.iter().map(|error| error.message().unwrap()).collect();
+    assert_eq!(
+        messages,
+        [
+            "binary operand `%x (x)` in function `f` has type i8 but the instruction expects i64",
+            "cast operand `7i16` in function `f` has type i16 but the instruction expects i32",
+        ]
+    );
+    assert!(errors.iter().all(|error| error.error_code() == Some(&ErrorCode::E3012)));
+}
+
+#[test]
+fn test_only_dead_blocks_may_end_in_unreachable() {
+    let dead = "function f () -> void:
+entry:
+  ret 0i32 void
+dead:
+  unreachable";
+    assert!(verify_module(&mut module(dead)).is_ok());
+
+    let (code, message) = single_error(&dead.replace("ret 0i32 void", "br dead"));
+    assert_eq!(code, ErrorCode::E3013);
+    assert_eq!(message, "block `dead` in function `f` is reachable but ends in `unreachable`");
+}
+
+#[test]
+fn test_verifier_phase_records_errors() {
+    let mut verifier = IrVerifier::new();
+    verifier.run(&mut module(DIAMOND));
+    assert!(verifier.errors().is_empty());
+
+    verifier.run(&mut module(&DIAMOND.replace("ret t3 i32", "ret t2 i32")));
+    assert_eq!(verifier.errors().len(), 1);
+    assert_eq!(verifier.take_errors().len(), 1);
+    assert!(verifier.errors().is_empty());
+}
+
+/// Reverses the instructions of every block, moving uses above definitions.
+struct ReverseInstructions;
+
+impl Phase for ReverseInstructions {
+    fn name(&self) -> &'static str {
+        "Reverse Instructions"
+    }
+
+    fn run(&mut self, ir: &mut Module) {
+        for function in &mut ir.functions {
+            for block in function.cfg.blocks_mut() {
+                block.instructions.reverse();
+            }
+        }
+    }
+}
+
+#[test]
+fn test_verified_pipeline_names_the_pass_that_broke_the_module() {
+    let pipeline: Vec<Box<dyn Phase>> = vec![phase_by_name("dce", false).unwrap(), Box::new(ReverseInstructions)];
+    let mut module = module(
+        "function f () -> i32:
+entry:
+  t1 = add 2i32 3i32, i32
+  t2 = mul t1 2i32, i32
+  ret t2 i32",
+    );
+    let errors = run_verified_pipeline(&mut module, pipeline).unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].message().unwrap(),
+        "after Reverse Instructions: `t1` is used in block `entry` of function `f` \
+         where its definition in block `entry` does not dominate it"
+    );
+}
+
+#[test]
+fn test_invalid_input_is_reported_before_optimization() {
+    let errors =
+        run_verified_pipeline(&mut module(&DIAMOND.replace("ret t3 i32", "ret t2 i32")), Vec::new()).unwrap_err();
+    assert!(errors[0].message().unwrap().starts_with("before optimization: "));
+}
+
+#[test]
+fn test_compiled_programs_stay_valid_through_the_pipeline() {
+    let mut checked = 0;
+    for dir in ["vn_files", "tests/vn/run"] {
+        let mut paths: Vec<_> = std::fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+        paths.sort();
+        for path in paths.iter().filter(|path| path.extension().is_some_and(|ext| ext == "vn")) {
+            let name = path.display().to_string();
+            // Some files exercise front-end diagnostics and never reach the IR.
+            let Ok(mut module) = compile(&name, &std::fs::read_to_string(path).unwrap()) else { continue };
+
+            let pipeline = PASS_NAMES.iter().filter_map(|name| phase_by_name(name, false)).collect();
+            if let Err(errors) = run_verified_pipeline(&mut module, pipeline) {
+                panic!("{name}: {errors:?}");
+            }
+            checked += 1;
+        }
+    }
+    assert!(checked >= 12, "only {checked} files were checked");
+}
@@ -227,15 +227,100 @@ fn string_char_literals() {
     assert_eq!(
         tokens,
         vec![
-            StringLiteral("Hello\\n".into()),
+            StringLiteral("Hello\n".into()),
             CharLiteral("a".into()),
-            StringLiteral("Escape\\\"Me".into()),
-            CharLiteral("\\'".into()),
+            StringLiteral("Escape\"Me".into()),
+            CharLiteral("'".into()),
             Eof
         ]
     );
 }
 
+#[test]
+fn escape_sequences() {
+    use TokenKind::*;
+    let input = r#""\t\r\\\0\'" "\x41\x7f" "\u{48}\u{e9}\u{1F600}" '\n' '\u{3b1}' 'é'"#;
+    let tokens = lex_kinds(input);
+    let tokens: Vec<TokenKind> = tokens.into_iter().map(|t| t.unwrap()).collect();
+    assert_eq!(
+        tokens,
+        vec![
+            StringLiteral("\t\r\\\0'".into()),
+            StringLiteral("A\x7f".into()),
+            StringLiteral("Hé\u{1F600}".into()),
+            CharLiteral("\n".into()),
+            CharLiteral("α".into()),
+            CharLiteral("é".into()),
+            Eof
+        ]
+    );
+}
+
+#[test]
+fn invalid_escape_sequences() {
+    let cases = vec![
+        (r#""a\qb""#, r"[E0007] Unknown escape sequence '\q' at test:line 1:column 3 - line 1:column 5"),
+        (
+            r#""\x4""#,
+            r"[E0011] Invalid hexadecimal escape '\x4': expected exactly two hexadecimal digits at test:line 1:column 2 - line 1:column 5",
+        ),
+        (
+            r#""\xff""#,
+            r"[E0011] Hexadecimal escape '\xff' is out of range: must be at most \x7F, use \u{FF} instead at test:line 1:column 2 - line 1:column 6",
+        ),
+        (
+            r#""\u41""#,
+            r"[E0012] Invalid unicode escape '\u': expected '{' after \u at test:line 1:column 2 - line 1:column 4",
+        ),
+        (
+            r#""\u{41""#,
+            r"[E0012] Invalid unicode escape '\u{41': missing closing '}' at test:line 1:column 2 - line 1:column 7",
+        ),
+        (
+            r#""\u{}""#,
+            r"[E0012] Invalid unicode escape '\u{}': expected at least one hexadecimal digit at test:line 1:column 2 - line 1:column 6",
+        ),
+        (
+            r#""\u{0000041}""#,
+            r"[E0012] Invalid unicode escape '\u{0000041}': at most six hexadecimal digits are allowed at test:line 1:column 2 - line 1:column 13",
+        ),
+        (
+            r"'\u{D800}'",
+            r"[E0012] Invalid unicode escape '\u{D800}': not a valid Unicode scalar value at test:line 1:column 2 - line 1:column 10",
+        ),
+        (
+            "'ab'",
+            "[E0013] Character literal must contain exactly one character, found 'ab' at test:line 1:column 1 - line 1:column 5",
+        ),
+    ];
+
+    for (input, expected) in cases {
+        let tokens = lex_kinds(input);
+        assert_eq!(tokens.len(), 3, "input {input}: an error, the literal and EOF");
+        match &tokens[0] {
+            Err(e) => assert_eq!(e.to_string(), expected),
+            Ok(kind) => panic!("expected an error for {input}, got {kind:?}"),
+        }
+        assert!(matches!(tokens[1], Ok(TokenKind::StringLiteral(_) | TokenKind::CharLiteral(_))));
+    }
+}
+
+#[test]
+fn invalid_escapes_are_all_reported_and_kept_verbatim() {
+    let mut lexer = Lexer::new("test", r#"x = "\q\n\z";"#);
+    let (tokens, errors) = lexer_tokenize_with_errors(&mut lexer);
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].to_string(),
+        r"[E0007] Unknown escape sequence '\q' at test:line 1:column 6 - line 1:column 8"
+    );
+    assert_eq!(
+        errors[1].to_string(),
+        r"[E0007] Unknown escape sequence '\z' at test:line 1:column 10 - line 1:column 12"
+    );
+    assert_eq!(tokens[2].kind, TokenKind::StringLiteral("\\q\n\\z".into()));
+}
+
 #[test]
 fn brackets() {
     use TokenKind::*;
@@ -0,0 +1,161 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use clap::Parser;
+use clap::error::ErrorKind;
+use jsavrs::cli::OptArgs;
+use jsavrs::ir::optimizer::{PASS_NAMES, phase_by_name};
+use predicates::prelude::*;
+use std::path::PathBuf;
+
+use predicate::str::contains;
+
+const DEAD_CODE: &str = r#"module dead {
+  data_layout = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128";
+  target_triple = "x86_64-unknown-linux-gnu";
+  function f (x: i32) -> i32:
+  blocks:
+  entry_f:
+    t1 = mul %x (x) 4i32, i32
+    t2 = sub t1 1i32, i32
+    br exit
+
+  exit:
+    ret t1 i32
+}"#;
+
+/// Writes `contents` next to the manifest and returns its path.
+fn write_input(name: &str, contents: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_every_pass_name_maps_to_a_phase() {
+    for name in PASS_NAMES {
+        assert!(phase_by_name(name, false).is_some(), "{name}");
+    }
+    assert_eq!(phase_by_name("sccp", false).unwrap().name(), "Constant Folding Optimizer (SCCP)");
+    assert_eq!(phase_by_name("dce", false).unwrap().name(), "Dead Code Elimination");
+    assert!(phase_by_name("licm", false).is_none());
+}
+
+#[test]
+fn test_passes_are_split_on_commas() {
+    let args = OptArgs::try_parse_from(["jsavrs-opt", "-i", "bug.nir", "--passes=dce,sccp", "-p", "dce"]).unwrap();
+    assert_eq!(args.passes, ["dce", "sccp", "dce"]);
+    assert!(!args.verbose);
+    assert!(args.output.is_none());
+
+    let args = OptArgs::try_parse_from(["jsavrs-opt", "-i", "bug.nir"]).unwrap();
+    assert!(args.passes.is_empty());
+}
+
+#[test]
+fn test_unknown_pass_names_are_rejected() {
+    let error = OptArgs::try_parse_from(["jsavrs-opt", "-i", "bug.nir", "--passes=sccp,licm"]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidValue);
+    assert!(error.to_string().contains("licm"));
+}
+
+#[test]
+fn test_prints_the_module_unchanged_without_passes() {
+    let input = write_input("test_opt_identity.nir", DEAD_CODE);
+    cargo_bin_cmd!("jsavrs-opt")
+        .arg("-i")
+        .arg(&input)
+        .assert()
+        .success()
+        .stdout(format!("{}\n", DEAD_CODE.replace("\n\n", "\n  \n")));
+    std::fs::remove_file(&input).unwrap();
+}
+
+#[test]
+fn test_runs_the_named_passes_in_order() {
+    let input = write_input("test_opt_dce.nir", DEAD_CODE);
+    cargo_bin_cmd!("jsavrs-opt")
+        .arg("-i")
+        .arg(&input)
+        .arg("--passes=sccp,dce")
+        .arg("--verbose")
+        .assert()
+        .success()
+        .stdout(contains("t1 = mul %x (x) 4i32, i32"))
+        .stdout(contains("t2 = ").not())
+        .stdout(contains("Running phase").not())
+        .stderr(contains("Running phase: Constant Folding Optimizer (SCCP)\n"))
+        .stderr(contains("Running phase: Dead Code Elimination\n"));
+
+    // Progress is only reported with --verbose.
+    cargo_bin_cmd!("jsavrs-opt").arg("-i").arg(&input).arg("--passes=sccp,dce").assert().success().stderr("");
+    std::fs::remove_file(&input).unwrap();
+}
+
+#[test]
+fn test_writes_the_module_to_the_output_file() {
+    let input = write_input("test_opt_output.nir", DEAD_CODE);
+    let output = input.with_extension("opt.nir");
+    cargo_bin_cmd!("jsavrs-opt").arg("-i").arg(&input).arg("-p").arg("dce").arg("-o").arg(&output).assert().success();
+
+    let optimized = std::fs::read_to_string(&output).unwrap();
+    assert!(optimized.starts_with("module dead {"));
+    assert!(!optimized.contains("t2 = "));
+    std::fs::remove_file(&input).unwrap();
+    std::fs::remove_file(&output).unwrap();
+}
+
+#[test]
+fn test_malformed_ir_is_reported() {
+    let input = write_input("test_opt_malformed.nir", "module m {\n  t1 = add 1i8 2i8, i8\n}");
+    cargo_bin_cmd!("jsavrs-opt")
+        .arg("-i")
+        .arg(&input)
+        .arg("--passes=dce")
+        .assert()
+        .failure()
+        .stderr(contains("E3009"))
+        .stderr(contains("instruction outside of a block"));
+    std::fs::remove_file(&input).unwrap();
+}
+
+#[test]
+fn test_missing_input_reports_io_error() {
+    cargo_bin_cmd!("jsavrs-opt").arg("-i").arg("does_not_exist.nir").assert().failure().stderr(contains("I/O"));
+}
+
+#[test]
+fn test_verify_reports_invalid_modules() {
+    let input =
+        write_input("test_opt_verify.nir", &DEAD_CODE.replace("ret t1 i32", "ret t1 i32\n  dead:\n    br exit"));
+    cargo_bin_cmd!("jsavrs-opt").arg("-i").arg(&input).arg("--passes=dce").arg("--verify").assert().success();
+    std::fs::remove_file(&input).unwrap();
+
+    let input = write_input("test_opt_verify_invalid.nir", &DEAD_CODE.replace("br exit", "unreachable"));
+    cargo_bin_cmd!("jsavrs-opt")
+        .arg("-i")
+        .arg(&input)
+        .arg("--verify")
+        .assert()
+        .failure()
+        .stderr(contains("E3013"))
+        .stderr(contains("before optimization"));
+    std::fs::remove_file(&input).unwrap();
+}
+
+#[test]
+fn test_optimization_level_runs_before_the_named_passes() {
+    let input = write_input("test_opt_level.nir", DEAD_CODE);
+    cargo_bin_cmd!("jsavrs-opt")
+        .arg("-i")
+        .arg(&input)
+        .arg("-O2")
+        .arg("--passes=sccp")
+        .arg("--time-passes")
+        .assert()
+        .success()
+        .stdout(contains("t2 = ").not())
+        .stdout(contains("Pass execution").not())
+        .stderr(contains("  sccp       3 run(s)"))
+        .stderr(contains("  dce        2 run(s)"))
+        .stderr(contains("DCE Stats: 1 instructions removed"));
+    std::fs::remove_file(&input).unwrap();
+}
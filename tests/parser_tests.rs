@@ -261,6 +261,148 @@ assignment_test!(
     "Invalid left-hand side in assignment"
 );
 
+assignment_test!(
+    test_compound_assignment_operators,
+    [
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::PlusEqual,
+        TokenKind::IdentifierAscii("y".into()),
+        TokenKind::XorEqual,
+        TokenKind::Numeric(Number::Integer(5)),
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::OpenBracket,
+        TokenKind::Numeric(Number::Integer(0)),
+        TokenKind::CloseBracket,
+        TokenKind::PercentEqual,
+        TokenKind::Numeric(Number::Integer(2)),
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::MinusEqual,
+        TokenKind::Numeric(Number::Integer(1)),
+        TokenKind::Star,
+        TokenKind::Numeric(Number::Integer(3)),
+        TokenKind::Eof,
+    ],
+    false,
+    [
+        Stmt::Expression {
+            expr: compound_assign_expr(
+                variable_expr("x"),
+                BinaryOp::Add,
+                compound_assign_expr(variable_expr("y"), BinaryOp::BitwiseXor, num_lit_i64(5))
+            )
+        },
+        Stmt::Expression {
+            expr: compound_assign_expr(
+                array_access_expr(variable_expr("x"), num_lit_i64(0)),
+                BinaryOp::Modulo,
+                num_lit_i64(2)
+            )
+        },
+        Stmt::Expression {
+            expr: compound_assign_expr(
+                variable_expr("x"),
+                BinaryOp::Subtract,
+                binary_expr(num_lit_i64(1), BinaryOp::Multiply, num_lit_i64(3))
+            )
+        },
+    ],
+    ""
+);
+
+assignment_test!(
+    test_compound_assignment_invalid_target,
+    [
+        TokenKind::Numeric(Number::Integer(5)),
+        TokenKind::PlusEqual,
+        TokenKind::Numeric(Number::Integer(10)),
+        TokenKind::Eof,
+    ],
+    true,
+    [],
+    "Invalid left-hand side in assignment"
+);
+
+assignment_test!(
+    test_prefix_and_postfix_updates,
+    [
+        TokenKind::PlusPlus,
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::OpenBracket,
+        TokenKind::Numeric(Number::Integer(0)),
+        TokenKind::CloseBracket,
+        TokenKind::MinusMinus,
+        TokenKind::IdentifierAscii("y".into()),
+        TokenKind::Equal,
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::PlusPlus,
+        TokenKind::Plus,
+        TokenKind::MinusMinus,
+        TokenKind::IdentifierAscii("z".into()),
+        TokenKind::Eof,
+    ],
+    false,
+    [
+        Stmt::Expression { expr: update_expr(variable_expr("x"), BinaryOp::Add, true) },
+        Stmt::Expression {
+            expr: update_expr(array_access_expr(variable_expr("x"), num_lit_i64(0)), BinaryOp::Subtract, false)
+        },
+        Stmt::Expression {
+            expr: assign_expr(
+                variable_expr("y"),
+                binary_expr(
+                    update_expr(variable_expr("x"), BinaryOp::Add, false),
+                    BinaryOp::Add,
+                    update_expr(variable_expr("z"), BinaryOp::Subtract, true)
+                )
+            )
+        },
+    ],
+    ""
+);
+
+assignment_test!(
+    test_prefix_update_invalid_operand,
+    [TokenKind::MinusMinus, TokenKind::Numeric(Number::Integer(5)), TokenKind::Eof],
+    true,
+    [],
+    "Invalid operand for prefix '--'"
+);
+
+assignment_test!(
+    test_postfix_update_invalid_operand,
+    [
+        TokenKind::IdentifierAscii("f".into()),
+        TokenKind::OpenParen,
+        TokenKind::CloseParen,
+        TokenKind::PlusPlus,
+        TokenKind::Eof
+    ],
+    true,
+    [],
+    "Invalid operand for postfix '++'"
+);
+
+#[test]
+fn test_update_at_line_start_begins_a_new_statement() {
+    let mut lexer = Lexer::new("test.vn", "x++\n++y\nz\n--w");
+    let (tokens, _lex_errors) = lexer_tokenize_with_errors(&mut lexer);
+    let (statements, errors) = JsavParser::new(&tokens).parse();
+    assert!(errors.is_empty(), "{errors:?}");
+    let updates: Vec<(&str, bool)> = statements
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::Expression { expr: Expr::Update { target, prefix, .. } } => match target.as_ref() {
+                Expr::Variable { name, .. } => Some((&**name, *prefix)),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(statements.len(), 4);
+    assert_eq!(updates, [("x", false), ("y", true), ("w", true)]);
+}
+
 #[test]
 fn test_function_call() {
     let tokens = create_tokens(vec![
@@ -312,6 +454,292 @@ fn test_array_access() {
     );
 }
 
+#[test]
+fn test_field_access_chain_and_assignment() {
+    let tokens = create_tokens(vec![
+        TokenKind::IdentifierAscii("a".into()),
+        TokenKind::Dot,
+        TokenKind::IdentifierAscii("b".into()),
+        TokenKind::OpenBracket,
+        TokenKind::Numeric(Number::Integer(0)),
+        TokenKind::CloseBracket,
+        TokenKind::Dot,
+        TokenKind::IdentifierAscii("c".into()),
+        TokenKind::Equal,
+        TokenKind::IdentifierAscii("p".into()),
+        TokenKind::Dot,
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::Plus,
+        TokenKind::Numeric(Number::Integer(1)),
+        TokenKind::Eof,
+    ]);
+    let parser = JsavParser::new(&tokens);
+    let (expr, errors) = parser.parse();
+    assert!(errors.is_empty(), "{errors:?}");
+    let target = field_access_expr(array_access_expr(field_access_expr(variable_expr("a"), "b"), num_lit_i64(0)), "c");
+    let value = binary_expr(field_access_expr(variable_expr("p"), "x"), BinaryOp::Add, num_lit_i64(1));
+    assert_eq!(expr, [Stmt::Expression { expr: assign_expr(target, value) }]);
+}
+
+#[test]
+fn test_struct_declaration() {
+    let tokens = create_tokens(vec![
+        TokenKind::KeywordStruct,
+        TokenKind::IdentifierAscii("Point".into()),
+        TokenKind::OpenBrace,
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::Colon,
+        TokenKind::TypeI32,
+        TokenKind::Comma,
+        TokenKind::IdentifierAscii("tag".into()),
+        TokenKind::Colon,
+        TokenKind::IdentifierAscii("Tag".into()),
+        TokenKind::Comma,
+        TokenKind::CloseBrace,
+        TokenKind::Eof,
+    ]);
+    let parser = JsavParser::new(&tokens);
+    let (stmts, errors) = parser.parse();
+    assert!(errors.is_empty(), "{errors:?}");
+    assert_eq!(
+        stmts,
+        [Stmt::Struct {
+            name: "Point".into(),
+            fields: vec![
+                Parameter { name: "x".into(), type_annotation: Type::I32, span: dummy_span() },
+                Parameter { name: "tag".into(), type_annotation: Type::Custom("Tag".into()), span: dummy_span() },
+            ],
+            span: dummy_span(),
+        }]
+    );
+}
+
+#[test]
+fn test_enum_declaration() {
+    let tokens = create_tokens(vec![
+        TokenKind::KeywordEnum,
+        TokenKind::IdentifierAscii("Color".into()),
+        TokenKind::OpenBrace,
+        TokenKind::IdentifierAscii("Red".into()),
+        TokenKind::Comma,
+        TokenKind::IdentifierAscii("Green".into()),
+        TokenKind::Equal,
+        TokenKind::Numeric(Number::Integer(5)),
+        TokenKind::Comma,
+        TokenKind::IdentifierAscii("Blue".into()),
+        TokenKind::Comma,
+        TokenKind::CloseBrace,
+        TokenKind::KeywordEnum,
+        TokenKind::IdentifierAscii("Level".into()),
+        TokenKind::Colon,
+        TokenKind::TypeU8,
+        TokenKind::OpenBrace,
+        TokenKind::IdentifierAscii("Low".into()),
+        TokenKind::CloseBrace,
+        TokenKind::Eof,
+    ]);
+    let parser = JsavParser::new(&tokens);
+    let (stmts, errors) = parser.parse();
+    assert!(errors.is_empty(), "{errors:?}");
+    let variant = |name: &str, value: Option<Expr>| EnumVariant { name: name.into(), value, span: dummy_span() };
+    assert_eq!(
+        stmts,
+        [
+            Stmt::Enum {
+                name: "Color".into(),
+                underlying_type: Type::I32,
+                variants: vec![variant("Red", None), variant("Green", Some(num_lit_i64(5))), variant("Blue", None)],
+                span: dummy_span(),
+            },
+            Stmt::Enum {
+                name: "Level".into(),
+                underlying_type: Type::U8,
+                variants: vec![variant("Low", None)],
+                span: dummy_span(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_struct_literal() {
+    let tokens = create_tokens(vec![
+        TokenKind::IdentifierAscii("Point".into()),
+        TokenKind::OpenBrace,
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::Colon,
+        TokenKind::Numeric(Number::Integer(1)),
+        TokenKind::Comma,
+        TokenKind::IdentifierAscii("y".into()),
+        TokenKind::Colon,
+        TokenKind::IdentifierAscii("Empty".into()),
+        TokenKind::OpenBrace,
+        TokenKind::CloseBrace,
+        TokenKind::CloseBrace,
+        TokenKind::Dot,
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::Eof,
+    ]);
+    let parser = JsavParser::new(&tokens);
+    let (expr, errors) = parser.parse();
+    assert!(errors.is_empty(), "{errors:?}");
+    let literal =
+        struct_literal_expr("Point", vec![("x", num_lit_i64(1)), ("y", struct_literal_expr("Empty", vec![]))]);
+    assert_eq!(expr, [Stmt::Expression { expr: field_access_expr(literal, "x") }]);
+}
+
+#[test]
+fn test_cast_binds_between_unary_and_binary_operators() {
+    let tokens = create_tokens(vec![
+        TokenKind::Minus,
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::KeywordAs,
+        TokenKind::TypeI8,
+        TokenKind::Star,
+        TokenKind::IdentifierAscii("y".into()),
+        TokenKind::KeywordAs,
+        TokenKind::TypeF64,
+        TokenKind::KeywordAs,
+        TokenKind::TypeI8,
+        TokenKind::Eof,
+    ]);
+    let parser = JsavParser::new(&tokens);
+    let (expr, errors) = parser.parse();
+    assert!(errors.is_empty(), "{errors:?}");
+    assert_eq!(
+        expr,
+        [Stmt::Expression {
+            expr: binary_expr(
+                cast_expr(unary_expr(UnaryOp::Negate, variable_expr("x")), Type::I8),
+                BinaryOp::Multiply,
+                cast_expr(cast_expr(variable_expr("y"), Type::F64), Type::I8),
+            ),
+        }]
+    );
+}
+
+#[test]
+fn test_cast_requires_a_type() {
+    let tokens = create_tokens(vec![
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::KeywordAs,
+        TokenKind::Plus,
+        TokenKind::Eof,
+    ]);
+    let parser = JsavParser::new(&tokens);
+    let (_expr, errors) = parser.parse();
+    assert!(!errors.is_empty());
+    assert_eq!(
+        errors[0].message().unwrap(),
+        "Invalid type specification, expected primitive type or custom identifier: '+'"
+    );
+}
+
+#[test]
+fn test_import_statement() {
+    let tokens = create_tokens(vec![
+        TokenKind::KeywordImport,
+        TokenKind::StringLiteral("lib/math.vn".into()),
+        TokenKind::KeywordImport,
+        TokenKind::IdentifierAscii("math".into()),
+        TokenKind::Eof,
+    ]);
+    let parser = JsavParser::new(&tokens);
+    let (statements, errors) = parser.parse();
+    assert_eq!(statements, [Stmt::Import { path: "lib/math.vn".into(), span: dummy_span() }]);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].message().unwrap().starts_with("Expected file path after 'import'"), "{errors:?}");
+}
+
+#[test]
+fn test_switch_statement() {
+    let tokens = create_tokens(vec![
+        TokenKind::KeywordSwitch,
+        TokenKind::OpenParen,
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::CloseParen,
+        TokenKind::OpenBrace,
+        TokenKind::KeywordCase,
+        TokenKind::Numeric(Number::I32(1)),
+        TokenKind::Colon,
+        TokenKind::KeywordBreak,
+        TokenKind::KeywordBreak,
+        TokenKind::KeywordCase,
+        TokenKind::Numeric(Number::I32(2)),
+        TokenKind::Colon,
+        TokenKind::KeywordDefault,
+        TokenKind::Colon,
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::CloseBrace,
+        TokenKind::Eof,
+    ]);
+    let parser = JsavParser::new(&tokens);
+    let (statements, errors) = parser.parse();
+    assert!(errors.is_empty(), "{errors:?}");
+    assert_eq!(
+        statements,
+        [Stmt::Switch {
+            scrutinee: variable_expr("x"),
+            cases: vec![
+                SwitchCase {
+                    value: num_lit_i32(1),
+                    body: vec![Stmt::Break { span: dummy_span() }, Stmt::Break { span: dummy_span() }],
+                    span: dummy_span(),
+                },
+                SwitchCase { value: num_lit_i32(2), body: vec![], span: dummy_span() },
+            ],
+            default: Some(vec![Stmt::Expression { expr: variable_expr("x") }]),
+            span: dummy_span(),
+        }]
+    );
+}
+
+#[test]
+fn test_switch_arm_errors() {
+    let tokens = create_tokens(vec![
+        TokenKind::KeywordSwitch,
+        TokenKind::OpenParen,
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::CloseParen,
+        TokenKind::OpenBrace,
+        TokenKind::IdentifierAscii("y".into()),
+        TokenKind::KeywordDefault,
+        TokenKind::Colon,
+        TokenKind::KeywordDefault,
+        TokenKind::Colon,
+        TokenKind::CloseBrace,
+        TokenKind::Eof,
+    ]);
+    let parser = JsavParser::new(&tokens);
+    let (_statements, errors) = parser.parse();
+    let messages: Vec<_> = errors.iter().map(|e| e.message().unwrap()).collect();
+    assert_eq!(
+        messages,
+        ["Expected 'case' or 'default' in switch: identifier 'y'", "Duplicate default arm in switch: 'default'"]
+    );
+}
+
+#[test]
+fn test_block_after_identifier_is_not_a_struct_literal() {
+    let tokens = create_tokens(vec![
+        TokenKind::IdentifierAscii("x".into()),
+        TokenKind::OpenBrace,
+        TokenKind::IdentifierAscii("y".into()),
+        TokenKind::CloseBrace,
+        TokenKind::Eof,
+    ]);
+    let parser = JsavParser::new(&tokens);
+    let (stmts, errors) = parser.parse();
+    assert!(errors.is_empty(), "{errors:?}");
+    assert_eq!(
+        stmts,
+        [
+            Stmt::Expression { expr: variable_expr("x") },
+            Stmt::Block { statements: vec![Stmt::Expression { expr: variable_expr("y") }], span: dummy_span() },
+        ]
+    );
+}
+
 #[test]
 fn test_array_access_empty_index() {
     let tokens = create_tokens(vec![
@@ -687,17 +1115,17 @@ fn test_nested_parsing_errors() {
 
 #[test]
 
-fn test_nested_unknown_binding_power() {
+fn test_field_access_requires_identifier() {
     let tokens = create_tokens(vec![
         TokenKind::IdentifierAscii("assssss".into()),
-        TokenKind::PlusEqual,
+        TokenKind::Dot,
         TokenKind::Numeric(Number::Integer(5)),
         TokenKind::Eof,
     ]);
     let parser = JsavParser::new(&tokens);
     let (_expr, errors) = parser.parse();
     assert!(!errors.is_empty());
-    assert_eq!(errors[0].message().unwrap(), "Unexpected operator: '+='");
+    assert_eq!(errors[0].message().unwrap(), "Expected identifier: number '5'");
 }
 
 #[test]
@@ -0,0 +1,266 @@
+use jsavrs::error::compile_error::CompileError;
+use jsavrs::error::error_code::ErrorCode;
+use jsavrs::ir::IrLiteralValue;
+use jsavrs::ir::generator::IrGenerator;
+use jsavrs::ir::interpreter::{Interpreter, RuntimeValue};
+use jsavrs::lexer::{Lexer, lexer_tokenize_with_errors};
+use jsavrs::parser::ast::Stmt;
+use jsavrs::parser::jsav_parser::JsavParser;
+use jsavrs::resolver::ModuleResolver;
+use std::path::{Path, PathBuf};
+
+/// Writes `files` into a fresh directory next to the manifest and returns it.
+fn write_files(dir: &str, files: &[(&str, &str)]) -> PathBuf {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(dir);
+    let _ = std::fs::remove_dir_all(&root);
+    for (name, contents) in files {
+        let path = root.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+    root
+}
+
+/// Lexes and parses the entry file and resolves its imports.
+fn resolve(root: &Path, entry: &str) -> ModuleResolver {
+    let path = root.join(entry).to_string_lossy().into_owned();
+    let source = std::fs::read_to_string(&path).unwrap();
+    let mut lexer = Lexer::new(&path, &source);
+    let (tokens, lexer_errors) = lexer_tokenize_with_errors(&mut lexer);
+    assert!(lexer_errors.is_empty(), "{lexer_errors:?}");
+    let (statements, parser_errors) = JsavParser::new(&tokens).parse();
+    assert!(parser_errors.is_empty(), "{parser_errors:?}");
+
+    let mut resolver = ModuleResolver::new();
+    resolver.resolve(&path, lexer.get_line_tracker().clone(), statements);
+    resolver
+}
+
+fn type_check(resolver: &ModuleResolver) -> Vec<CompileError> {
+    resolver.check_types()
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap()
+}
+
+const UTIL: &str = "fun add3(x: i32): i32 {
+    return x + 3i32
+}";
+
+#[test]
+fn test_shared_imports_are_loaded_once_before_their_importers() {
+    let root = write_files(
+        "test_resolver_diamond",
+        &[
+            (
+                "main.vn",
+                "import \"lib/math.vn\"\nimport \"lib/util.vn\"\nmain {\n    var r: i32 = square(add3(1i32))\n}",
+            ),
+            ("lib/math.vn", "import \"util.vn\"\nfun square(x: i32): i32 {\n    return add3(x) * x\n}"),
+            ("lib/util.vn", UTIL),
+        ],
+    );
+    let resolver = resolve(&root, "main.vn");
+    assert!(resolver.errors().is_empty(), "{:?}", resolver.errors());
+
+    let names: Vec<&str> = resolver.files().iter().map(|file| file_name(&file.path)).collect();
+    assert_eq!(names, ["util.vn", "math.vn", "main.vn"]);
+    assert_eq!(resolver.files()[1].imports, [0]);
+    assert_eq!(resolver.files()[2].imports, [1, 0]);
+
+    assert!(type_check(&resolver).is_empty());
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_linked_statements_keep_only_the_entry_main() {
+    let root = write_files(
+        "test_resolver_link",
+        &[
+            ("main.vn", "import \"util.vn\"\nmain {\n}"),
+            ("util.vn", &format!("{UTIL}\nvar calls: i32 = 0i32\nmain {{\n}}")),
+        ],
+    );
+    let resolver = resolve(&root, "main.vn");
+    let kinds: Vec<&str> = resolver
+        .linked_statements()
+        .iter()
+        .map(|stmt| match stmt {
+            Stmt::Function { .. } => "function",
+            Stmt::MainFunction { .. } => "main",
+            Stmt::Import { .. } => "import",
+            Stmt::VarDeclaration { .. } => "global",
+            _ => "other",
+        })
+        .collect();
+    assert_eq!(kinds, ["function", "global", "import", "main"]);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_imported_enums_are_linked() {
+    let root = write_files(
+        "test_resolver_enum",
+        &[
+            (
+                "main.vn",
+                "import \"level.vn\"\nvar r: i32 = 0i32\nmain {\n    r = level_value(true) + level_value(false)\n}",
+            ),
+            (
+                "level.vn",
+                "enum Level { Low = 1, High = 10 }\nfun level_value(high: bool): i32 {\n    \
+                 if (high) { return Level.High as i32 }\n    return Level.Low as i32\n}",
+            ),
+        ],
+    );
+    let resolver = resolve(&root, "main.vn");
+    assert!(type_check(&resolver).is_empty());
+
+    let (module, ir_errors) = IrGenerator::new().generate(resolver.linked_statements(), "main.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+    let mut interpreter = Interpreter::new(&module);
+    interpreter.run_main().unwrap();
+    assert_eq!(interpreter.global("r"), Some(RuntimeValue::Scalar(IrLiteralValue::I32(11))));
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_only_direct_imports_are_visible() {
+    let root = write_files(
+        "test_resolver_direct",
+        &[
+            ("main.vn", "import \"math.vn\"\nmain {\n    var r: i32 = add3(1i32)\n}"),
+            ("math.vn", "import \"util.vn\"\nfun twice(x: i32): i32 {\n    return add3(x) + add3(x)\n}"),
+            ("util.vn", UTIL),
+        ],
+    );
+    let errors = type_check(&resolve(&root, "main.vn"));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message(), Some("Undefined function: 'add3'"));
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_imported_functions_clash_with_local_ones() {
+    let root = write_files(
+        "test_resolver_clash",
+        &[("main.vn", "import \"util.vn\"\nfun add3(x: i32): i32 {\n    return x\n}"), ("util.vn", UTIL)],
+    );
+    let resolver = resolve(&root, "main.vn");
+    assert_eq!(resolver.errors().len(), 1);
+    assert_eq!(resolver.errors()[0].error_code(), Some(&ErrorCode::E2032));
+    let message = resolver.errors()[0].message().unwrap();
+    assert!(message.starts_with("Identifier 'add3' is already declared in '"), "{message}");
+    assert!(message.ends_with("util.vn'"), "{message}");
+    assert!(type_check(&resolver).is_empty());
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_top_level_names_clash_across_all_files() {
+    let root = write_files(
+        "test_resolver_sibling_clash",
+        &[
+            ("main.vn", "import \"a.vn\"\nimport \"b.vn\"\nmain {\n}"),
+            ("a.vn", "struct Pair { x: i32, y: i32 }\nvar limit: i32 = 1i32"),
+            ("b.vn", "import \"c.vn\"\nenum Pair { A, B }"),
+            ("c.vn", "const limit: i32 = 2i32"),
+        ],
+    );
+    let resolver = resolve(&root, "main.vn");
+    let messages: Vec<(&str, &str)> = resolver
+        .errors()
+        .iter()
+        .map(|error| {
+            (error.message().unwrap().split(" is ").next().unwrap(), file_name(&error.span().unwrap().file_path))
+        })
+        .collect();
+    assert_eq!(messages, [("Identifier 'limit'", "c.vn"), ("Identifier 'Pair'", "b.vn")]);
+    assert!(resolver.errors().iter().all(|error| error.error_code() == Some(&ErrorCode::E2032)));
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_imported_structs_enums_and_globals_are_visible() {
+    let root = write_files(
+        "test_resolver_exports",
+        &[
+            (
+                "main.vn",
+                "import \"shapes.vn\"\nvar r: i32 = 0i32\nmain {\n    var p: Pair = Pair { x: SCALE, y: 2i32 }\n    \
+                 var k: Kind = Kind.Wide\n    r = p.x * p.y + (k as i32)\n}",
+            ),
+            ("shapes.vn", "struct Pair { x: i32, y: i32 }\nenum Kind { Narrow, Wide = 5 }\nconst SCALE: i32 = 3i32"),
+        ],
+    );
+    let resolver = resolve(&root, "main.vn");
+    assert!(resolver.errors().is_empty(), "{:?}", resolver.errors());
+    assert!(type_check(&resolver).is_empty(), "{:?}", type_check(&resolver));
+
+    let (module, ir_errors) = IrGenerator::new().generate(resolver.linked_statements(), "main.vn");
+    assert!(ir_errors.is_empty(), "{ir_errors:?}");
+    let mut interpreter = Interpreter::new(&module);
+    interpreter.run_main().unwrap();
+    assert_eq!(interpreter.global("r"), Some(RuntimeValue::Scalar(IrLiteralValue::I32(11))));
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_circular_imports_are_reported() {
+    let root = write_files(
+        "test_resolver_cycle",
+        &[("a.vn", "import \"b.vn\"\nmain {\n}"), ("b.vn", "import \"c.vn\""), ("c.vn", "import \"b.vn\"")],
+    );
+    let resolver = resolve(&root, "a.vn");
+    assert_eq!(resolver.errors().len(), 1);
+    let error = &resolver.errors()[0];
+    assert_eq!(error.error_code(), Some(&ErrorCode::E2037));
+    let message = error.message().unwrap();
+    assert!(message.starts_with("Circular import: "), "{message}");
+    let cycle: Vec<&str> = message["Circular import: ".len()..].split(" -> ").map(file_name).collect();
+    assert_eq!(cycle, ["b.vn", "c.vn", "b.vn"]);
+    assert_eq!(file_name(&error.span().unwrap().file_path), "c.vn");
+    assert_eq!(resolver.files().len(), 3);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_missing_imports_are_reported_at_the_import() {
+    let root = write_files("test_resolver_missing", &[("main.vn", "main {\n}\nimport \"nope.vn\"")]);
+    let resolver = resolve(&root, "main.vn");
+    assert_eq!(resolver.errors().len(), 1);
+    let error = &resolver.errors()[0];
+    assert_eq!(error.error_code(), Some(&ErrorCode::E2038));
+    assert!(error.message().unwrap().starts_with("Cannot find imported file 'nope.vn'"));
+    assert_eq!(error.span().unwrap().start.line, 3);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_syntax_errors_point_into_the_imported_file() {
+    let root = write_files(
+        "test_resolver_syntax",
+        &[("main.vn", "import \"bad.vn\"\nmain {\n}"), ("bad.vn", "fun (): i32 {\n}")],
+    );
+    let resolver = resolve(&root, "main.vn");
+    assert!(!resolver.errors().is_empty());
+    assert!(matches!(resolver.errors()[0], CompileError::SyntaxError { .. }));
+    assert_eq!(file_name(&resolver.errors()[0].span().unwrap().file_path), "bad.vn");
+    assert_eq!(file_name(resolver.files()[0].line_tracker.file_path()), "bad.vn");
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_nested_imports_are_rejected() {
+    let root =
+        write_files("test_resolver_nested", &[("main.vn", "main {\n    import \"util.vn\"\n}"), ("util.vn", UTIL)]);
+    let resolver = resolve(&root, "main.vn");
+    assert_eq!(resolver.files().len(), 1, "only top-level imports are loaded");
+
+    let errors = type_check(&resolver);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error_code(), Some(&ErrorCode::E2038));
+    assert_eq!(errors[0].message(), Some("Import of 'util.vn' must be at the top level of the file"));
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,188 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use clap::Parser;
+use jsavrs::cli::TestArgs;
+use jsavrs::test_runner::{
+    Annotation, DiffLine, Expectation, Failure, check_file, check_source, collect_tests, parse_annotations,
+};
+use predicates::prelude::*;
+use std::path::{Path, PathBuf};
+
+use predicate::str::contains;
+
+fn vn_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/vn")
+}
+
+#[test]
+fn test_vn_suite_passes() {
+    let tests = collect_tests(&[vn_dir()]).unwrap();
+    assert!(tests.len() >= 8, "only {} files found", tests.len());
+    let report: Vec<String> = tests
+        .iter()
+        .flat_map(|path| check_file(path).into_iter().map(move |failure| format!("{}: {failure}", path.display())))
+        .collect();
+    assert!(report.is_empty(), "{}", report.join("\n"));
+}
+
+#[test]
+fn test_tests_are_collected_recursively_in_order() {
+    let tests = collect_tests(&[vn_dir()]).unwrap();
+    let mut sorted = tests.clone();
+    sorted.sort();
+    assert_eq!(tests, sorted);
+    assert!(tests.iter().all(|path| path.extension().is_some_and(|ext| ext == "vn")));
+    assert!(tests.iter().any(|path| path.parent().unwrap().ends_with("errors")));
+    assert!(collect_tests(&[vn_dir().join("missing.vn")]).is_err());
+}
+
+#[test]
+fn test_annotations_are_parsed() {
+    let source = "main {\n    break // ERROR: E2009\n}\n// ERROR:E2010\n// CHECK-IR:  add %a \n// RUN-RESULT: -1\n// RUN-RESULT: total = 42i64\n// RUN-RESULT: trap E3014\n// NOTE: not an annotation";
+    let expectations: Vec<_> = parse_annotations(source)
+        .unwrap()
+        .into_iter()
+        .map(|Annotation { line, expectation }| (line, expectation))
+        .collect();
+    assert_eq!(
+        expectations,
+        [
+            (2, Expectation::Error { code: "E2009".into(), line: Some(2) }),
+            (4, Expectation::Error { code: "E2010".into(), line: None }),
+            (5, Expectation::CheckIr("add %a".into())),
+            (6, Expectation::Status(-1)),
+            (7, Expectation::Global { name: "total".into(), value: "42i64".into() }),
+            (8, Expectation::Trap("E3014".into())),
+        ]
+    );
+}
+
+#[test]
+fn test_malformed_annotations_are_reported() {
+    for (source, message) in [
+        ("main { }\n// ERROR: 2009", "line 2: expected an error code such as E2023: `2009`"),
+        ("// CHECK-IR:", "line 1: expected the IR text to look for: ``"),
+        ("// RUN-RESULT: forty-two", "line 1: expected an exit status, `name = value` or `trap <code>`: `forty-two`"),
+        ("// RUN-RESULT: trap", "line 1: expected an exit status, `name = value` or `trap <code>`: `trap`"),
+    ] {
+        let failures = check_source("test.vn", source);
+        assert_eq!(failures.len(), 1, "{source}");
+        assert_eq!(failures[0].to_string(), message);
+    }
+}
+
+#[test]
+fn test_error_mismatches_are_shown_as_a_diff() {
+    let source = "main {\n    var x: i64 = true // ERROR: E2002\n    break // ERROR: E2010\n}\n// ERROR: E2024";
+    let failures = check_source("test.vn", source);
+    let [Failure::Errors(diff)] = failures.as_slice() else { panic!("{failures:?}") };
+    assert_eq!(
+        diff.as_slice(),
+        [
+            DiffLine::Same("E2002 at line 2: Cannot assign bool to i64 for variable 'x'".into()),
+            DiffLine::Expected("E2010 at line 3".into()),
+            DiffLine::Expected("E2024".into()),
+            DiffLine::Actual("E2009 at line 3: Break statement outside loop".into()),
+        ]
+    );
+    assert_eq!(
+        failures[0].to_string(),
+        "the reported errors do not match the ERROR annotations
+  E2002 at line 2: Cannot assign bool to i64 for variable 'x'
+- E2010 at line 3
+- E2024
++ E2009 at line 3: Break statement outside loop"
+    );
+
+    // Unannotated files must compile.
+    let failures = check_source("test.vn", "main { break }");
+    assert!(matches!(failures.as_slice(), [Failure::Errors(diff)] if matches!(diff.as_slice(), [DiffLine::Actual(_)])));
+    // An annotation on a line only matches errors on that line.
+    assert!(!check_source("test.vn", "main {\n    break\n    var a: i64 = 0 // ERROR: E2009\n}").is_empty());
+    assert!(check_source("test.vn", "main {\n    break\n    var a: i64 = 0\n}\n// ERROR: E2009").is_empty());
+}
+
+#[test]
+fn test_error_annotations_exclude_other_checks() {
+    let failures = check_source("test.vn", "main { break }\n// ERROR: E2009\n// RUN-RESULT: 0");
+    assert_eq!(
+        failures,
+        [Failure::Annotation {
+            line: 3,
+            message: "CHECK-IR and RUN-RESULT cannot be combined with ERROR annotations".into()
+        }]
+    );
+}
+
+#[test]
+fn test_check_ir_matches_in_order() {
+    let source = "fun add(a: i64, b: i64): i64 { return a + b }\nmain { var r: i64 = add(1, 2) }\n";
+    assert!(check_source("test.vn", &format!("{source}// CHECK-IR: function add\n// CHECK-IR: call")).is_empty());
+
+    let failures = check_source("test.vn", &format!("{source}// CHECK-IR: function main\n// CHECK-IR: add %a"));
+    let [Failure::CheckIr { line: 4, pattern, rest }] = failures.as_slice() else { panic!("{failures:?}") };
+    assert_eq!(pattern, "add %a");
+    assert!(rest.contains("call @add") && !rest.contains("add %a"), "{rest}");
+    assert!(failures[0].to_string().starts_with("line 4: CHECK-IR `add %a` not found in the rest of the IR:\n| "));
+}
+
+#[test]
+fn test_run_results_are_compared() {
+    let source = "var total: i64 = 1\nvar zero: i64 = 0\nmain { total = total + 41 }\n";
+    assert!(check_source("test.vn", &format!("{source}// RUN-RESULT: total = 42\n// RUN-RESULT: 0")).is_empty());
+
+    let failures = check_source(
+        "test.vn",
+        &format!(
+            "{source}// RUN-RESULT: total = 41\n// RUN-RESULT: count = 1\n// RUN-RESULT: 3\n// RUN-RESULT: trap E3014"
+        ),
+    );
+    let messages: Vec<_> = failures.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        [
+            "line 4: RUN-RESULT does not match\n- total = 41\n+ total = 42i64",
+            "line 5: RUN-RESULT does not match\n- count = 1\n+ no global named `count`",
+            "line 6: RUN-RESULT does not match\n- 3\n+ 0",
+            "line 7: RUN-RESULT does not match\n- trap E3014\n+ exit status 0",
+        ]
+    );
+
+    let trapping = "var zero: i64 = 0\nvar r: i64 = 0\nmain { r = 7 / zero }\n// RUN-RESULT: r = 0";
+    let failures = check_source("test.vn", trapping);
+    assert_eq!(
+        failures,
+        [Failure::RunResult { line: 4, expected: "r = 0".into(), actual: "trap E3014: division by zero".into() }]
+    );
+}
+
+#[test]
+fn test_runaway_programs_fail_instead_of_hanging() {
+    let failures = check_source("test.vn", "main { while (true) { } }\n// RUN-RESULT: 0");
+    assert!(matches!(failures.as_slice(), [Failure::RunResult { actual, .. }] if actual.starts_with("trap E3014")));
+}
+
+#[test]
+fn test_binary_reports_failures_and_exit_status() {
+    cargo_bin_cmd!("jsavrs-test")
+        .arg(vn_dir())
+        .arg("--verbose")
+        .assert()
+        .success()
+        .stdout(contains("ok "))
+        .stdout(contains("arithmetic.vn"))
+        .stdout(contains("0 failed"));
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_runner_failing.vn");
+    std::fs::write(&path, "var total: i64 = 0\nmain { total = 1 }\n// RUN-RESULT: total = 2\n").unwrap();
+    let assert = cargo_bin_cmd!("jsavrs-test").arg(&path).assert();
+    std::fs::remove_file(&path).unwrap();
+    assert
+        .failure()
+        .code(1)
+        .stdout(contains("test_runner_failing.vn"))
+        .stdout(contains("    - total = 2"))
+        .stdout(contains("    + total = 1i64"))
+        .stdout(contains("0 passed; 1 failed"));
+
+    assert!(TestArgs::try_parse_from(["jsavrs-test"]).is_err());
+}
@@ -225,6 +225,191 @@ fn test_immutable_assignment() {
     assert_eq!(errors[0].message(), Some("Cannot assign to immutable variable 'x'"));
 }
 
+#[test]
+fn test_compound_assignment_and_updates_valid() {
+    let ast = "main {
+        var x: i32 = 42i32
+        var f: f64 = 1.5
+        var arr: u8[2] = {1u8, 2u8}
+        x += 1i32
+        x ^= 3i8
+        f -= 2i32
+        arr[0] %= 2u8
+        var y: i32 = x++ + --x
+        f++
+        --arr[1]
+    }";
+
+    assert!(typecheck(ast).is_empty());
+}
+
+#[test]
+fn test_compound_assignment_to_immutable() {
+    let ast = "const x: i32 = 42i32
+    x += 1i32
+    x++";
+
+    let errors = typecheck(ast);
+    assert_eq!(errors.len(), 2);
+    assert!(errors.iter().all(|e| e.message() == Some("Cannot assign to immutable variable 'x'")));
+}
+
+#[test]
+fn test_compound_assignment_type_errors() {
+    let ast = "var x: i32 = 42i32
+    var f: f64 = 1.5
+    var b: bool = true
+    x += 3.5
+    f ^= 2.0
+    b -= true
+    b++";
+
+    let messages: Vec<_> = typecheck(ast).iter().map(|e| e.message().unwrap().to_string()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Cannot assign f64 to i32",
+            "Bitwise operator 'BitwiseXor' require integer operand types, found f64 and f64",
+            "Binary operator 'Subtract' requires numeric operands, found bool and bool",
+            "Increment requires numeric type operand, found bool",
+        ]
+    );
+}
+
+#[test]
+fn test_struct_declaration_literal_and_fields_valid() {
+    let ast = "struct Inner { flag: bool }
+    struct Point { x: i32, y: f64, inner: Inner }
+    fun x_of(p: Point): i32 {
+        return p.x
+    }
+    main {
+        var p: Point = Point { y: 2.5, inner: Inner { flag: true }, x: 1i32 }
+        p.x = p.x + 1i32
+        p.y += 1i32
+        p.inner.flag = !p.inner.flag
+        var x: i32 = x_of(p)
+    }";
+
+    assert!(typecheck(ast).is_empty());
+}
+
+#[test]
+fn test_struct_literal_errors() {
+    let ast = "struct Point { x: i32, y: i32 }
+    main {
+        var a: Point = Point { x: 1i32, x: 2i32, y: 3i32 }
+        var b: Point = Point { x: 1i32, z: 2i32 }
+        var c: Point = Point { x: true, y: 1i32 }
+        var d: Shape = Shape { }
+    }";
+
+    let errors = typecheck(ast);
+    let messages: Vec<_> = errors.iter().map(|e| e.message().unwrap().to_string()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Field 'x' specified more than once in literal of struct 'Point'",
+            "Struct 'Point' has no field 'z'",
+            "Missing field 'y' in literal of struct 'Point'",
+            "Cannot assign bool to field 'x' of type i32",
+            "Undefined type 'Shape'",
+            "Undefined type 'Shape'",
+        ]
+    );
+    let codes: Vec<_> = errors.iter().map(|e| e.error_code().unwrap().code()).collect();
+    assert_eq!(codes, ["E2032", "E2034", "E2035", "E2002", "E2033", "E2033"]);
+}
+
+#[test]
+fn test_field_access_errors() {
+    let ast = "struct Point { x: i32, y: i32 }
+    main {
+        const p: Point = Point { x: 1i32, y: 2i32 }
+        var n: i32 = 3i32
+        p.x = 4i32
+        var z: i32 = p.z
+        n.x = 1i32
+        var q: Point = p
+        q.y = 1.5
+    }";
+
+    let messages: Vec<_> = typecheck(ast).iter().map(|e| e.message().unwrap().to_string()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Cannot assign to field 'x' of immutable variable 'p'",
+            "Struct 'Point' has no field 'z'",
+            "Cannot access field 'x' on non-struct type i32",
+            "Cannot assign f64 to field 'y' of type i32",
+        ]
+    );
+}
+
+#[test]
+fn test_cast_expressions_valid() {
+    let ast = "main {
+        var big: i64 = 300
+        var small: i8 = big as i8
+        var ratio: f32 = small as f32 / 2.5f32
+        var code: u32 = 'a' as u32
+        var letter: char = code as char
+        var flag: bool = big as bool
+        var text: string = 42 as string
+        var number: i32 = \"42\" as i32
+    }";
+
+    let errors = typecheck(ast);
+    assert!(errors.is_empty(), "{errors:?}");
+}
+
+#[test]
+fn test_cast_errors() {
+    let ast = "struct Point { x: i32 }
+    main {
+        var p: Point = Point { x: 1i32 }
+        var n: i32 = p as i32
+        var a: i32[2] = {1i32, 2i32}
+        var m: i64 = a as i64
+        var q: Point = 1 as Point
+        var s: string = 1 as i32
+    }";
+
+    let errors = typecheck(ast);
+    let messages: Vec<_> = errors.iter().map(|e| e.message().unwrap().to_string()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Cannot cast Point to i32",
+            "Cannot cast [i32; 2] to i64",
+            "Cannot cast i64 to Point",
+            "Cannot assign i32 to string for variable 's'",
+        ]
+    );
+    assert!(errors[..3].iter().all(|e| e.error_code().unwrap().code() == "E2036"));
+}
+
+#[test]
+fn test_struct_declaration_errors() {
+    let ast = "struct Node { value: i32, value: i64, next: Node }
+    struct Node { id: i32 }
+    fun f(n: Missing): Other {
+        return nullptr
+    }";
+
+    let messages: Vec<_> = typecheck(ast).iter().map(|e| e.message().unwrap().to_string()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Field 'value' already declared in struct 'Node'",
+            "Undefined type 'Node'",
+            "Identifier 'Node' already declared in this Global scope",
+            "Undefined type 'Other'",
+            "Undefined type 'Missing'",
+        ]
+    );
+}
+
 #[test]
 fn test_assign_f64_to_i32() {
     let ast = "var x: i32 = 42i32
@@ -1370,3 +1555,284 @@ fn test_get_size() {
     let expr_variable = Expr::Variable { name: "x".into(), span: dummy_span() };
     assert_eq!(checker.get_size(&expr_variable), None);
 }
+
+#[test]
+fn test_global_variables_valid() {
+    let ast = "const SIZE: i64 = 4 * 8
+    const HALF: f64 = SIZE as f64 / 2.0
+    var counter: i64 = SIZE - 1
+    var ready: bool = !(SIZE > 100)
+    var initial: char = 'a'
+    fun bump(): i64 {
+        counter += SIZE
+        return counter
+    }";
+
+    let errors = typecheck(ast);
+    assert!(errors.is_empty(), "{errors:?}");
+}
+
+#[test]
+fn test_global_variable_errors() {
+    let ast = "fun seed(): i64 { return 4 }
+    var base: i64 = 1
+    var a: i64 = seed()
+    var b: i64 = base + 1
+    var c: string = \"text\"
+    struct Pair { x: i32, y: i32 }
+    var d: Pair = Pair { x: 1i32, y: 2i32 }
+    var e: i64[2] = {base, 2}";
+
+    let errors = typecheck(ast);
+    let messages: Vec<_> = errors.iter().map(|e| e.message().unwrap().to_string()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Initializer of global 'a' is not a constant expression",
+            "Initializer of global 'b' is not a constant expression",
+            "Global 'c' has type string; globals must have a number, bool or char type, or be arrays of them",
+            "Global 'd' has type Pair; globals must have a number, bool or char type, or be arrays of them",
+            "Initializer of global 'e' is not a constant expression",
+        ]
+    );
+    assert!(errors.iter().all(|e| e.error_code().unwrap().code() == "E2039"));
+}
+
+#[test]
+fn test_global_arrays_valid() {
+    let ast = "const LEN: i64 = 3
+    const PRIMES: u8[3] = {2u8, 3u8, 5u8}
+    var table: i64[3] = {LEN, LEN * 2, -LEN}
+    var flags: bool[2] = {true, !true}
+    fun first(): i64 {
+        table[0] += PRIMES[1] as i64
+        return table[0]
+    }";
+
+    let errors = typecheck(ast);
+    assert!(errors.is_empty(), "{errors:?}");
+}
+
+#[test]
+fn test_global_type_mismatch_is_only_reported_once() {
+    let ast = "var x: i32 = \"test\"
+    var arr: i32[2] = {1i32, 's'}";
+
+    let errors = typecheck(ast);
+    let codes: Vec<_> = errors.iter().map(|e| e.error_code().unwrap().code()).collect();
+    assert_eq!(codes, ["E2002", "E2021"], "{errors:?}");
+}
+
+#[test]
+fn test_struct_and_array_return_types_are_rejected() {
+    let ast = "struct Point { x: i32, y: i32 }
+    enum Color { Red, Green }
+    fun origin(): Point { return Point { x: 0i32, y: 0i32 } }
+    fun pair(): i32[2] { return {1i32, 2i32} }
+    fun red(): Color { return Color.Red }";
+
+    let errors = typecheck(ast);
+    let codes: Vec<_> = errors.iter().map(|e| e.error_code().unwrap().code()).collect();
+    assert_eq!(codes, ["E2044", "E2044"], "{errors:?}");
+    assert_eq!(errors[0].message(), Some("Function 'origin' returns Point; functions cannot return structs or arrays"));
+}
+
+#[test]
+fn test_call_to_function_declared_later() {
+    let ast = "main {
+    var x: i32 = twice(21i32)
+}
+fun twice(n: i32): i32 {
+    return n * 2i32
+}";
+
+    let errors = typecheck(ast);
+    assert!(errors.is_empty(), "Unexpected errors: {errors:?}");
+}
+
+#[test]
+fn test_mutually_recursive_functions() {
+    let ast = "fun is_even(n: i32): bool {
+    if (n == 0i32) { return true }
+    return is_odd(n - 1i32)
+}
+fun is_odd(n: i32): bool {
+    if (n == 0i32) { return false }
+    return is_even(n - 1i32)
+}";
+
+    let errors = typecheck(ast);
+    assert!(errors.is_empty(), "Unexpected errors: {errors:?}");
+}
+
+#[test]
+fn test_forward_call_is_checked_against_the_later_signature() {
+    let ast = "fun first(): i32 {
+    return second(true)
+}
+fun second(n: i32): i32 {
+    return n
+}
+fun second(): i32 {
+    return 0i32
+}";
+
+    let errors = typecheck(ast);
+    assert_eq!(errors.len(), 2, "{errors:?}");
+    assert_eq!(errors[0].message(), Some("Identifier 'second' already declared in this Global scope"));
+    assert_eq!(errors[1].message(), Some("Argument 1 type mismatch: expected i32, found bool"));
+}
+
+#[test]
+fn test_switch_statement_valid() {
+    let ast = "fun describe(n: i32, c: char): i32 {
+    const LIMIT: i32 = 10i32
+    switch (n) {
+        case 1i32:
+            return 1i32
+        case -2i32:
+            var doubled: i32 = n * 2i32
+            return doubled
+        case LIMIT:
+            break
+        default:
+            return 0i32
+    }
+    switch (c) {
+        case 'a': return 2i32
+    }
+    return 3i32
+}";
+
+    let errors = typecheck(ast);
+    assert!(errors.is_empty(), "Unexpected errors: {errors:?}");
+}
+
+#[test]
+fn test_switch_case_errors() {
+    let ast = "fun f(n: i32, x: f64, s: i32) {
+    switch (n) {
+        case 1i32:
+        case s:
+        case true:
+        case (1i32):
+    }
+    switch (x) {
+        default:
+    }
+}";
+
+    let errors = typecheck(ast);
+    let messages: Vec<_> = errors.iter().map(|e| e.message().unwrap()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Case value is not a constant expression",
+            "Case value of type bool does not match switch value of type i32",
+            "Duplicate case value in switch",
+            "Cannot switch on a value of type f64; expected an integer, enum, char or bool",
+        ]
+    );
+    let codes: Vec<_> = errors.iter().map(|e| e.error_code().unwrap().code()).collect();
+    assert_eq!(codes, ["E2040", "E2040", "E2041", "E2040"]);
+}
+
+#[test]
+fn test_break_in_switch_and_return_paths() {
+    let ast = "fun all_arms_return(n: i32): i32 {
+    switch (n) {
+        case 0i32: return 1i32
+        default: return 2i32
+    }
+}
+fun missing_default(n: i32): i32 {
+    switch (n) {
+        case 0i32: return 1i32
+    }
+}
+main {
+    break
+}";
+
+    let errors = typecheck(ast);
+    let messages: Vec<_> = errors.iter().map(|e| e.message().unwrap()).collect();
+    assert_eq!(messages.len(), 2, "{messages:?}");
+    assert!(messages[0].contains("missing_default"), "{messages:?}");
+    assert_eq!(messages[1], "Break statement outside loop");
+}
+
+#[test]
+fn test_enum_declarations_and_variants_valid() {
+    let ast = "enum Color { Red, Green = 5, Blue }
+enum Level: u8 { Low = 1, High = 255u8 }
+const FAVOURITE: Color = Color.Blue
+fun weight(c: Color): i32 {
+    switch (c) {
+        case Color.Red: return 1i32
+        case Color.Green: return 2i32
+        default: return c as i32
+    }
+}
+main {
+    var c: Color = FAVOURITE
+    var same: bool = c == Color.Blue
+    var level: u64 = Level.High as u64
+    c = Color.Red
+}";
+
+    let errors = typecheck(ast);
+    assert!(errors.is_empty(), "Unexpected errors: {errors:?}");
+}
+
+#[test]
+fn test_enum_declaration_errors() {
+    let ast = "enum Real: f32 { A }
+enum Level: u8 { Low = 255, High }
+enum Dup { A = 1, B = 0, C, A }
+enum Flag { On = true }";
+
+    let errors = typecheck(ast);
+    let messages: Vec<_> = errors.iter().map(|e| e.message().unwrap()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Underlying type of enum 'Real' must be an integer type, found f32",
+            "Discriminant 256 of variant 'High' does not fit in u8",
+            "Discriminant 1 of variant 'C' is already used by 'A'",
+            "Variant 'A' already declared in enum 'Dup'",
+            "Discriminant of variant 'On' is not an integer constant",
+        ]
+    );
+    let codes: Vec<_> = errors.iter().map(|e| e.error_code().unwrap().code()).collect();
+    assert_eq!(codes, ["E2042", "E2042", "E2042", "E2032", "E2042"]);
+}
+
+#[test]
+fn test_enums_are_distinct_nominal_types() {
+    let ast = "enum Color { Red, Green }
+enum Shape { Circle }
+main {
+    var s: Shape = Color.Red
+    var i: i32 = Color.Red
+    var c: Color = 1i32 as Color
+    var f: f64 = Color.Red as f64
+    var p: Color = Color.Purple
+    Color.Red = Color.Green
+}";
+
+    let errors = typecheck(ast);
+    let messages: Vec<_> = errors.iter().map(|e| e.message().unwrap()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Cannot assign Color to Shape for variable 's'",
+            "Cannot assign Color to i32 for variable 'i'",
+            "Cannot cast i32 to Color",
+            "Cannot cast Color to f64",
+            "Enum 'Color' has no variant 'Purple'",
+            "Cannot assign to variant 'Red' of enum 'Color'",
+        ]
+    );
+    let codes: Vec<_> = errors.iter().map(|e| e.error_code().unwrap().code()).collect();
+    assert_eq!(codes, ["E2002", "E2002", "E2036", "E2036", "E2043", "E2024"]);
+}